@@ -1,19 +1,16 @@
 pub mod packs;
 
+#[cfg(feature = "test-helpers")]
+pub mod test_support;
+
 #[cfg(test)]
 mod test_util {
-    use configuration::Configuration;
     use packs::parsing::ruby::zeitwerk::get_zeitwerk_constant_resolver;
-    use std::collections::{HashMap, HashSet};
     use std::path::PathBuf;
 
     use packs::configuration;
 
-    use crate::packs::configuration::from_raw;
     use crate::packs::constant_resolver::ConstantResolver;
-    use crate::packs::pack::Pack;
-    use crate::packs::raw_configuration::RawConfiguration;
-    use crate::packs::walk_directory::WalkDirectoryResult;
     use crate::packs::{self};
 
     pub const SIMPLE_APP: &str = "tests/fixtures/simple_app";
@@ -33,10 +30,21 @@ mod test_util {
             &configuration.constant_resolver_configuration(),
         ))
     }
+}
+
+#[cfg(any(test, feature = "test-helpers"))]
+mod defaults {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::packs::configuration::{from_raw, Configuration};
+    use crate::packs::pack::Pack;
+    use crate::packs::raw_configuration::RawConfiguration;
+    use crate::packs::walk_directory::WalkDirectoryResult;
 
     // Note that instead, we could derive the `Default` trait on `Pack`
     // However, there should be no reason the "production" code ever initializes
-    // a default Pack directly, so this implementation is test only.
+    // a default Pack directly, so this implementation is only for tests,
+    // including those of other crates (see test_support.rs).
     #[allow(clippy::derivable_impls)]
     impl Default for Pack {
         fn default() -> Self {
@@ -45,18 +53,32 @@ mod test_util {
                 name: Default::default(),
                 relative_path: Default::default(),
                 dependencies: Default::default(),
+                max_dependencies: None,
+                max_dependents: None,
+                strict_mode_grace_period_days: None,
+                test_dependencies: Default::default(),
                 ignored_dependencies: Default::default(),
                 ignored_private_constants: Default::default(),
                 private_constants: Default::default(),
+                privacy_exceptions: Default::default(),
+                waivers: Default::default(),
+                tags: Default::default(),
                 package_todo: Default::default(),
+                package_todo_path: None,
+                package_todo_yml: Default::default(),
                 visible_to: Default::default(),
+                namespaces: Default::default(),
                 public_folder: Default::default(),
+                public_paths: Default::default(),
                 layer: Default::default(),
+                inherited_layer: None,
+                duplicate_dependencies: vec![],
                 enforce_dependencies: Default::default(),
                 enforce_privacy: Default::default(),
                 enforce_visibility: Default::default(),
                 enforce_folder_privacy: Default::default(),
                 enforce_folder_visibility: None,
+                enforce_architecture: None,
                 enforce_layers: Default::default(),
                 client_keys: Default::default(),
                 owner: Default::default(),
@@ -80,6 +102,8 @@ mod test_util {
                 included_files: HashSet::new(),
                 included_packs,
                 owning_package_yml_for_file: HashMap::new(),
+                pksignore: Default::default(),
+                pack_errors: Vec::new(),
             };
             from_raw(
                 &default_absolute_root,
@@ -1,5 +1,14 @@
+use std::process::ExitCode;
+
 use packs::packs::cli;
 
-pub fn main() -> anyhow::Result<()> {
-    cli::run()
+pub fn main() -> ExitCode {
+    match cli::run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            // As printed when `main` returns an error
+            eprintln!("Error: {:?}", error);
+            cli::exit_code(&error)
+        }
+    }
 }
@@ -3,29 +3,70 @@
 pub mod cli;
 
 // Module declarations
+pub(crate) mod annotate;
+pub mod batch;
 pub(crate) mod bin_locater;
 pub(crate) mod caching;
 pub(crate) mod checker;
 pub(crate) mod configuration;
 pub(crate) mod constant_resolver;
+pub(crate) mod definition_export;
 pub(crate) mod dependencies;
 pub(crate) mod ignored;
+pub(crate) mod interner;
 pub(crate) mod monkey_patch_detection;
 pub mod pack;
 pub(crate) mod parsing;
+pub(crate) mod pksignore;
 pub(crate) mod raw_configuration;
 pub(crate) mod walk_directory;
 
+mod autocorrect;
+mod bench;
+mod code_url;
 mod constant_dependencies;
+mod dead_references;
+mod doctor;
+mod edge_export;
+mod enforcement_rollout;
+mod explain;
 mod file_utils;
+mod fingerprint;
+mod git;
+mod graph_metrics;
+mod hotspots;
+mod invert_dependency;
 mod logger;
+mod mapped_file;
+mod merge;
+mod move_pack;
+mod notifications;
+mod output_sink;
+mod pack_info;
 mod pack_set;
 mod package_todo;
+mod parity;
+mod progress;
+mod public_api;
 mod reference_extractor;
+mod report;
+mod resolution_fallback;
+mod split_suggestion;
+mod stats;
+mod todo_diff;
+mod unresolved;
+mod violation_store;
 
 use crate::packs;
+use crate::packs::cli::{
+    BenchCache, CheckOutputFormat, EdgeFormat, ExportFormat, GraphEdges,
+    GroupBy, OutputFormat, ReportFormat,
+};
+use crate::packs::constant_resolver::SharedConstantResolver;
 use crate::packs::pack::write_pack_to_disk;
+use crate::packs::pack::CheckerSetting;
 use crate::packs::pack::Pack;
+use crate::packs::pack_info::PackFilter;
 
 // Internal imports
 pub(crate) use self::checker::Violation;
@@ -34,9 +75,11 @@ pub(crate) use self::parsing::process_files_with_cache;
 pub(crate) use self::parsing::ruby::experimental::get_experimental_constant_resolver;
 pub(crate) use self::parsing::ruby::zeitwerk::get_zeitwerk_constant_resolver;
 pub(crate) use self::parsing::ParsedDefinition;
+pub(crate) use self::parsing::SyntaxError;
 pub(crate) use self::parsing::UnresolvedReference;
 use anyhow::bail;
 pub(crate) use configuration::Configuration;
+pub(crate) use output_sink::OutputSink;
 pub(crate) use package_todo::PackageTodo;
 
 // External imports
@@ -44,6 +87,8 @@ use anyhow::Context;
 use serde::Deserialize;
 use serde::Serialize;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::debug;
 
 pub fn greet() {
     println!("👋 Hello! Welcome to packs 📦 🔥 🎉 🌈. This tool is under construction.")
@@ -164,21 +209,236 @@ See https://github.com/rubyatscale/packs#readme for more info!",
     Ok(())
 }
 
-pub fn check(
+// How `check` reports and acts on what it finds
+pub(crate) struct CheckOptions<'a> {
+    pub group_by: Option<GroupBy>,
+    pub fix_dependencies: bool,
+    // Set to explain violations, optionally only those on the given constant
+    pub explain: Option<Option<&'a str>>,
+    pub output_format: CheckOutputFormat,
+    pub outputs: &'a [OutputSink],
+    // Print violations as they're found, which only applies to human output
+    pub stream: bool,
+    pub notify: bool,
+}
+
+pub(crate) fn check(
     configuration: &Configuration,
     files: Vec<String>,
+    options: CheckOptions,
 ) -> anyhow::Result<()> {
-    let result = checker::check_all(configuration, files)
-        .context("Failed to check files")?;
-    println!("{}", result);
+    let CheckOptions {
+        group_by,
+        fix_dependencies,
+        explain,
+        output_format,
+        outputs,
+        stream,
+        notify,
+    } = options;
+    // Only output to a terminal links to the code, as files and other
+    // programs would get the escape sequences
+    let code_url = configuration
+        .code_url
+        .as_ref()
+        .filter(|_| configuration.terminal_links);
+    let mut result = if stream {
+        checker::check_streaming(configuration, files, |violation| {
+            // A single call, so that violations found at once by different
+            // threads aren't interleaved
+            match code_url {
+                Some(code_url) => {
+                    print!("{}\n\n", violation.linked_message(code_url))
+                }
+                None => print!("{}\n\n", violation.message()),
+            }
+        })
+    } else {
+        checker::check_all(configuration, files)
+    }
+    .context("Failed to check files")?;
+    if fix_dependencies {
+        result.fix_dependencies(configuration)?;
+    }
+    if stream {
+        println!(
+            "{}",
+            checker::streaming::StreamedCheckAllResult { result: &result }
+        );
+    } else {
+        print!(
+            "{}",
+            render_check_result(&result, output_format, group_by, code_url)?
+        );
+    }
+    for output in outputs {
+        output.write(&render_check_result(
+            &result,
+            output.format,
+            group_by,
+            None,
+        )?)?;
+    }
+    if let Some(constant) = explain {
+        print!(
+            "{}",
+            explain::explain(
+                configuration,
+                result.reportable_violations(),
+                constant
+            )?
+        );
+    }
+    if notify {
+        let sent = notifications::notify(
+            configuration,
+            result.reportable_violations(),
+        );
+        debug!("Sent {} notification(s)", sent);
+    }
+    // Partial results are reported as such, even with violations
+    if result.timed_out() {
+        return Err(checker::TimedOut {
+            unanalyzed_files: result.unanalyzed_files().len(),
+        }
+        .into());
+    }
     if result.has_violations() {
         bail!("Violations found!")
     }
+    if configuration.strict_parsing && result.has_parse_errors() {
+        bail!("Files could not be parsed!")
+    }
+    Ok(())
+}
+
+fn render_check_result(
+    result: &checker::CheckAllResult,
+    output_format: CheckOutputFormat,
+    // Only applies to human output
+    group_by: Option<GroupBy>,
+    // Links the locations of violations in ungrouped human output
+    code_url: Option<&code_url::CodeUrl>,
+) -> anyhow::Result<String> {
+    let rendered = match (output_format, group_by) {
+        (CheckOutputFormat::Quickfix, _) => {
+            checker::quickfix::QuickfixCheckAllResult { result }.to_string()
+        }
+        (CheckOutputFormat::Human, Some(group_by)) => format!(
+            "{}\n",
+            checker::grouping::GroupedCheckAllResult { result, group_by }
+        ),
+        (CheckOutputFormat::Json, _) => format!(
+            "{}\n",
+            serde_json::to_string_pretty(
+                &checker::json::JsonCheckAllResult::new(result)
+            )
+            .context("Failed to serialize check result")?
+        ),
+        (CheckOutputFormat::Rubocop, _) => format!(
+            "{}\n",
+            serde_json::to_string_pretty(
+                &checker::rubocop::RubocopResult::new(result)
+            )
+            .context("Failed to serialize check result")?
+        ),
+        (CheckOutputFormat::Human, None) => match code_url {
+            Some(code_url) => format!(
+                "{}\n",
+                checker::LinkedCheckAllResult { result, code_url }
+            ),
+            None => format!("{}\n", result),
+        },
+    };
+    Ok(rendered)
+}
+
+pub fn check_batch(
+    absolute_root: &Path,
+    configurations: &mut [Configuration],
+) -> anyhow::Result<()> {
+    let results = batch::check_configurations(configurations)?;
+    for result in &results {
+        let project_root = result
+            .project_root
+            .strip_prefix(absolute_root)
+            .unwrap_or(&result.project_root);
+        let project_root = if project_root.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            project_root
+        };
+        println!("{}:\n{}\n", project_root.display(), result);
+    }
+    let failed = results.iter().filter(|r| r.has_violations()).count();
+    if failed > 0 {
+        bail!(
+            "Violations found in {} of {} project(s)!",
+            failed,
+            results.len()
+        )
+    }
     Ok(())
 }
 
-pub fn update(configuration: &Configuration) -> anyhow::Result<()> {
-    checker::update(configuration)
+pub fn annotate(
+    configuration: &Configuration,
+    files: Vec<String>,
+    diff: bool,
+) -> anyhow::Result<()> {
+    print!("{}", annotate::annotate(configuration, files, diff)?);
+    Ok(())
+}
+
+pub fn update(
+    configuration: &Configuration,
+    partial: &[String],
+    minimal_diff: bool,
+    outputs: &[OutputSink],
+    diff_json: Option<&Path>,
+) -> anyhow::Result<()> {
+    let before = todo_diff::loaded_entries(configuration);
+    let recorded = checker::update(configuration, partial, minimal_diff)?;
+    let diff = todo_diff::TodoDiff::new(
+        &before,
+        &todo_diff::stored_entries(configuration)?,
+    );
+    print!("{}", diff);
+    if let Some(path) = diff_json {
+        let json = serde_json::to_string_pretty(&diff)
+            .context("Failed to serialize the recorded violations diff")?;
+        std::fs::write(path, json)
+            .context(format!("Failed to write {}", path.display()))?;
+    }
+    for output in outputs {
+        output.write(&checker::render_recorded_violations(
+            &recorded,
+            output.format,
+        )?)?;
+    }
+    Ok(())
+}
+
+pub fn prune_todos(configuration: &Configuration) -> anyhow::Result<()> {
+    let removed = package_todo::prune_deleted_files(configuration)?;
+    println!(
+        "Removed {} recorded violation(s) in deleted files from package_todo.yml files",
+        removed
+    );
+    Ok(())
+}
+
+pub fn parity(
+    configuration: &mut Configuration,
+    files: Vec<String>,
+    sample_size: usize,
+) -> anyhow::Result<()> {
+    let report = parity::parity(configuration, files, sample_size)?;
+    println!("{}", report);
+    if report.has_differences() {
+        bail!("packwerk and packs reported different violations")
+    }
+    Ok(())
 }
 
 pub fn add_dependency(
@@ -218,7 +478,7 @@ pub fn add_dependency(
         &configuration.absolute_root,
         &configuration.input_files_count,
     )?;
-    let validation_result = packs::validate(&new_configuration);
+    let validation_result = packs::validate(&new_configuration, false);
     if validation_result.is_err() {
         println!("Added `{}` as a dependency to `{}`!", to, from);
         println!("Warning: This creates a cycle!");
@@ -229,6 +489,302 @@ pub fn add_dependency(
     Ok(())
 }
 
+// Reloads the packs from disk after a command rewrote them, keeping the
+// options given on the command line
+fn reload_configuration(
+    configuration: &Configuration,
+) -> anyhow::Result<Configuration> {
+    let mut new_configuration = configuration::get(
+        &configuration.absolute_root,
+        &configuration.input_files_count,
+    )?;
+    new_configuration.experimental_parser = configuration.experimental_parser;
+    new_configuration.cache_enabled = configuration.cache_enabled;
+    new_configuration.cache_directory = configuration.cache_directory.clone();
+    new_configuration.strict = configuration.strict;
+    new_configuration.max_memory = configuration.max_memory;
+    new_configuration.mmap_threshold = configuration.mmap_threshold;
+    new_configuration.snippet_lines = configuration.snippet_lines;
+    new_configuration.disable_enforce_dependencies =
+        configuration.disable_enforce_dependencies;
+    new_configuration.disable_enforce_folder_privacy =
+        configuration.disable_enforce_folder_privacy;
+    new_configuration.disable_enforce_layers =
+        configuration.disable_enforce_layers;
+    new_configuration.disable_enforce_privacy =
+        configuration.disable_enforce_privacy;
+    new_configuration.disable_enforce_visibility =
+        configuration.disable_enforce_visibility;
+    Ok(new_configuration)
+}
+
+pub fn enable_enforcement(
+    configuration: &Configuration,
+    enforcement: &str,
+    strict: bool,
+    patterns: &[String],
+) -> anyhow::Result<()> {
+    let setting = if strict {
+        CheckerSetting::Strict
+    } else {
+        CheckerSetting::True
+    };
+    let enabled_packs = enforcement_rollout::enable_enforcement(
+        configuration,
+        enforcement,
+        setting,
+        patterns,
+    )?;
+    if enabled_packs.is_empty() {
+        println!(
+            "`{}` is already enabled in every matching pack",
+            enforcement
+        );
+        return Ok(());
+    }
+    println!(
+        "Enabled `{}` in {} pack(s): {}",
+        enforcement,
+        enabled_packs.len(),
+        enabled_packs.join(", ")
+    );
+
+    let enabled_configuration = reload_configuration(configuration)?;
+    checker::update(&enabled_configuration, &[], true)?;
+    let new_todos_by_pack = enforcement_rollout::new_todos_by_pack(
+        configuration,
+        &reload_configuration(configuration)?,
+    );
+    if new_todos_by_pack.is_empty() {
+        println!("No new todos were recorded");
+    } else {
+        println!(
+            "Recorded {} new todo(s):",
+            new_todos_by_pack.values().sum::<usize>()
+        );
+        for (pack_name, new_todos) in &new_todos_by_pack {
+            println!("  {}: {}", pack_name, new_todos);
+        }
+    }
+    Ok(())
+}
+
+pub fn merge(
+    configuration: &Configuration,
+    absorbed: &str,
+    into: &str,
+) -> anyhow::Result<()> {
+    let result = merge::merge(configuration, absorbed, into)?;
+    println!(
+        "Merged `{}` into `{}`, moving {} file(s)",
+        absorbed, into, result.moved_files
+    );
+    for pack in &result.updated_packs {
+        println!("Updated `{}` to refer to `{}`", pack, into);
+    }
+
+    // The merged packs are reloaded from disk to report the violations the
+    // merge resulted in
+    let new_configuration = reload_configuration(configuration)?;
+    let check_result = checker::check_all(&new_configuration, vec![])
+        .context("Failed to check files")?;
+    if check_result.has_violations() {
+        println!("{}", check_result);
+        bail!(
+            "Merging `{}` into `{}` resulted in violations!",
+            absorbed,
+            into
+        )
+    }
+    println!("No violations found after merging!");
+    Ok(())
+}
+
+fn move_pack(
+    configuration: &Configuration,
+    from: &str,
+    to: &str,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let plan = move_pack::plan(configuration, from, to)?;
+    print!("{}", plan);
+    if dry_run {
+        return Ok(());
+    }
+
+    move_pack::apply(configuration, &plan)?;
+    println!("Moved `{}` to `{}`", plan.from, plan.to);
+    Ok(())
+}
+
+fn invert_dependency(
+    configuration: &Configuration,
+    from: &str,
+    to: &str,
+    new_pack: Option<String>,
+    apply: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let new_pack = new_pack
+        .unwrap_or_else(|| invert_dependency::default_new_pack_name(to));
+    let plan = invert_dependency::plan(configuration, from, to, &new_pack)?;
+    match format {
+        OutputFormat::Human => print!("{}", plan),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&plan)
+                .context("Failed to serialize inversion plan")?
+        ),
+    }
+    if !apply {
+        return Ok(());
+    }
+
+    let result = invert_dependency::apply(configuration, &plan)?;
+    println!(
+        "Created `{}`, moving {} file(s)",
+        plan.new_pack, result.moved_files
+    );
+    for pack in &result.updated_packs {
+        println!("Updated the dependencies of `{}`", pack);
+    }
+    println!("Run `pks update` to update package_todo.yml files");
+    Ok(())
+}
+
+fn autocorrect(
+    configuration: &Configuration,
+    apply: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let plan = autocorrect::plan(configuration)?;
+    match format {
+        OutputFormat::Human => print!("{}", plan),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&plan)
+                .context("Failed to serialize autocorrect plan")?
+        ),
+    }
+    if !apply {
+        return Ok(());
+    }
+
+    let result = autocorrect::apply(configuration, &plan)?;
+    for file in &result.created {
+        println!("Created {}", file);
+    }
+    for file in &result.skipped {
+        println!("Skipped {}, which already exists", file);
+    }
+    if !result.created.is_empty() {
+        println!(
+            "Review the facades, then reference them instead of the private constants"
+        );
+    }
+    Ok(())
+}
+
+pub fn list_parse_errors(configuration: &Configuration) -> anyhow::Result<()> {
+    let unparseable_files = checker::find_unparseable_files(configuration)?;
+    if unparseable_files.is_empty() {
+        println!("All included files were parsed successfully!");
+        return Ok(());
+    }
+    for unparseable_file in &unparseable_files {
+        for line in unparseable_file.lines() {
+            println!("{}", line);
+        }
+    }
+    bail!("{} file(s) could not be parsed", unparseable_files.len())
+}
+
+fn list_unresolved(
+    configuration: &Configuration,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let report = unresolved::find_unresolved(configuration)?;
+    match format {
+        OutputFormat::Human if report.is_empty() => {
+            println!("All references were attributed to a pack!")
+        }
+        OutputFormat::Human => print!("{}", report),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report)
+                .context("Failed to serialize unresolved constants")?
+        ),
+    }
+    Ok(())
+}
+
+fn list_dead_references(
+    configuration: &Configuration,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let dead_references = dead_references::find_dead_references(configuration)?;
+    match format {
+        OutputFormat::Human if dead_references.is_empty() => {
+            println!("No references to undefined constants!")
+        }
+        OutputFormat::Human => print!("{}", dead_references),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&dead_references)
+                .context("Failed to serialize dead references")?
+        ),
+    }
+    Ok(())
+}
+
+fn doctor(
+    absolute_root: &Path,
+    apply_overrides: &dyn Fn(&mut Configuration) -> anyhow::Result<()>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let diagnosis = doctor::diagnose(absolute_root, apply_overrides);
+    match format {
+        OutputFormat::Human => print!("{}", diagnosis),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&diagnosis)
+                .context("Failed to serialize diagnosis")?
+        ),
+    }
+    let errors = diagnosis.count(doctor::Severity::Error);
+    if errors > 0 {
+        bail!("Found {} configuration error(s)!", errors)
+    }
+    Ok(())
+}
+
+fn bench(
+    absolute_root: &Path,
+    apply_overrides: &dyn Fn(&mut Configuration) -> anyhow::Result<()>,
+    iterations: usize,
+    cache: BenchCache,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let cache_states = match cache {
+        BenchCache::Cold => vec![bench::CacheState::Cold],
+        BenchCache::Warm => vec![bench::CacheState::Warm],
+        BenchCache::Both => {
+            vec![bench::CacheState::Cold, bench::CacheState::Warm]
+        }
+    };
+    let report =
+        bench::run(absolute_root, apply_overrides, iterations, &cache_states)?;
+    match format {
+        OutputFormat::Human => print!("{}", report),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report)
+                .context("Failed to serialize benchmark report")?
+        ),
+    }
+    Ok(())
+}
+
 pub fn list_included_files(configuration: Configuration) -> anyhow::Result<()> {
     configuration
         .included_files
@@ -237,7 +793,29 @@ pub fn list_included_files(configuration: Configuration) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn validate(configuration: &Configuration) -> anyhow::Result<()> {
+pub fn validate(
+    configuration: &Configuration,
+    fix: bool,
+) -> anyhow::Result<()> {
+    if fix {
+        let removed = package_todo::prune_unknown_packs(configuration)?;
+        println!(
+            "Removed {} recorded violation(s) on packs that no longer exist from package_todo.yml files",
+            removed
+        );
+        for pack in checker::declared_dependencies::fix(configuration)? {
+            println!(
+                "Removed duplicate, self, and unknown dependencies from `{}`",
+                pack
+            );
+        }
+        // Validate the package_todo.yml files as they are now
+        let configuration = configuration::get(
+            &configuration.absolute_root,
+            &configuration.input_files_count,
+        )?;
+        return checker::validate_all(&configuration);
+    }
     checker::validate_all(configuration)
 }
 
@@ -296,10 +874,28 @@ pub fn update_dependencies_for_constant(
     }
 }
 
-pub fn list(configuration: Configuration) {
-    for pack in configuration.pack_set.packs {
-        println!("{}", pack.yml.display())
+pub fn list(
+    configuration: &Configuration,
+    filter: &PackFilter,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let infos = pack_info::list_pack_info(configuration, filter)?;
+    match format {
+        OutputFormat::Human => {
+            for info in &infos {
+                println!(
+                    "{}",
+                    configuration.absolute_root.join(&info.yml).display()
+                )
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&infos)
+                .context("Failed to serialize packs")?
+        ),
     }
+    Ok(())
 }
 
 pub fn lint_package_yml_files(
@@ -330,6 +926,9 @@ pub struct ProcessedFile {
 
     #[serde(default)] // Default to an empty Vec if not present
     pub sigils: Vec<Sigil>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parse_errors: Vec<SyntaxError>,
 }
 
 // A sigil is a way to specify some packs specific behavior at the top of a file, like
@@ -341,37 +940,54 @@ pub struct Sigil {
     pub value: bool,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Default, Eq, Clone)]
+#[derive(
+    Debug, PartialEq, Serialize, Deserialize, Default, Eq, Clone, Hash,
+)]
 pub struct SourceLocation {
     line: usize,
     column: usize,
 }
 
-pub(crate) fn list_definitions(
+// For the references built in the tests of other crates (see test_support.rs)
+#[cfg(feature = "test-helpers")]
+impl SourceLocation {
+    pub(crate) fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+fn get_constant_resolver_for_all_files(
     configuration: &Configuration,
-    ambiguous: bool,
-) -> anyhow::Result<()> {
-    let constant_resolver = if configuration.experimental_parser {
+) -> anyhow::Result<SharedConstantResolver> {
+    if configuration.experimental_parser {
         let processed_files: Vec<ProcessedFile> = process_files_with_cache(
             &configuration.included_files,
             configuration.get_cache(),
             configuration,
         )?;
 
-        get_experimental_constant_resolver(
+        Ok(Arc::from(get_experimental_constant_resolver(
             &configuration.absolute_root,
             &processed_files,
             &configuration.ignored_definitions,
-        )
+        )))
     } else {
-        if ambiguous {
-            bail!("Ambiguous mode is not supported for the Zeitwerk parser");
-        }
-        get_zeitwerk_constant_resolver(
-            &configuration.pack_set,
-            &configuration.constant_resolver_configuration(),
+        Ok(
+            reference_extractor::zeitwerk_constant_resolver_for_all_files(
+                configuration,
+            ),
         )
-    };
+    }
+}
+
+pub(crate) fn list_definitions(
+    configuration: &Configuration,
+    ambiguous: bool,
+) -> anyhow::Result<()> {
+    if ambiguous && !configuration.experimental_parser {
+        bail!("Ambiguous mode is not supported for the Zeitwerk parser");
+    }
+    let constant_resolver = get_constant_resolver_for_all_files(configuration)?;
 
     let constant_definition_map = constant_resolver
         .fully_qualified_constant_name_to_constant_definition_map();
@@ -392,6 +1008,26 @@ pub(crate) fn list_definitions(
     Ok(())
 }
 
+fn export_definitions(
+    configuration: &Configuration,
+    format: ExportFormat,
+) -> anyhow::Result<()> {
+    let constant_resolver = get_constant_resolver_for_all_files(configuration)?;
+    let index = definition_export::build_index(
+        configuration,
+        constant_resolver.as_ref(),
+    )?;
+    match format {
+        ExportFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&index)
+                .context("Failed to serialize definitions")?
+        ),
+        ExportFormat::Sql => print!("{}", definition_export::to_sql(&index)),
+    }
+    Ok(())
+}
+
 fn expose_monkey_patches(
     configuration: &Configuration,
     rubydir: &PathBuf,
@@ -408,6 +1044,269 @@ fn expose_monkey_patches(
     Ok(())
 }
 
+fn info(
+    configuration: &Configuration,
+    pack_name: &str,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let pack_info = pack_info::find_pack_info(configuration, pack_name)?;
+    match format {
+        OutputFormat::Human => println!("{}", pack_info),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&pack_info)
+                .context("Failed to serialize pack info")?
+        ),
+    }
+    Ok(())
+}
+
+fn public_api(
+    configuration: &Configuration,
+    pack_name: &str,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let public_api = public_api::find_public_api(configuration, pack_name)?;
+    match format {
+        OutputFormat::Human => println!("{}", public_api),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&public_api)
+                .context("Failed to serialize public API")?
+        ),
+    }
+    Ok(())
+}
+
+fn public_entrypoints(
+    configuration: &Configuration,
+    constant_name: &str,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let public_entrypoints =
+        public_api::find_public_entrypoints(configuration, constant_name)?;
+    match format {
+        OutputFormat::Human => println!("{}", public_entrypoints),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&public_entrypoints)
+                .context("Failed to serialize public entrypoints")?
+        ),
+    }
+    Ok(())
+}
+
+fn stats(
+    configuration: &Configuration,
+    by_product_group: bool,
+    // Set to only include the packs changed since this revision
+    changed_since: Option<&str>,
+    record: bool,
+    // Set to compare against the latest snapshot, or the latest one taken on
+    // or before the date
+    diff_since: Option<Option<&str>>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let absolute_root = &configuration.absolute_root;
+    if record {
+        let path = stats::history::record(
+            absolute_root,
+            stats::collect_stats(configuration, false, None),
+        )?;
+        eprintln!("Recorded stats in {}", path.display());
+    }
+    let only_pack_names = changed_since
+        .map(|base| git::changed_packs(configuration, base))
+        .transpose()?;
+    let stats = stats::collect_stats(
+        configuration,
+        by_product_group,
+        only_pack_names.as_ref(),
+    );
+    if let Some(date) = diff_since {
+        let snapshot = stats::history::find_snapshot(absolute_root, date)?;
+        let before = stats::regroup_stats(
+            configuration,
+            &snapshot.stats,
+            by_product_group,
+            only_pack_names.as_ref(),
+        );
+        let diff = stats::history::diff(&snapshot.date, &before, &stats);
+        match format {
+            OutputFormat::Human => print!("{}", diff),
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&diff)
+                    .context("Failed to serialize stats")?
+            ),
+        }
+        return Ok(());
+    }
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            stats::write_stats(&mut output, &stats)
+                .context("Failed to format stats")?;
+            print!("{}", output);
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&stats)
+                .context("Failed to serialize stats")?
+        ),
+    }
+    Ok(())
+}
+
+fn changed_packs(
+    configuration: &Configuration,
+    base: &str,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let pack_names = git::changed_packs(configuration, base)?;
+    match format {
+        OutputFormat::Human => {
+            for pack_name in &pack_names {
+                println!("{}", pack_name);
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&pack_names)
+                .context("Failed to serialize changed packs")?
+        ),
+    }
+    Ok(())
+}
+
+fn graph_metrics(
+    configuration: &Configuration,
+    edges: GraphEdges,
+    top: usize,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let metrics = graph_metrics::graph_metrics(configuration, edges, top);
+    match format {
+        OutputFormat::Human => print!("{}", metrics),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&metrics)
+                .context("Failed to serialize graph metrics")?
+        ),
+    }
+    Ok(())
+}
+
+fn hotspots(
+    configuration: &Configuration,
+    since: Option<&str>,
+    top: usize,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let hotspots = hotspots::hotspots(configuration, since, top)?;
+    match format {
+        OutputFormat::Human => print!("{}", hotspots),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&hotspots)
+                .context("Failed to serialize hotspots")?
+        ),
+    }
+    Ok(())
+}
+
+fn export_edges(
+    configuration: &Configuration,
+    format: EdgeFormat,
+) -> anyhow::Result<()> {
+    let edges = edge_export::export_edges(configuration)?;
+    match format {
+        EdgeFormat::Csv => print!("{}", edge_export::to_csv(&edges)),
+        EdgeFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&edges)
+                .context("Failed to serialize edges")?
+        ),
+    }
+    Ok(())
+}
+
+fn suggest_split(
+    configuration: &Configuration,
+    pack_name: &str,
+    candidates: usize,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let suggestion =
+        split_suggestion::suggest_split(configuration, pack_name, candidates)?;
+    match format {
+        OutputFormat::Human => print!("{}", suggestion),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&suggestion)
+                .context("Failed to serialize split suggestion")?
+        ),
+    }
+    Ok(())
+}
+
+fn report(
+    configuration: &Configuration,
+    format: Option<ReportFormat>,
+    output_directory: &Path,
+    by_owner: bool,
+    by_defining_owner: bool,
+    base: Option<&str>,
+) -> anyhow::Result<()> {
+    if base.is_some() && format != Some(ReportFormat::Markdown) {
+        bail!("`--base` summarizes the changes in Markdown, so pass `--format markdown`");
+    }
+    let report = report::build_report(configuration)?;
+    if let Some(base) = base {
+        let recorded_at_base =
+            report::pull_request::recorded_at_base(configuration, base)?;
+        let comparison =
+            report::pull_request::compare(&report, &recorded_at_base, base);
+        print!(
+            "{}",
+            report::pull_request::render_markdown(&comparison)
+                .context("Failed to render the report")?
+        );
+        return Ok(());
+    }
+    if by_owner {
+        let digests =
+            report::owners::build_owner_digests(&report, by_defining_owner);
+        let owners_directory = report::owners::write_owner_digests(
+            &digests,
+            output_directory,
+            format.unwrap_or(ReportFormat::Markdown),
+        )?;
+        println!(
+            "Wrote {} owner digest(s) to {}",
+            digests.len(),
+            owners_directory.display()
+        );
+        return Ok(());
+    }
+    match format.unwrap_or(ReportFormat::Html) {
+        ReportFormat::Html => {
+            report::write_html_report(&report, output_directory)?;
+            println!(
+                "Wrote report to {}",
+                output_directory.join("index.html").display()
+            );
+        }
+        ReportFormat::Markdown => {
+            bail!("Markdown reports are digests per owner or summaries of the changes since a base revision, so pass `--by-owner` or `--base`")
+        }
+        ReportFormat::Json => {
+            bail!("JSON reports are digests per owner, so pass `--by-owner`")
+        }
+    }
+    Ok(())
+}
+
 fn list_dependencies(
     configuration: &Configuration,
     pack_name: String,
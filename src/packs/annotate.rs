@@ -0,0 +1,151 @@
+// Renders files with `# pks: <violation>` comments appended to the lines that
+// contain violations, either inline or as a unified diff, for consumption by
+// code review tooling and editor gutters.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use anyhow::Context;
+
+use super::checker;
+use super::Configuration;
+
+const ANNOTATION_PREFIX: &str = "# pks:";
+
+// Relative file -> 1-based line -> violation descriptions
+type Annotations = BTreeMap<String, BTreeMap<usize, Vec<String>>>;
+
+pub(crate) fn annotate(
+    configuration: &Configuration,
+    files: Vec<String>,
+    diff: bool,
+) -> anyhow::Result<String> {
+    let result = checker::check_all(configuration, files)
+        .context("Failed to check files")?;
+
+    let mut annotations: Annotations = BTreeMap::new();
+    for violation in result.reportable_violations() {
+        annotations
+            .entry(violation.identifier.file.to_string())
+            .or_default()
+            .entry(violation.source_location.line)
+            .or_default()
+            .push(violation.description());
+    }
+
+    let mut output = String::new();
+    for (file, lines) in annotations.iter_mut() {
+        lines
+            .values_mut()
+            .for_each(|descriptions| descriptions.sort());
+
+        let contents =
+            std::fs::read_to_string(configuration.absolute_root.join(file))
+                .context(format!("Failed to read {}", file))?;
+        if diff {
+            write_diff(&mut output, file, &contents, lines)?;
+        } else {
+            write_annotated_file(&mut output, file, &contents, lines)?;
+        }
+    }
+    Ok(output)
+}
+
+fn annotated_line(line: &str, descriptions: &[String]) -> String {
+    format!(
+        "{} {} {}",
+        line.trim_end(),
+        ANNOTATION_PREFIX,
+        descriptions.join("; ")
+    )
+}
+
+fn write_annotated_file(
+    output: &mut String,
+    file: &str,
+    contents: &str,
+    lines: &BTreeMap<usize, Vec<String>>,
+) -> std::fmt::Result {
+    writeln!(output, "==> {} <==", file)?;
+    for (index, line) in contents.lines().enumerate() {
+        match lines.get(&(index + 1)) {
+            Some(descriptions) => {
+                writeln!(output, "{}", annotated_line(line, descriptions))?
+            }
+            None => writeln!(output, "{}", line)?,
+        }
+    }
+    writeln!(output)
+}
+
+fn write_diff(
+    output: &mut String,
+    file: &str,
+    contents: &str,
+    lines: &BTreeMap<usize, Vec<String>>,
+) -> std::fmt::Result {
+    let file_lines: Vec<&str> = contents.lines().collect();
+
+    writeln!(output, "--- a/{}", file)?;
+    writeln!(output, "+++ b/{}", file)?;
+    for (line_number, descriptions) in lines {
+        if let Some(line) = file_lines.get(line_number - 1) {
+            writeln!(output, "@@ -{},1 +{},1 @@", line_number, line_number)?;
+            writeln!(output, "-{}", line)?;
+            writeln!(output, "+{}", annotated_line(line, descriptions))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn lines() -> BTreeMap<usize, Vec<String>> {
+        BTreeMap::from([(
+            2,
+            vec![
+                String::from("Dependency violation on `::Bar`"),
+                String::from("Privacy violation on `::Bar`"),
+            ],
+        )])
+    }
+
+    #[test]
+    fn test_write_annotated_file() {
+        let mut output = String::new();
+        write_annotated_file(
+            &mut output,
+            "foo.rb",
+            "class Foo\n  Bar\nend\n",
+            &lines(),
+        )
+        .unwrap();
+
+        let expected = "\
+==> foo.rb <==
+class Foo
+  Bar # pks: Dependency violation on `::Bar`; Privacy violation on `::Bar`
+end
+
+";
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn test_write_diff() {
+        let mut output = String::new();
+        write_diff(&mut output, "foo.rb", "class Foo\n  Bar\nend\n", &lines())
+            .unwrap();
+
+        let expected = "\
+--- a/foo.rb
++++ b/foo.rb
+@@ -2,1 +2,1 @@
+-  Bar
++  Bar # pks: Dependency violation on `::Bar`; Privacy violation on `::Bar`
+";
+        assert_eq!(expected, output);
+    }
+}
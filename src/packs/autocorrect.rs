@@ -0,0 +1,278 @@
+// Proposes a public facade for each private constant that other packs
+// reference, so that they can reference the facade instead. A facade is a
+// module in the public folder of the defining pack, delegating every call to
+// the private constant. The generated Ruby is a starting point to review, not
+// a finished API.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use super::checker::find_violations_in_references;
+use super::reference_extractor::get_all_references_and_sigils;
+use super::Configuration;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct AutocorrectPlan {
+    // Sorted by pack, then constant
+    pub facades: Vec<Facade>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct Facade {
+    // The pack defining the private constant
+    pub pack: String,
+    pub constant: String,
+    pub facade: String,
+    pub file: String,
+    pub exists: bool,
+    // The files referencing the private constant from other packs
+    pub references: Vec<FacadeReference>,
+    pub contents: String,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct FacadeReference {
+    pub pack: String,
+    pub file: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct AutocorrectResult {
+    pub created: Vec<String>,
+    // Facades left alone, since their file already exists
+    pub skipped: Vec<String>,
+}
+
+impl fmt::Display for AutocorrectPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.facades.is_empty() {
+            return writeln!(f, "No privacy violations to autocorrect");
+        }
+        writeln!(
+            f,
+            "{} public facade(s) would fix the privacy violations:",
+            self.facades.len()
+        )?;
+        for facade in &self.facades {
+            writeln!(
+                f,
+                "  {} defines {}, delegating to {}{}",
+                facade.file,
+                facade.facade,
+                facade.constant,
+                if facade.exists {
+                    " (already exists)"
+                } else {
+                    ""
+                }
+            )?;
+            for reference in &facade.references {
+                writeln!(
+                    f,
+                    "    referenced from {} ({})",
+                    reference.file, reference.pack
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn plan(
+    configuration: &Configuration,
+) -> anyhow::Result<AutocorrectPlan> {
+    let (references, sigils, _parse_errors) = get_all_references_and_sigils(
+        configuration,
+        &configuration.included_files,
+    )?;
+    let violations =
+        find_violations_in_references(configuration, &references, &sigils)?;
+
+    let mut referencing_files: BTreeMap<
+        (&str, &str),
+        BTreeSet<FacadeReference>,
+    > = BTreeMap::new();
+    for violation in &violations {
+        let identifier = &violation.identifier;
+        if identifier.violation_type != "privacy" {
+            continue;
+        }
+        referencing_files
+            .entry((
+                identifier.defining_pack_name.as_str(),
+                identifier.constant_name.as_str(),
+            ))
+            .or_default()
+            .insert(FacadeReference {
+                pack: identifier.referencing_pack_name.to_string(),
+                file: identifier.file.to_string(),
+            });
+    }
+
+    let mut facades = vec![];
+    for ((pack_name, constant), references) in referencing_files {
+        let pack = configuration.pack_set.for_pack(pack_name)?;
+        let (facade, path) = facade_name_and_path(constant);
+        let file = pack.public_folder().join(path).display().to_string();
+        let references: Vec<FacadeReference> = references.into_iter().collect();
+        facades.push(Facade {
+            pack: pack_name.to_string(),
+            constant: constant.to_string(),
+            contents: render(constant, &facade, &references),
+            exists: configuration.absolute_root.join(&file).exists(),
+            facade,
+            file,
+            references,
+        });
+    }
+    Ok(AutocorrectPlan { facades })
+}
+
+// Writes the facades whose file doesn't exist yet
+pub(crate) fn apply(
+    configuration: &Configuration,
+    plan: &AutocorrectPlan,
+) -> anyhow::Result<AutocorrectResult> {
+    let mut result = AutocorrectResult {
+        created: vec![],
+        skipped: vec![],
+    };
+    for facade in &plan.facades {
+        let path = configuration.absolute_root.join(&facade.file);
+        if path.exists() {
+            result.skipped.push(facade.file.clone());
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create directory {}",
+                parent.display()
+            ))?;
+        }
+        fs::write(&path, &facade.contents)
+            .context(format!("Failed to write {}", path.display()))?;
+        result.created.push(facade.file.clone());
+    }
+    Ok(result)
+}
+
+// `::Bar::Widget` is wrapped by `Bar::WidgetFacade`, which Zeitwerk expects
+// in `bar/widget_facade.rb` of the public folder
+fn facade_name_and_path(constant: &str) -> (String, String) {
+    let facade = format!("{}Facade", constant.trim_start_matches("::"));
+    let path = facade
+        .split("::")
+        .map(underscore)
+        .collect::<Vec<String>>()
+        .join("/");
+    (facade, format!("{}.rb", path))
+}
+
+// Like ActiveSupport's `underscore`, without acronyms, e.g. `HTTPClient` is
+// `http_client`
+fn underscore(camel_cased: &str) -> String {
+    let chars: Vec<char> = camel_cased.chars().collect();
+    let mut underscored = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lowercase =
+                chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lowercase)
+            {
+                underscored.push('_');
+            }
+        }
+        underscored.extend(c.to_lowercase());
+    }
+    underscored
+}
+
+fn render(
+    constant: &str,
+    facade: &str,
+    references: &[FacadeReference],
+) -> String {
+    let mut ruby = String::from("# frozen_string_literal: true\n\n");
+    ruby.push_str(&format!(
+        "# Generated by `pks autocorrect` as the public API for {}, which is\n\
+         # private to its pack. Review it, then reference it instead of {}\n\
+         # from:\n",
+        constant, constant
+    ));
+    for reference in references {
+        ruby.push_str(&format!("#   {}\n", reference.file));
+    }
+    ruby.push_str(&format!(
+        "module {facade}\n  \
+           def self.method_missing(method_name, *args, **kwargs, &block)\n    \
+             if {constant}.respond_to?(method_name)\n      \
+               {constant}.public_send(method_name, *args, **kwargs, &block)\n    \
+             else\n      \
+               super\n    \
+             end\n  \
+           end\n\n  \
+           def self.respond_to_missing?(method_name, include_private = false)\n    \
+             {constant}.respond_to?(method_name) || super\n  \
+           end\n\
+         end\n",
+    ));
+    ruby
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_facade_name_and_path() {
+        assert_eq!(
+            (String::from("BarFacade"), String::from("bar_facade.rb")),
+            facade_name_and_path("::Bar")
+        );
+        assert_eq!(
+            (
+                String::from("Bar::HTTPClientFacade"),
+                String::from("bar/http_client_facade.rb")
+            ),
+            facade_name_and_path("::Bar::HTTPClient")
+        );
+        assert_eq!("v2_api", underscore("V2Api"));
+    }
+
+    #[test]
+    fn test_render() {
+        let references = vec![FacadeReference {
+            pack: String::from("packs/foo"),
+            file: String::from("packs/foo/app/services/foo.rb"),
+        }];
+        assert_eq!(
+            "# frozen_string_literal: true\n\
+             \n\
+             # Generated by `pks autocorrect` as the public API for ::Bar, which is\n\
+             # private to its pack. Review it, then reference it instead of ::Bar\n\
+             # from:\n\
+             #   packs/foo/app/services/foo.rb\n\
+             module BarFacade\n  \
+               def self.method_missing(method_name, *args, **kwargs, &block)\n    \
+                 if ::Bar.respond_to?(method_name)\n      \
+                   ::Bar.public_send(method_name, *args, **kwargs, &block)\n    \
+                 else\n      \
+                   super\n    \
+                 end\n  \
+               end\n\
+             \n  \
+               def self.respond_to_missing?(method_name, include_private = false)\n    \
+                 ::Bar.respond_to?(method_name) || super\n  \
+               end\n\
+             end\n",
+            render("::Bar", "BarFacade", &references)
+        );
+    }
+}
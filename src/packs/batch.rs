@@ -0,0 +1,114 @@
+// Checks several projects, e.g. the packwerk-enabled apps of a meta-repo, in
+// one process. The projects share the rayon thread pool, and optionally the
+// cache of processed files, which is keyed by absolute path. Each project
+// keeps its own constant resolver cache, and builds its constant resolvers
+// once, for the reference extraction and the checkers of its check.
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use super::checker::{self, CheckAllResult};
+use super::configuration::{self, Configuration};
+
+pub struct BatchCheckResult {
+    pub project_root: PathBuf,
+    result: CheckAllResult,
+}
+
+impl BatchCheckResult {
+    pub fn has_violations(&self) -> bool {
+        self.result.has_violations()
+    }
+}
+
+impl fmt::Display for BatchCheckResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.result)
+    }
+}
+
+// Checks every file of each project, in order
+pub fn check_batch(
+    project_roots: &[PathBuf],
+    shared_cache_directory: Option<&Path>,
+) -> anyhow::Result<Vec<BatchCheckResult>> {
+    let mut configurations =
+        load_configurations(project_roots, shared_cache_directory)?;
+    check_configurations(&mut configurations)
+}
+
+pub(crate) fn load_configurations(
+    project_roots: &[PathBuf],
+    shared_cache_directory: Option<&Path>,
+) -> anyhow::Result<Vec<Configuration>> {
+    project_roots
+        .iter()
+        .map(|project_root| {
+            let absolute_root = project_root.canonicalize().context(
+                format!("Project root {} not found", project_root.display()),
+            )?;
+            let mut configuration = configuration::get(&absolute_root, &0)
+                .context(format!(
+                    "Failed to load the configuration of {}",
+                    project_root.display()
+                ))?;
+            configuration.file_cache_directory =
+                shared_cache_directory.map(Path::to_path_buf);
+            Ok(configuration)
+        })
+        .collect()
+}
+
+pub(crate) fn check_configurations(
+    configurations: &mut [Configuration],
+) -> anyhow::Result<Vec<BatchCheckResult>> {
+    configurations
+        .iter_mut()
+        .map(|configuration| {
+            configuration.warm_constant_resolvers().context(format!(
+                "Failed to build the constant resolvers of {}",
+                configuration.absolute_root.display()
+            ))?;
+            let configuration = &*configuration;
+            let result =
+                checker::check_all(configuration, vec![]).context(format!(
+                    "Failed to check {}",
+                    configuration.absolute_root.display()
+                ))?;
+            Ok(BatchCheckResult {
+                project_root: configuration.absolute_root.clone(),
+                result,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::packs::reference_extractor::zeitwerk_constant_resolver_for_all_files;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_configurations_shares_warm_constant_resolvers() {
+        let mut configurations = load_configurations(
+            &[PathBuf::from("tests/fixtures/simple_app")],
+            None,
+        )
+        .unwrap();
+        let cold_result =
+            checker::check_all(&configurations[0], vec![]).unwrap();
+
+        let results = check_configurations(&mut configurations).unwrap();
+        let configuration = &configurations[0];
+        let warm = configuration.constant_resolvers.clone().unwrap();
+        assert!(Arc::ptr_eq(
+            &warm.get(&None).unwrap(),
+            &zeitwerk_constant_resolver_for_all_files(configuration)
+        ));
+        assert_eq!(cold_result.to_string(), results[0].to_string());
+    }
+}
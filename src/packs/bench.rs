@@ -0,0 +1,355 @@
+// Times each stage of `check` over several runs, with the cache cold and
+// warm, so that performance can be compared across releases on real projects.
+// Runs use a cache in a temporary directory, so the project's own cache is
+// neither used nor cleared, and nothing is written to the project.
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use serde::{Serialize, Serializer};
+
+use super::checker::find_violations_in_references;
+use super::package_todo::{read_package_todo, render_package_todos};
+use super::reference_extractor::{
+    process_files_for_references, resolve_references,
+};
+use super::{configuration, Configuration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Stage {
+    // Loading the configuration, which walks the project directory and reads
+    // the package.yml files
+    Walk,
+    // Parsing the included files, or reading them from the cache
+    Parse,
+    // Building the constant resolver and resolving references
+    Resolve,
+    // Running the checkers on the references
+    Check,
+    // Reading the package_todo.yml files, and rendering them from the
+    // violations found, without writing them
+    TodoIo,
+}
+
+const STAGES: [Stage; 5] = [
+    Stage::Walk,
+    Stage::Parse,
+    Stage::Resolve,
+    Stage::Check,
+    Stage::TodoIo,
+];
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Walk => write!(f, "walk"),
+            Stage::Parse => write!(f, "parse"),
+            Stage::Resolve => write!(f, "resolve"),
+            Stage::Check => write!(f, "check"),
+            Stage::TodoIo => write!(f, "todo_io"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CacheState {
+    // The cache is cleared before each run
+    Cold,
+    // The cache is filled by an untimed run first
+    Warm,
+}
+
+impl fmt::Display for CacheState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheState::Cold => write!(f, "Cold cache"),
+            CacheState::Warm => write!(f, "Warm cache"),
+        }
+    }
+}
+
+// How long a stage, or a whole run, took over the timed runs
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct Timing {
+    // A stage, or `total`
+    pub name: String,
+    #[serde(rename = "min_seconds", serialize_with = "serialize_seconds")]
+    pub min: Duration,
+    #[serde(rename = "median_seconds", serialize_with = "serialize_seconds")]
+    pub median: Duration,
+    #[serde(rename = "mean_seconds", serialize_with = "serialize_seconds")]
+    pub mean: Duration,
+    #[serde(rename = "max_seconds", serialize_with = "serialize_seconds")]
+    pub max: Duration,
+}
+
+fn serialize_seconds<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+impl Timing {
+    fn new(name: String, mut durations: Vec<Duration>) -> Self {
+        durations.sort();
+        let count = durations.len();
+        let median = if count % 2 == 0 {
+            (durations[count / 2 - 1] + durations[count / 2]) / 2
+        } else {
+            durations[count / 2]
+        };
+        Self {
+            name,
+            min: durations[0],
+            median,
+            mean: durations.iter().sum::<Duration>() / count as u32,
+            max: durations[count - 1],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct Variant {
+    pub cache: CacheState,
+    // One per stage, in pipeline order, then the total
+    pub timings: Vec<Timing>,
+}
+
+impl Variant {
+    // Each run has the duration of every stage, in pipeline order
+    fn new(cache: CacheState, runs: &[Vec<Duration>]) -> Self {
+        let mut timings: Vec<Timing> = STAGES
+            .iter()
+            .enumerate()
+            .map(|(index, stage)| {
+                Timing::new(
+                    stage.to_string(),
+                    runs.iter().map(|run| run[index]).collect(),
+                )
+            })
+            .collect();
+        timings.push(Timing::new(
+            String::from("total"),
+            runs.iter()
+                .map(|run| run.iter().sum::<Duration>())
+                .collect(),
+        ));
+        Self { cache, timings }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct BenchReport {
+    pub iterations: usize,
+    pub files: usize,
+    pub packs: usize,
+    pub variants: Vec<Variant>,
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Timed {} run(s) over {} file(s) in {} pack(s)",
+            self.iterations, self.files, self.packs
+        )?;
+        for variant in &self.variants {
+            writeln!(f)?;
+            writeln!(f, "{}:", variant.cache)?;
+            writeln!(
+                f,
+                "  {:<10}{:>10}{:>10}{:>10}{:>10}",
+                "stage", "min", "median", "mean", "max"
+            )?;
+            for timing in &variant.timings {
+                writeln!(
+                    f,
+                    "  {:<10}{:>10}{:>10}{:>10}{:>10}",
+                    timing.name,
+                    format_duration(timing.min),
+                    format_duration(timing.median),
+                    format_duration(timing.mean),
+                    format_duration(timing.max)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+// Times `iterations` runs for each of the cache states
+pub(crate) fn run(
+    absolute_root: &Path,
+    apply_overrides: &dyn Fn(&mut Configuration) -> anyhow::Result<()>,
+    iterations: usize,
+    cache_states: &[CacheState],
+) -> anyhow::Result<BenchReport> {
+    let cache_directory =
+        std::env::temp_dir().join(format!("pks-bench-{}", std::process::id()));
+    let report = run_in(
+        absolute_root,
+        apply_overrides,
+        iterations,
+        cache_states,
+        &cache_directory,
+    );
+    clear_cache(&cache_directory)?;
+    report
+}
+
+fn run_in(
+    absolute_root: &Path,
+    apply_overrides: &dyn Fn(&mut Configuration) -> anyhow::Result<()>,
+    iterations: usize,
+    cache_states: &[CacheState],
+    cache_directory: &Path,
+) -> anyhow::Result<BenchReport> {
+    let mut report = BenchReport {
+        iterations,
+        files: 0,
+        packs: 0,
+        variants: Vec::new(),
+    };
+    for cache_state in cache_states {
+        if *cache_state == CacheState::Warm {
+            clear_cache(cache_directory)?;
+            time_run(absolute_root, apply_overrides, cache_directory)?;
+        }
+        let mut runs = Vec::new();
+        for _ in 0..iterations {
+            if *cache_state == CacheState::Cold {
+                clear_cache(cache_directory)?;
+            }
+            let (durations, configuration) =
+                time_run(absolute_root, apply_overrides, cache_directory)?;
+            report.files = configuration.included_files.len();
+            report.packs = configuration.pack_set.packs.len();
+            runs.push(durations);
+        }
+        report.variants.push(Variant::new(*cache_state, &runs));
+    }
+    Ok(report)
+}
+
+fn clear_cache(cache_directory: &Path) -> anyhow::Result<()> {
+    if cache_directory.exists() {
+        std::fs::remove_dir_all(cache_directory).context(format!(
+            "Failed to remove {}",
+            cache_directory.display()
+        ))?;
+    }
+    Ok(())
+}
+
+// The duration of each stage, in pipeline order, and the configuration loaded
+fn time_run(
+    absolute_root: &Path,
+    apply_overrides: &dyn Fn(&mut Configuration) -> anyhow::Result<()>,
+    cache_directory: &Path,
+) -> anyhow::Result<(Vec<Duration>, Configuration)> {
+    let mut durations = Vec::with_capacity(STAGES.len());
+
+    let started = Instant::now();
+    let mut configuration = configuration::get(absolute_root, &0)?;
+    apply_overrides(&mut configuration)?;
+    configuration.cache_enabled = true;
+    configuration.cache_directory = cache_directory.to_path_buf();
+    configuration.file_cache_directory = None;
+    durations.push(started.elapsed());
+
+    let started = Instant::now();
+    let processed_files = process_files_for_references(
+        &configuration,
+        &configuration.included_files,
+    )?;
+    durations.push(started.elapsed());
+
+    let started = Instant::now();
+    let (references, sigils, _parse_errors) = resolve_references(
+        &configuration,
+        &configuration.included_files,
+        processed_files,
+    )?;
+    durations.push(started.elapsed());
+
+    let started = Instant::now();
+    let violations =
+        find_violations_in_references(&configuration, &references, &sigils)?;
+    durations.push(started.elapsed());
+
+    let started = Instant::now();
+    for pack in &configuration.pack_set.packs {
+        read_package_todo(&pack.package_todo_yml)?;
+    }
+    render_package_todos(&configuration, violations);
+    durations.push(started.elapsed());
+
+    Ok((durations, configuration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn ms(milliseconds: u64) -> Duration {
+        Duration::from_millis(milliseconds)
+    }
+
+    #[test]
+    fn test_timing() {
+        let timing = Timing::new(
+            String::from("parse"),
+            vec![ms(4), ms(1), ms(3), ms(8)],
+        );
+
+        assert_eq!(
+            timing,
+            Timing {
+                name: String::from("parse"),
+                min: ms(1),
+                median: ms(3) + Duration::from_micros(500),
+                mean: ms(4),
+                max: ms(8),
+            }
+        );
+    }
+
+    #[test]
+    fn test_display_report() {
+        let runs = vec![
+            vec![ms(1), ms(2), ms(3), ms(4), ms(5)],
+            vec![ms(3), ms(2), ms(3), ms(4), ms(5)],
+        ];
+        let report = BenchReport {
+            iterations: 2,
+            files: 10,
+            packs: 3,
+            variants: vec![Variant::new(CacheState::Warm, &runs)],
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "\
+Timed 2 run(s) over 10 file(s) in 3 pack(s)
+
+Warm cache:
+  stage            min    median      mean       max
+  walk           1.0ms     2.0ms     2.0ms     3.0ms
+  parse          2.0ms     2.0ms     2.0ms     2.0ms
+  resolve        3.0ms     3.0ms     3.0ms     3.0ms
+  check          4.0ms     4.0ms     4.0ms     4.0ms
+  todo_io        5.0ms     5.0ms     5.0ms     5.0ms
+  total         15.0ms    16.0ms    16.0ms    17.0ms
+"
+        );
+    }
+}
@@ -172,6 +172,7 @@ mod tests {
                 }],
                 definitions: vec![],
                 sigils: vec![],
+                parse_errors: vec![],
             }
         };
 
@@ -0,0 +1,260 @@
+// Caches the violations found in each file, so that the checkers are skipped
+// for files whose references resolve to the same constants as on the last
+// run, and whose involved packs are configured the same way.
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::packs::checker::reference::Reference;
+use crate::packs::checker::Violation;
+use crate::packs::file_utils::file_content_digest;
+use crate::packs::parsing::ruby::parse_utils::extract_sigils_from_contents;
+use crate::packs::{Configuration, Sigil};
+
+use super::create_cache_dir_idempotently;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ViolationCacheEntry {
+    key: String,
+    violations: Vec<Violation>,
+}
+
+pub(crate) struct ViolationCache {
+    cache_dir: PathBuf,
+    // Covers packwerk.yml, the options given on the command line or by a
+    // profile, and the version of pks, since checkers may change between
+    // versions
+    configuration_digest: String,
+    // Pack name -> digest of its package.yml
+    pack_digests: HashMap<String, String>,
+}
+
+impl ViolationCache {
+    // None when the cache is disabled
+    pub(crate) fn new(
+        configuration: &Configuration,
+    ) -> anyhow::Result<Option<ViolationCache>> {
+        if !configuration.cache_enabled {
+            return Ok(None);
+        }
+
+        let mut context = md5::Context::new();
+        context.consume(env!("CARGO_PKG_VERSION"));
+        for configuration_file in ["packwerk.yml", "packs.yml"] {
+            let path = configuration.absolute_root.join(configuration_file);
+            if path.exists() {
+                context.consume(file_content_digest(&path)?);
+            }
+        }
+        context.consume(format!(
+            "{:?}",
+            [
+                configuration.disable_enforce_dependencies,
+                configuration.disable_enforce_folder_privacy,
+                configuration.disable_enforce_layers,
+                configuration.disable_enforce_privacy,
+                configuration.disable_enforce_visibility,
+            ]
+        ));
+        context.consume(format!("{:?}", configuration.checkers));
+        // The rest of the settings a profile can override (see
+        // `Configuration::apply_profile`)
+        context.consume(format!(
+            "{:?}",
+            (
+                configuration.strict,
+                configuration.strict_parsing,
+                configuration.output_format,
+                &configuration.cache_directory,
+            )
+        ));
+
+        let mut pack_digests = HashMap::new();
+        for pack in &configuration.pack_set.packs {
+            let digest = if pack.yml.exists() {
+                file_content_digest(&pack.yml)?
+            } else {
+                String::new()
+            };
+            pack_digests.insert(pack.name.clone(), digest);
+        }
+
+        let cache_dir = configuration.cache_directory.join("violations");
+        create_cache_dir_idempotently(&cache_dir);
+
+        Ok(Some(ViolationCache {
+            cache_dir,
+            configuration_digest: format!("{:x}", context.compute()),
+            pack_digests,
+        }))
+    }
+
+    // The references of a file, as resolved on this run, along with the
+    // configuration of the packs involved and the sigils of the files that
+    // define the referenced constants (see the privacy checker)
+    pub(crate) fn key(
+        &self,
+        configuration: &Configuration,
+        references: &[&Reference],
+        sigils: &HashMap<PathBuf, Vec<Sigil>>,
+    ) -> String {
+        let mut context = md5::Context::new();
+        context.consume(&self.configuration_digest);
+
+        let mut involved_packs: BTreeSet<&str> = BTreeSet::new();
+        let mut defining_files: BTreeSet<&str> = BTreeSet::new();
+        for reference in references {
+            context.consume(format!(
+                "{}|{:?}|{:?}|{}|{:?}\n",
+                reference.constant_name,
+                reference.defining_pack_name,
+                reference.relative_defining_file,
+                reference.referencing_pack_name,
+                reference.source_location,
+            ));
+            involved_packs.insert(&reference.referencing_pack_name);
+            if let Some(defining_pack_name) = &reference.defining_pack_name {
+                involved_packs.insert(defining_pack_name);
+            }
+            if let Some(defining_file) = &reference.relative_defining_file {
+                defining_files.insert(defining_file);
+            }
+        }
+
+        for pack_name in involved_packs {
+            context.consume(format!(
+                "{}:{}\n",
+                pack_name,
+                self.pack_digests
+                    .get(pack_name)
+                    .map(String::as_str)
+                    .unwrap_or_default()
+            ));
+        }
+        for defining_file in defining_files {
+            context.consume(format!(
+                "{}:{:?}\n",
+                defining_file,
+                defining_file_sigils(configuration, sigils, defining_file)
+            ));
+        }
+        format!("{:x}", context.compute())
+    }
+
+    pub(crate) fn get(&self, file: &str, key: &str) -> Option<Vec<Violation>> {
+        let path = self.path_for(file);
+        if !path.exists() {
+            return None;
+        }
+        let entry: ViolationCacheEntry = match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| {
+                serde_json::from_str(&contents).map_err(anyhow::Error::from)
+            }) {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Failed to read cache file {:?}: {}", path, e);
+                return None;
+            }
+        };
+        (entry.key == key).then_some(entry.violations)
+    }
+
+    pub(crate) fn write(
+        &self,
+        file: &str,
+        key: &str,
+        violations: Vec<Violation>,
+    ) -> anyhow::Result<()> {
+        let entry = ViolationCacheEntry {
+            key: key.to_owned(),
+            violations,
+        };
+        let cache_data = serde_json::to_string(&entry)
+            .context("Failed to serialize violations")?;
+        let path = self.path_for(file);
+        fs::write(&path, cache_data)
+            .context(format!("Failed to write cache file {:?}", path))
+    }
+
+    fn path_for(&self, file: &str) -> PathBuf {
+        self.cache_dir
+            .join(format!("{:x}", md5::compute(file.as_bytes())))
+    }
+}
+
+// The sigils the privacy checker sees for a defining file, which are read
+// from the file itself when only some files are checked
+fn defining_file_sigils(
+    configuration: &Configuration,
+    sigils: &HashMap<PathBuf, Vec<Sigil>>,
+    relative_file: &str,
+) -> Vec<Sigil> {
+    let absolute_file = configuration.absolute_root.join(relative_file);
+    if let Some(file_sigils) = sigils.get(&absolute_file) {
+        return file_sigils.clone();
+    }
+    if configuration.input_files_count == 0 {
+        return vec![];
+    }
+    read_sigils(&absolute_file)
+}
+
+fn read_sigils(absolute_file: &Path) -> Vec<Sigil> {
+    fs::read_to_string(absolute_file)
+        .map(|contents| extract_sigils_from_contents(&contents))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::interner::InternedStr;
+    use crate::packs::SourceLocation;
+
+    fn reference(defining_pack_name: &str) -> Reference {
+        Reference {
+            constant_name: InternedStr::from("::Foo"),
+            defining_pack_name: Some(InternedStr::from(defining_pack_name)),
+            relative_defining_file: Some(InternedStr::from("packs/foo/foo.rb")),
+            referencing_pack_name: InternedStr::from("."),
+            relative_referencing_file: InternedStr::from("app/bar.rb"),
+            source_location: SourceLocation::default(),
+        }
+    }
+
+    #[test]
+    fn test_violation_cache() {
+        let cache_directory = std::env::temp_dir()
+            .join(format!("pks-violation-cache-{}", std::process::id()));
+        let configuration = Configuration {
+            cache_enabled: true,
+            cache_directory: cache_directory.clone(),
+            ..Configuration::default()
+        };
+        let cache = ViolationCache::new(&configuration).unwrap().unwrap();
+        let sigils = HashMap::new();
+
+        let key =
+            cache.key(&configuration, &[&reference("packs/foo")], &sigils);
+        assert_eq!(
+            key,
+            cache.key(&configuration, &[&reference("packs/foo")], &sigils)
+        );
+        assert_ne!(
+            key,
+            cache.key(&configuration, &[&reference("packs/baz")], &sigils)
+        );
+
+        assert_eq!(None, cache.get("app/bar.rb", &key));
+        cache.write("app/bar.rb", &key, vec![]).unwrap();
+        assert_eq!(Some(vec![]), cache.get("app/bar.rb", &key));
+        assert_eq!(None, cache.get("app/bar.rb", "another key"));
+
+        fs::remove_dir_all(cache_directory).unwrap();
+    }
+}
@@ -1,54 +1,245 @@
 // Module declarations
+pub(crate) mod declared_dependencies;
 mod dependency;
+mod dependency_budget;
+pub(crate) mod dependency_suggestion;
+mod dependency_triage;
 pub(crate) mod layer;
 
 mod common_test;
+mod constant_ambiguity;
 mod folder_privacy;
+mod gemspec_dependencies;
+pub(crate) mod grouping;
+pub(crate) mod json;
 mod output_helper;
 pub(crate) mod pack_checker;
 mod privacy;
+pub(crate) mod quickfix;
 pub(crate) mod reference;
+mod require_privacy;
+pub(crate) mod rubocop;
+pub(crate) mod sampling;
+pub(crate) mod streaming;
+mod strict_grace_period;
+pub(crate) mod summary;
+mod tag_policy;
+mod unknown_todo_packs;
 mod visibility;
+pub(crate) mod waiver;
 
 // Internal imports
+use crate::packs::caching::violation_cache::ViolationCache;
+use crate::packs::cli::CheckOutputFormat;
+use crate::packs::code_url::{terminal_link, CodeUrl};
+use crate::packs::fingerprint::{fingerprint, has_unrecorded, SourceLines};
+use crate::packs::interner::InternedStr;
 use crate::packs::pack::write_pack_to_disk;
 use crate::packs::pack::Pack;
 use crate::packs::package_todo;
+use crate::packs::process_files_with_cache;
+use crate::packs::progress::Progress;
+use crate::packs::unresolved::UnresolvedReport;
 use crate::packs::Configuration;
 
 use anyhow::bail;
 use anyhow::Context;
+use serde::{Deserialize, Serialize};
 // External imports
 use rayon::prelude::IntoParallelIterator;
-use rayon::prelude::IntoParallelRefIterator;
 use rayon::prelude::ParallelIterator;
+use rayon::slice::ParallelSlice;
 use reference::Reference;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
-use std::{collections::HashSet, path::PathBuf};
-use tracing::debug;
+use std::time::{Duration, Instant};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, field, info_span};
 
 use super::bin_locater;
-use super::reference_extractor::get_all_references_and_sigils;
+use super::reference_extractor::{
+    get_all_references_and_sigils, process_files_for_references,
+    resolve_references,
+};
 use super::Sigil;
+use super::SourceLocation;
+use super::SyntaxError;
+use dependency_suggestion::DependencySuggestion;
+use sampling::SampleEstimate;
 
-#[derive(PartialEq, Clone, Eq, Hash, Debug)]
+// The references checked together by a thread, with every checker
+const REFERENCE_CHUNK_SIZE: usize = 512;
+
+#[derive(Clone, Eq, Debug, Serialize, Deserialize)]
 pub struct ViolationIdentifier {
     pub violation_type: String,
     pub strict: bool,
+    // Names and files are interned (see interner.rs), like those of the
+    // references the violations are found in
+    pub file: InternedStr,
+    pub constant_name: InternedStr,
+    pub referencing_pack_name: InternedStr,
+    pub defining_pack_name: InternedStr,
+    // Relative to the project root. Unknown for violations recorded in
+    // package_todo.yml files before the v2 format, so it's left out of
+    // equality to keep those matching the violations found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defining_file: Option<InternedStr>,
+}
+
+impl PartialEq for ViolationIdentifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.violation_type == other.violation_type
+            && self.strict == other.strict
+            && self.file == other.file
+            && self.constant_name == other.constant_name
+            && self.referencing_pack_name == other.referencing_pack_name
+            && self.defining_pack_name == other.defining_pack_name
+    }
+}
+
+impl std::hash::Hash for ViolationIdentifier {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.violation_type.hash(state);
+        self.strict.hash(state);
+        self.file.hash(state);
+        self.constant_name.hash(state);
+        self.referencing_pack_name.hash(state);
+        self.defining_pack_name.hash(state);
+    }
+}
+#[derive(PartialEq, Clone, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct Violation {
+    message: String,
+    pub identifier: ViolationIdentifier,
+    pub source_location: SourceLocation,
+    // Only captured when `snippet_lines` is set (see `with_snippets`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
+}
+
+// The lines of code around a violating reference, so that JSON output and
+// reports can be reviewed without opening the file
+#[derive(
+    PartialEq, Clone, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize,
+)]
+pub struct Snippet {
+    // 1-based
+    pub first_line: usize,
+    pub lines: Vec<String>,
+}
+
+impl Violation {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    // The violation message without its leading location line, on a single line
+    pub fn description(&self) -> String {
+        let without_location = match self.message.split_once('\n') {
+            Some((_location, rest)) => rest,
+            None => &self.message,
+        };
+        without_location.trim().replace('\n', " ")
+    }
+
+    // The message with its leading location line linked to the code. Links
+    // are only added when printing to a terminal, as they point at the commit
+    // that was checked, whereas messages are cached and written to files.
+    pub(crate) fn linked_message(&self, code_url: &CodeUrl) -> String {
+        match self.message.split_once('\n') {
+            Some((location, rest)) => {
+                let url = code_url.for_location(
+                    &self.identifier.file,
+                    self.source_location.line,
+                );
+                format!("{}\n{}", terminal_link(&url, location), rest)
+            }
+            None => self.message.clone(),
+        }
+    }
+}
+
+// A violation that cannot be recorded, since strict mode is enabled for its
+// type in the enforcing pack
+#[derive(PartialEq, Clone, Eq, Debug)]
+pub struct StrictModeViolation {
+    pub identifier: ViolationIdentifier,
+    message: String,
+}
+
+// A recorded violation that is now referenced more times than the v2
+// package_todo.yml format recorded
+#[derive(PartialEq, Clone, Eq, Debug, PartialOrd, Ord)]
+pub struct MultipliedViolation {
     pub file: String,
     pub constant_name: String,
     pub referencing_pack_name: String,
     pub defining_pack_name: String,
+    pub recorded_count: usize,
+    pub found_count: usize,
 }
-#[derive(PartialEq, Clone, Eq, Hash, Debug)]
-pub struct Violation {
-    message: String,
-    pub identifier: ViolationIdentifier,
+
+impl MultipliedViolation {
+    fn message(&self) -> String {
+        if self.found_count > self.recorded_count {
+            format!(
+                "{} references `{}` {} time(s) in {}, but its package_todo.yml records {}. Please remove the new reference(s) or run `{} update`",
+                self.referencing_pack_name,
+                self.constant_name,
+                self.found_count,
+                self.file,
+                self.recorded_count,
+                bin_locater::packs_bin_name(),
+            )
+        } else {
+            format!(
+                "{} references `{}` in {} from code that its package_todo.yml does not record. Please remove the new reference(s) or run `{} update`",
+                self.referencing_pack_name,
+                self.constant_name,
+                self.file,
+                bin_locater::packs_bin_name(),
+            )
+        }
+    }
+}
+
+// A file with syntax errors. The references the parser recovered from it are
+// still checked, but others may be missed
+#[derive(Debug, PartialEq)]
+pub(crate) struct UnparseableFile {
+    // Relative to the project root
+    pub file: String,
+    pub errors: Vec<SyntaxError>,
+}
+
+impl UnparseableFile {
+    pub(crate) fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.errors.iter().map(|error| {
+            format!(
+                "{}:{}:{}: {}",
+                self.file, error.line, error.column, error.message
+            )
+        })
+    }
 }
 
+// The violation types of the checkers that run on references, which
+// `check --checker` selects from
+pub(crate) const CHECKER_NAMES: [&str; 6] = [
+    "dependency",
+    "privacy",
+    "visibility",
+    "layer",
+    "folder_privacy",
+    "tag_policy",
+];
+
 pub(crate) trait CheckerInterface {
     fn check(
         &self,
@@ -68,17 +259,183 @@ pub(crate) trait ValidatorInterface {
 pub struct CheckAllResult {
     reportable_violations: HashSet<Violation>,
     stale_violations: Vec<ViolationIdentifier>,
-    strict_mode_violations: Vec<ViolationIdentifier>,
+    strict_mode_violations: Vec<StrictModeViolation>,
+    multiplied_violations: Vec<MultipliedViolation>,
+    dependency_suggestions: Vec<DependencySuggestion>,
+    unparseable_files: Vec<UnparseableFile>,
+    files_analyzed: usize,
+    recorded_violations: usize,
+    elapsed: Duration,
+    // Empty unless `report_unresolved_constants` is set
+    unresolved: UnresolvedReport,
+    // Files skipped since the check timed out, relative to the project root
+    // and sorted
+    unanalyzed_files: Vec<String>,
+    // Set when only a sample of the files was checked
+    sample: Option<SampleEstimate>,
+}
+
+// The error `check` returns when it timed out, so that it exits with a
+// distinct code (see `cli::exit_code`)
+#[derive(Debug)]
+pub struct TimedOut {
+    pub unanalyzed_files: usize,
+}
+
+impl Display for TimedOut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Timed out before analyzing {} file(s)!",
+            self.unanalyzed_files
+        )
+    }
 }
 
+impl std::error::Error for TimedOut {}
+
 impl CheckAllResult {
     pub fn has_violations(&self) -> bool {
         !self.reportable_violations.is_empty()
             || !self.stale_violations.is_empty()
             || !self.strict_mode_violations.is_empty()
+            || !self.multiplied_violations.is_empty()
+    }
+
+    pub fn has_parse_errors(&self) -> bool {
+        !self.unparseable_files.is_empty()
+    }
+
+    pub fn timed_out(&self) -> bool {
+        !self.unanalyzed_files.is_empty()
+    }
+
+    pub(crate) fn unanalyzed_files(&self) -> &[String] {
+        &self.unanalyzed_files
+    }
+
+    pub(crate) fn summary(&self) -> summary::CheckSummary {
+        summary::CheckSummary::new(self)
+    }
+
+    pub(crate) fn reportable_violations(
+        &self,
+    ) -> impl Iterator<Item = &Violation> {
+        self.reportable_violations.iter()
+    }
+
+    pub(crate) fn reportable_violation_identifiers(
+        &self,
+    ) -> impl Iterator<Item = &ViolationIdentifier> {
+        self.reportable_violations.iter().map(|v| &v.identifier)
+    }
+
+    fn write_violations(
+        &self,
+        f: &mut Formatter<'_>,
+        code_url: Option<&CodeUrl>,
+    ) -> fmt::Result {
+        self.write_reportable_violations(f, code_url)?;
+        self.write_dependency_suggestions(f)?;
+        self.write_stale_and_strict_violations(f)
+    }
+
+    fn write_dependency_suggestions(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        if self.dependency_suggestions.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            f,
+            "To resolve the dependency violation(s), update the following package.yml file(s) or run `{} check --fix-dependencies`:",
+            bin_locater::packs_bin_name(),
+        )?;
+        for suggestion in &self.dependency_suggestions {
+            writeln!(f, "{}", suggestion)?;
+        }
+        Ok(())
+    }
+
+    // Adds the suggested dependencies to package.yml files, unless doing so
+    // would create a dependency cycle, and drops the violations they resolve
+    pub(crate) fn fix_dependencies(
+        &mut self,
+        configuration: &Configuration,
+    ) -> anyhow::Result<()> {
+        let suggestions = std::mem::take(&mut self.dependency_suggestions);
+        dependency_suggestion::apply_suggestions(configuration, &suggestions)?;
+
+        for suggestion in &suggestions {
+            println!(
+                "Added {} as dependencies of `{}`",
+                suggestion
+                    .missing_dependencies
+                    .iter()
+                    .map(|dependency| format!("`{}`", dependency))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                suggestion.pack_name,
+            );
+        }
+        let resolved = |identifier: &ViolationIdentifier| {
+            suggestions.iter().any(|s| s.resolves(identifier))
+        };
+        self.reportable_violations
+            .retain(|v| !resolved(&v.identifier));
+        self.strict_mode_violations
+            .retain(|v| !resolved(&v.identifier));
+        Ok(())
+    }
+
+    fn write_parse_errors(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.unparseable_files.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            f,
+            "Warning: {} file(s) could not be parsed, so references in them may not have been checked:",
+            self.unparseable_files.len()
+        )?;
+        for unparseable_file in &self.unparseable_files {
+            for line in unparseable_file.lines() {
+                writeln!(f, "{}", line)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_unanalyzed_files(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.unanalyzed_files.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            f,
+            "Warning: timed out before analyzing {} file(s), so violations in them were not reported:",
+            self.unanalyzed_files.len()
+        )?;
+        for file in &self.unanalyzed_files {
+            writeln!(f, "{}", file)?;
+        }
+        Ok(())
+    }
+
+    fn write_unresolved(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.unresolved.is_empty() {
+            return Ok(());
+        }
+        write!(f, "Warning: {}", self.unresolved)
     }
 
-    fn write_violations(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    fn write_reportable_violations(
+        &self,
+        f: &mut Formatter<'_>,
+        code_url: Option<&CodeUrl>,
+    ) -> fmt::Result {
         if !self.reportable_violations.is_empty() {
             let mut sorted_violations: Vec<&Violation> =
                 self.reportable_violations.iter().collect();
@@ -87,10 +444,21 @@ impl CheckAllResult {
             writeln!(f, "{} violation(s) detected:", sorted_violations.len())?;
 
             for violation in sorted_violations {
-                writeln!(f, "{}\n", violation.message)?;
+                match code_url {
+                    Some(code_url) => {
+                        writeln!(f, "{}\n", violation.linked_message(code_url))?
+                    }
+                    None => writeln!(f, "{}\n", violation.message)?,
+                }
             }
         }
+        Ok(())
+    }
 
+    fn write_stale_and_strict_violations(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
         if !self.stale_violations.is_empty() {
             writeln!(
                 f,
@@ -101,23 +469,64 @@ impl CheckAllResult {
 
         if !self.strict_mode_violations.is_empty() {
             for v in self.strict_mode_violations.iter() {
-                let error_message = build_strict_violation_message(v);
-                writeln!(f, "{}", error_message)?;
+                writeln!(f, "{}", v.message)?;
             }
         }
+
+        for v in self.multiplied_violations.iter() {
+            writeln!(f, "{}", v.message())?;
+        }
         Ok(())
     }
 }
 
-impl Display for CheckAllResult {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+impl CheckAllResult {
+    // The human output, with violation locations linked to `code_url`
+    fn write_human(
+        &self,
+        f: &mut Formatter<'_>,
+        code_url: Option<&CodeUrl>,
+    ) -> fmt::Result {
         if self.has_violations() {
-            self.write_violations(f)
-        } else {
-            write!(f, "No violations detected!")
+            self.write_violations(f, code_url)?;
         }
+        write_footer(f, self)
+    }
+}
+
+// What every human output of `check` ends with, after the violations
+pub(crate) fn write_footer(
+    f: &mut impl fmt::Write,
+    result: &CheckAllResult,
+) -> fmt::Result {
+    result.write_parse_errors(f)?;
+    result.write_unresolved(f)?;
+    result.write_unanalyzed_files(f)?;
+    if !result.has_violations() {
+        writeln!(f, "No violations detected!\n")?;
+    }
+    write!(f, "{}", result.summary())
+}
+
+impl Display for CheckAllResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_human(f, None)
+    }
+}
+
+// The human output of `check` on a terminal, with the locations of violations
+// linked to their code (see code_url.rs)
+pub(crate) struct LinkedCheckAllResult<'a> {
+    pub result: &'a CheckAllResult,
+    pub code_url: &'a CodeUrl,
+}
+
+impl Display for LinkedCheckAllResult<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.result.write_human(f, Some(self.code_url))
     }
 }
+
 struct CheckAllBuilder<'a> {
     configuration: &'a Configuration,
     found_violations: &'a FoundViolations,
@@ -126,6 +535,7 @@ struct CheckAllBuilder<'a> {
 struct FoundViolations {
     absolute_paths: HashSet<PathBuf>,
     violations: HashSet<Violation>,
+    parse_errors: HashMap<PathBuf, Vec<SyntaxError>>,
 }
 
 impl<'a> CheckAllBuilder<'a> {
@@ -141,26 +551,68 @@ impl<'a> CheckAllBuilder<'a> {
 
     pub fn build(mut self) -> anyhow::Result<CheckAllResult> {
         let recorded_violations = &self.configuration.pack_set.all_violations;
+        let reportable_violations =
+            self.build_reportable_violations(recorded_violations);
+        let dependency_suggestions = dependency_suggestion::build_suggestions(
+            self.configuration,
+            reportable_violations.iter().map(|v| &v.identifier),
+        )?;
+
+        let triage = dependency_triage::DependencyTriage::new(
+            self.configuration,
+            self.found_violations
+                .violations
+                .iter()
+                .map(|v| &v.identifier)
+                .chain(recorded_violations.iter()),
+        );
 
         Ok(CheckAllResult {
-            reportable_violations: self
-                .build_reportable_violations(recorded_violations)
+            reportable_violations: reportable_violations
                 .into_iter()
-                .cloned()
+                .map(|v| triage.annotate(v))
                 .collect(),
             stale_violations: self
                 .build_stale_violations(recorded_violations)?
                 .into_iter()
                 .cloned()
                 .collect(),
-            strict_mode_violations: self
-                .build_strict_mode_violations()
-                .into_iter()
-                .cloned()
-                .collect(),
+            strict_mode_violations: self.build_strict_mode_violations(),
+            multiplied_violations: self
+                .build_multiplied_violations(recorded_violations),
+            dependency_suggestions,
+            unparseable_files: self.build_unparseable_files(),
+            files_analyzed: self.found_violations.absolute_paths.len(),
+            recorded_violations: recorded_violations.len(),
+            // Set by check_all, which times the whole run
+            elapsed: Duration::ZERO,
+            // Set by check_all, which has the references
+            unresolved: UnresolvedReport::default(),
+            // Set by check_all, which knows which files were processed
+            unanalyzed_files: Vec::new(),
+            // Set by check_all, which knows which files were sampled
+            sample: None,
         })
     }
 
+    fn build_unparseable_files(&self) -> Vec<UnparseableFile> {
+        let mut unparseable_files: Vec<UnparseableFile> = self
+            .found_violations
+            .parse_errors
+            .iter()
+            .map(|(absolute_path, errors)| UnparseableFile {
+                file: absolute_path
+                    .strip_prefix(&self.configuration.absolute_root)
+                    .unwrap_or(absolute_path)
+                    .to_string_lossy()
+                    .to_string(),
+                errors: errors.clone(),
+            })
+            .collect();
+        unparseable_files.sort_by(|a, b| a.file.cmp(&b.file));
+        unparseable_files
+    }
+
     fn build_reportable_violations(
         &mut self,
         recorded_violations: &HashSet<ViolationIdentifier>,
@@ -183,12 +635,6 @@ impl<'a> CheckAllBuilder<'a> {
         &mut self,
         recorded_violations: &'a HashSet<ViolationIdentifier>,
     ) -> anyhow::Result<Vec<&'a ViolationIdentifier>> {
-        let found_violation_identifiers: HashSet<&ViolationIdentifier> = self
-            .found_violations
-            .violations
-            .par_iter()
-            .map(|v| &v.identifier)
-            .collect();
         let relative_files = self
             .found_violations
             .absolute_paths
@@ -214,39 +660,193 @@ impl<'a> CheckAllBuilder<'a> {
             })
             .collect::<anyhow::Result<HashSet<&str>>>()?;
 
-        let stale_violations = recorded_violations
-            .par_iter()
-            .filter(|v_identifier| {
-                Self::is_stale_violation(
-                    &relative_files,
-                    &found_violation_identifiers,
-                    v_identifier,
-                )
+        // Found violations by file, and recorded violations by referencing
+        // pack and then by file, so that each file is looked at once rather
+        // than once per recorded violation
+        let mut found_by_file: HashMap<&str, HashSet<&ViolationIdentifier>> =
+            HashMap::new();
+        for violation in &self.found_violations.violations {
+            found_by_file
+                .entry(violation.identifier.file.as_str())
+                .or_default()
+                .insert(&violation.identifier);
+        }
+        let mut recorded_by_pack: HashMap<
+            &str,
+            HashMap<&str, Vec<&'a ViolationIdentifier>>,
+        > = HashMap::new();
+        // Recorded violations of checkers that didn't run are unknown rather
+        // than stale
+        for identifier in recorded_violations.iter().filter(|identifier| {
+            self.configuration.runs_checker(&identifier.violation_type)
+        }) {
+            recorded_by_pack
+                .entry(identifier.referencing_pack_name.as_str())
+                .or_default()
+                .entry(identifier.file.as_str())
+                .or_default()
+                .push(identifier);
+        }
+
+        // When only some files are checked (e.g. from a pre-commit hook, a
+        // sample, or until a timeout), todo violations in the other files are
+        // unknown rather than stale
+        let checked_subset = self.configuration.input_files_count > 0
+            || self.configuration.sample.is_some()
+            || self.configuration.deadline.is_some();
+        let configuration = self.configuration;
+        let stale_violations = recorded_by_pack
+            .into_par_iter()
+            .flat_map_iter(|(_, recorded_by_file)| {
+                recorded_by_file.into_iter().flat_map(|(file, recorded)| {
+                    Self::stale_violations_in_file(
+                        file,
+                        recorded,
+                        &relative_files,
+                        &found_by_file,
+                        checked_subset,
+                        configuration,
+                    )
+                })
             })
             .collect::<Vec<&ViolationIdentifier>>();
         Ok(stale_violations)
     }
 
-    fn is_stale_violation(
+    fn stale_violations_in_file(
+        file: &str,
+        recorded: Vec<&'a ViolationIdentifier>,
         relative_files: &HashSet<&str>,
-        found_violation_identifiers: &HashSet<&ViolationIdentifier>,
-        todo_violation_identifier: &ViolationIdentifier,
-    ) -> bool {
-        let violation_path_exists =
-            relative_files.contains(todo_violation_identifier.file.as_str());
-        if violation_path_exists {
-            !found_violation_identifiers.contains(todo_violation_identifier)
+        found_by_file: &HashMap<&str, HashSet<&ViolationIdentifier>>,
+        checked_subset: bool,
+        configuration: &Configuration,
+    ) -> Vec<&'a ViolationIdentifier> {
+        if relative_files.contains(file) {
+            match found_by_file.get(file) {
+                Some(found) => recorded
+                    .into_iter()
+                    .filter(|identifier| !found.contains(identifier))
+                    .collect(),
+                None => recorded,
+            }
+        } else if checked_subset {
+            // Only stale if the file has since been deleted or ignored
+            let removed = !configuration.absolute_root.join(file).exists()
+                || configuration.pksignore.is_ignored_file(Path::new(file));
+            if removed {
+                recorded
+            } else {
+                vec![]
+            }
         } else {
-            true // The todo violation references a file that no longer exists
+            recorded // The todo violations reference a file that no longer exists
+        }
+    }
+
+    // Recorded violations are matched by identifier, so a recorded violation
+    // that gains new references is only caught via the occurrences in v2 todo
+    // files. References are compared by fingerprint, so moving or replacing a
+    // reference is caught even when the count stays the same.
+    fn build_multiplied_violations(
+        &self,
+        recorded_violations: &HashSet<ViolationIdentifier>,
+    ) -> Vec<MultipliedViolation> {
+        if self.configuration.ignore_recorded_violations {
+            return Vec::new();
+        }
+
+        let mut found_locations: HashMap<
+            (&str, &str, &str, &str),
+            HashSet<&SourceLocation>,
+        > = HashMap::new();
+        for violation in self
+            .found_violations
+            .violations
+            .iter()
+            .filter(|v| recorded_violations.contains(&v.identifier))
+        {
+            let identifier = &violation.identifier;
+            found_locations
+                .entry((
+                    identifier.referencing_pack_name.as_str(),
+                    identifier.defining_pack_name.as_str(),
+                    identifier.constant_name.as_str(),
+                    identifier.file.as_str(),
+                ))
+                .or_default()
+                .insert(&violation.source_location);
+        }
+
+        let mut source_lines =
+            SourceLines::new(&self.configuration.absolute_root);
+        let mut multiplied_violations = Vec::new();
+        for (
+            (referencing_pack_name, defining_pack_name, constant_name, file),
+            locations,
+        ) in found_locations
+        {
+            let Some(recorded) = self
+                .configuration
+                .pack_set
+                .for_pack(referencing_pack_name)
+                .ok()
+                .and_then(|pack| {
+                    pack.recorded_occurrences(
+                        defining_pack_name,
+                        constant_name,
+                        file,
+                    )
+                })
+            else {
+                continue;
+            };
+            // Fingerprints are computed from the file on disk, so contents
+            // passed via stdin can only be compared by count
+            let from_stdin = self.configuration.stdin_file_path.as_ref()
+                == Some(&self.configuration.absolute_root.join(file));
+            let multiplied = if recorded.fingerprints.is_empty() || from_stdin {
+                locations.len() > recorded.count
+            } else {
+                let found_fingerprints: Vec<String> = locations
+                    .iter()
+                    .map(|location| {
+                        fingerprint(
+                            constant_name,
+                            referencing_pack_name,
+                            defining_pack_name,
+                            source_lines.line(file, location.line),
+                        )
+                    })
+                    .collect();
+                has_unrecorded(&recorded.fingerprints, &found_fingerprints)
+            };
+            if multiplied {
+                multiplied_violations.push(MultipliedViolation {
+                    file: file.to_owned(),
+                    constant_name: constant_name.to_owned(),
+                    referencing_pack_name: referencing_pack_name.to_owned(),
+                    defining_pack_name: defining_pack_name.to_owned(),
+                    recorded_count: recorded.count,
+                    found_count: locations.len(),
+                });
+            }
         }
+        multiplied_violations.sort();
+        multiplied_violations
     }
 
-    fn build_strict_mode_violations(&self) -> Vec<&'a ViolationIdentifier> {
+    fn build_strict_mode_violations(&self) -> Vec<StrictModeViolation> {
         self.found_violations
             .violations
             .iter()
             .filter(|v| v.identifier.strict)
-            .map(|v| &v.identifier)
+            .map(|v| StrictModeViolation {
+                identifier: v.identifier.clone(),
+                message: build_strict_violation_message(
+                    &v.identifier,
+                    self.configuration,
+                ),
+            })
             .collect()
     }
 }
@@ -255,28 +855,119 @@ pub(crate) fn check_all(
     configuration: &Configuration,
     files: Vec<String>,
 ) -> anyhow::Result<CheckAllResult> {
+    check_streaming(configuration, files, |_| {})
+}
+
+// Like `check_all`, but also calls `on_violation` with each reportable
+// violation as soon as the file it's in is checked, possibly from several
+// threads at once, e.g. to print violations while a long run goes on. The
+// violations passed to it aren't annotated with dependency triage hints, as
+// those depend on all the violations found.
+pub(crate) fn check_streaming(
+    configuration: &Configuration,
+    files: Vec<String>,
+    on_violation: impl Fn(&Violation) + Sync,
+) -> anyhow::Result<CheckAllResult> {
+    let started = Instant::now();
     let checkers = get_checkers(configuration);
 
     debug!("Intersecting input files with configuration included files");
     let absolute_paths: HashSet<PathBuf> =
         configuration.intersect_files(files.clone());
+    let files_total = absolute_paths.len();
+    let absolute_paths = match &configuration.sample {
+        Some(sample) => {
+            sample.sample_files(&configuration.absolute_root, absolute_paths)
+        }
+        None => absolute_paths,
+    };
 
-    let violations: HashSet<Violation> =
-        get_all_violations(configuration, &absolute_paths, &checkers)?;
+    let recorded_violations = &configuration.pack_set.all_violations;
+    let on_file_violations = |violations: &[Violation]| {
+        violations
+            .iter()
+            .filter(|v| {
+                configuration.ignore_recorded_violations
+                    || !recorded_violations.contains(&v.identifier)
+            })
+            .for_each(&on_violation);
+    };
+    let processed_files =
+        process_files_for_references(configuration, &absolute_paths)?;
+    // Files skipped once the deadline passed aren't checked, nor are their
+    // recorded violations stale
+    let (absolute_paths, unanalyzed_paths): (
+        HashSet<PathBuf>,
+        HashSet<PathBuf>,
+    ) = if configuration.deadline.is_some() {
+        let processed_paths: HashSet<&PathBuf> = processed_files
+            .iter()
+            .map(|processed_file| &processed_file.absolute_path)
+            .collect();
+        absolute_paths
+            .into_iter()
+            .partition(|absolute_path| processed_paths.contains(absolute_path))
+    } else {
+        (absolute_paths, HashSet::new())
+    };
+    let (references, sigils, parse_errors) =
+        resolve_references(configuration, &absolute_paths, processed_files)?;
+    let violations = check_references(
+        configuration,
+        &references,
+        &sigils,
+        &checkers,
+        &on_file_violations,
+    )?;
     let found_violations = FoundViolations {
         absolute_paths,
         violations,
+        parse_errors,
     };
-    CheckAllBuilder::new(configuration, &found_violations).build()
+    let mut result =
+        CheckAllBuilder::new(configuration, &found_violations).build()?;
+    if configuration.report_unresolved_constants {
+        result.unresolved = UnresolvedReport::new(&references);
+    }
+    let mut unanalyzed_files: Vec<String> = unanalyzed_paths
+        .iter()
+        .map(|absolute_path| {
+            absolute_path
+                .strip_prefix(&configuration.absolute_root)
+                .unwrap_or(absolute_path)
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+    unanalyzed_files.sort();
+    result.unanalyzed_files = unanalyzed_files;
+    result.sample = configuration.sample.as_ref().map(|sample| {
+        SampleEstimate::new(
+            sample,
+            result.files_analyzed,
+            files_total,
+            result.reportable_violations.len(),
+        )
+    });
+    result.elapsed = started.elapsed();
+    Ok(result)
 }
 
 fn validate(configuration: &Configuration) -> Vec<String> {
     debug!("Running validators against packages");
     let validators: Vec<Box<dyn ValidatorInterface + Send + Sync>> = vec![
         Box::new(dependency::Checker {}),
+        Box::new(declared_dependencies::Validator {}),
         Box::new(layer::Checker {
             layers: configuration.layers.clone(),
         }),
+        Box::new(unknown_todo_packs::Validator {}),
+        Box::new(dependency_budget::Validator {}),
+        Box::new(constant_ambiguity::Validator {}),
+        Box::new(gemspec_dependencies::Validator {}),
+        Box::new(waiver::Validator {}),
+        Box::new(tag_policy::Checker {}),
+        Box::new(require_privacy::Validator {}),
     ];
 
     let mut validation_errors: Vec<String> = validators
@@ -290,9 +981,37 @@ fn validate(configuration: &Configuration) -> Vec<String> {
     validation_errors
 }
 
+// Layer violations also name the layers of both packs, since the layers
+// rather than the packs themselves determine what may be referenced
 pub(crate) fn build_strict_violation_message(
     violation_identifier: &ViolationIdentifier,
+    configuration: &Configuration,
 ) -> String {
+    if configuration.strict {
+        return format!("{} cannot have {} violations on {} because strict mode is enabled for all violations by the profile",
+        violation_identifier.referencing_pack_name,
+        violation_identifier.violation_type,
+        violation_identifier.defining_pack_name,);
+    }
+    if violation_identifier.violation_type == "layer" {
+        let layer_of = |pack_name: &str| {
+            configuration
+                .pack_set
+                .for_pack(pack_name)
+                .ok()
+                .and_then(|pack| pack.layer().cloned())
+        };
+        if let (Some(referencing_layer), Some(defining_layer)) = (
+            layer_of(&violation_identifier.referencing_pack_name),
+            layer_of(&violation_identifier.defining_pack_name),
+        ) {
+            return format!("{} (whose layer is `{}`) cannot have layer violations on {} (whose layer is `{}`) because strict mode is enabled for layer violations in the enforcing pack's package.yml file",
+            violation_identifier.referencing_pack_name,
+            referencing_layer,
+            violation_identifier.defining_pack_name,
+            defining_layer,);
+        }
+    }
     format!("{} cannot have {} violations on {} because strict mode is enabled for {} violations in the enforcing pack's package.yml file",
     violation_identifier.referencing_pack_name,
     violation_identifier.violation_type,
@@ -317,14 +1036,97 @@ pub(crate) fn validate_all(
     }
 }
 
-pub(crate) fn update(configuration: &Configuration) -> anyhow::Result<()> {
+// All violations in the included files, whether or not they are recorded
+pub(crate) fn find_all_violations(
+    configuration: &Configuration,
+) -> anyhow::Result<HashSet<Violation>> {
     let checkers = get_checkers(configuration);
-
-    let violations = get_all_violations(
+    let (violations, _parse_errors) = get_all_violations(
         configuration,
         &configuration.included_files,
         &checkers,
     )?;
+    Ok(violations)
+}
+
+// Files in the included files with syntax errors
+pub(crate) fn find_unparseable_files(
+    configuration: &Configuration,
+) -> anyhow::Result<Vec<UnparseableFile>> {
+    let processed_files = process_files_with_cache(
+        &configuration.included_files,
+        configuration.get_cache(),
+        configuration,
+    )?;
+    let found_violations = FoundViolations {
+        absolute_paths: HashSet::new(),
+        violations: HashSet::new(),
+        parse_errors: processed_files
+            .into_iter()
+            .filter(|processed_file| !processed_file.parse_errors.is_empty())
+            .map(|processed_file| {
+                (processed_file.absolute_path, processed_file.parse_errors)
+            })
+            .collect(),
+    };
+    Ok(CheckAllBuilder::new(configuration, &found_violations)
+        .build_unparseable_files())
+}
+
+// Packs whose name matches one of `patterns`, which are pack names or globs
+pub(crate) fn packs_matching(
+    configuration: &Configuration,
+    patterns: &[String],
+) -> anyhow::Result<HashSet<String>> {
+    let mut pack_names = HashSet::new();
+    for pattern in patterns {
+        let glob = glob::Pattern::new(pattern)
+            .context(format!("Invalid pack glob `{}`", pattern))?;
+        let matching: Vec<&String> = configuration
+            .pack_set
+            .packs
+            .iter()
+            .map(|pack| &pack.name)
+            .filter(|name| glob.matches(name))
+            .collect();
+        if matching.is_empty() {
+            bail!("No pack matches `{}`", pattern);
+        }
+        pack_names.extend(matching.into_iter().cloned());
+    }
+    Ok(pack_names)
+}
+
+// With `partial` pack names or globs, only the references originating in the
+// matching packs are checked, and only their package_todo.yml files are
+// rewritten. Returns the violations found, strict mode ones included
+pub(crate) fn update(
+    configuration: &Configuration,
+    partial: &[String],
+    minimal_diff: bool,
+) -> anyhow::Result<Vec<Violation>> {
+    let partial_pack_names = if partial.is_empty() {
+        None
+    } else {
+        Some(packs_matching(configuration, partial)?)
+    };
+    let violations = match &partial_pack_names {
+        None => find_all_violations(configuration)?,
+        Some(pack_names) => {
+            let mut absolute_paths = HashSet::new();
+            for absolute_path in &configuration.included_files {
+                let owned_by_partial_pack = configuration
+                    .pack_set
+                    .for_file(absolute_path)?
+                    .is_some_and(|pack| pack_names.contains(&pack.name));
+                if owned_by_partial_pack {
+                    absolute_paths.insert(absolute_path.clone());
+                }
+            }
+            let checkers = get_checkers(configuration);
+            get_all_violations(configuration, &absolute_paths, &checkers)?.0
+        }
+    };
 
     let strict_violations = &violations
         .iter()
@@ -332,8 +1134,10 @@ pub(crate) fn update(configuration: &Configuration) -> anyhow::Result<()> {
         .collect::<Vec<&Violation>>();
     if !strict_violations.is_empty() {
         for violation in strict_violations {
-            let strict_message =
-                build_strict_violation_message(&violation.identifier);
+            let strict_message = build_strict_violation_message(
+                &violation.identifier,
+                configuration,
+            );
             println!("{}", strict_message);
         }
         println!(
@@ -341,10 +1145,72 @@ pub(crate) fn update(configuration: &Configuration) -> anyhow::Result<()> {
             &strict_violations.len()
         );
     }
-    package_todo::write_violations_to_disk(configuration, violations);
-    println!("Successfully updated package_todo.yml files!");
+    let found = violations.iter().cloned().collect();
+    package_todo::write_violations_to_disk(
+        configuration,
+        violations,
+        partial_pack_names.as_ref(),
+        minimal_diff,
+    )?;
+    match &partial_pack_names {
+        None => println!("Successfully updated package_todo.yml files!"),
+        Some(pack_names) => println!(
+            "Successfully updated package_todo.yml files for {} pack(s)!",
+            pack_names.len()
+        ),
+    }
 
-    Ok(())
+    Ok(found)
+}
+
+// Renders the violations found by `update` for an output sink
+pub(crate) fn render_recorded_violations(
+    violations: &[Violation],
+    output_format: CheckOutputFormat,
+) -> anyhow::Result<String> {
+    let rendered = match output_format {
+        CheckOutputFormat::Human => {
+            let recorded =
+                violations.iter().filter(|v| !v.identifier.strict).count();
+            let mut rendered = String::new();
+            for violation in json::sorted(violations.iter()) {
+                rendered.push_str(&format!("{}\n\n", violation.message));
+            }
+            rendered.push_str(&format!(
+                "{} violation(s) recorded in package_todo.yml files, {} strict mode violation(s)\n",
+                recorded,
+                violations.len() - recorded
+            ));
+            rendered
+        }
+        CheckOutputFormat::Quickfix => json::sorted(violations.iter())
+            .into_iter()
+            .map(|violation| {
+                format!(
+                    "{}:{}:{}: {}\n",
+                    violation.identifier.file,
+                    violation.source_location.line,
+                    violation.source_location.column,
+                    violation.description()
+                )
+            })
+            .collect(),
+        CheckOutputFormat::Json => format!(
+            "{}\n",
+            serde_json::to_string_pretty(&json::JsonUpdateResult::new(
+                violations
+            ))
+            .context("Failed to serialize update result")?
+        ),
+        CheckOutputFormat::Rubocop => format!(
+            "{}\n",
+            serde_json::to_string_pretty(
+                &rubocop::RubocopResult::from_violations(violations)
+            )
+            .context("Failed to serialize update result")?
+        ),
+    };
+    Ok(rendered)
 }
 
 pub(crate) fn remove_unnecessary_dependencies(
@@ -361,7 +1227,7 @@ pub(crate) fn add_all_dependencies(
     configuration: &Configuration,
     pack_name: &str,
 ) -> anyhow::Result<()> {
-    let (references, _sigils) = get_all_references_and_sigils(
+    let (references, _sigils, _parse_errors) = get_all_references_and_sigils(
         configuration,
         &configuration.included_files,
     )?;
@@ -374,10 +1240,10 @@ pub(crate) fn add_all_dependencies(
     let mut defining_pack_names: HashSet<String> = HashSet::new();
 
     for reference in references {
-        if reference.referencing_pack_name == pack_name {
+        if *reference.referencing_pack_name == *pack_name {
             if let Some(defining_pack_name) = reference.defining_pack_name {
-                if defining_pack_name != pack_name {
-                    defining_pack_names.insert(defining_pack_name);
+                if *defining_pack_name != *pack_name {
+                    defining_pack_names.insert(defining_pack_name.to_string());
                 }
             }
         }
@@ -425,7 +1291,7 @@ pub(crate) fn check_unnecessary_dependencies(
 fn get_unnecessary_dependencies(
     configuration: &Configuration,
 ) -> anyhow::Result<HashMap<Pack, Vec<String>>> {
-    let (references, _sigils) = get_all_references_and_sigils(
+    let (references, _sigils, _parse_errors) = get_all_references_and_sigils(
         configuration,
         &configuration.included_files,
     )?;
@@ -433,8 +1299,10 @@ fn get_unnecessary_dependencies(
     for reference in references {
         let defining_pack_name = reference.defining_pack_name;
         if let Some(defining_pack_name) = defining_pack_name {
-            let edge_key =
-                (reference.referencing_pack_name, defining_pack_name);
+            let edge_key = (
+                reference.referencing_pack_name.to_string(),
+                defining_pack_name.to_string(),
+            );
 
             edge_counts
                 .entry(edge_key)
@@ -461,41 +1329,208 @@ fn get_unnecessary_dependencies(
     Ok(unnecessary_dependencies)
 }
 
+#[allow(clippy::type_complexity)]
 fn get_all_violations(
     configuration: &Configuration,
     absolute_paths: &HashSet<PathBuf>,
     checkers: &Vec<Box<dyn CheckerInterface + Send + Sync>>,
-) -> anyhow::Result<HashSet<Violation>> {
-    let (references, sigils) =
+) -> anyhow::Result<(HashSet<Violation>, HashMap<PathBuf, Vec<SyntaxError>>)> {
+    let (references, sigils, parse_errors) =
         get_all_references_and_sigils(configuration, absolute_paths)?;
+    let violations = check_references(
+        configuration,
+        &references,
+        &sigils,
+        checkers,
+        &|_| {},
+    )?;
+    Ok((violations, parse_errors))
+}
+
+// The violations of resolved references, found by all checkers
+pub(crate) fn find_violations_in_references(
+    configuration: &Configuration,
+    references: &[Reference],
+    sigils: &HashMap<PathBuf, Vec<Sigil>>,
+) -> anyhow::Result<HashSet<Violation>> {
+    check_references(
+        configuration,
+        references,
+        sigils,
+        &get_checkers(configuration),
+        &|_| {},
+    )
+}
+
+fn check_references(
+    configuration: &Configuration,
+    references: &[Reference],
+    sigils: &HashMap<PathBuf, Vec<Sigil>>,
+    checkers: &Vec<Box<dyn CheckerInterface + Send + Sync>>,
+    on_file_violations: &(dyn Fn(&[Violation]) + Sync),
+) -> anyhow::Result<HashSet<Violation>> {
     debug!("Running checkers on resolved references");
+    let span = info_span!(
+        "check",
+        references = references.len(),
+        checkers = checkers.len(),
+        violations = field::Empty
+    )
+    .entered();
 
-    let violations = checkers
-        .into_par_iter()
-        .try_fold(HashSet::new, |mut acc, c| {
-            for reference in &references {
-                if let Some(violation) =
-                    c.check(reference, configuration, &sigils)?
-                {
-                    acc.insert(violation);
+    let progress = Progress::new(
+        configuration,
+        "checking",
+        references.len() * checkers.len(),
+    );
+    let violation_cache = ViolationCache::new(configuration)?;
+    // Waivers and strict mode grace periods are applied after caching, since
+    // they end
+    let today = waiver::today();
+    let days_since_epoch = waiver::days_since_epoch();
+    let unwaived = |violations: Vec<Violation>| -> Vec<Violation> {
+        violations
+            .into_iter()
+            .filter(|violation| {
+                !waiver::is_waived(configuration, &violation.identifier, &today)
+            })
+            .map(|mut violation| {
+                if strict_grace_period::is_within_grace_period(
+                    configuration,
+                    &violation.identifier,
+                    days_since_epoch,
+                ) {
+                    violation.identifier.strict = false;
                 }
+                violation
+            })
+            .collect()
+    };
+    let mut references_by_file: HashMap<&str, Vec<&Reference>> = HashMap::new();
+    for reference in references {
+        references_by_file
+            .entry(&reference.relative_referencing_file)
+            .or_default()
+            .push(reference);
+    }
+    let violations: anyhow::Result<HashSet<Violation>> = references_by_file
+        .into_par_iter()
+        .try_fold(HashSet::new, |mut acc, (file, file_references)| {
+            let cache_key = violation_cache.as_ref().map(|cache| {
+                cache.key(configuration, &file_references, sigils)
+            });
+            let cached_violations = violation_cache
+                .as_ref()
+                .zip(cache_key.as_ref())
+                .and_then(|(cache, cache_key)| cache.get(file, cache_key));
+            if let Some(cached_violations) = cached_violations {
+                let cached_violations = with_snippets(
+                    configuration,
+                    file,
+                    unwaived(cached_violations),
+                );
+                on_file_violations(&cached_violations);
+                acc.extend(cached_violations);
+                progress.tick_by(file_references.len() * checkers.len());
+                return Ok(acc);
             }
+
+            // Generated files can have tens of thousands of references, so
+            // their chunks are checked in parallel too
+            let chunk_violations: Vec<Vec<Violation>> = file_references
+                .par_chunks(REFERENCE_CHUNK_SIZE)
+                .map(|chunk| {
+                    let violations = check_reference_chunk(
+                        configuration,
+                        chunk,
+                        sigils,
+                        checkers,
+                    );
+                    progress.tick_by(chunk.len() * checkers.len());
+                    violations
+                })
+                .collect::<anyhow::Result<_>>()?;
+            let file_violations: Vec<Violation> =
+                chunk_violations.into_iter().flatten().collect();
+            if let Some((cache, cache_key)) =
+                violation_cache.as_ref().zip(cache_key.as_ref())
+            {
+                cache.write(file, cache_key, file_violations.clone())?;
+            }
+            let file_violations =
+                with_snippets(configuration, file, unwaived(file_violations));
+            on_file_violations(&file_violations);
+            acc.extend(file_violations);
             Ok(acc)
         })
         .try_reduce(HashSet::new, |mut acc, v| {
             acc.extend(v);
             Ok(acc)
         });
+    progress.finish();
 
     debug!("Finished running checkers");
+    if let Ok(violations) = &violations {
+        span.record("violations", violations.len());
+    }
 
     violations
 }
 
+// Captures the lines around each violation of a file, if `snippet_lines` is
+// set. Like waivers, this is applied after caching, so that the cache doesn't
+// depend on the setting.
+fn with_snippets(
+    configuration: &Configuration,
+    file: &str,
+    mut violations: Vec<Violation>,
+) -> Vec<Violation> {
+    let context = configuration.snippet_lines;
+    if context == 0 || violations.is_empty() {
+        return violations;
+    }
+    let mut source_lines = SourceLines::new(&configuration.absolute_root);
+    let lines = source_lines.lines(file);
+    for violation in &mut violations {
+        let line = violation.source_location.line;
+        if line == 0 || line > lines.len() {
+            continue;
+        }
+        let first_line = line.saturating_sub(context).max(1);
+        let last_line = (line + context).min(lines.len());
+        violation.snippet = Some(Snippet {
+            first_line,
+            lines: lines[first_line - 1..last_line].to_vec(),
+        });
+    }
+    violations
+}
+
+// Runs every checker on each reference in turn, so that each reference is
+// visited once however many checkers there are
+fn check_reference_chunk(
+    configuration: &Configuration,
+    references: &[&Reference],
+    sigils: &HashMap<PathBuf, Vec<Sigil>>,
+    checkers: &[Box<dyn CheckerInterface + Send + Sync>],
+) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    for reference in references {
+        for c in checkers {
+            if let Some(violation) =
+                c.check(reference, configuration, sigils)?
+            {
+                violations.push(violation);
+            }
+        }
+    }
+    Ok(violations)
+}
+
 fn get_checkers(
     configuration: &Configuration,
 ) -> Vec<Box<dyn CheckerInterface + Send + Sync>> {
-    vec![
+    let mut checkers: Vec<Box<dyn CheckerInterface + Send + Sync>> = vec![
         Box::new(dependency::Checker {}),
         Box::new(privacy::Checker {}),
         Box::new(visibility::Checker {}),
@@ -503,7 +1538,12 @@ fn get_checkers(
             layers: configuration.layers.clone(),
         }),
         Box::new(folder_privacy::Checker {}),
-    ]
+        Box::new(tag_policy::Checker {}),
+    ];
+    checkers.retain(|checker| {
+        configuration.runs_checker(&checker.violation_type())
+    });
+    checkers
 }
 
 fn remove_reference_to_dependency(
@@ -523,9 +1563,139 @@ fn remove_reference_to_dependency(
 }
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
     use crate::packs::checker::{
-        CheckAllResult, Violation, ViolationIdentifier,
+        CheckAllBuilder, CheckAllResult, FoundViolations, Violation,
+        ViolationIdentifier,
     };
+    use crate::packs::code_url::CodeUrl;
+    use crate::packs::{Configuration, SourceLocation};
+
+    fn identifier(file: &str, constant_name: &str) -> ViolationIdentifier {
+        ViolationIdentifier {
+            violation_type: "dependency".to_string(),
+            strict: false,
+            file: file.into(),
+            constant_name: constant_name.into(),
+            referencing_pack_name: "packs/bar".into(),
+            defining_pack_name: "packs/foo".into(),
+            defining_file: None,
+        }
+    }
+
+    #[test]
+    fn test_build_stale_violations() {
+        let configuration = Configuration {
+            absolute_root: PathBuf::from("/app"),
+            ..Configuration::default()
+        };
+        let found_violations = FoundViolations {
+            absolute_paths: HashSet::from([
+                PathBuf::from("/app/packs/bar/a.rb"),
+                PathBuf::from("/app/packs/bar/b.rb"),
+            ]),
+            violations: HashSet::from([Violation {
+                message: String::new(),
+                identifier: identifier("packs/bar/a.rb", "::Foo"),
+                source_location: SourceLocation { line: 1, column: 1 },
+                snippet: None,
+            }]),
+            parse_errors: HashMap::new(),
+        };
+        let recorded_violations = HashSet::from([
+            identifier("packs/bar/a.rb", "::Foo"),
+            identifier("packs/bar/a.rb", "::Baz"),
+            identifier("packs/bar/b.rb", "::Foo"),
+            identifier("packs/bar/deleted.rb", "::Foo"),
+        ]);
+
+        let mut stale_violations =
+            CheckAllBuilder::new(&configuration, &found_violations)
+                .build_stale_violations(&recorded_violations)
+                .unwrap();
+        stale_violations.sort_by(|a, b| {
+            (&a.file, &a.constant_name).cmp(&(&b.file, &b.constant_name))
+        });
+
+        assert_eq!(
+            vec![
+                &identifier("packs/bar/a.rb", "::Baz"),
+                &identifier("packs/bar/b.rb", "::Foo"),
+                &identifier("packs/bar/deleted.rb", "::Foo"),
+            ],
+            stale_violations
+        );
+    }
+
+    #[test]
+    fn test_build_stale_violations_of_selected_checkers() {
+        let mut configuration = Configuration {
+            absolute_root: PathBuf::from("/app"),
+            ..Configuration::default()
+        };
+        configuration
+            .select_checkers(&[String::from("privacy")])
+            .unwrap();
+        let found_violations = FoundViolations {
+            absolute_paths: HashSet::from([PathBuf::from(
+                "/app/packs/bar/a.rb",
+            )]),
+            violations: HashSet::new(),
+            parse_errors: HashMap::new(),
+        };
+        let privacy_violation = ViolationIdentifier {
+            violation_type: "privacy".to_string(),
+            ..identifier("packs/bar/a.rb", "::Baz")
+        };
+        let recorded_violations = HashSet::from([
+            identifier("packs/bar/a.rb", "::Foo"),
+            privacy_violation.clone(),
+        ]);
+
+        let stale_violations =
+            CheckAllBuilder::new(&configuration, &found_violations)
+                .build_stale_violations(&recorded_violations)
+                .unwrap();
+
+        assert_eq!(vec![&privacy_violation], stale_violations);
+    }
+
+    #[test]
+    fn test_linked_message() {
+        let violation = Violation {
+            message: "foo/bar/file1.rb:10:5\nPrivacy violation".to_string(),
+            identifier: ViolationIdentifier {
+                violation_type: "Privacy".to_string(),
+                strict: false,
+                file: "foo/bar/file1.rb".into(),
+                constant_name: "::Foo::PrivateClass".into(),
+                referencing_pack_name: "bar".into(),
+                defining_pack_name: "foo".into(),
+                defining_file: None,
+            },
+            source_location: SourceLocation {
+                line: 10,
+                column: 5,
+            },
+            snippet: None,
+        };
+        let code_url =
+            CodeUrl::new("https://example.com/{file}#L{line}", Path::new("."))
+                .unwrap();
+
+        assert_eq!(
+            violation.linked_message(&code_url),
+            "\x1b]8;;https://example.com/foo/bar/file1.rb#L10\x1b\\foo/bar/file1.rb:10:5\x1b]8;;\x1b\\\nPrivacy violation"
+        );
+        // The message itself, e.g. as cached, has no link
+        assert_eq!(
+            violation.message(),
+            "foo/bar/file1.rb:10:5\nPrivacy violation"
+        );
+    }
 
     #[test]
     fn test_write_violations() {
@@ -536,26 +1706,41 @@ mod tests {
                     identifier: ViolationIdentifier {
                         violation_type: "Privacy".to_string(),
                         strict: false,
-                        file: "foo/bar/file1.rb".to_string(),
-                        constant_name: "::Foo::PrivateClass".to_string(),
-                        referencing_pack_name: "bar".to_string(),
-                        defining_pack_name: "foo".to_string(),
-                    }
+                        file: "foo/bar/file1.rb".into(),
+                        constant_name: "::Foo::PrivateClass".into(),
+                        referencing_pack_name: "bar".into(),
+                        defining_pack_name: "foo".into(),
+                        defining_file: None,
+                    },
+                    source_location: SourceLocation { line: 10, column: 5 },
+                    snippet: None,
                 },
                 Violation {
                     message: "foo/bar/file2.rb:15:3\nDependency violation: `::Foo::AnotherClass` is not allowed to depend on `::Bar::SomeClass`".to_string(),
                     identifier: ViolationIdentifier {
                         violation_type: "Dependency".to_string(),
                         strict: false,
-                        file: "foo/bar/file2.rb".to_string(),
-                        constant_name: "::Foo::AnotherClass".to_string(),
-                        referencing_pack_name: "foo".to_string(),
-                        defining_pack_name: "bar".to_string(),
-                    }
+                        file: "foo/bar/file2.rb".into(),
+                        constant_name: "::Foo::AnotherClass".into(),
+                        referencing_pack_name: "foo".into(),
+                        defining_pack_name: "bar".into(),
+                        defining_file: None,
+                    },
+                    source_location: SourceLocation { line: 15, column: 3 },
+                    snippet: None,
                 }
             ].iter().cloned().collect(),
             stale_violations: Vec::new(),
             strict_mode_violations: Vec::new(),
+            multiplied_violations: Vec::new(),
+            dependency_suggestions: Vec::new(),
+            unparseable_files: Vec::new(),
+            files_analyzed: 2,
+            recorded_violations: 0,
+            elapsed: Duration::ZERO,
+            unresolved: Default::default(),
+            unanalyzed_files: Vec::new(),
+            sample: None,
         };
 
         let expected_output = "2 violation(s) detected:
@@ -565,7 +1750,12 @@ Privacy violation: `::Foo::PrivateClass` is private to `foo`, but referenced fro
 foo/bar/file2.rb:15:3
 Dependency violation: `::Foo::AnotherClass` is not allowed to depend on `::Bar::SomeClass`
 
-";
+Summary:
+  Files analyzed: 2
+  New violations: 2 (Dependency: 1, Privacy: 1)
+  Recorded violations: 0
+  Stale violations: 0
+  Elapsed time: 0.00s";
 
         let actual = format!("{}", chec_result);
 
@@ -10,6 +10,7 @@ pub mod tests {
         checker::{
             reference::Reference, CheckerInterface, ViolationIdentifier,
         },
+        interner::InternedStr,
         pack::Pack,
         Configuration, PackSet, Sigil, SourceLocation, Violation,
     };
@@ -52,11 +53,14 @@ pub mod tests {
             identifier: ViolationIdentifier {
                 violation_type,
                 strict,
-                file: String::from("packs/foo/app/services/foo.rb"),
-                constant_name,
-                referencing_pack_name: String::from("packs/foo"),
-                defining_pack_name: String::from("packs/bar"),
+                file: "packs/foo/app/services/foo.rb".into(),
+                constant_name: constant_name.into(),
+                referencing_pack_name: "packs/foo".into(),
+                defining_pack_name: "packs/bar".into(),
+                defining_file: None,
             },
+            source_location: SourceLocation { line: 3, column: 1 },
+            snippet: None,
         }
     }
 
@@ -98,19 +102,18 @@ pub mod tests {
         let defing_pack_name = test_checker
             .defining_pack
             .as_ref()
-            .map(|pack| pack.name.clone());
+            .map(|pack| InternedStr::from(pack.name.as_str()));
         let reference = test_checker.reference.take();
         let reference = reference.unwrap_or_else(|| Reference {
-            constant_name: constant_name.clone(),
+            constant_name: InternedStr::from(constant_name.as_str()),
             defining_pack_name: defing_pack_name,
-            referencing_pack_name: test_checker
-                .referencing_pack
-                .name
-                .to_owned(),
-            relative_referencing_file: String::from(
+            referencing_pack_name: InternedStr::from(
+                test_checker.referencing_pack.name.as_str(),
+            ),
+            relative_referencing_file: InternedStr::from(
                 "packs/foo/app/services/foo.rb",
             ),
-            relative_defining_file: Some(String::from(
+            relative_defining_file: Some(InternedStr::from(
                 "packs/bar/app/services/public/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
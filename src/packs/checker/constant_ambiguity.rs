@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+
+use super::ValidatorInterface;
+use crate::packs::constant_resolver::ConstantDefinition;
+use crate::packs::reference_extractor::zeitwerk_constant_resolver_for_all_files;
+use crate::packs::Configuration;
+
+// Finds files that Zeitwerk maps to the same constant, e.g.
+// packs/a/app/models/foo.rb and packs/b/app/services/foo.rb both defining
+// `::Foo`. Rails loads only one of them, and references to the constant are
+// attributed to the pack the resolver picks, so violations on the others go
+// unnoticed. Definitions in different app roots don't shadow one another, and
+// definitions under `ignored_definitions` in packwerk.yml are left out.
+pub struct Validator {}
+
+impl ValidatorInterface for Validator {
+    fn validate(&self, configuration: &Configuration) -> Option<Vec<String>> {
+        let constant_resolver =
+            zeitwerk_constant_resolver_for_all_files(configuration);
+        let mut error_messages: Vec<String> = constant_resolver
+            .fully_qualified_constant_name_to_constant_definition_map()
+            .iter()
+            .filter_map(|(name, definitions)| {
+                let definitions: Vec<&ConstantDefinition> = definitions
+                    .iter()
+                    .filter(|d| !is_ignored(configuration, name, d))
+                    .collect();
+                if is_ambiguous(configuration, &definitions) {
+                    Some(error_message(configuration, name, &definitions))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        error_messages.sort();
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages)
+        }
+    }
+}
+
+fn relative_path<'a>(
+    configuration: &Configuration,
+    path: &'a Path,
+) -> &'a Path {
+    path.strip_prefix(&configuration.absolute_root)
+        .unwrap_or(path)
+}
+
+fn is_ignored(
+    configuration: &Configuration,
+    name: &str,
+    definition: &ConstantDefinition,
+) -> bool {
+    configuration
+        .ignored_definitions
+        .get(name)
+        .is_some_and(|paths| {
+            paths.contains(relative_path(
+                configuration,
+                &definition.absolute_path_of_definition,
+            ))
+        })
+}
+
+// Whether more than one of the definitions is visible from the same app root.
+// Definitions outside of app roots are visible from all of them
+fn is_ambiguous(
+    configuration: &Configuration,
+    definitions: &[&ConstantDefinition],
+) -> bool {
+    let app_roots: Vec<Option<PathBuf>> = definitions
+        .iter()
+        .map(|definition| {
+            configuration
+                .app_root_for_path(&definition.absolute_path_of_definition)
+                .map(|root| root.path.clone())
+        })
+        .collect();
+    app_roots.iter().any(|app_root| {
+        app_roots
+            .iter()
+            .filter(|other| other.is_none() || *other == app_root)
+            .count()
+            > 1
+    })
+}
+
+fn error_message(
+    configuration: &Configuration,
+    name: &str,
+    // Sorted by path, as in the resolver
+    definitions: &[&ConstantDefinition],
+) -> String {
+    let sites: Vec<String> = definitions
+        .iter()
+        .map(|definition| {
+            let pack_name = configuration
+                .pack_set
+                .for_file(&definition.absolute_path_of_definition)
+                .ok()
+                .flatten()
+                .map(|pack| pack.name.as_str())
+                .unwrap_or("no pack");
+            format!(
+                "  - {} ({})",
+                relative_path(
+                    configuration,
+                    &definition.absolute_path_of_definition
+                )
+                .display(),
+                pack_name
+            )
+        })
+        .collect();
+    let resolution = if configuration.experimental_parser {
+        String::from(
+            "The experimental parser attributes references to every defining pack.",
+        )
+    } else {
+        format!(
+            "References resolve to {}.",
+            relative_path(
+                configuration,
+                &definitions[0].absolute_path_of_definition
+            )
+            .display()
+        )
+    };
+    format!(
+        "`{}` is defined by {} files:\n{}\n{} Rename or remove all but one of them, or list the others under `ignored_definitions` in packwerk.yml.",
+        name,
+        definitions.len(),
+        sites.join("\n"),
+        resolution
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    fn configuration(fixture: &str) -> Configuration {
+        crate::packs::configuration::get(
+            PathBuf::from("tests/fixtures")
+                .join(fixture)
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_ambiguous_constants() {
+        let configuration = configuration("app_with_ambiguous_constants");
+
+        assert_eq!(
+            Validator {}.validate(&configuration),
+            Some(vec![String::from(
+                "\
+`::Order` is defined by 2 files:
+  - packs/legacy/app/services/order.rb (packs/legacy)
+  - packs/orders/app/models/order.rb (packs/orders)
+References resolve to packs/legacy/app/services/order.rb. Rename or remove all but one of them, or list the others under `ignored_definitions` in packwerk.yml."
+            )])
+        );
+    }
+
+    #[test]
+    fn test_validate_ignored_definitions() {
+        let mut configuration = configuration("app_with_ambiguous_constants");
+        configuration.ignored_definitions.insert(
+            String::from("::Order"),
+            HashSet::from([PathBuf::from(
+                "packs/legacy/app/services/order.rb",
+            )]),
+        );
+
+        assert_eq!(Validator {}.validate(&configuration), None);
+    }
+
+    #[test]
+    fn test_validate_constants_of_separate_app_roots() {
+        let configuration = configuration("app_with_multiple_roots");
+
+        assert_eq!(Validator {}.validate(&configuration), None);
+    }
+}
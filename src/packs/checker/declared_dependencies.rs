@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+
+use crate::packs::bin_locater;
+use crate::packs::checker::ValidatorInterface;
+use crate::packs::pack::{write_pack_to_disk, Pack};
+use crate::packs::Configuration;
+
+// Finds dependencies listed more than once in a package.yml file. Listing a
+// pack as its own dependency, or a pack that doesn't exist, is reported by
+// the dependency validator, and `validate --fix` removes all three.
+pub struct Validator {}
+
+impl ValidatorInterface for Validator {
+    fn validate(&self, configuration: &Configuration) -> Option<Vec<String>> {
+        let mut packs: Vec<&Pack> = configuration
+            .pack_set
+            .packs
+            .iter()
+            .filter(|pack| !pack.duplicate_dependencies.is_empty())
+            .collect();
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let error_messages: Vec<String> = packs
+            .into_iter()
+            .map(|pack| {
+                format!(
+                    "{} lists these dependencies more than once: {}. Run `{} validate --fix` to remove the duplicates.",
+                    pack.relative_yml().display(),
+                    pack.duplicate_dependencies.join(", "),
+                    bin_locater::packs_bin_name(),
+                )
+            })
+            .collect();
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages)
+        }
+    }
+}
+
+// Rewrites the package.yml files listing duplicate dependencies, the pack
+// itself, or packs that don't exist, without them. Returns the packs
+// rewritten, sorted.
+pub(crate) fn fix(
+    configuration: &Configuration,
+) -> anyhow::Result<Vec<String>> {
+    let mut fixed = vec![];
+    for pack in &configuration.pack_set.packs {
+        let dependencies: HashSet<String> = pack
+            .dependencies
+            .iter()
+            .filter(|dependency| {
+                **dependency != pack.name
+                    && configuration.pack_set.for_pack(dependency).is_ok()
+            })
+            .cloned()
+            .collect();
+        if dependencies == pack.dependencies
+            && pack.duplicate_dependencies.is_empty()
+        {
+            continue;
+        }
+        write_pack_to_disk(&Pack {
+            dependencies,
+            ..pack.clone()
+        })?;
+        fixed.push(pack.name.clone());
+    }
+    fixed.sort();
+    Ok(fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::PackSet;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_validate_duplicate_dependencies() {
+        let foo = Pack {
+            name: String::from("packs/foo"),
+            relative_path: "packs/foo".into(),
+            dependencies: HashSet::from([String::from("packs/bar")]),
+            duplicate_dependencies: vec![String::from("packs/bar")],
+            ..Pack::default()
+        };
+        let bar = Pack {
+            name: String::from("packs/bar"),
+            relative_path: "packs/bar".into(),
+            ..Pack::default()
+        };
+        let configuration = Configuration {
+            pack_set: PackSet::build(
+                HashSet::from([
+                    Pack {
+                        name: String::from("."),
+                        ..Pack::default()
+                    },
+                    foo,
+                    bar,
+                ]),
+                HashMap::new(),
+            )
+            .unwrap(),
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            Some(vec![format!(
+                "packs/foo/package.yml lists these dependencies more than once: packs/bar. Run `{} validate --fix` to remove the duplicates.",
+                bin_locater::packs_bin_name()
+            )]),
+            Validator {}.validate(&configuration)
+        );
+    }
+}
@@ -124,7 +124,7 @@ impl CheckerInterface for Checker {
         let ignored_dependency = pack_checker
             .referencing_pack
             .ignored_dependencies
-            .contains(&defining_pack.name);
+            .contains_key(&defining_pack.name);
 
         if referencing_pack_dependencies.contains(&defining_pack.name)
             || ignored_dependency
@@ -132,8 +132,20 @@ impl CheckerInterface for Checker {
             return Ok(None);
         }
 
-        let relative_defining_file =
-            reference.relative_defining_file.as_ref().context(format!(
+        let is_test_dependency = pack_checker
+            .referencing_pack
+            .test_dependencies
+            .contains(&defining_pack.name);
+        if is_test_dependency
+            && configuration.is_test_file(&reference.relative_referencing_file)
+        {
+            return Ok(None);
+        }
+
+        let relative_defining_file = reference
+            .relative_defining_file
+            .as_deref()
+            .context(format!(
                 "expected a relative defining file for defining pack: {}",
                 defining_pack.name
             ))?;
@@ -156,7 +168,7 @@ impl CheckerInterface for Checker {
         // END: Original packwerk message
 
         let loc = print_reference_location(reference);
-        let message = format!(
+        let mut message = format!(
                 "{}Dependency violation: `{}` belongs to `{}`, but `{}` does not specify a dependency on `{}`.",
                 loc,
                 reference.constant_name,
@@ -164,10 +176,18 @@ impl CheckerInterface for Checker {
                 pack_checker.referencing_pack.relative_yml().to_string_lossy(),
                 defining_pack.name,
             );
+        if is_test_dependency {
+            message.push_str(&format!(
+                " `{}` is only a test dependency, so it may only be referenced from test files.",
+                defining_pack.name
+            ));
+        }
 
         Ok(Some(Violation {
             message,
             identifier: pack_checker.violation_identifier(),
+            source_location: reference.source_location.clone(),
+            snippet: None,
         }))
     }
 
@@ -187,9 +207,13 @@ mod tests {
     };
 
     use super::*;
+    use crate::packs::interner::InternedStr;
     use crate::packs::*;
     use pretty_assertions::assert_eq;
-    use std::{collections::HashSet, path::PathBuf};
+    use std::{
+        collections::{BTreeMap, HashSet},
+        path::PathBuf,
+    };
 
     #[test]
     fn test_reference_and_defining_packs_are_identical() -> anyhow::Result<()> {
@@ -256,8 +280,10 @@ mod tests {
 
     #[test]
     fn test_ignored_dependency() -> anyhow::Result<()> {
-        let mut ignored_dependencies = HashSet::new();
-        ignored_dependencies.insert(String::from("packs/bar"));
+        let ignored_dependencies = BTreeMap::from([(
+            String::from("packs/bar"),
+            Some(String::from("Referenced by every pack")),
+        )]);
 
         let mut test_checker = TestChecker {
             reference: None,
@@ -278,6 +304,60 @@ mod tests {
         test_check(&Checker {}, &mut test_checker)
     }
 
+    #[test]
+    fn test_test_dependency_from_test_file() -> anyhow::Result<()> {
+        let mut test_checker = TestChecker {
+            reference: Some(Reference {
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
+                    "packs/foo/spec/services/foo_spec.rb",
+                ),
+                relative_defining_file: Some(InternedStr::from(
+                    "packs/bar/app/services/bar.rb",
+                )),
+                source_location: SourceLocation { line: 3, column: 1 },
+            }),
+            configuration: None,
+            defining_pack: Some(Pack {
+                name: "packs/bar".to_owned(),
+                ..default_defining_pack()
+            }),
+            referencing_pack: Pack {
+                relative_path: PathBuf::from("packs/foo"),
+                test_dependencies: HashSet::from([String::from("packs/bar")]),
+                enforce_dependencies: Some(CheckerSetting::True),
+                ..default_referencing_pack()
+            },
+            ..Default::default()
+        };
+        test_check(&Checker {}, &mut test_checker)
+    }
+
+    #[test]
+    fn test_test_dependency_from_production_file() -> anyhow::Result<()> {
+        let mut test_checker = TestChecker {
+            reference: None,
+            configuration: None,
+            referenced_constant_name: Some(String::from("::Bar")),
+            defining_pack: Some(Pack {
+                name: "packs/bar".to_owned(),
+                ..default_defining_pack()
+            }),
+            referencing_pack: Pack {
+                relative_path: PathBuf::from("packs/foo"),
+                test_dependencies: HashSet::from([String::from("packs/bar")]),
+                enforce_dependencies: Some(CheckerSetting::True),
+                ..default_referencing_pack()
+            },
+            expected_violation: Some(build_expected_violation(
+                "packs/foo/app/services/foo.rb:3:1\nDependency violation: `::Bar` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`. `packs/bar` is only a test dependency, so it may only be referenced from test files.".to_string(),
+                "dependency".to_string(), false)),
+        };
+        test_check(&Checker {}, &mut test_checker)
+    }
+
     #[test]
     fn test_with_enforcement_globs_ignore() -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
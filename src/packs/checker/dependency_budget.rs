@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use super::ValidatorInterface;
+use crate::packs::Configuration;
+
+// Enforces the `max_dependencies` and `max_dependents` budgets of packs, so
+// that hub packs don't grow unboundedly. Both count the `dependencies`
+// declared in package.yml files.
+pub struct Validator {}
+
+impl ValidatorInterface for Validator {
+    fn validate(&self, configuration: &Configuration) -> Option<Vec<String>> {
+        let mut dependents: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for pack in &configuration.pack_set.packs {
+            for dependency in &pack.dependencies {
+                dependents.entry(dependency).or_default().push(&pack.name);
+            }
+        }
+
+        let mut error_messages: Vec<String> = Vec::new();
+        for pack in &configuration.pack_set.packs {
+            if let Some(max_dependencies) = pack.max_dependencies {
+                if pack.dependencies.len() > max_dependencies {
+                    error_messages.push(format!(
+                        "{} declares {} dependencies, but its max_dependencies budget is {}",
+                        pack.relative_yml().display(),
+                        pack.dependencies.len(),
+                        max_dependencies,
+                    ));
+                }
+            }
+            if let Some(max_dependents) = pack.max_dependents {
+                let mut pack_dependents = dependents
+                    .get(pack.name.as_str())
+                    .cloned()
+                    .unwrap_or_default();
+                if pack_dependents.len() > max_dependents {
+                    pack_dependents.sort();
+                    error_messages.push(format!(
+                        "`{}` is a dependency of {} packs ({}), but the max_dependents budget in {} is {}",
+                        pack.name,
+                        pack_dependents.len(),
+                        pack_dependents.join(", "),
+                        pack.relative_yml().display(),
+                        max_dependents,
+                    ));
+                }
+            }
+        }
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            error_messages.sort();
+            Some(error_messages)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::packs::pack::Pack;
+    use crate::packs::PackSet;
+    use pretty_assertions::assert_eq;
+
+    fn pack(name: &str, dependencies: &[&str]) -> Pack {
+        Pack {
+            name: name.to_owned(),
+            yml: PathBuf::from(name).join("package.yml"),
+            relative_path: PathBuf::from(name),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Pack::default()
+        }
+    }
+
+    fn configuration(packs: Vec<Pack>) -> Configuration {
+        Configuration {
+            pack_set: PackSet::build(
+                packs
+                    .into_iter()
+                    .chain([pack(".", &[])])
+                    .collect::<HashSet<Pack>>(),
+                HashMap::new(),
+            )
+            .unwrap(),
+            ..Configuration::default()
+        }
+    }
+
+    #[test]
+    fn test_within_budget() {
+        let configuration = configuration(vec![
+            Pack {
+                max_dependencies: Some(2),
+                ..pack("packs/foo", &["packs/bar", "packs/baz"])
+            },
+            Pack {
+                max_dependents: Some(1),
+                ..pack("packs/bar", &[])
+            },
+            pack("packs/baz", &[]),
+        ]);
+
+        assert_eq!(None, Validator {}.validate(&configuration));
+    }
+
+    #[test]
+    fn test_over_budget() {
+        let configuration = configuration(vec![
+            Pack {
+                max_dependencies: Some(1),
+                ..pack("packs/foo", &["packs/bar", "packs/baz"])
+            },
+            Pack {
+                max_dependents: Some(1),
+                ..pack("packs/bar", &[])
+            },
+            pack("packs/baz", &["packs/bar"]),
+        ]);
+
+        assert_eq!(
+            Some(vec![
+                String::from("`packs/bar` is a dependency of 2 packs (packs/baz, packs/foo), but the max_dependents budget in packs/bar/package.yml is 1"),
+                String::from("packs/foo/package.yml declares 2 dependencies, but its max_dependencies budget is 1"),
+            ]),
+            Validator {}.validate(&configuration)
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use anyhow::bail;
+use petgraph::algo::tarjan_scc;
+use petgraph::prelude::{DiGraph, NodeIndex};
+
+use crate::packs::pack::{write_pack_to_disk, Pack};
+use crate::packs::Configuration;
+
+use super::ViolationIdentifier;
+
+// The `dependencies:` a referencing pack needs to resolve its dependency violations
+#[derive(Debug, PartialEq, Eq)]
+pub struct DependencySuggestion {
+    pub pack_name: String,
+    // Relative to the project root
+    pub package_yml: String,
+    pub missing_dependencies: BTreeSet<String>,
+    // The pack's existing dependencies along with the missing ones
+    pub dependencies: BTreeSet<String>,
+}
+
+impl DependencySuggestion {
+    pub(crate) fn resolves(&self, identifier: &ViolationIdentifier) -> bool {
+        identifier.violation_type == "dependency"
+            && identifier.referencing_pack_name == self.pack_name
+            && self
+                .missing_dependencies
+                .contains(identifier.defining_pack_name.as_str())
+    }
+}
+
+impl Display for DependencySuggestion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# {}", self.package_yml)?;
+        writeln!(f, "dependencies:")?;
+        for dependency in &self.dependencies {
+            writeln!(f, "- {}", dependency)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn build_suggestions<'a>(
+    configuration: &Configuration,
+    identifiers: impl Iterator<Item = &'a ViolationIdentifier>,
+) -> anyhow::Result<Vec<DependencySuggestion>> {
+    let mut missing_by_pack: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    for identifier in identifiers.filter(|i| i.violation_type == "dependency") {
+        missing_by_pack
+            .entry(&identifier.referencing_pack_name)
+            .or_default()
+            .insert(identifier.defining_pack_name.to_string());
+    }
+
+    missing_by_pack
+        .into_iter()
+        .map(|(pack_name, missing_dependencies)| {
+            let pack = configuration.pack_set.for_pack(pack_name)?;
+            let mut dependencies: BTreeSet<String> =
+                pack.dependencies.iter().cloned().collect();
+            dependencies.extend(missing_dependencies.iter().cloned());
+            Ok(DependencySuggestion {
+                pack_name: pack.name.clone(),
+                package_yml: pack.relative_yml().to_string_lossy().to_string(),
+                missing_dependencies,
+                dependencies,
+            })
+        })
+        .collect()
+}
+
+// The dependency cycles that applying the suggestions would introduce, as
+// comma separated pack names. Existing cycles are not included.
+pub(crate) fn introduced_cycles(
+    configuration: &Configuration,
+    suggestions: &[DependencySuggestion],
+) -> Vec<String> {
+    let mut graph = DiGraph::<&str, ()>::new();
+    let nodes: HashMap<&str, NodeIndex> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| (pack.name.as_str(), graph.add_node(pack.name.as_str())))
+        .collect();
+
+    let mut add_edge = |from: &str, to: &str| {
+        if let (Some(from_node), Some(to_node)) =
+            (nodes.get(from), nodes.get(to))
+        {
+            graph.add_edge(*from_node, *to_node, ());
+        }
+    };
+    for pack in &configuration.pack_set.packs {
+        for dependency in &pack.dependencies {
+            add_edge(&pack.name, dependency);
+        }
+    }
+    let mut added_edges: Vec<(&str, &str)> = Vec::new();
+    for suggestion in suggestions {
+        for dependency in &suggestion.missing_dependencies {
+            add_edge(&suggestion.pack_name, dependency);
+            added_edges
+                .push((suggestion.pack_name.as_str(), dependency.as_str()));
+        }
+    }
+
+    tarjan_scc(&graph)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .filter_map(|component| {
+            let pack_names: BTreeSet<&str> =
+                component.iter().map(|node| graph[*node]).collect();
+            let introduced = added_edges.iter().any(|(from, to)| {
+                pack_names.contains(from) && pack_names.contains(to)
+            });
+            introduced.then(|| {
+                pack_names.into_iter().collect::<Vec<&str>>().join(", ")
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn apply_suggestions(
+    configuration: &Configuration,
+    suggestions: &[DependencySuggestion],
+) -> anyhow::Result<()> {
+    let cycles = introduced_cycles(configuration, suggestions);
+    if !cycles.is_empty() {
+        bail!(
+            "Did not add dependencies, since the following groups of packages would form a cycle:\n\n{}",
+            cycles.join("\n\n")
+        );
+    }
+
+    for suggestion in suggestions {
+        let pack = configuration.pack_set.for_pack(&suggestion.pack_name)?;
+        let updated_pack = Pack {
+            dependencies: suggestion.dependencies.iter().cloned().collect(),
+            ..pack.clone()
+        };
+        write_pack_to_disk(&updated_pack)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn identifier(referencing: &str, defining: &str) -> ViolationIdentifier {
+        ViolationIdentifier {
+            violation_type: "dependency".to_string(),
+            strict: false,
+            file: format!("{}/app/services/file.rb", referencing).into(),
+            constant_name: "::Foo".into(),
+            referencing_pack_name: referencing.into(),
+            defining_pack_name: defining.into(),
+            defining_file: None,
+        }
+    }
+
+    fn configuration() -> Configuration {
+        crate::packs::configuration::get(
+            PathBuf::from("tests/fixtures/app_with_dependency_cycles")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_display() {
+        let suggestion = DependencySuggestion {
+            pack_name: "packs/foo".to_string(),
+            package_yml: "packs/foo/package.yml".to_string(),
+            missing_dependencies: BTreeSet::from(["packs/baz".to_string()]),
+            dependencies: BTreeSet::from([
+                "packs/bar".to_string(),
+                "packs/baz".to_string(),
+            ]),
+        };
+
+        let expected = "\
+# packs/foo/package.yml
+dependencies:
+- packs/bar
+- packs/baz
+";
+        assert_eq!(expected, format!("{}", suggestion));
+    }
+
+    #[test]
+    fn test_build_suggestions_deduplicates() {
+        let configuration = configuration();
+        let identifiers = [
+            identifier("packs/foo", "packs/bar"),
+            identifier("packs/foo", "packs/bar"),
+        ];
+
+        let suggestions =
+            build_suggestions(&configuration, identifiers.iter()).unwrap();
+
+        assert_eq!(1, suggestions.len());
+        assert_eq!(
+            BTreeSet::from(["packs/bar".to_string()]),
+            suggestions[0].missing_dependencies
+        );
+        assert!(suggestions[0].resolves(&identifiers[0]));
+    }
+
+    #[test]
+    fn test_introduced_cycles() {
+        let configuration = configuration();
+
+        // packs/foo and packs/bar already form a cycle
+        let suggestions = build_suggestions(
+            &configuration,
+            [identifier("packs/baz", "packs/foo")].iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            Vec::<String>::new(),
+            introduced_cycles(&configuration, &suggestions)
+        );
+
+        let suggestions = build_suggestions(
+            &configuration,
+            [
+                identifier("packs/baz", "packs/foo"),
+                identifier("packs/foo", "packs/baz"),
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["packs/bar, packs/baz, packs/foo".to_string()],
+            introduced_cycles(&configuration, &suggestions)
+        );
+    }
+}
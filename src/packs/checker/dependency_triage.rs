@@ -0,0 +1,222 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::packs::Configuration;
+
+use super::{Violation, ViolationIdentifier};
+
+// A constant referenced without a dependency from at least this many packs is
+// better off in a pack of its own than added to each of their dependencies
+const SHARED_CONSTANT_PACK_COUNT: usize = 3;
+
+// Classifies dependency violations by the remediation that fits them best,
+// based on the declared dependency graph and on which other packs reference
+// the same constant without depending on its pack
+pub(crate) struct DependencyTriage<'a> {
+    dependencies: HashMap<&'a str, Vec<&'a str>>,
+    referencing_packs_by_constant: HashMap<&'a str, BTreeSet<&'a str>>,
+}
+
+impl<'a> DependencyTriage<'a> {
+    // `identifiers` are the found and recorded violations
+    pub(crate) fn new(
+        configuration: &'a Configuration,
+        identifiers: impl Iterator<Item = &'a ViolationIdentifier>,
+    ) -> Self {
+        let dependencies = configuration
+            .pack_set
+            .packs
+            .iter()
+            .map(|pack| {
+                let mut dependencies: Vec<&str> =
+                    pack.dependencies.iter().map(String::as_str).collect();
+                dependencies.sort();
+                (pack.name.as_str(), dependencies)
+            })
+            .collect();
+
+        let mut referencing_packs_by_constant: HashMap<&str, BTreeSet<&str>> =
+            HashMap::new();
+        for identifier in
+            identifiers.filter(|i| i.violation_type == "dependency")
+        {
+            referencing_packs_by_constant
+                .entry(&identifier.constant_name)
+                .or_default()
+                .insert(&identifier.referencing_pack_name);
+        }
+
+        Self {
+            dependencies,
+            referencing_packs_by_constant,
+        }
+    }
+
+    // The shortest chain of declared dependencies from one pack to another
+    fn dependency_path(&self, from: &'a str, to: &str) -> Option<Vec<&'a str>> {
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut visited: HashSet<&str> = HashSet::from([from]);
+        let mut queue: VecDeque<&str> = VecDeque::from([from]);
+        while let Some(pack) = queue.pop_front() {
+            if pack == to {
+                let mut path = vec![pack];
+                let mut current = pack;
+                while let Some(&prior) = previous.get(current) {
+                    path.push(prior);
+                    current = prior;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &dependency in self.dependencies.get(pack).into_iter().flatten()
+            {
+                if visited.insert(dependency) {
+                    previous.insert(dependency, pack);
+                    queue.push_back(dependency);
+                }
+            }
+        }
+        None
+    }
+
+    pub(crate) fn suggestion(
+        &self,
+        identifier: &ViolationIdentifier,
+    ) -> String {
+        let referencing = identifier.referencing_pack_name.as_str();
+        let defining = identifier.defining_pack_name.as_str();
+
+        let pack_count = self
+            .referencing_packs_by_constant
+            .get(identifier.constant_name.as_str())
+            .map_or(0, BTreeSet::len);
+        if pack_count >= SHARED_CONSTANT_PACK_COUNT {
+            return format!(
+                "Suggestion: `{}` is referenced without a dependency on `{}` from {} packs. Consider extracting it into a shared pack they can all depend on.",
+                identifier.constant_name, defining, pack_count
+            );
+        }
+
+        if let Some(path) = self.dependency_path(defining, referencing) {
+            return format!(
+                "Suggestion: adding a dependency on `{}` would create a cycle, since it already depends on `{}` ({}). Consider inverting the dependency, e.g. by moving this code into `{}` or by having `{}` pass in what is needed.",
+                defining,
+                referencing,
+                path.join(" -> "),
+                defining,
+                defining
+            );
+        }
+
+        format!(
+            "Suggestion: add `{}` to the dependencies of `{}`.",
+            defining, referencing
+        )
+    }
+
+    // Appends the suggestion to the messages of dependency violations
+    pub(crate) fn annotate(&self, violation: &Violation) -> Violation {
+        let mut violation = violation.clone();
+        if violation.identifier.violation_type == "dependency" {
+            violation.message = format!(
+                "{}\n{}",
+                violation.message,
+                self.suggestion(&violation.identifier)
+            );
+        }
+        violation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::pack::Pack;
+    use crate::packs::PackSet;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn identifier(
+        referencing: &str,
+        defining: &str,
+        constant_name: &str,
+    ) -> ViolationIdentifier {
+        ViolationIdentifier {
+            violation_type: "dependency".to_string(),
+            strict: false,
+            file: format!("{}/app/services/file.rb", referencing).into(),
+            constant_name: constant_name.into(),
+            referencing_pack_name: referencing.into(),
+            defining_pack_name: defining.into(),
+            defining_file: None,
+        }
+    }
+
+    fn pack(name: &str, dependencies: &[&str]) -> Pack {
+        Pack {
+            name: name.to_owned(),
+            yml: PathBuf::from(name).join("package.yml"),
+            relative_path: PathBuf::from(name),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Pack::default()
+        }
+    }
+
+    // packs/foo -> packs/baz -> packs/bar
+    fn configuration() -> Configuration {
+        Configuration {
+            pack_set: PackSet::build(
+                HashSet::from([
+                    pack(".", &[]),
+                    pack("packs/foo", &["packs/baz"]),
+                    pack("packs/baz", &["packs/bar"]),
+                    pack("packs/bar", &[]),
+                ]),
+                HashMap::new(),
+            )
+            .unwrap(),
+            ..Configuration::default()
+        }
+    }
+
+    #[test]
+    fn test_suggests_adding_the_dependency() {
+        let configuration = configuration();
+        let violation = identifier("packs/foo", ".", "::Root");
+        let triage =
+            DependencyTriage::new(&configuration, std::iter::once(&violation));
+
+        assert_eq!(
+            "Suggestion: add `.` to the dependencies of `packs/foo`.",
+            triage.suggestion(&violation)
+        );
+    }
+
+    #[test]
+    fn test_suggests_inverting_a_dependency_that_would_create_a_cycle() {
+        let configuration = configuration();
+        let violation = identifier("packs/bar", "packs/foo", "::Foo");
+        let triage =
+            DependencyTriage::new(&configuration, std::iter::once(&violation));
+
+        assert_eq!(
+            "Suggestion: adding a dependency on `packs/foo` would create a cycle, since it already depends on `packs/bar` (packs/foo -> packs/baz -> packs/bar). Consider inverting the dependency, e.g. by moving this code into `packs/foo` or by having `packs/foo` pass in what is needed.",
+            triage.suggestion(&violation)
+        );
+    }
+
+    #[test]
+    fn test_suggests_extracting_a_widely_referenced_constant() {
+        let configuration = configuration();
+        let violations = [
+            identifier("packs/foo", ".", "::Shared"),
+            identifier("packs/bar", ".", "::Shared"),
+            identifier("packs/baz", ".", "::Shared"),
+        ];
+        let triage = DependencyTriage::new(&configuration, violations.iter());
+
+        assert_eq!(
+            "Suggestion: `::Shared` is referenced without a dependency on `.` from 3 packs. Consider extracting it into a shared pack they can all depend on.",
+            triage.suggestion(&violations[0])
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+use regex::Regex;
+
+use super::ValidatorInterface;
+use crate::packs::pack::Pack;
+use crate::packs::Configuration;
+
+// For repos mixing gems and packs, checks that the `dependencies` of packs
+// that are also gems (i.e. whose directory has a gemspec) match the runtime
+// dependencies of their gemspecs. A dependency between two such packs must be
+// declared in both, since bundler and pks otherwise disagree on what the pack
+// may reference. Dependencies on packs that aren't gems, and on gems from
+// outside the repo, are left alone.
+pub struct Validator {}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Gemspec {
+    // Relative to the project root
+    path: PathBuf,
+    name: String,
+    dependencies: BTreeSet<String>,
+}
+
+impl ValidatorInterface for Validator {
+    fn validate(&self, configuration: &Configuration) -> Option<Vec<String>> {
+        let gemspecs: BTreeMap<&str, Gemspec> = configuration
+            .pack_set
+            .packs
+            .iter()
+            .filter_map(|pack| Some((pack.name.as_str(), read_gemspec(pack)?)))
+            .collect();
+
+        let mut error_messages =
+            error_messages(&configuration.pack_set.packs, &gemspecs);
+        error_messages.sort();
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages)
+        }
+    }
+}
+
+fn error_messages(
+    packs: &[Pack],
+    gemspecs: &BTreeMap<&str, Gemspec>,
+) -> Vec<String> {
+    let pack_name_by_gem: BTreeMap<&str, &str> = gemspecs
+        .iter()
+        .map(|(pack_name, gemspec)| (gemspec.name.as_str(), *pack_name))
+        .collect();
+
+    let mut error_messages: Vec<String> = Vec::new();
+    for pack in packs {
+        let Some(gemspec) = gemspecs.get(pack.name.as_str()) else {
+            continue;
+        };
+        for dependency in &pack.dependencies {
+            let Some(dependency_gemspec) = gemspecs.get(dependency.as_str())
+            else {
+                continue;
+            };
+            if !gemspec.dependencies.contains(&dependency_gemspec.name) {
+                error_messages.push(format!(
+                    "{} declares a dependency on `{}`, but {} doesn't depend on its gem `{}`",
+                    pack.relative_yml().display(),
+                    dependency,
+                    gemspec.path.display(),
+                    dependency_gemspec.name,
+                ));
+            }
+        }
+        for gem in &gemspec.dependencies {
+            let Some(dependency) = pack_name_by_gem.get(gem.as_str()) else {
+                continue;
+            };
+            if !pack.dependencies.contains(*dependency) {
+                error_messages.push(format!(
+                    "{} depends on the gem `{}` of `{}`, but {} doesn't declare it as a dependency",
+                    gemspec.path.display(),
+                    gem,
+                    dependency,
+                    pack.relative_yml().display(),
+                ));
+            }
+        }
+    }
+    error_messages
+}
+
+// The first gemspec in the directory of the pack, if any
+fn read_gemspec(pack: &Pack) -> Option<Gemspec> {
+    let directory = pack.yml.parent()?;
+    let mut paths: Vec<PathBuf> = fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "gemspec")
+        })
+        .collect();
+    paths.sort();
+    let path = paths.into_iter().next()?;
+    let contents = fs::read_to_string(&path).ok()?;
+    let file_stem = path.file_stem()?.to_string_lossy();
+    Some(parse_gemspec(
+        pack.relative_path.join(path.file_name()?),
+        &file_stem,
+        &contents,
+    ))
+}
+
+// Gemspecs are Ruby, so this only understands the common
+// `spec.name = "..."` and `spec.add_dependency "..."` forms. The name
+// defaults to that of the file.
+fn parse_gemspec(path: PathBuf, file_stem: &str, contents: &str) -> Gemspec {
+    let name_regex = Regex::new(r#"\.name\s*=\s*['"]([^'"]+)['"]"#).unwrap();
+    let dependency_regex =
+        Regex::new(r#"\.add_(?:runtime_)?dependency\(?\s*['"]([^'"]+)['"]"#)
+            .unwrap();
+
+    let name = name_regex
+        .captures(contents)
+        .map(|captures| captures[1].to_owned())
+        .unwrap_or_else(|| file_stem.to_owned());
+    let dependencies = dependency_regex
+        .captures_iter(contents)
+        .map(|captures| captures[1].to_owned())
+        .collect();
+    Gemspec {
+        path,
+        name,
+        dependencies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pack(name: &str, dependencies: &[&str]) -> Pack {
+        Pack {
+            name: name.to_owned(),
+            yml: PathBuf::from(name).join("package.yml"),
+            relative_path: PathBuf::from(name),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Pack::default()
+        }
+    }
+
+    fn gemspec(pack_name: &str, name: &str, dependencies: &[&str]) -> Gemspec {
+        Gemspec {
+            path: PathBuf::from(pack_name).join(format!("{}.gemspec", name)),
+            name: name.to_owned(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_gemspec() {
+        let contents = r#"
+Gem::Specification.new do |spec|
+  spec.name = "billing_core"
+  spec.add_dependency "money", "~> 6.0"
+  spec.add_runtime_dependency('ledger')
+  spec.add_development_dependency "rspec"
+end
+"#;
+
+        assert_eq!(
+            gemspec("packs/billing", "billing_core", &["ledger", "money"]),
+            parse_gemspec(
+                PathBuf::from("packs/billing/billing_core.gemspec"),
+                "billing",
+                contents
+            )
+        );
+    }
+
+    #[test]
+    fn test_mismatched_dependencies() {
+        let packs = vec![
+            pack("packs/billing", &["packs/ledger", "packs/utils"]),
+            pack("packs/ledger", &[]),
+            pack("packs/payments", &[]),
+            pack("packs/utils", &[]),
+        ];
+        let gemspecs = BTreeMap::from([
+            (
+                "packs/billing",
+                gemspec("packs/billing", "billing", &["money"]),
+            ),
+            ("packs/ledger", gemspec("packs/ledger", "ledger", &[])),
+            (
+                "packs/payments",
+                gemspec("packs/payments", "payments", &["ledger", "money"]),
+            ),
+        ]);
+
+        assert_eq!(
+            vec![
+                String::from("packs/billing/package.yml declares a dependency on `packs/ledger`, but packs/billing/billing.gemspec doesn't depend on its gem `ledger`"),
+                String::from("packs/payments/payments.gemspec depends on the gem `ledger` of `packs/ledger`, but packs/payments/package.yml doesn't declare it as a dependency"),
+            ],
+            error_messages(&packs, &gemspecs)
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::packs::cli::GroupBy;
+
+use super::{write_footer, CheckAllResult, ViolationIdentifier};
+
+// Aggregates reportable violations so that large violation lists are reviewable,
+// e.g. "`::Foo::Bar`: 14 violation(s) (privacy) from 14 file(s) in 3 pack(s)"
+pub(crate) struct GroupedCheckAllResult<'a> {
+    pub result: &'a CheckAllResult,
+    pub group_by: GroupBy,
+}
+
+#[derive(Default)]
+struct ViolationGroup<'a> {
+    count: usize,
+    violation_types: BTreeSet<&'a str>,
+    files: BTreeSet<&'a str>,
+    constants: BTreeSet<&'a str>,
+    referencing_packs: BTreeSet<&'a str>,
+    defining_packs: BTreeSet<&'a str>,
+}
+
+impl<'a> ViolationGroup<'a> {
+    fn add(&mut self, identifier: &'a ViolationIdentifier) {
+        self.count += 1;
+        self.violation_types.insert(&identifier.violation_type);
+        self.files.insert(&identifier.file);
+        self.constants.insert(&identifier.constant_name);
+        self.referencing_packs
+            .insert(&identifier.referencing_pack_name);
+        self.defining_packs.insert(&identifier.defining_pack_name);
+    }
+
+    fn violation_types(&self) -> String {
+        self.violation_types
+            .iter()
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+}
+
+impl<'a> GroupedCheckAllResult<'a> {
+    fn group_key(&self, identifier: &ViolationIdentifier) -> String {
+        match self.group_by {
+            GroupBy::Constant => identifier.constant_name.to_string(),
+            GroupBy::PackPair => format!(
+                "{} -> {}",
+                identifier.referencing_pack_name, identifier.defining_pack_name
+            ),
+            GroupBy::File => identifier.file.to_string(),
+        }
+    }
+
+    fn describe(&self, key: &str, group: &ViolationGroup) -> String {
+        match self.group_by {
+            GroupBy::Constant => format!(
+                "`{}` (defined in {}): {} violation(s) ({}) from {} file(s) in {} pack(s)",
+                key,
+                group.defining_packs.iter().copied().collect::<Vec<&str>>().join(", "),
+                group.count,
+                group.violation_types(),
+                group.files.len(),
+                group.referencing_packs.len(),
+            ),
+            GroupBy::PackPair => format!(
+                "{}: {} violation(s) ({}) on {} constant(s) from {} file(s)",
+                key,
+                group.count,
+                group.violation_types(),
+                group.constants.len(),
+                group.files.len(),
+            ),
+            GroupBy::File => format!(
+                "{}: {} violation(s) ({}) on {} constant(s) in {} pack(s)",
+                key,
+                group.count,
+                group.violation_types(),
+                group.constants.len(),
+                group.defining_packs.len(),
+            ),
+        }
+    }
+
+    fn write_grouped_violations(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let violations = &self.result.reportable_violations;
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        let mut groups: HashMap<String, ViolationGroup> = HashMap::new();
+        for violation in violations {
+            groups
+                .entry(self.group_key(&violation.identifier))
+                .or_default()
+                .add(&violation.identifier);
+        }
+
+        // Largest groups first, ties broken by key for stable output
+        let sorted_groups: BTreeMap<(std::cmp::Reverse<usize>, &String), _> =
+            groups
+                .iter()
+                .map(|(key, group)| {
+                    ((std::cmp::Reverse(group.count), key), group)
+                })
+                .collect();
+
+        writeln!(
+            f,
+            "{} violation(s) detected in {} group(s):",
+            violations.len(),
+            sorted_groups.len()
+        )?;
+        for ((_, key), group) in sorted_groups {
+            writeln!(f, "{}", self.describe(key, group))?;
+        }
+        writeln!(f)
+    }
+}
+
+impl Display for GroupedCheckAllResult<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.result.has_violations() {
+            self.write_grouped_violations(f)?;
+            self.result.write_dependency_suggestions(f)?;
+            self.result.write_stale_and_strict_violations(f)?;
+        }
+        write_footer(f, self.result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::checker::Violation;
+    use crate::packs::SourceLocation;
+    use std::time::Duration;
+
+    fn violation(
+        violation_type: &str,
+        file: &str,
+        constant_name: &str,
+        referencing_pack_name: &str,
+    ) -> Violation {
+        Violation {
+            message: String::new(),
+            identifier: ViolationIdentifier {
+                violation_type: violation_type.to_string(),
+                strict: false,
+                file: file.into(),
+                constant_name: constant_name.into(),
+                referencing_pack_name: referencing_pack_name.into(),
+                defining_pack_name: "packs/foo".into(),
+                defining_file: None,
+            },
+            source_location: SourceLocation::default(),
+            snippet: None,
+        }
+    }
+
+    fn check_all_result() -> CheckAllResult {
+        CheckAllResult {
+            reportable_violations: vec![
+                violation(
+                    "privacy",
+                    "packs/bar/a.rb",
+                    "::Foo::Bar",
+                    "packs/bar",
+                ),
+                violation(
+                    "privacy",
+                    "packs/bar/b.rb",
+                    "::Foo::Bar",
+                    "packs/bar",
+                ),
+                violation(
+                    "privacy",
+                    "packs/baz/c.rb",
+                    "::Foo::Bar",
+                    "packs/baz",
+                ),
+                violation(
+                    "dependency",
+                    "packs/baz/c.rb",
+                    "::Foo::Baz",
+                    "packs/baz",
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            stale_violations: Vec::new(),
+            strict_mode_violations: Vec::new(),
+            multiplied_violations: Vec::new(),
+            dependency_suggestions: Vec::new(),
+            unparseable_files: Vec::new(),
+            files_analyzed: 3,
+            recorded_violations: 0,
+            elapsed: Duration::ZERO,
+            unresolved: Default::default(),
+            unanalyzed_files: Vec::new(),
+            sample: None,
+        }
+    }
+
+    const SUMMARY: &str = "Summary:
+  Files analyzed: 3
+  New violations: 4 (dependency: 1, privacy: 3)
+  Recorded violations: 0
+  Stale violations: 0
+  Elapsed time: 0.00s";
+
+    #[test]
+    fn test_group_by_constant() {
+        let result = check_all_result();
+        let actual = format!(
+            "{}",
+            GroupedCheckAllResult {
+                result: &result,
+                group_by: GroupBy::Constant
+            }
+        );
+
+        let expected = format!(
+            "4 violation(s) detected in 2 group(s):
+`::Foo::Bar` (defined in packs/foo): 3 violation(s) (privacy) from 3 file(s) in 2 pack(s)
+`::Foo::Baz` (defined in packs/foo): 1 violation(s) (dependency) from 1 file(s) in 1 pack(s)
+
+{}",
+            SUMMARY
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_group_by_pack_pair() {
+        let result = check_all_result();
+        let actual = format!(
+            "{}",
+            GroupedCheckAllResult {
+                result: &result,
+                group_by: GroupBy::PackPair
+            }
+        );
+
+        let expected = format!(
+            "4 violation(s) detected in 2 group(s):
+packs/bar -> packs/foo: 2 violation(s) (privacy) on 1 constant(s) from 2 file(s)
+packs/baz -> packs/foo: 2 violation(s) (dependency, privacy) on 2 constant(s) from 1 file(s)
+
+{}",
+            SUMMARY
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_group_by_file() {
+        let result = check_all_result();
+        let actual = format!(
+            "{}",
+            GroupedCheckAllResult {
+                result: &result,
+                group_by: GroupBy::File
+            }
+        );
+
+        let expected = format!(
+            "4 violation(s) detected in 3 group(s):
+packs/baz/c.rb: 2 violation(s) (dependency, privacy) on 2 constant(s) in 1 pack(s)
+packs/bar/a.rb: 1 violation(s) (privacy) on 1 constant(s) in 1 pack(s)
+packs/bar/b.rb: 1 violation(s) (privacy) on 1 constant(s) in 1 pack(s)
+
+{}",
+            SUMMARY
+        );
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,149 @@
+use serde::Serialize;
+
+use super::summary::CheckSummary;
+use super::{CheckAllResult, Violation, ViolationIdentifier};
+
+// The result of a check as a JSON object, for CI steps and tools that
+// post-process violations rather than reading them
+#[derive(Serialize)]
+pub(crate) struct JsonCheckAllResult<'a> {
+    // Sorted by file and location
+    violations: Vec<&'a Violation>,
+    stale_violations: &'a [ViolationIdentifier],
+    strict_mode_violations: Vec<&'a str>,
+    multiplied_violations: Vec<String>,
+    parse_errors: Vec<String>,
+    // Files skipped since the check timed out
+    unanalyzed_files: &'a [String],
+    summary: CheckSummary,
+}
+
+impl<'a> JsonCheckAllResult<'a> {
+    pub(crate) fn new(result: &'a CheckAllResult) -> Self {
+        Self {
+            violations: sorted(result.reportable_violations.iter()),
+            stale_violations: &result.stale_violations,
+            strict_mode_violations: result
+                .strict_mode_violations
+                .iter()
+                .map(|v| v.message.as_str())
+                .collect(),
+            multiplied_violations: result
+                .multiplied_violations
+                .iter()
+                .map(|v| v.message())
+                .collect(),
+            parse_errors: result
+                .unparseable_files
+                .iter()
+                .flat_map(|unparseable_file| unparseable_file.lines())
+                .collect(),
+            unanalyzed_files: &result.unanalyzed_files,
+            summary: result.summary(),
+        }
+    }
+}
+
+// The violations found by `update`, split into those it recorded in
+// package_todo.yml files and the strict mode violations it could not record
+#[derive(Serialize)]
+pub(crate) struct JsonUpdateResult<'a> {
+    recorded_violations: Vec<&'a Violation>,
+    strict_mode_violations: Vec<&'a Violation>,
+}
+
+impl<'a> JsonUpdateResult<'a> {
+    pub(crate) fn new(violations: &'a [Violation]) -> Self {
+        let (strict, recorded): (Vec<&Violation>, Vec<&Violation>) =
+            violations.iter().partition(|v| v.identifier.strict);
+        Self {
+            recorded_violations: sorted(recorded.into_iter()),
+            strict_mode_violations: sorted(strict.into_iter()),
+        }
+    }
+}
+
+// Sorted by file and location
+pub(crate) fn sorted<'a>(
+    violations: impl Iterator<Item = &'a Violation>,
+) -> Vec<&'a Violation> {
+    let mut violations: Vec<&Violation> = violations.collect();
+    violations.sort_by(|a, b| {
+        (
+            &a.identifier.file,
+            a.source_location.line,
+            a.source_location.column,
+            &a.message,
+        )
+            .cmp(&(
+                &b.identifier.file,
+                b.source_location.line,
+                b.source_location.column,
+                &b.message,
+            ))
+    });
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::SourceLocation;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn violation(file: &str, line: usize) -> Violation {
+        Violation {
+            message: format!("{}:{}:3\nPrivacy violation", file, line),
+            identifier: ViolationIdentifier {
+                violation_type: String::from("privacy"),
+                strict: false,
+                file: file.into(),
+                constant_name: "::Foo".into(),
+                referencing_pack_name: "packs/bar".into(),
+                defining_pack_name: "packs/foo".into(),
+                defining_file: None,
+            },
+            source_location: SourceLocation { line, column: 3 },
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_json_check_all_result() {
+        let result = CheckAllResult {
+            reportable_violations: vec![
+                violation("packs/bar/b.rb", 2),
+                violation("packs/bar/a.rb", 4),
+            ]
+            .into_iter()
+            .collect(),
+            stale_violations: Vec::new(),
+            strict_mode_violations: Vec::new(),
+            multiplied_violations: Vec::new(),
+            dependency_suggestions: Vec::new(),
+            unparseable_files: Vec::new(),
+            files_analyzed: 2,
+            recorded_violations: 1,
+            elapsed: Duration::ZERO,
+            unresolved: Default::default(),
+            unanalyzed_files: Vec::new(),
+            sample: None,
+        };
+
+        let json =
+            serde_json::to_value(JsonCheckAllResult::new(&result)).unwrap();
+
+        assert_eq!(
+            json["violations"][0]["identifier"]["file"],
+            "packs/bar/a.rb"
+        );
+        assert_eq!(
+            json["violations"][1]["identifier"]["file"],
+            "packs/bar/b.rb"
+        );
+        assert_eq!(json["summary"]["new_violations"], 2);
+        assert_eq!(json["summary"]["recorded_violations"], 1);
+        assert_eq!(json["summary"]["violations_by_checker"]["privacy"], 2);
+    }
+}
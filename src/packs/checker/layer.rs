@@ -42,10 +42,7 @@ impl Layers {
     }
 
     fn pack_enforces_layers<'a>(&self, pack: &'a Pack) -> &'a CheckerSetting {
-        match &pack.enforce_layers {
-            Some(setting) => setting,
-            None => &CheckerSetting::False,
-        }
+        pack.enforce_layers()
     }
 
     fn violation_type(&self) -> String {
@@ -59,7 +56,7 @@ impl Layers {
 
 impl Checker {
     fn validate_pack(&self, pack: &Pack) -> Option<String> {
-        match &pack.layer {
+        match pack.layer() {
             Some(layer) => {
                 if self.layers.layers.contains(layer) {
                     None
@@ -93,6 +90,23 @@ impl ValidatorInterface for Checker {
             }
         }
 
+        for exception in &configuration.architecture_exceptions {
+            for pack_name in [&exception.from, &exception.to] {
+                if configuration.pack_set.for_pack(pack_name).is_err() {
+                    error_messages.push(format!(
+                        "`architecture_exceptions` in `packwerk.yml` references `{}`, but that pack cannot be found",
+                        pack_name
+                    ));
+                }
+            }
+            if exception.reason.trim().is_empty() {
+                error_messages.push(format!(
+                    "`architecture_exceptions` in `packwerk.yml` must specify a `reason` for `{}` -> `{}`",
+                    exception.from, exception.to
+                ));
+            }
+        }
+
         if error_messages.is_empty() {
             None
         } else {
@@ -119,7 +133,7 @@ impl CheckerInterface for Checker {
         }
         let defining_pack = pack_checker.defining_pack.unwrap();
 
-        match (&pack_checker.referencing_pack.layer, &defining_pack.layer) {
+        match (pack_checker.referencing_pack.layer(), defining_pack.layer()) {
             (Some(referencing_layer), Some(defining_layer)) => {
                 if self
                     .layers
@@ -128,6 +142,17 @@ impl CheckerInterface for Checker {
                     return Ok(None);
                 }
 
+                // Intentional exceptions are configured separately from package_todo.yml,
+                // so they are never reported nor recorded as debt
+                let is_excepted =
+                    configuration.architecture_exceptions.iter().any(|e| {
+                        e.from == pack_checker.referencing_pack.name
+                            && e.to == defining_pack.name
+                    });
+                if is_excepted {
+                    return Ok(None);
+                }
+
                 let loc = print_reference_location(reference);
 
                 let message = format!(
@@ -144,6 +169,8 @@ impl CheckerInterface for Checker {
                 Ok(Some(Violation {
                     message,
                     identifier: pack_checker.violation_identifier(),
+                    source_location: reference.source_location.clone(),
+                    snippet: None,
                 }))
             }
             _ => Ok(None),
@@ -166,6 +193,7 @@ mod tests {
         default_referencing_pack, test_check, TestChecker,
     };
     use crate::packs::pack::EnforcementGlobsIgnore;
+    use crate::packs::raw_configuration::ArchitectureException;
     use crate::packs::{
         configuration,
         pack::{CheckerSetting, Pack},
@@ -297,6 +325,99 @@ mod tests {
         test_check(&checker_with_layers(), &mut test_checker)
     }
 
+    fn configuration_with_architecture_exceptions(
+        defining_pack: &Pack,
+        referencing_pack: &Pack,
+        architecture_exceptions: Vec<ArchitectureException>,
+    ) -> Configuration {
+        let root_pack = Pack {
+            name: String::from("."),
+            ..Pack::default()
+        };
+        Configuration {
+            pack_set: PackSet::build(
+                HashSet::from_iter(vec![
+                    root_pack,
+                    defining_pack.clone(),
+                    referencing_pack.clone(),
+                ]),
+                HashMap::new(),
+            )
+            .unwrap(),
+            architecture_exceptions,
+            ..Configuration::default()
+        }
+    }
+
+    #[test]
+    fn reference_is_a_layer_violation_with_architecture_exception(
+    ) -> anyhow::Result<()> {
+        let defining_pack = Pack {
+            name: "packs/bar".to_owned(),
+            layer: Some("product".to_string()),
+            ..default_defining_pack()
+        };
+        let referencing_pack = Pack {
+            name: "packs/foo".to_owned(),
+            enforce_layers: Some(CheckerSetting::True),
+            layer: Some("utilities".to_string()),
+            ..default_referencing_pack()
+        };
+        let configuration = configuration_with_architecture_exceptions(
+            &defining_pack,
+            &referencing_pack,
+            vec![ArchitectureException {
+                from: String::from("packs/foo"),
+                to: String::from("packs/bar"),
+                reason: String::from("Intentional"),
+            }],
+        );
+        let mut test_checker = TestChecker {
+            configuration: Some(configuration),
+            referenced_constant_name: Some(String::from("::Bar")),
+            defining_pack: Some(defining_pack),
+            referencing_pack,
+            ..Default::default()
+        };
+        test_check(&checker_with_layers(), &mut test_checker)
+    }
+
+    #[test]
+    fn reference_is_a_layer_violation_with_reversed_architecture_exception(
+    ) -> anyhow::Result<()> {
+        let defining_pack = Pack {
+            name: "packs/bar".to_owned(),
+            layer: Some("product".to_string()),
+            ..default_defining_pack()
+        };
+        let referencing_pack = Pack {
+            name: "packs/foo".to_owned(),
+            enforce_layers: Some(CheckerSetting::True),
+            layer: Some("utilities".to_string()),
+            ..default_referencing_pack()
+        };
+        let configuration = configuration_with_architecture_exceptions(
+            &defining_pack,
+            &referencing_pack,
+            vec![ArchitectureException {
+                from: String::from("packs/bar"),
+                to: String::from("packs/foo"),
+                reason: String::from("Intentional"),
+            }],
+        );
+        let mut test_checker = TestChecker {
+            configuration: Some(configuration),
+            referenced_constant_name: Some(String::from("::Bar")),
+            defining_pack: Some(defining_pack),
+            referencing_pack,
+            expected_violation: Some(build_expected_violation(
+                "packs/foo/app/services/foo.rb:3:1\nLayer violation: `::Bar` belongs to `packs/bar` (whose layer is `product`) cannot be accessed from `packs/foo` (whose layer is `utilities`)".to_string(),
+                "layer".to_string(), false)),
+            ..Default::default()
+        };
+        test_check(&checker_with_layers(), &mut test_checker)
+    }
+
     #[test]
     fn test_with_enforcement_globs_ignore() -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
@@ -496,4 +617,37 @@ mod tests {
         ];
         assert_eq!(errors, expected_errors);
     }
+
+    #[test]
+    fn validate_architecture_exceptions() {
+        let root_pack = Pack {
+            name: String::from("."),
+            ..Pack::default()
+        };
+        let foo_pack = Pack {
+            name: String::from("packs/foo"),
+            ..Pack::default()
+        };
+        let configuration = Configuration {
+            pack_set: PackSet::build(
+                HashSet::from_iter(vec![root_pack, foo_pack]),
+                HashMap::new(),
+            )
+            .unwrap(),
+            architecture_exceptions: vec![ArchitectureException {
+                from: String::from("packs/foo"),
+                to: String::from("packs/missing"),
+                reason: String::from(" "),
+            }],
+            ..Configuration::default()
+        };
+
+        let errors = checker_with_layers().validate(&configuration);
+
+        let expected_errors = vec![
+            "`architecture_exceptions` in `packwerk.yml` references `packs/missing`, but that pack cannot be found".to_string(),
+            "`architecture_exceptions` in `packwerk.yml` must specify a `reason` for `packs/foo` -> `packs/missing`".to_string(),
+        ];
+        assert_eq!(errors, Some(expected_errors));
+    }
 }
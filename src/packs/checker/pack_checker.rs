@@ -24,6 +24,7 @@ pub enum ViolationType {
     FolderPrivacy,
     Layer,
     Privacy,
+    TagPolicy,
     Visibility,
 }
 
@@ -34,6 +35,7 @@ impl From<&str> for ViolationType {
             "folder_privacy" => ViolationType::FolderPrivacy,
             "layer" => ViolationType::Layer,
             "privacy" => ViolationType::Privacy,
+            "tag_policy" => ViolationType::TagPolicy,
             "visibility" => ViolationType::Visibility,
             _ => panic!("unknown violation type: {}", s),
         }
@@ -47,11 +49,25 @@ impl From<ViolationType> for &str {
             ViolationType::FolderPrivacy => "folder_privacy",
             ViolationType::Layer => "layer",
             ViolationType::Privacy => "privacy",
+            ViolationType::TagPolicy => "tag_policy",
             ViolationType::Visibility => "visibility",
         }
     }
 }
 
+impl ViolationType {
+    // Whether the referencing pack, rather than the defining pack, enforces
+    // the checker of this violation type
+    pub fn is_enforced_by_referencing_pack(self) -> bool {
+        matches!(
+            self,
+            ViolationType::Dependency
+                | ViolationType::Layer
+                | ViolationType::TagPolicy
+        )
+    }
+}
+
 impl<'a> PackChecker<'a> {
     pub fn new(
         configuration: &'a Configuration,
@@ -69,13 +85,10 @@ impl<'a> PackChecker<'a> {
     }
 
     fn violation_direction(&self) -> ViolationDirection {
-        match self.violation_type {
-            ViolationType::Dependency | ViolationType::Layer => {
-                ViolationDirection::Outgoing
-            }
-            ViolationType::Privacy
-            | ViolationType::FolderPrivacy
-            | ViolationType::Visibility => ViolationDirection::Incoming,
+        if self.violation_type.is_enforced_by_referencing_pack() {
+            ViolationDirection::Outgoing
+        } else {
+            ViolationDirection::Incoming
         }
     }
 
@@ -99,7 +112,12 @@ impl<'a> PackChecker<'a> {
     }
 
     pub fn is_strict(&self) -> bool {
-        self.rules_checker_setting().is_strict()
+        let other_pack_name = match self.violation_direction() {
+            ViolationDirection::Outgoing => self.defining_pack_name(),
+            ViolationDirection::Incoming => self.referencing_pack_name(),
+        };
+        self.configuration.strict
+            || self.rules_checker_setting().is_strict_for(other_pack_name)
     }
 
     fn defining_pack_name(&self) -> &str {
@@ -117,12 +135,13 @@ impl<'a> PackChecker<'a> {
             ViolationType::FolderPrivacy => {
                 self.rules_pack().enforce_folder_privacy()
             }
-            ViolationType::Layer => {
-                self.checker_setting_for(&self.rules_pack().enforce_layers)
-            }
+            ViolationType::Layer => self.rules_pack().enforce_layers(),
             ViolationType::Privacy => {
                 self.checker_setting_for(&self.rules_pack().enforce_privacy)
             }
+            // Tag policies are enforced wherever they apply, since they're
+            // configured in packwerk.yml rather than by each pack
+            ViolationType::TagPolicy => &CheckerSetting::True,
             ViolationType::Visibility => {
                 self.checker_setting_for(&self.rules_pack().enforce_visibility)
             }
@@ -141,6 +160,7 @@ impl<'a> PackChecker<'a> {
             ViolationType::Privacy => {
                 self.configuration.disable_enforce_privacy
             }
+            ViolationType::TagPolicy => false,
             ViolationType::Visibility => {
                 self.configuration.disable_enforce_visibility
             }
@@ -169,10 +189,10 @@ impl<'a> PackChecker<'a> {
     fn is_ignored(&self) -> anyhow::Result<bool> {
         let file_path = match self.violation_direction() {
             ViolationDirection::Incoming => {
-                &self.reference.relative_referencing_file
+                &*self.reference.relative_referencing_file
             }
             ViolationDirection::Outgoing => {
-                self.reference.relative_defining_file.as_ref().unwrap()
+                self.reference.relative_defining_file.as_deref().unwrap()
             }
         };
         self.rules_pack()
@@ -186,8 +206,13 @@ impl<'a> PackChecker<'a> {
             strict: self.is_strict(),
             file: self.reference.relative_referencing_file.clone(),
             constant_name: self.reference.constant_name.clone(),
-            referencing_pack_name: self.referencing_pack.name.clone(),
-            defining_pack_name: self.defining_pack.unwrap().name.clone(),
+            referencing_pack_name: self.reference.referencing_pack_name.clone(),
+            defining_pack_name: self
+                .reference
+                .defining_pack_name
+                .clone()
+                .unwrap(),
+            defining_file: self.reference.relative_defining_file.clone(),
         }
     }
 }
@@ -24,49 +24,53 @@ impl CheckerInterface for Checker {
         let defining_pack = pack_checker.defining_pack.unwrap();
         if defining_pack
             .ignored_private_constants
-            .contains(&reference.constant_name)
+            .contains(&*reference.constant_name)
+        {
+            return Ok(None);
+        }
+        if defining_pack
+            .is_privacy_exception(&pack_checker.referencing_pack.name)
         {
             return Ok(None);
         }
 
-        // This is a hack for now – we need to read package.yml file public_paths at some point,
-        // and probably find a better way to check if the constant is public
-        let public_folder = &defining_pack.public_folder();
         let is_public = reference
             .relative_defining_file
-            .as_ref()
+            .as_deref()
             .map(|relative_file| {
                 let absolute_file =
                     configuration.absolute_root.join(relative_file);
 
                 // if configuration.input_files_count is greater than zero, we're just going to individually
                 // take the contents of the absolute file and call extract_sigils_from_contents on it to get the sigils
-                // and then check if a "public" sigil is contained. manual_read_of_defining_file_contains_sigil
+                // and then check if a "public" sigil is contained.
                 // That's because we need to process a file to get the sigils, and if files are inputted, we likely didn't also input
                 // the file that defines the constant and has the sigil.
-                let manual_read_of_defining_file_contains_sigil =
-                    if configuration.input_files_count > 0 {
-                        if let Ok(contents) =
-                            std::fs::read_to_string(&absolute_file)
-                        {
-                            let sigils =
-                                ruby::parse_utils::extract_sigils_from_contents(
-                                    &contents,
-                                );
-
-                            sigils.iter().any(|sigil| sigil.name == "public")
-                        } else {
-                            false
+                let sigil_value = sigils
+                    .get(&absolute_file)
+                    .and_then(|sigils| {
+                        ruby::parse_utils::public_sigil_value(sigils)
+                    })
+                    .or_else(|| {
+                        if configuration.input_files_count == 0 {
+                            return None;
                         }
-                    } else {
-                        false
-                    };
-
-                // Check if the relative file starts with `public_folder` or the absolute file is in `sigils`
-                relative_file
-                    .starts_with(public_folder.to_string_lossy().as_ref())
-                    || sigils.contains_key(&absolute_file)
-                    || manual_read_of_defining_file_contains_sigil
+                        let contents =
+                            std::fs::read_to_string(&absolute_file).ok()?;
+                        ruby::parse_utils::public_sigil_value(
+                            &ruby::parse_utils::extract_sigils_from_contents(
+                                &contents,
+                            ),
+                        )
+                    });
+
+                // A `pack_public` or `pack_private` sigil overrides whether the
+                // file is in one of the public paths
+                sigil_value.unwrap_or_else(|| {
+                    configuration
+                        .pack_set
+                        .is_public_file(defining_pack, relative_file)
+                })
             })
             .unwrap_or(false);
 
@@ -83,7 +87,7 @@ impl CheckerInterface for Checker {
 
         if !private_constants.is_empty() {
             let constant_is_private =
-                private_constants.contains(&reference.constant_name);
+                private_constants.contains(&*reference.constant_name);
 
             let constant_is_in_private_namespace =
                 private_constants.iter().any(|private_constant| {
@@ -118,6 +122,8 @@ impl CheckerInterface for Checker {
         Ok(Some(Violation {
             message,
             identifier: pack_checker.violation_identifier(),
+            source_location: reference.source_location.clone(),
+            snippet: None,
         }))
     }
 
@@ -141,6 +147,7 @@ mod tests {
 
     use super::*;
     use crate::packs::{
+        interner::InternedStr,
         pack::{CheckerSetting, Pack},
         *,
     };
@@ -185,6 +192,45 @@ mod tests {
         test_check(&Checker {}, &mut test_checker)
     }
 
+    #[test]
+    fn test_with_privacy_exception() -> anyhow::Result<()> {
+        let mut test_checker = TestChecker {
+            reference: None,
+            configuration: None,
+            referenced_constant_name: Some(String::from("::Bar")),
+            defining_pack: Some(Pack {
+                name: "packs/bar".to_owned(),
+                enforce_privacy: Some(CheckerSetting::True),
+                privacy_exceptions: HashSet::from([String::from("packs/f*")]),
+                ..default_defining_pack()
+            }),
+            referencing_pack: default_referencing_pack(),
+            ..Default::default()
+        };
+        test_check(&Checker {}, &mut test_checker)
+    }
+
+    #[test]
+    fn test_with_privacy_exception_for_another_pack() -> anyhow::Result<()> {
+        let mut test_checker = TestChecker {
+            reference: None,
+            configuration: None,
+            referenced_constant_name: Some(String::from("::Bar")),
+            defining_pack: Some(Pack {
+                name: "packs/bar".to_owned(),
+                enforce_privacy: Some(CheckerSetting::True),
+                privacy_exceptions: HashSet::from([String::from("packs/baz")]),
+                ..default_defining_pack()
+            }),
+            referencing_pack: default_referencing_pack(),
+            expected_violation: Some(build_expected_violation(
+                String::from("packs/foo/app/services/foo.rb:3:1\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"),
+                String::from("privacy"), false,
+            )),
+        };
+        test_check(&Checker {}, &mut test_checker)
+    }
+
     #[test]
     fn test_with_privacy_violation() -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
@@ -282,13 +328,13 @@ mod tests {
     fn test_with_public_constant() -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Bar"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/public/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
@@ -310,13 +356,13 @@ mod tests {
     fn test_public_folder_detection() -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Bar"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/public/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
@@ -339,13 +385,13 @@ mod tests {
     fn test_custom_public_folder_detection() -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Bar"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/api/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
@@ -364,18 +410,93 @@ mod tests {
         test_check(&Checker {}, &mut test_checker)
     }
 
+    #[test]
+    fn test_multiple_public_paths_detection() -> anyhow::Result<()> {
+        for relative_defining_file in [
+            "packs/bar/app/public/bar.rb",
+            "packs/bar/app/contracts/bar_contract.rb",
+        ] {
+            let mut test_checker = TestChecker {
+                reference: Some(Reference {
+                    constant_name: InternedStr::from("::Bar"),
+                    defining_pack_name: Some(InternedStr::from("packs/bar")),
+                    referencing_pack_name: InternedStr::from("packs/foo"),
+                    relative_referencing_file: InternedStr::from(
+                        "packs/foo/app/services/foo.rb",
+                    ),
+                    relative_defining_file: Some(InternedStr::from(
+                        relative_defining_file,
+                    )),
+                    source_location: SourceLocation { line: 3, column: 1 },
+                }),
+                configuration: None,
+                defining_pack: Some(Pack {
+                    name: "packs/bar".to_owned(),
+                    enforce_privacy: Some(CheckerSetting::True),
+                    relative_path: PathBuf::from("packs/bar"),
+                    public_paths: Some(vec![
+                        String::from("app/public"),
+                        String::from("app/contracts/*_contract.rb"),
+                    ]),
+                    ..default_defining_pack()
+                }),
+                referencing_pack: default_referencing_pack(),
+                expected_violation: None,
+                ..Default::default()
+            };
+            test_check(&Checker {}, &mut test_checker)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_outside_of_public_paths() -> anyhow::Result<()> {
+        let mut test_checker = TestChecker {
+            reference: Some(Reference {
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
+                    "packs/foo/app/services/foo.rb",
+                ),
+                relative_defining_file: Some(InternedStr::from(
+                    "packs/bar/app/contracts/helper.rb",
+                )),
+                source_location: SourceLocation { line: 3, column: 1 },
+            }),
+            configuration: None,
+            defining_pack: Some(Pack {
+                name: "packs/bar".to_owned(),
+                enforce_privacy: Some(CheckerSetting::True),
+                relative_path: PathBuf::from("packs/bar"),
+                public_paths: Some(vec![
+                    String::from("app/public"),
+                    String::from("app/contracts/*_contract.rb"),
+                ]),
+                ..default_defining_pack()
+            }),
+            referencing_pack: default_referencing_pack(),
+            expected_violation: Some(build_expected_violation(
+                String::from("packs/foo/app/services/foo.rb:3:1\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"),
+                String::from("privacy"), false,
+            )),
+            ..Default::default()
+        };
+        test_check(&Checker {}, &mut test_checker)
+    }
+
     #[test]
     fn test_privacy_constants_exclude_referenced_constant() -> anyhow::Result<()>
     {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Different"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Different"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/services/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
@@ -402,13 +523,13 @@ mod tests {
     ) -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Bar::BarChild"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar::BarChild"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/services/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
@@ -438,13 +559,13 @@ mod tests {
     fn test_privacy_constants_match_full_namespace() -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Barbie::BarChild"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Barbie::BarChild"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/api/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
@@ -471,13 +592,13 @@ mod tests {
     ) -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Bar"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/api/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
@@ -504,13 +625,13 @@ mod tests {
     ) -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Bar"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/public/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
@@ -536,13 +657,13 @@ mod tests {
     fn test_defining_pack_not_found() -> anyhow::Result<()> {
         let mut test_checker = TestChecker {
             reference: Some(Reference {
-                constant_name: String::from("::Bar"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/public/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
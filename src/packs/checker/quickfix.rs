@@ -0,0 +1,139 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::packs::bin_locater;
+
+use super::CheckAllResult;
+
+// Vim/compiler-style `file:line:col: message` lines, sorted by file, so that
+// violations can be loaded into Vim's quickfix list, Emacs compilation-mode,
+// and the like. Problems without a location in a file are listed last.
+pub(crate) struct QuickfixCheckAllResult<'a> {
+    pub result: &'a CheckAllResult,
+}
+
+impl QuickfixCheckAllResult<'_> {
+    fn entries(&self) -> Vec<(&str, usize, usize, String)> {
+        let mut entries: Vec<(&str, usize, usize, String)> = self
+            .result
+            .reportable_violations
+            .iter()
+            .map(|violation| {
+                (
+                    violation.identifier.file.as_str(),
+                    violation.source_location.line,
+                    violation.source_location.column,
+                    violation.description(),
+                )
+            })
+            .collect();
+        // Multiplied violations are about the file as a whole
+        entries.extend(
+            self.result
+                .multiplied_violations
+                .iter()
+                .map(|v| (v.file.as_str(), 1, 1, v.message())),
+        );
+        entries.extend(self.result.unparseable_files.iter().flat_map(
+            |unparseable_file| {
+                unparseable_file.errors.iter().map(|error| {
+                    (
+                        unparseable_file.file.as_str(),
+                        error.line,
+                        error.column,
+                        format!("Parse error: {}", error.message),
+                    )
+                })
+            },
+        ));
+        entries.sort();
+        entries.dedup();
+        entries
+    }
+}
+
+impl Display for QuickfixCheckAllResult<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (file, line, column, message) in self.entries() {
+            writeln!(f, "{}:{}:{}: {}", file, line, column, message)?;
+        }
+        if !self.result.stale_violations.is_empty() {
+            writeln!(
+                f,
+                "There were stale violations found, please run `{} update`",
+                bin_locater::packs_bin_name(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::checker::{
+        MultipliedViolation, Violation, ViolationIdentifier,
+    };
+    use crate::packs::SourceLocation;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn violation(file: &str, line: usize, message: &str) -> Violation {
+        Violation {
+            message: format!("{}:{}:3\n{}", file, line, message),
+            identifier: ViolationIdentifier {
+                violation_type: "privacy".to_string(),
+                strict: false,
+                file: file.into(),
+                constant_name: "::Foo".into(),
+                referencing_pack_name: "packs/bar".into(),
+                defining_pack_name: "packs/foo".into(),
+                defining_file: None,
+            },
+            source_location: SourceLocation { line, column: 3 },
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_quickfix_lines_are_sorted_by_file() {
+        let result = CheckAllResult {
+            reportable_violations: vec![
+                violation("packs/bar/b.rb", 2, "Privacy violation: b"),
+                violation("packs/bar/a.rb", 10, "Privacy violation: a2"),
+                violation("packs/bar/a.rb", 4, "Privacy violation: a1"),
+            ]
+            .into_iter()
+            .collect(),
+            stale_violations: Vec::new(),
+            strict_mode_violations: Vec::new(),
+            multiplied_violations: vec![MultipliedViolation {
+                file: String::from("packs/bar/a.rb"),
+                constant_name: String::from("::Foo"),
+                referencing_pack_name: String::from("packs/bar"),
+                defining_pack_name: String::from("packs/foo"),
+                recorded_count: 1,
+                found_count: 2,
+            }],
+            dependency_suggestions: Vec::new(),
+            unparseable_files: Vec::new(),
+            files_analyzed: 3,
+            recorded_violations: 0,
+            elapsed: Duration::ZERO,
+            unresolved: Default::default(),
+            unanalyzed_files: Vec::new(),
+            sample: None,
+        };
+
+        let expected = "\
+packs/bar/a.rb:1:1: packs/bar references `::Foo` 2 time(s) in packs/bar/a.rb, but its package_todo.yml records 1. Please remove the new reference(s) or run `packs update`
+packs/bar/a.rb:4:3: Privacy violation: a1
+packs/bar/a.rb:10:3: Privacy violation: a2
+packs/bar/b.rb:2:3: Privacy violation: b
+";
+        assert_eq!(
+            expected,
+            QuickfixCheckAllResult { result: &result }.to_string()
+        );
+    }
+}
@@ -3,17 +3,22 @@ use std::path::Path;
 use anyhow::{bail, Context};
 
 use crate::packs::{
-    constant_resolver::ConstantResolver, pack::Pack,
-    parsing::UnresolvedReference, Configuration, PackSet, SourceLocation,
+    constant_resolver::ConstantResolver,
+    interner::{InternedStr, Interner},
+    pack::Pack,
+    parsing::UnresolvedReference,
+    Configuration, PackSet, SourceLocation,
 };
 
+// Names and files are interned (see interner.rs), since there are many
+// references for each of them
 #[derive(Debug)]
 pub struct Reference {
-    pub constant_name: String,
-    pub defining_pack_name: Option<String>,
-    pub relative_defining_file: Option<String>,
-    pub referencing_pack_name: String,
-    pub relative_referencing_file: String,
+    pub constant_name: InternedStr,
+    pub defining_pack_name: Option<InternedStr>,
+    pub relative_defining_file: Option<InternedStr>,
+    pub referencing_pack_name: InternedStr,
+    pub relative_referencing_file: InternedStr,
     pub source_location: SourceLocation,
 }
 
@@ -50,11 +55,12 @@ impl Reference {
         constant_resolver: &(dyn ConstantResolver + Send + Sync),
         unresolved_reference: &UnresolvedReference,
         referencing_file_path: &Path,
+        interner: &Interner,
     ) -> anyhow::Result<Vec<Reference>> {
         let referencing_pack_name = match configuration
             .pack_set
             .for_file(referencing_file_path)?
-            .map(|pack| pack.name.clone())
+            .map(|pack| interner.intern(&pack.name))
         {
             Some(pack_name) => pack_name,
             None => bail!(
@@ -75,7 +81,7 @@ impl Reference {
             .to_path_buf();
 
         let relative_referencing_file =
-            relative_referencing_file_path.to_str().unwrap().to_string();
+            interner.intern(relative_referencing_file_path.to_str().unwrap());
 
         let str_namespace_path: Vec<&str> = unresolved_reference
             .namespace_path
@@ -92,21 +98,32 @@ impl Reference {
                 .map(move |constant| {
                     let absolute_path_of_definition =
                         &constant.absolute_path_of_definition;
-                    let relative_defining_file = absolute_path_of_definition
-                        .strip_prefix(&configuration.absolute_root)
-                        .unwrap()
-                        .to_path_buf()
-                        .to_str()
-                        .unwrap()
-                        .to_string();
-
-                    let defining_pack_name = configuration
+                    let relative_defining_file = interner.intern(
+                        absolute_path_of_definition
+                            .strip_prefix(&configuration.absolute_root)
+                            .unwrap()
+                            .to_str()
+                            .unwrap(),
+                    );
+
+                    let defining_pack = configuration
                         .pack_set
-                        .for_file(absolute_path_of_definition)?
-                        .map(|pack| pack.name.clone());
+                        .for_file(absolute_path_of_definition)?;
+                    // Constants defined in the root pack belong to the pack
+                    // declaring their namespace, if any
+                    let defining_pack = match defining_pack {
+                        Some(pack) if pack.name != "." => Some(pack),
+                        _ => configuration
+                            .pack_set
+                            .for_namespace(&constant.fully_qualified_name)
+                            .or(defining_pack),
+                    };
+                    let defining_pack_name =
+                        defining_pack.map(|pack| interner.intern(&pack.name));
 
                     let relative_defining_file = Some(relative_defining_file);
-                    let constant_name = constant.fully_qualified_name.clone();
+                    let constant_name =
+                        interner.intern(&constant.fully_qualified_name);
 
                     Ok(Reference {
                         constant_name,
@@ -123,7 +140,7 @@ impl Reference {
             let defining_pack_name = None;
             let relative_defining_file = None;
             // Contant name is not known, so we'll just use the unresolved name for now
-            let constant_name = unresolved_reference.name.clone();
+            let constant_name = interner.intern(&unresolved_reference.name);
 
             Ok(vec![Reference {
                 constant_name,
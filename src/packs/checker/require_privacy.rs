@@ -0,0 +1,212 @@
+use std::path::{Component, Path, PathBuf};
+
+use regex::Regex;
+
+use super::ValidatorInterface;
+use crate::packs::Configuration;
+
+// With `check_requires: true` in packwerk.yml, finds `require` and
+// `require_relative` statements that load a private file of another pack.
+// Requiring a file doesn't reference its constants by name, so it gets around
+// the privacy and dependency checkers entirely. `require_relative` paths are
+// relative to the requiring file, and `require` paths to the `lib` directory
+// of the project or of a pack. Requires of files outside the packs, e.g. of
+// gems, and of interpolated paths, are left alone.
+pub struct Validator {}
+
+// A `require` or `require_relative` of a string literal
+#[derive(Debug, PartialEq, Eq)]
+struct Require {
+    line: usize,
+    relative: bool,
+    path: String,
+}
+
+impl ValidatorInterface for Validator {
+    fn validate(&self, configuration: &Configuration) -> Option<Vec<String>> {
+        if !configuration.check_requires
+            || configuration.disable_enforce_privacy
+        {
+            return None;
+        }
+
+        let mut files: Vec<&PathBuf> = configuration
+            .included_files
+            .iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rb"))
+            .collect();
+        files.sort();
+
+        let mut error_messages: Vec<String> = Vec::new();
+        for file in files {
+            let Ok(contents) = std::fs::read_to_string(file) else {
+                continue;
+            };
+            for require in extract_requires(&contents) {
+                if let Some(error_message) =
+                    error_message(configuration, file, &require)
+                {
+                    error_messages.push(error_message);
+                }
+            }
+        }
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages)
+        }
+    }
+}
+
+fn error_message(
+    configuration: &Configuration,
+    requiring_file: &Path,
+    require: &Require,
+) -> Option<String> {
+    let pack_set = &configuration.pack_set;
+    let requiring_pack = pack_set.for_file(requiring_file).ok()??;
+    let required_file =
+        resolve_require(configuration, requiring_file, require)?;
+    let defining_pack = pack_set.for_file(&required_file).ok()??;
+    let enforces_privacy = defining_pack
+        .enforce_privacy
+        .as_ref()
+        .is_some_and(|setting| !setting.is_false());
+    if defining_pack.name == requiring_pack.name
+        || !enforces_privacy
+        || defining_pack.is_privacy_exception(&requiring_pack.name)
+    {
+        return None;
+    }
+
+    let relative_required_file = required_file
+        .strip_prefix(&configuration.absolute_root)
+        .ok()?
+        .to_string_lossy();
+    if pack_set.is_public_file(defining_pack, &relative_required_file) {
+        return None;
+    }
+
+    let relative_requiring_file = requiring_file
+        .strip_prefix(&configuration.absolute_root)
+        .ok()?;
+    Some(format!(
+        "{}:{} requires {}, which is private to `{}`, from `{}`",
+        relative_requiring_file.display(),
+        require.line,
+        relative_required_file,
+        defining_pack.name,
+        requiring_pack.name,
+    ))
+}
+
+// The absolute path of the file a require loads, if it's in the project
+fn resolve_require(
+    configuration: &Configuration,
+    requiring_file: &Path,
+    require: &Require,
+) -> Option<PathBuf> {
+    let mut path = PathBuf::from(&require.path);
+    if path.extension().is_none() {
+        path.set_extension("rb");
+    }
+    if require.relative {
+        let candidate = normalize(&requiring_file.parent()?.join(&path));
+        return candidate.is_file().then_some(candidate);
+    }
+
+    let absolute_root = &configuration.absolute_root;
+    std::iter::once(absolute_root.join("lib"))
+        .chain(
+            configuration.pack_set.packs.iter().map(|pack| {
+                absolute_root.join(&pack.relative_path).join("lib")
+            }),
+        )
+        .map(|lib| normalize(&lib.join(&path)))
+        .find(|candidate| candidate.is_file())
+}
+
+// Removes the `.` and `..` of a path without following symlinks, so that
+// files in symlinked packs keep their symlinked path
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// Only requires of a string literal at the start of a line are found, e.g.
+// `require "foo/bar"` or `require_relative("../bar")`
+fn extract_requires(contents: &str) -> Vec<Require> {
+    let require_regex =
+        Regex::new(r##"^\s*require(_relative)?[\s(]\s*['"]([^'"#]+)['"]"##)
+            .unwrap();
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let captures = require_regex.captures(line)?;
+            Some(Require {
+                line: index + 1,
+                relative: captures.get(1).is_some(),
+                path: captures[2].to_owned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_extract_requires() {
+        let contents = r##"# frozen_string_literal: true
+require "json"
+require_relative "../ledger/entry"
+  require_relative('internal/helper')
+require "#{root}/lib/foo"
+# require "commented/out"
+required = "not a require"
+"##;
+        assert_eq!(
+            vec![
+                Require {
+                    line: 2,
+                    relative: false,
+                    path: String::from("json"),
+                },
+                Require {
+                    line: 3,
+                    relative: true,
+                    path: String::from("../ledger/entry"),
+                },
+                Require {
+                    line: 4,
+                    relative: true,
+                    path: String::from("internal/helper"),
+                },
+            ],
+            extract_requires(contents)
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            PathBuf::from("/app/packs/ledger/app/services/entry.rb"),
+            normalize(Path::new(
+                "/app/packs/billing/app/services/../../../ledger/./app/services/entry.rb"
+            ))
+        );
+    }
+}
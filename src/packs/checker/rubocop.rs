@@ -0,0 +1,316 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use super::{CheckAllResult, Violation};
+
+// The cop that RuboCop reports syntax errors under
+const SYNTAX_COP_NAME: &str = "Lint/Syntax";
+
+// Violations in RuboCop's JSON format (that of `rubocop --format json`), so
+// that code quality dashboards and editor plugins that read RuboCop output
+// show them unchanged. Each violation is an offense whose cop is the type of
+// the checker that found it. Syntax errors are `Lint/Syntax` offenses, as in
+// RuboCop, and references multiplied beyond what package_todo.yml records are
+// `package_todo` offenses. Only files with offenses are listed.
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct RubocopResult {
+    metadata: Metadata,
+    // Sorted by path
+    files: Vec<File>,
+    summary: Summary,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Metadata {
+    pks_version: &'static str,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct File {
+    // Relative to the project root
+    path: String,
+    // Sorted by location
+    offenses: Vec<Offense>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Offense {
+    severity: &'static str,
+    message: String,
+    cop_name: String,
+    corrected: bool,
+    correctable: bool,
+    location: Location,
+}
+
+// Violations are located where the reference starts, so their extent is empty
+#[derive(Debug, PartialEq, Serialize)]
+struct Location {
+    start_line: usize,
+    start_column: usize,
+    last_line: usize,
+    last_column: usize,
+    length: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    fn new(line: usize, column: usize) -> Self {
+        Self {
+            start_line: line,
+            start_column: column,
+            last_line: line,
+            last_column: column,
+            length: 0,
+            line,
+            column,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Summary {
+    offense_count: usize,
+    target_file_count: usize,
+    inspected_file_count: usize,
+}
+
+impl Offense {
+    fn from_violation(violation: &Violation) -> Self {
+        Self {
+            severity: "error",
+            message: violation.description(),
+            cop_name: violation.identifier.violation_type.clone(),
+            corrected: false,
+            correctable: false,
+            location: Location::new(
+                violation.source_location.line,
+                violation.source_location.column,
+            ),
+        }
+    }
+}
+
+impl RubocopResult {
+    pub(crate) fn new(result: &CheckAllResult) -> Self {
+        let mut offenses: Vec<(String, Offense)> = result
+            .reportable_violations
+            .iter()
+            .map(|violation| {
+                (
+                    violation.identifier.file.to_string(),
+                    Offense::from_violation(violation),
+                )
+            })
+            .collect();
+        // Multiplied violations are about the file as a whole
+        offenses.extend(result.multiplied_violations.iter().map(|v| {
+            (
+                v.file.clone(),
+                Offense {
+                    severity: "error",
+                    message: v.message(),
+                    cop_name: String::from("package_todo"),
+                    corrected: false,
+                    correctable: false,
+                    location: Location::new(1, 1),
+                },
+            )
+        }));
+        offenses.extend(result.unparseable_files.iter().flat_map(
+            |unparseable_file| {
+                unparseable_file.errors.iter().map(|error| {
+                    (
+                        unparseable_file.file.clone(),
+                        Offense {
+                            severity: "fatal",
+                            message: error.message.clone(),
+                            cop_name: String::from(SYNTAX_COP_NAME),
+                            corrected: false,
+                            correctable: false,
+                            location: Location::new(error.line, error.column),
+                        },
+                    )
+                })
+            },
+        ));
+        Self::from_offenses(offenses, result.files_analyzed)
+    }
+
+    // The violations found by `update`, when the files it inspected aren't
+    // known apart from those with violations
+    pub(crate) fn from_violations(violations: &[Violation]) -> Self {
+        let offenses: Vec<(String, Offense)> = violations
+            .iter()
+            .map(|violation| {
+                (
+                    violation.identifier.file.to_string(),
+                    Offense::from_violation(violation),
+                )
+            })
+            .collect();
+        let files_with_offenses = offenses
+            .iter()
+            .map(|(path, _)| path)
+            .collect::<BTreeSet<&String>>()
+            .len();
+        Self::from_offenses(offenses, files_with_offenses)
+    }
+
+    fn from_offenses(
+        offenses: Vec<(String, Offense)>,
+        inspected_file_count: usize,
+    ) -> Self {
+        let mut offenses_by_path: BTreeMap<String, Vec<Offense>> =
+            BTreeMap::new();
+        for (path, offense) in offenses {
+            offenses_by_path.entry(path).or_default().push(offense);
+        }
+        let files: Vec<File> = offenses_by_path
+            .into_iter()
+            .map(|(path, mut offenses)| {
+                offenses.sort_by(|a, b| {
+                    (a.location.line, a.location.column, &a.message).cmp(&(
+                        b.location.line,
+                        b.location.column,
+                        &b.message,
+                    ))
+                });
+                offenses.dedup();
+                File { path, offenses }
+            })
+            .collect();
+        let offense_count = files.iter().map(|file| file.offenses.len()).sum();
+        Self {
+            metadata: Metadata {
+                pks_version: env!("CARGO_PKG_VERSION"),
+            },
+            files,
+            summary: Summary {
+                offense_count,
+                target_file_count: inspected_file_count,
+                inspected_file_count,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::checker::{UnparseableFile, ViolationIdentifier};
+    use crate::packs::{SourceLocation, SyntaxError};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn violation(file: &str, line: usize, violation_type: &str) -> Violation {
+        Violation {
+            message: format!(
+                "{}:{}:3\nPrivacy violation: `::Foo` is private",
+                file, line
+            ),
+            identifier: ViolationIdentifier {
+                violation_type: violation_type.to_string(),
+                strict: false,
+                file: file.into(),
+                constant_name: "::Foo".into(),
+                referencing_pack_name: "packs/bar".into(),
+                defining_pack_name: "packs/foo".into(),
+                defining_file: None,
+            },
+            source_location: SourceLocation { line, column: 3 },
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_rubocop_result() {
+        let result = CheckAllResult {
+            reportable_violations: vec![
+                violation("packs/bar/b.rb", 2, "privacy"),
+                violation("packs/bar/a.rb", 4, "dependency"),
+            ]
+            .into_iter()
+            .collect(),
+            stale_violations: Vec::new(),
+            strict_mode_violations: Vec::new(),
+            multiplied_violations: Vec::new(),
+            dependency_suggestions: Vec::new(),
+            unparseable_files: vec![UnparseableFile {
+                file: String::from("packs/bar/c.rb"),
+                errors: vec![SyntaxError {
+                    message: String::from("unexpected end-of-input"),
+                    line: 7,
+                    column: 1,
+                }],
+            }],
+            files_analyzed: 5,
+            recorded_violations: 0,
+            elapsed: Duration::ZERO,
+            unresolved: Default::default(),
+            unanalyzed_files: Vec::new(),
+            sample: None,
+        };
+
+        let location = |line: usize, column: usize| {
+            json!({
+                "start_line": line,
+                "start_column": column,
+                "last_line": line,
+                "last_column": column,
+                "length": 0,
+                "line": line,
+                "column": column,
+            })
+        };
+        assert_eq!(
+            serde_json::to_value(RubocopResult::new(&result)).unwrap(),
+            json!({
+                "metadata": { "pks_version": env!("CARGO_PKG_VERSION") },
+                "files": [
+                    {
+                        "path": "packs/bar/a.rb",
+                        "offenses": [{
+                            "severity": "error",
+                            "message": "Privacy violation: `::Foo` is private",
+                            "cop_name": "dependency",
+                            "corrected": false,
+                            "correctable": false,
+                            "location": location(4, 3),
+                        }],
+                    },
+                    {
+                        "path": "packs/bar/b.rb",
+                        "offenses": [{
+                            "severity": "error",
+                            "message": "Privacy violation: `::Foo` is private",
+                            "cop_name": "privacy",
+                            "corrected": false,
+                            "correctable": false,
+                            "location": location(2, 3),
+                        }],
+                    },
+                    {
+                        "path": "packs/bar/c.rb",
+                        "offenses": [{
+                            "severity": "fatal",
+                            "message": "unexpected end-of-input",
+                            "cop_name": "Lint/Syntax",
+                            "corrected": false,
+                            "correctable": false,
+                            "location": location(7, 1),
+                        }],
+                    },
+                ],
+                "summary": {
+                    "offense_count": 3,
+                    "target_file_count": 5,
+                    "inspected_file_count": 5,
+                },
+            })
+        );
+    }
+}
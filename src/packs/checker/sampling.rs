@@ -0,0 +1,136 @@
+// Deterministic samples of files for `check --sample`, to smoke-test
+// configuration changes on huge codebases. A file is in the sample when the
+// hash of its path and the seed falls below the rate, so the same seed picks
+// the same files on every run and machine, and mostly the same files as the
+// codebase changes.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    // The share of files checked, in (0, 1]
+    pub rate: f64,
+    pub seed: u64,
+}
+
+impl Sample {
+    pub(crate) fn new(rate: f64, seed: u64) -> anyhow::Result<Self> {
+        if rate.is_nan() || rate <= 0.0 || rate > 1.0 {
+            bail!(
+                "The sample rate must be greater than 0 and at most 1, e.g. 0.1, but is {}",
+                rate
+            );
+        }
+        Ok(Self { rate, seed })
+    }
+
+    fn includes(&self, relative_path: &Path) -> bool {
+        let digest =
+            md5::compute(format!("{}\n{}", self.seed, relative_path.display()));
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        (u64::from_be_bytes(bytes) as f64 / u64::MAX as f64) < self.rate
+    }
+
+    pub(crate) fn sample_files(
+        &self,
+        absolute_root: &Path,
+        absolute_paths: HashSet<PathBuf>,
+    ) -> HashSet<PathBuf> {
+        absolute_paths
+            .into_iter()
+            .filter(|absolute_path| {
+                self.includes(
+                    absolute_path
+                        .strip_prefix(absolute_root)
+                        .unwrap_or(absolute_path),
+                )
+            })
+            .collect()
+    }
+}
+
+// How the sampled files stand for all of them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct SampleEstimate {
+    pub rate: f64,
+    pub seed: u64,
+    pub files_sampled: usize,
+    pub files_total: usize,
+    // The new violations found, scaled by the share of files sampled
+    pub estimated_new_violations: usize,
+}
+
+impl SampleEstimate {
+    pub(crate) fn new(
+        sample: &Sample,
+        files_sampled: usize,
+        files_total: usize,
+        new_violations: usize,
+    ) -> Self {
+        let estimated_new_violations = if files_sampled == 0 {
+            0
+        } else {
+            (new_violations as f64 * files_total as f64 / files_sampled as f64)
+                .round() as usize
+        };
+        Self {
+            rate: sample.rate,
+            seed: sample.seed,
+            files_sampled,
+            files_total,
+            estimated_new_violations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn paths(count: usize) -> HashSet<PathBuf> {
+        (0..count)
+            .map(|i| PathBuf::from(format!("/app/packs/foo/app/file_{}.rb", i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_sample_files() {
+        let root = Path::new("/app");
+        let sample = Sample::new(0.1, 42).unwrap();
+
+        let sampled = sample.sample_files(root, paths(1000));
+        assert_eq!(sampled, sample.sample_files(root, paths(1000)));
+        assert!((50..150).contains(&sampled.len()), "{}", sampled.len());
+
+        let other_seed = Sample::new(0.1, 7).unwrap();
+        assert_ne!(sampled, other_seed.sample_files(root, paths(1000)));
+
+        let everything = Sample::new(1.0, 42).unwrap();
+        assert_eq!(1000, everything.sample_files(root, paths(1000)).len());
+    }
+
+    #[test]
+    fn test_invalid_rate() {
+        assert!(Sample::new(0.0, 0).is_err());
+        assert!(Sample::new(1.5, 0).is_err());
+        assert!(Sample::new(f64::NAN, 0).is_err());
+    }
+
+    #[test]
+    fn test_estimate() {
+        let sample = Sample::new(0.1, 42).unwrap();
+        assert_eq!(
+            30,
+            SampleEstimate::new(&sample, 100, 1000, 3).estimated_new_violations
+        );
+        assert_eq!(
+            0,
+            SampleEstimate::new(&sample, 0, 10, 0).estimated_new_violations
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use super::{write_footer, CheckAllResult};
+
+// What `check --stream` prints once the run is over: everything but the
+// reportable violations, which were printed as they were found
+pub(crate) struct StreamedCheckAllResult<'a> {
+    pub result: &'a CheckAllResult,
+}
+
+impl Display for StreamedCheckAllResult<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let result = self.result;
+        if result.has_violations() {
+            result.write_dependency_suggestions(f)?;
+            result.write_stale_and_strict_violations(f)?;
+        }
+        write_footer(f, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::checker::{
+        StrictModeViolation, Violation, ViolationIdentifier,
+    };
+    use crate::packs::SourceLocation;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn identifier() -> ViolationIdentifier {
+        ViolationIdentifier {
+            violation_type: String::from("privacy"),
+            strict: true,
+            file: "packs/bar/a.rb".into(),
+            constant_name: "::Foo".into(),
+            referencing_pack_name: "packs/bar".into(),
+            defining_pack_name: "packs/foo".into(),
+            defining_file: None,
+        }
+    }
+
+    #[test]
+    fn test_streamed_check_all_result() {
+        let result = CheckAllResult {
+            reportable_violations: vec![Violation {
+                message: String::from("packs/bar/a.rb:1:1\nPrivacy violation"),
+                identifier: identifier(),
+                source_location: SourceLocation::default(),
+                snippet: None,
+            }]
+            .into_iter()
+            .collect(),
+            stale_violations: Vec::new(),
+            strict_mode_violations: vec![StrictModeViolation {
+                identifier: identifier(),
+                message: String::from("packs/bar/a.rb:1:1\nStrict violation"),
+            }],
+            multiplied_violations: Vec::new(),
+            dependency_suggestions: Vec::new(),
+            unparseable_files: Vec::new(),
+            files_analyzed: 1,
+            recorded_violations: 0,
+            elapsed: Duration::ZERO,
+            unresolved: Default::default(),
+            unanalyzed_files: Vec::new(),
+            sample: None,
+        };
+
+        assert_eq!(
+            StreamedCheckAllResult { result: &result }.to_string(),
+            "\
+packs/bar/a.rb:1:1
+Strict violation
+Summary:
+  Files analyzed: 1
+  New violations: 1 (privacy: 1)
+  Recorded violations: 0
+  Stale violations: 0
+  Elapsed time: 0.00s"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use super::pack_checker::ViolationType;
+use super::{waiver, ViolationIdentifier};
+use crate::packs::Configuration;
+
+// With `strict_mode_grace_period_days:` in packwerk.yml, or in the package.yml
+// of the pack enforcing a checker, a strict violation recorded in the
+// package_todo.yml of its referencing pack within that many days is treated
+// as a recorded violation rather than failing `check`. This way packs can be
+// made strict before their recorded violations are resolved. `update` dates
+// recorded violations when a grace period is configured.
+pub(crate) fn is_within_grace_period(
+    configuration: &Configuration,
+    identifier: &ViolationIdentifier,
+    today: i64,
+) -> bool {
+    if !identifier.strict {
+        return false;
+    }
+    let pack_set = &configuration.pack_set;
+    let violation_type =
+        ViolationType::from(identifier.violation_type.as_str());
+    let enforcing_pack_name =
+        if violation_type.is_enforced_by_referencing_pack() {
+            &identifier.referencing_pack_name
+        } else {
+            &identifier.defining_pack_name
+        };
+    let Some(days) = pack_set
+        .for_pack(enforcing_pack_name)
+        .ok()
+        .and_then(|pack| pack.strict_mode_grace_period_days)
+        .or(configuration.strict_mode_grace_period_days)
+    else {
+        return false;
+    };
+    let Ok(referencing_pack) =
+        pack_set.for_pack(&identifier.referencing_pack_name)
+    else {
+        return false;
+    };
+    // Dates are compared as strings, since they're formatted as `YYYY-MM-DD`
+    referencing_pack
+        .recorded_on(
+            &identifier.violation_type,
+            &identifier.defining_pack_name,
+            &identifier.constant_name,
+            &identifier.file,
+        )
+        .is_some_and(|recorded_on| {
+            recorded_on > waiver::date_from_days(today - days as i64).as_str()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+
+    use super::*;
+    use crate::packs::pack::Pack;
+    use crate::packs::package_todo::{PackageTodo, ViolationGroup};
+    use crate::packs::PackSet;
+
+    // 2024-03-31
+    const TODAY: i64 = 19_813;
+
+    fn configuration(recorded_on: &str) -> Configuration {
+        let package_todo = PackageTodo {
+            violations_by_defining_pack: BTreeMap::from([(
+                String::from("packs/bar"),
+                BTreeMap::from([(
+                    String::from("::Bar"),
+                    ViolationGroup {
+                        violation_types: HashSet::from([String::from(
+                            "dependency",
+                        )]),
+                        files: HashSet::from([String::from(
+                            "packs/foo/foo.rb",
+                        )]),
+                        recorded_on: BTreeMap::from([(
+                            String::from("packs/foo/foo.rb"),
+                            recorded_on.to_owned(),
+                        )]),
+                        ..ViolationGroup::default()
+                    },
+                )]),
+            )]),
+        };
+        let packs = vec![
+            Pack {
+                name: String::from("."),
+                ..Pack::default()
+            },
+            Pack {
+                name: String::from("packs/foo"),
+                strict_mode_grace_period_days: Some(30),
+                package_todo,
+                ..Pack::default()
+            },
+            Pack {
+                name: String::from("packs/bar"),
+                ..Pack::default()
+            },
+        ];
+        Configuration {
+            pack_set: PackSet::build(
+                packs.into_iter().collect(),
+                Default::default(),
+            )
+            .unwrap(),
+            ..Configuration::default()
+        }
+    }
+
+    fn identifier(violation_type: &str) -> ViolationIdentifier {
+        ViolationIdentifier {
+            violation_type: violation_type.to_owned(),
+            strict: true,
+            file: "packs/foo/foo.rb".into(),
+            constant_name: "::Bar".into(),
+            referencing_pack_name: "packs/foo".into(),
+            defining_pack_name: "packs/bar".into(),
+            defining_file: None,
+        }
+    }
+
+    #[test]
+    fn test_is_within_grace_period() {
+        assert!(is_within_grace_period(
+            &configuration("2024-03-15"),
+            &identifier("dependency"),
+            TODAY
+        ));
+        assert!(!is_within_grace_period(
+            &configuration("2024-03-01"),
+            &identifier("dependency"),
+            TODAY
+        ));
+        // Privacy is enforced by packs/bar, which has no grace period
+        assert!(!is_within_grace_period(
+            &configuration("2024-03-15"),
+            &identifier("privacy"),
+            TODAY
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+use super::sampling::SampleEstimate;
+use super::CheckAllResult;
+
+// The shape of a check run, so that CI logs convey it at a glance
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct CheckSummary {
+    pub files_analyzed: usize,
+    // Set when only a sample of the files was checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample: Option<SampleEstimate>,
+    // Violations not recorded in package_todo.yml files
+    pub new_violations: usize,
+    // New violations by the type of the checker that found them
+    pub violations_by_checker: BTreeMap<String, usize>,
+    // Violations recorded in package_todo.yml files
+    pub recorded_violations: usize,
+    pub stale_violations: usize,
+    #[serde(rename = "elapsed_seconds", serialize_with = "serialize_seconds")]
+    pub elapsed: Duration,
+}
+
+fn serialize_seconds<S: Serializer>(
+    elapsed: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(elapsed.as_secs_f64())
+}
+
+impl CheckSummary {
+    pub(crate) fn new(result: &CheckAllResult) -> Self {
+        let mut violations_by_checker: BTreeMap<String, usize> =
+            BTreeMap::new();
+        for violation in &result.reportable_violations {
+            *violations_by_checker
+                .entry(violation.identifier.violation_type.clone())
+                .or_default() += 1;
+        }
+        Self {
+            files_analyzed: result.files_analyzed,
+            sample: result.sample.clone(),
+            new_violations: result.reportable_violations.len(),
+            violations_by_checker,
+            recorded_violations: result.recorded_violations,
+            stale_violations: result.stale_violations.len(),
+            elapsed: result.elapsed,
+        }
+    }
+}
+
+impl Display for CheckSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Summary:")?;
+        writeln!(f, "  Files analyzed: {}", self.files_analyzed)?;
+        if let Some(sample) = &self.sample {
+            writeln!(
+                f,
+                "  Sampled: {} of {} file(s) (rate {}, seed {})",
+                sample.files_sampled,
+                sample.files_total,
+                sample.rate,
+                sample.seed
+            )?;
+            writeln!(
+                f,
+                "  Estimated new violations in all files: {}",
+                sample.estimated_new_violations
+            )?;
+        }
+        if self.violations_by_checker.is_empty() {
+            writeln!(f, "  New violations: {}", self.new_violations)?;
+        } else {
+            writeln!(
+                f,
+                "  New violations: {} ({})",
+                self.new_violations,
+                self.violations_by_checker
+                    .iter()
+                    .map(|(checker, count)| format!("{}: {}", checker, count))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )?;
+        }
+        writeln!(f, "  Recorded violations: {}", self.recorded_violations)?;
+        writeln!(f, "  Stale violations: {}", self.stale_violations)?;
+        write!(f, "  Elapsed time: {:.2}s", self.elapsed.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::checker::{Violation, ViolationIdentifier};
+    use crate::packs::SourceLocation;
+    use pretty_assertions::assert_eq;
+
+    fn identifier(violation_type: &str, file: &str) -> ViolationIdentifier {
+        ViolationIdentifier {
+            violation_type: violation_type.to_string(),
+            strict: false,
+            file: file.into(),
+            constant_name: "::Foo".into(),
+            referencing_pack_name: "packs/bar".into(),
+            defining_pack_name: "packs/foo".into(),
+            defining_file: None,
+        }
+    }
+
+    fn violation(violation_type: &str, file: &str) -> Violation {
+        Violation {
+            message: format!("{}: {} violation", file, violation_type),
+            identifier: identifier(violation_type, file),
+            source_location: SourceLocation::default(),
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let result = CheckAllResult {
+            reportable_violations: vec![
+                violation("privacy", "packs/bar/a.rb"),
+                violation("privacy", "packs/bar/b.rb"),
+                violation("dependency", "packs/bar/a.rb"),
+            ]
+            .into_iter()
+            .collect(),
+            stale_violations: vec![identifier("privacy", "packs/bar/c.rb")],
+            strict_mode_violations: Vec::new(),
+            multiplied_violations: Vec::new(),
+            dependency_suggestions: Vec::new(),
+            unparseable_files: Vec::new(),
+            files_analyzed: 12,
+            recorded_violations: 4,
+            elapsed: Duration::from_millis(1250),
+            unresolved: Default::default(),
+            unanalyzed_files: Vec::new(),
+            sample: None,
+        };
+
+        let summary = CheckSummary::new(&result);
+
+        assert_eq!(
+            summary.to_string(),
+            "\
+Summary:
+  Files analyzed: 12
+  New violations: 3 (dependency: 1, privacy: 2)
+  Recorded violations: 4
+  Stale violations: 1
+  Elapsed time: 1.25s"
+        );
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "files_analyzed": 12,
+                "new_violations": 3,
+                "violations_by_checker": {"dependency": 1, "privacy": 2},
+                "recorded_violations": 4,
+                "stale_violations": 1,
+                "elapsed_seconds": 1.25,
+            })
+        );
+    }
+}
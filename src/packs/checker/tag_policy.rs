@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use super::output_helper::print_reference_location;
+use super::pack_checker::PackChecker;
+use super::{CheckerInterface, ValidatorInterface};
+use crate::packs::checker::Reference;
+use crate::packs::pack::Pack;
+use crate::packs::raw_configuration::TagPolicy;
+use crate::packs::{Configuration, Violation};
+
+const VIOLATION_TYPE: &str = "tag_policy";
+
+// Like layers, but between any tags of packs: each policy in `tag_policies`
+// forbids packs with one tag from depending on packs with another, e.g.
+// packs tagged `pci` on packs tagged `experimental`. References are checked,
+// and so are the dependencies declared in package.yml files.
+pub struct Checker {}
+
+// The first policy forbidding `referencing_pack` from depending on
+// `defining_pack`, if any
+fn broken_policy<'a>(
+    configuration: &'a Configuration,
+    referencing_pack: &Pack,
+    defining_pack: &Pack,
+) -> Option<&'a TagPolicy> {
+    configuration.tag_policies.iter().find(|policy| {
+        referencing_pack.tags.contains(&policy.from)
+            && defining_pack.tags.contains(&policy.cannot_depend_on)
+    })
+}
+
+fn describe(policy: &TagPolicy) -> String {
+    let mut description = format!(
+        "packs tagged `{}` cannot depend on packs tagged `{}`",
+        policy.from, policy.cannot_depend_on
+    );
+    if let Some(reason) = &policy.reason {
+        description.push_str(&format!(" ({})", reason));
+    }
+    description
+}
+
+impl ValidatorInterface for Checker {
+    fn validate(&self, configuration: &Configuration) -> Option<Vec<String>> {
+        let mut error_messages: Vec<String> = vec![];
+
+        for policy in &configuration.tag_policies {
+            if policy.from.trim().is_empty()
+                || policy.cannot_depend_on.trim().is_empty()
+            {
+                error_messages.push(String::from(
+                    "`tag_policies` in `packwerk.yml` must specify both `from` and `cannot_depend_on` tags",
+                ));
+            }
+        }
+
+        for pack in &configuration.pack_set.packs {
+            let mut dependencies: Vec<&String> =
+                pack.dependencies.iter().collect();
+            dependencies.sort();
+            for dependency in dependencies {
+                let Ok(dependency_pack) =
+                    configuration.pack_set.for_pack(dependency)
+                else {
+                    continue;
+                };
+                if let Some(policy) =
+                    broken_policy(configuration, pack, dependency_pack)
+                {
+                    error_messages.push(format!(
+                        "`{}` depends on `{}`, but {}",
+                        pack.relative_yml().display(),
+                        dependency,
+                        describe(policy)
+                    ));
+                }
+            }
+        }
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages)
+        }
+    }
+}
+
+impl CheckerInterface for Checker {
+    fn check(
+        &self,
+        reference: &Reference,
+        configuration: &Configuration,
+        _sigils: &HashMap<std::path::PathBuf, Vec<crate::packs::Sigil>>,
+    ) -> anyhow::Result<Option<Violation>> {
+        if configuration.tag_policies.is_empty() {
+            return Ok(None);
+        }
+        let pack_checker =
+            PackChecker::new(configuration, reference, &self.violation_type())?;
+        if !pack_checker.checkable()? {
+            return Ok(None);
+        }
+        let defining_pack = pack_checker.defining_pack.unwrap();
+        let Some(policy) = broken_policy(
+            configuration,
+            pack_checker.referencing_pack,
+            defining_pack,
+        ) else {
+            return Ok(None);
+        };
+
+        let message = format!(
+            "{}Tag policy violation: `{}` belongs to `{}`, which `{}` cannot reference because {}",
+            print_reference_location(reference),
+            reference.constant_name,
+            defining_pack.name,
+            pack_checker.referencing_pack.name,
+            describe(policy),
+        );
+        Ok(Some(Violation {
+            message,
+            identifier: pack_checker.violation_identifier(),
+            source_location: reference.source_location.clone(),
+            snippet: None,
+        }))
+    }
+
+    fn violation_type(&self) -> String {
+        VIOLATION_TYPE.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pack(name: &str, tags: &[&str]) -> Pack {
+        Pack {
+            name: String::from(name),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Pack::default()
+        }
+    }
+
+    #[test]
+    fn test_broken_policy() {
+        let configuration = Configuration {
+            tag_policies: vec![TagPolicy {
+                from: String::from("pci"),
+                cannot_depend_on: String::from("experimental"),
+                reason: Some(String::from("PCI code must be audited")),
+            }],
+            ..Configuration::default()
+        };
+        let payments = pack("packs/payments", &["pci", "core"]);
+        let labs = pack("packs/labs", &["experimental"]);
+
+        let policy = broken_policy(&configuration, &payments, &labs).unwrap();
+        assert_eq!(
+            describe(policy),
+            "packs tagged `pci` cannot depend on packs tagged `experimental` (PCI code must be audited)"
+        );
+        assert!(broken_policy(&configuration, &labs, &payments).is_none());
+        assert!(broken_policy(&configuration, &payments, &payments).is_none());
+    }
+}
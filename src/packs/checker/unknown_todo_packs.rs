@@ -0,0 +1,79 @@
+use super::ValidatorInterface;
+use crate::packs::bin_locater;
+use crate::packs::Configuration;
+
+// Finds package_todo.yml entries on defining packs that no longer exist, e.g.
+// after a pack is renamed or deleted. The referencing pack of each entry is
+// the pack the package_todo.yml file belongs to, so it always exists.
+pub struct Validator {}
+
+impl ValidatorInterface for Validator {
+    fn validate(&self, configuration: &Configuration) -> Option<Vec<String>> {
+        let mut error_messages: Vec<String> = Vec::new();
+        for pack in &configuration.pack_set.packs {
+            for (defining_pack_name, violations_by_constant) in
+                &pack.package_todo.violations_by_defining_pack
+            {
+                if configuration.pack_set.for_pack(defining_pack_name).is_ok() {
+                    continue;
+                }
+                let constant_names: Vec<&str> = violations_by_constant
+                    .keys()
+                    .map(|constant_name| constant_name.as_str())
+                    .collect();
+                error_messages.push(format!(
+                    "{} records violations on `{}`, which does not exist: {}. Run `{} validate --fix` to remove them.",
+                    pack.package_todo_yml
+                        .strip_prefix(&configuration.absolute_root)
+                        .unwrap_or(&pack.package_todo_yml)
+                        .display(),
+                    defining_pack_name,
+                    constant_names.join(", "),
+                    bin_locater::packs_bin_name(),
+                ));
+            }
+        }
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::packs::{configuration, PackSet};
+
+    #[test]
+    fn test_validate_with_unknown_defining_pack() {
+        let mut configuration = configuration::get(
+            PathBuf::from("tests/fixtures/contains_package_todo")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+        assert_eq!(None, Validator {}.validate(&configuration));
+
+        let packs = configuration
+            .pack_set
+            .packs
+            .iter()
+            .filter(|pack| pack.name != "packs/bar")
+            .cloned()
+            .collect();
+        configuration.pack_set = PackSet::build(packs, HashMap::new()).unwrap();
+        let error_messages = Validator {}.validate(&configuration).unwrap();
+        assert_eq!(1, error_messages.len());
+        assert!(error_messages[0].starts_with(
+            "packs/foo/package_todo.yml records violations on `packs/bar`, which does not exist: ::Bar."
+        ));
+    }
+}
@@ -24,12 +24,10 @@ impl CheckerInterface for Checker {
             return Ok(None);
         }
         let defining_pack = pack_checker.defining_pack.unwrap();
-        if defining_pack
-            .visible_to
-            .as_ref()
-            .unwrap_or(&HashSet::new())
-            .contains(&pack_checker.referencing_pack.name)
-        {
+        if configuration.is_visible_to(
+            defining_pack.visible_to.as_ref().unwrap_or(&HashSet::new()),
+            &pack_checker.referencing_pack.name,
+        ) {
             return Ok(None);
         }
 
@@ -46,6 +44,8 @@ impl CheckerInterface for Checker {
         Ok(Some(Violation {
             message,
             identifier: pack_checker.violation_identifier(),
+            source_location: reference.source_location.clone(),
+            snippet: None,
         }))
     }
 
@@ -56,7 +56,7 @@ impl CheckerInterface for Checker {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{BTreeMap, HashSet};
 
     use self::packs::{
         checker::common_test::tests::{
@@ -69,7 +69,7 @@ mod tests {
     use super::*;
     use crate::packs::{
         pack::{CheckerSetting, Pack},
-        *,
+        PackSet, *,
     };
 
     #[test]
@@ -189,4 +189,46 @@ mod tests {
         };
         test_check(&Checker {}, &mut test_checker)
     }
+
+    #[test]
+    fn reference_from_a_visibility_group() -> anyhow::Result<()> {
+        let defining_pack = Pack {
+            name: "packs/bar".to_owned(),
+            enforce_visibility: Some(CheckerSetting::True),
+            visible_to: Some(HashSet::from([String::from("payments_team")])),
+            ..default_defining_pack()
+        };
+        let referencing_pack = Pack {
+            relative_path: PathBuf::from("packs/foo"),
+            ..default_referencing_pack()
+        };
+        let configuration = Configuration {
+            pack_set: PackSet::build(
+                HashSet::from([
+                    Pack {
+                        name: String::from("."),
+                        ..Pack::default()
+                    },
+                    referencing_pack.clone(),
+                    defining_pack.clone(),
+                ]),
+                HashMap::new(),
+            )?,
+            visibility_groups: BTreeMap::from([(
+                String::from("payments_team"),
+                vec![glob::Pattern::new("packs/f*").unwrap()],
+            )]),
+            ..Configuration::default()
+        };
+
+        let mut test_checker = TestChecker {
+            reference: None,
+            configuration: Some(configuration),
+            referenced_constant_name: Some(String::from("::Bar")),
+            defining_pack: Some(defining_pack),
+            referencing_pack,
+            ..Default::default()
+        };
+        test_check(&Checker {}, &mut test_checker)
+    }
 }
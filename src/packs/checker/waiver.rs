@@ -0,0 +1,146 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{ValidatorInterface, ViolationIdentifier};
+use crate::packs::Configuration;
+
+// Waivers in a package.yml suppress violations of the pack's constants by a
+// referencing pack until they expire, after which `check` reports the
+// violations again. Unlike package_todo.yml entries, each waiver states why
+// it exists and until when.
+pub struct Validator {}
+
+impl ValidatorInterface for Validator {
+    fn validate(&self, configuration: &Configuration) -> Option<Vec<String>> {
+        let mut error_messages: Vec<String> = Vec::new();
+        for pack in &configuration.pack_set.packs {
+            for waiver in &pack.waivers {
+                let description = format!(
+                    "`waivers` in `{}` for `{}` referenced from `{}`",
+                    pack.relative_yml().display(),
+                    waiver.constant,
+                    waiver.referencing_pack
+                );
+                if configuration
+                    .pack_set
+                    .for_pack(&waiver.referencing_pack)
+                    .is_err()
+                {
+                    error_messages.push(format!(
+                        "{} references `{}`, but that pack cannot be found",
+                        description, waiver.referencing_pack
+                    ));
+                }
+                if waiver.reason.trim().is_empty() {
+                    error_messages.push(format!(
+                        "{} must specify a `reason`",
+                        description
+                    ));
+                }
+                if !is_date(&waiver.expires_on) {
+                    error_messages.push(format!(
+                        "{} must specify `expires_on` as a date like 2024-03-31, not `{}`",
+                        description, waiver.expires_on
+                    ));
+                }
+            }
+        }
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages)
+        }
+    }
+}
+
+// Whether the defining pack of a violation waives it on `today`
+pub(crate) fn is_waived(
+    configuration: &Configuration,
+    identifier: &ViolationIdentifier,
+    today: &str,
+) -> bool {
+    configuration
+        .pack_set
+        .for_pack(&identifier.defining_pack_name)
+        .is_ok_and(|pack| {
+            pack.is_waived(
+                &identifier.constant_name,
+                &identifier.referencing_pack_name,
+                today,
+            )
+        })
+}
+
+// The current UTC date, e.g. `2024-03-31`
+pub(crate) fn today() -> String {
+    date_from_days(days_since_epoch())
+}
+
+// The days from 1970-01-01 to the current UTC date
+pub(crate) fn days_since_epoch() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    (seconds / 86_400) as i64
+}
+
+// The date a number of days after 1970-01-01, see
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub(crate) fn date_from_days(days: i64) -> String {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Dates are compared as strings, so they must be formatted as `YYYY-MM-DD`
+fn is_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = parts[..] else {
+        return false;
+    };
+    let is_number = |part: &str, length: usize| {
+        part.len() == length && part.chars().all(|c| c.is_ascii_digit())
+    };
+    is_number(year, 4)
+        && is_number(month, 2)
+        && is_number(day, 2)
+        && (1..=12).contains(&month.parse::<u32>().unwrap_or(0))
+        && (1..=31).contains(&day.parse::<u32>().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_date_from_days() {
+        assert_eq!("1970-01-01", date_from_days(0));
+        assert_eq!("2000-02-29", date_from_days(11_016));
+        assert_eq!("2024-03-31", date_from_days(19_813));
+        assert_eq!("1969-12-31", date_from_days(-1));
+    }
+
+    #[test]
+    fn test_is_date() {
+        assert!(is_date("2024-03-31"));
+        assert!(!is_date("2024-3-31"));
+        assert!(!is_date("2024-13-01"));
+        assert!(!is_date("March 31, 2024"));
+    }
+}
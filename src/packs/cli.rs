@@ -1,13 +1,27 @@
 use crate::packs;
 
-use crate::packs::file_utils::get_absolute_path;
-use clap::{Parser, Subcommand};
+use crate::packs::checker::sampling::Sample;
+use crate::packs::checker::TimedOut;
+use crate::packs::configuration::Configuration;
+use crate::packs::file_utils::{get_absolute_path, read_file_list};
+use crate::packs::pack_info::PackFilter;
+use crate::packs::OutputSink;
+use anyhow::Context;
+use clap::{Parser, Subcommand, ValueEnum};
 use clap_derive::Args;
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 use tracing::debug;
 
 use super::logger::install_logger;
 
+// The revision that changed packs are compared to by default
+const DEFAULT_BASE: &str = "origin/main";
+
 /// A CLI to interact with packs
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -19,6 +33,11 @@ struct Args {
     #[arg(long, default_value = ".")]
     project_root: PathBuf,
 
+    /// Apply the overrides of this profile under `profiles:` in packwerk.yml,
+    /// e.g. `ci`. Other options take precedence over the profile
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
     /// Run with performance debug mode
     #[arg(short, long)]
     debug: bool,
@@ -54,6 +73,39 @@ struct Args {
     /// Globally disable enforce_visibility
     #[arg(long)]
     disable_enforce_visibility: bool,
+
+    /// Do not report progress while running `check` and `update`
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Number of threads to use, which defaults to the number of CPUs
+    #[arg(long, value_name = "N")]
+    threads: Option<usize>,
+
+    /// Approximate memory budget for parsing files, e.g. `2G`. Files are
+    /// parsed in batches estimated to fit within it
+    #[arg(long, value_name = "SIZE")]
+    max_memory: Option<String>,
+
+    /// Memory-map files of at least this size, e.g. `1M`, when reading them
+    /// for parsing. Speeds up repos with very large (e.g. generated) files
+    #[arg(long, value_name = "SIZE")]
+    mmap_threshold: Option<String>,
+
+    /// Include this many lines of code before and after each violation in
+    /// JSON output and reports, overriding `snippet_lines` in packwerk.yml
+    #[arg(long, value_name = "N")]
+    snippet_lines: Option<usize>,
+
+    /// How to report progress to stderr while running `check` and `update`.
+    /// Human progress is only shown when stderr is a terminal
+    #[arg(long, value_enum, default_value_t = ProgressFormat::Human)]
+    progress: ProgressFormat,
+
+    /// How to write logs to stderr. JSON logs also include the duration of
+    /// each stage, e.g. parsing, with its file counts and cache hit rate
+    #[arg(long, value_enum, default_value_t = LogFormat::Human)]
+    log_format: LogFormat,
 }
 
 #[derive(Subcommand, Debug)]
@@ -77,25 +129,182 @@ enum Command {
         #[arg(long)]
         ignore_recorded_violations: bool,
 
+        /// Aggregate reported violations instead of listing each one
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+
+        /// Also check the newline-delimited list of files piped to stdin
+        #[arg(long)]
+        stdin_filelist: bool,
+
+        /// Add the dependencies needed to resolve dependency violations to package.yml files, unless they would create a cycle
+        #[arg(long)]
+        fix_dependencies: bool,
+
+        /// Explain how each reported violation was found: how its constant was resolved, which autoload paths were considered, which packs own the files involved, and which checkers reported it
+        #[arg(long)]
+        explain: bool,
+
+        /// Only explain violations on this constant, e.g. `::Foo::Bar`
+        #[arg(long, requires = "explain")]
+        constant: Option<String>,
+
+        /// How to print violations. `quickfix` prints `file:line:col: message` lines for editors,
+        /// `json` prints an object with the violations and a summary of the run, and `rubocop`
+        /// prints them in RuboCop's JSON format
+        #[arg(long, value_enum, conflicts_with_all = ["group_by", "explain"])]
+        output_format: Option<CheckOutputFormat>,
+
+        /// Post new violations to the webhooks under `notifications:` in packwerk.yml
+        #[arg(long)]
+        notify: bool,
+
+        /// Fail if any file could not be parsed, instead of only warning
+        #[arg(long)]
+        strict_parsing: bool,
+
+        /// Also write violations in another format, to a file with
+        /// `FORMAT=PATH` (e.g. `json=artifacts/violations.json`) or to stdout
+        /// with `FORMAT`. Can be repeated
+        #[arg(long = "out", value_name = "FORMAT=PATH")]
+        outputs: Vec<OutputSink>,
+
+        /// Print violations as soon as the file they're in is checked, rather
+        /// than once all files are
+        #[arg(long, conflicts_with_all = ["group_by", "output_format", "fix_dependencies"])]
+        stream: bool,
+
+        /// Stop parsing files after SECS seconds, and report the violations in
+        /// the files parsed by then. The files left unanalyzed are listed,
+        /// and the exit code is 3
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+
+        /// Only check the files of the packs changed in git since the merge
+        /// base of BASE, which defaults to origin/main
+        #[arg(long, value_name = "BASE", num_args = 0..=1, default_missing_value = DEFAULT_BASE, conflicts_with = "stdin_filelist")]
+        changed_packs: Option<String>,
+
+        /// Only check the files changed in git since the merge base of REV,
+        /// and the files referencing constants whose definitions changed,
+        /// e.g. constants that moved or were made private
+        #[arg(long, value_name = "REV", conflicts_with_all = ["stdin_filelist", "changed_packs"])]
+        since: Option<String>,
+
+        /// Like --since, with the merge base of the default branch, which is
+        /// detected on GitHub and GitLab CI, and fetched in shallow clones
+        #[arg(long, conflicts_with_all = ["stdin_filelist", "changed_packs", "since"])]
+        changed: bool,
+
+        /// Only run these checkers, e.g. `privacy,dependency`. Recorded
+        /// violations of the other checkers aren't reported as stale
+        #[arg(long = "checker", value_name = "CHECKER", value_delimiter = ',')]
+        checkers: Vec<String>,
+
+        /// Only check a deterministic sample of RATE of the files, e.g. 0.1,
+        /// and estimate the new violations in all of them
+        #[arg(long, value_name = "RATE")]
+        sample: Option<f64>,
+
+        /// Pick another sample of files with `--sample`
+        #[arg(long, value_name = "N", requires = "sample")]
+        seed: Option<u64>,
+
+        files: Vec<String>,
+    },
+
+    #[clap(
+        about = "Print files with `# pks:` comments on lines that contain violations"
+    )]
+    Annotate {
+        /// Ignore recorded violations when annotating violations
+        #[arg(long)]
+        ignore_recorded_violations: bool,
+
+        /// Print a unified diff that adds the comments instead
+        #[arg(long)]
+        diff: bool,
+
         files: Vec<String>,
     },
 
+    #[clap(
+        about = "Check several projects, e.g. the apps of a meta-repo, in one process"
+    )]
+    CheckBatch {
+        /// Cache processed files of all projects in this directory, instead
+        /// of in the cache directory of each project
+        #[arg(long)]
+        shared_cache_directory: Option<PathBuf>,
+
+        /// The roots of the projects to check, relative to --project-root
+        #[arg(required = true)]
+        project_roots: Vec<PathBuf>,
+    },
+
     #[clap(about = "Check file contents piped to stdin")]
     CheckContents {
         /// Ignore recorded violations when reporting violations
         #[arg(long)]
         ignore_recorded_violations: bool,
 
+        /// How to print violations. `quickfix` prints `file:line:col: message` lines for editors,
+        /// `json` prints an object with the violations and a summary of the run
+        #[arg(long, value_enum, default_value_t = CheckOutputFormat::Human)]
+        output_format: CheckOutputFormat,
+
         file: String,
     },
 
     #[clap(
         about = "Update package_todo.yml files with the current violations"
     )]
-    Update,
+    Update {
+        /// Only regenerate the package_todo.yml files of these packs (names
+        /// or globs, e.g. `packs/billing/*`), from the references originating
+        /// in them. Other package_todo.yml files are left untouched
+        #[arg(long, value_name = "PACK")]
+        partial: Vec<String>,
+
+        /// Keep the order of the entries already in package_todo.yml files,
+        /// only inserting and removing the entries that changed, and leave
+        /// files whose entries are unchanged untouched
+        #[arg(long)]
+        minimal_diff: bool,
+
+        /// Write the violations found in a format, to a file with
+        /// `FORMAT=PATH` (e.g. `json=artifacts/violations.json`) or to stdout
+        /// with `FORMAT`. Can be repeated
+        #[arg(long = "out", value_name = "FORMAT=PATH")]
+        outputs: Vec<OutputSink>,
+
+        /// Write the recorded violations added and removed, as JSON, to PATH
+        #[arg(long, value_name = "PATH")]
+        diff_json: Option<PathBuf>,
+    },
+
+    #[clap(
+        about = "Remove recorded violations in files that no longer exist from package_todo.yml files, without checking for new violations"
+    )]
+    PruneTodos,
+
+    #[clap(
+        about = "Compare violations reported by packwerk and packs on a sample of files"
+    )]
+    Parity {
+        /// The maximum number of files to compare
+        #[arg(long, default_value_t = 100)]
+        sample: usize,
+
+        files: Vec<String>,
+    },
 
     #[clap(about = "Look for validation errors in the codebase")]
-    Validate,
+    Validate {
+        /// Remove recorded violations on packs that no longer exist from package_todo.yml files
+        #[arg(long)]
+        fix: bool,
+    },
 
     #[clap(about = "Add a dependency from one pack to another")]
     AddDependency {
@@ -106,6 +315,89 @@ enum Command {
         to: String,
     },
 
+    #[clap(
+        about = "Enable an enforcement in the packs matching a glob, and record the violations it results in"
+    )]
+    EnableEnforcement {
+        /// The package.yml key of the enforcement, e.g. `enforce_privacy`
+        enforcement: String,
+
+        /// The packs to enable it in (names or globs, e.g. `packs/billing/*`)
+        #[arg(required = true, value_name = "PACK")]
+        packs: Vec<String>,
+
+        /// Enable the enforcement in strict mode, in which violations cannot
+        /// be recorded
+        #[arg(long)]
+        strict: bool,
+    },
+
+    #[clap(
+        about = "Merge a pack into another, moving its files and rewriting everything that refers to it"
+    )]
+    Merge {
+        /// The pack that is merged and removed
+        absorbed: String,
+
+        /// The pack it is merged into
+        into: String,
+    },
+
+    #[clap(
+        about = "Move a pack to another directory, rewriting everything that refers to it"
+    )]
+    MvPack {
+        /// The pack to move, e.g. `packs/foo`
+        from: String,
+
+        /// Its new directory, relative to the project root, e.g.
+        /// `packs/customer/foo`
+        to: String,
+
+        /// Print what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    #[clap(
+        about = "List the references that make a pack depend on another, and extract the constants they use into a new pack"
+    )]
+    InvertDependency {
+        /// The pack that depends on the other
+        from: String,
+
+        /// The pack it depends on
+        to: String,
+
+        /// The pack to extract the constants into. Defaults to
+        /// `<to>_interface`
+        #[arg(long)]
+        new_pack: Option<String>,
+
+        /// Create the new pack, move the files defining the constants into
+        /// it, and update the dependencies of the packs that reference them
+        #[arg(long)]
+        apply: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "Propose public facades for the private constants that other packs reference"
+    )]
+    Autocorrect {
+        /// Write the facades into the public folders of the packs, skipping
+        /// any that already exist
+        #[arg(long)]
+        apply: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
     #[clap(
         about = "Add missing dependencies for the pack that defines the constant"
     )]
@@ -141,7 +433,191 @@ enum Command {
     #[clap(
         about = "List packs based on configuration in packwerk.yml (for debugging purposes)"
     )]
-    ListPacks,
+    ListPacks {
+        /// Only list packs with this enforcement enabled, e.g. `privacy`
+        #[arg(long, value_name = "ENFORCEMENT")]
+        enforcing: Option<String>,
+
+        /// Only list packs in this layer
+        #[arg(long)]
+        layer: Option<String>,
+
+        /// Only list packs with this owner
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Only list packs with violations recorded in their package_todo.yml
+        #[arg(long)]
+        has_todos: bool,
+
+        /// Output format. Human output lists the package.yml of each pack,
+        /// and JSON output the configuration of each pack, like `info`
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(about = "Show the effective configuration of a pack")]
+    Info {
+        /// The pack to show
+        pack: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "List the constants and public methods in a pack's public paths and public files"
+    )]
+    PublicApi {
+        /// The pack to list
+        pack: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "List the public constants of the pack defining a constant that reference it, e.g. to find a public entrypoint to use instead of a private constant"
+    )]
+    PublicEntrypoints {
+        /// The constant, e.g. `::Foo::Bar`
+        constant: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "Summarize the violations recorded in package_todo.yml files"
+    )]
+    Stats {
+        /// Roll up the stats of packs by the `product_groups` in packwerk.yml
+        #[arg(long)]
+        by_product_group: bool,
+
+        /// Only include the packs changed in git since the merge base of
+        /// BASE, which defaults to origin/main
+        #[arg(long, value_name = "BASE", num_args = 0..=1, default_missing_value = DEFAULT_BASE)]
+        changed_packs: Option<String>,
+
+        /// Append a snapshot of the stats of each pack to
+        /// .pks/stats-history.jsonl
+        #[arg(long)]
+        record: bool,
+
+        /// Compare against the latest snapshot, or the latest one taken on or
+        /// before DATE, e.g. 2024-03-31
+        #[arg(long, value_name = "DATE", num_args = 0..=1)]
+        diff: Option<Option<String>>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "Compute metrics of the pack graph, e.g. cycles and the most central packs"
+    )]
+    GraphMetrics {
+        /// Which edges between packs make up the graph
+        #[arg(long, value_enum, default_value_t = GraphEdges::Declared)]
+        edges: GraphEdges,
+
+        /// The number of most central packs to list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "Export every edge between packs, with its reference and violation counts"
+    )]
+    ExportEdges {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = EdgeFormat::Csv)]
+        format: EdgeFormat,
+    },
+
+    #[clap(
+        about = "Rank the packs and files where violations and frequent changes in git meet"
+    )]
+    Hotspots {
+        /// Only count the commits since this date, e.g. "6 months ago"
+        #[arg(long)]
+        since: Option<String>,
+
+        /// The number of packs and of files to list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "Suggest how a pack could be split, by clustering its files by the references between them"
+    )]
+    SuggestSplit {
+        /// The pack to split
+        pack: String,
+
+        /// The number of candidate packs to split it into
+        #[arg(long, default_value_t = 2)]
+        candidates: usize,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "Write a report of the violations in the codebase, e.g. to publish from CI"
+    )]
+    Report {
+        /// Output format. Defaults to html, or to markdown with --by-owner
+        #[arg(long, value_enum)]
+        format: Option<ReportFormat>,
+
+        /// The directory to write the report to
+        #[arg(long, default_value = "pks-report")]
+        output: PathBuf,
+
+        /// Write a digest per team of the violations of the packs it owns,
+        /// both new and recorded, to the `owners` directory of the output
+        #[arg(long)]
+        by_owner: bool,
+
+        /// Group each team's digest by the owner of the defining packs,
+        /// rather than by referencing pack
+        #[arg(long, requires = "by_owner")]
+        by_defining_owner: bool,
+
+        /// With --format markdown, print a summary of the violations added
+        /// and resolved since the merge base of BASE and HEAD, e.g. for a
+        /// pull request comment
+        #[arg(long, value_name = "BASE", conflicts_with = "by_owner")]
+        base: Option<String>,
+    },
+
+    #[clap(
+        about = "List the packs with files changed in git since a base revision"
+    )]
+    ChangedPacks {
+        /// Compare to the merge base of this revision and HEAD
+        #[arg(long, default_value = DEFAULT_BASE)]
+        base: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
 
     #[clap(about = "List packs that depend on a pack")]
     ListPackDependencies {
@@ -154,10 +630,154 @@ enum Command {
     )]
     ListIncludedFiles,
 
+    #[clap(about = "List the included files that could not be parsed")]
+    ParseErrors,
+
+    #[clap(
+        about = "List the references whose defining pack could not be determined, by constant"
+    )]
+    Unresolved {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "List the references to constants that aren't defined, although their namespace is, by pack"
+    )]
+    DeadReferences {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "Diagnose problems with the configuration, packs, and cache, and time a sample parse"
+    )]
+    Doctor {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    #[clap(
+        about = "Time each stage of `check` (walk, parse, resolve, check, todo_io) over several runs, with the cache cold and warm"
+    )]
+    Bench {
+        /// Number of timed runs for each cache state
+        #[arg(long, default_value = "5")]
+        iterations: NonZeroUsize,
+
+        /// Which cache states to time
+        #[arg(long, value_enum, default_value_t = BenchCache::Both)]
+        cache: BenchCache,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
     #[clap(
         about = "List the constants that packs sees and where it sees them (for debugging purposes)"
     )]
     ListDefinitions(ListDefinitionsArgs),
+
+    #[clap(
+        about = "Export the constants that packs sees and where they are defined, for use by other tools"
+    )]
+    ExportDefinitions {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckOutputFormat {
+    Human,
+    /// Vim/compiler-style `file:line:col: message` lines, sorted by file
+    Quickfix,
+    /// A JSON object with the violations and a summary of the run
+    Json,
+    /// RuboCop's JSON format, with an offense per violation whose cop is
+    /// the checker type
+    Rubocop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    Human,
+    /// One JSON object per line, for UIs that wrap pks
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum LogFormat {
+    Human,
+    /// One JSON object per line for each event and stage, for build systems
+    /// that track the performance of pks
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ExportFormat {
+    Json,
+    /// SQL statements that load the definitions into a SQLite database
+    Sql,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum EdgeFormat {
+    /// A row per edge, with a column per violation type
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ReportFormat {
+    /// A standalone HTML page per pack, and an index.html linking them
+    Html,
+    /// A Markdown digest per owner, with --by-owner, or a summary of the
+    /// changes since --base
+    Markdown,
+    /// A JSON digest per owner, with --by-owner
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum GraphEdges {
+    /// The dependencies in package.yml files
+    Declared,
+    /// The violations recorded in package_todo.yml files
+    Violations,
+    /// Both declared dependencies and recorded violations
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum BenchCache {
+    /// Clear the cache before each run
+    Cold,
+    /// Fill the cache with an untimed run first
+    Warm,
+    /// Time cold runs, then warm runs
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum GroupBy {
+    Constant,
+    PackPair,
+    File,
 }
 
 #[derive(Debug, Args)]
@@ -186,15 +806,97 @@ impl Args {
             .canonicalize()
             .map_err(anyhow::Error::from)
     }
+
+    // Applies the global options to a loaded configuration
+    fn apply_overrides(
+        &self,
+        configuration: &mut Configuration,
+    ) -> anyhow::Result<()> {
+        if let Some(profile) = &self.profile {
+            debug!("Using profile {}", profile);
+            configuration.apply_profile(profile)?;
+        }
+
+        if let Some(max_memory) = &self.max_memory {
+            configuration.max_memory = Some(
+                packs::configuration::parse_memory_size(max_memory).context(
+                    format!("Invalid --max-memory `{}`", max_memory),
+                )?,
+            );
+        }
+
+        if let Some(mmap_threshold) = &self.mmap_threshold {
+            configuration.mmap_threshold = Some(
+                packs::configuration::parse_memory_size(mmap_threshold)
+                    .context(format!(
+                        "Invalid --mmap-threshold `{}`",
+                        mmap_threshold
+                    ))?,
+            );
+        }
+
+        if let Some(snippet_lines) = self.snippet_lines {
+            configuration.snippet_lines = snippet_lines;
+        }
+
+        if self.print_files {
+            configuration.print_files = true;
+        }
+
+        if self.experimental_parser {
+            debug!("Using experimental parser");
+            configuration.experimental_parser = true;
+        }
+
+        if self.no_cache {
+            debug!("Cache is disabled");
+            configuration.cache_enabled = false;
+        }
+
+        if self.disable_enforce_dependencies {
+            configuration.disable_enforce_dependencies = true;
+        }
+
+        if self.disable_enforce_folder_privacy {
+            configuration.disable_enforce_folder_privacy = true;
+        }
+
+        if self.disable_enforce_layers {
+            configuration.disable_enforce_layers = true;
+        }
+
+        if self.disable_enforce_privacy {
+            configuration.disable_enforce_privacy = true;
+        }
+
+        if self.disable_enforce_visibility {
+            configuration.disable_enforce_visibility = true;
+        }
+        Ok(())
+    }
+}
+
+// The exit code of `check` when it timed out before analyzing every file,
+// which is distinct from that of violations (1) and usage errors (2)
+const TIMED_OUT_EXIT_CODE: u8 = 3;
+
+pub fn exit_code(error: &anyhow::Error) -> ExitCode {
+    if error.is::<TimedOut>() {
+        ExitCode::from(TIMED_OUT_EXIT_CODE)
+    } else {
+        ExitCode::FAILURE
+    }
 }
 
 pub fn run() -> anyhow::Result<()> {
+    // The timeout of `check` includes loading the configuration
+    let started = Instant::now();
     let args = Args::parse();
     let absolute_root = args
         .absolute_project_root()
         .expect("Issue getting absolute_project_root!");
 
-    install_logger(args.debug);
+    install_logger(args.debug, args.log_format);
 
     // The `init` command is run in directories which have no configuration yet, however, below we
     // attempt to load configuration before the CLI commands are processed. To avoid this catch-22
@@ -204,42 +906,81 @@ pub fn run() -> anyhow::Result<()> {
         packs::init(&absolute_root, use_packwerk)?
     }
 
-    // Input filesize TBD
-    let mut configuration = packs::configuration::get(&absolute_root, &0)?;
-
-    if args.print_files {
-        configuration.print_files = true;
+    // The thread pool is configured before the configuration is loaded, since
+    // walking the project directory already uses it
+    let threads = match args.threads {
+        Some(threads) => Some(threads),
+        None => packs::raw_configuration::get(&absolute_root)?.threads,
+    };
+    if let Some(threads) = threads {
+        debug!("Using {} thread(s)", threads);
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .context("Failed to configure the thread pool")?;
     }
 
-    if args.experimental_parser {
-        debug!("Using experimental parser");
-        configuration.experimental_parser = true;
-    }
+    let progress_format = match args.progress {
+        _ if args.quiet => None,
+        ProgressFormat::Human if !std::io::stderr().is_terminal() => None,
+        progress_format => Some(progress_format),
+    };
 
-    if args.no_cache {
-        debug!("Cache is disabled");
-        configuration.cache_enabled = false;
-    }
-
-    if args.disable_enforce_dependencies {
-        configuration.disable_enforce_dependencies = true;
+    // Each project of a batch has its own configuration, and the project root
+    // only needs one when it is also listed
+    if let Command::CheckBatch {
+        project_roots,
+        shared_cache_directory,
+    } = &args.command
+    {
+        let project_roots: Vec<PathBuf> = project_roots
+            .iter()
+            .map(|project_root| absolute_root.join(project_root))
+            .collect();
+        let shared_cache_directory = shared_cache_directory
+            .as_ref()
+            .map(|directory| absolute_root.join(directory));
+        let mut configurations = packs::batch::load_configurations(
+            &project_roots,
+            shared_cache_directory.as_deref(),
+        )?;
+        for configuration in &mut configurations {
+            args.apply_overrides(configuration)?;
+            configuration.progress_format = progress_format;
+        }
+        return packs::check_batch(&absolute_root, &mut configurations);
     }
 
-    if args.disable_enforce_folder_privacy {
-        configuration.disable_enforce_folder_privacy = true;
+    // The configuration may fail to load, which is what `doctor` diagnoses
+    if let Command::Doctor { format } = &args.command {
+        return packs::doctor(
+            &absolute_root,
+            &|configuration| args.apply_overrides(configuration),
+            *format,
+        );
     }
 
-    if args.disable_enforce_layers {
-        configuration.disable_enforce_layers = true;
+    // Each run of a benchmark loads the configuration again, since loading it
+    // is timed
+    if let Command::Bench {
+        iterations,
+        cache,
+        format,
+    } = &args.command
+    {
+        return packs::bench(
+            &absolute_root,
+            &|configuration| args.apply_overrides(configuration),
+            iterations.get(),
+            *cache,
+            *format,
+        );
     }
 
-    if args.disable_enforce_privacy {
-        configuration.disable_enforce_privacy = true;
-    }
+    // Input filesize TBD
+    let mut configuration = packs::configuration::get(&absolute_root, &0)?;
 
-    if args.disable_enforce_visibility {
-        configuration.disable_enforce_visibility = true;
-    }
+    args.apply_overrides(&mut configuration)?;
 
     match args.command {
         Command::Greet => {
@@ -253,28 +994,244 @@ pub fn run() -> anyhow::Result<()> {
             );
             Ok(())
         }
-        Command::ListPacks => {
-            packs::list(configuration);
-            Ok(())
+        Command::ListPacks {
+            enforcing,
+            layer,
+            owner,
+            has_todos,
+            format,
+        } => packs::list(
+            &configuration,
+            &PackFilter {
+                enforcing,
+                layer,
+                owner,
+                has_todos,
+            },
+            format,
+        ),
+        Command::Stats {
+            by_product_group,
+            changed_packs,
+            record,
+            diff,
+            format,
+        } => packs::stats(
+            &configuration,
+            by_product_group,
+            changed_packs.as_deref(),
+            record,
+            diff.as_ref().map(Option::as_deref),
+            format,
+        ),
+        Command::ChangedPacks { base, format } => {
+            packs::changed_packs(&configuration, &base, format)
+        }
+        Command::GraphMetrics { edges, top, format } => {
+            packs::graph_metrics(&configuration, edges, top, format)
+        }
+        Command::ExportEdges { format } => {
+            packs::export_edges(&configuration, format)
+        }
+        Command::Hotspots { since, top, format } => {
+            configuration.progress_format = progress_format;
+            packs::hotspots(&configuration, since.as_deref(), top, format)
+        }
+        Command::SuggestSplit {
+            pack,
+            candidates,
+            format,
+        } => {
+            configuration.progress_format = progress_format;
+            packs::suggest_split(&configuration, &pack, candidates, format)
+        }
+        Command::Report {
+            format,
+            output,
+            by_owner,
+            by_defining_owner,
+            base,
+        } => {
+            configuration.progress_format = progress_format;
+            packs::report(
+                &configuration,
+                format,
+                &output,
+                by_owner,
+                by_defining_owner,
+                base.as_deref(),
+            )
         }
         Command::ListPackDependencies { pack } => {
             packs::list_dependencies(&configuration, pack)
         }
+        Command::Info { pack, format } => {
+            packs::info(&configuration, &pack, format)
+        }
+        Command::PublicApi { pack, format } => {
+            packs::public_api(&configuration, &pack, format)
+        }
+        Command::PublicEntrypoints { constant, format } => {
+            packs::public_entrypoints(&configuration, &constant, format)
+        }
         Command::AddDependency { from, to } => {
             packs::add_dependency(&configuration, from, to)
         }
+        Command::EnableEnforcement {
+            enforcement,
+            packs: patterns,
+            strict,
+        } => packs::enable_enforcement(
+            &configuration,
+            &enforcement,
+            strict,
+            &patterns,
+        ),
+        Command::Merge { absorbed, into } => {
+            packs::merge(&configuration, &absorbed, &into)
+        }
+        Command::MvPack { from, to, dry_run } => {
+            packs::move_pack(&configuration, &from, &to, dry_run)
+        }
+        Command::InvertDependency {
+            from,
+            to,
+            new_pack,
+            apply,
+            format,
+        } => packs::invert_dependency(
+            &configuration,
+            &from,
+            &to,
+            new_pack,
+            apply,
+            format,
+        ),
+        Command::Autocorrect { apply, format } => {
+            packs::autocorrect(&configuration, apply, format)
+        }
         Command::ListIncludedFiles => packs::list_included_files(configuration),
+        Command::ParseErrors => packs::list_parse_errors(&configuration),
+        Command::Unresolved { format } => {
+            packs::list_unresolved(&configuration, format)
+        }
+        Command::DeadReferences { format } => {
+            packs::list_dead_references(&configuration, format)
+        }
         Command::Check {
             ignore_recorded_violations,
+            group_by,
+            stdin_filelist,
+            fix_dependencies,
+            explain,
+            constant,
+            output_format,
+            notify,
+            strict_parsing,
+            outputs,
+            stream,
+            timeout,
+            changed_packs,
+            since,
+            changed,
+            checkers,
+            sample,
+            seed,
+            mut files,
+        } => {
+            if !checkers.is_empty() {
+                configuration.select_checkers(&checkers)?;
+            }
+            configuration.sample = sample
+                .map(|rate| Sample::new(rate, seed.unwrap_or(0)))
+                .transpose()?;
+            if let Some(base) = changed_packs {
+                let pack_names =
+                    packs::git::changed_packs(&configuration, &base)?;
+                files.extend(packs::git::files_of_packs(
+                    &configuration,
+                    &pack_names,
+                )?);
+                if files.is_empty() {
+                    // Without any files, every file would be checked
+                    println!("No files to check in changed packs");
+                    return Ok(());
+                }
+            }
+            let since = if changed {
+                Some(packs::git::default_base(&configuration)?)
+            } else {
+                since
+            };
+            if let Some(base) = since {
+                files.extend(packs::git::files_affected_since(
+                    &configuration,
+                    &base,
+                )?);
+                if files.is_empty() {
+                    // Without any files, every file would be checked
+                    println!("No files affected since {}", base);
+                    return Ok(());
+                }
+            }
+            if stdin_filelist {
+                files.extend(read_file_list(std::io::stdin().lock())?);
+                if files.is_empty() {
+                    // Without any files, every file would be checked
+                    println!("No files to check");
+                    return Ok(());
+                }
+            }
+            configuration.ignore_recorded_violations =
+                ignore_recorded_violations;
+            configuration.strict_parsing |= strict_parsing;
+            configuration.terminal_links = std::io::stdout().is_terminal();
+            configuration.input_files_count = files.len();
+            // The output format of the profile only applies to plain lists of
+            // violations
+            let output_format = match output_format {
+                Some(output_format) => output_format,
+                None if group_by.is_some() || explain || stream => {
+                    CheckOutputFormat::Human
+                }
+                None => configuration
+                    .output_format
+                    .unwrap_or(CheckOutputFormat::Human),
+            };
+            configuration.progress_format = progress_format;
+            configuration.deadline =
+                timeout.map(|timeout| started + Duration::from_secs(timeout));
+            packs::check(
+                &configuration,
+                files,
+                packs::CheckOptions {
+                    group_by,
+                    fix_dependencies,
+                    explain: explain.then_some(constant.as_deref()),
+                    output_format,
+                    outputs: &outputs,
+                    stream,
+                    notify,
+                },
+            )
+        }
+        Command::Annotate {
+            ignore_recorded_violations,
+            diff,
             files,
         } => {
             configuration.ignore_recorded_violations =
                 ignore_recorded_violations;
             configuration.input_files_count = files.len();
-            packs::check(&configuration, files)
+            packs::annotate(&configuration, files, diff)
         }
+        // Handled before the configuration is loaded
+        Command::CheckBatch { .. } => Ok(()),
+        Command::Doctor { .. } => Ok(()),
+        Command::Bench { .. } => Ok(()),
         Command::CheckContents {
             ignore_recorded_violations,
+            output_format,
             file,
         } => {
             configuration.ignore_recorded_violations =
@@ -283,11 +1240,41 @@ pub fn run() -> anyhow::Result<()> {
             let absolute_path = get_absolute_path(file.clone(), &configuration);
             configuration.stdin_file_path = Some(absolute_path);
             configuration.input_files_count = 1;
-            packs::check(&configuration, vec![file])
+            packs::check(
+                &configuration,
+                vec![file],
+                packs::CheckOptions {
+                    group_by: None,
+                    fix_dependencies: false,
+                    explain: None,
+                    output_format,
+                    outputs: &[],
+                    stream: false,
+                    notify: false,
+                },
+            )
         }
-        Command::Update => packs::update(&configuration),
-        Command::Validate => {
-            packs::validate(&configuration)
+        Command::Update {
+            partial,
+            minimal_diff,
+            outputs,
+            diff_json,
+        } => {
+            configuration.progress_format = progress_format;
+            packs::update(
+                &configuration,
+                &partial,
+                minimal_diff,
+                &outputs,
+                diff_json.as_deref(),
+            )
+        }
+        Command::PruneTodos => packs::prune_todos(&configuration),
+        Command::Parity { sample, files } => {
+            packs::parity(&mut configuration, files, sample)
+        }
+        Command::Validate { fix } => {
+            packs::validate(&configuration, fix)
             // Err("💡 Please use `packs check` to detect dependency cycles and run other configuration validations".into())
         }
         Command::CheckUnnecessaryDependencies { auto_correct } => {
@@ -307,6 +1294,9 @@ pub fn run() -> anyhow::Result<()> {
             let ambiguous = args.ambiguous;
             packs::list_definitions(&configuration, ambiguous)
         }
+        Command::ExportDefinitions { format } => {
+            packs::export_definitions(&configuration, format)
+        }
         Command::ExposeMonkeyPatches(args) => packs::expose_monkey_patches(
             &configuration,
             &args.rubydir,
@@ -0,0 +1,132 @@
+// Links from violations to their code on a code host, built from the
+// `code_url` template in packwerk.yml, e.g.
+// `https://github.com/acme/app/blob/{commit}/{file}#L{line}`, so each
+// violation is one click away from the code in terminals, Markdown and HTML.
+use std::path::Path;
+
+use anyhow::bail;
+
+use super::git;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeUrl {
+    template: String,
+    // The commit of HEAD, when the template has a `{commit}`
+    commit: Option<String>,
+}
+
+impl CodeUrl {
+    pub(crate) fn new(
+        template: &str,
+        absolute_root: &Path,
+    ) -> anyhow::Result<Self> {
+        if !template.contains("{file}") {
+            bail!(
+                "`code_url` in packwerk.yml must contain `{{file}}`, e.g. `https://github.com/acme/app/blob/{{commit}}/{{file}}#L{{line}}`, but is `{}`",
+                template
+            );
+        }
+        // Outside of a git repository, code hosts resolve `HEAD` to the
+        // default branch
+        let commit = template.contains("{commit}").then(|| {
+            git::head_commit(absolute_root)
+                .unwrap_or_else(|_| String::from("HEAD"))
+        });
+        Ok(Self {
+            template: template.to_owned(),
+            commit,
+        })
+    }
+
+    // `file` is relative to the project root
+    pub(crate) fn for_location(&self, file: &str, line: usize) -> String {
+        let url = self
+            .template
+            .replace("{file}", &encode_path(file))
+            .replace("{line}", &line.to_string());
+        match &self.commit {
+            Some(commit) => url.replace("{commit}", commit),
+            None => url,
+        }
+    }
+}
+
+// Percent-encodes each segment of a path, so that file names with spaces or
+// characters reserved in URLs, e.g. `#` or `?`, link to the file. Only
+// unreserved characters (RFC 3986) are left as they are.
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            segment
+                .bytes()
+                .map(|byte| match byte {
+                    b'A'..=b'Z'
+                    | b'a'..=b'z'
+                    | b'0'..=b'9'
+                    | b'-'
+                    | b'.'
+                    | b'_'
+                    | b'~' => (byte as char).to_string(),
+                    _ => format!("%{:02X}", byte),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// An OSC 8 hyperlink, which terminals that support it open on click, and
+// others print as plain text
+pub(crate) fn terminal_link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_for_location() {
+        let code_url = CodeUrl {
+            template: String::from(
+                "https://github.com/acme/app/blob/{commit}/{file}#L{line}",
+            ),
+            commit: Some(String::from("abc123")),
+        };
+        assert_eq!(
+            code_url.for_location("packs/foo/app/services/my foo.rb", 3),
+            "https://github.com/acme/app/blob/abc123/packs/foo/app/services/my%20foo.rb#L3"
+        );
+    }
+
+    #[test]
+    fn test_for_location_with_reserved_characters() {
+        let code_url = CodeUrl {
+            template: String::from("https://example.com/{file}#L{line}"),
+            commit: None,
+        };
+        assert_eq!(
+            code_url.for_location("packs/c#/app/what?100%[1]+é.rb", 3),
+            "https://example.com/packs/c%23/app/what%3F100%25%5B1%5D%2B%C3%A9.rb#L3"
+        );
+    }
+
+    #[test]
+    fn test_template_without_file() {
+        assert_eq!(
+            CodeUrl::new("https://github.com/acme/app", Path::new("."))
+                .unwrap_err()
+                .to_string(),
+            "`code_url` in packwerk.yml must contain `{file}`, e.g. `https://github.com/acme/app/blob/{commit}/{file}#L{line}`, but is `https://github.com/acme/app`"
+        );
+    }
+
+    #[test]
+    fn test_terminal_link() {
+        assert_eq!(
+            terminal_link("https://example.com", "foo.rb:3:1"),
+            "\x1b]8;;https://example.com\x1b\\foo.rb:3:1\x1b]8;;\x1b\\"
+        );
+    }
+}
@@ -3,20 +3,40 @@ use super::caching::{
     per_file_cache::PerFileCache,
 };
 use super::checker::layer::Layers;
-use super::file_utils::user_inputted_paths_to_absolute_filepaths;
+use super::checker::sampling::Sample;
+use super::checker::CHECKER_NAMES;
+use super::cli::{CheckOutputFormat, ProgressFormat};
+use super::code_url::CodeUrl;
+use super::constant_resolver::WarmConstantResolvers;
+use super::file_utils::{
+    build_glob_set, user_inputted_paths_to_absolute_filepaths,
+};
+use super::pack::Pack;
+use super::package_todo::PackageTodoFormat;
+use super::parsing::ExtractionHandlers;
+use super::pksignore::PksIgnore;
+use super::raw_configuration::{
+    ArchitectureException, Notification, Profile, ResolutionFallback, TagPolicy,
+};
 
 use super::{
     constant_resolver::ConstantResolverConfiguration, raw_configuration,
-    raw_configuration::RawConfiguration, walk_directory,
+    raw_configuration::RawConfiguration,
+    reference_extractor::build_warm_constant_resolvers, violation_store,
+    violation_store::ViolationStoreConfig, walk_directory,
     walk_directory::WalkDirectoryResult, PackSet,
 };
 
-use std::collections::HashMap;
+use anyhow::{bail, Context};
+use globset::GlobSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::{
     collections::HashSet,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
 };
-use tracing::debug;
+use tracing::{debug, field, info_span};
 use walk_directory::walk_directory;
 
 pub struct Configuration {
@@ -25,25 +45,101 @@ pub struct Configuration {
     pub absolute_root: PathBuf,
     pub cache_enabled: bool,
     pub cache_directory: PathBuf,
+    // Where processed files are cached, when shared by several projects (see
+    // batch.rs) rather than kept in `cache_directory`
+    pub file_cache_directory: Option<PathBuf>,
     pub pack_set: PackSet,
+    pub roots: Vec<Root>,
     pub layers: Layers,
+    pub architecture_exceptions: Vec<ArchitectureException>,
+    pub tag_policies: Vec<TagPolicy>,
+    pub resolution_fallbacks: Vec<ResolutionFallback>,
+    pub notifications: Vec<Notification>,
     pub experimental_parser: bool,
+    // Extension -> extractor of the references of files (see
+    // parsing/extraction_handlers.rs)
+    pub extraction_handlers: ExtractionHandlers,
     pub ignored_definitions: HashMap<String, HashSet<PathBuf>>,
     pub autoload_roots: HashMap<PathBuf, String>,
+    // Directories that don't introduce a namespace, relative to the project
+    // root
+    pub collapse_dirs: Vec<glob::Pattern>,
     pub inflections_path: PathBuf,
+    pub acronyms: HashSet<String>,
     pub custom_associations: Vec<String>,
+    pub constantize_references: bool,
+    pub constantize_methods: Vec<String>,
+    pub test_paths: GlobSet,
+    // Rules from .pksignore, which supplement `exclude:`
+    pub pksignore: PksIgnore,
     pub stdin_file_path: Option<PathBuf>,
     // Note that it'd probably be better to use the logger library, `tracing` (see logger.rs)
     // and configure logging in one place. As the complexity of how/why we want to see different logs
     // grows, we can refactor this.
     pub print_files: bool,
+    // None unless progress should be reported (see progress.rs)
+    pub progress_format: Option<ProgressFormat>,
     pub packs_first_mode: bool,
+    pub package_todo_format: PackageTodoFormat,
+    // Where `update` records violations (see violation_store.rs)
+    pub violation_store: ViolationStoreConfig,
+    // In bytes. Files are parsed in batches estimated to fit within it.
+    pub max_memory: Option<u64>,
+    // In bytes. Files at least this large are memory-mapped when read for
+    // parsing (see mapped_file.rs).
+    pub mmap_threshold: Option<u64>,
+    // Product group name -> patterns matching the names of its packs
+    pub product_groups: BTreeMap<String, Vec<glob::Pattern>>,
+    // Visibility group name -> patterns matching the names of its packs
+    pub visibility_groups: BTreeMap<String, Vec<glob::Pattern>>,
+    // Links to the code of violations (see code_url.rs)
+    pub code_url: Option<CodeUrl>,
+    // Whether violation locations link to `code_url`, when printed to a
+    // terminal
+    pub terminal_links: bool,
+    // The branch `check --changed` compares against (see git.rs)
+    pub default_branch: Option<String>,
     pub ignore_recorded_violations: bool,
+    // Whether files that could not be parsed fail `check`
+    pub strict_parsing: bool,
+    // Whether `check` lists the references it couldn't attribute to a pack
+    pub report_unresolved_constants: bool,
+    // Whether `validate` reports requires of private files of other packs
+    pub check_requires: bool,
+    // The lines of code before and after each violation to capture, if any
+    pub snippet_lines: usize,
     pub disable_enforce_dependencies: bool,
     pub disable_enforce_folder_privacy: bool,
     pub disable_enforce_layers: bool,
     pub disable_enforce_privacy: bool,
     pub disable_enforce_visibility: bool,
+    // The violation types of the checkers to run (see `check --checker`), or
+    // None to run all of them
+    pub checkers: Option<BTreeSet<String>>,
+    // Files not yet being parsed by then are skipped (see `check --timeout`)
+    pub deadline: Option<Instant>,
+    // Only the files in the sample are checked (see `check --sample`)
+    pub sample: Option<Sample>,
+    // Profile name -> overrides, applied with `--profile`
+    pub profiles: BTreeMap<String, Profile>,
+    // Whether every violation is strict, as set by a profile
+    pub strict: bool,
+    // The days recorded violations are treated as recorded rather than
+    // strict, unless their enforcing pack sets its own
+    pub strict_mode_grace_period_days: Option<u64>,
+    // The output format of `check` when `--output-format` isn't passed
+    pub output_format: Option<CheckOutputFormat>,
+    // Built once and shared by the commands run against this configuration
+    // (see `warm_constant_resolvers`)
+    pub constant_resolvers: Option<Arc<WarmConstantResolvers>>,
+}
+
+// An analysis root of a monorepo, e.g. `apps/admin`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Root {
+    // Relative to the project root
+    pub path: PathBuf,
+    pub shared: bool,
 }
 
 impl Configuration {
@@ -65,13 +161,157 @@ impl Configuration {
         }
     }
 
+    // Limits the checkers that run to those named, e.g. `privacy`
+    pub(crate) fn select_checkers(
+        &mut self,
+        names: &[String],
+    ) -> anyhow::Result<()> {
+        for name in names {
+            if !CHECKER_NAMES.contains(&name.as_str()) {
+                bail!(
+                    "Unknown checker `{}`. Checkers: {}",
+                    name,
+                    CHECKER_NAMES.join(", ")
+                );
+            }
+        }
+        self.checkers = Some(names.iter().cloned().collect());
+        Ok(())
+    }
+
+    // Whether `update` dates recorded violations (see
+    // checker/strict_grace_period.rs)
+    pub(crate) fn has_strict_mode_grace_period(&self) -> bool {
+        self.strict_mode_grace_period_days.is_some()
+            || self
+                .pack_set
+                .packs
+                .iter()
+                .any(|pack| pack.strict_mode_grace_period_days.is_some())
+    }
+
+    pub(crate) fn runs_checker(&self, violation_type: &str) -> bool {
+        match &self.checkers {
+            Some(checkers) => checkers.contains(violation_type),
+            None => true,
+        }
+    }
+
+    // The most specific root containing the path, if any
+    pub(crate) fn root_for_path(&self, absolute_path: &Path) -> Option<&Root> {
+        let relative_path =
+            absolute_path.strip_prefix(&self.absolute_root).ok()?;
+        self.roots
+            .iter()
+            .filter(|root| relative_path.starts_with(&root.path))
+            .max_by_key(|root| root.path.components().count())
+    }
+
+    // Constants are resolved separately for each app root, so that apps may
+    // define the same constants. Shared roots, and code outside of any root,
+    // are visible from every app root.
+    pub(crate) fn app_root_for_path(
+        &self,
+        absolute_path: &Path,
+    ) -> Option<&Root> {
+        self.root_for_path(absolute_path)
+            .filter(|root| !root.shared)
+    }
+
+    // The first product group, by name, with a pattern matching the pack
+    pub(crate) fn product_group_for_pack(
+        &self,
+        pack_name: &str,
+    ) -> Option<&str> {
+        self.product_groups
+            .iter()
+            .find(|(_, patterns)| {
+                patterns.iter().any(|pattern| pattern.matches(pack_name))
+            })
+            .map(|(group, _)| group.as_str())
+    }
+
+    // Whether a `visible_to` list names the pack, or a visibility group with a
+    // pattern matching it
+    pub(crate) fn is_visible_to(
+        &self,
+        visible_to: &HashSet<String>,
+        pack_name: &str,
+    ) -> bool {
+        visible_to.contains(pack_name)
+            || visible_to.iter().any(|name| {
+                self.visibility_groups.get(name).is_some_and(|patterns| {
+                    patterns.iter().any(|pattern| pattern.matches(pack_name))
+                })
+            })
+    }
+
+    pub(crate) fn is_test_file(&self, relative_file: &str) -> bool {
+        self.test_paths.is_match(relative_file)
+    }
+
+    // Where processed files are cached, separately for each parser
+    pub(crate) fn parser_cache_directory(&self) -> PathBuf {
+        let cache_directory = self
+            .file_cache_directory
+            .as_ref()
+            .unwrap_or(&self.cache_directory);
+        if self.experimental_parser {
+            cache_directory.join("experimental")
+        } else {
+            cache_directory.join("zeitwerk")
+        }
+    }
+
+    pub(crate) fn apply_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            let names: Vec<&str> =
+                self.profiles.keys().map(String::as_str).collect();
+            bail!(
+                "Unknown profile `{}`. Profiles in packwerk.yml: {}",
+                name,
+                if names.is_empty() {
+                    String::from("(none)")
+                } else {
+                    names.join(", ")
+                }
+            );
+        };
+        if let Some(cache) = profile.cache {
+            self.cache_enabled = cache;
+        }
+        if let Some(cache_directory) = &profile.cache_directory {
+            self.cache_directory = self.absolute_root.join(cache_directory);
+        }
+        for checker in &profile.disabled_checkers {
+            match checker.as_str() {
+                "dependency" => self.disable_enforce_dependencies = true,
+                "folder_privacy" => self.disable_enforce_folder_privacy = true,
+                "layer" => self.disable_enforce_layers = true,
+                "privacy" => self.disable_enforce_privacy = true,
+                "visibility" => self.disable_enforce_visibility = true,
+                _ => bail!(
+                    "Unknown checker `{}` in `disabled_checkers` of profile `{}`",
+                    checker,
+                    name
+                ),
+            }
+        }
+        if let Some(strict) = profile.strict {
+            self.strict = strict;
+        }
+        if let Some(strict_parsing) = profile.strict_parsing {
+            self.strict_parsing = strict_parsing;
+        }
+        if profile.output_format.is_some() {
+            self.output_format = profile.output_format;
+        }
+        Ok(())
+    }
+
     pub(crate) fn get_cache(&self) -> Box<dyn Cache + Send + Sync> {
         if self.cache_enabled {
-            let cache_dir = if self.experimental_parser {
-                self.cache_directory.join("experimental")
-            } else {
-                self.cache_directory.join("zeitwerk")
-            };
+            let cache_dir = self.parser_cache_directory();
 
             create_cache_dir_idempotently(&cache_dir);
 
@@ -81,6 +321,22 @@ impl Configuration {
         }
     }
 
+    // Builds the Zeitwerk constant resolvers of every app root once, so that
+    // the commands run against this configuration afterwards, e.g. the
+    // checks of `check-batch` (see batch.rs), share them rather than building
+    // their own. They
+    // don't see files added or removed since. The experimental parser gets
+    // definitions from the contents of files, so each command still builds
+    // its constant resolvers.
+    pub fn warm_constant_resolvers(&mut self) -> anyhow::Result<()> {
+        if self.experimental_parser {
+            return Ok(());
+        }
+        self.constant_resolvers =
+            Some(Arc::new(build_warm_constant_resolvers(self)?));
+        Ok(())
+    }
+
     pub(crate) fn constant_resolver_configuration(
         &self,
     ) -> ConstantResolverConfiguration {
@@ -89,7 +345,9 @@ impl Configuration {
             cache_directory: &self.cache_directory,
             cache_enabled: self.cache_enabled,
             autoload_roots: &self.autoload_roots,
+            collapse_dirs: &self.collapse_dirs,
             inflections_path: &self.inflections_path,
+            acronyms: &self.acronyms,
         }
     }
 }
@@ -99,10 +357,14 @@ pub(crate) fn get(
     input_files_count: &usize,
 ) -> anyhow::Result<Configuration> {
     debug!("Beginning to build configuration");
+    let span = info_span!("walk", files = field::Empty, packs = field::Empty)
+        .entered();
 
     let raw_config = raw_configuration::get(absolute_root)?;
     let walk_directory_result =
         walk_directory(absolute_root.to_path_buf(), &raw_config)?;
+    span.record("files", walk_directory_result.included_files.len());
+    span.record("packs", walk_directory_result.included_packs.len());
 
     from_raw(
         absolute_root,
@@ -112,6 +374,123 @@ pub(crate) fn get(
     )
 }
 
+// The checkers that `require:` entries of packwerk.yml load into packwerk.
+// The dependency checker is built into packwerk.
+const PACKWERK_EXTENSIONS: &[(&str, &[&str])] = &[
+    (
+        "packwerk-extensions",
+        &["privacy", "visibility", "folder_privacy", "layer"],
+    ),
+    ("packwerk/privacy/checker", &["privacy"]),
+    ("packwerk/visibility/checker", &["visibility"]),
+    ("packwerk/folder_privacy/checker", &["folder_privacy"]),
+    ("packwerk/layer/checker", &["layer"]),
+    ("packwerk/architecture/checker", &["layer"]), // deprecated
+];
+
+// The extension checkers that `require:` enables, or None to enable all of
+// them when it requires no known extension, e.g. when it's absent. Other
+// entries, such as custom checkers, are ignored.
+fn required_checkers(require: &[String]) -> Option<HashSet<&'static str>> {
+    let mut checkers = None;
+    for required in require {
+        match PACKWERK_EXTENSIONS
+            .iter()
+            .find(|(extension, _)| extension == required)
+        {
+            Some((_, extension_checkers)) => checkers
+                .get_or_insert_with(HashSet::new)
+                .extend(extension_checkers.iter().copied()),
+            None => debug!("Ignoring `{}` in require", required),
+        }
+    }
+    checkers
+}
+
+// A size in bytes, optionally suffixed with K, M, or G (powers of 1024), e.g.
+// `512M` or `2GB`
+pub(crate) fn parse_memory_size(size: &str) -> anyhow::Result<u64> {
+    let size = size.trim().to_ascii_uppercase();
+    let size = size
+        .strip_suffix("IB")
+        .or_else(|| size.strip_suffix('B'))
+        .unwrap_or(&size);
+    let (number, multiplier) = match size.chars().last() {
+        Some('K') => (&size[..size.len() - 1], 1 << 10),
+        Some('M') => (&size[..size.len() - 1], 1 << 20),
+        Some('G') => (&size[..size.len() - 1], 1 << 30),
+        _ => (size, 1),
+    };
+    let number: u64 = number.trim().parse().context(
+        "Expected a number of bytes, optionally suffixed with K, M, or G",
+    )?;
+    number
+        .checked_mul(multiplier)
+        .context("The size is too large to be represented in bytes")
+}
+
+// Assigns the layers of `layer_globs` to the packs whose package.yml doesn't
+// set `layer`. A pack may only match the globs of one layer.
+fn inherit_layers(
+    packs: HashSet<Pack>,
+    layer_globs: &BTreeMap<String, Vec<String>>,
+    layers: &[String],
+) -> anyhow::Result<HashSet<Pack>> {
+    if layer_globs.is_empty() {
+        return Ok(packs);
+    }
+    let mut patterns_by_layer = Vec::new();
+    for (layer, pack_globs) in layer_globs {
+        if !layers.contains(layer) {
+            bail!(
+                "Layer `{}` in layer_globs is not one of the layers defined in packwerk.yml",
+                layer
+            );
+        }
+        let mut patterns = Vec::new();
+        for pack_glob in pack_globs {
+            match glob::Pattern::new(pack_glob.trim_end_matches('/')) {
+                Ok(pattern) => patterns.push(pattern),
+                Err(e) => bail!(
+                    "Invalid pack glob `{}` for layer `{}` in packwerk.yml: {}",
+                    pack_glob,
+                    layer,
+                    e
+                ),
+            }
+        }
+        patterns_by_layer.push((layer, patterns));
+    }
+
+    let mut inheriting_packs = HashSet::new();
+    for mut pack in packs {
+        if pack.layer.is_none() {
+            let matching_layers: Vec<&String> = patterns_by_layer
+                .iter()
+                .filter(|(_, patterns)| {
+                    patterns.iter().any(|pattern| pattern.matches(&pack.name))
+                })
+                .map(|(layer, _)| *layer)
+                .collect();
+            match matching_layers.as_slice() {
+                [] => {}
+                [layer] => pack.inherited_layer = Some(layer.to_string()),
+                _ => bail!(
+                    "`{}` matches the layer_globs of more than one layer: {}",
+                    pack.name,
+                    matching_layers
+                        .iter()
+                        .map(|layer| layer.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                ),
+            }
+        }
+        inheriting_packs.insert(pack);
+    }
+    Ok(inheriting_packs)
+}
+
 pub(crate) fn from_raw(
     absolute_root: &Path,
     raw_config: RawConfiguration,
@@ -122,23 +501,146 @@ pub(crate) fn from_raw(
         included_files,
         included_packs,
         owning_package_yml_for_file,
+        pksignore,
+        pack_errors,
     } = walk_directory_result;
+    // `doctor` reports every pack that could not be loaded
+    if let Some((_package_yml, error)) = pack_errors.into_iter().next() {
+        return Err(error);
+    }
 
     let absolute_root = absolute_root.to_path_buf();
+    let included_packs = inherit_layers(
+        included_packs,
+        &raw_config.layer_globs,
+        &raw_config.layers,
+    )?;
+    let violation_store = violation_store::resolve(
+        &absolute_root,
+        raw_config.violation_store,
+        raw_config.violation_store_path.as_deref(),
+    )?;
+    // package_todo.yml files are read along with their pack
+    let included_packs = if violation_store == ViolationStoreConfig::Yml {
+        included_packs
+    } else {
+        let mut package_todos =
+            violation_store::open(&violation_store).load(&included_packs)?;
+        included_packs
+            .into_iter()
+            .map(|pack| Pack {
+                package_todo: package_todos
+                    .remove(&pack.name)
+                    .unwrap_or_default(),
+                ..pack
+            })
+            .collect()
+    };
     let pack_set = PackSet::build(included_packs, owning_package_yml_for_file)?;
 
     let cache_directory = absolute_root.join(raw_config.cache_directory);
     let cache_enabled = raw_config.cache;
     let experimental_parser = raw_config.experimental_parser;
+    let extraction_handlers =
+        ExtractionHandlers::from_raw(&raw_config.extraction_handlers)?;
+
+    let mut roots = Vec::new();
+    for raw_root in raw_config.roots {
+        let path = PathBuf::from(raw_root.path.trim_end_matches('/'));
+        if !absolute_root.join(&path).is_dir() {
+            bail!(
+                "Root `{}` in packwerk.yml is not a directory",
+                path.display()
+            );
+        }
+        roots.push(Root {
+            path,
+            shared: raw_root.shared,
+        });
+    }
+
+    let mut product_groups = BTreeMap::new();
+    for (group, pack_globs) in raw_config.product_groups {
+        let mut patterns = Vec::new();
+        for pack_glob in pack_globs {
+            match glob::Pattern::new(pack_glob.trim_end_matches('/')) {
+                Ok(pattern) => patterns.push(pattern),
+                Err(e) => bail!(
+                    "Invalid pack glob `{}` for product group `{}` in packwerk.yml: {}",
+                    pack_glob,
+                    group,
+                    e
+                ),
+            }
+        }
+        product_groups.insert(group, patterns);
+    }
+
+    let code_url = raw_config
+        .code_url
+        .as_deref()
+        .map(|template| CodeUrl::new(template, &absolute_root))
+        .transpose()?;
+
+    let mut visibility_groups = BTreeMap::new();
+    for (group, pack_globs) in raw_config.visibility_groups {
+        let mut patterns = Vec::new();
+        for pack_glob in pack_globs {
+            match glob::Pattern::new(pack_glob.trim_end_matches('/')) {
+                Ok(pattern) => patterns.push(pattern),
+                Err(e) => bail!(
+                    "Invalid pack glob `{}` for visibility group `{}` in packwerk.yml: {}",
+                    pack_glob,
+                    group,
+                    e
+                ),
+            }
+        }
+        visibility_groups.insert(group, patterns);
+    }
 
     let layers = Layers {
         layers: raw_config.layers,
     };
 
+    let architecture_exceptions = raw_config.architecture_exceptions;
+    let tag_policies = raw_config.tag_policies;
+    let resolution_fallbacks = raw_config.resolution_fallbacks;
+    let notifications = raw_config.notifications;
+
     let ignored_definitions = raw_config.ignored_definitions;
     let autoload_roots: HashMap<PathBuf, String> = raw_config.autoload_roots;
+    let mut collapse_dirs = Vec::new();
+    for collapse_dir in raw_config.collapse_dirs {
+        match glob::Pattern::new(collapse_dir.trim_end_matches('/')) {
+            Ok(pattern) => collapse_dirs.push(pattern),
+            Err(e) => bail!(
+                "Invalid glob `{}` in `collapse_dirs` in packwerk.yml: {}",
+                collapse_dir,
+                e
+            ),
+        }
+    }
 
     let packs_first_mode = raw_config.packs_first_mode;
+    let report_unresolved_constants = raw_config.report_unresolved_constants;
+    let package_todo_format = raw_config.package_todo_format;
+    let profiles = raw_config.profiles;
+    let required_checkers = required_checkers(&raw_config.require);
+    let is_disabled = |checker: &str| {
+        required_checkers
+            .as_ref()
+            .is_some_and(|required| !required.contains(checker))
+    };
+    let max_memory = match &raw_config.max_memory {
+        Some(max_memory) => Some(parse_memory_size(max_memory).context(
+            format!("Invalid max_memory `{}` in packwerk.yml", max_memory),
+        )?),
+        None => None,
+    };
+    let constantize_references = raw_config.constantize_references;
+    let constantize_methods = raw_config.constantize_methods;
+    let test_paths = build_glob_set(&raw_config.test_paths);
 
     let inflections_path = absolute_root.join(
         raw_config
@@ -146,6 +648,8 @@ pub(crate) fn from_raw(
             .unwrap_or(PathBuf::from("config/initializers/inflections.rb")),
     );
 
+    let acronyms = raw_config.inflections.acronyms.into_iter().collect();
+
     let custom_associations = raw_config
         .custom_associations
         .iter()
@@ -161,22 +665,57 @@ pub(crate) fn from_raw(
         absolute_root,
         cache_enabled,
         cache_directory,
+        file_cache_directory: None,
         pack_set,
+        roots,
         layers,
+        architecture_exceptions,
+        tag_policies,
+        resolution_fallbacks,
+        notifications,
         experimental_parser,
+        extraction_handlers,
         ignored_definitions,
         autoload_roots,
+        collapse_dirs,
         inflections_path,
+        acronyms,
         custom_associations,
+        constantize_references,
+        constantize_methods,
+        test_paths,
+        pksignore,
         stdin_file_path: None,
         print_files: false,
+        progress_format: None,
         packs_first_mode,
+        package_todo_format,
+        violation_store,
+        max_memory,
+        mmap_threshold: None,
+        product_groups,
+        visibility_groups,
+        code_url,
+        terminal_links: false,
+        default_branch: raw_config.default_branch,
         ignore_recorded_violations: false,
+        strict_parsing: false,
+        report_unresolved_constants,
+        check_requires: raw_config.check_requires,
+        snippet_lines: raw_config.snippet_lines,
         disable_enforce_dependencies: false,
-        disable_enforce_folder_privacy: false,
-        disable_enforce_layers: false,
-        disable_enforce_privacy: false,
-        disable_enforce_visibility: false,
+        disable_enforce_folder_privacy: is_disabled("folder_privacy"),
+        disable_enforce_layers: is_disabled("layer"),
+        disable_enforce_privacy: is_disabled("privacy"),
+        disable_enforce_visibility: is_disabled("visibility"),
+        checkers: None,
+        deadline: None,
+        sample: None,
+        profiles,
+        strict: false,
+        strict_mode_grace_period_days: raw_config.strict_mode_grace_period_days,
+        output_format: None,
+        constant_resolvers: None,
     })
 }
 
@@ -220,19 +759,34 @@ mod tests {
                 enforce_visibility: None,
                 enforce_folder_privacy: None,
                 enforce_folder_visibility: None,
+                enforce_architecture: None,
                 enforce_layers: None,
                 owner: None,
+                max_dependencies: None,
+                max_dependents: None,
+                strict_mode_grace_period_days: None,
                 yml: absolute_root.join("packs/bar/package.yml"),
                 name: String::from("packs/bar"),
                 relative_path: PathBuf::from("packs/bar"),
                 dependencies: HashSet::new(),
+                test_dependencies: HashSet::new(),
                 visible_to: None,
+                namespaces: vec![],
                 package_todo: PackageTodo::default(),
-                ignored_dependencies: HashSet::new(),
+                package_todo_path: None,
+                package_todo_yml: absolute_root
+                    .join("packs/bar/package_todo.yml"),
+                ignored_dependencies: BTreeMap::new(),
                 ignored_private_constants: HashSet::new(),
+                privacy_exceptions: HashSet::new(),
+                waivers: vec![],
+                tags: vec![],
                 private_constants: HashSet::new(),
                 public_folder: None,
+                public_paths: None,
                 layer: None,
+                inherited_layer: None,
+                duplicate_dependencies: vec![],
                 client_keys: HashMap::new(),
                 enforcement_globs_ignore: None,
             },
@@ -242,19 +796,34 @@ mod tests {
                 enforce_visibility: None,
                 enforce_folder_privacy: None,
                 enforce_folder_visibility: None,
+                enforce_architecture: None,
                 enforce_layers: None,
                 owner: None,
+                max_dependencies: None,
+                max_dependents: None,
+                strict_mode_grace_period_days: None,
                 yml: absolute_root.join("packs/baz/package.yml"),
                 name: String::from("packs/baz"),
                 relative_path: PathBuf::from("packs/baz"),
                 dependencies: HashSet::new(),
+                test_dependencies: HashSet::new(),
                 visible_to: None,
+                namespaces: vec![],
                 package_todo: PackageTodo::default(),
-                ignored_dependencies: HashSet::new(),
+                package_todo_path: None,
+                package_todo_yml: absolute_root
+                    .join("packs/baz/package_todo.yml"),
+                ignored_dependencies: BTreeMap::new(),
                 ignored_private_constants: HashSet::new(),
+                privacy_exceptions: HashSet::new(),
+                waivers: vec![],
+                tags: vec![],
                 private_constants: HashSet::new(),
                 public_folder: None,
+                public_paths: None,
                 layer: None,
+                inherited_layer: None,
+                duplicate_dependencies: vec![],
                 client_keys: HashMap::new(),
                 enforcement_globs_ignore: None,
             },
@@ -264,22 +833,37 @@ mod tests {
                 enforce_visibility: None,
                 enforce_folder_privacy: None,
                 enforce_folder_visibility: None,
+                enforce_architecture: None,
                 enforce_layers: None,
                 owner: None,
+                max_dependencies: None,
+                max_dependents: None,
+                strict_mode_grace_period_days: None,
                 yml: absolute_root.join("packs/foo/package.yml"),
                 name: String::from("packs/foo"),
                 relative_path: PathBuf::from("packs/foo"),
                 dependencies: HashSet::from_iter(vec![String::from(
                     "packs/baz",
                 )]),
+                test_dependencies: HashSet::new(),
                 visible_to: None,
+                namespaces: vec![],
                 package_todo: PackageTodo::default(),
-                ignored_dependencies: HashSet::new(),
+                package_todo_path: None,
+                package_todo_yml: absolute_root
+                    .join("packs/foo/package_todo.yml"),
+                ignored_dependencies: BTreeMap::new(),
                 ignored_private_constants: HashSet::new(),
+                privacy_exceptions: HashSet::new(),
+                waivers: vec![],
+                tags: vec![],
                 private_constants: HashSet::new(),
                 public_folder: None,
+                public_paths: None,
 
                 layer: None,
+                inherited_layer: None,
+                duplicate_dependencies: vec![],
                 client_keys: HashMap::new(),
                 enforcement_globs_ignore: None,
             },
@@ -289,19 +873,33 @@ mod tests {
                 enforce_visibility: None,
                 enforce_folder_privacy: None,
                 enforce_folder_visibility: None,
+                enforce_architecture: None,
                 enforce_layers: None,
                 owner: None,
+                max_dependencies: None,
+                max_dependents: None,
+                strict_mode_grace_period_days: None,
                 yml: absolute_root.join("package.yml"),
                 name: String::from("."),
                 relative_path: PathBuf::from("."),
                 dependencies: HashSet::new(),
+                test_dependencies: HashSet::new(),
                 visible_to: None,
+                namespaces: vec![],
                 package_todo: PackageTodo::default(),
-                ignored_dependencies: HashSet::new(),
+                package_todo_path: None,
+                package_todo_yml: absolute_root.join("package_todo.yml"),
+                ignored_dependencies: BTreeMap::new(),
                 ignored_private_constants: HashSet::new(),
+                privacy_exceptions: HashSet::new(),
+                waivers: vec![],
+                tags: vec![],
                 private_constants: HashSet::new(),
                 public_folder: None,
+                public_paths: None,
                 layer: None,
+                inherited_layer: None,
+                duplicate_dependencies: vec![],
                 client_keys: HashMap::new(),
                 enforcement_globs_ignore: None,
             },
@@ -385,6 +983,8 @@ mod tests {
             included_files: Default::default(),
             included_packs,
             owning_package_yml_for_file: Default::default(),
+            pksignore: Default::default(),
+            pack_errors: Vec::new(),
         };
 
         let configuration = configuration::from_raw(
@@ -399,4 +999,209 @@ mod tests {
 
         assert_eq!(actual_associations, expected_paths);
     }
+
+    #[test]
+    fn app_root_for_path_with_multiple_roots() {
+        let absolute_root =
+            PathBuf::from("tests/fixtures/app_with_multiple_roots")
+                .canonicalize()
+                .expect("Could not canonicalize path");
+        let configuration = configuration::get(&absolute_root, &0).unwrap();
+
+        let admin_file =
+            absolute_root.join("apps/admin/app/services/admin_service.rb");
+        assert_eq!(
+            configuration.app_root_for_path(&admin_file),
+            Some(&Root {
+                path: PathBuf::from("apps/admin"),
+                shared: false,
+            })
+        );
+
+        let billing_file =
+            absolute_root.join("components/billing/app/services/billing.rb");
+        assert_eq!(
+            configuration
+                .root_for_path(&billing_file)
+                .map(|root| root.path.clone()),
+            Some(PathBuf::from("components"))
+        );
+        assert_eq!(configuration.app_root_for_path(&billing_file), None);
+
+        assert_eq!(
+            configuration.app_root_for_path(&absolute_root.join("package.yml")),
+            None
+        );
+    }
+
+    #[test]
+    fn apply_profile() {
+        let mut configuration = Configuration {
+            profiles: BTreeMap::from([(
+                String::from("ci"),
+                Profile {
+                    cache: Some(false),
+                    cache_directory: Some(String::from("tmp/ci-cache")),
+                    disabled_checkers: vec![String::from("layer")],
+                    strict: Some(true),
+                    output_format: Some(CheckOutputFormat::Json),
+                    ..Profile::default()
+                },
+            )]),
+            ..Configuration::default()
+        };
+        configuration.apply_profile("ci").unwrap();
+
+        assert!(!configuration.cache_enabled);
+        assert_eq!(
+            configuration.cache_directory,
+            configuration.absolute_root.join("tmp/ci-cache")
+        );
+        assert!(configuration.disable_enforce_layers);
+        assert!(!configuration.disable_enforce_privacy);
+        assert!(configuration.strict);
+        assert!(!configuration.strict_parsing);
+        assert_eq!(configuration.output_format, Some(CheckOutputFormat::Json));
+
+        assert_eq!(
+            configuration.apply_profile("dev").unwrap_err().to_string(),
+            "Unknown profile `dev`. Profiles in packwerk.yml: ci"
+        );
+    }
+
+    #[test]
+    fn product_group_for_pack() {
+        let configuration = Configuration {
+            product_groups: BTreeMap::from([
+                (
+                    String::from("payments"),
+                    vec![
+                        glob::Pattern::new("packs/billing").unwrap(),
+                        glob::Pattern::new("packs/payments/*").unwrap(),
+                    ],
+                ),
+                (
+                    String::from("platform"),
+                    vec![glob::Pattern::new("packs/*").unwrap()],
+                ),
+            ]),
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            configuration.product_group_for_pack("packs/billing"),
+            Some("payments")
+        );
+        assert_eq!(
+            configuration.product_group_for_pack("packs/payments/refunds"),
+            Some("payments")
+        );
+        assert_eq!(
+            configuration.product_group_for_pack("packs/logging"),
+            Some("platform")
+        );
+        assert_eq!(configuration.product_group_for_pack("."), None);
+    }
+
+    #[test]
+    fn is_test_file() {
+        let configuration = Configuration::default();
+
+        assert!(configuration.is_test_file("spec/models/user_spec.rb"));
+        assert!(configuration.is_test_file("packs/foo/test/foo_test.rb"));
+        assert!(!configuration.is_test_file("packs/foo/app/models/spec.rb"));
+    }
+
+    #[test]
+    fn test_parse_memory_size() {
+        assert_eq!(1024, parse_memory_size("1024").unwrap());
+        assert_eq!(512 << 20, parse_memory_size("512M").unwrap());
+        assert_eq!(2 << 30, parse_memory_size("2GB").unwrap());
+        assert_eq!(64 << 10, parse_memory_size("64kib").unwrap());
+        assert!(parse_memory_size("lots").is_err());
+        assert!(parse_memory_size("99999999999G").is_err());
+    }
+
+    #[test]
+    fn test_required_checkers() {
+        assert_eq!(None, required_checkers(&[]));
+        assert_eq!(
+            None,
+            required_checkers(&[String::from("./lib/custom_checker.rb")])
+        );
+        assert_eq!(
+            Some(HashSet::from(["privacy", "layer"])),
+            required_checkers(&[
+                String::from("packwerk/privacy/checker"),
+                String::from("packwerk/architecture/checker"),
+                String::from("./lib/custom_checker.rb"),
+            ])
+        );
+        assert_eq!(
+            Some(HashSet::from([
+                "privacy",
+                "visibility",
+                "folder_privacy",
+                "layer"
+            ])),
+            required_checkers(&[String::from("packwerk-extensions")])
+        );
+    }
+
+    #[test]
+    fn test_inherit_layers() {
+        let pack = |name: &str, layer: Option<&str>| Pack {
+            name: name.to_owned(),
+            layer: layer.map(String::from),
+            ..Pack::default()
+        };
+        let packs = HashSet::from([
+            pack("packs/product/cart", None),
+            pack("packs/product/admin", Some("utilities")),
+            pack("packs/platform", None),
+        ]);
+        let layer_globs = BTreeMap::from([(
+            String::from("product"),
+            vec![String::from("packs/product/**")],
+        )]);
+        let layers = vec![String::from("product"), String::from("utilities")];
+
+        let packs = inherit_layers(packs, &layer_globs, &layers).unwrap();
+        let mut layers_by_pack: Vec<(&str, Option<&str>)> = packs
+            .iter()
+            .map(|pack| (pack.name.as_str(), pack.layer().map(String::as_str)))
+            .collect();
+        layers_by_pack.sort();
+
+        assert_eq!(
+            vec![
+                ("packs/platform", None),
+                ("packs/product/admin", Some("utilities")),
+                ("packs/product/cart", Some("product")),
+            ],
+            layers_by_pack
+        );
+    }
+
+    #[test]
+    fn test_inherit_layers_from_several_globs() {
+        let packs = HashSet::from([Pack {
+            name: String::from("packs/product/cart"),
+            ..Pack::default()
+        }]);
+        let layer_globs = BTreeMap::from([
+            (
+                String::from("product"),
+                vec![String::from("packs/product/*")],
+            ),
+            (String::from("utilities"), vec![String::from("packs/**")]),
+        ]);
+        let layers = vec![String::from("product"), String::from("utilities")];
+
+        let error = inherit_layers(packs, &layer_globs, &layers).unwrap_err();
+        assert_eq!(
+            "`packs/product/cart` matches the layer_globs of more than one layer: product, utilities",
+            error.to_string()
+        );
+    }
 }
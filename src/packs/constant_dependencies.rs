@@ -13,10 +13,11 @@ pub fn update_dependencies_for_constant(
     configuration: &Configuration,
     constant_name: &str,
 ) -> anyhow::Result<usize> {
-    let (all_references, _sigils) = get_all_references_and_sigils(
-        configuration,
-        &configuration.included_files,
-    )?;
+    let (all_references, _sigils, _parse_errors) =
+        get_all_references_and_sigils(
+            configuration,
+            &configuration.included_files,
+        )?;
     if let Some((defining_pack_name, reference_pack_names_set)) =
         find_defining_and_referencing_packs(&all_references, constant_name)
     {
@@ -69,16 +70,17 @@ fn find_defining_and_referencing_packs(
     constant_name: &str,
 ) -> Option<(String, HashSet<String>)> {
     let mut defining_pack_name_option: Option<&str> = None;
-    let reference_pack_names_set: HashSet<&String> = all_references
+    let reference_pack_names_set: HashSet<&str> = all_references
         .iter()
         .filter_map(|reference| {
-            if reference.constant_name == constant_name {
-                if let Some(defining_pack_name) = &reference.defining_pack_name
+            if *reference.constant_name == *constant_name {
+                if let Some(defining_pack_name) =
+                    reference.defining_pack_name.as_deref()
                 {
-                    if defining_pack_name != &reference.referencing_pack_name {
+                    if defining_pack_name != &*reference.referencing_pack_name {
                         defining_pack_name_option
                             .get_or_insert(defining_pack_name);
-                        return Some(&reference.referencing_pack_name);
+                        return Some(&*reference.referencing_pack_name);
                     }
                 }
             }
@@ -101,42 +103,42 @@ mod tests {
     use std::collections::HashMap;
 
     use super::*;
-    use crate::packs::{PackSet, SourceLocation};
+    use crate::packs::{interner::InternedStr, PackSet, SourceLocation};
 
     fn example_references() -> Vec<Reference> {
         vec![
             Reference {
-                constant_name: String::from("::Bar::BarChild"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/foo"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar::BarChild"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/foo"),
+                relative_referencing_file: InternedStr::from(
                     "packs/foo/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/api/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
             },
             Reference {
-                constant_name: String::from("::Bar::BarChild"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/bar"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar::BarChild"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/bar"),
+                relative_referencing_file: InternedStr::from(
                     "packs/bar/app/services/foo.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/api/bar.rb",
                 )),
                 source_location: SourceLocation { line: 3, column: 1 },
             },
             Reference {
-                constant_name: String::from("::BarChild"),
-                defining_pack_name: Some(String::from("packs/diff_bar")),
-                referencing_pack_name: String::from("packs/baz"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::BarChild"),
+                defining_pack_name: Some(InternedStr::from("packs/diff_bar")),
+                referencing_pack_name: InternedStr::from("packs/baz"),
+                relative_referencing_file: InternedStr::from(
                     "packs/baz/app/services/baz.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/diff_bar/app/api/diff_bar.rb",
                 )),
                 source_location: SourceLocation {
@@ -145,13 +147,13 @@ mod tests {
                 },
             },
             Reference {
-                constant_name: String::from("::Bar"),
-                defining_pack_name: Some(String::from("packs/bar")),
-                referencing_pack_name: String::from("packs/bizz"),
-                relative_referencing_file: String::from(
+                constant_name: InternedStr::from("::Bar"),
+                defining_pack_name: Some(InternedStr::from("packs/bar")),
+                referencing_pack_name: InternedStr::from("packs/bizz"),
+                relative_referencing_file: InternedStr::from(
                     "packs/bizz/app/services/baz.rb",
                 ),
-                relative_defining_file: Some(String::from(
+                relative_defining_file: Some(InternedStr::from(
                     "packs/bar/app/api/bar.rb",
                 )),
                 source_location: SourceLocation {
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -14,7 +18,9 @@ pub struct ConstantResolverConfiguration<'a> {
     pub cache_directory: &'a PathBuf,
     pub cache_enabled: bool,
     pub inflections_path: &'a PathBuf,
+    pub acronyms: &'a HashSet<String>,
     pub autoload_roots: &'a HashMap<PathBuf, String>,
+    pub collapse_dirs: &'a [glob::Pattern],
 }
 
 pub trait ConstantResolver {
@@ -28,3 +34,24 @@ pub trait ConstantResolver {
         &self,
     ) -> &HashMap<String, Vec<ConstantDefinition>>;
 }
+
+// A constant resolver shared by threads, or by the commands run in one
+// process. Resolving only reads it.
+pub type SharedConstantResolver = Arc<dyn ConstantResolver + Send + Sync>;
+
+// The Zeitwerk constant resolvers of a project, with its resolution
+// fallbacks, built once for every app root (see
+// `Configuration::warm_constant_resolvers`)
+#[derive(Clone, Default)]
+pub struct WarmConstantResolvers {
+    pub(crate) by_app_root: HashMap<Option<PathBuf>, SharedConstantResolver>,
+}
+
+impl WarmConstantResolvers {
+    pub(crate) fn get(
+        &self,
+        app_root: &Option<PathBuf>,
+    ) -> Option<SharedConstantResolver> {
+        self.by_app_root.get(app_root).cloned()
+    }
+}
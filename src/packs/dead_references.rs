@@ -0,0 +1,208 @@
+// References to constants that nothing defines, although their namespace is
+// defined in the codebase, e.g. `Billing::LegacyInvoice` once it was deleted or
+// renamed, or misspelled. They aren't attributed to any pack, so `check` skips
+// them, and they often only fail at runtime, e.g. when loaded dynamically.
+// Constants whose namespace isn't defined in the codebase, like `String` or
+// `ActiveRecord::Base`, are assumed to come from Ruby or gems instead.
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::checker::reference::Reference;
+use super::constant_resolver::ConstantDefinition;
+use super::reference_extractor::get_all_references_and_sigils;
+use super::{get_constant_resolver_for_all_files, Configuration};
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct DeadReferences {
+    pub references: usize,
+    // Referencing pack -> its dead references, sorted by file and line
+    pub packs: BTreeMap<String, Vec<DeadReference>>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct DeadReference {
+    pub file: String,
+    pub line: usize,
+    // As referenced, since it couldn't be fully qualified
+    pub constant: String,
+}
+
+impl DeadReferences {
+    pub(crate) fn new(
+        references: &[Reference],
+        definitions: &HashMap<String, Vec<ConstantDefinition>>,
+    ) -> Self {
+        let mut packs: BTreeMap<String, Vec<DeadReference>> = BTreeMap::new();
+        for reference in references {
+            if reference.relative_defining_file.is_some()
+                || !has_defined_namespace(&reference.constant_name, definitions)
+            {
+                continue;
+            }
+            packs
+                .entry(reference.referencing_pack_name.to_string())
+                .or_default()
+                .push(DeadReference {
+                    file: reference.relative_referencing_file.to_string(),
+                    line: reference.source_location.line,
+                    constant: reference.constant_name.to_string(),
+                });
+        }
+        for dead_references in packs.values_mut() {
+            dead_references.sort();
+            dead_references.dedup();
+        }
+        Self {
+            references: packs.values().map(Vec::len).sum(),
+            packs,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.packs.is_empty()
+    }
+}
+
+// Whether the namespace of a constant, e.g. `Billing` of `Billing::Invoice`,
+// is defined in the codebase, within any namespace, since the reference
+// couldn't be resolved to tell which
+fn has_defined_namespace(
+    constant_name: &str,
+    definitions: &HashMap<String, Vec<ConstantDefinition>>,
+) -> bool {
+    let Some((namespace, _)) =
+        constant_name.trim_start_matches("::").rsplit_once("::")
+    else {
+        return false;
+    };
+    let as_constant = format!("::{}", namespace);
+    let as_namespace = format!("::{}::", namespace);
+    definitions.keys().any(|name| {
+        name.ends_with(&as_constant) || name.contains(&as_namespace)
+    })
+}
+
+impl fmt::Display for DeadReferences {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} reference(s) to undefined constants in {} pack(s):",
+            self.references,
+            self.packs.len()
+        )?;
+        for (pack, dead_references) in &self.packs {
+            writeln!(f, "{}", pack)?;
+            for dead_reference in dead_references {
+                writeln!(
+                    f,
+                    "  {}:{} {}",
+                    dead_reference.file,
+                    dead_reference.line,
+                    dead_reference.constant
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The dead references of all included files
+pub(crate) fn find_dead_references(
+    configuration: &Configuration,
+) -> anyhow::Result<DeadReferences> {
+    let constant_resolver = get_constant_resolver_for_all_files(configuration)?;
+    let (references, _sigils, _parse_errors) = get_all_references_and_sigils(
+        configuration,
+        &configuration.included_files,
+    )?;
+    Ok(DeadReferences::new(
+        &references,
+        constant_resolver
+            .fully_qualified_constant_name_to_constant_definition_map(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::interner::InternedStr;
+    use crate::packs::SourceLocation;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn reference(
+        pack: &str,
+        file: &str,
+        line: usize,
+        constant: &str,
+        defining_file: Option<&str>,
+    ) -> Reference {
+        Reference {
+            constant_name: InternedStr::from(constant),
+            defining_pack_name: None,
+            relative_defining_file: defining_file.map(InternedStr::from),
+            referencing_pack_name: InternedStr::from(pack),
+            relative_referencing_file: InternedStr::from(file),
+            source_location: SourceLocation { line, column: 1 },
+        }
+    }
+
+    fn definitions(names: &[&str]) -> HashMap<String, Vec<ConstantDefinition>> {
+        names
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    vec![ConstantDefinition {
+                        fully_qualified_name: name.to_string(),
+                        absolute_path_of_definition: PathBuf::from("a.rb"),
+                    }],
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dead_references() {
+        let references = vec![
+            reference("packs/foo", "packs/foo/b.rb", 3, "Billing::Old", None),
+            reference("packs/foo", "packs/foo/a.rb", 7, "Billing::Old", None),
+            reference("packs/bar", "packs/bar/a.rb", 2, "::Foo::Gone", None),
+            // Defined, though outside of any pack
+            reference(
+                "packs/foo",
+                "packs/foo/a.rb",
+                1,
+                "::Billing::Invoice",
+                Some("lib/billing/invoice.rb"),
+            ),
+            // Probably from a gem
+            reference(
+                "packs/foo",
+                "packs/foo/a.rb",
+                2,
+                "ActiveRecord::Base",
+                None,
+            ),
+            reference("packs/foo", "packs/foo/a.rb", 4, "Typo", None),
+        ];
+        let definitions = definitions(&["::Billing::Invoice", "::Admin::Foo"]);
+
+        let dead_references = DeadReferences::new(&references, &definitions);
+
+        assert_eq!(3, dead_references.references);
+        assert_eq!(
+            dead_references.to_string(),
+            "\
+3 reference(s) to undefined constants in 2 pack(s):
+packs/bar
+  packs/bar/a.rb:2 ::Foo::Gone
+packs/foo
+  packs/foo/a.rb:7 Billing::Old
+  packs/foo/b.rb:3 Billing::Old
+"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+// Exports the constant definitions we resolve, so that other tools (e.g. code search
+// or docs generators) can reuse the resolver rather than reimplementing Zeitwerk.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use super::constant_resolver::ConstantResolver;
+use super::Configuration;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct DefinitionLocation {
+    pub file: String,
+    // None if the file is not included in any pack
+    pub pack: Option<String>,
+}
+
+// Fully qualified constant name -> where it is defined. Constants with more than
+// one definition are ambiguous.
+pub(crate) type DefinitionIndex = BTreeMap<String, Vec<DefinitionLocation>>;
+
+pub(crate) fn build_index(
+    configuration: &Configuration,
+    constant_resolver: &dyn ConstantResolver,
+) -> anyhow::Result<DefinitionIndex> {
+    let mut index = DefinitionIndex::new();
+    for (name, definitions) in constant_resolver
+        .fully_qualified_constant_name_to_constant_definition_map()
+    {
+        let mut locations = Vec::new();
+        for definition in definitions {
+            let absolute_path = &definition.absolute_path_of_definition;
+            let relative_path = absolute_path
+                .strip_prefix(&configuration.absolute_root)
+                .context("Failed to strip project root from definition")?;
+            let pack = configuration
+                .pack_set
+                .for_file(absolute_path)?
+                .map(|pack| pack.name.clone());
+            locations.push(DefinitionLocation {
+                file: relative_path.to_string_lossy().to_string(),
+                pack,
+            });
+        }
+        locations.sort();
+        index.insert(name.clone(), locations);
+    }
+    Ok(index)
+}
+
+// SQL statements that load the index into SQLite, e.g.
+// `pks export-definitions --format sql | sqlite3 definitions.db`
+pub(crate) fn to_sql(index: &DefinitionIndex) -> String {
+    let mut sql = String::from(
+        "DROP TABLE IF EXISTS definitions;
+CREATE TABLE definitions (constant TEXT NOT NULL, file TEXT NOT NULL, pack TEXT);
+CREATE INDEX definitions_constant ON definitions (constant);
+BEGIN TRANSACTION;
+",
+    );
+    for (name, locations) in index {
+        for location in locations {
+            let pack = location
+                .pack
+                .as_deref()
+                .map(sql_string)
+                .unwrap_or_else(|| String::from("NULL"));
+            // Writing to a String cannot fail
+            let _ = writeln!(
+                sql,
+                "INSERT INTO definitions VALUES ({}, {}, {});",
+                sql_string(name),
+                sql_string(&location.file),
+                pack
+            );
+        }
+    }
+    sql.push_str("COMMIT;\n");
+    sql
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::{configuration, get_zeitwerk_constant_resolver};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_build_index() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/simple_app")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+        let constant_resolver = get_zeitwerk_constant_resolver(
+            &configuration.pack_set,
+            &configuration.constant_resolver_configuration(),
+        );
+
+        let index =
+            build_index(&configuration, constant_resolver.as_ref()).unwrap();
+
+        assert_eq!(
+            index["::Bar"],
+            vec![DefinitionLocation {
+                file: String::from("packs/bar/app/services/bar.rb"),
+                pack: Some(String::from("packs/bar")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_to_sql() {
+        let index = DefinitionIndex::from([(
+            String::from("::Foo"),
+            vec![
+                DefinitionLocation {
+                    file: String::from("packs/foo/app/models/foo.rb"),
+                    pack: Some(String::from("packs/foo")),
+                },
+                DefinitionLocation {
+                    file: String::from("lib/o'foo.rb"),
+                    pack: None,
+                },
+            ],
+        )]);
+
+        let expected = "\
+DROP TABLE IF EXISTS definitions;
+CREATE TABLE definitions (constant TEXT NOT NULL, file TEXT NOT NULL, pack TEXT);
+CREATE INDEX definitions_constant ON definitions (constant);
+BEGIN TRANSACTION;
+INSERT INTO definitions VALUES ('::Foo', 'packs/foo/app/models/foo.rb', 'packs/foo');
+INSERT INTO definitions VALUES ('::Foo', 'lib/o''foo.rb', NULL);
+COMMIT;
+";
+        assert_eq!(expected, to_sql(&index));
+    }
+}
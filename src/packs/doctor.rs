@@ -0,0 +1,496 @@
+// Diagnoses problems with the environment and configuration of a project,
+// which would otherwise surface as confusing errors or missed violations
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::caching::per_file_cache::CacheEntry;
+use super::file_utils::expand_glob;
+use super::pack::Pack;
+use super::package_todo::PackageTodo;
+use super::walk_directory::walk_directory;
+use super::{configuration, parsing, raw_configuration, Configuration};
+
+// The number of files parsed to estimate how long parsing all of them takes
+const PARSE_SAMPLE_SIZE: usize = 50;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct Finding {
+    pub severity: Severity,
+    // What was checked, e.g. `package_todo` or `cache`
+    pub check: &'static str,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, check: &'static str, message: String) -> Self {
+        Self {
+            severity,
+            check,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.severity, self.check, self.message)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Diagnosis {
+    // Sorted from the most severe
+    pub findings: Vec<Finding>,
+}
+
+impl Diagnosis {
+    pub(crate) fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+}
+
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            writeln!(f, "{}", finding)?;
+        }
+        writeln!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        )
+    }
+}
+
+// `apply_overrides` applies the global command line options, e.g. --no-cache,
+// once the configuration could be loaded
+pub(crate) fn diagnose(
+    absolute_root: &Path,
+    apply_overrides: &dyn Fn(&mut Configuration) -> anyhow::Result<()>,
+) -> Diagnosis {
+    let mut findings = vec![];
+    if let Some(mut configuration) =
+        load_configuration(absolute_root, &mut findings)
+    {
+        match apply_overrides(&mut configuration) {
+            Ok(()) => {
+                findings.extend(nested_pack_findings(&configuration));
+                findings.extend(autoload_findings(&configuration));
+                findings.extend(cache_findings(&configuration));
+                findings.extend(parse_timing_findings(&configuration));
+            }
+            Err(error) => findings.push(Finding::new(
+                Severity::Error,
+                "configuration",
+                format!("{:#}", error),
+            )),
+        }
+    }
+    // Stable, so findings of the same severity keep the order of the checks
+    findings.sort_by_key(|finding| finding.severity);
+    Diagnosis { findings }
+}
+
+// Loads the configuration, reporting every pack that could not be loaded,
+// rather than only the first one
+fn load_configuration(
+    absolute_root: &Path,
+    findings: &mut Vec<Finding>,
+) -> Option<Configuration> {
+    let raw_config = match raw_configuration::get(absolute_root) {
+        Ok(raw_config) => raw_config,
+        Err(error) => {
+            findings.push(Finding::new(
+                Severity::Error,
+                "configuration",
+                format!("{:#}", error),
+            ));
+            return None;
+        }
+    };
+    let mut walk_directory_result =
+        match walk_directory(absolute_root.to_path_buf(), &raw_config) {
+            Ok(walk_directory_result) => walk_directory_result,
+            Err(error) => {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "files",
+                    format!("{:#}", error),
+                ));
+                return None;
+            }
+        };
+
+    let mut pack_errors =
+        std::mem::take(&mut walk_directory_result.pack_errors);
+    if !pack_errors.is_empty() {
+        pack_errors.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (package_yml, error) in pack_errors {
+            let check =
+                if loads_without_package_todo(&package_yml, absolute_root) {
+                    "package_todo"
+                } else {
+                    "package_yml"
+                };
+            findings.push(Finding::new(
+                Severity::Error,
+                check,
+                format!("{:#}", error),
+            ));
+        }
+        return None;
+    }
+
+    match configuration::from_raw(
+        absolute_root,
+        raw_config,
+        walk_directory_result,
+        &0,
+    ) {
+        Ok(configuration) => Some(configuration),
+        Err(error) => {
+            findings.push(Finding::new(
+                Severity::Error,
+                "configuration",
+                format!("{:#}", error),
+            ));
+            None
+        }
+    }
+}
+
+// Whether only the package_todo.yml of a pack that failed to load is invalid
+fn loads_without_package_todo(
+    package_yml: &Path,
+    absolute_root: &Path,
+) -> bool {
+    std::fs::read_to_string(package_yml).is_ok_and(|contents| {
+        Pack::from_contents(
+            package_yml,
+            absolute_root,
+            &contents,
+            PackageTodo::default(),
+        )
+        .is_ok()
+    })
+}
+
+// Files under a nested pack belong to it rather than to the enclosing pack,
+// which is easily overlooked when moving code around
+fn nested_pack_findings(configuration: &Configuration) -> Vec<Finding> {
+    let packs: Vec<&Pack> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter(|pack| pack.name != ".")
+        .collect();
+    let mut findings = vec![];
+    for pack in &packs {
+        // The closest enclosing pack
+        let enclosing_pack = packs
+            .iter()
+            .filter(|other| {
+                other.name != pack.name
+                    && pack.relative_path.starts_with(&other.relative_path)
+            })
+            .max_by_key(|other| other.relative_path.components().count());
+        if let Some(enclosing_pack) = enclosing_pack {
+            findings.push(Finding::new(
+                Severity::Warning,
+                "pack_paths",
+                format!(
+                    "`{}` is nested in `{}`, so files under it belong to `{}` rather than `{}`",
+                    pack.name, enclosing_pack.name, pack.name, enclosing_pack.name
+                ),
+            ));
+        }
+    }
+    findings.sort_by(|a, b| a.message.cmp(&b.message));
+    findings
+}
+
+// Constants are only resolved within autoload directories, so references to
+// the constants of a pack without any are missed
+fn autoload_findings(configuration: &Configuration) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    let mut configured_roots: Vec<&PathBuf> =
+        configuration.autoload_roots.keys().collect();
+    configured_roots.sort();
+    for configured_root in &configured_roots {
+        let pattern = configuration.absolute_root.join(configured_root);
+        if expand_glob(&pattern.to_string_lossy()).is_empty() {
+            findings.push(Finding::new(
+                Severity::Warning,
+                "autoload",
+                format!(
+                    "`autoload_roots` in packwerk.yml lists `{}`, which matches no directory",
+                    configured_root.display()
+                ),
+            ));
+        }
+    }
+
+    // Ruby files outside of tests, by the pack they belong to
+    let mut ruby_files_by_pack: HashMap<&str, usize> = HashMap::new();
+    for absolute_path in &configuration.included_files {
+        let is_ruby = absolute_path.extension().is_some_and(|ext| ext == "rb");
+        let is_test = absolute_path
+            .strip_prefix(&configuration.absolute_root)
+            .is_ok_and(|relative_path| {
+                configuration.is_test_file(&relative_path.to_string_lossy())
+            });
+        if let (true, false, Ok(Some(owner))) = (
+            is_ruby,
+            is_test,
+            configuration.pack_set.for_file(absolute_path),
+        ) {
+            *ruby_files_by_pack.entry(owner.name.as_str()).or_default() += 1;
+        }
+    }
+
+    for pack in &configuration.pack_set.packs {
+        if pack.name == "."
+            || !pack.default_autoload_roots().is_empty()
+            || configured_roots
+                .iter()
+                .any(|root| root.starts_with(&pack.relative_path))
+        {
+            continue;
+        }
+        let ruby_files = ruby_files_by_pack
+            .get(pack.name.as_str())
+            .copied()
+            .unwrap_or_default();
+        if ruby_files > 0 {
+            findings.push(Finding::new(
+                Severity::Warning,
+                "autoload",
+                format!(
+                    "`{}` has {} Ruby file(s) outside of tests, but no `app/*` directories, so references to its constants can't be resolved. Move them under `app/`, or list their directory under `autoload_roots` in packwerk.yml",
+                    pack.name, ruby_files
+                ),
+            ));
+        }
+    }
+    findings
+}
+
+fn cache_findings(configuration: &Configuration) -> Vec<Finding> {
+    if !configuration.cache_enabled {
+        return vec![Finding::new(
+            Severity::Info,
+            "cache",
+            String::from("Caching is disabled, so every run parses every file"),
+        )];
+    }
+
+    let cache_directory = configuration.parser_cache_directory();
+    let relative_cache_directory = cache_directory
+        .strip_prefix(&configuration.absolute_root)
+        .unwrap_or(&cache_directory)
+        .display()
+        .to_string();
+    let entries: Vec<PathBuf> = match std::fs::read_dir(&cache_directory) {
+        Ok(read_dir) => read_dir
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect(),
+        Err(_) => {
+            return vec![Finding::new(
+                Severity::Info,
+                "cache",
+                format!(
+                "The cache at {} is empty, so the next run parses every file",
+                relative_cache_directory
+            ),
+            )]
+        }
+    };
+
+    let mut findings = vec![];
+    let probe = cache_directory.join(".pks-doctor");
+    if let Err(error) =
+        std::fs::write(&probe, "").and_then(|()| std::fs::remove_file(&probe))
+    {
+        findings.push(Finding::new(
+            Severity::Error,
+            "cache",
+            format!(
+                "The cache at {} isn't writable ({}). Fix its permissions, or pass --no-cache",
+                relative_cache_directory, error
+            ),
+        ));
+    }
+
+    let mut bytes = 0;
+    let mut unreadable = 0;
+    for entry in &entries {
+        let contents = std::fs::read(entry).unwrap_or_default();
+        bytes += contents.len();
+        if serde_json::from_slice::<CacheEntry>(&contents).is_err() {
+            unreadable += 1;
+        }
+    }
+    if unreadable > 0 {
+        findings.push(Finding::new(
+            Severity::Warning,
+            "cache",
+            format!(
+                "{} of the {} entries of the cache at {} can't be read, so those files are parsed on every run. Run `pks delete-cache`",
+                unreadable,
+                entries.len(),
+                relative_cache_directory
+            ),
+        ));
+    }
+    findings.push(Finding::new(
+        Severity::Info,
+        "cache",
+        format!(
+            "The cache at {} holds {} entries ({:.1} MB)",
+            relative_cache_directory,
+            entries.len(),
+            bytes as f64 / 1_000_000.0
+        ),
+    ));
+    findings
+}
+
+// Parses a sample of the included files, spread over all of them, without the
+// cache, to estimate how long a run without a cache takes
+fn parse_timing_findings(configuration: &Configuration) -> Vec<Finding> {
+    let mut files: Vec<&PathBuf> =
+        configuration.included_files.iter().collect();
+    if files.is_empty() {
+        return vec![Finding::new(
+            Severity::Warning,
+            "parsing",
+            String::from(
+                "No files are included. Check `include` and `exclude` in packwerk.yml",
+            ),
+        )];
+    }
+    files.sort();
+    let step = files.len().div_ceil(PARSE_SAMPLE_SIZE);
+    let sample: Vec<&PathBuf> = files.iter().step_by(step).copied().collect();
+
+    let started = Instant::now();
+    let failed = sample
+        .iter()
+        .filter(|path| parsing::process_file(path, configuration).is_err())
+        .count();
+    let elapsed = started.elapsed();
+    let per_file = elapsed / sample.len() as u32;
+    let estimate = per_file * files.len() as u32;
+
+    let mut findings = vec![Finding::new(
+        Severity::Info,
+        "parsing",
+        format!(
+            "Parsed {} sample file(s) in {}, {} per file, so parsing all {} included file(s) without the cache takes about {} on one thread",
+            sample.len(),
+            format_duration(elapsed),
+            format_duration(per_file),
+            files.len(),
+            format_duration(estimate)
+        ),
+    )];
+    if failed > 0 {
+        findings.push(Finding::new(
+            Severity::Warning,
+            "parsing",
+            format!(
+                "{} of the {} sample file(s) could not be read. Run `pks list-included-files` to check which files are included",
+                failed,
+                sample.len()
+            ),
+        ));
+    }
+    findings
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_nested_pack_findings() {
+        let mut configuration = Configuration::default();
+        for name in ["packs/foo", "packs/foo/bar", "packs/foo/bar/baz"] {
+            configuration.pack_set.packs.push(Pack {
+                name: String::from(name),
+                relative_path: PathBuf::from(name),
+                ..Pack::default()
+            });
+        }
+
+        assert_eq!(
+            nested_pack_findings(&configuration),
+            vec![
+                Finding::new(
+                    Severity::Warning,
+                    "pack_paths",
+                    String::from("`packs/foo/bar/baz` is nested in `packs/foo/bar`, so files under it belong to `packs/foo/bar/baz` rather than `packs/foo/bar`"),
+                ),
+                Finding::new(
+                    Severity::Warning,
+                    "pack_paths",
+                    String::from("`packs/foo/bar` is nested in `packs/foo`, so files under it belong to `packs/foo/bar` rather than `packs/foo`"),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let diagnosis = Diagnosis {
+            findings: vec![
+                Finding::new(Severity::Error, "cache", String::from("a")),
+                Finding::new(Severity::Warning, "autoload", String::from("b")),
+            ],
+        };
+
+        assert_eq!(
+            diagnosis.to_string(),
+            "[error] cache: a\n[warning] autoload: b\n1 error(s), 1 warning(s)\n"
+        );
+    }
+}
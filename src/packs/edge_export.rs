@@ -0,0 +1,180 @@
+// Every edge between packs that code references, with how many references
+// make up the edge and how many of them are violations, as input for graph
+// tooling and dashboards outside of pks.
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::checker::find_violations_in_references;
+use super::reference_extractor::get_all_references_and_sigils;
+use super::{Configuration, SourceLocation};
+
+// The columns of the CSV output, in order
+const VIOLATION_TYPES: [&str; 6] = [
+    "dependency",
+    "privacy",
+    "visibility",
+    "layer",
+    "folder_privacy",
+    "tag_policy",
+];
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct Edge {
+    pub from: String,
+    pub to: String,
+    // Whether `from` declares a dependency on `to`
+    pub declared: bool,
+    pub references: usize,
+    // References with at least one violation, which may be of several types
+    pub violations: usize,
+    pub violations_by_type: BTreeMap<String, usize>,
+}
+
+// Edges sorted by referencing pack, then defining pack. References within a
+// pack, or to constants defined outside of any pack, aren't edges.
+pub(crate) fn export_edges(
+    configuration: &Configuration,
+) -> anyhow::Result<Vec<Edge>> {
+    let (references, sigils, _parse_errors) = get_all_references_and_sigils(
+        configuration,
+        &configuration.included_files,
+    )?;
+    let violations =
+        find_violations_in_references(configuration, &references, &sigils)?;
+
+    // The violation types of each reference, by file, location and constant
+    let mut violation_types: HashMap<
+        (&str, &SourceLocation, &str),
+        HashSet<&str>,
+    > = HashMap::new();
+    for violation in &violations {
+        let identifier = &violation.identifier;
+        violation_types
+            .entry((
+                identifier.file.as_str(),
+                &violation.source_location,
+                identifier.constant_name.as_str(),
+            ))
+            .or_default()
+            .insert(identifier.violation_type.as_str());
+    }
+
+    let mut edges: BTreeMap<(&str, &str), Edge> = BTreeMap::new();
+    for reference in &references {
+        let Some(defining_pack_name) = &reference.defining_pack_name else {
+            continue;
+        };
+        let referencing_pack_name = &reference.referencing_pack_name;
+        if defining_pack_name == referencing_pack_name {
+            continue;
+        }
+
+        let edge = edges
+            .entry((
+                referencing_pack_name.as_ref(),
+                defining_pack_name.as_ref(),
+            ))
+            .or_insert_with(|| Edge {
+                from: referencing_pack_name.to_string(),
+                to: defining_pack_name.to_string(),
+                declared: configuration
+                    .pack_set
+                    .for_pack(referencing_pack_name)
+                    .is_ok_and(|pack| {
+                        pack.dependencies.contains(defining_pack_name.as_ref())
+                    }),
+                ..Edge::default()
+            });
+        edge.references += 1;
+        if let Some(types) = violation_types.get(&(
+            reference.relative_referencing_file.as_ref(),
+            &reference.source_location,
+            reference.constant_name.as_ref(),
+        )) {
+            edge.violations += 1;
+            for violation_type in types {
+                *edge
+                    .violations_by_type
+                    .entry(violation_type.to_string())
+                    .or_default() += 1;
+            }
+        }
+    }
+    Ok(edges.into_values().collect())
+}
+
+pub(crate) fn to_csv(edges: &[Edge]) -> String {
+    let mut csv = format!(
+        "from,to,declared,references,violations,{}\n",
+        VIOLATION_TYPES.join(",")
+    );
+    for edge in edges {
+        let by_type: Vec<String> = VIOLATION_TYPES
+            .iter()
+            .map(|violation_type| {
+                edge.violations_by_type
+                    .get(*violation_type)
+                    .copied()
+                    .unwrap_or(0)
+                    .to_string()
+            })
+            .collect();
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&edge.from),
+            csv_field(&edge.to),
+            edge.declared,
+            edge.references,
+            edge.violations,
+            by_type.join(",")
+        ));
+    }
+    csv
+}
+
+// Pack names are paths, but may still contain commas or quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_to_csv() {
+        let edges = vec![
+            Edge {
+                from: "packs/foo".to_string(),
+                to: "packs/bar".to_string(),
+                declared: false,
+                references: 3,
+                violations: 2,
+                violations_by_type: BTreeMap::from([
+                    ("dependency".to_string(), 2),
+                    ("privacy".to_string(), 1),
+                ]),
+            },
+            Edge {
+                from: "packs/foo".to_string(),
+                to: "packs/a,b".to_string(),
+                declared: true,
+                references: 1,
+                ..Edge::default()
+            },
+        ];
+
+        assert_eq!(
+            "from,to,declared,references,violations,dependency,privacy,visibility,layer,folder_privacy,tag_policy\n\
+             packs/foo,packs/bar,false,3,2,2,1,0,0,0,0\n\
+             packs/foo,\"packs/a,b\",true,1,0,0,0,0,0,0,0\n",
+            to_csv(&edges)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+// Rolls an enforcement out to many packs at once: the setting is flipped in
+// each of their package.yml files, and the violations it results in are
+// recorded in package_todo.yml files, to be burned down over time.
+use std::collections::BTreeMap;
+
+use anyhow::bail;
+
+use super::checker;
+use super::pack::{write_pack_to_disk, CheckerSetting, Pack};
+use super::Configuration;
+
+const ENFORCEMENTS: [&str; 5] = [
+    "enforce_dependencies",
+    "enforce_privacy",
+    "enforce_visibility",
+    "enforce_layers",
+    "enforce_folder_privacy",
+];
+
+// Sets `enforcement` to `setting` in the packs matching `patterns`, returning
+// the packs whose package.yml changed, sorted
+pub(crate) fn enable_enforcement(
+    configuration: &Configuration,
+    enforcement: &str,
+    setting: CheckerSetting,
+    patterns: &[String],
+) -> anyhow::Result<Vec<String>> {
+    if !ENFORCEMENTS.contains(&enforcement) {
+        bail!(
+            "Unknown enforcement `{}`, expected one of: {}",
+            enforcement,
+            ENFORCEMENTS.join(", ")
+        );
+    }
+    let pack_names = checker::packs_matching(configuration, patterns)?;
+
+    let mut enabled_packs: Vec<String> = Vec::new();
+    for pack in &configuration.pack_set.packs {
+        if !pack_names.contains(&pack.name) {
+            continue;
+        }
+        let mut enabled_pack = pack.clone();
+        let current = setting_mut(&mut enabled_pack, enforcement);
+        if current.as_ref() == Some(&setting) {
+            continue;
+        }
+        *current = Some(setting.clone());
+        write_pack_to_disk(&enabled_pack)?;
+        enabled_packs.push(pack.name.clone());
+    }
+    enabled_packs.sort();
+    Ok(enabled_packs)
+}
+
+// The number of todos each pack gained between two configurations, for packs
+// that gained any
+pub(crate) fn new_todos_by_pack(
+    before: &Configuration,
+    after: &Configuration,
+) -> BTreeMap<String, usize> {
+    let todos_before = todos_by_pack(before);
+    todos_by_pack(after)
+        .into_iter()
+        .filter_map(|(pack_name, todos)| {
+            let before = todos_before.get(&pack_name).copied().unwrap_or(0);
+            (todos > before).then(|| (pack_name, todos - before))
+        })
+        .collect()
+}
+
+fn setting_mut<'a>(
+    pack: &'a mut Pack,
+    enforcement: &str,
+) -> &'a mut Option<CheckerSetting> {
+    match enforcement {
+        "enforce_dependencies" => &mut pack.enforce_dependencies,
+        "enforce_privacy" => &mut pack.enforce_privacy,
+        "enforce_visibility" => &mut pack.enforce_visibility,
+        "enforce_layers" => &mut pack.enforce_layers,
+        "enforce_folder_privacy" => &mut pack.enforce_folder_privacy,
+        _ => unreachable!("unknown enforcement `{}`", enforcement),
+    }
+}
+
+// The violations recorded in the package_todo.yml file of each pack
+fn todos_by_pack(configuration: &Configuration) -> BTreeMap<String, usize> {
+    configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| (pack.name.clone(), pack.all_violations().len()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_setting_mut() {
+        let mut pack = Pack::default();
+        for enforcement in ENFORCEMENTS {
+            *setting_mut(&mut pack, enforcement) = Some(CheckerSetting::True);
+        }
+
+        assert_eq!(Some(CheckerSetting::True), pack.enforce_dependencies);
+        assert_eq!(Some(CheckerSetting::True), pack.enforce_privacy);
+        assert_eq!(Some(CheckerSetting::True), pack.enforce_visibility);
+        assert_eq!(Some(CheckerSetting::True), pack.enforce_layers);
+        assert_eq!(Some(CheckerSetting::True), pack.enforce_folder_privacy);
+    }
+}
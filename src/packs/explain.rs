@@ -0,0 +1,431 @@
+// Explains how `check` arrived at each violation: how the referenced constant
+// was resolved, which autoload path defines it, which packs the referencing and
+// defining files belong to, and which checkers reported it.
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use super::checker::Violation;
+use super::constant_resolver::{ConstantDefinition, SharedConstantResolver};
+use super::file_utils::expand_glob;
+use super::parsing::{process_file, UnresolvedReference};
+use super::{get_constant_resolver_for_all_files, Configuration};
+
+// One fully qualified guess at what a reference refers to, in the order Ruby
+// (and the Zeitwerk resolver) tries them
+#[derive(Debug, PartialEq, Eq)]
+struct Candidate {
+    fully_qualified_name: String,
+    defined: bool,
+}
+
+// Mirrors ZeitwerkConstantResolver: each enclosing namespace is tried from
+// the innermost outwards, until one that defines the first segment of the
+// name. Within it, the parent constants are tried, since e.g. `Foo::Bar::Baz`
+// may be defined in the file for `Foo::Bar`
+fn candidates(
+    name: &str,
+    namespace_path: &[String],
+    definitions: &HashMap<String, Vec<ConstantDefinition>>,
+) -> Vec<Candidate> {
+    let (namespace_path, name) = match name.strip_prefix("::") {
+        Some(name) => (&[][..], name),
+        None => (namespace_path, name),
+    };
+    let segments: Vec<&str> = name.split("::").collect();
+    let qualify = |namespace_length: usize, name_length: usize| {
+        let mut parts: Vec<&str> = namespace_path[..namespace_length]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        let name = segments[..name_length].join("::");
+        parts.push(&name);
+        format!("::{}", parts.join("::"))
+    };
+
+    let mut candidates = Vec::new();
+    for namespace_length in (0..=namespace_path.len()).rev() {
+        let fully_qualified_name = qualify(namespace_length, segments.len());
+        let defined = definitions.contains_key(&fully_qualified_name);
+        candidates.push(Candidate {
+            fully_qualified_name,
+            defined,
+        });
+        if defined {
+            return candidates;
+        }
+
+        let first_segment = qualify(namespace_length, 1);
+        let namespace_prefix = format!("{}::", first_segment);
+        let is_namespace = definitions.contains_key(&first_segment)
+            || definitions
+                .keys()
+                .any(|name| name.starts_with(&namespace_prefix));
+        if !is_namespace {
+            continue;
+        }
+        for name_length in (1..segments.len()).rev() {
+            let fully_qualified_name = qualify(namespace_length, name_length);
+            let defined = definitions.contains_key(&fully_qualified_name);
+            candidates.push(Candidate {
+                fully_qualified_name,
+                defined,
+            });
+            if defined {
+                break;
+            }
+        }
+        return candidates;
+    }
+    candidates
+}
+
+fn relative<'a>(configuration: &Configuration, path: &'a Path) -> &'a Path {
+    path.strip_prefix(&configuration.absolute_root)
+        .unwrap_or(path)
+}
+
+// The default autoload roots of the pack, plus any configured in packwerk.yml
+// that are within it
+fn autoload_roots(
+    configuration: &Configuration,
+    pack_name: &str,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let pack = configuration.pack_set.for_pack(pack_name)?;
+    let pack_root = pack.yml.parent().unwrap_or(&configuration.absolute_root);
+    let mut roots = pack.default_autoload_roots();
+    for relative_root in configuration.autoload_roots.keys() {
+        let absolute_root = configuration.absolute_root.join(relative_root);
+        roots.extend(
+            expand_glob(absolute_root.to_str().unwrap_or_default())
+                .into_iter()
+                .filter(|root| root.starts_with(pack_root)),
+        );
+    }
+    roots.sort();
+    roots.dedup();
+    Ok(roots)
+}
+
+fn write_ownership(
+    out: &mut String,
+    configuration: &Configuration,
+    relative_file: &Path,
+) -> anyhow::Result<()> {
+    let absolute_file = configuration.absolute_root.join(relative_file);
+    match configuration.pack_set.for_file(&absolute_file)? {
+        Some(pack) => writeln!(
+            out,
+            "    {} belongs to `{}`, since {} is the nearest package.yml",
+            relative_file.display(),
+            pack.name,
+            pack.relative_yml().display()
+        )?,
+        None => writeln!(
+            out,
+            "    {} does not belong to any pack",
+            relative_file.display()
+        )?,
+    }
+    Ok(())
+}
+
+fn write_resolution(
+    out: &mut String,
+    reference: Option<&UnresolvedReference>,
+    definitions: &HashMap<String, Vec<ConstantDefinition>>,
+) -> anyhow::Result<()> {
+    let Some(reference) = reference else {
+        writeln!(
+            out,
+            "    The reference could not be found by re-parsing the file"
+        )?;
+        return Ok(());
+    };
+    if reference.namespace_path.is_empty() {
+        writeln!(
+            out,
+            "    `{}` is referenced at the top level",
+            reference.name
+        )?;
+    } else {
+        writeln!(
+            out,
+            "    `{}` is referenced within `{}`",
+            reference.name,
+            reference.namespace_path.join("::")
+        )?;
+    }
+    let tried: Vec<String> =
+        candidates(&reference.name, &reference.namespace_path, definitions)
+            .into_iter()
+            .map(|candidate| {
+                format!(
+                    "`{}` ({})",
+                    candidate.fully_qualified_name,
+                    if candidate.defined {
+                        "defined"
+                    } else {
+                        "not defined"
+                    }
+                )
+            })
+            .collect();
+    writeln!(out, "    Tried {}", tried.join(", "))?;
+    Ok(())
+}
+
+fn write_definitions(
+    out: &mut String,
+    configuration: &Configuration,
+    constant_name: &str,
+    defining_pack_name: &str,
+    definitions: &HashMap<String, Vec<ConstantDefinition>>,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let defining_files: Vec<PathBuf> = definitions
+        .get(constant_name)
+        .map(|definitions| {
+            definitions
+                .iter()
+                .map(|d| d.absolute_path_of_definition.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    for defining_file in &defining_files {
+        writeln!(
+            out,
+            "    `{}` is defined in {}",
+            constant_name,
+            relative(configuration, defining_file).display()
+        )?;
+    }
+
+    writeln!(out, "  Autoload paths of `{}`:", defining_pack_name)?;
+    let roots = autoload_roots(configuration, defining_pack_name)?;
+    if roots.is_empty() {
+        writeln!(out, "    (none)")?;
+    }
+    for root in roots {
+        let defines = defining_files.iter().any(|file| file.starts_with(&root));
+        writeln!(
+            out,
+            "    {}{}",
+            relative(configuration, &root).display(),
+            if defines {
+                " (contains the definition)"
+            } else {
+                ""
+            }
+        )?;
+    }
+    Ok(defining_files)
+}
+
+// Violations are explained once per reference, listing every checker that
+// reported it. `constant` limits the explanations to references to it.
+pub(crate) fn explain<'a>(
+    configuration: &Configuration,
+    violations: impl Iterator<Item = &'a Violation>,
+    constant: Option<&str>,
+) -> anyhow::Result<String> {
+    let constant = constant.map(|name| {
+        if name.starts_with("::") {
+            name.to_owned()
+        } else {
+            format!("::{}", name)
+        }
+    });
+
+    let mut violations_by_reference: BTreeMap<
+        (&str, usize, usize, &str),
+        Vec<&Violation>,
+    > = BTreeMap::new();
+    for violation in violations {
+        let identifier = &violation.identifier;
+        if constant
+            .as_ref()
+            .is_some_and(|constant| constant != &identifier.constant_name)
+        {
+            continue;
+        }
+        violations_by_reference
+            .entry((
+                identifier.file.as_str(),
+                violation.source_location.line,
+                violation.source_location.column,
+                identifier.constant_name.as_str(),
+            ))
+            .or_default()
+            .push(violation);
+    }
+
+    let mut out = String::new();
+    if violations_by_reference.is_empty() {
+        match &constant {
+            Some(constant) => {
+                writeln!(out, "No violations on `{}` to explain", constant)?
+            }
+            None => writeln!(out, "No violations to explain")?,
+        }
+        return Ok(out);
+    }
+
+    let constant_resolver: SharedConstantResolver =
+        get_constant_resolver_for_all_files(configuration)?;
+    let definitions = constant_resolver
+        .fully_qualified_constant_name_to_constant_definition_map();
+    let mut unresolved_references_by_file: HashMap<
+        &str,
+        Vec<UnresolvedReference>,
+    > = HashMap::new();
+
+    for ((file, line, column, constant_name), violations) in
+        violations_by_reference
+    {
+        if !unresolved_references_by_file.contains_key(file) {
+            let processed_file = process_file(
+                &configuration.absolute_root.join(file),
+                configuration,
+            )
+            .context(format!("Failed to parse {}", file))?;
+            unresolved_references_by_file
+                .insert(file, processed_file.unresolved_references);
+        }
+        let reference = unresolved_references_by_file[file].iter().find(|r| {
+            r.location.start_row == line && r.location.start_col == column
+        });
+        let defining_pack_name =
+            violations[0].identifier.defining_pack_name.as_str();
+
+        writeln!(
+            out,
+            "{}:{}:{} references `{}`",
+            file, line, column, constant_name
+        )?;
+        writeln!(out, "  Resolution:")?;
+        write_resolution(&mut out, reference, definitions)?;
+        let defining_files = write_definitions(
+            &mut out,
+            configuration,
+            constant_name,
+            defining_pack_name,
+            definitions,
+        )?;
+
+        writeln!(out, "  Pack ownership:")?;
+        write_ownership(&mut out, configuration, Path::new(file))?;
+        for defining_file in &defining_files {
+            write_ownership(
+                &mut out,
+                configuration,
+                relative(configuration, defining_file),
+            )?;
+        }
+
+        writeln!(out, "  Reported by:")?;
+        for violation in violations {
+            writeln!(
+                out,
+                "    {}{}: {}",
+                violation.identifier.violation_type,
+                if violation.identifier.strict {
+                    " (strict)"
+                } else {
+                    ""
+                },
+                violation.description()
+            )?;
+        }
+        writeln!(out)?;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn definitions(names: &[&str]) -> HashMap<String, Vec<ConstantDefinition>> {
+        names
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    vec![ConstantDefinition {
+                        fully_qualified_name: name.to_string(),
+                        absolute_path_of_definition: PathBuf::from("foo.rb"),
+                    }],
+                )
+            })
+            .collect()
+    }
+
+    fn candidate_names(candidates: Vec<Candidate>) -> Vec<String> {
+        candidates
+            .into_iter()
+            .map(|candidate| candidate.fully_qualified_name)
+            .collect()
+    }
+
+    #[test]
+    fn test_candidates_traverse_the_namespace_path() {
+        let namespace_path = vec![String::from("Foo"), String::from("Bar")];
+
+        let actual =
+            candidates("Baz", &namespace_path, &definitions(&["::Foo::Baz"]));
+
+        assert_eq!(
+            vec!["::Foo::Bar::Baz", "::Foo::Baz"],
+            candidate_names(actual)
+        );
+    }
+
+    #[test]
+    fn test_candidates_fall_back_to_the_parent_constant() {
+        let namespace_path = vec![String::from("Foo")];
+
+        let actual =
+            candidates("::Bar::Baz", &namespace_path, &definitions(&["::Bar"]));
+
+        assert_eq!(vec!["::Bar::Baz", "::Bar"], candidate_names(actual));
+    }
+
+    #[test]
+    fn test_candidates_stop_at_the_namespace_of_the_first_segment() {
+        let namespace_path = vec![String::from("Billing")];
+
+        let actual = candidates(
+            "Payment::Receipt",
+            &namespace_path,
+            &definitions(&["::Billing::Payment", "::Payment::Receipt"]),
+        );
+
+        assert_eq!(
+            vec!["::Billing::Payment::Receipt", "::Billing::Payment"],
+            candidate_names(actual)
+        );
+    }
+
+    #[test]
+    fn test_candidates_when_nothing_is_defined() {
+        let actual =
+            candidates("Baz", &[String::from("Foo")], &definitions(&[]));
+
+        assert_eq!(
+            vec![
+                Candidate {
+                    fully_qualified_name: String::from("::Foo::Baz"),
+                    defined: false,
+                },
+                Candidate {
+                    fully_qualified_name: String::from("::Baz"),
+                    defined: false,
+                },
+            ],
+            actual
+        );
+    }
+}
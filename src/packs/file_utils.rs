@@ -5,42 +5,12 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use crate::packs::mapped_file::{read_contents, FileContents};
 use crate::packs::Configuration;
 use anyhow::Context;
 use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use regex::Regex;
 
-#[derive(PartialEq, Debug)]
-pub enum SupportedFileType {
-    Ruby,
-    Erb,
-}
-
-pub fn get_file_type(path: &Path) -> Option<SupportedFileType> {
-    let ruby_special_files = ["Gemfile", "Rakefile"];
-    let ruby_extensions = vec!["rb", "rake", "builder", "gemspec", "ru"];
-
-    let extension = path.extension();
-    // Eventually, we can have packs::parsing::ruby, packs::parsing::erb, etc.
-    // These would implement a packs::parsing::interface::Parser trait and can
-    // hold the logic for determining if a parser can parse a file.
-
-    let is_ruby_file = ruby_extensions
-        .into_iter()
-        .any(|ext| extension.map_or(false, |e| e == ext))
-        || ruby_special_files.iter().any(|file| path.ends_with(file));
-
-    let is_erb_file = path.extension().map_or(false, |ext| ext == "erb");
-
-    if is_ruby_file {
-        Some(SupportedFileType::Ruby)
-    } else if is_erb_file {
-        Some(SupportedFileType::Erb)
-    } else {
-        None
-    }
-}
-
 pub fn build_glob_set(globs: &[String]) -> GlobSet {
     let mut builder = GlobSetBuilder::new();
 
@@ -101,14 +71,12 @@ pub fn user_inputted_paths_to_absolute_filepaths(
         .collect::<HashSet<_>>()
 }
 
-pub(crate) fn convert_erb_to_ruby_without_sourcemaps(
-    contents: String,
-) -> String {
+pub(crate) fn convert_erb_to_ruby_without_sourcemaps(contents: &str) -> String {
     let regex_pattern = r"(?s)<%=?-?\s*(.*?)\s*-?%>";
     let regex = Regex::new(regex_pattern).unwrap();
 
     let extracted_contents: Vec<&str> = regex
-        .captures_iter(&contents)
+        .captures_iter(contents)
         .map(|capture| capture.get(1).unwrap().as_str())
         .collect();
 
@@ -129,23 +97,40 @@ pub(crate) fn file_content_digest(file: &Path) -> anyhow::Result<String> {
     Ok(format!("{:x}", md5::compute(&file_content)))
 }
 
-pub fn file_read_contents(
+pub(crate) fn file_read_contents(
     path: &Path,
     configuration: &Configuration,
-) -> anyhow::Result<String> {
+) -> anyhow::Result<FileContents> {
     if is_stdin_file(path, configuration) {
-        Ok(io::read_to_string(io::stdin()).context(format!(
-            "Failed to read contents of {} from stdin",
-            path.to_string_lossy()
-        ))?)
+        Ok(FileContents::Read(
+            io::read_to_string(io::stdin()).context(format!(
+                "Failed to read contents of {} from stdin",
+                path.to_string_lossy()
+            ))?,
+        ))
     } else {
-        fs::read_to_string(path).context(format!(
+        read_contents(path, configuration.mmap_threshold).context(format!(
             "Failed to read contents of {}",
             path.to_string_lossy()
         ))
     }
 }
 
+// Newline-delimited paths, as produced by pre-commit frameworks (e.g. lint-staged)
+pub(crate) fn read_file_list(
+    reader: impl io::BufRead,
+) -> anyhow::Result<Vec<String>> {
+    let mut files = Vec::new();
+    for line in reader.lines() {
+        let line = line.context("Failed to read the list of files")?;
+        let file = line.trim();
+        if !file.is_empty() {
+            files.push(file.to_owned());
+        }
+    }
+    Ok(files)
+}
+
 pub fn is_stdin_file(path: &Path, configuration: &Configuration) -> bool {
     match &configuration.stdin_file_path {
         Some(stdin_path) => path == stdin_path.as_path(),
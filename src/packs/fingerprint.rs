@@ -0,0 +1,128 @@
+// Content-based fingerprints of references to a constant. A fingerprint hashes
+// what is referenced, from where, and the whitespace-normalized line of code
+// containing the reference, but not its line number, so the recorded
+// references in v2 package_todo.yml files still match after unrelated edits
+// shift lines around.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub(crate) fn fingerprint(
+    constant_name: &str,
+    referencing_pack_name: &str,
+    defining_pack_name: &str,
+    context: &str,
+) -> String {
+    let normalized_context: Vec<&str> = context.split_whitespace().collect();
+    let digest = md5::compute(format!(
+        "{}\n{}\n{}\n{}",
+        constant_name,
+        referencing_pack_name,
+        defining_pack_name,
+        normalized_context.join(" ")
+    ));
+    // Long enough to be unique among the references to a constant in a file
+    format!("{:x}", digest)[..12].to_owned()
+}
+
+// The lines of source files, reading each file at most once
+pub(crate) struct SourceLines {
+    absolute_root: PathBuf,
+    lines_by_file: HashMap<String, Vec<String>>,
+}
+
+impl SourceLines {
+    pub fn new(absolute_root: &Path) -> Self {
+        SourceLines {
+            absolute_root: absolute_root.to_path_buf(),
+            lines_by_file: HashMap::new(),
+        }
+    }
+
+    // Empty if the file or line no longer exists. `line` is 1-based.
+    pub fn line(&mut self, relative_file: &str, line: usize) -> &str {
+        line.checked_sub(1)
+            .and_then(|index| self.lines(relative_file).get(index))
+            .map_or("", String::as_str)
+    }
+
+    // Empty if the file no longer exists
+    pub fn lines(&mut self, relative_file: &str) -> &[String] {
+        let absolute_root = &self.absolute_root;
+        self.lines_by_file
+            .entry(relative_file.to_owned())
+            .or_insert_with(|| {
+                std::fs::read_to_string(absolute_root.join(relative_file))
+                    .map(|contents| {
+                        contents.lines().map(String::from).collect()
+                    })
+                    .unwrap_or_default()
+            })
+    }
+}
+
+// Whether any of the found fingerprints is not recorded, counting duplicates,
+// e.g. when the same line of code appears twice in a file
+pub(crate) fn has_unrecorded(recorded: &[String], found: &[String]) -> bool {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for fingerprint in recorded {
+        *remaining.entry(fingerprint).or_default() += 1;
+    }
+    found.iter().any(|fingerprint| {
+        match remaining.get_mut(fingerprint.as_str()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_whitespace() {
+        assert_eq!(
+            fingerprint("::Bar", "packs/foo", "packs/bar", "    Bar.call(1)"),
+            fingerprint("::Bar", "packs/foo", "packs/bar", "Bar.call(1)  ")
+        );
+        assert_ne!(
+            fingerprint("::Bar", "packs/foo", "packs/bar", "Bar.call(1)"),
+            fingerprint("::Bar", "packs/foo", "packs/bar", "Bar.call(2)")
+        );
+        assert_ne!(
+            fingerprint("::Bar", "packs/foo", "packs/bar", "Bar.call(1)"),
+            fingerprint("::Bar", "packs/baz", "packs/bar", "Bar.call(1)")
+        );
+    }
+
+    #[test]
+    fn test_has_unrecorded() {
+        let recorded = vec![String::from("a"), String::from("b")];
+
+        assert!(!has_unrecorded(&recorded, &[String::from("b")]));
+        assert!(has_unrecorded(&recorded, &[String::from("c")]));
+        assert!(has_unrecorded(
+            &recorded,
+            &[String::from("a"), String::from("a")]
+        ));
+    }
+
+    #[test]
+    fn test_source_lines() {
+        let mut source_lines =
+            SourceLines::new(Path::new("tests/fixtures/simple_app"));
+
+        assert_eq!(
+            "  def calls_bar_without_a_stated_dependency",
+            source_lines.line("packs/foo/app/services/foo.rb", 2)
+        );
+        assert_eq!("", source_lines.line("packs/foo/app/services/foo.rb", 0));
+        assert_eq!(
+            "",
+            source_lines.line("packs/foo/app/services/missing.rb", 1)
+        );
+    }
+}
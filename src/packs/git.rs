@@ -0,0 +1,457 @@
+// Finds the packs touched since a base revision, to scope work to them, e.g.
+// on the CI build of a pull request. Uses the git CLI, as CI runners and
+// developer machines have it, rather than linking a git library.
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+
+use super::reference_extractor::get_all_references_and_sigils;
+use super::Configuration;
+
+fn git(absolute_root: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .current_dir(absolute_root)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "`git {}` exited with {}: {}",
+            args.join(" "),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout)
+        .context(format!("`git {}` printed non-UTF-8 output", args.join(" ")))
+}
+
+// How many more commits `merge_base` fetches on each attempt to find the
+// merge base in a shallow clone, before fetching the whole history
+const DEEPEN_STEPS: [u32; 3] = [50, 200, 1000];
+
+// The commit of the merge base of `base` and HEAD. Shallow clones, as made by
+// CI runners, are deepened until the merge base is part of the history.
+pub(crate) fn merge_base(
+    absolute_root: &Path,
+    base: &str,
+) -> anyhow::Result<String> {
+    let context = || format!("Failed to find the merge base of `{}`", base);
+    let mut deepen_steps = DEEPEN_STEPS.iter();
+    loop {
+        let error = match git(absolute_root, &["merge-base", base, "HEAD"]) {
+            Ok(merge_base) => return Ok(merge_base.trim().to_owned()),
+            Err(error) => error,
+        };
+        if !is_shallow(absolute_root) {
+            return Err(error.context(context()));
+        }
+        let deepen = match deepen_steps.next() {
+            Some(step) => format!("--deepen={}", step),
+            None => String::from("--unshallow"),
+        };
+        let mut args = vec!["fetch", "--no-tags", deepen.as_str(), "origin"];
+        let refspec = base.strip_prefix("origin/").map(remote_branch_refspec);
+        if let Some(refspec) = &refspec {
+            args.push(refspec);
+        }
+        git(absolute_root, &args).with_context(context)?;
+    }
+}
+
+// False outside of a git repository too, so the original error is reported
+fn is_shallow(absolute_root: &Path) -> bool {
+    git(absolute_root, &["rev-parse", "--is-shallow-repository"])
+        .is_ok_and(|shallow| shallow.trim() == "true")
+}
+
+fn succeeds(absolute_root: &Path, args: &[&str]) -> anyhow::Result<bool> {
+    Ok(Command::new("git")
+        .current_dir(absolute_root)
+        .args(args)
+        .output()
+        .context("Failed to run git")?
+        .status
+        .success())
+}
+
+fn remote_branch_refspec(branch: &str) -> String {
+    format!("+refs/heads/{}:refs/remotes/origin/{}", branch, branch)
+}
+
+// CI variables naming the branch a pull or merge request targets
+const TARGET_BRANCH_VARIABLES: [&str; 2] =
+    ["GITHUB_BASE_REF", "CI_MERGE_REQUEST_TARGET_BRANCH_NAME"];
+
+// The branch `check --changed` compares against: the target branch of the pull
+// or merge request being built, `default_branch` in packwerk.yml, the default
+// branch of the project on GitLab CI, the branch of origin/HEAD, or `main`
+fn detect_default_branch(
+    configured: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+    origin_head: impl FnOnce() -> Option<String>,
+) -> String {
+    // CI runners set some of these variables to an empty string when unknown
+    let var = |name: &str| env(name).filter(|value| !value.is_empty());
+    TARGET_BRANCH_VARIABLES
+        .iter()
+        .find_map(|&name| var(name))
+        .or_else(|| configured.map(str::to_owned))
+        .or_else(|| var("CI_DEFAULT_BRANCH"))
+        .or_else(origin_head)
+        .unwrap_or_else(|| String::from("main"))
+}
+
+// The remote-tracking branch of the default branch, e.g. `origin/main`. It's
+// fetched when missing, as CI runners often only fetch the commit they build.
+pub(crate) fn default_base(
+    configuration: &Configuration,
+) -> anyhow::Result<String> {
+    let absolute_root = configuration.absolute_root.as_path();
+    let branch = detect_default_branch(
+        configuration.default_branch.as_deref(),
+        |name| std::env::var(name).ok(),
+        || {
+            let origin_head = git(
+                absolute_root,
+                &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+            )
+            .ok()?;
+            origin_head
+                .trim()
+                .strip_prefix("origin/")
+                .map(str::to_owned)
+        },
+    );
+    let base = format!("origin/{}", branch);
+    let commit = format!("{}^{{commit}}", base);
+    if !succeeds(
+        absolute_root,
+        &["rev-parse", "--verify", "--quiet", &commit],
+    )? {
+        let refspec = remote_branch_refspec(&branch);
+        let mut args = vec!["fetch", "--no-tags"];
+        if is_shallow(absolute_root) {
+            args.push("--depth=50");
+        }
+        args.extend(["origin", refspec.as_str()]);
+        git(absolute_root, &args)
+            .context(format!("Failed to fetch `{}`", base))?;
+    }
+    Ok(base)
+}
+
+pub(crate) fn head_commit(absolute_root: &Path) -> anyhow::Result<String> {
+    Ok(git(absolute_root, &["rev-parse", "HEAD"])?
+        .trim()
+        .to_owned())
+}
+
+// The contents of a file, relative to the project root, at a commit, or None
+// when the file didn't exist then
+pub(crate) fn file_at_revision(
+    absolute_root: &Path,
+    revision: &str,
+    relative_path: &Path,
+) -> anyhow::Result<Option<String>> {
+    // `./` makes the path relative to the project root rather than to the
+    // root of the repository
+    let object = format!("{}:./{}", revision, relative_path.display());
+    if !succeeds(absolute_root, &["cat-file", "-e", &object])? {
+        return Ok(None);
+    }
+    git(absolute_root, &["show", &object]).map(Some)
+}
+
+// The files changed by each commit since `since`, a date git understands,
+// e.g. `6 months ago`, or by every commit. Paths are relative to the project
+// root, and files outside of it are left out.
+pub(crate) fn files_changed_by_commit(
+    absolute_root: &Path,
+    since: Option<&str>,
+) -> anyhow::Result<Vec<BTreeSet<PathBuf>>> {
+    // Each commit starts with a record separator, followed by its files
+    let mut args = vec![
+        "-c",
+        "core.quotePath=false",
+        "log",
+        "--format=%x1e",
+        "--name-only",
+        "--no-renames",
+        "--relative",
+    ];
+    let since_arg = since.map(|since| format!("--since={}", since));
+    if let Some(since_arg) = &since_arg {
+        args.push(since_arg);
+    }
+    let log = git(absolute_root, &args)?;
+    Ok(log
+        .split('\u{1e}')
+        .map(|commit| {
+            commit
+                .lines()
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from)
+                .collect::<BTreeSet<PathBuf>>()
+        })
+        .filter(|files| !files.is_empty())
+        .collect())
+}
+
+// Files changed between the merge base of `base` and HEAD and the working
+// tree, untracked files included, relative to the project root. Both the old
+// and the new path of renamed files are changed. Files outside of the
+// project root are left out.
+pub(crate) fn changed_files(
+    absolute_root: &Path,
+    base: &str,
+) -> anyhow::Result<BTreeSet<PathBuf>> {
+    let merge_base = merge_base(absolute_root, base)?;
+    let diff = git(
+        absolute_root,
+        &[
+            "diff",
+            "--name-only",
+            "--no-renames",
+            "--relative",
+            "-z",
+            &merge_base,
+        ],
+    )?;
+    let untracked = git(
+        absolute_root,
+        &["ls-files", "--others", "--exclude-standard", "-z"],
+    )?;
+    Ok(diff
+        .split('\0')
+        .chain(untracked.split('\0'))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+// The names of the packs owning the changed files. A package_todo.yml belongs
+// to its pack wherever it is, and other files that are not checked, e.g. a
+// package.yml or a deleted file, belong to the pack of the closest directory
+// with a package.yml.
+pub(crate) fn changed_packs(
+    configuration: &Configuration,
+    base: &str,
+) -> anyhow::Result<BTreeSet<String>> {
+    let pack_names_by_package_todo: HashMap<&Path, &str> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| (pack.package_todo_yml.as_path(), pack.name.as_str()))
+        .collect();
+    let mut pack_names = BTreeSet::new();
+    for relative_path in changed_files(&configuration.absolute_root, base)? {
+        let absolute_path = configuration.absolute_root.join(&relative_path);
+        if let Some(pack_name) =
+            pack_names_by_package_todo.get(absolute_path.as_path())
+        {
+            pack_names.insert(pack_name.to_string());
+            continue;
+        }
+        let pack_name = match configuration.pack_set.for_file(&absolute_path)? {
+            Some(pack) => Some(pack.name.clone()),
+            None => closest_pack_name(configuration, &relative_path),
+        };
+        if let Some(pack_name) = pack_name {
+            pack_names.insert(pack_name);
+        }
+    }
+    Ok(pack_names)
+}
+
+fn closest_pack_name(
+    configuration: &Configuration,
+    relative_path: &Path,
+) -> Option<String> {
+    relative_path.ancestors().skip(1).find_map(|directory| {
+        let name = if directory.as_os_str().is_empty() {
+            "."
+        } else {
+            directory.to_str()?
+        };
+        configuration
+            .pack_set
+            .for_pack(name)
+            .ok()
+            .map(|pack| pack.name.clone())
+    })
+}
+
+// The checked files owned by the packs, relative to the project root
+pub(crate) fn files_of_packs(
+    configuration: &Configuration,
+    pack_names: &BTreeSet<String>,
+) -> anyhow::Result<Vec<String>> {
+    let mut files = Vec::new();
+    for absolute_path in &configuration.included_files {
+        let owned = configuration
+            .pack_set
+            .for_file(absolute_path)?
+            .is_some_and(|pack| pack_names.contains(&pack.name));
+        if owned {
+            if let Ok(relative_path) =
+                absolute_path.strip_prefix(&configuration.absolute_root)
+            {
+                files.push(relative_path.to_string_lossy().into_owned());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// The checked files whose violations may have changed since `base`: changed
+// files, and the files referencing constants whose definitions changed, i.e.
+// constants defined in changed files, which includes constants that moved,
+// and constants of packs whose package.yml changed, e.g. to make them
+// private. A changed packwerk.yml affects every file.
+pub(crate) fn files_affected_since(
+    configuration: &Configuration,
+    base: &str,
+) -> anyhow::Result<Vec<String>> {
+    let changed = changed_files(&configuration.absolute_root, base)?;
+    let all_packs: BTreeSet<String> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| pack.name.clone())
+        .collect();
+    if changed.contains(Path::new("packwerk.yml")) {
+        return files_of_packs(configuration, &all_packs);
+    }
+    let changed_pack_names: HashSet<&str> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter(|pack| changed.contains(&pack.relative_yml()))
+        .map(|pack| pack.name.as_str())
+        .collect();
+
+    let mut files: BTreeSet<String> = changed
+        .iter()
+        .filter(|relative_path| {
+            configuration
+                .included_files
+                .contains(&configuration.absolute_root.join(relative_path))
+        })
+        .map(|relative_path| relative_path.to_string_lossy().into_owned())
+        .collect();
+    let (references, _sigils, _parse_errors) = get_all_references_and_sigils(
+        configuration,
+        &configuration.included_files,
+    )?;
+    for reference in &references {
+        let definition_changed = reference
+            .relative_defining_file
+            .as_deref()
+            .is_some_and(|file| changed.contains(Path::new(file)));
+        let pack_changed = reference
+            .defining_pack_name
+            .as_deref()
+            .is_some_and(|name| changed_pack_names.contains(name));
+        if definition_changed || pack_changed {
+            files.insert(reference.relative_referencing_file.to_string());
+        }
+    }
+    Ok(files.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::configuration;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_closest_pack_name() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/simple_app")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+
+        assert_eq!(
+            closest_pack_name(
+                &configuration,
+                Path::new("packs/foo/app/services/deleted.rb")
+            ),
+            Some(String::from("packs/foo"))
+        );
+        assert_eq!(
+            closest_pack_name(
+                &configuration,
+                Path::new("packs/foo/package.yml")
+            ),
+            Some(String::from("packs/foo"))
+        );
+        assert_eq!(
+            closest_pack_name(&configuration, Path::new("config/routes.rb")),
+            Some(String::from("."))
+        );
+    }
+
+    #[test]
+    fn test_detect_default_branch() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let origin_head = || Some(String::from("trunk"));
+
+        assert_eq!(
+            detect_default_branch(
+                Some("master"),
+                env(&[
+                    ("GITHUB_BASE_REF", "release"),
+                    ("CI_DEFAULT_BRANCH", "develop"),
+                ]),
+                origin_head,
+            ),
+            "release"
+        );
+        assert_eq!(
+            detect_default_branch(
+                Some("master"),
+                env(&[
+                    ("CI_MERGE_REQUEST_TARGET_BRANCH_NAME", "release"),
+                    ("CI_DEFAULT_BRANCH", "develop"),
+                ]),
+                origin_head,
+            ),
+            "release"
+        );
+        // GitHub sets GITHUB_BASE_REF to an empty string outside of pull
+        // requests
+        assert_eq!(
+            detect_default_branch(
+                Some("master"),
+                env(&[("GITHUB_BASE_REF", "")]),
+                origin_head,
+            ),
+            "master"
+        );
+        assert_eq!(
+            detect_default_branch(
+                None,
+                env(&[("CI_DEFAULT_BRANCH", "develop")]),
+                origin_head,
+            ),
+            "develop"
+        );
+        assert_eq!(detect_default_branch(None, env(&[]), origin_head), "trunk");
+        assert_eq!(detect_default_branch(None, env(&[]), || None), "main");
+    }
+}
@@ -0,0 +1,342 @@
+// Metrics of the graph of packs, with an edge from each pack to the packs it
+// depends on, for finding the packs that hold the architecture together.
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+
+use petgraph::algo::{condensation, toposort};
+use petgraph::prelude::{DiGraph, NodeIndex};
+use serde::Serialize;
+
+use super::cli::GraphEdges;
+use super::interner::InternedStr;
+use super::Configuration;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct GraphMetrics {
+    pub packs: usize,
+    pub edges: usize,
+    // Strongly connected components of more than one pack
+    pub cycles: usize,
+    // The number of packs once each cycle is collapsed into a single node
+    pub condensation_size: usize,
+    // The longest chain of dependencies once cycles are collapsed, with the
+    // packs of each collapsed cycle together
+    pub longest_path: Vec<Vec<String>>,
+    // Sorted from the most central pack
+    pub centrality: Vec<PackCentrality>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct PackCentrality {
+    pub pack: String,
+    // The number of shortest paths between other packs that go through this
+    // pack, where paths tied for shortest share the credit
+    pub betweenness: f64,
+    pub dependencies: usize,
+    pub dependents: usize,
+}
+
+impl fmt::Display for GraphMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Packs: {}", self.packs)?;
+        writeln!(f, "Edges: {}", self.edges)?;
+        writeln!(f, "Cycles: {}", self.cycles)?;
+        writeln!(f, "Condensation size: {}", self.condensation_size)?;
+        let path: Vec<String> = self
+            .longest_path
+            .iter()
+            .map(|component| match component.as_slice() {
+                [pack] => pack.clone(),
+                packs => format!("{{{}}}", packs.join(", ")),
+            })
+            .collect();
+        writeln!(
+            f,
+            "Longest path ({}): {}",
+            self.longest_path.len().saturating_sub(1),
+            path.join(" -> ")
+        )?;
+        writeln!(f, "Most central packs:")?;
+        for pack in &self.centrality {
+            writeln!(
+                f,
+                "  {}: betweenness {:.2}, {} dependencies, {} dependents",
+                pack.pack, pack.betweenness, pack.dependencies, pack.dependents
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn graph_metrics(
+    configuration: &Configuration,
+    edges: GraphEdges,
+    top: usize,
+) -> GraphMetrics {
+    let pack_names: Vec<&str> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| pack.name.as_str())
+        .collect();
+    // Recorded violations are read from package_todo.yml files as owned
+    // identifiers, so their edges are collected before borrowing them
+    let violation_edges: BTreeSet<(InternedStr, InternedStr)> =
+        if edges == GraphEdges::Declared {
+            BTreeSet::new()
+        } else {
+            configuration
+                .pack_set
+                .packs
+                .iter()
+                .flat_map(|pack| pack.all_violations())
+                .map(|v| (v.referencing_pack_name, v.defining_pack_name))
+                .collect()
+        };
+    let mut pack_edges: BTreeSet<(&str, &str)> = violation_edges
+        .iter()
+        .map(|(from, to)| (from.as_str(), to.as_str()))
+        .collect();
+    if edges != GraphEdges::Violations {
+        for pack in &configuration.pack_set.packs {
+            for dependency in &pack.dependencies {
+                pack_edges.insert((pack.name.as_str(), dependency.as_str()));
+            }
+        }
+    }
+    build_metrics(&pack_names, &pack_edges, top)
+}
+
+fn build_metrics(
+    pack_names: &[&str],
+    pack_edges: &BTreeSet<(&str, &str)>,
+    top: usize,
+) -> GraphMetrics {
+    let mut sorted_pack_names = pack_names.to_vec();
+    sorted_pack_names.sort();
+    sorted_pack_names.dedup();
+
+    let mut graph = DiGraph::<String, ()>::new();
+    let nodes: Vec<NodeIndex> = sorted_pack_names
+        .iter()
+        .map(|name| graph.add_node(name.to_string()))
+        .collect();
+    let node_for = |name: &str| {
+        sorted_pack_names
+            .binary_search(&name)
+            .ok()
+            .map(|index| nodes[index])
+    };
+    for &(from, to) in pack_edges {
+        // Self-dependencies are reported by `validate`, and edges to unknown
+        // packs by `check`
+        if from == to {
+            continue;
+        }
+        if let (Some(from), Some(to)) = (node_for(from), node_for(to)) {
+            graph.add_edge(from, to, ());
+        }
+    }
+
+    let betweenness = betweenness_centrality(&graph);
+    let mut centrality: Vec<PackCentrality> = graph
+        .node_indices()
+        .map(|node| PackCentrality {
+            pack: graph[node].clone(),
+            betweenness: betweenness[node.index()],
+            dependencies: graph
+                .neighbors_directed(node, petgraph::Direction::Outgoing)
+                .count(),
+            dependents: graph
+                .neighbors_directed(node, petgraph::Direction::Incoming)
+                .count(),
+        })
+        .collect();
+    centrality.sort_by(|a, b| {
+        b.betweenness
+            .total_cmp(&a.betweenness)
+            .then_with(|| a.pack.cmp(&b.pack))
+    });
+    centrality.truncate(top);
+
+    let packs = graph.node_count();
+    let edges = graph.edge_count();
+    let condensed = condensation(graph, true);
+    let cycles = condensed
+        .node_weights()
+        .filter(|component| component.len() > 1)
+        .count();
+
+    GraphMetrics {
+        packs,
+        edges,
+        cycles,
+        condensation_size: condensed.node_count(),
+        longest_path: longest_path(&condensed),
+        centrality,
+    }
+}
+
+// Brandes' algorithm, over unweighted edges
+fn betweenness_centrality(graph: &DiGraph<String, ()>) -> Vec<f64> {
+    let node_count = graph.node_count();
+    let mut centrality = vec![0.0; node_count];
+    for source in graph.node_indices() {
+        let mut visited: Vec<NodeIndex> = Vec::new();
+        let mut predecessors: Vec<Vec<NodeIndex>> = vec![vec![]; node_count];
+        let mut shortest_paths = vec![0.0; node_count];
+        let mut distances: Vec<Option<usize>> = vec![None; node_count];
+        shortest_paths[source.index()] = 1.0;
+        distances[source.index()] = Some(0);
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            visited.push(node);
+            let Some(distance) = distances[node.index()] else {
+                continue;
+            };
+            for neighbor in graph.neighbors(node) {
+                if distances[neighbor.index()].is_none() {
+                    distances[neighbor.index()] = Some(distance + 1);
+                    queue.push_back(neighbor);
+                }
+                if distances[neighbor.index()] == Some(distance + 1) {
+                    shortest_paths[neighbor.index()] +=
+                        shortest_paths[node.index()];
+                    predecessors[neighbor.index()].push(node);
+                }
+            }
+        }
+
+        let mut dependencies = vec![0.0; node_count];
+        while let Some(node) = visited.pop() {
+            for predecessor in &predecessors[node.index()] {
+                dependencies[predecessor.index()] += shortest_paths
+                    [predecessor.index()]
+                    / shortest_paths[node.index()]
+                    * (1.0 + dependencies[node.index()]);
+            }
+            if node != source {
+                centrality[node.index()] += dependencies[node.index()];
+            }
+        }
+    }
+    centrality
+}
+
+// The longest path through the acyclic graph of collapsed cycles
+fn longest_path(condensed: &DiGraph<Vec<String>, ()>) -> Vec<Vec<String>> {
+    let Ok(sorted) = toposort(condensed, None) else {
+        return vec![];
+    };
+    let mut lengths = vec![0; condensed.node_count()];
+    let mut previous: Vec<Option<NodeIndex>> =
+        vec![None; condensed.node_count()];
+    for node in &sorted {
+        for neighbor in condensed.neighbors(*node) {
+            if lengths[node.index()] + 1 > lengths[neighbor.index()] {
+                lengths[neighbor.index()] = lengths[node.index()] + 1;
+                previous[neighbor.index()] = Some(*node);
+            }
+        }
+    }
+
+    let mut end = sorted.iter().copied().max_by(|a, b| {
+        lengths[a.index()]
+            .cmp(&lengths[b.index()])
+            // Prefer the first node on ties, for stable output
+            .then_with(|| b.index().cmp(&a.index()))
+    });
+    let mut path = vec![];
+    while let Some(node) = end {
+        let mut component = condensed[node].clone();
+        component.sort();
+        path.push(component);
+        end = previous[node.index()];
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_build_metrics() {
+        let pack_edges = BTreeSet::from([
+            ("packs/a", "packs/b"),
+            ("packs/b", "packs/c"),
+            ("packs/c", "packs/b"),
+            ("packs/c", "packs/d"),
+            ("packs/e", "packs/e"),
+        ]);
+
+        let metrics = build_metrics(
+            &["packs/a", "packs/b", "packs/c", "packs/d", "packs/e"],
+            &pack_edges,
+            2,
+        );
+
+        assert_eq!(
+            metrics,
+            GraphMetrics {
+                packs: 5,
+                edges: 4,
+                cycles: 1,
+                condensation_size: 4,
+                longest_path: vec![
+                    vec!["packs/a".to_owned()],
+                    vec!["packs/b".to_owned(), "packs/c".to_owned()],
+                    vec!["packs/d".to_owned()],
+                ],
+                centrality: vec![
+                    PackCentrality {
+                        pack: "packs/b".to_owned(),
+                        betweenness: 2.0,
+                        dependencies: 1,
+                        dependents: 2,
+                    },
+                    PackCentrality {
+                        pack: "packs/c".to_owned(),
+                        betweenness: 2.0,
+                        dependencies: 2,
+                        dependents: 1,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_betweenness_centrality_splits_credit_between_shortest_paths() {
+        let pack_edges = BTreeSet::from([
+            ("packs/a", "packs/b"),
+            ("packs/a", "packs/c"),
+            ("packs/b", "packs/d"),
+            ("packs/c", "packs/d"),
+        ]);
+
+        let metrics = build_metrics(
+            &["packs/a", "packs/b", "packs/c", "packs/d"],
+            &pack_edges,
+            4,
+        );
+
+        let betweenness: Vec<(&str, f64)> = metrics
+            .centrality
+            .iter()
+            .map(|pack| (pack.pack.as_str(), pack.betweenness))
+            .collect();
+        assert_eq!(
+            betweenness,
+            vec![
+                ("packs/b", 0.5),
+                ("packs/c", 0.5),
+                ("packs/a", 0.0),
+                ("packs/d", 0.0)
+            ]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+// Where modularity debt and churn meet: files with violations that change
+// often, ranked by the number of commits changing them times their number of
+// violations, so refactoring goes where it pays off most. A pack is ranked by
+// the sum of the scores of its files.
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::checker::find_all_violations;
+use super::{git, Configuration};
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct Hotspots {
+    // Sorted from the highest score
+    pub packs: Vec<Hotspot>,
+    pub files: Vec<Hotspot>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct Hotspot {
+    // A pack name, or a file relative to the project root
+    pub name: String,
+    // The commits changing the file, or any file of the pack
+    pub commits: usize,
+    // Violations in the file, or the files of the pack, recorded or not
+    pub violations: usize,
+    pub score: usize,
+}
+
+impl fmt::Display for Hotspots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.files.is_empty() {
+            return writeln!(f, "No changed files with violations");
+        }
+        for (title, hotspots) in
+            [("Packs", &self.packs), ("Files", &self.files)]
+        {
+            writeln!(f, "{}:", title)?;
+            for hotspot in hotspots {
+                writeln!(
+                    f,
+                    "  {}: score {}, {} commit(s), {} violation(s)",
+                    hotspot.name,
+                    hotspot.score,
+                    hotspot.commits,
+                    hotspot.violations
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn hotspots(
+    configuration: &Configuration,
+    since: Option<&str>,
+    top: usize,
+) -> anyhow::Result<Hotspots> {
+    let absolute_root = &configuration.absolute_root;
+    let commits = git::files_changed_by_commit(absolute_root, since)?;
+
+    let mut pack_by_file: HashMap<PathBuf, String> = HashMap::new();
+    for absolute_path in &configuration.included_files {
+        if let (Some(pack), Ok(relative_path)) = (
+            configuration.pack_set.for_file(absolute_path)?,
+            absolute_path.strip_prefix(absolute_root),
+        ) {
+            pack_by_file.insert(relative_path.to_path_buf(), pack.name.clone());
+        }
+    }
+
+    let mut violations_by_file: HashMap<PathBuf, usize> = HashMap::new();
+    for violation in find_all_violations(configuration)? {
+        *violations_by_file
+            .entry(PathBuf::from(violation.identifier.file.as_str()))
+            .or_default() += 1;
+    }
+
+    Ok(build_hotspots(
+        &commits,
+        &pack_by_file,
+        &violations_by_file,
+        top,
+    ))
+}
+
+fn build_hotspots(
+    commits: &[BTreeSet<PathBuf>],
+    pack_by_file: &HashMap<PathBuf, String>,
+    violations_by_file: &HashMap<PathBuf, usize>,
+    top: usize,
+) -> Hotspots {
+    let mut commits_by_file: HashMap<&PathBuf, usize> = HashMap::new();
+    let mut commits_by_pack: HashMap<&str, usize> = HashMap::new();
+    for files in commits {
+        let mut packs: BTreeSet<&str> = BTreeSet::new();
+        for file in files {
+            if let Some(pack) = pack_by_file.get(file) {
+                *commits_by_file.entry(file).or_default() += 1;
+                packs.insert(pack.as_str());
+            }
+        }
+        for pack in packs {
+            *commits_by_pack.entry(pack).or_default() += 1;
+        }
+    }
+
+    let mut files: Vec<Hotspot> = Vec::new();
+    let mut packs: HashMap<&str, Hotspot> = HashMap::new();
+    for (file, commits) in commits_by_file {
+        let violations = violations_by_file.get(file).copied().unwrap_or(0);
+        if violations == 0 {
+            continue;
+        }
+        let score = commits * violations;
+        let pack_name = pack_by_file[file].as_str();
+        let pack = packs.entry(pack_name).or_insert_with(|| Hotspot {
+            name: pack_name.to_owned(),
+            commits: commits_by_pack[pack_name],
+            violations: 0,
+            score: 0,
+        });
+        pack.violations += violations;
+        pack.score += score;
+        files.push(Hotspot {
+            name: file.to_string_lossy().into_owned(),
+            commits,
+            violations,
+            score,
+        });
+    }
+
+    Hotspots {
+        packs: ranked(packs.into_values().collect(), top),
+        files: ranked(files, top),
+    }
+}
+
+fn ranked(mut hotspots: Vec<Hotspot>, top: usize) -> Vec<Hotspot> {
+    hotspots.sort_by(|a, b| b.score.cmp(&a.score).then(a.name.cmp(&b.name)));
+    hotspots.truncate(top);
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn hotspot(
+        name: &str,
+        commits: usize,
+        violations: usize,
+        score: usize,
+    ) -> Hotspot {
+        Hotspot {
+            name: name.to_owned(),
+            commits,
+            violations,
+            score,
+        }
+    }
+
+    #[test]
+    fn test_build_hotspots() {
+        let foo = PathBuf::from("packs/foo/app/services/foo.rb");
+        let other_foo = PathBuf::from("packs/foo/app/services/other_foo.rb");
+        let bar = PathBuf::from("packs/bar/app/services/bar.rb");
+        let baz = PathBuf::from("packs/baz/app/services/baz.rb");
+        let commits = vec![
+            BTreeSet::from([foo.clone(), other_foo.clone(), bar.clone()]),
+            BTreeSet::from([foo.clone(), PathBuf::from("README.md")]),
+            BTreeSet::from([foo.clone(), baz.clone()]),
+            BTreeSet::from([bar.clone()]),
+        ];
+        let pack_by_file = HashMap::from([
+            (foo.clone(), String::from("packs/foo")),
+            (other_foo.clone(), String::from("packs/foo")),
+            (bar.clone(), String::from("packs/bar")),
+            (baz.clone(), String::from("packs/baz")),
+        ]);
+        let violations_by_file =
+            HashMap::from([(foo, 2), (other_foo, 1), (bar, 1)]);
+
+        assert_eq!(
+            build_hotspots(&commits, &pack_by_file, &violations_by_file, 2),
+            Hotspots {
+                packs: vec![
+                    hotspot("packs/foo", 3, 3, 7),
+                    hotspot("packs/bar", 2, 1, 2),
+                ],
+                files: vec![
+                    hotspot("packs/foo/app/services/foo.rb", 3, 2, 6),
+                    hotspot("packs/bar/app/services/bar.rb", 2, 1, 2),
+                ],
+            }
+        );
+    }
+}
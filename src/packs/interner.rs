@@ -0,0 +1,179 @@
+// Shares one allocation between all copies of a string. Large codebases produce
+// millions of references and violations, but only thousands of distinct pack
+// names, constant names, and files, so references and violations hold
+// `InternedStr`s rather than `String`s.
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// Strings are spread over the shards by hash, so that the threads resolving
+// references rarely wait on each other to intern them
+const SHARD_COUNT: usize = 64;
+
+pub struct Interner {
+    hasher: RandomState,
+    shards: Vec<Mutex<HashSet<InternedStr>>>,
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self {
+            hasher: RandomState::new(),
+            shards: (0..SHARD_COUNT).map(|_| Mutex::default()).collect(),
+        }
+    }
+}
+
+impl Interner {
+    pub fn intern(&self, string: &str) -> InternedStr {
+        let shard = self.hasher.hash_one(string) as usize % SHARD_COUNT;
+        let mut strings = self.shards[shard]
+            .lock()
+            .expect("Interner lock was poisoned");
+        if let Some(interned) = strings.get(string) {
+            return interned.clone();
+        }
+        let interned = InternedStr::from(string);
+        strings.insert(interned.clone());
+        interned
+    }
+}
+
+// An immutable string whose clones share its allocation. It compares, hashes,
+// orders, and (de)serializes like the string it holds.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InternedStr(Arc<str>);
+
+impl InternedStr {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for InternedStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for InternedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for InternedStr {
+    fn from(string: &str) -> Self {
+        Self(Arc::from(string))
+    }
+}
+
+impl From<String> for InternedStr {
+    fn from(string: String) -> Self {
+        Self(Arc::from(string))
+    }
+}
+
+impl From<&String> for InternedStr {
+    fn from(string: &String) -> Self {
+        Self(Arc::from(string.as_str()))
+    }
+}
+
+impl From<InternedStr> for String {
+    fn from(string: InternedStr) -> Self {
+        string.0.to_string()
+    }
+}
+
+impl PartialEq<&str> for InternedStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for InternedStr {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl PartialEq<InternedStr> for String {
+    fn eq(&self, other: &InternedStr) -> bool {
+        self.as_str() == &*other.0
+    }
+}
+
+impl fmt::Display for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Debug for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl Serialize for InternedStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(InternedStr::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_allocations() {
+        let interner = Interner::default();
+
+        let first = interner.intern("packs/foo");
+        let second = interner.intern(&String::from("packs/foo"));
+        let other = interner.intern("packs/bar");
+
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+        assert_eq!(other, "packs/bar");
+        assert!(!Arc::ptr_eq(&first.0, &other.0));
+    }
+
+    #[test]
+    fn test_interned_str_serde() {
+        let interned = InternedStr::from("packs/foo");
+
+        let yaml = serde_yaml::to_string(&interned).unwrap();
+        assert_eq!("packs/foo\n", yaml);
+        assert_eq!(
+            interned,
+            serde_yaml::from_str::<InternedStr>(&yaml).unwrap()
+        );
+    }
+}
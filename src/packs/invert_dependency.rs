@@ -0,0 +1,478 @@
+// Helps invert a dependency of one pack on another, by extracting the
+// constants it uses into a new pack that both can depend on. The files
+// defining those constants move into the new pack, along with the files of
+// the other pack they reference, so that the new pack doesn't depend on the
+// pack it was extracted from.
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use serde::Serialize;
+
+use super::checker::reference::Reference;
+use super::pack::{write_pack_to_disk, Pack};
+use super::reference_extractor::get_all_references_and_sigils;
+use super::{Configuration, PackageTodo, SourceLocation};
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct InversionPlan {
+    // The pack that depends on the other
+    pub from: String,
+    // The pack it depends on
+    pub to: String,
+    pub new_pack: String,
+    // The references from `from` to `to`, sorted by file and location
+    pub references: Vec<DependencyReference>,
+    // The files of `to` to move into the new pack, sorted
+    pub files: Vec<FileToMove>,
+    // The packs that would depend on the new pack, other than `from`
+    pub dependents: Vec<String>,
+    // The packs the new pack would depend on
+    pub dependencies: Vec<String>,
+    // References from the files to move to `from`, which would make the new
+    // pack depend on `from`
+    pub blocking_references: Vec<DependencyReference>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct DependencyReference {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub constant: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct FileToMove {
+    pub file: String,
+    // The constants of the file that are referenced from other files
+    pub constants: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct InversionResult {
+    pub moved_files: usize,
+    // The existing packs whose package.yml was rewritten
+    pub updated_packs: Vec<String>,
+}
+
+impl DependencyReference {
+    fn new(reference: &Reference) -> Self {
+        let SourceLocation { line, column } = reference.source_location;
+        Self {
+            file: reference.relative_referencing_file.to_string(),
+            line,
+            column,
+            constant: reference.constant_name.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for InversionPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.references.is_empty() {
+            return writeln!(
+                f,
+                "`{}` has no references to `{}`",
+                self.from, self.to
+            );
+        }
+        writeln!(
+            f,
+            "`{}` depends on `{}` through {} reference(s):",
+            self.from,
+            self.to,
+            self.references.len()
+        )?;
+        for reference in &self.references {
+            writeln!(
+                f,
+                "  {}:{}:{} {}",
+                reference.file,
+                reference.line,
+                reference.column,
+                reference.constant
+            )?;
+        }
+        writeln!(
+            f,
+            "Move {} file(s) into `{}`:",
+            self.files.len(),
+            self.new_pack
+        )?;
+        for file in &self.files {
+            writeln!(f, "  {} ({})", file.file, file.constants.join(", "))?;
+        }
+        writeln!(
+            f,
+            "`{}` would depend on `{}` instead of `{}`",
+            self.from, self.new_pack, self.to
+        )?;
+        if !self.dependents.is_empty() {
+            writeln!(
+                f,
+                "Packs that would also depend on `{}`: {}",
+                self.new_pack,
+                self.dependents.join(", ")
+            )?;
+        }
+        if !self.dependencies.is_empty() {
+            writeln!(
+                f,
+                "`{}` would depend on: {}",
+                self.new_pack,
+                self.dependencies.join(", ")
+            )?;
+        }
+        if !self.blocking_references.is_empty() {
+            writeln!(
+                f,
+                "These references would make `{}` depend on `{}`, and must be removed first:",
+                self.new_pack, self.from
+            )?;
+            for reference in &self.blocking_references {
+                writeln!(
+                    f,
+                    "  {}:{}:{} {}",
+                    reference.file,
+                    reference.line,
+                    reference.column,
+                    reference.constant
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The default name of the new pack, next to `to`
+pub(crate) fn default_new_pack_name(to: &str) -> String {
+    format!("{}_interface", to)
+}
+
+pub(crate) fn plan(
+    configuration: &Configuration,
+    from_name: &str,
+    to_name: &str,
+    new_pack_name: &str,
+) -> anyhow::Result<InversionPlan> {
+    let pack_set = &configuration.pack_set;
+    let from = pack_set
+        .for_pack(from_name)
+        .context(format!("`{}` not found", from_name))?;
+    let to = pack_set
+        .for_pack(to_name)
+        .context(format!("`{}` not found", to_name))?;
+    if from.name == to.name {
+        bail!("Cannot invert the dependency of `{}` on itself", from.name);
+    }
+    if to.name == "." {
+        bail!("Constants cannot be extracted from the root pack");
+    }
+
+    let (references, _sigils, _parse_errors) = get_all_references_and_sigils(
+        configuration,
+        &configuration.included_files,
+    )?;
+    Ok(build_plan(&from.name, &to.name, new_pack_name, &references))
+}
+
+fn build_plan(
+    from: &str,
+    to: &str,
+    new_pack: &str,
+    references: &[Reference],
+) -> InversionPlan {
+    let defined_in = |reference: &Reference, pack_name: &str| {
+        reference.defining_pack_name.as_deref() == Some(pack_name)
+    };
+    let dependency_references: Vec<&Reference> = references
+        .iter()
+        .filter(|reference| {
+            &*reference.referencing_pack_name == from
+                && defined_in(reference, to)
+        })
+        .collect();
+
+    // The files of `to` that `from` references, and those they reference in
+    // turn, so that none of the moved files reference `to`
+    let mut files: BTreeSet<&str> = dependency_references
+        .iter()
+        .filter_map(|reference| reference.relative_defining_file.as_deref())
+        .collect();
+    let mut pending: Vec<&str> = files.iter().copied().collect();
+    while let Some(file) = pending.pop() {
+        for reference in references {
+            if &*reference.relative_referencing_file != file
+                || !defined_in(reference, to)
+            {
+                continue;
+            }
+            if let Some(defining_file) =
+                reference.relative_defining_file.as_deref()
+            {
+                if files.insert(defining_file) {
+                    pending.push(defining_file);
+                }
+            }
+        }
+    }
+
+    let mut constants_by_file: BTreeMap<&str, BTreeSet<String>> =
+        files.iter().map(|file| (*file, BTreeSet::new())).collect();
+    let mut dependents = BTreeSet::new();
+    let mut dependencies = BTreeSet::new();
+    let mut blocking_references = vec![];
+    for reference in references {
+        let referencing_file = &*reference.relative_referencing_file;
+        let defining_file = reference.relative_defining_file.as_deref();
+        let is_moved_referencing = files.contains(referencing_file);
+        let is_moved_defining =
+            defining_file.is_some_and(|file| files.contains(file));
+
+        if let Some(constants) =
+            defining_file.and_then(|file| constants_by_file.get_mut(file))
+        {
+            if defining_file != Some(referencing_file) {
+                constants.insert(reference.constant_name.to_string());
+            }
+        }
+        if is_moved_defining
+            && !is_moved_referencing
+            && &*reference.referencing_pack_name != from
+        {
+            dependents.insert(reference.referencing_pack_name.to_string());
+        }
+        if is_moved_referencing && !is_moved_defining {
+            match reference.defining_pack_name.as_deref() {
+                Some(pack_name) if pack_name == from => blocking_references
+                    .push(DependencyReference::new(reference)),
+                Some(pack_name) if pack_name != to => {
+                    dependencies.insert(pack_name.to_owned());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut references: Vec<DependencyReference> = dependency_references
+        .into_iter()
+        .map(DependencyReference::new)
+        .collect();
+    references.sort();
+    references.dedup();
+    blocking_references.sort();
+    blocking_references.dedup();
+
+    InversionPlan {
+        from: from.to_owned(),
+        to: to.to_owned(),
+        new_pack: new_pack.to_owned(),
+        references,
+        files: constants_by_file
+            .into_iter()
+            .map(|(file, constants)| FileToMove {
+                file: file.to_owned(),
+                constants: constants.into_iter().collect(),
+            })
+            .collect(),
+        dependents: dependents.into_iter().collect(),
+        dependencies: dependencies.into_iter().collect(),
+        blocking_references,
+    }
+}
+
+// Creates the new pack, moves the files into it, and updates the dependencies
+// of the packs that reference them
+pub(crate) fn apply(
+    configuration: &Configuration,
+    plan: &InversionPlan,
+) -> anyhow::Result<InversionResult> {
+    let pack_set = &configuration.pack_set;
+    if plan.files.is_empty() {
+        bail!("`{}` has no references to `{}`", plan.from, plan.to);
+    }
+    if !plan.blocking_references.is_empty() {
+        bail!(
+            "Cannot extract `{}`, since it would depend on `{}`",
+            plan.new_pack,
+            plan.from
+        );
+    }
+    if pack_set.for_pack(&plan.new_pack).is_ok() {
+        bail!("`{}` already exists", plan.new_pack);
+    }
+    let to = pack_set.for_pack(&plan.to)?;
+
+    // Nothing is written until every destination is known to be free
+    let absolute_root = &configuration.absolute_root;
+    let to_prefix = format!("{}/", to.relative_path.display());
+    let new_pack_dir = absolute_root.join(&plan.new_pack);
+    let mut moves: Vec<(PathBuf, PathBuf)> = vec![];
+    for file in &plan.files {
+        let relative_file = file.file.strip_prefix(&to_prefix).context(
+            format!("Expected {} to be in `{}`", file.file, plan.to),
+        )?;
+        let destination = new_pack_dir.join(relative_file);
+        if destination.exists() {
+            bail!(
+                "Cannot move {} into `{}`, since {} already exists",
+                file.file,
+                plan.new_pack,
+                relative_to(&destination, absolute_root)
+            );
+        }
+        moves.push((absolute_root.join(&file.file), destination));
+    }
+
+    let mut new_pack = Pack::from_contents(
+        &new_pack_dir.join("package.yml"),
+        absolute_root,
+        "enforce_dependencies: true",
+        PackageTodo::default(),
+    )?;
+    new_pack.dependencies = plan.dependencies.iter().cloned().collect();
+
+    for (source, destination) in &moves {
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create directory {}",
+                parent.display()
+            ))?;
+        }
+        fs::rename(source, destination).context(format!(
+            "Failed to move {} to {}",
+            source.display(),
+            destination.display()
+        ))?;
+    }
+    write_pack_to_disk(&new_pack)?;
+
+    let dependents: HashSet<&str> =
+        plan.dependents.iter().map(String::as_str).collect();
+    let mut updated_packs = vec![];
+    for pack in &pack_set.packs {
+        let mut updated = pack.clone();
+        if pack.name == plan.from {
+            // Every reference to `to` was to a moved file
+            updated.dependencies.remove(&plan.to);
+            updated.dependencies.insert(plan.new_pack.clone());
+        } else if dependents.contains(pack.name.as_str()) {
+            updated.dependencies.insert(plan.new_pack.clone());
+        }
+        if updated.dependencies != pack.dependencies {
+            write_pack_to_disk(&updated)?;
+            updated_packs.push(pack.name.clone());
+        }
+    }
+    updated_packs.sort();
+
+    Ok(InversionResult {
+        moved_files: moves.len(),
+        updated_packs,
+    })
+}
+
+fn relative_to(path: &Path, absolute_root: &Path) -> String {
+    path.strip_prefix(absolute_root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::interner::InternedStr;
+    use pretty_assertions::assert_eq;
+
+    fn reference(
+        (referencing_pack, referencing_file): (&str, &str),
+        constant: &str,
+        (defining_pack, defining_file): (&str, &str),
+    ) -> Reference {
+        Reference {
+            constant_name: InternedStr::from(constant),
+            defining_pack_name: Some(InternedStr::from(defining_pack)),
+            relative_defining_file: Some(InternedStr::from(defining_file)),
+            referencing_pack_name: InternedStr::from(referencing_pack),
+            relative_referencing_file: InternedStr::from(referencing_file),
+            source_location: SourceLocation { line: 2, column: 4 },
+        }
+    }
+
+    #[test]
+    fn test_build_plan() {
+        let a = ("packs/a", "packs/a/app/services/a.rb");
+        let api = ("packs/b", "packs/b/app/services/api.rb");
+        let result = ("packs/b", "packs/b/app/models/result.rb");
+        let b = ("packs/b", "packs/b/app/services/b.rb");
+        let c = ("packs/c", "packs/c/app/services/c.rb");
+        let utils = ("packs/utils", "packs/utils/app/lib/utils.rb");
+        let references = vec![
+            reference(a, "::Api", api),
+            reference(api, "::Result", result),
+            reference(api, "::Utils", utils),
+            reference(b, "::Api", api),
+            reference(c, "::Result", result),
+            reference(c, "::B", b),
+        ];
+
+        let plan =
+            build_plan("packs/a", "packs/b", "packs/b_interface", &references);
+
+        assert_eq!(
+            plan,
+            InversionPlan {
+                from: String::from("packs/a"),
+                to: String::from("packs/b"),
+                new_pack: String::from("packs/b_interface"),
+                references: vec![DependencyReference {
+                    file: String::from("packs/a/app/services/a.rb"),
+                    line: 2,
+                    column: 4,
+                    constant: String::from("::Api"),
+                }],
+                files: vec![
+                    FileToMove {
+                        file: String::from("packs/b/app/models/result.rb"),
+                        constants: vec![String::from("::Result")],
+                    },
+                    FileToMove {
+                        file: String::from("packs/b/app/services/api.rb"),
+                        constants: vec![String::from("::Api")],
+                    },
+                ],
+                dependents: vec![
+                    String::from("packs/b"),
+                    String::from("packs/c")
+                ],
+                dependencies: vec![String::from("packs/utils")],
+                blocking_references: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_plan_with_blocking_references() {
+        let a = ("packs/a", "packs/a/app/services/a.rb");
+        let api = ("packs/b", "packs/b/app/services/api.rb");
+        let references =
+            vec![reference(a, "::Api", api), reference(api, "::A", a)];
+
+        let plan =
+            build_plan("packs/a", "packs/b", "packs/b_interface", &references);
+
+        assert_eq!(
+            plan.blocking_references,
+            vec![DependencyReference {
+                file: String::from("packs/b/app/services/api.rb"),
+                line: 2,
+                column: 4,
+                constant: String::from("::A"),
+            }]
+        );
+    }
+}
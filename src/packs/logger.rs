@@ -1,7 +1,17 @@
+use std::fmt;
+use std::time::Instant;
+
+use serde_json::{json, Map, Value};
+use tracing::field::{Field, Visit};
 use tracing::metadata::LevelFilter;
-use tracing::Level;
+use tracing::{span, Event, Level, Subscriber};
 use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::Context;
 use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use super::cli::LogFormat;
 
 //
 // This allows us to run the binary with timing and debug output, like so:
@@ -13,7 +23,12 @@ use tracing_subscriber::prelude::*;
 //    0.072214542s DEBUG src/packs/checker.rs:159: Filtering out recorded violations
 //    0.072355292s DEBUG src/packs/checker.rs:168: Finished filtering out recorded violations
 //
-pub fn install_logger(debug: bool) {
+pub fn install_logger(debug: bool, log_format: LogFormat) {
+    if log_format == LogFormat::Json {
+        install_json_logger(debug);
+        return;
+    }
+
     let filter = tracing_subscriber::filter::Targets::new()
         .with_default(LevelFilter::DEBUG)
         // Disable all traces from `globset`.
@@ -49,3 +64,142 @@ pub fn install_logger(debug: bool) {
         layered_subscriber.init();
     }
 }
+
+// With `--log-format json`, each event, and each span once it closes, is
+// written to stderr as a JSON object on its own line, for build systems to
+// ingest. Stages of a run such as `walk` and `parse` are info spans, so they
+// are logged even without `--debug`, along with their durations and the
+// counts recorded on them, e.g. files parsed and cache hits.
+fn install_json_logger(debug: bool) {
+    let max_level = if debug {
+        std::env::set_var("RUST_BACKTRACE", "1");
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::INFO
+    };
+    let filter = tracing_subscriber::filter::Targets::new()
+        .with_default(max_level)
+        .with_target("globset", LevelFilter::OFF);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(JsonLayer {
+            started: Instant::now(),
+        })
+        .init();
+}
+
+struct JsonLayer {
+    started: Instant,
+}
+
+// Stored in the extensions of each span
+struct SpanFields {
+    created: Instant,
+    fields: Map<String, Value>,
+}
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), json!(format!("{:?}", value)));
+    }
+}
+
+impl<S> Layer<S> for JsonLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        attrs: &span::Attributes<'_>,
+        id: &span::Id,
+        ctx: Context<'_, S>,
+    ) {
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields {
+                created: Instant::now(),
+                fields,
+            });
+        }
+    }
+
+    fn on_record(
+        &self,
+        id: &span::Id,
+        values: &span::Record<'_>,
+        ctx: Context<'_, S>,
+    ) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(span_fields) =
+                span.extensions_mut().get_mut::<SpanFields>()
+            {
+                values.record(&mut JsonVisitor(&mut span_fields.fields));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+        let metadata = event.metadata();
+        eprintln!(
+            "{}",
+            json!({
+                "type": "event",
+                "elapsed_seconds": self.started.elapsed().as_secs_f64(),
+                "level": metadata.level().to_string(),
+                "stage": ctx.event_span(event).map(|span| span.name()),
+                "file": metadata.file(),
+                "line": metadata.line(),
+                "fields": fields,
+            })
+        );
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(span_fields) = extensions.get::<SpanFields>() else {
+            return;
+        };
+        eprintln!(
+            "{}",
+            json!({
+                "type": "span",
+                "elapsed_seconds": self.started.elapsed().as_secs_f64(),
+                "level": span.metadata().level().to_string(),
+                "stage": span.name(),
+                "parent": span.parent().map(|parent| parent.name()),
+                "duration_seconds": span_fields.created.elapsed().as_secs_f64(),
+                "fields": span_fields.fields,
+            })
+        );
+    }
+}
@@ -0,0 +1,109 @@
+// Reads files with memory-mapped IO, so that large files, e.g. generated
+// Ruby files, are parsed straight from the page cache rather than copied into
+// a buffer first. Small files are cheaper to read, so only files of at least
+// `--mmap-threshold` bytes are mapped.
+use std::fs::{self, File};
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::{Mmap, MmapOptions};
+
+pub(crate) enum FileContents {
+    Mapped(Mmap),
+    Read(String),
+}
+
+impl Deref for FileContents {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            // SAFETY: the mapping was checked to be UTF-8 when it was made (see
+            // `map` for files rewritten since)
+            FileContents::Mapped(mapping) => unsafe {
+                std::str::from_utf8_unchecked(mapping)
+            },
+            FileContents::Read(contents) => contents,
+        }
+    }
+}
+
+impl AsRef<str> for FileContents {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+// Maps the file if it has at least `threshold` bytes, and otherwise reads it
+pub(crate) fn read_contents(
+    path: &Path,
+    threshold: Option<u64>,
+) -> io::Result<FileContents> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    match threshold {
+        // Empty files can't be mapped
+        Some(threshold) if len >= threshold && len > 0 => {
+            match map(&file, len)? {
+                Some(mapping) => Ok(FileContents::Mapped(mapping)),
+                None => fs::read_to_string(path).map(FileContents::Read),
+            }
+        }
+        _ => fs::read_to_string(path).map(FileContents::Read),
+    }
+}
+
+// Accessing the pages of a file truncated while it is mapped kills the process
+// with SIGBUS, so the mapping is dropped, and the file read instead, if its
+// length changed while it was mapped and validated. Like with any
+// memory-mapped reader, files must otherwise not be rewritten in place while
+// they are parsed.
+fn map(file: &File, len: u64) -> io::Result<Option<Mmap>> {
+    // SAFETY: the mapping is read-only, and pks never writes the files it
+    // parses. Truncation by other processes is handled as described above.
+    let mapping = unsafe { MmapOptions::new().len(len as usize).map(file)? };
+    if file.metadata()?.len() != len {
+        return Ok(None);
+    }
+    std::str::from_utf8(&mapping)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    if file.metadata()?.len() != len {
+        return Ok(None);
+    }
+    Ok(Some(mapping))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const FILE: &str =
+        "tests/fixtures/simple_app/packs/foo/app/services/foo.rb";
+
+    #[test]
+    fn test_read_contents() {
+        let expected = fs::read_to_string(FILE).unwrap();
+
+        let read = read_contents(Path::new(FILE), None).unwrap();
+        assert!(matches!(read, FileContents::Read(_)));
+        assert_eq!(expected, &*read);
+
+        let mapped = read_contents(Path::new(FILE), Some(1)).unwrap();
+        assert!(matches!(mapped, FileContents::Mapped(_)));
+        assert_eq!(expected, &*mapped);
+    }
+
+    #[test]
+    fn test_read_contents_rejects_invalid_utf8() {
+        let path = std::env::temp_dir()
+            .join(format!("pks_mapped_file_{}.rb", std::process::id()));
+        fs::write(&path, b"class Foo\xff; end").unwrap();
+
+        let error = read_contents(&path, Some(1)).err().unwrap();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
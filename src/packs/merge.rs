@@ -0,0 +1,452 @@
+// Merges one pack into another: its files move into the other pack, and every
+// package.yml and package_todo.yml that referred to it refers to the other
+// pack instead.
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+use super::pack::{write_pack_to_disk, CheckerSetting, Pack};
+use super::package_todo::write_or_delete_package_todo;
+use super::{Configuration, PackageTodo};
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct MergeResult {
+    pub moved_files: usize,
+    // The other packs whose package.yml or package_todo.yml was rewritten
+    pub updated_packs: Vec<String>,
+}
+
+pub(crate) fn merge(
+    configuration: &Configuration,
+    absorbed_name: &str,
+    into_name: &str,
+) -> anyhow::Result<MergeResult> {
+    let pack_set = &configuration.pack_set;
+    let absorbed = pack_set
+        .for_pack(absorbed_name)
+        .context(format!("`{}` not found", absorbed_name))?;
+    let into = pack_set
+        .for_pack(into_name)
+        .context(format!("`{}` not found", into_name))?;
+
+    if absorbed.name == into.name {
+        bail!("Cannot merge `{}` into itself", absorbed.name);
+    }
+    if absorbed.name == "." || into.name == "." {
+        bail!("The root pack cannot be merged");
+    }
+    if let Some(nested) = pack_set.packs.iter().find(|pack| {
+        pack.name != absorbed.name
+            && pack.relative_path.starts_with(&absorbed.relative_path)
+    }) {
+        bail!(
+            "Cannot merge `{}`, since `{}` is nested in it",
+            absorbed.name,
+            nested.name
+        );
+    }
+    if let (Some(absorbed_layer), Some(into_layer)) =
+        (absorbed.layer(), into.layer())
+    {
+        if absorbed_layer != into_layer {
+            bail!(
+                "Cannot merge `{}` (layer `{}`) into `{}` (layer `{}`)",
+                absorbed.name,
+                absorbed_layer,
+                into.name,
+                into_layer
+            );
+        }
+    }
+
+    // Nothing is written until every destination is known to be free
+    let absorbed_dir =
+        configuration.absolute_root.join(&absorbed.relative_path);
+    let into_dir = configuration.absolute_root.join(&into.relative_path);
+    let moves = files_to_move(absorbed, &absorbed_dir, &into_dir);
+    for (_, destination) in &moves {
+        if destination.exists() {
+            bail!(
+                "Cannot merge `{}` into `{}`, since {} already exists",
+                absorbed.name,
+                into.name,
+                relative_to(destination, &configuration.absolute_root)
+            );
+        }
+    }
+
+    for (source, destination) in &moves {
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create directory {}",
+                parent.display()
+            ))?;
+        }
+        fs::rename(source, destination).context(format!(
+            "Failed to move {} to {}",
+            source.display(),
+            destination.display()
+        ))?;
+    }
+
+    write_pack_to_disk(&merged_pack(absorbed, into))?;
+
+    let absorbed_prefix = format!("{}/", absorbed.relative_path.display());
+    let into_prefix = format!("{}/", into.relative_path.display());
+    let move_file = |file: &str| match file.strip_prefix(&absorbed_prefix) {
+        Some(rest) => format!("{}{}", into_prefix, rest),
+        None => file.to_owned(),
+    };
+
+    let mut updated_packs = vec![];
+    for pack in &pack_set.packs {
+        if pack.name == absorbed.name {
+            continue;
+        }
+
+        let mut package_todo = pack.package_todo.clone();
+        if pack.name == into.name {
+            let mut absorbed_todo = absorbed.package_todo.clone();
+            absorbed_todo.rename_files(move_file);
+            package_todo.merge(absorbed_todo);
+        }
+        package_todo.rename_defining_pack(&absorbed.name, &into.name);
+        // References within the merged pack are no longer violations
+        package_todo.retain_defining_packs(|defining_pack_name| {
+            pack.name != into.name || defining_pack_name != into.name
+        });
+        let todo_changed = package_todo != pack.package_todo;
+        if todo_changed {
+            write_or_delete_package_todo(configuration, pack, &package_todo)?;
+        }
+
+        if pack.name == into.name {
+            continue;
+        }
+        let repointed = repointed_pack(pack, &absorbed.name, &into.name);
+        let pack_changed = repointed.dependencies != pack.dependencies
+            || repointed.test_dependencies != pack.test_dependencies
+            || repointed.ignored_dependencies != pack.ignored_dependencies
+            || repointed.visible_to != pack.visible_to
+            || repointed.privacy_exceptions != pack.privacy_exceptions;
+        if pack_changed {
+            write_pack_to_disk(&repointed)?;
+        }
+        if todo_changed || pack_changed {
+            updated_packs.push(pack.name.clone());
+        }
+    }
+    updated_packs.sort();
+
+    // The package_todo.yml of the absorbed pack may be outside of its
+    // directory (see `package_todo_path`)
+    write_or_delete_package_todo(
+        configuration,
+        absorbed,
+        &PackageTodo::default(),
+    )?;
+    // Only package.yml, package_todo.yml, and empty directories are left
+    fs::remove_dir_all(&absorbed_dir)
+        .context(format!("Failed to remove {}", absorbed_dir.display()))?;
+
+    Ok(MergeResult {
+        moved_files: moves.len(),
+        updated_packs,
+    })
+}
+
+// Every file of the absorbed pack, hidden or not, other than its package.yml
+// and package_todo.yml
+fn files_to_move(
+    absorbed: &Pack,
+    absorbed_dir: &Path,
+    into_dir: &Path,
+) -> Vec<(PathBuf, PathBuf)> {
+    let mut moves: Vec<(PathBuf, PathBuf)> = jwalk::WalkDir::new(absorbed_dir)
+        .skip_hidden(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let relative_path = path.strip_prefix(absorbed_dir).ok()?;
+            if relative_path == Path::new("package.yml")
+                || path == absorbed.package_todo_yml
+            {
+                return None;
+            }
+            let destination = into_dir.join(relative_path);
+            Some((path, destination))
+        })
+        .collect();
+    moves.sort();
+    moves
+}
+
+fn relative_to(path: &Path, absolute_root: &Path) -> String {
+    path.strip_prefix(absolute_root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+// The package.yml of `into` once `absorbed` is merged into it, where the most
+// restrictive setting of the two wins
+fn merged_pack(absorbed: &Pack, into: &Pack) -> Pack {
+    let mut merged = into.clone();
+    let is_merged =
+        |name: &String| name == &absorbed.name || name == &into.name;
+    let union = |a: &HashSet<String>, b: &HashSet<String>| -> HashSet<String> {
+        a.union(b)
+            .filter(|name| !is_merged(name))
+            .cloned()
+            .collect()
+    };
+
+    merged.dependencies = union(&into.dependencies, &absorbed.dependencies);
+    merged.test_dependencies =
+        union(&into.test_dependencies, &absorbed.test_dependencies);
+    // The reasons of `into` win
+    merged.ignored_dependencies = absorbed
+        .ignored_dependencies
+        .iter()
+        .chain(&into.ignored_dependencies)
+        .filter(|(name, _)| !is_merged(name))
+        .map(|(name, reason)| (name.clone(), reason.clone()))
+        .collect();
+    merged
+        .private_constants
+        .extend(absorbed.private_constants.iter().cloned());
+    merged
+        .ignored_private_constants
+        .extend(absorbed.ignored_private_constants.iter().cloned());
+    merged.privacy_exceptions =
+        union(&into.privacy_exceptions, &absorbed.privacy_exceptions);
+    for namespace in &absorbed.namespaces {
+        if !merged.namespaces.contains(namespace) {
+            merged.namespaces.push(namespace.clone());
+        }
+    }
+
+    merged.enforce_dependencies = most_restrictive(
+        &into.enforce_dependencies,
+        &absorbed.enforce_dependencies,
+    );
+    merged.enforce_privacy =
+        most_restrictive(&into.enforce_privacy, &absorbed.enforce_privacy);
+    merged.enforce_visibility = most_restrictive(
+        &into.enforce_visibility,
+        &absorbed.enforce_visibility,
+    );
+    merged.enforce_layers =
+        most_restrictive(&into.enforce_layers, &absorbed.enforce_layers);
+    merged.enforce_folder_privacy = most_restrictive(
+        &into.enforce_folder_privacy,
+        &absorbed.enforce_folder_privacy,
+    );
+    merged.enforce_folder_visibility = most_restrictive(
+        &into.enforce_folder_visibility,
+        &absorbed.enforce_folder_visibility,
+    );
+    merged.enforce_architecture = most_restrictive(
+        &into.enforce_architecture,
+        &absorbed.enforce_architecture,
+    );
+
+    // Packs that could see either pack can see the merged pack
+    merged.visible_to = match (&into.visible_to, &absorbed.visible_to) {
+        (Some(a), Some(b)) => Some(union(a, b)),
+        (Some(visible_to), None) | (None, Some(visible_to)) => {
+            Some(union(visible_to, &HashSet::new()))
+        }
+        (None, None) => None,
+    };
+    merged.max_dependencies =
+        min_limit(into.max_dependencies, absorbed.max_dependencies);
+    merged.max_dependents =
+        min_limit(into.max_dependents, absorbed.max_dependents);
+    merged.layer = into.layer.clone().or_else(|| absorbed.layer.clone());
+    merged.owner = into.owner.clone().or_else(|| absorbed.owner.clone());
+    merged.public_paths = merged_public_paths(absorbed, into);
+    for (key, value) in &absorbed.client_keys {
+        merged
+            .client_keys
+            .entry(key.clone())
+            .or_insert_with(|| value.clone());
+    }
+
+    merged
+}
+
+fn most_restrictive(
+    a: &Option<CheckerSetting>,
+    b: &Option<CheckerSetting>,
+) -> Option<CheckerSetting> {
+    let rank = |setting: &Option<CheckerSetting>| match setting {
+        None => 0,
+        Some(CheckerSetting::False) => 1,
+        Some(CheckerSetting::True) => 2,
+        Some(CheckerSetting::StrictFor(_)) => 3,
+        Some(CheckerSetting::Strict) => 4,
+    };
+    if rank(b) > rank(a) {
+        b.clone()
+    } else {
+        a.clone()
+    }
+}
+
+fn min_limit(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+// The public paths of `absorbed` are kept public once its files have moved,
+// which only needs to be spelled out when they differ from those of `into`
+fn merged_public_paths(absorbed: &Pack, into: &Pack) -> Option<Vec<String>> {
+    let into_paths = into.public_paths();
+    let moved_paths: Vec<PathBuf> = absorbed
+        .public_paths()
+        .into_iter()
+        .map(|path| match path.strip_prefix(&absorbed.relative_path) {
+            Ok(rest) => into.relative_path.join(rest),
+            Err(_) => path,
+        })
+        .filter(|path| !into_paths.contains(path))
+        .collect();
+    if moved_paths.is_empty() {
+        return into.public_paths.clone();
+    }
+
+    Some(
+        into_paths
+            .iter()
+            .chain(&moved_paths)
+            .map(|path| {
+                path.strip_prefix(&into.relative_path)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect(),
+    )
+}
+
+// `pack`, with each mention of `absorbed` replaced by `into`
+pub(crate) fn repointed_pack(pack: &Pack, absorbed: &str, into: &str) -> Pack {
+    let repoint = |names: &HashSet<String>| -> HashSet<String> {
+        names
+            .iter()
+            .map(|name| {
+                if name == absorbed {
+                    into.to_owned()
+                } else {
+                    name.clone()
+                }
+            })
+            .filter(|name| name != &pack.name)
+            .collect()
+    };
+
+    let mut repointed = pack.clone();
+    repointed.dependencies = repoint(&pack.dependencies);
+    repointed.test_dependencies = repoint(&pack.test_dependencies);
+    repointed.ignored_dependencies = pack
+        .ignored_dependencies
+        .iter()
+        .map(|(name, reason)| {
+            let name = if name == absorbed { into } else { name };
+            (name.to_owned(), reason.clone())
+        })
+        .filter(|(name, _)| name != &pack.name)
+        .collect();
+    repointed.privacy_exceptions = repoint(&pack.privacy_exceptions);
+    repointed.visible_to = pack.visible_to.as_ref().map(repoint);
+    repointed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pack(name: &str) -> Pack {
+        Pack {
+            name: name.to_owned(),
+            relative_path: PathBuf::from(name),
+            yml: PathBuf::from(name).join("package.yml"),
+            ..Pack::default()
+        }
+    }
+
+    #[test]
+    fn test_merged_pack() {
+        let absorbed = Pack {
+            dependencies: HashSet::from([
+                String::from("packs/baz"),
+                String::from("packs/into"),
+            ]),
+            enforce_dependencies: Some(CheckerSetting::Strict),
+            enforce_privacy: Some(CheckerSetting::False),
+            max_dependencies: Some(3),
+            public_paths: Some(vec![String::from("app/api")]),
+            ..pack("packs/absorbed")
+        };
+        let into = Pack {
+            dependencies: HashSet::from([String::from("packs/qux")]),
+            enforce_dependencies: Some(CheckerSetting::True),
+            enforce_privacy: Some(CheckerSetting::True),
+            max_dependencies: Some(5),
+            ..pack("packs/into")
+        };
+
+        let merged = merged_pack(&absorbed, &into);
+
+        assert_eq!(
+            merged.dependencies,
+            HashSet::from([
+                String::from("packs/baz"),
+                String::from("packs/qux")
+            ])
+        );
+        assert_eq!(merged.enforce_dependencies, Some(CheckerSetting::Strict));
+        assert_eq!(merged.enforce_privacy, Some(CheckerSetting::True));
+        assert_eq!(merged.enforce_layers, None);
+        assert_eq!(merged.max_dependencies, Some(3));
+        assert_eq!(
+            merged.public_paths,
+            Some(vec![String::from("app/public"), String::from("app/api")])
+        );
+    }
+
+    #[test]
+    fn test_repointed_pack() {
+        let dependent = Pack {
+            dependencies: HashSet::from([
+                String::from("packs/absorbed"),
+                String::from("packs/baz"),
+            ]),
+            visible_to: Some(HashSet::from([String::from("packs/absorbed")])),
+            ..pack("packs/dependent")
+        };
+
+        let repointed =
+            repointed_pack(&dependent, "packs/absorbed", "packs/into");
+
+        assert_eq!(
+            repointed.dependencies,
+            HashSet::from([
+                String::from("packs/into"),
+                String::from("packs/baz")
+            ])
+        );
+        assert_eq!(
+            repointed.visible_to,
+            Some(HashSet::from([String::from("packs/into")]))
+        );
+    }
+}
@@ -0,0 +1,344 @@
+// Moves a pack to another directory, which renames it, e.g. `packs/foo` to
+// `packs/customer/foo`. Every package.yml and package_todo.yml that referred
+// to the pack, and each entry in packwerk.yml naming it (e.g. in
+// `layer_globs`), refers to the new name instead.
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context};
+use regex::Regex;
+
+use super::merge::repointed_pack;
+use super::pack::{write_pack_to_disk, Pack};
+use super::package_todo::{package_todo_yml, write_or_delete_package_todo};
+use super::raw_configuration::{self, RawConfiguration};
+use super::walk_directory::is_pack_directory;
+use super::{Configuration, PackageTodo};
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct MovePlan {
+    pub from: String,
+    pub to: String,
+    // The other packs whose package.yml refers to `from`, sorted
+    pub updated_package_ymls: Vec<String>,
+    // The other packs whose package_todo.yml records violations on `from`,
+    // sorted
+    pub updated_package_todos: Vec<String>,
+    // The configuration file, e.g. packwerk.yml, and its 1-based lines naming
+    // `from`
+    pub configuration_file: Option<String>,
+    pub configuration_lines: Vec<usize>,
+}
+
+impl fmt::Display for MovePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Move `{}` to `{}`", self.from, self.to)?;
+        if !self.updated_package_ymls.is_empty() {
+            writeln!(
+                f,
+                "Update the package.yml of: {}",
+                self.updated_package_ymls.join(", ")
+            )?;
+        }
+        if !self.updated_package_todos.is_empty() {
+            writeln!(
+                f,
+                "Update the package_todo.yml of: {}",
+                self.updated_package_todos.join(", ")
+            )?;
+        }
+        if let Some(configuration_file) = &self.configuration_file {
+            if !self.configuration_lines.is_empty() {
+                let lines: Vec<String> = self
+                    .configuration_lines
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect();
+                writeln!(
+                    f,
+                    "Update {} on line(s): {}",
+                    configuration_file,
+                    lines.join(", ")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Nothing is written, and every error is found before `apply` writes
+// anything
+pub(crate) fn plan(
+    configuration: &Configuration,
+    from: &str,
+    to: &str,
+) -> anyhow::Result<MovePlan> {
+    let pack_set = &configuration.pack_set;
+    let pack = pack_set
+        .for_pack(from)
+        .context(format!("`{}` not found", from))?;
+    if pack.name == "." {
+        bail!("The root pack cannot be moved");
+    }
+    let to = normalized(to)?;
+    if to == pack.name {
+        bail!("`{}` is already at `{}`", pack.name, to);
+    }
+    if Path::new(&to).starts_with(&pack.relative_path) {
+        bail!("Cannot move `{}` into itself", pack.name);
+    }
+    if let Some(nested) = pack_set.packs.iter().find(|other| {
+        other.name != pack.name
+            && other.relative_path.starts_with(&pack.relative_path)
+    }) {
+        bail!(
+            "Cannot move `{}`, since `{}` is nested in it",
+            pack.name,
+            nested.name
+        );
+    }
+    if configuration.absolute_root.join(&to).exists() {
+        bail!("Cannot move `{}`, since {} already exists", pack.name, to);
+    }
+    let raw = raw_configuration::get(&configuration.absolute_root)?;
+    if !is_pack_directory(&raw, Path::new(&to)) {
+        bail!(
+            "Cannot move `{}` to {}, since it doesn't match `package_paths` in packwerk.yml",
+            pack.name,
+            to
+        );
+    }
+
+    let mut updated_package_ymls = vec![];
+    let mut updated_package_todos = vec![];
+    for other in &pack_set.packs {
+        if other.name == pack.name {
+            continue;
+        }
+        if repointed_pack(other, &pack.name, &to) != *other {
+            updated_package_ymls.push(other.name.clone());
+        }
+        if other
+            .package_todo
+            .violations_by_defining_pack
+            .contains_key(&pack.name)
+        {
+            updated_package_todos.push(other.name.clone());
+        }
+    }
+    updated_package_ymls.sort();
+    updated_package_todos.sort();
+
+    let configuration_file = configuration_file(configuration);
+    let configuration_lines = match &configuration_file {
+        Some(file) => {
+            let contents =
+                fs::read_to_string(configuration.absolute_root.join(file))
+                    .context(format!("Failed to read {}", file))?;
+            renamed_configuration(&contents, &pack.name, &to).1
+        }
+        None => vec![],
+    };
+
+    Ok(MovePlan {
+        from: pack.name.clone(),
+        to,
+        updated_package_ymls,
+        updated_package_todos,
+        configuration_file,
+        configuration_lines,
+    })
+}
+
+pub(crate) fn apply(
+    configuration: &Configuration,
+    plan: &MovePlan,
+) -> anyhow::Result<()> {
+    let absolute_root = &configuration.absolute_root;
+    let pack = configuration.pack_set.for_pack(&plan.from)?;
+    let raw: RawConfiguration = raw_configuration::get(absolute_root)?;
+
+    let from_dir = absolute_root.join(&pack.relative_path);
+    let to_dir = absolute_root.join(&plan.to);
+    if let Some(parent) = to_dir.parent() {
+        fs::create_dir_all(parent).context(format!(
+            "Failed to create directory {}",
+            parent.display()
+        ))?;
+    }
+    fs::rename(&from_dir, &to_dir).context(format!(
+        "Failed to move {} to {}",
+        from_dir.display(),
+        to_dir.display()
+    ))?;
+
+    let from_prefix = format!("{}/", pack.relative_path.display());
+    let to_prefix = format!("{}/", plan.to);
+    let move_file = |file: &str| match file.strip_prefix(&from_prefix) {
+        Some(rest) => format!("{}{}", to_prefix, rest),
+        None => file.to_owned(),
+    };
+
+    // The package_todo.yml of the moved pack moved along with it, unless
+    // `package_todo_path` puts it elsewhere or violations aren't recorded in
+    // package_todo.yml files (see `violation_store`)
+    let mut moved = Pack {
+        name: plan.to.clone(),
+        relative_path: PathBuf::from(&plan.to),
+        yml: to_dir.join("package.yml"),
+        ..pack.clone()
+    };
+    moved.package_todo_yml = package_todo_yml(
+        &moved,
+        absolute_root,
+        raw.package_todo_path.as_deref(),
+    )?;
+    write_or_delete_package_todo(configuration, pack, &PackageTodo::default())?;
+    let mut package_todo = pack.package_todo.clone();
+    package_todo.rename_files(move_file);
+    write_or_delete_package_todo(configuration, &moved, &package_todo)?;
+
+    for other in &configuration.pack_set.packs {
+        if other.name == pack.name {
+            continue;
+        }
+        let repointed = repointed_pack(other, &pack.name, &plan.to);
+        if repointed != *other {
+            write_pack_to_disk(&repointed)?;
+        }
+        let mut package_todo = other.package_todo.clone();
+        package_todo.rename_defining_pack(&pack.name, &plan.to);
+        package_todo.rename_defining_files(move_file);
+        if package_todo != other.package_todo {
+            write_or_delete_package_todo(configuration, other, &package_todo)?;
+        }
+    }
+
+    if let Some(file) = &plan.configuration_file {
+        let path = absolute_root.join(file);
+        let contents = fs::read_to_string(&path)
+            .context(format!("Failed to read {}", file))?;
+        let (renamed, lines) =
+            renamed_configuration(&contents, &pack.name, &plan.to);
+        if !lines.is_empty() {
+            fs::write(&path, renamed)
+                .context(format!("Failed to write {}", file))?;
+        }
+    }
+    Ok(())
+}
+
+// The destination relative to the project root, without a trailing slash
+fn normalized(to: &str) -> anyhow::Result<String> {
+    let path = Path::new(to.trim_end_matches('/'));
+    if path.as_os_str().is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        bail!(
+            "`{}` must be a directory relative to the project root, e.g. `packs/customer/foo`",
+            to
+        );
+    }
+    Ok(path.display().to_string())
+}
+
+fn configuration_file(configuration: &Configuration) -> Option<String> {
+    ["packwerk.yml", "packs.yml"]
+        .into_iter()
+        .find(|file| configuration.absolute_root.join(file).exists())
+        .map(String::from)
+}
+
+// The configuration with each value that is exactly `from`, e.g. a list item
+// in `layer_globs` or the `from` of an architecture exception, replaced by
+// `to`, and the 1-based lines replaced on. Globs matching `from` along with
+// other packs, e.g. `packs/*`, are left alone.
+fn renamed_configuration(
+    contents: &str,
+    from: &str,
+    to: &str,
+) -> (String, Vec<usize>) {
+    let value = Regex::new(&format!(
+        r#"^(\s*(?:-\s+)?(?:[\w-]+:\s+)?)(['"]?){}(/?)(['"]?)(\s*(?:#.*)?)$"#,
+        regex::escape(from)
+    ))
+    .unwrap();
+    let mut replaced_lines = vec![];
+    let lines: Vec<String> = contents
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| match value.captures(line) {
+            Some(captures) if captures[2] == captures[4] => {
+                replaced_lines.push(index + 1);
+                format!(
+                    "{}{}{}{}{}{}",
+                    &captures[1],
+                    &captures[2],
+                    to,
+                    &captures[3],
+                    &captures[4],
+                    &captures[5]
+                )
+            }
+            _ => line.to_owned(),
+        })
+        .collect();
+    (lines.join("\n"), replaced_lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_renamed_configuration() {
+        let contents = "layers:
+  - product
+  - utilities
+layer_globs:
+  product:
+    - packs/foo/
+    - 'packs/foo' # quoted
+    - packs/foo_bar
+    - packs/*
+architecture_exceptions:
+  - from: packs/foo
+    to: packs/utils
+";
+        let (renamed, lines) =
+            renamed_configuration(contents, "packs/foo", "packs/customer/foo");
+
+        assert_eq!(vec![6, 7, 11], lines);
+        assert_eq!(
+            "layers:
+  - product
+  - utilities
+layer_globs:
+  product:
+    - packs/customer/foo/
+    - 'packs/customer/foo' # quoted
+    - packs/foo_bar
+    - packs/*
+architecture_exceptions:
+  - from: packs/customer/foo
+    to: packs/utils
+",
+            renamed
+        );
+    }
+
+    #[test]
+    fn test_normalized() {
+        assert_eq!(
+            "packs/customer/foo",
+            normalized("packs/customer/foo/").unwrap()
+        );
+        assert!(normalized("../foo").is_err());
+        assert!(normalized("/packs/foo").is_err());
+        assert!(normalized("").is_err());
+    }
+}
@@ -0,0 +1,297 @@
+// Posts new violations found by `check --notify` to the webhooks configured
+// under `notifications:` in packwerk.yml, once per team owning the packs
+// that introduced them, so that violations introduced on main reach owners.
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context};
+use serde::Serialize;
+
+use super::checker::Violation;
+use super::raw_configuration::{Notification, NotificationFormat};
+use super::Configuration;
+
+// The team of violations from packs without an `owner:`
+pub(crate) const UNOWNED: &str = "unowned";
+
+const DEFAULT_SLACK_TEXT: &str =
+    "{count} new pks violation(s) for {team}:\n{violations}";
+
+// So that an unresponsive webhook doesn't hold up CI
+const CONNECT_TIMEOUT_SECONDS: &str = "10";
+const MAX_TIME_SECONDS: &str = "30";
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct NotifiedViolation<'a> {
+    violation_type: &'a str,
+    file: &'a str,
+    line: usize,
+    column: usize,
+    constant_name: &'a str,
+    referencing_pack_name: &'a str,
+    defining_pack_name: &'a str,
+    message: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct TeamSummary<'a> {
+    team: &'a str,
+    violation_count: usize,
+    violations: Vec<NotifiedViolation<'a>>,
+}
+
+fn summaries_by_team<'a>(
+    configuration: &'a Configuration,
+    violations: impl Iterator<Item = &'a Violation>,
+) -> Vec<TeamSummary<'a>> {
+    let mut violations_by_team: BTreeMap<&str, Vec<NotifiedViolation>> =
+        BTreeMap::new();
+    for violation in violations {
+        let identifier = &violation.identifier;
+        let team = configuration
+            .pack_set
+            .for_pack(&identifier.referencing_pack_name)
+            .ok()
+            .and_then(|pack| pack.owner.as_deref())
+            .unwrap_or(UNOWNED);
+        violations_by_team
+            .entry(team)
+            .or_default()
+            .push(NotifiedViolation {
+                violation_type: &identifier.violation_type,
+                file: &identifier.file,
+                line: violation.source_location.line,
+                column: violation.source_location.column,
+                constant_name: &identifier.constant_name,
+                referencing_pack_name: &identifier.referencing_pack_name,
+                defining_pack_name: &identifier.defining_pack_name,
+                message: violation.description(),
+            });
+    }
+
+    violations_by_team
+        .into_iter()
+        .map(|(team, mut violations)| {
+            violations.sort_by(|a, b| {
+                (a.file, a.line, a.column).cmp(&(b.file, b.line, b.column))
+            });
+            TeamSummary {
+                team,
+                violation_count: violations.len(),
+                violations,
+            }
+        })
+        .collect()
+}
+
+fn payload(
+    notification: &Notification,
+    summary: &TeamSummary,
+) -> anyhow::Result<String> {
+    let payload = match notification.format {
+        NotificationFormat::Json => serde_json::to_string(summary)?,
+        NotificationFormat::Slack => {
+            let violations: Vec<String> = summary
+                .violations
+                .iter()
+                .map(|v| format!("• {}:{}: {}", v.file, v.line, v.message))
+                .collect();
+            let text = notification
+                .text
+                .as_deref()
+                .unwrap_or(DEFAULT_SLACK_TEXT)
+                .replace("{team}", summary.team)
+                .replace("{count}", &summary.violation_count.to_string())
+                .replace("{violations}", &violations.join("\n"));
+            serde_json::json!({ "text": text }).to_string()
+        }
+    };
+    Ok(payload)
+}
+
+fn url(notification: &Notification) -> anyhow::Result<String> {
+    match (&notification.url, &notification.url_env) {
+        (Some(url), _) => Ok(url.clone()),
+        (None, Some(url_env)) => std::env::var(url_env).context(format!(
+            "The webhook URL environment variable `{}` is not set",
+            url_env
+        )),
+        (None, None) => bail!("Notifications need a `url` or a `url_env`"),
+    }
+}
+
+// A curl config with the URL and the payload, read from stdin, since webhook
+// URLs often hold secrets, and the arguments of a process are visible to
+// other users, e.g. in `ps`
+fn curl_config(url: &str, payload: &str) -> String {
+    // The payload is a JSON object, so it doesn't start with the `@` of a
+    // file to read
+    format!("url = {}\ndata-binary = {}\n", quoted(url), quoted(payload))
+}
+
+fn quoted(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Uses curl, which is available on CI runners, rather than an HTTP client
+fn post(url: &str, payload: &str) -> anyhow::Result<()> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--request", "POST"])
+        .args(["--connect-timeout", CONNECT_TIMEOUT_SECONDS])
+        .args(["--max-time", MAX_TIME_SECONDS])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .context("Failed to run curl")?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open curl's stdin")?
+        .write_all(curl_config(url, payload).as_bytes())?;
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("curl exited with {}", status);
+    }
+    Ok(())
+}
+
+// Returns the number of notifications sent. Failures are printed as warnings
+// and don't stop the other notifications, nor hide the violations themselves.
+pub(crate) fn notify<'a>(
+    configuration: &'a Configuration,
+    violations: impl Iterator<Item = &'a Violation>,
+) -> usize {
+    let summaries = summaries_by_team(configuration, violations);
+    let mut sent = 0;
+    for notification in &configuration.notifications {
+        let url = match url(notification) {
+            Ok(url) => url,
+            Err(err) => {
+                eprintln!("Warning: failed to send notifications: {:#}", err);
+                continue;
+            }
+        };
+        for summary in &summaries {
+            if !notification.teams.is_empty()
+                && !notification.teams.iter().any(|team| team == summary.team)
+            {
+                continue;
+            }
+            match payload(notification, summary)
+                .and_then(|payload| post(&url, &payload))
+            {
+                Ok(()) => sent += 1,
+                Err(err) => eprintln!(
+                    "Warning: failed to notify about the violations of {}: {:#}",
+                    summary.team, err
+                ),
+            }
+        }
+    }
+    sent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn summary() -> TeamSummary<'static> {
+        TeamSummary {
+            team: "payments",
+            violation_count: 1,
+            violations: vec![NotifiedViolation {
+                violation_type: "privacy",
+                file: "packs/foo/app/services/foo.rb",
+                line: 3,
+                column: 4,
+                constant_name: "::Bar",
+                referencing_pack_name: "packs/foo",
+                defining_pack_name: "packs/bar",
+                message: String::from("Privacy violation: `::Bar` is private"),
+            }],
+        }
+    }
+
+    fn notification(format: NotificationFormat) -> Notification {
+        Notification {
+            url: Some(String::from("https://example.com/hook")),
+            url_env: None,
+            format,
+            text: None,
+            teams: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_json_payload() {
+        let actual =
+            payload(&notification(NotificationFormat::Json), &summary())
+                .unwrap();
+
+        assert_eq!(
+            r#"{"team":"payments","violation_count":1,"violations":[{"violation_type":"privacy","file":"packs/foo/app/services/foo.rb","line":3,"column":4,"constant_name":"::Bar","referencing_pack_name":"packs/foo","defining_pack_name":"packs/bar","message":"Privacy violation: `::Bar` is private"}]}"#,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_slack_payload() {
+        let actual =
+            payload(&notification(NotificationFormat::Slack), &summary())
+                .unwrap();
+
+        assert_eq!(
+            r#"{"text":"1 new pks violation(s) for payments:\n• packs/foo/app/services/foo.rb:3: Privacy violation: `::Bar` is private"}"#,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_slack_payload_with_custom_text() {
+        let notification = Notification {
+            text: Some(String::from("<!subteam^{team}> {count} new")),
+            ..notification(NotificationFormat::Slack)
+        };
+
+        let actual = payload(&notification, &summary()).unwrap();
+
+        assert_eq!(r#"{"text":"<!subteam^payments> 1 new"}"#, actual);
+    }
+
+    #[test]
+    fn test_curl_config() {
+        assert_eq!(
+            "url = \"https://example.com/hook?token=a\\\"b\"\ndata-binary = \"{\\\"text\\\":\\\"a\\\\nb\\\"}\"\n",
+            curl_config(
+                "https://example.com/hook?token=a\"b",
+                r#"{"text":"a\nb"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_url_requires_url_or_url_env() {
+        let notification = Notification {
+            url: None,
+            ..notification(NotificationFormat::Json)
+        };
+
+        assert!(url(&notification).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+// An additional output of `check` or `update`, so that a single run can print
+// violations for people and write them for tools, e.g. in CI
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::Context;
+use clap::ValueEnum;
+
+use crate::packs::cli::CheckOutputFormat;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputSink {
+    pub format: CheckOutputFormat,
+    // Printed to stdout without a path
+    pub path: Option<PathBuf>,
+}
+
+// Parses `FORMAT=PATH`, or `FORMAT` (or `FORMAT=-`) for stdout
+impl FromStr for OutputSink {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (format, path) = match s.split_once('=') {
+            Some((format, path)) => (format, Some(path)),
+            None => (s, None),
+        };
+        let format =
+            CheckOutputFormat::from_str(format, true).map_err(|_| {
+                format!(
+                    "unknown output format `{}`, expected one of: {}",
+                    format,
+                    CheckOutputFormat::value_variants()
+                        .iter()
+                        .filter_map(|v| v.to_possible_value())
+                        .map(|v| v.get_name().to_owned())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            })?;
+        let path = match path {
+            None | Some("-") => None,
+            Some("") => return Err(String::from("missing path after `=`")),
+            Some(path) => Some(PathBuf::from(path)),
+        };
+        Ok(Self { format, path })
+    }
+}
+
+impl OutputSink {
+    pub(crate) fn write(&self, contents: &str) -> anyhow::Result<()> {
+        match &self.path {
+            None => {
+                let mut stdout = std::io::stdout().lock();
+                stdout
+                    .write_all(contents.as_bytes())
+                    .context("Failed to write to stdout")?;
+                stdout.flush().context("Failed to write to stdout")
+            }
+            Some(path) => {
+                if let Some(parent) = path.parent() {
+                    if !parent.as_os_str().is_empty() {
+                        fs::create_dir_all(parent).context(format!(
+                            "Failed to create {}",
+                            parent.display()
+                        ))?;
+                    }
+                }
+                fs::write(path, contents)
+                    .context(format!("Failed to write {}", path.display()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_output_sink() {
+        assert_eq!(
+            OutputSink::from_str("json=artifacts/violations.json"),
+            Ok(OutputSink {
+                format: CheckOutputFormat::Json,
+                path: Some(PathBuf::from("artifacts/violations.json")),
+            })
+        );
+        assert_eq!(
+            OutputSink::from_str("quickfix"),
+            Ok(OutputSink {
+                format: CheckOutputFormat::Quickfix,
+                path: None,
+            })
+        );
+        assert_eq!(
+            OutputSink::from_str("human=-"),
+            Ok(OutputSink {
+                format: CheckOutputFormat::Human,
+                path: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_output_sink() {
+        assert_eq!(
+            OutputSink::from_str("xml=out.xml"),
+            Err(String::from(
+                "unknown output format `xml`, expected one of: human, quickfix, json, rubocop"
+            ))
+        );
+        assert_eq!(
+            OutputSink::from_str("json="),
+            Err(String::from("missing path after `=`"))
+        );
+    }
+}
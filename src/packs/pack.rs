@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fs::File,
     hash::Hasher,
     io::Read,
@@ -8,13 +8,34 @@ use std::{
 
 use anyhow::Context;
 use core::hash::Hash;
+use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_yaml::Value;
 
 use super::{
-    checker::ViolationIdentifier, file_utils::expand_glob, ignored, PackageTodo,
+    checker::ViolationIdentifier,
+    file_utils::expand_glob,
+    ignored,
+    package_todo::{self, Occurrences},
+    PackageTodo,
 };
 
+// Directories of `app/` that Rails doesn't autoload
+const NON_AUTOLOADED_APP_DIRS: [&str; 3] = ["assets", "javascript", "views"];
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct Waiver {
+    pub constant: String,
+
+    pub referencing_pack: String,
+
+    // Why the violation is temporarily permitted (required)
+    pub reason: String,
+
+    // The last day the waiver applies, e.g. `2024-03-31`
+    pub expires_on: String,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct Pack {
     #[serde(skip)]
@@ -64,6 +85,16 @@ pub struct Pack {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub layer: Option<String>,
 
+    // The layer `layer_globs` in packwerk.yml assigns to the pack, which is
+    // never written to package.yml (see `Pack::layer`)
+    #[serde(skip)]
+    pub inherited_layer: Option<String>,
+
+    // Arbitrary labels, e.g. `pci`, which `tag_policies` in packwerk.yml
+    // constrain the dependencies of
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
     #[serde(
         default,
         skip_serializing_if = "HashSet::is_empty",
@@ -71,12 +102,44 @@ pub struct Pack {
     )]
     pub dependencies: HashSet<String>,
 
+    // Dependencies listed more than once in package.yml, which `dependencies`
+    // can't tell, sorted. Writing the pack removes them.
+    #[serde(skip)]
+    pub duplicate_dependencies: Vec<String>,
+
+    // Budgets on the number of declared dependencies of this pack, and of
+    // packs declaring it as a dependency, enforced by `validate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_dependencies: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_dependents: Option<usize>,
+
+    // The days recorded violations of the checkers this pack enforces in
+    // strict mode are treated as recorded rather than strict, overriding
+    // `strict_mode_grace_period_days` in packwerk.yml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict_mode_grace_period_days: Option<u64>,
+
+    // Dependencies that may only be referenced from test files (see `test_paths`
+    // in packwerk.yml)
     #[serde(
         default,
         skip_serializing_if = "HashSet::is_empty",
         serialize_with = "serialize_sorted_hashset_of_strings"
     )]
-    pub ignored_dependencies: HashSet<String>,
+    pub test_dependencies: HashSet<String>,
+
+    // Packs that may be referenced without a dependency on them, e.g.
+    // framework-level packs referenced everywhere, with the reasons they're
+    // ignored, if given
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        serialize_with = "serialize_ignored_dependencies",
+        deserialize_with = "deserialize_ignored_dependencies"
+    )]
+    pub ignored_dependencies: BTreeMap<String, Option<String>>,
 
     #[serde(
         default,
@@ -92,9 +155,33 @@ pub struct Pack {
     )]
     pub private_constants: HashSet<String>,
 
+    // Names of referencing packs, which may be globs, that may reference the
+    // private constants of this pack
+    #[serde(
+        default,
+        skip_serializing_if = "HashSet::is_empty",
+        serialize_with = "serialize_sorted_hashset_of_strings"
+    )]
+    pub privacy_exceptions: HashSet<String>,
+
+    // Violations of this pack's constants that referencing packs may commit
+    // until the waivers expire
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waivers: Vec<Waiver>,
+
     #[serde(skip)]
     pub package_todo: PackageTodo,
 
+    // Where the package_todo.yml file of this pack is, relative to the pack
+    // directory, when it isn't in the pack directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_todo_path: Option<String>,
+
+    // The absolute path of the package_todo.yml file of this pack (see
+    // `package_todo::package_todo_yml`)
+    #[serde(skip)]
+    pub package_todo_yml: PathBuf,
+
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -102,6 +189,12 @@ pub struct Pack {
     )]
     pub visible_to: Option<HashSet<String>>,
 
+    // Namespaces, e.g. `Billing`, whose constants belong to this pack even
+    // when defined in files of the root pack, e.g. in `app/models`, to ease
+    // extracting them gradually
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub namespaces: Vec<String>,
+
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -118,9 +211,21 @@ pub struct Pack {
     )]
     pub enforce_folder_visibility: Option<CheckerSetting>, // deprecated
 
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_checker_setting",
+        deserialize_with = "deserialize_checker_setting"
+    )]
+    pub enforce_architecture: Option<CheckerSetting>, // deprecated
+
     #[serde(skip_serializing_if = "is_default_public_folder")]
     pub public_folder: Option<PathBuf>,
 
+    // Relative to the pack, and may be globs. Takes precedence over `public_folder`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_paths: Option<Vec<String>>,
+
     #[serde(flatten)]
     pub client_keys: HashMap<String, Value>,
 
@@ -128,12 +233,54 @@ pub struct Pack {
     pub enforcement_globs_ignore: Option<Vec<EnforcementGlobsIgnore>>,
 }
 
+// The public paths of a pack, relative to the project root: files and
+// directories, which match the files in them, and globs
+#[derive(Debug, Default)]
+pub(crate) struct PublicPathMatcher {
+    directories: Vec<PathBuf>,
+    globs: Vec<glob::Pattern>,
+}
+
+impl PublicPathMatcher {
+    pub(crate) fn matches(&self, relative_file: &str) -> bool {
+        let path = Path::new(relative_file);
+        self.directories
+            .iter()
+            .any(|directory| path.starts_with(directory))
+            || self.globs.iter().any(|glob| glob.matches(relative_file))
+    }
+}
+
 impl Hash for Pack {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.name.hash(state);
     }
 }
 
+// The dependencies as listed, including any duplicates
+#[derive(Deserialize)]
+struct ListedDependencies {
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+fn duplicate_dependencies(package_yml_contents: &str) -> Vec<String> {
+    let Ok(listed) =
+        serde_yaml::from_str::<ListedDependencies>(package_yml_contents)
+    else {
+        return vec![];
+    };
+    let mut seen = HashSet::new();
+    let mut duplicates: Vec<String> = listed
+        .dependencies
+        .into_iter()
+        .filter(|dependency| !seen.insert(dependency.clone()))
+        .collect();
+    duplicates.sort();
+    duplicates.dedup();
+    duplicates
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize, Clone)]
 pub struct EnforcementGlobsIgnore {
     #[serde(
@@ -160,6 +307,11 @@ pub enum CheckerSetting {
     False,
     True,
     Strict,
+    // Enforced, and strict only for violations involving these packs (names
+    // or globs): the referencing packs of privacy, visibility and folder
+    // privacy violations, and the defining packs of dependency and layer
+    // violations
+    StrictFor(Vec<String>),
 }
 
 impl CheckerSetting {
@@ -170,6 +322,20 @@ impl CheckerSetting {
     pub fn is_strict(&self) -> bool {
         matches!(self, Self::Strict)
     }
+
+    // Whether violations involving the other pack are strict
+    pub fn is_strict_for(&self, other_pack_name: &str) -> bool {
+        match self {
+            Self::Strict => true,
+            Self::StrictFor(patterns) => patterns.iter().any(|pattern| {
+                pattern == other_pack_name
+                    || glob::Pattern::new(pattern)
+                        .map(|pattern| pattern.matches(other_pack_name))
+                        .unwrap_or(false)
+            }),
+            Self::False | Self::True => false,
+        }
+    }
 }
 
 impl Pack {
@@ -178,32 +344,49 @@ impl Pack {
     }
 
     pub fn all_violations(&self) -> Vec<ViolationIdentifier> {
-        let mut violations = Vec::new();
-        let violations_by_pack = &self.package_todo.violations_by_defining_pack;
-        for (defining_pack_name, violation_groups) in violations_by_pack {
-            for (constant_name, violation_group) in violation_groups {
-                for violation_type in &violation_group.violation_types {
-                    for file in &violation_group.files {
-                        let identifier = ViolationIdentifier {
-                            violation_type: violation_type.clone(),
-                            strict: false,
-                            file: file.clone(),
-                            constant_name: constant_name.clone(),
-                            referencing_pack_name: self.name.clone(),
-                            defining_pack_name: defining_pack_name.clone(),
-                        };
-
-                        violations.push(identifier);
-                    }
-                }
-            }
+        self.package_todo.violation_identifiers(&self.name)
+    }
+
+    // Only recorded in the v2 package_todo.yml format
+    pub fn recorded_occurrences(
+        &self,
+        defining_pack_name: &str,
+        constant_name: &str,
+        file: &str,
+    ) -> Option<&Occurrences> {
+        self.package_todo
+            .violations_by_defining_pack
+            .get(defining_pack_name)?
+            .get(constant_name)?
+            .occurrences
+            .get(file)
+    }
+
+    // Only recorded when a strict mode grace period is configured
+    pub fn recorded_on(
+        &self,
+        violation_type: &str,
+        defining_pack_name: &str,
+        constant_name: &str,
+        file: &str,
+    ) -> Option<&str> {
+        let violation_group = self
+            .package_todo
+            .violations_by_defining_pack
+            .get(defining_pack_name)?
+            .get(constant_name)?;
+        if !violation_group.violation_types.contains(violation_type) {
+            return None;
         }
-        violations
+        violation_group.recorded_on.get(file).map(String::as_str)
     }
 
+    // `package_todo_path` is the location of package_todo.yml files set in
+    // packwerk.yml, if any
     pub fn from_path(
         package_yml_absolute_path: &Path,
         absolute_root: &Path,
+        package_todo_path: Option<&str>,
     ) -> anyhow::Result<Pack> {
         let mut yaml_contents = String::new();
 
@@ -221,35 +404,20 @@ impl Pack {
             ))
         })?;
 
-        let absolute_path_to_package_todo = package_yml_absolute_path
-            .parent()
-            .unwrap()
-            .join("package_todo.yml");
-
-        let package_todo: PackageTodo = if absolute_path_to_package_todo
-            .exists()
-        {
-            let mut package_todo_contents = String::new();
-            let mut file = File::open(&absolute_path_to_package_todo)
-                .context("Failed to open the package_todo.yml file")?;
-            file.read_to_string(&mut package_todo_contents)
-                .context("Could not read the package_todo.yml file")?;
-            serde_yaml::from_str(&package_todo_contents).with_context(|| {
-                format!(
-                    "Failed to deserialize the package_todo.yml file at {}. Try deleting the file and running the `update` command to regenerate it.",
-                    absolute_path_to_package_todo.display()
-                )
-            })?
-        } else {
-            PackageTodo::default()
-        };
-
-        Pack::from_contents(
+        let mut pack = Pack::from_contents(
             package_yml_absolute_path,
             absolute_root,
             &yaml_contents,
-            package_todo,
-        )
+            PackageTodo::default(),
+        )?;
+        pack.package_todo_yml = package_todo::package_todo_yml(
+            &pack,
+            absolute_root,
+            package_todo_path,
+        )?;
+        pack.package_todo =
+            package_todo::read_package_todo(&pack.package_todo_yml)?;
+        Ok(pack)
     }
 
     pub fn from_contents(
@@ -290,22 +458,35 @@ impl Pack {
             relative_path = PathBuf::from(".");
         };
 
-        let pack: Pack = Pack {
+        let mut pack: Pack = Pack {
             yml: yml.to_path_buf(),
             name,
             relative_path,
             package_todo,
+            duplicate_dependencies: duplicate_dependencies(
+                package_yml_contents,
+            ),
             ..pack
         };
+        pack.package_todo_yml =
+            package_todo::package_todo_yml(&pack, absolute_root, None)?;
 
         Ok(pack)
     }
 
+    // Like Rails, every directory of `app/` and its `concerns/`, except for
+    // those that aren't Ruby code
     pub fn default_autoload_roots(&self) -> Vec<PathBuf> {
-        let root_pattern = self.yml.parent().unwrap().join("app").join("*");
+        let app = self.yml.parent().unwrap().join("app");
+        let root_pattern = app.join("*");
         let concerns_pattern = root_pattern.join("concerns");
         let mut roots = expand_glob(root_pattern.to_str().unwrap());
         roots.extend(expand_glob(concerns_pattern.to_str().unwrap()));
+        roots.retain(|root| {
+            !NON_AUTOLOADED_APP_DIRS
+                .iter()
+                .any(|dir| root.starts_with(app.join(dir)))
+        });
 
         roots
     }
@@ -314,6 +495,20 @@ impl Pack {
         self.relative_path.join("package.yml")
     }
 
+    // The layer set in package.yml, or else the one inherited from
+    // `layer_globs`
+    pub fn layer(&self) -> Option<&String> {
+        self.layer.as_ref().or(self.inherited_layer.as_ref())
+    }
+
+    pub(crate) fn enforce_layers(&self) -> &CheckerSetting {
+        // enforce_architecture is deprecated
+        match (&self.enforce_layers, &self.enforce_architecture) {
+            (Some(setting), _) | (None, Some(setting)) => setting,
+            (None, None) => &CheckerSetting::False,
+        }
+    }
+
     pub(crate) fn enforce_folder_privacy(&self) -> &CheckerSetting {
         if self.enforce_folder_privacy.is_none() {
             // enforce_folder_visibility is deprecated
@@ -336,6 +531,70 @@ impl Pack {
         }
     }
 
+    // Relative to the project root
+    pub(crate) fn public_paths(&self) -> Vec<PathBuf> {
+        match &self.public_paths {
+            Some(paths) => paths
+                .iter()
+                .map(|path| {
+                    let path = path.trim_end_matches('/');
+                    if self.name == "." {
+                        PathBuf::from(path)
+                    } else {
+                        self.relative_path.join(path)
+                    }
+                })
+                .collect(),
+            None => vec![self.public_folder()],
+        }
+    }
+
+    // Compiled once per pack set, since the privacy checker matches every
+    // reference against it (see `PackSet::is_public_file`)
+    pub(crate) fn public_path_matcher(&self) -> PublicPathMatcher {
+        let mut matcher = PublicPathMatcher::default();
+        for public_path in self.public_paths() {
+            let public_path_str = public_path.to_string_lossy();
+            if public_path_str.contains(['*', '?', '[']) {
+                // Invalid globs match nothing
+                if let Ok(pattern) = glob::Pattern::new(&public_path_str) {
+                    matcher.globs.push(pattern);
+                }
+            } else {
+                matcher.directories.push(public_path);
+            }
+        }
+        matcher
+    }
+
+    // Whether a waiver of this pack lets `referencing_pack_name` violate
+    // `constant_name` on `today`, e.g. `2024-03-31`
+    pub(crate) fn is_waived(
+        &self,
+        constant_name: &str,
+        referencing_pack_name: &str,
+        today: &str,
+    ) -> bool {
+        self.waivers.iter().any(|waiver| {
+            waiver.constant.trim_start_matches("::")
+                == constant_name.trim_start_matches("::")
+                && waiver.referencing_pack == referencing_pack_name
+                && today <= waiver.expires_on.as_str()
+        })
+    }
+
+    pub(crate) fn is_privacy_exception(
+        &self,
+        referencing_pack_name: &str,
+    ) -> bool {
+        self.privacy_exceptions.iter().any(|exception| {
+            exception == referencing_pack_name
+                || glob::Pattern::new(exception)
+                    .map(|pattern| pattern.matches(referencing_pack_name))
+                    .unwrap_or(false)
+        })
+    }
+
     pub(crate) fn add_dependency(&self, to_pack: &Pack) -> Pack {
         let mut new_pack = self.clone();
         new_pack.dependencies.insert(to_pack.name.clone());
@@ -379,6 +638,64 @@ where
     value.serialize(serializer)
 }
 
+// An entry of `ignored_dependencies:`, which is the name of the pack, or the
+// name with the reason it's ignored:
+//
+//   ignored_dependencies:
+//     - packs/logging
+//     - pack: packs/framework
+//       reason: Referenced by every pack
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum IgnoredDependency {
+    Pack(String),
+    WithReason { pack: String, reason: String },
+}
+
+fn serialize_ignored_dependencies<S>(
+    value: &BTreeMap<String, Option<String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let entries: Vec<IgnoredDependency> = value
+        .iter()
+        .map(|(pack, reason)| match reason {
+            Some(reason) => IgnoredDependency::WithReason {
+                pack: pack.clone(),
+                reason: reason.clone(),
+            },
+            None => IgnoredDependency::Pack(pack.clone()),
+        })
+        .collect();
+    entries.serialize(serializer)
+}
+
+fn deserialize_ignored_dependencies<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Option<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entries: Vec<IgnoredDependency> = Vec::deserialize(deserializer)
+        .map_err(|error| {
+            serde::de::Error::custom(format!(
+                "expected `ignored_dependencies` to list pack names, or `pack:` and `reason:` pairs: {}",
+                error
+            ))
+        })?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| match entry {
+            IgnoredDependency::Pack(pack) => (pack, None),
+            IgnoredDependency::WithReason { pack, reason } => {
+                (pack, Some(reason))
+            }
+        })
+        .collect())
+}
+
 fn serialize_sorted_option_hashset_of_strings<S>(
     value: &Option<HashSet<String>>,
     serializer: S,
@@ -408,12 +725,20 @@ const KEY_SORT_ORDER: &[&str] = &[
     "enforce_folder_visibility",
     "enforce_architecture",
     "layer",
+    "tags",
     "public_path",
+    "public_paths",
     "dependencies",
+    "max_dependencies",
+    "max_dependents",
     "owner",
     "private_constants",
+    "privacy_exceptions",
+    "waivers",
     "visible_to",
+    "namespaces",
     "enforcement_globs_ignore",
+    "package_todo_path",
     "metadata",
 ];
 
@@ -498,6 +823,13 @@ where
         Some(CheckerSetting::False) => serializer.serialize_bool(false),
         Some(CheckerSetting::True) => serializer.serialize_bool(true),
         Some(CheckerSetting::Strict) => serializer.serialize_str("strict"),
+        Some(CheckerSetting::StrictFor(patterns)) => {
+            let mut sorted_patterns: Vec<&String> = patterns.iter().collect();
+            sorted_patterns.sort();
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry("strict_for", &sorted_patterns)?;
+            map.end()
+        }
         None => serializer.serialize_none(),
     }
 }
@@ -508,16 +840,32 @@ fn deserialize_checker_setting<'de, D>(
 where
     D: Deserializer<'de>,
 {
-    // Deserialize an optional String
-    let s = String::deserialize(deserializer);
-
-    match s.unwrap().as_str() {
-        "false" => Ok(Some(CheckerSetting::False)),
-        "true" => Ok(Some(CheckerSetting::True)),
-        "strict" => Ok(Some(CheckerSetting::Strict)),
-        _ => Err(serde::de::Error::custom(
-            "expected one of: false, true, strict",
-        )),
+    let invalid = || {
+        serde::de::Error::custom(
+            "expected one of: false, true, strict, or a `strict_for:` list of packs",
+        )
+    };
+    match Value::deserialize(deserializer)? {
+        Value::Bool(false) => Ok(Some(CheckerSetting::False)),
+        Value::Bool(true) => Ok(Some(CheckerSetting::True)),
+        Value::String(s) => match s.as_str() {
+            "false" => Ok(Some(CheckerSetting::False)),
+            "true" => Ok(Some(CheckerSetting::True)),
+            "strict" => Ok(Some(CheckerSetting::Strict)),
+            _ => Err(invalid()),
+        },
+        Value::Mapping(mapping) if mapping.len() == 1 => {
+            let patterns = mapping
+                .get("strict_for")
+                .and_then(Value::as_sequence)
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|pattern| pattern.as_str().map(String::from))
+                .collect::<Option<Vec<String>>>()
+                .ok_or_else(invalid)?;
+            Ok(Some(CheckerSetting::StrictFor(patterns)))
+        }
+        _ => Err(invalid()),
     }
 }
 
@@ -585,6 +933,55 @@ foobar: true
         assert_eq!(expected, actual)
     }
 
+    #[test]
+    fn test_serde_with_strict_for() {
+        let pack_yml = r#"
+enforce_privacy:
+  strict_for:
+    - packs/orders/*
+    - packs/billing
+enforce_dependencies: true
+"#;
+
+        let actual = reserialize_pack(pack_yml);
+
+        let expected = r#"
+enforce_dependencies: true
+enforce_privacy:
+  strict_for:
+  - packs/billing
+  - packs/orders/*
+"#
+        .trim_start();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_is_strict_for() {
+        let setting = CheckerSetting::StrictFor(vec![
+            String::from("packs/billing"),
+            String::from("packs/orders/*"),
+        ]);
+
+        assert!(setting.is_strict_for("packs/billing"));
+        assert!(setting.is_strict_for("packs/orders/refunds"));
+        assert!(!setting.is_strict_for("packs/shipping"));
+        assert!(CheckerSetting::Strict.is_strict_for("packs/shipping"));
+        assert!(!CheckerSetting::True.is_strict_for("packs/billing"));
+    }
+
+    #[test]
+    fn test_deserialize_invalid_checker_setting() {
+        let error =
+            serde_yaml::from_str::<Pack>("enforce_privacy:\n  strict: true\n")
+                .unwrap_err();
+
+        assert!(error.to_string().contains(
+            "expected one of: false, true, strict, or a `strict_for:` list of packs"
+        ));
+    }
+
     #[test]
     fn test_serde_with_arbitrary_client_keys() {
         let pack_yml = r#"
@@ -610,6 +1007,50 @@ foobar: true
         assert_eq!(expected, actual)
     }
 
+    #[test]
+    fn test_serde_with_ignored_dependencies() {
+        let pack_yml = r#"
+ignored_dependencies:
+  - packs/logging
+  - pack: packs/framework
+    reason: Referenced by every pack
+"#;
+
+        let pack = serde_yaml::from_str::<Pack>(pack_yml).unwrap();
+        assert_eq!(
+            BTreeMap::from([
+                (
+                    String::from("packs/framework"),
+                    Some(String::from("Referenced by every pack"))
+                ),
+                (String::from("packs/logging"), None),
+            ]),
+            pack.ignored_dependencies
+        );
+
+        let expected = r#"
+ignored_dependencies:
+- pack: packs/framework
+  reason: Referenced by every pack
+- packs/logging
+"#
+        .trim_start();
+
+        assert_eq!(expected, serialize_pack(&pack))
+    }
+
+    #[test]
+    fn test_deserialize_invalid_ignored_dependencies() {
+        let error = serde_yaml::from_str::<Pack>(
+            "ignored_dependencies:\n  - reason: Referenced by every pack\n",
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("`pack:` and `reason:` pairs"));
+        assert!(error
+            .to_string()
+            .contains("did not match any variant of untagged enum"));
+    }
+
     #[test]
     fn test_serde_with_duplicate_dependencies() {
         let pack_yml = r#"
@@ -633,6 +1074,25 @@ dependencies:
         assert_eq!(expected, actual)
     }
 
+    #[test]
+    fn test_duplicate_dependencies() {
+        let pack_yml = r#"
+dependencies:
+  - packs/b
+  - packs/a
+  - packs/b
+  - packs/c
+  - packs/b
+  - packs/a
+"#;
+
+        assert_eq!(
+            vec![String::from("packs/a"), String::from("packs/b")],
+            duplicate_dependencies(pack_yml)
+        );
+        assert!(duplicate_dependencies("enforce_privacy: true").is_empty());
+    }
+
     #[test]
     fn test_serde_with_explicitly_empty_visible() {
         let pack_yml = r#"
@@ -717,6 +1177,72 @@ owner: Foobar
         assert_eq!(expected, actual)
     }
 
+    #[test]
+    fn test_serde_with_privacy_exceptions() {
+        let pack_yml = r#"
+privacy_exceptions:
+- packs/foo/*
+- packs/bar
+enforce_privacy: true
+"#;
+
+        let actual = reserialize_pack(pack_yml);
+
+        let expected = r#"
+enforce_privacy: true
+privacy_exceptions:
+- packs/bar
+- packs/foo/*
+"#
+        .trim_start();
+
+        assert_eq!(expected, actual)
+    }
+
+    #[test]
+    fn test_public_path_matcher() {
+        let pack = Pack {
+            name: String::from("packs/bar"),
+            relative_path: PathBuf::from("packs/bar"),
+            public_paths: Some(vec![
+                String::from("app/contracts/"),
+                String::from("app/models/bar.rb"),
+                String::from("app/services/*_service.rb"),
+                String::from("app/[invalid"),
+            ]),
+            ..Pack::default()
+        };
+        let matcher = pack.public_path_matcher();
+
+        assert!(matcher.matches("packs/bar/app/contracts/bar_contract.rb"));
+        assert!(matcher.matches("packs/bar/app/models/bar.rb"));
+        assert!(matcher.matches("packs/bar/app/services/bar_service.rb"));
+        assert!(!matcher.matches("packs/bar/app/contracts_legacy/bar.rb"));
+        assert!(!matcher.matches("packs/bar/app/models/bar.rb.bak"));
+        assert!(!matcher.matches("packs/bar/app/services/bar.rb"));
+        assert!(!matcher.matches("packs/bar/app/public/bar.rb"));
+    }
+
+    #[test]
+    fn test_is_waived() {
+        let pack: Pack = serde_yaml::from_str(
+            r#"
+waivers:
+- constant: Bar
+  referencing_pack: packs/foo
+  reason: Foo is moving off of Bar
+  expires_on: 2024-03-31
+"#,
+        )
+        .unwrap();
+
+        assert!(pack.is_waived("::Bar", "packs/foo", "2024-03-30"));
+        assert!(pack.is_waived("::Bar", "packs/foo", "2024-03-31"));
+        assert!(!pack.is_waived("::Bar", "packs/foo", "2024-04-01"));
+        assert!(!pack.is_waived("::Bar", "packs/baz", "2024-03-30"));
+        assert!(!pack.is_waived("::Bar::Baz", "packs/foo", "2024-03-30"));
+    }
+
     #[test]
     fn test_serde_with_enforcement_globs() {
         let pack_yml = r#"
@@ -796,8 +1322,11 @@ enforcement_globs_ignore:
     #[test]
     fn test_autoload_roots() {
         let root = test_util::get_absolute_root(test_util::SIMPLE_APP);
-        let pack =
-            Pack::from_path(root.join("package.yml").as_path(), root.as_path());
+        let pack = Pack::from_path(
+            root.join("package.yml").as_path(),
+            root.as_path(),
+            None,
+        );
         assert!(pack.is_ok());
 
         let actual = pack.unwrap().default_autoload_roots();
@@ -814,6 +1343,7 @@ enforcement_globs_ignore:
         let pack = Pack::from_path(
             root.join("packs/foo/package.yml").as_path(),
             root.as_path(),
+            None,
         )?;
 
         let mut actual = pack.all_violations();
@@ -823,18 +1353,20 @@ enforcement_globs_ignore:
             ViolationIdentifier {
                 violation_type: "dependency".to_string(),
                 strict: false,
-                file: "packs/foo/app/services/foo.rb".to_string(),
-                constant_name: "::Bar".to_string(),
-                referencing_pack_name: "packs/foo".to_string(),
-                defining_pack_name: "packs/bar".to_string(),
+                file: "packs/foo/app/services/foo.rb".into(),
+                constant_name: "::Bar".into(),
+                referencing_pack_name: "packs/foo".into(),
+                defining_pack_name: "packs/bar".into(),
+                defining_file: None,
             },
             ViolationIdentifier {
                 violation_type: "dependency".to_string(),
                 strict: false,
-                file: "packs/foo/app/services/other_foo.rb".to_string(),
-                constant_name: "::Bar".to_string(),
-                referencing_pack_name: "packs/foo".to_string(),
-                defining_pack_name: "packs/bar".to_string(),
+                file: "packs/foo/app/services/other_foo.rb".into(),
+                constant_name: "::Bar".into(),
+                referencing_pack_name: "packs/foo".into(),
+                defining_pack_name: "packs/bar".into(),
+                defining_file: None,
             },
         ];
 
@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use anyhow::bail;
+use serde::Serialize;
+
+use super::pack::{CheckerSetting, Pack};
+use super::Configuration;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct PackInfo {
+    pub name: String,
+    pub yml: PathBuf,
+    pub owner: Option<String>,
+    pub layer: Option<String>,
+    pub public_paths: Vec<PathBuf>,
+    // Keyed by the package.yml key, e.g. `enforce_privacy`, with defaults resolved
+    pub enforcements: BTreeMap<String, String>,
+    pub dependencies: Vec<String>,
+    pub todo_counts: TodoCounts,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TodoCounts {
+    // Violations recorded in this pack's package_todo.yml
+    pub outgoing: usize,
+    // Violations recorded against this pack in other packs' package_todo.yml
+    pub incoming: usize,
+}
+
+// Selects packs for `list-packs`. Every filter that is set must match
+#[derive(Debug, Default)]
+pub struct PackFilter {
+    // An enforcement that isn't disabled, e.g. `privacy` or `enforce_privacy`
+    pub enforcing: Option<String>,
+    pub layer: Option<String>,
+    pub owner: Option<String>,
+    // Whether the pack has violations recorded in its package_todo.yml
+    pub has_todos: bool,
+}
+
+impl PackFilter {
+    fn matches(&self, info: &PackInfo) -> anyhow::Result<bool> {
+        if let Some(enforcing) = &self.enforcing {
+            let key = if enforcing.starts_with("enforce_") {
+                enforcing.to_owned()
+            } else {
+                format!("enforce_{}", enforcing)
+            };
+            match info.enforcements.get(&key) {
+                Some(setting) if setting == "false" => return Ok(false),
+                Some(_) => {}
+                None => bail!(
+                    "Unknown enforcement `{}`, expected one of: {}",
+                    enforcing,
+                    info.enforcements
+                        .keys()
+                        .map(|key| key.trim_start_matches("enforce_"))
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                ),
+            }
+        }
+        Ok(self
+            .layer
+            .as_ref()
+            .map_or(true, |layer| info.layer.as_ref() == Some(layer))
+            && self
+                .owner
+                .as_ref()
+                .map_or(true, |owner| info.owner.as_ref() == Some(owner))
+            && (!self.has_todos || info.todo_counts.outgoing > 0))
+    }
+}
+
+pub fn find_pack_info(
+    configuration: &Configuration,
+    pack_name: &str,
+) -> anyhow::Result<PackInfo> {
+    let pack = configuration.pack_set.for_pack(pack_name)?;
+    let incoming = configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter(|p| p.name != pack.name)
+        .flat_map(|p| p.all_violations())
+        .filter(|v| v.defining_pack_name == pack.name)
+        .count();
+    Ok(pack_info(pack, incoming))
+}
+
+// The packs matching the filter, sorted by name
+pub fn list_pack_info(
+    configuration: &Configuration,
+    filter: &PackFilter,
+) -> anyhow::Result<Vec<PackInfo>> {
+    let mut incoming_by_pack: BTreeMap<String, usize> = BTreeMap::new();
+    for pack in &configuration.pack_set.packs {
+        for violation in pack.all_violations() {
+            if violation.defining_pack_name != pack.name {
+                *incoming_by_pack
+                    .entry(violation.defining_pack_name.into())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    let mut infos = Vec::new();
+    for pack in &configuration.pack_set.packs {
+        let incoming = incoming_by_pack.get(&pack.name).copied().unwrap_or(0);
+        let info = pack_info(pack, incoming);
+        if filter.matches(&info)? {
+            infos.push(info);
+        }
+    }
+    infos.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(infos)
+}
+
+fn pack_info(pack: &Pack, incoming: usize) -> PackInfo {
+    let mut dependencies: Vec<String> =
+        pack.dependencies.iter().cloned().collect();
+    dependencies.sort();
+
+    PackInfo {
+        name: pack.name.clone(),
+        yml: pack.relative_yml(),
+        owner: pack.owner.clone(),
+        layer: pack.layer().cloned(),
+        public_paths: pack.public_paths(),
+        enforcements: effective_enforcements(pack),
+        dependencies,
+        todo_counts: TodoCounts {
+            outgoing: pack.all_violations().len(),
+            incoming,
+        },
+    }
+}
+
+fn effective_enforcements(pack: &Pack) -> BTreeMap<String, String> {
+    let settings = [
+        ("enforce_dependencies", pack.enforce_dependencies.as_ref()),
+        ("enforce_privacy", pack.enforce_privacy.as_ref()),
+        ("enforce_visibility", pack.enforce_visibility.as_ref()),
+        ("enforce_layers", Some(pack.enforce_layers())),
+        (
+            "enforce_folder_privacy",
+            Some(pack.enforce_folder_privacy()),
+        ),
+    ];
+
+    settings
+        .into_iter()
+        .map(|(key, setting)| {
+            let value = match setting.unwrap_or(&CheckerSetting::False) {
+                CheckerSetting::False => String::from("false"),
+                CheckerSetting::True => String::from("true"),
+                CheckerSetting::Strict => String::from("strict"),
+                CheckerSetting::StrictFor(patterns) => {
+                    format!("strict for {}", patterns.join(", "))
+                }
+            };
+            (key.to_string(), value)
+        })
+        .collect()
+}
+
+impl fmt::Display for PackInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Pack: {}", self.name)?;
+        writeln!(f, "Config: {}", self.yml.display())?;
+        writeln!(f, "Owner: {}", self.owner.as_deref().unwrap_or("None"))?;
+        writeln!(f, "Layer: {}", self.layer.as_deref().unwrap_or("None"))?;
+        let public_paths: Vec<String> = self
+            .public_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        writeln!(f, "Public paths: {}", public_paths.join(", "))?;
+
+        writeln!(f, "\nEnforcements:")?;
+        for (key, value) in &self.enforcements {
+            writeln!(f, "- {}: {}", key, value)?;
+        }
+
+        writeln!(f, "\nDependencies ({}):", self.dependencies.len())?;
+        if self.dependencies.is_empty() {
+            writeln!(f, "- None")?;
+        } else {
+            for dependency in &self.dependencies {
+                writeln!(f, "- {}", dependency)?;
+            }
+        }
+
+        writeln!(f, "\nTodo violations:")?;
+        writeln!(f, "- outgoing: {}", self.todo_counts.outgoing)?;
+        write!(f, "- incoming: {}", self.todo_counts.incoming)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::packs::configuration;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn pack_info_with_resolved_defaults() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/simple_app")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+
+        let info = find_pack_info(&configuration, "packs/foo").unwrap();
+
+        assert_eq!(
+            info.public_paths,
+            vec![PathBuf::from("packs/foo/app/public")]
+        );
+        assert_eq!(info.dependencies, vec![String::from("packs/baz")]);
+        assert_eq!(info.enforcements["enforce_dependencies"], "true");
+        assert_eq!(info.enforcements["enforce_layers"], "false");
+        assert_eq!(info.todo_counts, TodoCounts::default());
+    }
+
+    #[test]
+    fn pack_info_todo_counts() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/contains_package_todo")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+
+        let foo = find_pack_info(&configuration, "packs/foo").unwrap();
+        assert_eq!(
+            foo.todo_counts,
+            TodoCounts {
+                outgoing: 2,
+                incoming: 0
+            }
+        );
+
+        let bar = find_pack_info(&configuration, "packs/bar").unwrap();
+        assert_eq!(
+            bar.todo_counts,
+            TodoCounts {
+                outgoing: 0,
+                incoming: 2
+            }
+        );
+    }
+
+    #[test]
+    fn list_pack_info_with_filters() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/contains_package_todo")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+        let names = |filter: PackFilter| -> Vec<String> {
+            list_pack_info(&configuration, &filter)
+                .unwrap()
+                .into_iter()
+                .map(|info| info.name)
+                .collect()
+        };
+
+        assert_eq!(
+            vec![String::from("packs/foo")],
+            names(PackFilter {
+                has_todos: true,
+                ..PackFilter::default()
+            })
+        );
+        assert_eq!(
+            vec![String::from("packs/foo")],
+            names(PackFilter {
+                enforcing: Some(String::from("enforce_dependencies")),
+                ..PackFilter::default()
+            })
+        );
+        assert!(names(PackFilter {
+            enforcing: Some(String::from("privacy")),
+            ..PackFilter::default()
+        })
+        .is_empty());
+        assert!(list_pack_info(
+            &configuration,
+            &PackFilter {
+                enforcing: Some(String::from("everything")),
+                ..PackFilter::default()
+            }
+        )
+        .is_err());
+    }
+}
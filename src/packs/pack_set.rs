@@ -6,13 +6,22 @@ use std::{
 
 use itertools::Itertools;
 
-use super::{checker::ViolationIdentifier, pack::Pack, Configuration};
+use super::{
+    checker::ViolationIdentifier,
+    pack::{Pack, PublicPathMatcher},
+    Configuration,
+};
 
 #[derive(Default, Debug)]
 pub struct PackSet {
     pub packs: Vec<Pack>,
     indexed_packs: HashMap<String, Pack>,
     owning_pack_name_for_file: HashMap<PathBuf, String>,
+    // Fully qualified namespace, e.g. `::Billing` -> the name of the pack
+    // declaring it in `namespaces`
+    pack_name_for_namespace: HashMap<String, String>,
+    // Pack name -> its public paths (see `PackSet::is_public_file`)
+    public_path_matchers: HashMap<String, PublicPathMatcher>,
     // For now, we keep track of all violations so that we can diff them and only
     // present the ones that are not recorded.
     // Eventually, we'll need to rewrite these to disk, in which case we'll need
@@ -45,7 +54,25 @@ impl PackSet {
         let mut indexed_packs_by_yml: HashMap<PathBuf, String> = HashMap::new();
 
         let mut all_violations = HashSet::new();
+        let mut pack_name_for_namespace: HashMap<String, String> =
+            HashMap::new();
         for pack in &packs {
+            for namespace in &pack.namespaces {
+                let namespace =
+                    format!("::{}", namespace.trim_start_matches("::"));
+                if let Some(other) = pack_name_for_namespace
+                    .insert(namespace.clone(), pack.name.clone())
+                {
+                    if other != pack.name {
+                        bail!(
+                            "`{}` is in the `namespaces` of both `{}` and `{}`",
+                            namespace,
+                            other,
+                            pack.name
+                        );
+                    }
+                }
+            }
             indexed_packs_by_name.insert(pack.name.clone(), pack.clone());
             indexed_packs_by_yml.insert(pack.yml.clone(), pack.name.clone());
             for violation_identifier in pack.all_violations() {
@@ -63,6 +90,10 @@ impl PackSet {
         }
 
         let indexed_packs = indexed_packs_by_name;
+        let public_path_matchers = packs
+            .iter()
+            .map(|pack| (pack.name.clone(), pack.public_path_matcher()))
+            .collect();
 
         if !indexed_packs.contains_key(".") {
             bail!("No root pack found. First double check a root pack exists (a package.yml file in the application root). Secondly, double check your packwerk.yml `package_paths` includes the root pack by using command packs list-packs.");
@@ -73,6 +104,8 @@ impl PackSet {
             packs,
             all_violations,
             owning_pack_name_for_file,
+            pack_name_for_namespace,
+            public_path_matchers,
         })
     }
 
@@ -92,6 +125,35 @@ impl PackSet {
             })
     }
 
+    // The pack declaring the innermost namespace of a fully qualified
+    // constant, e.g. `::Billing` of `::Billing::Invoice`, if any
+    pub fn for_namespace(&self, constant_name: &str) -> Option<&Pack> {
+        if self.pack_name_for_namespace.is_empty() {
+            return None;
+        }
+        let mut namespace = constant_name;
+        loop {
+            if let Some(pack_name) = self.pack_name_for_namespace.get(namespace)
+            {
+                return self.indexed_packs.get(pack_name);
+            }
+            namespace = &namespace[..namespace.rfind("::")?];
+        }
+    }
+
+    // Whether a file, relative to the project root, is in one of the public
+    // paths of the pack
+    pub(crate) fn is_public_file(
+        &self,
+        pack: &Pack,
+        relative_file: &str,
+    ) -> bool {
+        match self.public_path_matchers.get(&pack.name) {
+            Some(matcher) => matcher.matches(relative_file),
+            None => pack.public_path_matcher().matches(relative_file),
+        }
+    }
+
     pub fn for_pack(&self, pack_name: &str) -> Result<&Pack> {
         // Trim trailing slash on pack_name.
         // Since often the input arg here comes from the command line,
@@ -164,4 +226,65 @@ mod tests {
         let actual_pack = pack_set.for_pack("packs/foo/");
         assert!(actual_pack.is_ok());
     }
+
+    #[test]
+    fn for_namespace() {
+        let packs = HashSet::from([
+            Pack {
+                name: ".".to_string(),
+                ..Pack::default()
+            },
+            Pack {
+                name: "packs/billing".to_string(),
+                namespaces: vec!["Billing".to_string()],
+                ..Pack::default()
+            },
+            Pack {
+                name: "packs/invoicing".to_string(),
+                namespaces: vec!["::Billing::Invoices".to_string()],
+                ..Pack::default()
+            },
+        ]);
+        let pack_set = PackSet::build(packs, HashMap::new()).unwrap();
+        let pack_name = |constant_name: &str| {
+            pack_set
+                .for_namespace(constant_name)
+                .map(|pack| pack.name.as_str())
+        };
+
+        assert_eq!(pack_name("::Billing"), Some("packs/billing"));
+        assert_eq!(pack_name("::Billing::Card"), Some("packs/billing"));
+        assert_eq!(
+            pack_name("::Billing::Invoices::Line"),
+            Some("packs/invoicing")
+        );
+        assert_eq!(pack_name("::BillingReport"), None);
+        assert_eq!(pack_name("::Foo::Billing"), None);
+    }
+
+    #[test]
+    fn namespace_of_two_packs() {
+        let packs = HashSet::from([
+            Pack {
+                name: ".".to_string(),
+                ..Pack::default()
+            },
+            Pack {
+                name: "packs/a".to_string(),
+                namespaces: vec!["Billing".to_string()],
+                ..Pack::default()
+            },
+            Pack {
+                name: "packs/b".to_string(),
+                namespaces: vec!["Billing".to_string()],
+                ..Pack::default()
+            },
+        ]);
+        assert_eq!(
+            PackSet::build(packs, HashMap::new())
+                .unwrap_err()
+                .to_string(),
+            "`::Billing` is in the `namespaces` of both `packs/a` and `packs/b`"
+        );
+    }
 }
@@ -1,10 +1,32 @@
+use anyhow::{bail, Context};
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use serde_yaml::{Mapping, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tracing::debug;
 
+use super::checker::{waiver, ViolationIdentifier};
+use super::fingerprint::{fingerprint, SourceLines};
+use super::interner::InternedStr;
+use super::violation_store::{self, ViolationStore};
 use super::{pack::Pack, Configuration, Violation};
 
+// Stands for the pack name in `package_todo_path` in packwerk.yml
+const PACK_PLACEHOLDER: &str = "{pack}";
+
+// `v1` is packwerk's format. `v2` additionally records where each violation
+// occurs, so that `check` can detect recorded violations that have multiplied.
+#[derive(
+    PartialEq, Debug, Eq, Deserialize, Serialize, Default, Clone, Copy,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageTodoFormat {
+    #[default]
+    V1,
+    V2,
+}
+
 #[derive(PartialEq, Debug, Eq, Deserialize, Serialize, Default, Clone)]
 pub struct ViolationGroup {
     // Use serde rename to parse the key as violations
@@ -12,6 +34,43 @@ pub struct ViolationGroup {
     pub violation_types: HashSet<String>,
     #[serde(serialize_with = "serialize_sorted_set")]
     pub files: HashSet<String>,
+    // Keyed by file, only written in the v2 format
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub occurrences: BTreeMap<String, Occurrences>,
+    // Relative to the project root, only written in the v2 format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defining_file: Option<String>,
+    // File -> the date it was first recorded, e.g. `2024-03-31`, only written
+    // when a strict mode grace period is configured
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub recorded_on: BTreeMap<String, String>,
+}
+
+#[derive(PartialEq, Debug, Eq, Deserialize, Serialize, Default, Clone)]
+pub struct Occurrences {
+    // The number of references to the constant in the file
+    pub count: usize,
+    pub lines: Vec<usize>,
+    // One per reference, independent of line numbers. Files written before
+    // fingerprints were recorded are compared by count.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fingerprints: Vec<String>,
+}
+
+impl Occurrences {
+    fn from_locations(
+        locations: &BTreeSet<(usize, usize)>,
+        mut fingerprints: Vec<String>,
+    ) -> Self {
+        let lines: BTreeSet<usize> =
+            locations.iter().map(|(line, _column)| *line).collect();
+        fingerprints.sort();
+        Occurrences {
+            count: locations.len(),
+            lines: lines.into_iter().collect(),
+            fingerprints,
+        }
+    }
 }
 
 fn serialize_sorted_set<S>(
@@ -76,10 +135,204 @@ where
     map_serializer.end()
 }
 
+impl PackageTodo {
+    // The violations recorded in the package_todo.yml file of the pack
+    pub fn violation_identifiers(
+        &self,
+        referencing_pack_name: &str,
+    ) -> Vec<ViolationIdentifier> {
+        let mut violations = Vec::new();
+        for (defining_pack_name, violation_groups) in
+            &self.violations_by_defining_pack
+        {
+            for (constant_name, violation_group) in violation_groups {
+                for violation_type in &violation_group.violation_types {
+                    for file in &violation_group.files {
+                        let identifier = ViolationIdentifier {
+                            violation_type: violation_type.clone(),
+                            strict: false,
+                            file: file.into(),
+                            constant_name: constant_name.into(),
+                            referencing_pack_name: referencing_pack_name.into(),
+                            defining_pack_name: defining_pack_name.into(),
+                            defining_file: violation_group
+                                .defining_file
+                                .as_ref()
+                                .map(InternedStr::from),
+                        };
+
+                        violations.push(identifier);
+                    }
+                }
+            }
+        }
+        violations
+    }
+
+    // Removes the files for which `keep` is false, along with any constants and
+    // defining packs left without files. Returns the number of removed entries.
+    pub fn retain_files(&mut self, keep: impl Fn(&str) -> bool) -> usize {
+        let mut removed = 0;
+        for violations_by_constant in
+            self.violations_by_defining_pack.values_mut()
+        {
+            for violation_group in violations_by_constant.values_mut() {
+                let files_count = violation_group.files.len();
+                violation_group.files.retain(|file| keep(file));
+                violation_group.occurrences.retain(|file, _| keep(file));
+                violation_group.recorded_on.retain(|file, _| keep(file));
+                removed += files_count - violation_group.files.len();
+            }
+            violations_by_constant
+                .retain(|_, violation_group| !violation_group.files.is_empty());
+        }
+        self.violations_by_defining_pack
+            .retain(|_, violations_by_constant| {
+                !violations_by_constant.is_empty()
+            });
+        removed
+    }
+
+    // Removes the violations on defining packs for which `keep` is false.
+    // Returns the number of removed constants.
+    pub fn retain_defining_packs(
+        &mut self,
+        keep: impl Fn(&str) -> bool,
+    ) -> usize {
+        let mut removed = 0;
+        self.violations_by_defining_pack.retain(
+            |defining_pack_name, violations_by_constant| {
+                let retained = keep(defining_pack_name);
+                if !retained {
+                    removed += violations_by_constant.len();
+                }
+                retained
+            },
+        );
+        removed
+    }
+
+    // Adds the violations recorded in `other`
+    pub fn merge(&mut self, other: PackageTodo) {
+        for (defining_pack_name, violations_by_constant) in
+            other.violations_by_defining_pack
+        {
+            self.add_violations(&defining_pack_name, violations_by_constant);
+        }
+    }
+
+    // Records the violations on `from` as violations on `to`
+    pub fn rename_defining_pack(&mut self, from: &str, to: &str) {
+        if let Some(violations_by_constant) =
+            self.violations_by_defining_pack.remove(from)
+        {
+            self.add_violations(to, violations_by_constant);
+        }
+    }
+
+    // Replaces each file with `rename(file)`
+    pub fn rename_files(&mut self, rename: impl Fn(&str) -> String) {
+        for violations_by_constant in
+            self.violations_by_defining_pack.values_mut()
+        {
+            for violation_group in violations_by_constant.values_mut() {
+                violation_group.files = violation_group
+                    .files
+                    .iter()
+                    .map(|file| rename(file))
+                    .collect();
+                violation_group.occurrences =
+                    std::mem::take(&mut violation_group.occurrences)
+                        .into_iter()
+                        .map(|(file, occurrences)| (rename(&file), occurrences))
+                        .collect();
+                violation_group.recorded_on =
+                    std::mem::take(&mut violation_group.recorded_on)
+                        .into_iter()
+                        .map(|(file, date)| (rename(&file), date))
+                        .collect();
+            }
+        }
+    }
+
+    // Replaces each defining file with `rename(file)`
+    pub fn rename_defining_files(&mut self, rename: impl Fn(&str) -> String) {
+        for violations_by_constant in
+            self.violations_by_defining_pack.values_mut()
+        {
+            for violation_group in violations_by_constant.values_mut() {
+                if let Some(defining_file) = &violation_group.defining_file {
+                    violation_group.defining_file = Some(rename(defining_file));
+                }
+            }
+        }
+    }
+
+    // Dates each recorded file with when it was first recorded: the date in
+    // `previous` if it was recorded there already, or else `today`
+    pub fn stamp_recorded_on(&mut self, previous: &PackageTodo, today: &str) {
+        for (defining_pack_name, violations_by_constant) in
+            &mut self.violations_by_defining_pack
+        {
+            for (constant_name, violation_group) in violations_by_constant {
+                let previous_group = previous
+                    .violations_by_defining_pack
+                    .get(defining_pack_name)
+                    .and_then(|groups| groups.get(constant_name));
+                for file in &violation_group.files {
+                    let date = previous_group
+                        .and_then(|group| group.recorded_on.get(file))
+                        .map_or(today, String::as_str);
+                    violation_group
+                        .recorded_on
+                        .insert(file.clone(), date.to_owned());
+                }
+            }
+        }
+    }
+
+    fn add_violations(
+        &mut self,
+        defining_pack_name: &str,
+        violations_by_constant: BTreeMap<String, ViolationGroup>,
+    ) {
+        let existing = self
+            .violations_by_defining_pack
+            .entry(defining_pack_name.to_owned())
+            .or_default();
+        for (constant_name, violation_group) in violations_by_constant {
+            let existing_group = existing.entry(constant_name).or_default();
+            existing_group
+                .violation_types
+                .extend(violation_group.violation_types);
+            existing_group.files.extend(violation_group.files);
+            existing_group
+                .occurrences
+                .extend(violation_group.occurrences);
+            // The earliest date is kept
+            for (file, date) in violation_group.recorded_on {
+                let existing_date = existing_group
+                    .recorded_on
+                    .entry(file)
+                    .or_insert_with(|| date.clone());
+                if date < *existing_date {
+                    *existing_date = date;
+                }
+            }
+            if existing_group.defining_file.is_none() {
+                existing_group.defining_file = violation_group.defining_file;
+            }
+        }
+    }
+}
+
 pub fn package_todos_for_pack_name(
     violations_by_responsible_pack_name: HashMap<String, Vec<Violation>>,
+    format: PackageTodoFormat,
+    absolute_root: &Path,
 ) -> HashMap<String, PackageTodo> {
     let mut ret = HashMap::new();
+    let mut source_lines = SourceLines::new(absolute_root);
 
     // Then we group violations by the defining pack, since that's how they're grouped in the package_todo.yml file
     for (responsible_pack_name, mut violations) in
@@ -89,6 +342,13 @@ pub fn package_todos_for_pack_name(
             String,
             BTreeMap<String, ViolationGroup>,
         > = BTreeMap::new();
+        // Distinct (line, column) locations, keyed by (defining pack, constant, file).
+        // A single reference can produce violations of several types, so
+        // occurrences are counted by location rather than by violation.
+        let mut locations: BTreeMap<
+            (String, String, String),
+            BTreeSet<(usize, usize)>,
+        > = BTreeMap::new();
         // Sort violations by the defining pack name, then constant name, then file name
         // This ensures they show up deterministically in the package_todo.yml file.
         violations.sort_by(|a, b| {
@@ -103,22 +363,68 @@ pub fn package_todos_for_pack_name(
 
         for violation in violations {
             let defining_pack_name =
-                violation.identifier.defining_pack_name.to_owned();
+                violation.identifier.defining_pack_name.to_string();
             let existing_violations_by_constant_group =
                 violations_by_defining_pack
                     .entry(defining_pack_name)
                     .or_default();
 
             let violation_group = existing_violations_by_constant_group
-                .entry(violation.identifier.constant_name.to_owned())
+                .entry(violation.identifier.constant_name.to_string())
                 .or_default();
 
             violation_group
                 .files
-                .insert(violation.identifier.file.to_owned());
+                .insert(violation.identifier.file.to_string());
             violation_group
                 .violation_types
                 .insert(violation.identifier.violation_type.to_owned());
+
+            if format == PackageTodoFormat::V2 {
+                if violation_group.defining_file.is_none() {
+                    violation_group.defining_file = violation
+                        .identifier
+                        .defining_file
+                        .as_deref()
+                        .map(str::to_owned);
+                }
+                locations
+                    .entry((
+                        violation.identifier.defining_pack_name.to_string(),
+                        violation.identifier.constant_name.to_string(),
+                        violation.identifier.file.to_string(),
+                    ))
+                    .or_default()
+                    .insert((
+                        violation.source_location.line,
+                        violation.source_location.column,
+                    ));
+            }
+        }
+
+        for ((defining_pack_name, constant_name, file), file_locations) in
+            locations
+        {
+            let fingerprints = file_locations
+                .iter()
+                .map(|(line, _column)| {
+                    fingerprint(
+                        &constant_name,
+                        &responsible_pack_name,
+                        &defining_pack_name,
+                        source_lines.line(&file, *line),
+                    )
+                })
+                .collect();
+            if let Some(violation_group) = violations_by_defining_pack
+                .get_mut(&defining_pack_name)
+                .and_then(|groups| groups.get_mut(&constant_name))
+            {
+                violation_group.occurrences.insert(
+                    file,
+                    Occurrences::from_locations(&file_locations, fingerprints),
+                );
+            }
         }
 
         let package_todo = PackageTodo {
@@ -130,12 +436,65 @@ pub fn package_todos_for_pack_name(
 
     ret
 }
-pub fn write_violations_to_disk(
-    configuration: &Configuration,
+// Where the package_todo.yml file of the pack is: at `package_todo_path` in
+// its package.yml, relative to the pack directory, or else at
+// `package_todo_path` in packwerk.yml, relative to the project root with
+// `{pack}` standing for the pack name (`root` for the root pack), or else in
+// the pack directory
+pub(crate) fn package_todo_yml(
+    pack: &Pack,
+    absolute_root: &Path,
+    package_todo_path: Option<&str>,
+) -> anyhow::Result<PathBuf> {
+    let pack_directory = pack
+        .yml
+        .parent()
+        .context("Expected package.yml to be in a directory")?;
+    if let Some(path) = &pack.package_todo_path {
+        return Ok(pack_directory.join(path));
+    }
+    match package_todo_path {
+        Some(template) if !template.contains(PACK_PLACEHOLDER) => bail!(
+            "`package_todo_path` in packwerk.yml must contain `{}`, so that each pack has its own package_todo.yml, but is `{}`",
+            PACK_PLACEHOLDER,
+            template
+        ),
+        Some(template) => {
+            let pack_name = if pack.name == "." {
+                "root"
+            } else {
+                pack.name.as_str()
+            };
+            Ok(absolute_root.join(template.replace(PACK_PLACEHOLDER, pack_name)))
+        }
+        None => Ok(pack_directory.join("package_todo.yml")),
+    }
+}
+
+// Reads the package_todo.yml file at the path, which records nothing when it
+// doesn't exist
+pub(crate) fn read_package_todo(
+    absolute_path_to_package_todo: &Path,
+) -> anyhow::Result<PackageTodo> {
+    if !absolute_path_to_package_todo.exists() {
+        return Ok(PackageTodo::default());
+    }
+    let package_todo_contents =
+        std::fs::read_to_string(absolute_path_to_package_todo)
+            .context("Could not read the package_todo.yml file")?;
+    serde_yaml::from_str(&package_todo_contents).with_context(|| {
+        format!(
+            "Failed to deserialize the package_todo.yml file at {}. Try deleting the file and running the `update` command to regenerate it.",
+            absolute_path_to_package_todo.display()
+        )
+    })
+}
+
+// Strict mode violations can't be recorded, and the others are recorded by
+// the referencing pack
+fn violations_by_responsible_pack(
     violations: HashSet<Violation>,
-) {
-    debug!("Starting writing violations to disk");
-    // First we need to group the violations by the repsonsible pack, which today is always the referencing pack
+) -> HashMap<String, Vec<Violation>> {
     // Later if we change where a violation shows up, we should delegate to the checker
     // to decide what pack it should be in.
     let mut violations_by_responsible_pack: HashMap<String, Vec<Violation>> =
@@ -145,30 +504,197 @@ pub fn write_violations_to_disk(
             continue;
         }
         let referencing_pack_name =
-            violation.identifier.referencing_pack_name.to_owned();
+            violation.identifier.referencing_pack_name.to_string();
         violations_by_responsible_pack
             .entry(referencing_pack_name)
             .or_default()
             .push(violation);
     }
+    violations_by_responsible_pack
+}
 
-    let package_todos_by_pack_name =
-        package_todos_for_pack_name(violations_by_responsible_pack);
+// The package_todo.yml files that would record the violations, keyed by the
+// name of their pack, without writing them
+pub(crate) fn render_package_todos(
+    configuration: &Configuration,
+    violations: HashSet<Violation>,
+) -> HashMap<String, String> {
+    package_todos_for_pack_name(
+        violations_by_responsible_pack(violations),
+        configuration.package_todo_format,
+        &configuration.absolute_root,
+    )
+    .into_iter()
+    .map(|(pack_name, package_todo)| {
+        let rendered = serialize_package_todo(
+            &pack_name,
+            &package_todo,
+            configuration.packs_first_mode,
+        );
+        (pack_name, rendered)
+    })
+    .collect()
+}
 
-    let all_packs = &configuration.pack_set.packs;
-    all_packs.par_iter().for_each(|p| {
-        let package_todo = package_todos_by_pack_name.get(&p.name);
-        match package_todo {
-            Some(package_todo) => write_package_todo_to_disk(
-                p,
-                package_todo,
-                configuration.packs_first_mode,
-            ),
-            None => delete_package_todo_from_disk(p),
-        }
-    });
+// When `only_pack_names` is given, the violations of other packs are left
+// untouched. With `minimal_diff`, the order of the entries already in each
+// package_todo.yml file is kept, and files whose entries are unchanged aren't
+// rewritten.
+pub fn write_violations_to_disk(
+    configuration: &Configuration,
+    violations: HashSet<Violation>,
+    only_pack_names: Option<&HashSet<String>>,
+    minimal_diff: bool,
+) -> anyhow::Result<()> {
+    debug!("Starting writing violations to disk");
+    // First we need to group the violations by the repsonsible pack, which today is always the referencing pack
+    let mut package_todos_by_pack_name = package_todos_for_pack_name(
+        violations_by_responsible_pack(violations),
+        configuration.package_todo_format,
+        &configuration.absolute_root,
+    );
+
+    // Recorded violations are dated only when they can be in a grace period
+    let today = configuration
+        .has_strict_mode_grace_period()
+        .then(waiver::today);
+    let package_todos: Vec<(&Pack, PackageTodo)> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter(|p| match only_pack_names {
+            Some(names) => names.contains(&p.name),
+            None => true,
+        })
+        .map(|p| {
+            let mut package_todo = package_todos_by_pack_name
+                .remove(&p.name)
+                .unwrap_or_default();
+            if let Some(today) = &today {
+                package_todo.stamp_recorded_on(&p.package_todo, today);
+            }
+            (p, package_todo)
+        })
+        .collect();
+    violation_store::for_configuration(configuration).save(
+        configuration,
+        &package_todos,
+        minimal_diff,
+    )?;
 
     debug!("Finished writing violations to disk");
+    Ok(())
+}
+
+// Removes recorded violations in files that no longer exist, without parsing
+// anything. Returns the number of removed entries.
+pub fn prune_deleted_files(
+    configuration: &Configuration,
+) -> anyhow::Result<usize> {
+    rewrite_package_todos(configuration, |package_todo| {
+        package_todo.retain_files(|file| {
+            configuration.absolute_root.join(file).exists()
+        })
+    })
+}
+
+// Removes recorded violations on packs that no longer exist. Returns the
+// number of removed constants.
+pub fn prune_unknown_packs(
+    configuration: &Configuration,
+) -> anyhow::Result<usize> {
+    rewrite_package_todos(configuration, |package_todo| {
+        package_todo.retain_defining_packs(|defining_pack_name| {
+            configuration.pack_set.for_pack(defining_pack_name).is_ok()
+        })
+    })
+}
+
+// Applies `prune` to the recorded violations of each pack, and saves those
+// of the packs it removed anything from
+fn rewrite_package_todos(
+    configuration: &Configuration,
+    prune: impl Fn(&mut PackageTodo) -> usize + Sync,
+) -> anyhow::Result<usize> {
+    let pruned: Vec<(usize, &Pack, PackageTodo)> = configuration
+        .pack_set
+        .packs
+        .par_iter()
+        .map(|p| {
+            let mut package_todo = p.package_todo.clone();
+            let removed = prune(&mut package_todo);
+            (removed, p, package_todo)
+        })
+        .filter(|(removed, _, _)| *removed > 0)
+        .collect();
+    let removed: usize = pruned.iter().map(|(removed, _, _)| removed).sum();
+    let package_todos: Vec<(&Pack, PackageTodo)> = pruned
+        .into_iter()
+        .map(|(_, p, package_todo)| (p, package_todo))
+        .collect();
+    violation_store::for_configuration(configuration).save(
+        configuration,
+        &package_todos,
+        false,
+    )?;
+    Ok(removed)
+}
+
+// Records `package_todo` as the violations of `responsible_pack`, or forgets
+// them when nothing is recorded
+pub(crate) fn write_or_delete_package_todo(
+    configuration: &Configuration,
+    responsible_pack: &Pack,
+    package_todo: &PackageTodo,
+) -> anyhow::Result<()> {
+    violation_store::for_configuration(configuration).save(
+        configuration,
+        &[(responsible_pack, package_todo.clone())],
+        false,
+    )
+}
+
+// Records violations in the package_todo.yml file of each pack
+pub(crate) struct YmlStore;
+
+impl ViolationStore for YmlStore {
+    fn load(
+        &self,
+        packs: &HashSet<Pack>,
+    ) -> anyhow::Result<HashMap<String, PackageTodo>> {
+        packs
+            .iter()
+            .map(|p| {
+                Ok((p.name.clone(), read_package_todo(&p.package_todo_yml)?))
+            })
+            .collect()
+    }
+
+    fn save(
+        &self,
+        configuration: &Configuration,
+        package_todos: &[(&Pack, PackageTodo)],
+        minimal_diff: bool,
+    ) -> anyhow::Result<()> {
+        package_todos.par_iter().for_each(|(p, package_todo)| {
+            if package_todo.violations_by_defining_pack.is_empty() {
+                delete_package_todo_from_disk(p)
+            } else if !minimal_diff {
+                write_package_todo_to_disk(
+                    p,
+                    package_todo,
+                    configuration.packs_first_mode,
+                )
+            } else if package_todo != &p.package_todo {
+                write_package_todo_with_minimal_diff(
+                    p,
+                    package_todo,
+                    configuration.packs_first_mode,
+                )
+            }
+        });
+        Ok(())
+    }
 }
 
 fn serialize_package_todo(
@@ -190,14 +716,14 @@ fn write_package_todo_to_disk(
     package_todo: &PackageTodo,
     packs_first_mode: bool,
 ) {
-    let package_todo_yml_absolute_filepath = responsible_pack
-        .yml
-        .parent()
-        .unwrap()
-        .join("package_todo.yml");
+    let package_todo_yml_absolute_filepath = &responsible_pack.package_todo_yml;
 
     if !package_todo_yml_absolute_filepath.exists() {
-        std::fs::File::create(&package_todo_yml_absolute_filepath).unwrap();
+        // package_todo.yml files may be kept outside of the pack directories
+        if let Some(parent) = package_todo_yml_absolute_filepath.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::File::create(package_todo_yml_absolute_filepath).unwrap();
     }
 
     let package_todo_yml = serialize_package_todo(
@@ -210,12 +736,192 @@ fn write_package_todo_to_disk(
         .unwrap();
 }
 
+fn write_package_todo_with_minimal_diff(
+    responsible_pack: &Pack,
+    package_todo: &PackageTodo,
+    packs_first_mode: bool,
+) {
+    let package_todo_yml_absolute_filepath = &responsible_pack.package_todo_yml;
+
+    let Ok(previous_yml) =
+        std::fs::read_to_string(package_todo_yml_absolute_filepath)
+    else {
+        return write_package_todo_to_disk(
+            responsible_pack,
+            package_todo,
+            packs_first_mode,
+        );
+    };
+
+    let package_todo_yml = serialize_package_todo_like(
+        &previous_yml,
+        &responsible_pack.name,
+        package_todo,
+        packs_first_mode,
+    );
+
+    std::fs::write(package_todo_yml_absolute_filepath, package_todo_yml)
+        .unwrap();
+}
+
+// Serializes `package_todo` with its entries in the order of `previous_yml`,
+// the current contents of the file. Entries that weren't there before are
+// inserted where they sort among the existing ones.
+fn serialize_package_todo_like(
+    previous_yml: &str,
+    responsible_pack_name: &String,
+    package_todo: &PackageTodo,
+    packs_first_mode: bool,
+) -> String {
+    let previous: Value =
+        serde_yaml::from_str(previous_yml).unwrap_or_default();
+
+    let mut mapping = Mapping::new();
+    for defining_pack_name in ordered_like(
+        &mapping_keys(Some(&previous)),
+        package_todo.violations_by_defining_pack.keys(),
+    ) {
+        let violations_by_constant =
+            &package_todo.violations_by_defining_pack[&defining_pack_name];
+        let previous_constants = previous.get(defining_pack_name.as_str());
+
+        let mut constants_mapping = Mapping::new();
+        for constant_name in ordered_like(
+            &mapping_keys(previous_constants),
+            violations_by_constant.keys(),
+        ) {
+            let violation_group = &violations_by_constant[&constant_name];
+            let previous_group = previous_constants
+                .and_then(|constants| constants.get(constant_name.as_str()));
+
+            let mut group_mapping = Mapping::new();
+            group_mapping.insert(
+                Value::from("violations"),
+                Value::from(ordered_like(
+                    &sequence_strings(
+                        previous_group
+                            .and_then(|group| group.get("violations")),
+                    ),
+                    &violation_group.violation_types,
+                )),
+            );
+            let files = ordered_like(
+                &sequence_strings(
+                    previous_group.and_then(|group| group.get("files")),
+                ),
+                &violation_group.files,
+            );
+            let mut occurrences = Mapping::new();
+            for file in &files {
+                if let Some(file_occurrences) =
+                    violation_group.occurrences.get(file)
+                {
+                    occurrences.insert(
+                        Value::from(file.as_str()),
+                        serde_yaml::to_value(file_occurrences).unwrap(),
+                    );
+                }
+            }
+            group_mapping.insert(Value::from("files"), Value::from(files));
+            if !occurrences.is_empty() {
+                group_mapping.insert(
+                    Value::from("occurrences"),
+                    Value::Mapping(occurrences),
+                );
+            }
+            if !violation_group.recorded_on.is_empty() {
+                group_mapping.insert(
+                    Value::from("recorded_on"),
+                    serde_yaml::to_value(&violation_group.recorded_on).unwrap(),
+                );
+            }
+
+            // HACK: See `serialize_violations_by_defining_pack`
+            constants_mapping.insert(
+                Value::from(format!("#{}#", constant_name)),
+                Value::Mapping(group_mapping),
+            );
+        }
+
+        let key = if defining_pack_name == "." {
+            String::from("#.#")
+        } else {
+            defining_pack_name
+        };
+        mapping.insert(Value::from(key), Value::Mapping(constants_mapping));
+    }
+
+    let package_todo_yml =
+        serde_yaml::to_string(&Value::Mapping(mapping)).unwrap();
+    let package_todo_yml = package_todo_yml.replace("'#", "\"");
+    let package_todo_yml = package_todo_yml.replace("#'", "\"");
+
+    // The comments at the top of the file are kept too
+    let previous_header: String = previous_yml
+        .lines()
+        .take_while(|line| line.starts_with('#') || *line == "---")
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let header = if previous_header.is_empty() {
+        header(responsible_pack_name, packs_first_mode)
+    } else {
+        previous_header
+    };
+    header + &package_todo_yml
+}
+
+// The entries of `current`, in the order of `previous`. Entries that aren't
+// in `previous` are inserted before the first entry that sorts after them.
+fn ordered_like<'a>(
+    previous: &[String],
+    current: impl IntoIterator<Item = &'a String>,
+) -> Vec<String> {
+    let current: BTreeSet<&String> = current.into_iter().collect();
+    let previous_entries: HashSet<&String> = previous.iter().collect();
+    let mut ordered: Vec<String> = previous
+        .iter()
+        .filter(|entry| current.contains(entry))
+        .cloned()
+        .collect();
+    for entry in current {
+        if previous_entries.contains(entry) {
+            continue;
+        }
+        let position = ordered
+            .iter()
+            .position(|existing| existing > entry)
+            .unwrap_or(ordered.len());
+        ordered.insert(position, entry.clone());
+    }
+    ordered
+}
+
+fn mapping_keys(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_mapping)
+        .map(|mapping| {
+            mapping
+                .keys()
+                .filter_map(|key| key.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn sequence_strings(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_sequence)
+        .map(|sequence| {
+            sequence
+                .iter()
+                .filter_map(|entry| entry.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn delete_package_todo_from_disk(responsible_pack: &Pack) {
-    let package_todo_yml_absolute_filepath = responsible_pack
-        .yml
-        .parent()
-        .unwrap()
-        .join("package_todo.yml");
+    let package_todo_yml_absolute_filepath = &responsible_pack.package_todo_yml;
 
     if package_todo_yml_absolute_filepath.exists() {
         // Delete package_todo_yml_absolute_filepath
@@ -268,6 +974,9 @@ mod tests {
             ViolationGroup {
                 violation_types,
                 files,
+                occurrences: BTreeMap::new(),
+                defining_file: None,
+                recorded_on: BTreeMap::new(),
             },
         );
 
@@ -318,6 +1027,240 @@ mod tests {
         }
     }
 
+    fn pack_at(absolute_root: &Path, name: &str) -> Pack {
+        Pack {
+            yml: absolute_root.join(name).join("package.yml"),
+            name: name.to_string(),
+            relative_path: PathBuf::from(name),
+            ..Pack::default()
+        }
+    }
+
+    #[test]
+    fn test_package_todo_yml() -> anyhow::Result<()> {
+        let absolute_root = Path::new("/app");
+        let template = Some(".pks/todos/{pack}.yml");
+
+        assert_eq!(
+            package_todo_yml(
+                &pack_at(absolute_root, "packs/foo"),
+                absolute_root,
+                None
+            )?,
+            PathBuf::from("/app/packs/foo/package_todo.yml")
+        );
+        assert_eq!(
+            package_todo_yml(
+                &pack_at(absolute_root, "packs/foo"),
+                absolute_root,
+                template
+            )?,
+            PathBuf::from("/app/.pks/todos/packs/foo.yml")
+        );
+        let root_pack = Pack {
+            yml: absolute_root.join("package.yml"),
+            name: String::from("."),
+            relative_path: PathBuf::from("."),
+            ..Pack::default()
+        };
+        assert_eq!(
+            package_todo_yml(&root_pack, absolute_root, template)?,
+            PathBuf::from("/app/.pks/todos/root.yml")
+        );
+        let overridden = Pack {
+            package_todo_path: Some(String::from("todo/package_todo.yml")),
+            ..pack_at(absolute_root, "packs/foo")
+        };
+        assert_eq!(
+            package_todo_yml(&overridden, absolute_root, template)?,
+            PathBuf::from("/app/packs/foo/todo/package_todo.yml")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_todo_yml_without_pack_placeholder() {
+        let absolute_root = Path::new("/app");
+        let error = package_todo_yml(
+            &pack_at(absolute_root, "packs/foo"),
+            absolute_root,
+            Some(".pks/todos.yml"),
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().starts_with(
+            "`package_todo_path` in packwerk.yml must contain `{pack}`"
+        ));
+    }
+
+    #[test]
+    fn test_retain_files() {
+        let mut package_todo = example_package_todo(String::from("packs/bar"));
+        package_todo
+            .violations_by_defining_pack
+            .get_mut("packs/bar")
+            .unwrap()
+            .get_mut("::Baz")
+            .unwrap()
+            .files
+            .insert(String::from("packs/foo/app/services/deleted.rb"));
+        package_todo.violations_by_defining_pack.insert(
+            String::from("packs/baz"),
+            construct_violations(
+                String::from("::Baz"),
+                vec![String::from("privacy")],
+                vec![String::from("packs/foo/app/services/deleted.rb")],
+            ),
+        );
+
+        let removed = package_todo
+            .retain_files(|file| file != "packs/foo/app/services/deleted.rb");
+
+        assert_eq!(2, removed);
+        assert_eq!(
+            example_package_todo(String::from("packs/bar")),
+            package_todo
+        );
+    }
+
+    #[test]
+    fn test_retain_defining_packs() {
+        let mut package_todo = example_package_todo(String::from("packs/bar"));
+        package_todo
+            .violations_by_defining_pack
+            .insert(String::from("packs/deleted"), bar_blah_violations());
+
+        let removed =
+            package_todo.retain_defining_packs(|defining_pack_name| {
+                defining_pack_name != "packs/deleted"
+            });
+
+        assert_eq!(1, removed);
+        assert_eq!(
+            example_package_todo(String::from("packs/bar")),
+            package_todo
+        );
+    }
+
+    #[test]
+    fn test_rename_defining_pack_and_files() {
+        let mut package_todo = example_package_todo(String::from("packs/old"));
+        package_todo.merge(PackageTodo {
+            violations_by_defining_pack: BTreeMap::from([(
+                String::from("packs/bar"),
+                construct_violations(
+                    String::from("::Bar"),
+                    vec![String::from("privacy")],
+                    vec![String::from("packs/old/app/services/old.rb")],
+                ),
+            )]),
+        });
+
+        package_todo.rename_defining_pack("packs/old", "packs/bar");
+        package_todo
+            .rename_files(|file| file.replace("packs/old/", "packs/foo/"));
+
+        let mut expected = example_package_todo(String::from("packs/bar"));
+        let bar = expected
+            .violations_by_defining_pack
+            .get_mut("packs/bar")
+            .unwrap()
+            .get_mut("::Bar")
+            .unwrap();
+        bar.violation_types.insert(String::from("privacy"));
+        bar.files
+            .insert(String::from("packs/foo/app/services/old.rb"));
+        assert_eq!(expected, package_todo);
+    }
+
+    #[test]
+    fn test_serialize_package_todo_like_previous_file() {
+        let previous_yml = r#"# A custom header
+---
+packs/baz:
+  "::Baz":
+    violations:
+    - privacy
+    - dependency
+    files:
+    - packs/foo/b.rb
+    - packs/foo/a.rb
+packs/bar:
+  "::Bar":
+    violations:
+    - dependency
+    files:
+    - packs/foo/a.rb
+"#;
+        let mut violations_by_defining_pack = BTreeMap::new();
+        violations_by_defining_pack.insert(
+            String::from("packs/baz"),
+            construct_violations(
+                String::from("::Baz"),
+                vec![String::from("dependency"), String::from("privacy")],
+                vec![String::from("packs/foo/a.rb")],
+            ),
+        );
+        let mut bar_violations = construct_violations(
+            String::from("::Bar"),
+            vec![String::from("dependency")],
+            vec![String::from("packs/foo/a.rb")],
+        );
+        bar_violations.extend(construct_violations(
+            String::from("::Bam"),
+            vec![String::from("dependency")],
+            vec![String::from("packs/foo/a.rb")],
+        ));
+        violations_by_defining_pack
+            .insert(String::from("packs/bar"), bar_violations);
+        violations_by_defining_pack.insert(
+            String::from("packs/qux"),
+            construct_violations(
+                String::from("::Qux"),
+                vec![String::from("dependency")],
+                vec![String::from("packs/foo/a.rb")],
+            ),
+        );
+
+        let actual = serialize_package_todo_like(
+            previous_yml,
+            &String::from("packs/foo"),
+            &PackageTodo {
+                violations_by_defining_pack,
+            },
+            false,
+        );
+
+        let expected = r#"# A custom header
+---
+packs/baz:
+  "::Baz":
+    violations:
+    - privacy
+    - dependency
+    files:
+    - packs/foo/a.rb
+packs/bar:
+  "::Bam":
+    violations:
+    - dependency
+    files:
+    - packs/foo/a.rb
+  "::Bar":
+    violations:
+    - dependency
+    files:
+    - packs/foo/a.rb
+packs/qux:
+  "::Qux":
+    violations:
+    - dependency
+    files:
+    - packs/foo/a.rb
+"#;
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn test_deserialize_trivial_case() {
         let contents: String = String::from(
@@ -482,4 +1425,104 @@ packs/bar:
 
         assert_eq!(expected, actual);
     }
+
+    fn v2_package_todo() -> PackageTodo {
+        let mut violations = construct_violations(
+            String::from("::Bar"),
+            vec![String::from("dependency"), String::from("privacy")],
+            vec![String::from("packs/foo/app/services/foo.rb")],
+        );
+        violations.get_mut("::Bar").unwrap().occurrences.insert(
+            String::from("packs/foo/app/services/foo.rb"),
+            Occurrences {
+                count: 2,
+                lines: vec![3, 7],
+                fingerprints: vec![
+                    String::from("3a1f0c9e2b7d"),
+                    String::from("c45e8d01f6a2"),
+                ],
+            },
+        );
+        violations.get_mut("::Bar").unwrap().defining_file =
+            Some(String::from("packs/bar/app/models/bar.rb"));
+
+        PackageTodo {
+            violations_by_defining_pack: BTreeMap::from([(
+                String::from("packs/bar"),
+                violations,
+            )]),
+        }
+    }
+
+    const V2_PACKAGE_TODO_BODY: &str = "\
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    - privacy
+    files:
+    - packs/foo/app/services/foo.rb
+    occurrences:
+      packs/foo/app/services/foo.rb:
+        count: 2
+        lines:
+        - 3
+        - 7
+        fingerprints:
+        - 3a1f0c9e2b7d
+        - c45e8d01f6a2
+    defining_file: packs/bar/app/models/bar.rb
+";
+
+    #[test]
+    fn test_serialize_v2() {
+        let actual = serialize_package_todo(
+            &String::from("packs/foo"),
+            &v2_package_todo(),
+            true,
+        );
+
+        assert!(actual.ends_with(&format!("---\n{}", V2_PACKAGE_TODO_BODY)));
+    }
+
+    #[test]
+    fn test_deserialize_v2() {
+        let actual: PackageTodo =
+            serde_yaml::from_str(V2_PACKAGE_TODO_BODY).unwrap();
+
+        assert_eq!(v2_package_todo(), actual);
+    }
+
+    #[test]
+    fn test_occurrences_from_locations() {
+        let locations: BTreeSet<(usize, usize)> =
+            vec![(3, 4), (3, 12), (7, 2)].into_iter().collect();
+
+        assert_eq!(
+            Occurrences {
+                count: 3,
+                lines: vec![3, 7],
+                fingerprints: vec![String::from("a"), String::from("b")],
+            },
+            Occurrences::from_locations(
+                &locations,
+                vec![String::from("b"), String::from("a")]
+            )
+        );
+    }
+
+    #[test]
+    fn test_deserialize_v2_without_fingerprints() {
+        let body = V2_PACKAGE_TODO_BODY
+            .split("        fingerprints:")
+            .next()
+            .unwrap();
+        let actual: PackageTodo = serde_yaml::from_str(body).unwrap();
+
+        let occurrences = &actual.violations_by_defining_pack["packs/bar"]
+            ["::Bar"]
+            .occurrences["packs/foo/app/services/foo.rb"];
+        assert_eq!(2, occurrences.count);
+        assert!(occurrences.fingerprints.is_empty());
+    }
 }
@@ -0,0 +1,353 @@
+// Compares the violations packwerk reports with the ones we report on the same files,
+// so that migrations from packwerk can be verified.
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use regex::Regex;
+
+use super::checker;
+use super::Configuration;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct ParityViolation {
+    pub file: String,
+    pub violation_type: String,
+    pub constant_name: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ParityReport {
+    pub files_checked: usize,
+    pub matching: usize,
+    pub only_in_packwerk: Vec<ParityViolation>,
+    pub only_in_pks: Vec<ParityViolation>,
+}
+
+impl ParityReport {
+    pub fn build(
+        files_checked: usize,
+        packwerk_violations: BTreeSet<ParityViolation>,
+        pks_violations: BTreeSet<ParityViolation>,
+    ) -> Self {
+        ParityReport {
+            files_checked,
+            matching: packwerk_violations.intersection(&pks_violations).count(),
+            only_in_packwerk: packwerk_violations
+                .difference(&pks_violations)
+                .cloned()
+                .collect(),
+            only_in_pks: pks_violations
+                .difference(&packwerk_violations)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn has_differences(&self) -> bool {
+        !self.only_in_packwerk.is_empty() || !self.only_in_pks.is_empty()
+    }
+}
+
+fn write_parity_violations(
+    f: &mut fmt::Formatter<'_>,
+    heading: &str,
+    violations: &[ParityViolation],
+) -> fmt::Result {
+    writeln!(f, "\n{} ({}):", heading, violations.len())?;
+    if violations.is_empty() {
+        writeln!(f, "- None")?;
+    }
+    for v in violations {
+        writeln!(
+            f,
+            "- {}: {} violation on `{}`",
+            v.file, v.violation_type, v.constant_name
+        )?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for ParityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked {} file(s), {} violation(s) reported by both packwerk and pks",
+            self.files_checked, self.matching
+        )?;
+        write_parity_violations(
+            f,
+            "Only reported by packwerk",
+            &self.only_in_packwerk,
+        )?;
+        write_parity_violations(f, "Only reported by pks", &self.only_in_pks)
+    }
+}
+
+pub(crate) fn parity(
+    configuration: &mut Configuration,
+    files: Vec<String>,
+    sample_size: usize,
+) -> anyhow::Result<ParityReport> {
+    ensure_packwerk_in_gemfile(&configuration.absolute_root)?;
+
+    let relative_files = sample_files(configuration, files, sample_size)?;
+    if relative_files.is_empty() {
+        bail!("No files to compare");
+    }
+    // Only the sampled files are checked, like the files passed to `check`
+    configuration.input_files_count = relative_files.len();
+
+    let packwerk_output =
+        run_packwerk_check(&configuration.absolute_root, &relative_files)?;
+    let packwerk_violations = parse_packwerk_output(&packwerk_output);
+
+    let result = checker::check_all(configuration, relative_files.clone())
+        .context("Failed to check files")?;
+    let pks_violations = result
+        .reportable_violation_identifiers()
+        .map(|identifier| ParityViolation {
+            file: identifier.file.to_string(),
+            violation_type: identifier.violation_type.clone(),
+            constant_name: identifier.constant_name.to_string(),
+        })
+        .collect();
+
+    Ok(ParityReport::build(
+        relative_files.len(),
+        packwerk_violations,
+        pks_violations,
+    ))
+}
+
+fn ensure_packwerk_in_gemfile(absolute_root: &Path) -> anyhow::Result<()> {
+    let packwerk_gem =
+        Regex::new(r#"(?m)^\s*(gem\s+['"]packwerk['"]|packwerk \()"#).unwrap();
+    let in_gemfile = ["Gemfile", "Gemfile.lock"].iter().any(|file| {
+        std::fs::read_to_string(absolute_root.join(file))
+            .map(|contents| packwerk_gem.is_match(&contents))
+            .unwrap_or(false)
+    });
+
+    if !in_gemfile {
+        bail!(
+            "packwerk was not found in the Gemfile at {}",
+            absolute_root.display()
+        );
+    }
+    Ok(())
+}
+
+// Samples evenly across the sorted list of included files so that the
+// sample covers the codebase, and so that repeated runs are comparable.
+fn sample_files(
+    configuration: &Configuration,
+    files: Vec<String>,
+    sample_size: usize,
+) -> anyhow::Result<Vec<String>> {
+    let mut absolute_paths: Vec<PathBuf> =
+        configuration.intersect_files(files).into_iter().collect();
+    absolute_paths.sort();
+
+    let step = (absolute_paths.len() / sample_size.max(1)).max(1);
+    absolute_paths
+        .iter()
+        .step_by(step)
+        .take(sample_size)
+        .map(|path| {
+            path.strip_prefix(&configuration.absolute_root)
+                .context("Failed to strip project root from file")
+                .map(|relative| relative.to_string_lossy().to_string())
+        })
+        .collect()
+}
+
+fn run_packwerk_check(
+    absolute_root: &Path,
+    relative_files: &[String],
+) -> anyhow::Result<String> {
+    let binstub = absolute_root.join("bin/packwerk");
+    let mut command = if binstub.exists() {
+        Command::new(binstub)
+    } else {
+        let mut command = Command::new("bundle");
+        command.arg("exec").arg("packwerk");
+        command
+    };
+
+    let output = command
+        .arg("check")
+        .args(relative_files)
+        .current_dir(absolute_root)
+        .output()
+        .context("Failed to run packwerk")?;
+
+    packwerk_stdout(output.status.code(), &output.stdout, &output.stderr)
+}
+
+// packwerk exits with 1 when it finds violations, so that isn't an error. Any
+// other exit code, or output without packwerk's summary line, means packwerk
+// didn't run (e.g. missing gems or a broken binstub), and comparing with its
+// empty output would report every violation as only found by pks.
+fn packwerk_stdout(
+    exit_code: Option<i32>,
+    stdout: &[u8],
+    stderr: &[u8],
+) -> anyhow::Result<String> {
+    let summary =
+        Regex::new(r"(?m)^\s*(No offenses|\d+ offenses?) detected").unwrap();
+    let stdout = String::from_utf8_lossy(stdout).to_string();
+    let finished = matches!(exit_code, Some(0) | Some(1));
+    if !finished || !summary.is_match(&stdout) {
+        bail!(
+            "packwerk check failed ({}): {}",
+            exit_code.map_or_else(
+                || String::from("killed by a signal"),
+                |code| format!("exit code {}", code)
+            ),
+            String::from_utf8_lossy(stderr).trim()
+        );
+    }
+    Ok(stdout)
+}
+
+// Packwerk reports each violation as a location line followed by a message, e.g.
+// packs/foo/app/services/foo.rb:3:4
+// Privacy violation: '::Bar' is private to 'packs/bar' but referenced from 'packs/foo'.
+fn parse_packwerk_output(output: &str) -> BTreeSet<ParityViolation> {
+    let location = Regex::new(r"^(\S+):\d+:\d+$").unwrap();
+    let message =
+        Regex::new(r"^(\w[\w ]*?) violation: '?(::[\w:]+)'?").unwrap();
+
+    let mut violations = BTreeSet::new();
+    let mut current_file: Option<&str> = None;
+    for line in output.lines().map(|line| line.trim()) {
+        if let Some(captures) = location.captures(line) {
+            current_file = captures.get(1).map(|m| m.as_str());
+        } else if let (Some(file), Some(captures)) =
+            (current_file, message.captures(line))
+        {
+            violations.insert(ParityViolation {
+                file: file.to_string(),
+                violation_type: captures[1].to_lowercase().replace(' ', "_"),
+                constant_name: captures[2].to_string(),
+            });
+            current_file = None;
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parity_violation(
+        file: &str,
+        violation_type: &str,
+        constant_name: &str,
+    ) -> ParityViolation {
+        ParityViolation {
+            file: file.to_string(),
+            violation_type: violation_type.to_string(),
+            constant_name: constant_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_packwerk_output() {
+        let output = "\
+📦 Packwerk is inspecting 2 files
+..E
+📦 Finished in 0.5 seconds
+
+packs/foo/app/services/foo.rb:3:4
+Dependency violation: ::Bar belongs to 'packs/bar', but 'packs/foo/package.yml' does not specify a dependency on 'packs/bar'.
+Are we missing an abstraction?
+
+packs/foo/app/services/foo.rb:3:4
+Privacy violation: '::Bar' is private to 'packs/bar' but referenced from 'packs/foo'.
+Is there a public entrypoint in 'packs/bar/app/public/' that you can use instead?
+
+2 offenses detected
+";
+        let expected: BTreeSet<ParityViolation> = vec![
+            parity_violation(
+                "packs/foo/app/services/foo.rb",
+                "dependency",
+                "::Bar",
+            ),
+            parity_violation(
+                "packs/foo/app/services/foo.rb",
+                "privacy",
+                "::Bar",
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(expected, parse_packwerk_output(output));
+    }
+
+    #[test]
+    fn test_packwerk_stdout() {
+        let output =
+            "📦 Packwerk is inspecting 2 files\n..\nNo offenses detected\n";
+        assert_eq!(
+            output,
+            packwerk_stdout(Some(0), output.as_bytes(), b"").unwrap()
+        );
+        let output = "packs/foo/app/services/foo.rb:3:4\n1 offense detected\n";
+        assert_eq!(
+            output,
+            packwerk_stdout(Some(1), output.as_bytes(), b"").unwrap()
+        );
+
+        let error = packwerk_stdout(
+            Some(1),
+            b"",
+            b"Could not find gem 'packwerk' in locally installed gems.\n",
+        )
+        .unwrap_err();
+        assert_eq!(
+            "packwerk check failed (exit code 1): Could not find gem 'packwerk' in locally installed gems.",
+            error.to_string()
+        );
+        assert!(
+            packwerk_stdout(Some(7), b"No offenses detected\n", b"").is_err()
+        );
+        assert!(packwerk_stdout(None, b"", b"").is_err());
+    }
+
+    #[test]
+    fn test_parity_report() {
+        let shared = parity_violation("a.rb", "privacy", "::Foo");
+        let packwerk_only = parity_violation("b.rb", "dependency", "::Bar");
+        let pks_only = parity_violation("c.rb", "layer", "::Baz");
+
+        let report = ParityReport::build(
+            3,
+            vec![shared.clone(), packwerk_only.clone()]
+                .into_iter()
+                .collect(),
+            vec![shared, pks_only.clone()].into_iter().collect(),
+        );
+
+        assert!(report.has_differences());
+        assert_eq!(
+            format!("{}", report),
+            "\
+Checked 3 file(s), 1 violation(s) reported by both packwerk and pks
+
+Only reported by packwerk (1):
+- b.rb: dependency violation on `::Bar`
+
+Only reported by pks (1):
+- c.rb: layer violation on `::Baz`
+"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::packs::file_utils::file_read_contents;
 use crate::packs::{
-    file_utils::convert_erb_to_ruby_without_sourcemaps, parsing::Range,
+    file_utils::convert_erb_to_ruby_without_sourcemaps,
+    parsing::{Range, SyntaxError},
     Configuration, ProcessedFile, UnresolvedReference,
 };
 use std::path::Path;
@@ -16,11 +17,12 @@ pub(crate) fn process_from_path(
 }
 
 pub(crate) fn process_from_contents(
-    contents: String,
+    contents: impl AsRef<str>,
     path: &Path,
     configuration: &Configuration,
 ) -> ProcessedFile {
-    let ruby_contents = convert_erb_to_ruby_without_sourcemaps(contents);
+    let ruby_contents =
+        convert_erb_to_ruby_without_sourcemaps(contents.as_ref());
     let processed_file =
         process_from_ruby_contents(ruby_contents, path, configuration);
     let references = processed_file.unresolved_references;
@@ -35,11 +37,22 @@ pub(crate) fn process_from_contents(
             ..r.clone()
         })
         .collect();
+    let parse_errors = processed_file
+        .parse_errors
+        .into_iter()
+        .map(|error| SyntaxError {
+            // As with references, these locations refer to the generated Ruby
+            line: 0,
+            column: 0,
+            ..error
+        })
+        .collect();
 
     ProcessedFile {
         absolute_path: path.to_path_buf(),
         unresolved_references: references_without_range,
         definitions: vec![],
         sigils: vec![],
+        parse_errors,
     }
 }
@@ -0,0 +1,250 @@
+// Which extractor finds the references and definitions of a file, by its
+// extension. Ruby and ERB are built in. `extraction_handlers:` in packwerk.yml
+// maps more extensions to them, or to a command printing the Ruby code of a
+// file, e.g. to support a template language:
+//
+//   extraction_handlers:
+//     jbuilder: ruby
+//     slim:
+//       command: bin/slim_to_ruby
+//
+// The command gets the path of the file, relative to the project root, as its
+// argument and the contents of the file on stdin.
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::packs::file_utils::file_read_contents;
+use crate::packs::{Configuration, ProcessedFile};
+
+use super::{
+    process_from_erb_path, process_from_erb_path_experimental,
+    process_from_ruby_path, process_from_ruby_path_experimental,
+    ruby::experimental::parser::process_from_contents as process_from_ruby_contents_experimental,
+    ruby::packwerk::parser::process_from_contents as process_from_ruby_contents,
+};
+
+const RUBY_EXTENSIONS: [&str; 5] = ["rb", "rake", "builder", "gemspec", "ru"];
+const RUBY_FILE_NAMES: [&str; 2] = ["Gemfile", "Rakefile"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtractionHandler {
+    Ruby,
+    Erb,
+    // A command printing the Ruby code of the file, which is then parsed as
+    // Ruby. References are reported at their lines in that code, so commands
+    // should keep each piece of Ruby on the line it came from.
+    Command(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BuiltinHandler {
+    Ruby,
+    Erb,
+}
+
+// An entry of `extraction_handlers:` in packwerk.yml
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum RawExtractionHandler {
+    Builtin(BuiltinHandler),
+    Command { command: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractionHandlers {
+    // Extension, without the leading `.` -> handler
+    by_extension: HashMap<String, ExtractionHandler>,
+}
+
+impl Default for ExtractionHandlers {
+    fn default() -> Self {
+        let mut by_extension: HashMap<String, ExtractionHandler> =
+            RUBY_EXTENSIONS
+                .iter()
+                .map(|extension| {
+                    (extension.to_string(), ExtractionHandler::Ruby)
+                })
+                .collect();
+        by_extension.insert(String::from("erb"), ExtractionHandler::Erb);
+        Self { by_extension }
+    }
+}
+
+impl ExtractionHandlers {
+    // The built-in handlers, with the configured ones added or overriding them
+    pub(crate) fn from_raw(
+        raw_handlers: &BTreeMap<String, RawExtractionHandler>,
+    ) -> anyhow::Result<Self> {
+        let mut handlers = Self::default();
+        for (extension, raw_handler) in raw_handlers {
+            let extension = extension.trim_start_matches('.');
+            if extension.is_empty() {
+                bail!("`extraction_handlers` has an empty extension");
+            }
+            let handler = match raw_handler {
+                RawExtractionHandler::Builtin(BuiltinHandler::Ruby) => {
+                    ExtractionHandler::Ruby
+                }
+                RawExtractionHandler::Builtin(BuiltinHandler::Erb) => {
+                    ExtractionHandler::Erb
+                }
+                RawExtractionHandler::Command { command } => {
+                    ExtractionHandler::Command(command.clone())
+                }
+            };
+            handlers.by_extension.insert(extension.to_owned(), handler);
+        }
+        Ok(handlers)
+    }
+
+    pub fn for_path(&self, path: &Path) -> Option<&ExtractionHandler> {
+        if RUBY_FILE_NAMES.iter().any(|name| path.ends_with(name)) {
+            return Some(&ExtractionHandler::Ruby);
+        }
+        let extension = path.extension()?.to_str()?;
+        self.by_extension.get(extension)
+    }
+}
+
+pub(crate) fn process_from_path(
+    handler: &ExtractionHandler,
+    path: &Path,
+    configuration: &Configuration,
+) -> anyhow::Result<ProcessedFile> {
+    match (handler, configuration.experimental_parser) {
+        (ExtractionHandler::Ruby, false) => {
+            process_from_ruby_path(path, configuration)
+        }
+        (ExtractionHandler::Ruby, true) => {
+            process_from_ruby_path_experimental(path, configuration)
+        }
+        (ExtractionHandler::Erb, false) => {
+            process_from_erb_path(path, configuration)
+        }
+        (ExtractionHandler::Erb, true) => {
+            process_from_erb_path_experimental(path, configuration)
+        }
+        (ExtractionHandler::Command(command), experimental_parser) => {
+            let ruby_contents = run_command(command, path, configuration)?;
+            Ok(if experimental_parser {
+                process_from_ruby_contents_experimental(
+                    ruby_contents,
+                    path,
+                    configuration,
+                )
+            } else {
+                process_from_ruby_contents(ruby_contents, path, configuration)
+            })
+        }
+    }
+}
+
+// Runs through the shell from the project root, so that the command can be
+// a binstub or take arguments of its own
+fn run_command(
+    command: &str,
+    path: &Path,
+    configuration: &Configuration,
+) -> anyhow::Result<String> {
+    let contents = file_read_contents(path, configuration)?;
+    let relative_path = path
+        .strip_prefix(&configuration.absolute_root)
+        .unwrap_or(path);
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", command))
+        .arg("sh")
+        .arg(relative_path)
+        .current_dir(&configuration.absolute_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context(format!("Failed to run `{}`", command))?;
+    // Written from another thread, so that a command printing before it has
+    // read all of its input can't deadlock
+    let mut stdin = child
+        .stdin
+        .take()
+        .context(format!("Failed to open the stdin of `{}`", command))?;
+    let writer =
+        std::thread::spawn(move || stdin.write_all(contents.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .context(format!("Failed to run `{}`", command))?;
+    // A command may exit without reading its input, which isn't an error
+    let _ = writer.join();
+    if !output.status.success() {
+        bail!(
+            "`{}` failed on {} with {}: {}",
+            command,
+            relative_path.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context(format!(
+        "`{}` printed invalid UTF-8 for {}",
+        command,
+        relative_path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_default_handlers() {
+        let handlers = ExtractionHandlers::default();
+        assert_eq!(
+            Some(&ExtractionHandler::Ruby),
+            handlers.for_path(Path::new("foo.rake"))
+        );
+        assert_eq!(
+            Some(&ExtractionHandler::Ruby),
+            handlers.for_path(Path::new("Gemfile"))
+        );
+        assert_eq!(
+            Some(&ExtractionHandler::Erb),
+            handlers.for_path(Path::new("foo.html.erb"))
+        );
+        assert_eq!(None, handlers.for_path(Path::new("foo.slim")));
+    }
+
+    #[test]
+    fn test_configured_handlers() {
+        let raw_handlers: BTreeMap<String, RawExtractionHandler> =
+            serde_yaml::from_str(
+                "jbuilder: ruby\n.slim:\n  command: bin/slim_to_ruby\nrake: erb",
+            )
+            .unwrap();
+        let handlers = ExtractionHandlers::from_raw(&raw_handlers).unwrap();
+
+        assert_eq!(
+            Some(&ExtractionHandler::Ruby),
+            handlers.for_path(Path::new("show.json.jbuilder"))
+        );
+        assert_eq!(
+            Some(&ExtractionHandler::Command(String::from(
+                "bin/slim_to_ruby"
+            ))),
+            handlers.for_path(Path::new("show.html.slim"))
+        );
+        assert_eq!(
+            Some(&ExtractionHandler::Erb),
+            handlers.for_path(Path::new("foo.rake"))
+        );
+        assert_eq!(
+            Some(&ExtractionHandler::Ruby),
+            handlers.for_path(Path::new("foo.rb"))
+        );
+    }
+}
@@ -1,22 +1,28 @@
 use std::{
     collections::HashSet,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
 };
 
 pub(crate) mod ruby;
 pub(crate) use ruby::experimental::parser::process_from_path as process_from_ruby_path_experimental;
 pub(crate) use ruby::packwerk::parser::process_from_path as process_from_ruby_path;
 mod erb;
+mod extraction_handlers;
 pub(crate) use erb::experimental::parser::process_from_path as process_from_erb_path_experimental;
 pub(crate) use erb::packwerk::parser::process_from_path as process_from_erb_path;
+pub(crate) use extraction_handlers::RawExtractionHandler;
+pub use extraction_handlers::{ExtractionHandler, ExtractionHandlers};
 
 use crate::packs::file_utils::is_stdin_file;
+use crate::packs::progress::Progress;
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
+use tracing::{debug, field, info_span};
 
 use super::{
     caching::{cache::Cache, CacheResult},
-    file_utils::{get_file_type, SupportedFileType},
     Configuration, ProcessedFile,
 };
 
@@ -27,25 +33,10 @@ pub fn process_file(
     if configuration.print_files {
         println!("Started processing {}", path.display());
     }
-    let file_type_option = get_file_type(path);
-
-    let result = if let Some(file_type) = file_type_option {
-        match file_type {
-            SupportedFileType::Ruby => {
-                if configuration.experimental_parser {
-                    process_from_ruby_path_experimental(path, configuration)
-                } else {
-                    process_from_ruby_path(path, configuration)
-                }
-            }
-            SupportedFileType::Erb => {
-                if configuration.experimental_parser {
-                    process_from_erb_path_experimental(path, configuration)
-                } else {
-                    process_from_erb_path(path, configuration)
-                }
-            }
-        }
+    let handler_option = configuration.extraction_handlers.for_path(path);
+
+    let result = if let Some(handler) = handler_option {
+        extraction_handlers::process_from_path(handler, path, configuration)
     } else {
         // Later, we can perhaps have this error, since in theory the Configuration.intersect
         // method should make sure we never get any files we can't handle.
@@ -54,6 +45,7 @@ pub fn process_file(
             unresolved_references: vec![],
             definitions: vec![], // TODO
             sigils: vec![],
+            parse_errors: vec![],
         })
     };
 
@@ -71,6 +63,15 @@ pub struct UnresolvedReference {
     pub location: Range,
 }
 
+// A syntax error the parser recovered from, or that kept it from parsing the
+// file at all
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct SyntaxError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Default)]
 pub struct Range {
     pub start_row: usize,
@@ -85,50 +86,159 @@ pub struct ParsedDefinition {
     pub location: Range,
 }
 
+// A rough estimate of the memory needed to parse a byte of source, mostly for
+// the AST
+const PARSING_BYTES_PER_SOURCE_BYTE: u64 = 20;
+
+// Splits the paths into batches whose estimated parsing memory fits within
+// `max_memory`, so that only one batch is parsed at a time. A file too large
+// to fit is parsed in a batch of its own.
+fn memory_bounded_batches(
+    paths: &HashSet<PathBuf>,
+    max_memory: u64,
+    file_size: impl Fn(&Path) -> u64,
+) -> Vec<Vec<&PathBuf>> {
+    let mut sorted_paths: Vec<&PathBuf> = paths.iter().collect();
+    sorted_paths.sort();
+
+    let mut batches: Vec<Vec<&PathBuf>> = Vec::new();
+    let mut batch: Vec<&PathBuf> = Vec::new();
+    let mut batch_memory = 0;
+    for path in sorted_paths {
+        let memory = file_size(path) * PARSING_BYTES_PER_SOURCE_BYTE;
+        if !batch.is_empty() && batch_memory + memory > max_memory {
+            batches.push(std::mem::take(&mut batch));
+            batch_memory = 0;
+        }
+        batch.push(path);
+        batch_memory += memory;
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
 pub fn process_files_with_cache(
     paths: &HashSet<PathBuf>,
     cache: Box<dyn Cache + Send + Sync>,
     configuration: &Configuration,
+) -> anyhow::Result<Vec<ProcessedFile>> {
+    let batches = match configuration.max_memory {
+        Some(max_memory) => memory_bounded_batches(paths, max_memory, |path| {
+            std::fs::metadata(path).map(|m| m.len()).unwrap_or_default()
+        }),
+        None => vec![paths.iter().collect()],
+    };
+    debug!("Parsing files in {} batch(es)", batches.len());
+    let span = info_span!(
+        "parse",
+        files = paths.len(),
+        batches = batches.len(),
+        cache_hits = field::Empty,
+        cache_misses = field::Empty,
+        cache_hit_rate = field::Empty
+    )
+    .entered();
+
+    let progress = Progress::new(configuration, "parsing", paths.len());
+    let cache_hits = AtomicUsize::new(0);
+    let mut processed_files = Vec::with_capacity(paths.len());
+    for batch in batches {
+        processed_files.extend(process_batch(
+            &batch,
+            cache.as_ref(),
+            configuration,
+            &progress,
+            &cache_hits,
+        )?);
+    }
+    progress.finish();
+
+    let cache_hits = cache_hits.into_inner();
+    span.record("cache_hits", cache_hits);
+    span.record("cache_misses", processed_files.len() - cache_hits);
+    if !processed_files.is_empty() {
+        span.record(
+            "cache_hit_rate",
+            cache_hits as f64 / processed_files.len() as f64,
+        );
+    }
+    Ok(processed_files)
+}
+
+fn process_batch(
+    paths: &[&PathBuf],
+    cache: &(dyn Cache + Send + Sync),
+    configuration: &Configuration,
+    progress: &Progress,
+    cache_hits: &AtomicUsize,
 ) -> anyhow::Result<Vec<ProcessedFile>> {
     paths
         .par_iter()
-        .map(|absolute_path| -> anyhow::Result<ProcessedFile> {
-            if is_stdin_file(absolute_path, configuration) {
-                process_file(absolute_path, configuration)
-            } else {
-                match cache.get(absolute_path)? {
-                    CacheResult::Processed(processed_file) => {
-                        Ok(processed_file)
-                    }
-                    CacheResult::Miss(empty_cache_entry) => {
-                        let processed_file =
-                            process_file(absolute_path, configuration)?;
-                        cache.write(&empty_cache_entry, &processed_file)?;
-                        Ok(processed_file)
-                    }
-                }
+        .filter_map(|absolute_path| {
+            // Files are skipped once the deadline has passed, so that callers
+            // can report partial results
+            if configuration
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                progress.tick();
+                return None;
             }
+            let processed_file = process_file_with_cache(
+                absolute_path,
+                cache,
+                configuration,
+                cache_hits,
+            );
+            progress.tick();
+            Some(processed_file)
         })
         .collect()
 }
 
+fn process_file_with_cache(
+    absolute_path: &Path,
+    cache: &(dyn Cache + Send + Sync),
+    configuration: &Configuration,
+    cache_hits: &AtomicUsize,
+) -> anyhow::Result<ProcessedFile> {
+    if is_stdin_file(absolute_path, configuration) {
+        return process_file(absolute_path, configuration);
+    }
+    match cache.get(absolute_path)? {
+        CacheResult::Processed(processed_file) => {
+            cache_hits.fetch_add(1, Ordering::Relaxed);
+            Ok(processed_file)
+        }
+        CacheResult::Miss(empty_cache_entry) => {
+            let processed_file = process_file(absolute_path, configuration)?;
+            cache.write(&empty_cache_entry, &processed_file)?;
+            Ok(processed_file)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::packs::file_utils::get_file_type;
-
     use super::*;
 
     fn assert_is_ruby(filename: &str) {
         assert_eq!(
-            SupportedFileType::Ruby,
-            get_file_type(Path::new(filename)).expect("Should be supported")
+            &ExtractionHandler::Ruby,
+            ExtractionHandlers::default()
+                .for_path(Path::new(filename))
+                .expect("Should be supported")
         )
     }
 
     fn assert_is_erb(filename: &str) {
         assert_eq!(
-            SupportedFileType::Erb,
-            get_file_type(Path::new(filename)).expect("Should be supported")
+            &ExtractionHandler::Erb,
+            ExtractionHandlers::default()
+                .for_path(Path::new(filename))
+                .expect("Should be supported")
         )
     }
 
@@ -144,4 +254,30 @@ mod tests {
     fn identifies_erb_files() {
         assert_is_erb("foo.erb");
     }
+
+    #[test]
+    fn test_memory_bounded_batches() {
+        let paths: HashSet<PathBuf> = ["a.rb", "b.rb", "c.rb", "d.rb"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let file_size = |path: &Path| match path.to_str().unwrap() {
+            "c.rb" => 100,
+            _ => 1,
+        };
+
+        let batches: Vec<Vec<&str>> = memory_bounded_batches(
+            &paths,
+            2 * PARSING_BYTES_PER_SOURCE_BYTE,
+            file_size,
+        )
+        .into_iter()
+        .map(|batch| batch.iter().map(|p| p.to_str().unwrap()).collect())
+        .collect();
+
+        assert_eq!(
+            vec![vec!["a.rb", "b.rb"], vec!["c.rb"], vec!["d.rb"]],
+            batches
+        );
+    }
 }
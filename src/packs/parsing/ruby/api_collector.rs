@@ -0,0 +1,435 @@
+// Collects the classes, modules, and constants defined in a Ruby file, along
+// with their public methods. This is a light parse: visibility is tracked
+// through `private`/`protected`/`public`, `private_class_method`,
+// `private_constant`, and `module_function`, but not through
+// metaprogramming such as `define_method` or `send(:private, ...)`.
+use std::collections::{BTreeMap, BTreeSet};
+
+use lib_ruby_parser::{
+    nodes, traverse::visitor::Visitor, Node, Parser, ParserOptions,
+};
+use serde::Serialize;
+
+use super::parse_utils::fetch_const_name;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ConstantKind {
+    Class,
+    Module,
+    Constant,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Clone)]
+pub struct ApiConstant {
+    pub name: String,
+    pub kind: ConstantKind,
+    // Instance methods are prefixed with `#`, singleton methods with `.`
+    pub methods: Vec<String>,
+}
+
+#[derive(Default)]
+struct ApiCollector {
+    namespaces: Vec<String>,
+    // Whether methods defined next in the current body are public
+    private: bool,
+    module_function: bool,
+    in_singleton_class: bool,
+    constants: BTreeMap<String, (ConstantKind, BTreeSet<String>)>,
+    private_constants: Vec<String>,
+}
+
+impl ApiCollector {
+    fn qualify(&self, name: &str) -> String {
+        if name.starts_with("::") {
+            return name.to_owned();
+        }
+        match self.namespaces.last() {
+            Some(namespace) => format!("{}::{}", namespace, name),
+            None => format!("::{}", name),
+        }
+    }
+
+    fn current_methods(&mut self) -> Option<&mut BTreeSet<String>> {
+        let namespace = self.namespaces.last()?;
+        self.constants
+            .get_mut(namespace)
+            .map(|(_kind, methods)| methods)
+    }
+
+    fn add_method(&mut self, method: String) {
+        if let Some(methods) = self.current_methods() {
+            methods.insert(method);
+        }
+    }
+
+    fn remove_method(&mut self, method: &str) {
+        if let Some(methods) = self.current_methods() {
+            methods.remove(method);
+        }
+    }
+
+    fn instance_prefix(&self) -> &'static str {
+        if self.in_singleton_class {
+            "."
+        } else {
+            "#"
+        }
+    }
+
+    fn visit_namespace(
+        &mut self,
+        name: &Node,
+        kind: ConstantKind,
+        body: &Option<Box<Node>>,
+    ) {
+        // Namespaces named through metaprogramming are skipped entirely
+        let Ok(name) = fetch_const_name(name) else {
+            return;
+        };
+        let name = self.qualify(&name);
+        self.constants
+            .entry(name.clone())
+            .or_insert_with(|| (kind, BTreeSet::new()));
+
+        self.namespaces.push(name);
+        let previous =
+            (self.private, self.module_function, self.in_singleton_class);
+        (self.private, self.module_function, self.in_singleton_class) =
+            (false, false, false);
+        if let Some(body) = body {
+            self.visit(body);
+        }
+        (self.private, self.module_function, self.in_singleton_class) =
+            previous;
+        self.namespaces.pop();
+    }
+
+    fn symbol_arguments(node: &nodes::Send) -> Vec<String> {
+        node.args
+            .iter()
+            .filter_map(|arg| match arg {
+                Node::Sym(sym) => Some(sym.name.to_string_lossy()),
+                Node::Str(string) => Some(string.value.to_string_lossy()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn on_visibility(&mut self, node: &nodes::Send, private: bool) {
+        if node.args.is_empty() {
+            self.private = private;
+            self.module_function = false;
+            return;
+        }
+        for arg in &node.args {
+            match arg {
+                // e.g. `private def foo`
+                Node::Def(_) => {
+                    let previous = self.private;
+                    self.private = private;
+                    self.visit(arg);
+                    self.private = previous;
+                }
+                Node::Sym(_) | Node::Str(_) => {}
+                _ => self.visit(arg),
+            }
+        }
+        if private {
+            let prefix = self.instance_prefix();
+            for method in Self::symbol_arguments(node) {
+                self.remove_method(&format!("{}{}", prefix, method));
+            }
+        }
+    }
+
+    fn on_attribute(&mut self, node: &nodes::Send) {
+        if self.private {
+            return;
+        }
+        let prefix = self.instance_prefix();
+        for attribute in Self::symbol_arguments(node) {
+            if node.method_name != "attr_writer" {
+                self.add_method(format!("{}{}", prefix, attribute));
+            }
+            if node.method_name != "attr_reader" {
+                self.add_method(format!("{}{}=", prefix, attribute));
+            }
+        }
+    }
+}
+
+impl Visitor for ApiCollector {
+    fn on_class(&mut self, node: &nodes::Class) {
+        self.visit_namespace(&node.name, ConstantKind::Class, &node.body);
+    }
+
+    fn on_module(&mut self, node: &nodes::Module) {
+        self.visit_namespace(&node.name, ConstantKind::Module, &node.body);
+    }
+
+    fn on_s_class(&mut self, node: &nodes::SClass) {
+        // Only `class << self` opens the singleton class of the namespace
+        if !matches!(*node.expr, Node::Self_(_)) {
+            return;
+        }
+        let previous = (self.private, self.in_singleton_class);
+        (self.private, self.in_singleton_class) = (false, true);
+        if let Some(body) = &node.body {
+            self.visit(body);
+        }
+        (self.private, self.in_singleton_class) = previous;
+    }
+
+    fn on_casgn(&mut self, node: &nodes::Casgn) {
+        if node.scope.is_none() && !self.namespaces.is_empty() {
+            let name = self.qualify(&node.name);
+            self.constants
+                .entry(name)
+                .or_insert_with(|| (ConstantKind::Constant, BTreeSet::new()));
+        }
+    }
+
+    fn on_def(&mut self, node: &nodes::Def) {
+        if self.module_function {
+            self.add_method(format!(".{}", node.name));
+        } else if !self.private {
+            self.add_method(format!("{}{}", self.instance_prefix(), node.name));
+        }
+    }
+
+    fn on_defs(&mut self, node: &nodes::Defs) {
+        // `private` doesn't apply to methods defined with `def self.`
+        if matches!(*node.definee, Node::Self_(_)) {
+            self.add_method(format!(".{}", node.name));
+        }
+    }
+
+    fn on_send(&mut self, node: &nodes::Send) {
+        if node.recv.is_some() {
+            return lib_ruby_parser::traverse::visitor::visit_send(self, node);
+        }
+        match node.method_name.as_str() {
+            "private" | "protected" => self.on_visibility(node, true),
+            "public" => self.on_visibility(node, false),
+            "private_class_method" => {
+                for method in Self::symbol_arguments(node) {
+                    self.remove_method(&format!(".{}", method));
+                }
+            }
+            "private_constant" => {
+                for constant in Self::symbol_arguments(node) {
+                    let constant = self.qualify(&constant);
+                    self.private_constants.push(constant);
+                }
+            }
+            "module_function" => {
+                let methods = Self::symbol_arguments(node);
+                if methods.is_empty() {
+                    self.module_function = true;
+                }
+                for method in methods {
+                    self.remove_method(&format!("#{}", method));
+                    self.add_method(format!(".{}", method));
+                }
+            }
+            "attr_reader" | "attr_writer" | "attr_accessor" => {
+                self.on_attribute(node)
+            }
+            _ => lib_ruby_parser::traverse::visitor::visit_send(self, node),
+        }
+    }
+}
+
+fn collect(contents: &str) -> ApiCollector {
+    let options = ParserOptions {
+        buffer_name: "".to_string(),
+        ..Default::default()
+    };
+    let parser = Parser::new(contents.to_owned(), options);
+    let mut collector = ApiCollector::default();
+    if let Some(ast) = parser.do_parse().ast {
+        collector.visit(&ast);
+    }
+    collector
+}
+
+// Every class, module, and constant the file defines, including namespaces
+// and private constants, e.g. to read declarations from Sorbet RBI files
+pub(crate) fn extract_constant_names(contents: &str) -> Vec<String> {
+    collect(contents).constants.into_keys().collect()
+}
+
+pub(crate) fn extract_public_api(contents: &str) -> Vec<ApiConstant> {
+    let collector = collect(contents);
+
+    let private_constants = collector.private_constants;
+    let is_private = |name: &str| {
+        private_constants.iter().any(|private_constant| {
+            name == private_constant
+                || name.starts_with(&format!("{}::", private_constant))
+        })
+    };
+    // Modules that only wrap other definitions, e.g. `module Foo` around
+    // `class Foo::Api`, are namespaces rather than part of the API
+    let constants = &collector.constants;
+    let is_namespace =
+        |name: &str, kind: &ConstantKind, methods: &BTreeSet<String>| {
+            *kind == ConstantKind::Module
+                && methods.is_empty()
+                && constants
+                    .keys()
+                    .any(|other| other.starts_with(&format!("{}::", name)))
+        };
+    let namespaces: Vec<String> = constants
+        .iter()
+        .filter(|(name, (kind, methods))| is_namespace(name, kind, methods))
+        .map(|(name, _)| name.clone())
+        .collect();
+    collector
+        .constants
+        .into_iter()
+        .filter(|(name, _)| !is_private(name) && !namespaces.contains(name))
+        .map(|(name, (kind, methods))| ApiConstant {
+            name,
+            kind,
+            methods: methods.into_iter().collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn constant(
+        name: &str,
+        kind: ConstantKind,
+        methods: &[&str],
+    ) -> ApiConstant {
+        ApiConstant {
+            name: name.to_owned(),
+            kind,
+            methods: methods.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_public_methods() {
+        let contents = r#"
+module Foo
+  class Api
+    attr_reader :name
+
+    def self.build; end
+
+    def call; end
+
+    private def helper; end
+
+    def public_after_inline_private; end
+
+    protected
+
+    def compare; end
+
+    private
+
+    def secret; end
+
+    def self.still_public; end
+  end
+end
+"#;
+
+        assert_eq!(
+            vec![constant(
+                "::Foo::Api",
+                ConstantKind::Class,
+                &[
+                    "#call",
+                    "#name",
+                    "#public_after_inline_private",
+                    ".build",
+                    ".still_public"
+                ]
+            )],
+            extract_public_api(contents)
+        );
+    }
+
+    #[test]
+    fn test_singleton_classes_and_private_members() {
+        let contents = r#"
+class Foo::Api
+  VERSION = "1"
+
+  class << self
+    def create; end
+
+    private
+
+    def hidden; end
+  end
+
+  def self.internal; end
+  private_class_method :internal
+
+  def exposed; end
+  def hidden_later; end
+  private :hidden_later
+
+  class Internals
+    def call; end
+  end
+  private_constant :Internals
+end
+"#;
+
+        assert_eq!(
+            vec![
+                constant(
+                    "::Foo::Api",
+                    ConstantKind::Class,
+                    &["#exposed", ".create"]
+                ),
+                constant("::Foo::Api::VERSION", ConstantKind::Constant, &[]),
+            ],
+            extract_public_api(contents)
+        );
+    }
+
+    #[test]
+    fn test_extract_constant_names() {
+        let contents = r#"
+module Foo
+  class Bar < Base
+    Baz = T.let(T.unsafe(nil), Integer)
+  end
+  private_constant :Bar
+end
+"#;
+
+        assert_eq!(
+            vec!["::Foo", "::Foo::Bar", "::Foo::Bar::Baz"],
+            extract_constant_names(contents)
+        );
+    }
+
+    #[test]
+    fn test_module_function() {
+        let contents = r#"
+module Helpers
+  module_function
+
+  def format; end
+end
+"#;
+
+        assert_eq!(
+            vec![constant("::Helpers", ConstantKind::Module, &[".format"])],
+            extract_public_api(contents)
+        );
+    }
+}
@@ -82,6 +82,7 @@ mod tests {
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -112,6 +113,7 @@ mod tests {
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -143,6 +145,7 @@ mod tests {
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -174,6 +177,7 @@ mod tests {
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -201,6 +205,7 @@ end
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -238,6 +243,7 @@ end
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -275,6 +281,7 @@ end
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -311,6 +318,7 @@ end
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -351,6 +359,7 @@ end
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
         assert_eq!(expected, actual);
     }
@@ -392,6 +401,7 @@ end
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
 
         assert_eq!(expected, actual);
@@ -434,6 +444,7 @@ end
             unresolved_references,
             definitions,
             sigils: vec![],
+            parse_errors: vec![],
         };
 
         assert_eq!(expected, actual);
@@ -509,4 +520,145 @@ Foo
             vec![]
         );
     }
+
+    #[test]
+    fn syntax_errors() {
+        let contents: String = String::from("class Foo\n  def bar\nend\n");
+
+        let configuration = Configuration::default();
+        let processed_file = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        );
+
+        assert!(!processed_file.parse_errors.is_empty());
+    }
+
+    #[test]
+    fn modern_syntax() {
+        let contents: String = String::from(
+            "\
+case result
+in Foo::Bar(value:)
+  value
+in [Baz, *rest]
+  rest
+end
+
+def call = Qux.new
+
+{Quux:}
+",
+        );
+
+        let configuration = Configuration::default();
+        let names: Vec<String> = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references
+        .into_iter()
+        .map(|reference| reference.name)
+        .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                String::from("Foo::Bar"),
+                String::from("Baz"),
+                String::from("Qux"),
+                String::from("Quux"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rails_macros_naming_classes() {
+        let contents: String = String::from(
+            "\
+class Order
+  composed_of :balance, class_name: \"Money::Amount\"
+  delegate :charge, to: \"Payments::Gateway\"
+  delegate :name, to: :customer
+end
+",
+        );
+
+        let configuration = Configuration::default();
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+
+        assert_eq!(
+            references,
+            vec![
+                UnresolvedReference {
+                    name: String::from("Money::Amount"),
+                    namespace_path: vec![String::from("Order")],
+                    location: Range {
+                        start_row: 2,
+                        start_col: 2,
+                        end_row: 2,
+                        end_col: 52
+                    }
+                },
+                UnresolvedReference {
+                    name: String::from("Payments::Gateway"),
+                    namespace_path: vec![String::from("Order")],
+                    location: Range {
+                        start_row: 3,
+                        start_col: 2,
+                        end_row: 3,
+                        end_col: 44
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn singleton_class_constants_are_not_definitions() {
+        let contents: String = String::from(
+            "\
+class Foo
+  class << self
+    CACHE = {}
+
+    class Helper
+      def call; end
+    end
+
+    def build
+      Bar.new
+    end
+  end
+end
+",
+        );
+
+        let configuration = Configuration::default();
+        let processed_file = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        );
+        let definition_names: Vec<String> = processed_file
+            .definitions
+            .into_iter()
+            .map(|definition| definition.fully_qualified_name)
+            .collect();
+        let reference_names: Vec<String> = processed_file
+            .unresolved_references
+            .into_iter()
+            .map(|reference| reference.name)
+            .collect();
+
+        assert_eq!(definition_names, vec![String::from("::Foo")]);
+        assert_eq!(reference_names, vec![String::from("Bar")]);
+    }
 }
@@ -1,11 +1,15 @@
 use crate::packs::file_utils::file_read_contents;
-use crate::packs::parsing::ruby::parse_utils::extract_sigils_from_contents;
+use crate::packs::parsing::ruby::parse_utils::{
+    extract_sigils_from_contents, syntax_errors,
+};
 use crate::packs::{
     parsing::{
         ruby::parse_utils::{
             fetch_const_const_name, fetch_const_name, fetch_node_location,
             get_constant_assignment_definition, get_definition_from,
-            get_reference_from_active_record_association, loc_to_range,
+            get_reference_from_active_record_association,
+            get_reference_from_constantize, get_reference_from_rails_macro,
+            loc_to_range,
         },
         ParsedDefinition, UnresolvedReference,
     },
@@ -23,7 +27,12 @@ struct ReferenceCollector<'a> {
     pub current_namespaces: Vec<String>,
     pub line_col_lookup: LineColLookup<'a>,
     pub behavioral_change_in_namespace: bool,
+    // Within `class << self`, where constants are defined on the singleton
+    // class rather than in the enclosing namespace
+    pub in_singleton_class: bool,
     pub custom_associations: Vec<String>,
+    // None unless constantize references are enabled
+    pub constantize_methods: Option<Vec<String>>,
 }
 
 impl<'a> Visitor for ReferenceCollector<'a> {
@@ -63,7 +72,7 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             self.visit(inner);
         }
 
-        if self.behavioral_change_in_namespace {
+        if self.behavioral_change_in_namespace && !self.in_singleton_class {
             self.definitions.push(definition);
         }
 
@@ -91,6 +100,27 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             if let Some(association_reference) = association_reference {
                 self.references.push(association_reference);
             }
+
+            if let Some(macro_reference) = get_reference_from_rails_macro(
+                node,
+                &self.current_namespaces,
+                &self.line_col_lookup,
+                &self.custom_associations,
+            ) {
+                self.references.push(macro_reference);
+            }
+
+            if let Some(constantize_methods) = &self.constantize_methods {
+                if let Some(constantize_reference) =
+                    get_reference_from_constantize(
+                        node,
+                        &self.line_col_lookup,
+                        constantize_methods,
+                    )
+                {
+                    self.references.push(constantize_reference);
+                }
+            }
         }
         lib_ruby_parser::traverse::visitor::visit_send(self, node);
     }
@@ -102,7 +132,10 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             &self.line_col_lookup,
         );
 
-        if let Some(definition) = definition {
+        // `class << self; FOO = 1; end` doesn't define `FOO` in the namespace
+        let on_singleton_class =
+            self.in_singleton_class && node.scope.is_none();
+        if let Some(definition) = definition.filter(|_| !on_singleton_class) {
             self.definitions.push(definition);
         }
 
@@ -139,7 +172,7 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             self.visit(inner);
         }
 
-        if self.behavioral_change_in_namespace {
+        if self.behavioral_change_in_namespace && !self.in_singleton_class {
             self.definitions.push(definition);
         }
 
@@ -150,6 +183,18 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         self.current_namespaces.pop();
     }
 
+    fn on_s_class(&mut self, node: &nodes::SClass) {
+        // Methods defined on the singleton class are behavior of the
+        // enclosing namespace, but its constants aren't in the namespace
+        self.visit(&node.expr);
+        let outer_in_singleton_class = self.in_singleton_class;
+        self.in_singleton_class = true;
+        if let Some(inner) = &node.body {
+            self.visit(inner);
+        }
+        self.in_singleton_class = outer_in_singleton_class;
+    }
+
     fn on_const(&mut self, node: &nodes::Const) {
         let Ok(name) = fetch_const_const_name(node) else {
             if let Some(s) = &node.scope {
@@ -192,19 +237,22 @@ pub(crate) fn process_from_path(
 }
 
 pub(crate) fn process_from_contents(
-    contents: String,
+    contents: impl AsRef<str>,
     path: &Path,
     configuration: &Configuration,
 ) -> ProcessedFile {
+    let contents = contents.as_ref();
     let options = ParserOptions {
         buffer_name: "".to_string(),
         ..Default::default()
     };
 
-    let lookup = LineColLookup::new(&contents);
-    let parser = Parser::new(contents.clone(), options);
+    let lookup = LineColLookup::new(contents);
+    let parser = Parser::new(contents, options);
     let parse_result = parser.do_parse();
 
+    let parse_errors = syntax_errors(&parse_result.diagnostics, &lookup);
+
     let ast_option: Option<Box<Node>> = parse_result.ast;
 
     let ast = match ast_option {
@@ -215,6 +263,7 @@ pub(crate) fn process_from_contents(
                 unresolved_references: vec![],
                 definitions: vec![],
                 sigils: vec![],
+                parse_errors,
             }
         }
     };
@@ -225,7 +274,11 @@ pub(crate) fn process_from_contents(
         definitions: vec![],
         line_col_lookup: lookup,
         behavioral_change_in_namespace: false,
+        in_singleton_class: false,
         custom_associations: configuration.custom_associations.clone(),
+        constantize_methods: configuration
+            .constantize_references
+            .then(|| configuration.constantize_methods.clone()),
     };
 
     collector.visit(&ast);
@@ -239,12 +292,13 @@ pub(crate) fn process_from_contents(
     // For a parser that uses parsed constants, see the experimental parser
     let definitions = collector.definitions;
 
-    let sigils = extract_sigils_from_contents(&contents);
+    let sigils = extract_sigils_from_contents(contents);
 
     ProcessedFile {
         absolute_path,
         unresolved_references,
         definitions,
         sigils,
+        parse_errors,
     }
 }
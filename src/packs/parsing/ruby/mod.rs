@@ -1,3 +1,4 @@
+pub(crate) mod api_collector;
 pub(crate) mod experimental;
 mod inflector_shim;
 mod namespace_calculator;
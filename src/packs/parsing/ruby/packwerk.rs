@@ -1355,6 +1355,25 @@ end
         );
     }
 
+    #[test]
+    fn private_sigil() {
+        let contents: String = String::from("# pack_private: true\nFoo");
+        let configuration = Configuration::default();
+
+        std::assert_eq!(
+            process_from_contents(
+                contents,
+                &PathBuf::from("path/to/file.rb"),
+                &configuration,
+            )
+            .sigils,
+            vec![Sigil {
+                name: String::from("public"),
+                value: false,
+            }]
+        );
+    }
+
     #[test]
     fn sigil_on_line_6() {
         let contents: String = String::from(
@@ -1381,4 +1400,267 @@ Foo
             vec![]
         );
     }
+
+    fn constantize_configuration() -> Configuration {
+        Configuration {
+            constantize_references: true,
+            constantize_methods: vec!["resolve_class".to_owned()],
+            ..Configuration::default()
+        }
+    }
+
+    fn reference_names(
+        contents: &str,
+        configuration: &Configuration,
+    ) -> Vec<String> {
+        process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            configuration,
+        )
+        .unresolved_references
+        .into_iter()
+        .map(|r| r.name)
+        .collect()
+    }
+
+    #[test]
+    fn constantize_is_ignored_by_default() {
+        let contents = "\"Foo::Bar\".constantize";
+
+        assert_eq!(
+            reference_names(contents, &Configuration::default()),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn constantize_string_literal() {
+        let contents: String = String::from(
+            "\
+module Baz
+  \"Foo::Bar\".constantize
+end
+        ",
+        );
+
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &constantize_configuration(),
+        )
+        .unresolved_references;
+        assert_eq!(
+            UnresolvedReference {
+                name: String::from("::Foo::Bar"),
+                namespace_path: vec![],
+                location: Range {
+                    start_row: 2,
+                    start_col: 2,
+                    end_row: 2,
+                    end_col: 25
+                }
+            },
+            references[1],
+        );
+    }
+
+    #[test]
+    fn constantize_variants() {
+        let contents = "\
+\"Foo\".safe_constantize
+Object.const_get(:Bar)
+::Object.const_get(\"Baz::Qux\")
+Quux.const_get(\"Corge\")
+resolve_class(\"Grault\")
+\"not a constant\".constantize
+";
+
+        assert_eq!(
+            reference_names(contents, &constantize_configuration()),
+            vec![
+                String::from("::Foo"),
+                String::from("::Bar"),
+                String::from("Object"),
+                String::from("::Baz::Qux"),
+                String::from("::Object"),
+                String::from("Quux"),
+                String::from("::Grault"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rails_macros_naming_classes() {
+        let contents = "\
+class Order
+  composed_of :balance, class_name: \"Money::Amount\"
+  delegate :charge, to: \"Payments::Gateway\"
+  delegate :refund, to: :Refunds
+  delegate :name, to: :customer
+  has_many :items, class_name: \"LineItem\"
+end
+";
+
+        assert_eq!(
+            reference_names(contents, &Configuration::default()),
+            vec![
+                String::from("::Order"),
+                String::from("Money::Amount"),
+                String::from("Payments::Gateway"),
+                String::from("Refunds"),
+                String::from("LineItem"),
+            ]
+        );
+    }
+
+    #[test]
+    fn constants_in_pattern_matching() {
+        let contents = "\
+case result
+in Foo::Bar(value:)
+  value
+in [Baz, *rest]
+  rest
+in {status: Qux}
+  nil
+in Quux => other
+  other
+end
+";
+
+        assert_eq!(
+            reference_names(contents, &Configuration::default()),
+            vec![
+                String::from("Foo::Bar"),
+                String::from("Baz"),
+                String::from("Qux"),
+                String::from("Quux"),
+            ]
+        );
+    }
+
+    #[test]
+    fn constants_in_endless_methods() {
+        let contents = "\
+def call = Foo::Service.new
+def self.build(options) = Factory.build(options)
+";
+
+        assert_eq!(
+            reference_names(contents, &Configuration::default()),
+            vec![String::from("Foo::Service"), String::from("Factory")]
+        );
+    }
+
+    #[test]
+    fn constants_in_hash_shorthand() {
+        let contents = "\
+user = current_user
+{user:, Config:}
+";
+
+        assert_eq!(
+            reference_names(contents, &Configuration::default()),
+            vec![String::from("Config")]
+        );
+    }
+
+    fn references_with_namespaces(
+        contents: &str,
+    ) -> Vec<(String, Vec<String>)> {
+        process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &Configuration::default(),
+        )
+        .unresolved_references
+        .into_iter()
+        .map(|r| (r.name, r.namespace_path))
+        .collect()
+    }
+
+    fn reference(name: &str, namespace_path: &[&str]) -> (String, Vec<String>) {
+        (
+            name.to_owned(),
+            namespace_path.iter().map(|n| n.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn references_in_singleton_classes() {
+        let contents = "\
+class Foo
+  class << self
+    class Helper
+    end
+
+    def build
+      Bar.new
+    end
+  end
+
+  Baz
+end
+";
+
+        assert_eq!(
+            references_with_namespaces(contents),
+            vec![
+                reference("::Foo", &[]),
+                reference("Bar", &["Foo"]),
+                reference("Baz", &["Foo"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_after_module_function() {
+        let contents = "\
+module Formatting
+  module_function
+
+  def format(value)
+    Formatter.format(value)
+  end
+end
+";
+
+        assert_eq!(
+            references_with_namespaces(contents),
+            vec![
+                reference("::Formatting", &[]),
+                reference("Formatter", &["Formatting"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_in_reopened_classes() {
+        let contents = "\
+class Report < Base
+  class Row
+  end
+
+  Base
+end
+
+class Report
+  Summary
+end
+";
+
+        assert_eq!(
+            references_with_namespaces(contents),
+            vec![
+                reference("Base", &[]),
+                reference("::Report", &[]),
+                reference("::Report::Row", &["Report"]),
+                // The superclass of `Report` is still known after `Row`
+                reference("Base", &[]),
+                // The second definition of `Report` is a local reference
+                reference("Summary", &["Report"]),
+            ]
+        );
+    }
 }
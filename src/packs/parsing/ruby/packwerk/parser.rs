@@ -1,5 +1,7 @@
 use crate::packs::file_utils::file_read_contents;
-use crate::packs::parsing::ruby::parse_utils::extract_sigils_from_contents;
+use crate::packs::parsing::ruby::parse_utils::{
+    extract_sigils_from_contents, syntax_errors,
+};
 
 use crate::packs::{
     parsing::{
@@ -8,7 +10,9 @@ use crate::packs::{
             parse_utils::{
                 fetch_const_const_name, fetch_const_name, fetch_node_location,
                 get_constant_assignment_definition, get_definition_from,
-                get_reference_from_active_record_association, loc_to_range,
+                get_reference_from_active_record_association,
+                get_reference_from_constantize, get_reference_from_rails_macro,
+                loc_to_range,
             },
         },
         ParsedDefinition, Range, UnresolvedReference,
@@ -35,7 +39,12 @@ struct ReferenceCollector<'a> {
     pub line_col_lookup: LineColLookup<'a>,
     pub in_superclass: bool,
     pub superclasses: Vec<SuperclassReference>,
+    // Within `class << self`, where classes and modules are defined on the
+    // singleton class rather than in the enclosing namespace
+    pub in_singleton_class: bool,
     pub custom_associations: Vec<String>,
+    // None unless constantize references are enabled
+    pub constantize_methods: Option<Vec<String>>,
 }
 
 impl<'a> Visitor for ReferenceCollector<'a> {
@@ -50,6 +59,8 @@ impl<'a> Visitor for ReferenceCollector<'a> {
 
         let namespace = namespace_result.unwrap();
 
+        // A superclass may contain several constants, e.g. `Class.new(Base)`
+        let outer_superclasses = self.superclasses.len();
         if let Some(inner) = node.superclass.as_ref() {
             self.in_superclass = true;
             self.visit(inner);
@@ -64,16 +75,7 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             &location,
         );
 
-        let name = definition.fully_qualified_name.to_owned();
-        let namespace_path = self.current_namespaces.to_owned();
-        self.definitions.push(definition);
-
-        // Packwerk also considers a definition to be a "reference"
-        self.references.push(UnresolvedReference {
-            name,
-            namespace_path,
-            location,
-        });
+        self.push_definition(definition, location);
 
         // Note – is there a way to use lifetime specifiers to get rid of this and
         // just keep current namespaces as a vector of string references or something else
@@ -85,7 +87,8 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         }
 
         self.current_namespaces.pop();
-        self.superclasses.pop();
+        // Nested classes without a superclass must not drop that of this one
+        self.superclasses.truncate(outer_superclasses);
     }
 
     fn on_send(&mut self, node: &nodes::Send) {
@@ -101,6 +104,25 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             self.references.push(association_reference);
         }
 
+        if let Some(macro_reference) = get_reference_from_rails_macro(
+            node,
+            &self.current_namespaces,
+            &self.line_col_lookup,
+            &self.custom_associations,
+        ) {
+            self.references.push(macro_reference);
+        }
+
+        if let Some(constantize_methods) = &self.constantize_methods {
+            if let Some(constantize_reference) = get_reference_from_constantize(
+                node,
+                &self.line_col_lookup,
+                constantize_methods,
+            ) {
+                self.references.push(constantize_reference);
+            }
+        }
+
         lib_ruby_parser::traverse::visitor::visit_send(self, node);
     }
 
@@ -136,16 +158,7 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             &location,
         );
 
-        let name = definition.fully_qualified_name.to_owned();
-        let namespace_path = self.current_namespaces.to_owned();
-        self.definitions.push(definition);
-
-        // Packwerk also considers a definition to be a "reference"
-        self.references.push(UnresolvedReference {
-            name,
-            namespace_path,
-            location,
-        });
+        self.push_definition(definition, location);
 
         // Note – is there a way to use lifetime specifiers to get rid of this and
         // just keep current namespaces as a vector of string references or something else
@@ -159,6 +172,19 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         self.current_namespaces.pop();
     }
 
+    fn on_s_class(&mut self, node: &nodes::SClass) {
+        // The object whose singleton class is opened is in the outer scope.
+        // Constant lookup within `class << self` is unchanged, so references
+        // keep the namespaces of the enclosing class.
+        self.visit(&node.expr);
+        let outer_in_singleton_class = self.in_singleton_class;
+        self.in_singleton_class = true;
+        if let Some(inner) = &node.body {
+            self.visit(inner);
+        }
+        self.in_singleton_class = outer_in_singleton_class;
+    }
+
     fn on_const(&mut self, node: &nodes::Const) {
         let Ok(name) = fetch_const_const_name(node) else {
             if let Some(s) = &node.scope {
@@ -205,6 +231,29 @@ impl<'a> Visitor for ReferenceCollector<'a> {
     }
 }
 
+impl<'a> ReferenceCollector<'a> {
+    fn push_definition(
+        &mut self,
+        definition: ParsedDefinition,
+        location: Range,
+    ) {
+        let name = definition.fully_qualified_name.to_owned();
+        let namespace_path = self.current_namespaces.to_owned();
+        self.definitions.push(definition);
+
+        // Packwerk also considers a definition to be a "reference", except
+        // within `class << self`, where e.g. `class Foo` doesn't define
+        // `Foo` in the enclosing namespace, so can't refer to another pack's
+        if !self.in_singleton_class {
+            self.references.push(UnresolvedReference {
+                name,
+                namespace_path,
+                location,
+            });
+        }
+    }
+}
+
 pub(crate) fn process_from_path(
     path: &Path,
     configuration: &Configuration,
@@ -214,19 +263,22 @@ pub(crate) fn process_from_path(
 }
 
 pub(crate) fn process_from_contents(
-    contents: String,
+    contents: impl AsRef<str>,
     path: &Path,
     configuration: &Configuration,
 ) -> ProcessedFile {
+    let contents = contents.as_ref();
     let options = ParserOptions {
         buffer_name: "".to_string(),
         ..Default::default()
     };
 
-    let lookup = LineColLookup::new(&contents);
-    let parser = Parser::new(contents.clone(), options);
+    let lookup = LineColLookup::new(contents);
+    let parser = Parser::new(contents, options);
     let parse_result = parser.do_parse();
 
+    let parse_errors = syntax_errors(&parse_result.diagnostics, &lookup);
+
     let ast_option: Option<Box<Node>> = parse_result.ast;
 
     let ast = match ast_option {
@@ -237,6 +289,7 @@ pub(crate) fn process_from_contents(
                 unresolved_references: vec![],
                 definitions: vec![],
                 sigils: vec![],
+                parse_errors,
             }
         }
     };
@@ -248,7 +301,11 @@ pub(crate) fn process_from_contents(
         line_col_lookup: lookup,
         in_superclass: false,
         superclasses: vec![],
+        in_singleton_class: false,
         custom_associations: configuration.custom_associations.clone(),
+        constantize_methods: configuration
+            .constantize_references
+            .then(|| configuration.constantize_methods.clone()),
     };
 
     collector.visit(&ast);
@@ -313,12 +370,13 @@ pub(crate) fn process_from_contents(
     // For a parser that uses parsed constants, see the experimental parser
     let definitions = vec![];
 
-    let sigils = extract_sigils_from_contents(&contents);
+    let sigils = extract_sigils_from_contents(contents);
 
     ProcessedFile {
         absolute_path,
         unresolved_references,
         definitions,
         sigils,
+        parse_errors,
     }
 }
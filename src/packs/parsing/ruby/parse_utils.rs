@@ -1,10 +1,10 @@
 use std::collections::HashSet;
 
-use lib_ruby_parser::{nodes, Loc, Node};
+use lib_ruby_parser::{nodes, Diagnostic, Loc, Node};
 use line_col::LineColLookup;
 
 use crate::packs::{
-    parsing::{ParsedDefinition, Range, UnresolvedReference},
+    parsing::{ParsedDefinition, Range, SyntaxError, UnresolvedReference},
     Sigil,
 };
 
@@ -63,6 +63,24 @@ pub fn loc_to_range(loc: &Loc, lookup: &LineColLookup) -> Range {
     }
 }
 
+pub fn syntax_errors(
+    diagnostics: &[Diagnostic],
+    lookup: &LineColLookup,
+) -> Vec<SyntaxError> {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .map(|diagnostic| {
+            let (line, column) = lookup.get(diagnostic.loc.begin);
+            SyntaxError {
+                message: diagnostic.render_message(),
+                line,
+                column,
+            }
+        })
+        .collect()
+}
+
 pub fn fetch_const_name(node: &nodes::Node) -> Result<String, ParseError> {
     match node {
         Node::Const(const_node) => Ok(fetch_const_const_name(const_node)?),
@@ -113,17 +131,7 @@ pub fn get_reference_from_active_record_association(
 ) -> Option<UnresolvedReference> {
     // TODO: Read in args, process associations as a separate class
     // These can get complicated! e.g. we can specify a class name
-    let combined_associations: Vec<String> = custom_associations
-        .iter()
-        .map(|s| s.to_owned())
-        .chain(ASSOCIATION_METHOD_NAMES.iter().copied().map(String::from))
-        .collect();
-
-    let is_association = combined_associations
-        .iter()
-        .any(|association_method| node.method_name == *association_method);
-
-    if is_association {
+    if is_association(node, custom_associations) {
         let first_arg: Option<&Node> = node.args.first();
 
         let mut name: Option<String> = None;
@@ -169,6 +177,106 @@ pub fn get_reference_from_active_record_association(
     }
 }
 
+fn is_association(node: &nodes::Send, custom_associations: &[String]) -> bool {
+    custom_associations
+        .iter()
+        .map(|s| s.as_str())
+        .chain(ASSOCIATION_METHOD_NAMES.iter().copied())
+        .any(|association_method| node.method_name == association_method)
+}
+
+// Other Rails macros that name a class in a string, e.g.
+// `composed_of :balance, class_name: "Money"`, and `delegate` to a constant,
+// e.g. `delegate :charge, to: "Payments::Gateway"`, since Rails evaluates the
+// target of a delegation as code within the class
+pub fn get_reference_from_rails_macro(
+    node: &nodes::Send,
+    current_namespaces: &[String],
+    line_col_lookup: &LineColLookup,
+    custom_associations: &[String],
+) -> Option<UnresolvedReference> {
+    // Associations handle their own `class_name:`
+    if is_association(node, custom_associations) {
+        return None;
+    }
+
+    let option = if node.method_name == "delegate" {
+        "to"
+    } else {
+        "class_name"
+    };
+    let name = node.args.iter().find_map(|arg| match arg {
+        Node::Kwargs(kwargs) => extract_name_from_kwargs(kwargs, option),
+        _ => None,
+    })?;
+    if !is_constant_name(&name) {
+        return None;
+    }
+
+    Some(UnresolvedReference {
+        name,
+        namespace_path: current_namespaces.to_owned(),
+        location: loc_to_range(&node.expression_l, line_col_lookup),
+    })
+}
+
+const CONSTANTIZE_METHOD_NAMES: [&str; 3] =
+    ["constantize", "safe_constantize", "const_get"];
+
+// A reference to a constant named by a string literal, e.g. `"Foo::Bar".constantize`
+// or `Object.const_get("Foo::Bar")`. Like `constantize`, names are resolved from
+// the top level, regardless of where the call is made.
+pub fn get_reference_from_constantize(
+    node: &nodes::Send,
+    line_col_lookup: &LineColLookup,
+    constantize_methods: &[String],
+) -> Option<UnresolvedReference> {
+    let is_constantize = constantize_methods
+        .iter()
+        .map(|s| s.as_str())
+        .chain(CONSTANTIZE_METHOD_NAMES.iter().copied())
+        .any(|method_name| node.method_name == method_name);
+    if !is_constantize {
+        return None;
+    }
+
+    // `Foo.const_get("Bar")` is relative to `Foo`, which we don't track
+    if node.method_name == "const_get" {
+        let receiver_is_object = matches!(
+            node.recv.as_deref(),
+            Some(Node::Const(c)) if c.name == "Object"
+                && matches!(c.scope.as_deref(), None | Some(Node::Cbase(_)))
+        );
+        if !receiver_is_object {
+            return None;
+        }
+    }
+
+    let name = match (node.recv.as_deref(), node.args.first()) {
+        (Some(Node::Str(s)), _) | (_, Some(Node::Str(s))) => {
+            s.value.to_string_lossy()
+        }
+        (_, Some(Node::Sym(s))) => s.name.to_string_lossy(),
+        _ => return None,
+    };
+    if !is_constant_name(&name) {
+        return None;
+    }
+
+    Some(UnresolvedReference {
+        name: format!("::{}", name.trim_start_matches("::")),
+        namespace_path: vec![],
+        location: loc_to_range(&node.expression_l, line_col_lookup),
+    })
+}
+
+fn is_constant_name(name: &str) -> bool {
+    name.trim_start_matches("::").split("::").all(|part| {
+        part.starts_with(|c: char| c.is_ascii_uppercase())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
 fn extract_class_name_from_kwargs(kwargs: &nodes::Kwargs) -> Option<String> {
     for pair_node in kwargs.pairs.iter() {
         if let Node::Pair(pair) = pair_node {
@@ -185,6 +293,25 @@ fn extract_class_name_from_kwargs(kwargs: &nodes::Kwargs) -> Option<String> {
     None
 }
 
+// The string or symbol value of the `key:` option, e.g. `"Foo::Bar"` or `:Foo`
+fn extract_name_from_kwargs(
+    kwargs: &nodes::Kwargs,
+    key: &str,
+) -> Option<String> {
+    kwargs.pairs.iter().find_map(|pair_node| match pair_node {
+        Node::Pair(pair) => match (&*pair.key, &*pair.value) {
+            (Node::Sym(k), Node::Str(v)) if k.name.to_string_lossy() == key => {
+                Some(v.value.to_string_lossy())
+            }
+            (Node::Sym(k), Node::Sym(v)) if k.name.to_string_lossy() == key => {
+                Some(v.name.to_string_lossy())
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
 pub fn get_constant_assignment_definition(
     node: &nodes::Casgn,
     current_namespaces: Vec<String>,
@@ -210,18 +337,37 @@ pub fn get_constant_assignment_definition(
     })
 }
 
+// `# pack_public: true` and `# pack_private: true` make the constants of a
+// file public or private, overriding the public paths of its pack
+const PUBLIC_SIGILS: [(&str, bool); 4] = [
+    ("pack_public: true", true),
+    ("pack_public: false", false),
+    ("pack_private: true", false),
+    ("pack_private: false", true),
+];
+
 pub fn extract_sigils_from_contents(contents: &str) -> Vec<Sigil> {
     let mut sigils: Vec<Sigil> = Vec::new();
 
     // Hardcoded to public, but later we can make this a convention like `pack_*: true`, if we find it more generally useful
     contents.lines().take(5).for_each(|line| {
-        if line.contains("pack_public: true") {
-            sigils.push(Sigil {
-                name: "public".to_string(),
-                value: true,
-            });
+        for (comment, public) in PUBLIC_SIGILS {
+            if line.contains(comment) {
+                sigils.push(Sigil {
+                    name: "public".to_string(),
+                    value: public,
+                });
+            }
         }
     });
 
     sigils
 }
+
+// Whether the sigils make a file public or private, if they say either way
+pub fn public_sigil_value(sigils: &[Sigil]) -> Option<bool> {
+    sigils
+        .iter()
+        .find(|sigil| sigil.name == "public")
+        .map(|sigil| sigil.value)
+}
@@ -10,20 +10,73 @@ use regex::Regex;
 pub(crate) fn get_acronyms_from_disk(
     inflections_path: &Path,
 ) -> HashSet<String> {
-    let mut acronyms: HashSet<String> = HashSet::new();
-
-    if inflections_path.exists() {
-        let inflections_file =
-            std::fs::read_to_string(inflections_path).unwrap();
-        let inflections_lines = inflections_file.lines();
-        for line in inflections_lines {
-            if line.contains(".acronym") {
-                let re = Regex::new(r#"['\\"]"#).unwrap();
-                let acronym = re.split(line).nth(1).unwrap();
-                acronyms.insert(acronym.to_string());
-            }
-        }
+    match std::fs::read_to_string(inflections_path) {
+        Ok(inflections_file) => get_acronyms_from_contents(&inflections_file),
+        Err(_) => HashSet::new(),
     }
+}
 
+// Acronyms and custom inflections are merged from the inflections file and the
+// `inflections` key of the configuration file, so either source can be used on its own.
+pub(crate) fn get_all_acronyms(
+    inflections_path: &Path,
+    configured_acronyms: &HashSet<String>,
+) -> HashSet<String> {
+    let mut acronyms = get_acronyms_from_disk(inflections_path);
+    acronyms.extend(configured_acronyms.iter().cloned());
     acronyms
 }
+
+fn get_acronyms_from_contents(contents: &str) -> HashSet<String> {
+    // Matches `inflect.acronym 'API'`, `inflect.acronym("API")`, etc.
+    let re = Regex::new(r#"\.acronym\(?\s*['"]([^'"]+)['"]"#).unwrap();
+
+    contents
+        .lines()
+        .map(|line| line.trim_start())
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| re.captures(line))
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_acronyms_from_contents() {
+        let contents = r#"
+ActiveSupport::Inflector.inflections(:en) do |inflect|
+  inflect.acronym 'API'
+  inflect.acronym "CSV"
+  inflect.acronym('HTML')
+  # inflect.acronym 'SKIPPED'
+  inflect.irregular 'person', 'people'
+end
+"#;
+        let expected: HashSet<String> = ["API", "CSV", "HTML"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(expected, get_acronyms_from_contents(contents));
+    }
+
+    #[test]
+    fn test_get_all_acronyms_merges_configured_acronyms() {
+        let configured: HashSet<String> =
+            ["GraphQL".to_string()].into_iter().collect();
+
+        let actual = get_all_acronyms(
+            Path::new("tests/fixtures/app_with_inflections/config/initializers/inflections.rb"),
+            &configured,
+        );
+        let expected: HashSet<String> = ["API", "CSV", "GraphQL"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+}
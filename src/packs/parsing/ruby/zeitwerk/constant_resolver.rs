@@ -1,6 +1,6 @@
 use tracing::debug;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::packs::{
     constant_resolver::{ConstantDefinition, ConstantResolver},
@@ -11,6 +11,9 @@ use crate::packs::{
 pub struct ZeitwerkConstantResolver {
     pub fully_qualified_constant_name_to_constant_definition_map:
         HashMap<String, Vec<ConstantDefinition>>,
+    // Every namespace of a defined constant, e.g. `::Foo` and `::Foo::Bar`
+    // for `::Foo::Bar::Baz`
+    namespaces: HashSet<String>,
 }
 
 impl ConstantResolver for ZeitwerkConstantResolver {
@@ -21,19 +24,13 @@ impl ConstantResolver for ZeitwerkConstantResolver {
     ) -> Option<Vec<ConstantDefinition>> {
         // If the fully_or_partially_qualified_constant is prefixed with ::, the namespace path is technically empty, since it's a global reference
         let (namespace_path, const_name) =
-            if fully_or_partially_qualified_constant.starts_with("::") {
-                // `resolve_constant` will add a leading :: before it makes a guess at the fully qualified name
-                // so we remove it here and represent it as a relative constant with no namespace path
-                let const_name = fully_or_partially_qualified_constant
-                    .strip_prefix("::")
-                    .unwrap();
-                let namespace_path: &[&str] = &[];
-                (namespace_path, const_name)
-            } else {
-                (namespace_path, fully_or_partially_qualified_constant)
+            match fully_or_partially_qualified_constant.strip_prefix("::") {
+                Some(const_name) => (&[][..], const_name),
+                None => (namespace_path, fully_or_partially_qualified_constant),
             };
 
-        self.resolve_constant(const_name, namespace_path, const_name)
+        self.resolve_constant(const_name, namespace_path)
+            .map(|definition| vec![definition])
     }
 
     fn fully_qualified_constant_name_to_constant_definition_map(
@@ -56,26 +53,35 @@ impl ZeitwerkConstantResolver {
 
         // TODO: Do this in parallel?
         for constant in constants {
-            let fully_qualified_constant_name =
-                constant.fully_qualified_name.clone();
-
-            let existing_constant = fully_qualified_constant_to_constant_map
-                .get(&fully_qualified_constant_name);
-
-            if let Some(existing_constant) = existing_constant {
-                // TODO: This still needs to be handled more elegantly. For now, we just panic.
-                // Probably, we should have the HashMap have a Vec<Constant> instead of a single Constant, and then we can add to the Vec.
-                // Then, when we create references, we can create one reference to each unique pack that defines the constant.
-
-                // Later, we can allow the checkers to skip over constants where it's pointing at a pack that defines it as an ignored_monkeypatch: path/to/definition.rb
-                // We should be sure to validate that ignored_monkeypatch paths match the absolute_path_to_definition of the constant.
-                panic!(
-                    "Found two constants with the same name: {:?} and {:?}",
-                    existing_constant, constant
-                );
-            } else {
-                fully_qualified_constant_to_constant_map
-                    .insert(fully_qualified_constant_name, vec![constant]);
+            fully_qualified_constant_to_constant_map
+                .entry(constant.fully_qualified_name.clone())
+                .or_default()
+                .push(constant);
+        }
+        // Files mapping to the same constant are ambiguous, and the
+        // constant_ambiguity validator reports them. Until they're fixed,
+        // references resolve to the first file by path, rather than to
+        // whichever was inferred first.
+        for definitions in fully_qualified_constant_to_constant_map.values_mut()
+        {
+            definitions.sort_by(|a, b| {
+                a.absolute_path_of_definition
+                    .cmp(&b.absolute_path_of_definition)
+            });
+        }
+
+        // Namespaces may have no file of their own, e.g. `::Foo::Bar` when
+        // only `foo/bar/baz.rb` exists, since Zeitwerk defines them implicitly
+        let mut namespaces = HashSet::new();
+        for fully_qualified_name in
+            fully_qualified_constant_to_constant_map.keys()
+        {
+            let mut namespace = fully_qualified_name.as_str();
+            while let Some((parent, _)) = namespace.rsplit_once("::") {
+                if parent.is_empty() || !namespaces.insert(parent.to_owned()) {
+                    break;
+                }
+                namespace = parent;
             }
         }
 
@@ -84,115 +90,100 @@ impl ZeitwerkConstantResolver {
         Box::new(ZeitwerkConstantResolver {
             fully_qualified_constant_name_to_constant_definition_map:
                 fully_qualified_constant_to_constant_map,
+            namespaces,
         })
     }
 
-    fn resolve_constant<'a>(
-        &'a self,
-        const_name: &'a str,
-        current_namespace_path: &'a [&str],
-        original_name: &'a str,
-    ) -> Option<Vec<ConstantDefinition>> {
-        let constant = self.resolve_traversing_namespace_path(
-            const_name,
-            current_namespace_path,
-            original_name,
-        );
-        match constant {
-            Some(definition) => Some(vec![definition]),
-            None => {
-                // If we couldn't find a match, it's possible the constant is defined within its parent namespace and not within its own file.
-                // For example, `Boo` above could be defined in `foo/bar.rb` as:
-                // module Foo
-                //   module Bar
-                //     class Boo
-                //     end
-                //   end
-                // end
-                // Therefore, we take the given const_name, remove the last part of the fully qualified name, and try again.
-                // In this case, we'd try to resolve `::Foo::Bar` instead of `::Foo::Bar::Boo`
-                let split_const = const_name.split("::").collect::<Vec<&str>>();
-                if split_const.len() <= 1 {
-                    return None;
-                }
-                let parent_constant =
-                    split_const[0..=split_const.len() - 2].join("::");
-                self.resolve_constant(
-                    &parent_constant,
-                    current_namespace_path,
-                    original_name,
-                )
-            }
-        }
-    }
-
     // In Ruby, say we have this code:
     //
     // module Foo
     //   module Bar
     //     module Baz
-    //       Boo
+    //       Boo::Bam
     //     end
     //   end
     // end
     //
-    // The `current_namespace_path` here is: ['Foo', 'Bar', 'Baz']
-    // The `const_name` here is: `Boo`
-    // Ruby constant resolution rules dictate that `Boo` coudl refer to any of the following,
-    // in this specific order:
+    // The `namespace_path` here is: ['Foo', 'Bar', 'Baz']
+    // The `const_name` here is: `Boo::Bam`
+    // Ruby looks up only the first segment, `Boo`, lexically, in this order:
     //
     // ::Foo::Bar::Baz::Boo
     // ::Foo::Bar::Boo
     // ::Foo::Boo
     // ::Boo
     //
-    // We need to check each of these possibilities in order, and return the first one that exists
-    // If none of them exist, return None
-    fn resolve_traversing_namespace_path<'a>(
-        &'a self,
-        const_name: &'a str,
-        current_namespace_path: &'a [&str],
-        original_name: &'a str,
+    // and then looks up `Bam` within the first of these that exists. If that's
+    // `::Foo::Bar::Boo`, the reference is to `::Foo::Bar::Boo::Bam`, even if
+    // `::Boo::Bam` exists and `::Foo::Bar::Boo::Bam` doesn't, so we never try
+    // namespaces further out than the one `Boo` is found in.
+    fn resolve_constant(
+        &self,
+        const_name: &str,
+        namespace_path: &[&str],
     ) -> Option<ConstantDefinition> {
-        let fully_qualified_name_guess = combine_namespace_with_constant_name(
-            current_namespace_path,
-            const_name,
-        );
+        let segments: Vec<&str> = const_name.split("::").collect();
+        for namespace_length in (0..=namespace_path.len()).rev() {
+            let namespace = &namespace_path[..namespace_length];
+            let fully_qualified_name =
+                combine_namespace_with_constant_name(namespace, const_name);
+            if let Some(constant) =
+                self.constant_for_fully_qualified_name(&fully_qualified_name)
+            {
+                return Some(ConstantDefinition {
+                    fully_qualified_name,
+                    absolute_path_of_definition: constant
+                        .absolute_path_of_definition
+                        .to_owned(),
+                });
+            }
 
-        if let Some(constant) =
-            self.constant_for_fully_qualified_name(&fully_qualified_name_guess)
-        {
-            // Since the ContantResolver might say that some constant Foo::Bar::Baz is defined in Foo::Bar,
-            // we want to return a ConstantDefinition that has the fully qualified name of the constant we're looking for.
-            // In this case, we want to return a ConstantDefinition with the fully qualified name of Foo::Bar::Baz
-            // even though the ConstantDefinition we found has the fully qualified name of Foo::Bar
-            // The ConstantResolver from the experimental parser does not need to do this, so we might be better off
-            // having a separate ConstantResolver for that implementation
-            let fully_qualified_name = combine_namespace_with_constant_name(
-                current_namespace_path,
-                original_name,
-            );
-
-            let absolute_path_of_definition =
-                constant.absolute_path_of_definition.to_owned();
-            Some(ConstantDefinition {
-                fully_qualified_name,
-                absolute_path_of_definition,
-            })
-        } else {
-            // In this case, we couldn't find a constant with the given name under the given namespace.
-            // However, it's possible the constant is defined within the parent namespace.
-            let split_result = current_namespace_path.split_last();
-            match split_result {
-                Some((_last, parent_namespace)) => self
-                    .resolve_traversing_namespace_path(
-                        const_name,
-                        parent_namespace,
-                        original_name,
-                    ),
-                None => None,
+            let first_segment =
+                combine_namespace_with_constant_name(namespace, segments[0]);
+            if !self.is_defined_or_namespace(&first_segment) {
+                continue;
             }
+            // It's possible the constant is defined within its parent
+            // namespace and not within its own file. For example, `Bam` above
+            // could be defined in `foo/bar/boo.rb` as:
+            // module Foo
+            //   module Bar
+            //     class Boo
+            //       class Bam
+            //       end
+            //     end
+            //   end
+            // end
+            // Therefore, we remove the last part of the fully qualified name
+            // and try again, i.e. `::Foo::Bar::Boo`. Since the definition is
+            // for the parent, we return it with the fully qualified name of the
+            // constant we're looking for.
+            for name_length in (1..segments.len()).rev() {
+                let parent_name = combine_namespace_with_constant_name(
+                    namespace,
+                    &segments[..name_length].join("::"),
+                );
+                if let Some(constant) =
+                    self.constant_for_fully_qualified_name(&parent_name)
+                {
+                    return Some(ConstantDefinition {
+                        fully_qualified_name,
+                        absolute_path_of_definition: constant
+                            .absolute_path_of_definition
+                            .to_owned(),
+                    });
+                }
+            }
+            return None;
         }
+        None
+    }
+
+    fn is_defined_or_namespace(&self, fully_qualified_name: &String) -> bool {
+        self.namespaces.contains(fully_qualified_name)
+            || self
+                .fully_qualified_constant_name_to_constant_definition_map
+                .contains_key(fully_qualified_name)
     }
 
     fn constant_for_fully_qualified_name(
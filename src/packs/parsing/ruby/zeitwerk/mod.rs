@@ -17,7 +17,7 @@ use crate::packs::{
     },
     file_utils::expand_glob,
     pack::Pack,
-    parsing::ruby::rails_utils::get_acronyms_from_disk,
+    parsing::ruby::rails_utils::get_all_acronyms,
     PackSet,
 };
 
@@ -30,8 +30,27 @@ pub fn get_zeitwerk_constant_resolver(
     pack_set: &PackSet,
     configuration: &ConstantResolverConfiguration,
 ) -> Box<dyn ConstantResolver + Send + Sync> {
-    let constants = inferred_constants_from_pack_set(pack_set, configuration);
+    let packs: Vec<&Pack> = pack_set.packs.iter().collect();
+    get_zeitwerk_constant_resolver_for_packs(&packs, configuration)
+}
+
+// Only constants autoloaded from the given packs are resolvable, e.g. the packs
+// visible from one app root of a monorepo
+pub fn get_zeitwerk_constant_resolver_for_packs(
+    packs: &[&Pack],
+    configuration: &ConstantResolverConfiguration,
+) -> Box<dyn ConstantResolver + Send + Sync> {
+    let constants = inferred_constants_from_packs(packs, configuration);
+
+    ZeitwerkConstantResolver::create(constants)
+}
 
+// Resolves constants among the given definitions with the same lexical
+// scoping rules, e.g. for definitions read from outside the autoload paths.
+// Definitions must have distinct names.
+pub fn get_zeitwerk_constant_resolver_for_definitions(
+    constants: Vec<ConstantDefinition>,
+) -> Box<dyn ConstantResolver + Send + Sync> {
     ZeitwerkConstantResolver::create(constants)
 }
 
@@ -94,8 +113,8 @@ fn get_pack_namespace_settings(pack: &Pack) -> PackNamespaceSettings {
         }) // Default to false and empty set if metadata doesn't exist
 }
 
-fn inferred_constants_from_pack_set(
-    pack_set: &PackSet,
+fn inferred_constants_from_packs(
+    packs: &[&Pack],
     configuration: &ConstantResolverConfiguration,
 ) -> Vec<ConstantDefinition> {
     // build the full list of default autoload roots from the pack set, using the default namespace for each.
@@ -107,8 +126,13 @@ fn inferred_constants_from_pack_set(
     //     - app/models # Exclude models
     // For packs that have this configuration, if the autoload root is not in the list of automatic_pack_namespace_exclusions,
     // set the namespace associated with that root to inflector_shim::camelize(pack.name).
-    let mut full_autoload_roots: HashMap<PathBuf, String> = pack_set
-        .packs
+    debug!("Getting acronyms from disk and configuration");
+    let acronyms = &get_all_acronyms(
+        configuration.inflections_path,
+        configuration.acronyms,
+    );
+
+    let mut full_autoload_roots: HashMap<PathBuf, String> = packs
         .iter()
         .flat_map(|pack| {
             let default_roots = pack.default_autoload_roots();
@@ -125,19 +149,11 @@ fn inferred_constants_from_pack_set(
                 if automatic_pack_namespace
                     && !automatic_pack_namespace_exclusions.contains(&path)
                 {
-                    // Pass an empty set of acronyms as the second argument
-                    // NOTE: This is not the correct implementation – if we want automatic namespacing to work with
-                    // acronym-based pack names, we need to pull from the file, preferably from the cache.
-                    let empty_acronyms = HashSet::new();
-
                     // Camelized pack namespace based on pack name with leading double colon:
                     // e.g. pack name "packs/my_pack" -> "::MyPack"
                     let namespace = format!(
                         "::{}",
-                        inflector_shim::camelize(
-                            pack.last_name(),
-                            &empty_acronyms,
-                        )
+                        inflector_shim::camelize(pack.last_name(), acronyms,)
                     );
 
                     (path, namespace)
@@ -166,12 +182,17 @@ fn inferred_constants_from_pack_set(
                 });
         });
 
-    inferred_constants_from_autoload_paths(configuration, full_autoload_roots)
+    inferred_constants_from_autoload_paths(
+        configuration,
+        full_autoload_roots,
+        acronyms,
+    )
 }
 
 fn inferred_constants_from_autoload_paths(
     configuration: &ConstantResolverConfiguration,
     full_autoload_roots: HashMap<PathBuf, String>,
+    acronyms: &HashSet<String>,
 ) -> Vec<ConstantDefinition> {
     debug!("Get constant resolver cache");
     let cache_data = get_constant_resolver_cache(configuration.cache_directory);
@@ -220,9 +241,6 @@ fn inferred_constants_from_autoload_paths(
         }
     }
 
-    debug!("Getting acronyms from disk");
-    let acronyms = &get_acronyms_from_disk(configuration.inflections_path);
-
     debug!("Inferring constants from file name (using cache)");
     let constants: Vec<ConstantDefinition> = file_to_longest_path
         .into_iter()
@@ -245,6 +263,7 @@ fn inferred_constants_from_autoload_paths(
                     absolute_autoload_path,
                     acronyms,
                     default_namespace,
+                    configuration,
                 )
             }
         })
@@ -265,11 +284,16 @@ fn inferred_constant_from_file(
     absolute_autoload_path: &PathBuf,
     acronyms: &HashSet<String>,
     default_namespace: &String,
+    configuration: &ConstantResolverConfiguration,
 ) -> ConstantDefinition {
     let relative_path =
         absolute_path.strip_prefix(absolute_autoload_path).unwrap();
 
-    let relative_path = relative_path.with_extension("");
+    let relative_path = collapse_dirs(
+        &relative_path.with_extension(""),
+        absolute_autoload_path,
+        configuration,
+    );
 
     let relative_path_str = relative_path.to_str().unwrap();
     let camelized_path = inflector_shim::camelize(relative_path_str, acronyms);
@@ -282,6 +306,41 @@ fn inferred_constant_from_file(
     }
 }
 
+// Drops the directories matching `collapse_dirs` from the path of a file
+// relative to its autoload root, e.g. `booking/actions/create` becomes
+// `booking/create` when `packs/*/app/services/booking/actions` is collapsed
+fn collapse_dirs(
+    relative_path: &Path,
+    absolute_autoload_path: &Path,
+    configuration: &ConstantResolverConfiguration,
+) -> PathBuf {
+    if configuration.collapse_dirs.is_empty() {
+        return relative_path.to_path_buf();
+    }
+    let components: Vec<_> = relative_path.components().collect();
+    let Some((file_name, directories)) = components.split_last() else {
+        return relative_path.to_path_buf();
+    };
+
+    let mut directory = absolute_autoload_path.to_path_buf();
+    let mut collapsed = PathBuf::new();
+    for component in directories {
+        directory.push(component);
+        let relative_directory = directory
+            .strip_prefix(configuration.absolute_root)
+            .unwrap_or(&directory);
+        let is_collapsed = configuration
+            .collapse_dirs
+            .iter()
+            .any(|pattern| pattern.matches_path(relative_directory));
+        if !is_collapsed {
+            collapsed.push(component);
+        }
+    }
+    collapsed.push(file_name);
+    collapsed
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 struct ConstantResolverCache {
     file_definition_map: HashMap<PathBuf, String>,
@@ -465,6 +524,96 @@ mod tests {
         teardown();
     }
 
+    #[test]
+    fn constant_in_collapsed_dir() {
+        let app = "tests/fixtures/app_with_collapsed_dirs";
+        let absolute_root = get_absolute_root(app);
+        let resolver = get_zeitwerk_constant_resolver_for_fixture(app).unwrap();
+
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::Booking::Create".to_string(),
+                absolute_path_of_definition: absolute_root.join(
+                    "packs/booking/app/services/booking/actions/create.rb"
+                )
+            }],
+            resolver.resolve("Create", &["Booking"]).unwrap()
+        );
+        assert_eq!(None, resolver.resolve("::Booking::Actions::Create", &[]));
+        // app/views isn't autoloaded
+        assert_eq!(None, resolver.resolve("::Booking::Helper", &[]));
+    }
+
+    #[test]
+    fn nested_references_resolve_within_the_namespace_of_their_first_segment() {
+        let app = "tests/fixtures/app_with_nested_namespaces";
+        let absolute_root = get_absolute_root(app);
+        let resolver = get_zeitwerk_constant_resolver_for_fixture(app).unwrap();
+        let resolve = |name: &str, namespace_path: &[&str]| {
+            resolver.resolve(name, namespace_path).map(|definitions| {
+                definitions
+                    .into_iter()
+                    .map(|definition| {
+                        (
+                            definition.fully_qualified_name,
+                            definition
+                                .absolute_path_of_definition
+                                .strip_prefix(&absolute_root)
+                                .unwrap()
+                                .to_owned(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        let definition = |name: &str, path: &str| {
+            Some(vec![(name.to_owned(), PathBuf::from(path))])
+        };
+
+        // The innermost namespace defining the constant wins
+        assert_eq!(
+            definition(
+                "::Billing::Payment",
+                "packs/billing/app/models/billing/payment.rb"
+            ),
+            resolve("Payment", &["Billing", "Invoice"])
+        );
+        // Unless the reference is absolute
+        assert_eq!(
+            definition("::Payment", "packs/payments/app/models/payment.rb"),
+            resolve("::Payment", &["Billing", "Invoice"])
+        );
+        // `Payment` is `::Billing::Payment`, so the reference isn't to
+        // `::Payment::Receipt`, but to a constant within `::Billing::Payment`
+        assert_eq!(
+            definition(
+                "::Billing::Payment::Receipt",
+                "packs/billing/app/models/billing/payment.rb"
+            ),
+            resolve("Payment::Receipt", &["Billing", "Invoice"])
+        );
+        assert_eq!(
+            definition(
+                "::Payment::Receipt",
+                "packs/payments/app/models/payment/receipt.rb"
+            ),
+            resolve("::Payment::Receipt", &["Billing", "Invoice"])
+        );
+        // Implicit namespaces shadow outer constants too
+        assert_eq!(None, resolve("Tax::Rate", &["Billing", "Invoice"]));
+        assert_eq!(
+            definition("::Tax::Rate", "packs/payments/app/models/tax/rate.rb"),
+            resolve("Tax::Rate", &["Payment"])
+        );
+        // `class Billing::Refund` doesn't nest within `Billing`
+        assert_eq!(
+            definition("::Payment", "packs/payments/app/models/payment.rb"),
+            resolve("Payment", &["Billing::Refund"])
+        );
+
+        teardown();
+    }
+
     #[test]
     fn inflected_constant() {
         let app = "tests/fixtures/app_with_inflections";
@@ -496,6 +645,26 @@ mod tests {
         teardown();
     }
 
+    #[test]
+    fn inflected_constant_from_configuration() {
+        let app = "tests/fixtures/app_with_configured_inflections";
+        let absolute_root = get_absolute_root(app);
+        let resolver = get_zeitwerk_constant_resolver_for_fixture(app).unwrap();
+
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::MyModule::SomeAPIClass".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("app/services/my_module/some_api_class.rb")
+            }],
+            resolver
+                .resolve(&String::from("::MyModule::SomeAPIClass"), &[])
+                .unwrap()
+        );
+
+        teardown();
+    }
+
     #[test]
     fn test_file_map() {
         let absolute_root = &PathBuf::from("tests/fixtures/simple_app")
@@ -0,0 +1,150 @@
+// Rules from a `.pksignore` file in the project root, in gitignore syntax. Ignored
+// files are skipped like those matching `exclude:` in packwerk.yml, and
+// violations recorded in them are stale.
+use std::path::Path;
+
+use anyhow::Context;
+use globset::{GlobBuilder, GlobMatcher};
+
+pub const PKSIGNORE_FILE: &str = ".pksignore";
+
+#[derive(Debug, Clone)]
+struct Rule {
+    matcher: GlobMatcher,
+    // `!pattern` re-includes paths ignored by an earlier rule
+    negated: bool,
+    // `pattern/` only matches directories
+    directory_only: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PksIgnore {
+    rules: Vec<Rule>,
+}
+
+impl PksIgnore {
+    // An empty set of rules if there is no `.pksignore` file
+    pub fn from_root(absolute_root: &Path) -> anyhow::Result<Self> {
+        let path = absolute_root.join(PKSIGNORE_FILE);
+        if !path.exists() {
+            return Ok(PksIgnore::default());
+        }
+        let contents = std::fs::read_to_string(&path)
+            .context(format!("Failed to read {}", path.display()))?;
+        Self::parse(&contents).context(format!("Invalid {}", PKSIGNORE_FILE))
+    }
+
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let mut rules = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (negated, pattern) = match line.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+            let (directory_only, pattern) = match pattern.strip_suffix('/') {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern),
+            };
+            // Like gitignore, a pattern containing a slash is relative to the
+            // root, and any other pattern matches at any depth
+            let glob = if pattern.contains('/') {
+                pattern.trim_start_matches('/').to_owned()
+            } else {
+                format!("**/{}", pattern)
+            };
+
+            let matcher = GlobBuilder::new(&glob)
+                .literal_separator(true)
+                .build()
+                .context(format!("Invalid pattern `{}`", line))?
+                .compile_matcher();
+            rules.push(Rule {
+                matcher,
+                negated,
+                directory_only,
+            });
+        }
+        Ok(PksIgnore { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // Whether the path itself is ignored, regardless of its parent directories.
+    // The last matching rule wins.
+    pub fn is_ignored(&self, relative_path: &Path, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                (is_dir || !rule.directory_only)
+                    && rule.matcher.is_match(relative_path)
+            })
+            .is_some_and(|rule| !rule.negated)
+    }
+
+    // Whether the file, or any directory containing it, is ignored. As with
+    // gitignore, files in an ignored directory cannot be re-included.
+    pub fn is_ignored_file(&self, relative_file: &Path) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        relative_file
+            .ancestors()
+            .skip(1)
+            .filter(|directory| !directory.as_os_str().is_empty())
+            .any(|directory| self.is_ignored(directory, true))
+            || self.is_ignored(relative_file, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_ignored_file(pksignore: &PksIgnore, relative_file: &str) -> bool {
+        pksignore.is_ignored_file(Path::new(relative_file))
+    }
+
+    #[test]
+    fn test_unanchored_patterns_match_at_any_depth() {
+        let pksignore =
+            PksIgnore::parse("# comment\n\n*.generated.rb\n").unwrap();
+
+        assert!(is_ignored_file(&pksignore, "schema.generated.rb"));
+        assert!(is_ignored_file(
+            &pksignore,
+            "packs/foo/app/schema.generated.rb"
+        ));
+        assert!(!is_ignored_file(&pksignore, "packs/foo/app/schema.rb"));
+    }
+
+    #[test]
+    fn test_anchored_and_directory_patterns() {
+        let pksignore =
+            PksIgnore::parse("/legacy\npacks/*/fixtures/\n").unwrap();
+
+        assert!(is_ignored_file(&pksignore, "legacy/foo.rb"));
+        assert!(!is_ignored_file(&pksignore, "packs/foo/legacy/foo.rb"));
+        assert!(is_ignored_file(&pksignore, "packs/foo/fixtures/foo.rb"));
+        assert!(!pksignore.is_ignored(Path::new("packs/foo/fixtures"), false));
+    }
+
+    #[test]
+    fn test_negated_patterns() {
+        let pksignore =
+            PksIgnore::parse("*.rb\n!keep.rb\nvendored/\n!vendored/keep.rb\n")
+                .unwrap();
+
+        assert!(is_ignored_file(&pksignore, "packs/foo/app/foo.rb"));
+        assert!(!is_ignored_file(&pksignore, "packs/foo/app/keep.rb"));
+        // Files in an ignored directory cannot be re-included
+        assert!(is_ignored_file(&pksignore, "vendored/keep.rb"));
+    }
+}
@@ -0,0 +1,192 @@
+// Reports progress through a stage of a long run (e.g. parsing files) to stderr,
+// either as a single updating line for humans, or as a stream of JSON objects
+// (one per line) for UIs that wrap pks.
+use std::fmt;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Instant;
+
+use serde::Serialize;
+
+use super::cli::ProgressFormat;
+use super::Configuration;
+
+const REPORT_INTERVAL_MILLIS: u64 = 100;
+
+pub(crate) struct Progress {
+    format: Option<ProgressFormat>,
+    stage: &'static str,
+    total: usize,
+    completed: AtomicUsize,
+    started: Instant,
+    last_report_millis: AtomicU64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct ProgressEvent<'a> {
+    stage: &'a str,
+    completed: usize,
+    total: usize,
+    per_second: f64,
+    eta_seconds: Option<f64>,
+    done: bool,
+}
+
+impl<'a> ProgressEvent<'a> {
+    fn build(
+        stage: &'a str,
+        completed: usize,
+        total: usize,
+        elapsed_seconds: f64,
+        done: bool,
+    ) -> Self {
+        let per_second = if elapsed_seconds > 0.0 {
+            completed as f64 / elapsed_seconds
+        } else {
+            0.0
+        };
+        let eta_seconds = if done {
+            Some(0.0)
+        } else if per_second > 0.0 {
+            Some(total.saturating_sub(completed) as f64 / per_second)
+        } else {
+            None
+        };
+        ProgressEvent {
+            stage,
+            completed,
+            total,
+            per_second,
+            eta_seconds,
+            done,
+        }
+    }
+}
+
+impl fmt::Display for ProgressEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}/{} ({:.0}/s",
+            self.stage, self.completed, self.total, self.per_second
+        )?;
+        match self.eta_seconds {
+            Some(eta_seconds) if !self.done => {
+                write!(f, ", ETA {:.0}s)", eta_seconds.ceil())
+            }
+            _ => write!(f, ")"),
+        }
+    }
+}
+
+impl Progress {
+    pub fn new(
+        configuration: &Configuration,
+        stage: &'static str,
+        total: usize,
+    ) -> Self {
+        Progress {
+            format: configuration.progress_format,
+            stage,
+            total,
+            completed: AtomicUsize::new(0),
+            started: Instant::now(),
+            last_report_millis: AtomicU64::new(0),
+        }
+    }
+
+    // Safe to call from many threads. Reports are throttled, so this is cheap.
+    pub fn tick(&self) {
+        self.tick_by(1);
+    }
+
+    pub fn tick_by(&self, count: usize) {
+        let completed =
+            self.completed.fetch_add(count, Ordering::Relaxed) + count;
+        if self.format.is_none() {
+            return;
+        }
+
+        let elapsed_millis = self.started.elapsed().as_millis() as u64;
+        let last_report_millis =
+            self.last_report_millis.load(Ordering::Relaxed);
+        if elapsed_millis >= last_report_millis + REPORT_INTERVAL_MILLIS
+            && self
+                .last_report_millis
+                .compare_exchange(
+                    last_report_millis,
+                    elapsed_millis,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+        {
+            self.report(completed, false);
+        }
+    }
+
+    pub fn finish(&self) {
+        if self.format.is_some() {
+            self.report(self.completed.load(Ordering::Relaxed), true);
+        }
+    }
+
+    fn report(&self, completed: usize, done: bool) {
+        let event = ProgressEvent::build(
+            self.stage,
+            completed,
+            self.total,
+            self.started.elapsed().as_secs_f64(),
+            done,
+        );
+
+        // Progress is best effort, so failures to write it are ignored
+        let mut stderr = std::io::stderr().lock();
+        let _ = match self.format {
+            Some(ProgressFormat::Human) if done => {
+                writeln!(stderr, "\r{}", event)
+            }
+            Some(ProgressFormat::Human) => write!(stderr, "\r{}", event),
+            Some(ProgressFormat::Json) => match serde_json::to_string(&event) {
+                Ok(json) => writeln!(stderr, "{}", json),
+                Err(_) => Ok(()),
+            },
+            None => Ok(()),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_progress_event_in_progress() {
+        let event = ProgressEvent::build("parsing", 250, 1000, 0.5, false);
+
+        assert_eq!(event.per_second, 500.0);
+        assert_eq!(event.eta_seconds, Some(1.5));
+        assert_eq!(format!("{}", event), "parsing: 250/1000 (500/s, ETA 2s)");
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"stage":"parsing","completed":250,"total":1000,"per_second":500.0,"eta_seconds":1.5,"done":false}"#
+        );
+    }
+
+    #[test]
+    fn test_progress_event_done() {
+        let event = ProgressEvent::build("checking", 1000, 1000, 2.0, true);
+
+        assert_eq!(event.eta_seconds, Some(0.0));
+        assert_eq!(format!("{}", event), "checking: 1000/1000 (500/s)");
+    }
+
+    #[test]
+    fn test_progress_event_without_elapsed_time() {
+        let event = ProgressEvent::build("parsing", 0, 1000, 0.0, false);
+
+        assert_eq!(event.eta_seconds, None);
+        assert_eq!(format!("{}", event), "parsing: 0/1000 (0/s)");
+    }
+}
@@ -0,0 +1,349 @@
+// The exported surface of a pack: the classes, modules, and constants defined
+// in its public paths or in files with a `pack_public` sigil, with their
+// public methods.
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Serialize;
+
+use super::pack::Pack;
+use super::parsing::ruby::api_collector::{extract_public_api, ConstantKind};
+use super::parsing::ruby::parse_utils::{
+    extract_sigils_from_contents, public_sigil_value,
+};
+use super::parsing::ExtractionHandler;
+use super::reference_extractor::get_all_references_and_sigils;
+use super::{get_constant_resolver_for_all_files, Configuration};
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct PublicApi {
+    pub pack: String,
+    pub public_paths: Vec<PathBuf>,
+    pub constants: Vec<PublicConstant>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct PublicConstant {
+    pub name: String,
+    pub kind: ConstantKind,
+    pub file: PathBuf,
+    // Instance methods are prefixed with `#`, singleton methods with `.`
+    pub methods: Vec<String>,
+}
+
+pub fn find_public_api(
+    configuration: &Configuration,
+    pack_name: &str,
+) -> anyhow::Result<PublicApi> {
+    let pack = configuration.pack_set.for_pack(pack_name)?;
+
+    let mut constants = Vec::new();
+    for (file, contents) in public_files(configuration, pack)? {
+        constants.extend(public_constants(&file, &contents));
+    }
+    constants.sort_by(|a, b| a.name.cmp(&b.name).then(a.file.cmp(&b.file)));
+
+    Ok(PublicApi {
+        pack: pack.name.clone(),
+        public_paths: pack.public_paths(),
+        constants,
+    })
+}
+
+// The public constants of a pack that reference a constant, e.g. one
+// referenced privately, or a constant nested within it
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct PublicEntrypoints {
+    pub constant: String,
+    pub pack: String,
+    // Whether the constant is defined in a public file itself
+    pub is_public: bool,
+    pub public_paths: Vec<PathBuf>,
+    pub entrypoints: Vec<PublicEntrypoint>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct PublicEntrypoint {
+    pub name: String,
+    pub kind: ConstantKind,
+    pub file: PathBuf,
+    // The lines of the file referencing the constant
+    pub lines: Vec<usize>,
+}
+
+pub fn find_public_entrypoints(
+    configuration: &Configuration,
+    constant_name: &str,
+) -> anyhow::Result<PublicEntrypoints> {
+    let constant = if constant_name.starts_with("::") {
+        constant_name.to_owned()
+    } else {
+        format!("::{}", constant_name)
+    };
+    let constant_resolver = get_constant_resolver_for_all_files(configuration)?;
+    let defining_file = constant_resolver
+        .fully_qualified_constant_name_to_constant_definition_map()
+        .get(&constant)
+        .and_then(|definitions| definitions.first())
+        .map(|definition| definition.absolute_path_of_definition.clone())
+        .context(format!("`{}` is not defined", constant))?;
+    let pack =
+        configuration
+            .pack_set
+            .for_file(&defining_file)?
+            .context(format!(
+                "{} does not belong to any pack",
+                defining_file.display()
+            ))?;
+
+    let public_files = public_files(configuration, pack)?;
+    let absolute_paths: HashSet<PathBuf> = public_files
+        .iter()
+        .map(|(file, _)| configuration.absolute_root.join(file))
+        .collect();
+    let is_public = absolute_paths.contains(&defining_file);
+
+    let nested_prefix = format!("{}::", constant);
+    let mut lines_by_file: BTreeMap<PathBuf, BTreeSet<usize>> = BTreeMap::new();
+    let (references, _, _) =
+        get_all_references_and_sigils(configuration, &absolute_paths)?;
+    for reference in references {
+        let name: &str = &reference.constant_name;
+        if name != constant && !name.starts_with(&nested_prefix) {
+            continue;
+        }
+        lines_by_file
+            .entry(PathBuf::from(&*reference.relative_referencing_file))
+            .or_default()
+            .insert(reference.source_location.line);
+    }
+
+    let mut entrypoints = Vec::new();
+    for (file, contents) in &public_files {
+        let Some(lines) = lines_by_file.get(file) else {
+            continue;
+        };
+        entrypoints.extend(public_constants(file, contents).into_iter().map(
+            |public_constant| PublicEntrypoint {
+                name: public_constant.name,
+                kind: public_constant.kind,
+                file: public_constant.file,
+                lines: lines.iter().copied().collect(),
+            },
+        ));
+    }
+    entrypoints.retain(|entrypoint| entrypoint.name != constant);
+    entrypoints.sort_by(|a, b| a.name.cmp(&b.name).then(a.file.cmp(&b.file)));
+
+    Ok(PublicEntrypoints {
+        constant,
+        pack: pack.name.clone(),
+        is_public,
+        public_paths: pack.public_paths(),
+        entrypoints,
+    })
+}
+
+// The Ruby files of the pack that are public, relative to the project root,
+// with their contents
+fn public_files(
+    configuration: &Configuration,
+    pack: &Pack,
+) -> anyhow::Result<Vec<(PathBuf, String)>> {
+    let mut public_files: Vec<(PathBuf, String)> = Vec::new();
+    for absolute_path in &configuration.included_files {
+        if configuration.extraction_handlers.for_path(absolute_path)
+            != Some(&ExtractionHandler::Ruby)
+        {
+            continue;
+        }
+        let Ok(relative_path) =
+            absolute_path.strip_prefix(&configuration.absolute_root)
+        else {
+            continue;
+        };
+        let owned_by_pack = configuration
+            .pack_set
+            .for_file(absolute_path)?
+            .is_some_and(|owner| owner.name == pack.name);
+        if !owned_by_pack {
+            continue;
+        }
+        let contents = std::fs::read_to_string(absolute_path)
+            .context(format!("Failed to read {}", absolute_path.display()))?;
+        // A `pack_public` or `pack_private` sigil overrides the public paths
+        let is_public =
+            public_sigil_value(&extract_sigils_from_contents(&contents))
+                .unwrap_or_else(|| {
+                    configuration
+                        .pack_set
+                        .is_public_file(pack, &relative_path.to_string_lossy())
+                });
+        if is_public {
+            public_files.push((relative_path.to_path_buf(), contents));
+        }
+    }
+    public_files.sort();
+    Ok(public_files)
+}
+
+fn public_constants(file: &Path, contents: &str) -> Vec<PublicConstant> {
+    extract_public_api(contents)
+        .into_iter()
+        .map(|constant| PublicConstant {
+            name: constant.name,
+            kind: constant.kind,
+            file: file.to_path_buf(),
+            methods: constant.methods,
+        })
+        .collect()
+}
+
+impl fmt::Display for ConstantKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            ConstantKind::Class => "class",
+            ConstantKind::Module => "module",
+            ConstantKind::Constant => "constant",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+impl fmt::Display for PublicApi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let public_paths: Vec<String> = self
+            .public_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        write!(
+            f,
+            "{} exports {} constant(s) from {}",
+            self.pack,
+            self.constants.len(),
+            public_paths.join(", ")
+        )?;
+        for constant in &self.constants {
+            write!(
+                f,
+                "\n{} ({}, {})",
+                constant.name,
+                constant.kind,
+                constant.file.display()
+            )?;
+            for method in &constant.methods {
+                write!(f, "\n  {}", method)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PublicEntrypoints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_public {
+            write!(f, "{} is public in {}", self.constant, self.pack)?;
+        } else {
+            write!(f, "{} is private to {}", self.constant, self.pack)?;
+        }
+        if self.entrypoints.is_empty() {
+            let public_paths: Vec<String> = self
+                .public_paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            return write!(
+                f,
+                ", and no public constant references it. Consider adding one to {}",
+                public_paths.join(", ")
+            );
+        }
+        write!(
+            f,
+            ". {} public constant(s) reference it:",
+            self.entrypoints.len()
+        )?;
+        for entrypoint in &self.entrypoints {
+            let lines: Vec<String> =
+                entrypoint.lines.iter().map(usize::to_string).collect();
+            write!(
+                f,
+                "\n{} ({}, {}:{})",
+                entrypoint.name,
+                entrypoint.kind,
+                entrypoint.file.display(),
+                lines.join(",")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::configuration;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_find_public_api() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/app_with_public_api")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+
+        let public_api = find_public_api(&configuration, "packs/foo").unwrap();
+
+        assert_eq!(
+            vec![PublicConstant {
+                name: String::from("::Foo::Api"),
+                kind: ConstantKind::Class,
+                file: PathBuf::from("packs/foo/app/public/foo/api.rb"),
+                methods: vec![String::from("#call"), String::from(".build")],
+            }],
+            public_api.constants
+        );
+        assert_eq!(
+            vec![PathBuf::from("packs/foo/app/public")],
+            public_api.public_paths
+        );
+    }
+
+    #[test]
+    fn test_find_public_entrypoints() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/app_with_public_api")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+
+        let public_entrypoints =
+            find_public_entrypoints(&configuration, "Foo::Internal").unwrap();
+
+        assert_eq!(
+            PublicEntrypoints {
+                constant: String::from("::Foo::Internal"),
+                pack: String::from("packs/foo"),
+                is_public: false,
+                public_paths: vec![PathBuf::from("packs/foo/app/public")],
+                entrypoints: vec![PublicEntrypoint {
+                    name: String::from("::Foo::Api"),
+                    kind: ConstantKind::Class,
+                    file: PathBuf::from("packs/foo/app/public/foo/api.rb"),
+                    lines: vec![8],
+                }],
+            },
+            public_entrypoints
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt,
     fs::File,
     path::{Path, PathBuf},
@@ -10,6 +10,11 @@ use serde::{
     Deserialize, Deserializer, Serialize,
 };
 
+use super::cli::CheckOutputFormat;
+use super::package_todo::PackageTodoFormat;
+use super::parsing::RawExtractionHandler;
+use super::violation_store::ViolationStoreKind;
+
 const CONFIG_FILE_NAME: &str = "packwerk.yml";
 const PACKS_FIRST_CONFIG_FILE_NAME: &str = "packs.yml";
 
@@ -36,6 +41,19 @@ pub(crate) struct RawConfiguration {
     #[serde(default = "default_custom_associations")]
     pub custom_associations: Vec<String>,
 
+    // Track constants named by string literals passed to `constantize`,
+    // `safe_constantize`, `Object.const_get`, and `constantize_methods`
+    #[serde(default)]
+    pub constantize_references: bool,
+
+    // Additional methods that resolve a string literal to a constant
+    #[serde(default)]
+    pub constantize_methods: Vec<String>,
+
+    // Patterns for test files, which may reference the `test_dependencies` of their pack
+    #[serde(default = "default_test_paths")]
+    pub test_paths: Vec<String>,
+
     // Whether or not you want the cache enabled
     #[serde(default = "default_cache")]
     pub cache: bool,
@@ -52,10 +70,61 @@ pub(crate) struct RawConfiguration {
     #[serde(default)]
     pub layers: Vec<String>,
 
+    // Layer name -> globs of the pack names in the layer, for packs whose
+    // package.yml doesn't set `layer`
+    #[serde(default)]
+    pub layer_globs: BTreeMap<String, Vec<String>>,
+
+    // Pack pairs that are permanently permitted to violate layering
+    #[serde(default)]
+    pub architecture_exceptions: Vec<ArchitectureException>,
+
+    // Rules on the `tags` of packs, e.g. that packs tagged `pci` cannot
+    // depend on packs tagged `experimental`
+    #[serde(default)]
+    pub tag_policies: Vec<TagPolicy>,
+
+    // Ruby files and gems packwerk loads, e.g. `packwerk-extensions`, which
+    // determine the checkers packwerk runs
+    #[serde(default)]
+    pub require: Vec<String>,
+
     // Experimental parser
     #[serde(default)]
     pub experimental_parser: bool,
 
+    // File extension -> the extractor of its references, `ruby`, `erb`, or a
+    // `command` printing the Ruby code of a file, e.g. for templates
+    #[serde(default)]
+    pub extraction_handlers: BTreeMap<String, RawExtractionHandler>,
+
+    // Secondary sources of constant definitions, for constants the autoload
+    // paths can't resolve, e.g. those defined through metaprogramming
+    #[serde(default)]
+    pub resolution_fallbacks: Vec<ResolutionFallback>,
+
+    // The days recorded violations are treated as recorded rather than strict
+    // once the checker is strict, which package.yml files can override
+    #[serde(default)]
+    pub strict_mode_grace_period_days: Option<u64>,
+
+    // Whether `check` lists the references it couldn't attribute to a pack
+    #[serde(default)]
+    pub report_unresolved_constants: bool,
+
+    // Whether `validate` reports `require` and `require_relative` statements
+    // loading private files of other packs
+    #[serde(default)]
+    pub check_requires: bool,
+
+    // The lines of code around each violation in JSON output and reports
+    #[serde(default)]
+    pub snippet_lines: usize,
+
+    // Webhooks notified of new violations by `check --notify`
+    #[serde(default)]
+    pub notifications: Vec<Notification>,
+
     // Ignored monkey patches
     #[serde(default)]
     pub ignored_definitions: HashMap<String, HashSet<PathBuf>>,
@@ -64,13 +133,211 @@ pub(crate) struct RawConfiguration {
     #[serde(default)]
     pub autoload_roots: HashMap<PathBuf, String>,
 
+    // Globs of directories, relative to the project root, whose files are in
+    // the namespace of their parent directory, like Zeitwerk's `collapse`
+    #[serde(default)]
+    pub collapse_dirs: Vec<String>,
+
     // Relative path to inflections file
     #[serde(default)]
     pub inflections_path: Option<PathBuf>,
 
+    // Inflections declared directly in configuration, merged with the inflections file
+    #[serde(default)]
+    pub inflections: RawInflections,
+
     // Use packs copy
     #[serde(default)]
     pub packs_first_mode: bool,
+
+    // Analysis roots of a monorepo, e.g. one per Rails app
+    #[serde(default)]
+    pub roots: Vec<RawRoot>,
+
+    // Product group name -> globs of the pack names in the group, for roll-up reporting
+    #[serde(default)]
+    pub product_groups: BTreeMap<String, Vec<String>>,
+
+    // Visibility group name -> globs of the pack names in the group, which
+    // `visible_to` lists can name instead of listing every pack
+    #[serde(default)]
+    pub visibility_groups: BTreeMap<String, Vec<String>>,
+
+    // The branch `check --changed` compares against when CI doesn't name the
+    // target branch, e.g. `master`. Defaults to the branch of origin/HEAD
+    #[serde(default)]
+    pub default_branch: Option<String>,
+
+    // Template of links to the code of violations, with `{file}`, `{line}` and
+    // `{commit}`, e.g. `https://github.com/acme/app/blob/{commit}/{file}#L{line}`
+    #[serde(default)]
+    pub code_url: Option<String>,
+
+    // Format of package_todo.yml files written by `update`
+    #[serde(default)]
+    pub package_todo_format: PackageTodoFormat,
+
+    // Where package_todo.yml files are, relative to the project root, with
+    // `{pack}` standing for the pack name, e.g. `.pks/todos/{pack}.yml`.
+    // Defaults to the pack directories
+    #[serde(default)]
+    pub package_todo_path: Option<String>,
+
+    // Where `update` records violations: `yml` (package_todo.yml files),
+    // `json`, or `sqlite`
+    #[serde(default)]
+    pub violation_store: ViolationStoreKind,
+
+    // The file of the `json` or `sqlite` store, relative to the project root.
+    // Defaults to `package_todo.json` or `package_todo.sqlite3`
+    #[serde(default)]
+    pub violation_store_path: Option<PathBuf>,
+
+    // Size of the thread pool, which defaults to the number of CPUs
+    #[serde(default)]
+    pub threads: Option<usize>,
+
+    // Approximate memory budget for parsing files, e.g. `2G`
+    #[serde(default)]
+    pub max_memory: Option<String>,
+
+    // Named sets of overrides, selected with `--profile`, e.g. `ci`
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+// Settings a profile overrides, which are left as configured when unset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Profile {
+    #[serde(default)]
+    pub cache: Option<bool>,
+
+    #[serde(default)]
+    pub cache_directory: Option<String>,
+
+    // Checkers disabled in every pack, e.g. `privacy`
+    #[serde(default)]
+    pub disabled_checkers: Vec<String>,
+
+    // Whether every violation is strict, so recorded violations fail `check`
+    #[serde(default)]
+    pub strict: Option<bool>,
+
+    #[serde(default)]
+    pub strict_parsing: Option<bool>,
+
+    // The default `--output-format` of `check`
+    #[serde(default)]
+    pub output_format: Option<CheckOutputFormat>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct RawInflections {
+    // Acronyms, e.g. `API`, so `some_api_client.rb` maps to `SomeAPIClient`
+    #[serde(default)]
+    pub acronyms: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct RawRoot {
+    // Relative path to the root, e.g. `apps/admin`
+    pub path: String,
+
+    // Whether the root's constants are visible from every other root, e.g. `components`
+    #[serde(default)]
+    pub shared: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ArchitectureException {
+    // The referencing pack
+    pub from: String,
+
+    // The defining pack
+    pub to: String,
+
+    // Why the exception is intentional (required)
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TagPolicy {
+    // The tag of the referencing packs
+    pub from: String,
+
+    // The tag of the packs they cannot depend on
+    pub cannot_depend_on: String,
+
+    // Why the policy exists, which violations mention
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResolutionFallbackType {
+    // Sorbet RBI files
+    Rbi,
+    // A tags file generated by ctags
+    Ctags,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ResolutionFallback {
+    #[serde(rename = "type")]
+    pub fallback_type: ResolutionFallbackType,
+
+    // A glob of RBI files or the path to a tags file, relative to the root.
+    // Defaults to `sorbet/rbi/**/*.rbi` and `tags` respectively.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+impl ResolutionFallback {
+    pub fn path(&self) -> &str {
+        match (&self.path, self.fallback_type) {
+            (Some(path), _) => path,
+            (None, ResolutionFallbackType::Rbi) => "sorbet/rbi/**/*.rbi",
+            (None, ResolutionFallbackType::Ctags) => "tags",
+        }
+    }
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationFormat {
+    // A JSON summary of the violations
+    #[default]
+    Json,
+    // A Slack-compatible `{"text": ...}` message
+    Slack,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Notification {
+    // The webhook URL
+    #[serde(default)]
+    pub url: Option<String>,
+
+    // The environment variable holding the webhook URL, to keep it out of
+    // packwerk.yml
+    #[serde(default)]
+    pub url_env: Option<String>,
+
+    #[serde(default)]
+    pub format: NotificationFormat,
+
+    // The text of Slack messages, with `{team}`, `{count}`, and `{violations}`
+    // placeholders
+    #[serde(default)]
+    pub text: Option<String>,
+
+    // Only notify about violations from packs owned by these teams, or about
+    // every violation when empty
+    #[serde(default)]
+    pub teams: Vec<String>,
 }
 
 pub(crate) fn get(absolute_root: &Path) -> anyhow::Result<RawConfiguration> {
@@ -144,6 +411,10 @@ fn default_package_paths() -> Vec<String> {
     vec![String::from("**/*")]
 }
 
+fn default_test_paths() -> Vec<String> {
+    vec![String::from("**/spec/**/*"), String::from("**/test/**/*")]
+}
+
 fn default_custom_associations() -> Vec<String> {
     vec![]
 }
@@ -210,4 +481,193 @@ mod tests {
 
         assert_eq!(raw_configuration.package_paths, vec!["**/*"]);
     }
+
+    #[test]
+    fn test_deserialize_roots() {
+        let raw_configuration_string = String::from(
+            "roots:\n  - path: apps/admin\n  - path: components\n    shared: true",
+        );
+        let raw_configuration =
+            serde_yaml::from_str::<RawConfiguration>(&raw_configuration_string)
+                .expect("Could not deserialize roots");
+
+        assert_eq!(
+            raw_configuration.roots,
+            vec![
+                RawRoot {
+                    path: String::from("apps/admin"),
+                    shared: false,
+                },
+                RawRoot {
+                    path: String::from("components"),
+                    shared: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_constantize_references() {
+        let raw_configuration = RawConfiguration::default();
+        assert!(!raw_configuration.constantize_references);
+        assert!(raw_configuration.constantize_methods.is_empty());
+
+        let raw_configuration = serde_yaml::from_str::<RawConfiguration>(
+            "constantize_references: true\nconstantize_methods:\n  - resolve_class",
+        )
+        .expect("Could not deserialize constantize_references");
+
+        assert!(raw_configuration.constantize_references);
+        assert_eq!(
+            raw_configuration.constantize_methods,
+            vec![String::from("resolve_class")]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_product_groups() {
+        let raw_configuration = serde_yaml::from_str::<RawConfiguration>(
+            "product_groups:\n  payments:\n    - packs/billing\n    - packs/payments/*",
+        )
+        .expect("Could not deserialize product_groups");
+
+        assert_eq!(
+            raw_configuration.product_groups,
+            BTreeMap::from([(
+                String::from("payments"),
+                vec![
+                    String::from("packs/billing"),
+                    String::from("packs/payments/*")
+                ]
+            )])
+        );
+    }
+
+    #[test]
+    fn test_deserialize_package_todo_format() {
+        assert_eq!(
+            RawConfiguration::default().package_todo_format,
+            PackageTodoFormat::V1
+        );
+
+        let raw_configuration =
+            serde_yaml::from_str::<RawConfiguration>("package_todo_format: v2")
+                .expect("Could not deserialize package_todo_format");
+
+        assert_eq!(
+            raw_configuration.package_todo_format,
+            PackageTodoFormat::V2
+        );
+    }
+
+    #[test]
+    fn test_deserialize_violation_store() {
+        assert_eq!(
+            RawConfiguration::default().violation_store,
+            ViolationStoreKind::Yml
+        );
+
+        let raw_configuration = serde_yaml::from_str::<RawConfiguration>(
+            "violation_store: json\nviolation_store_path: tmp/todos.json",
+        )
+        .expect("Could not deserialize violation_store");
+
+        assert_eq!(raw_configuration.violation_store, ViolationStoreKind::Json);
+        assert_eq!(
+            raw_configuration.violation_store_path,
+            Some(PathBuf::from("tmp/todos.json"))
+        );
+    }
+
+    #[test]
+    fn test_deserialize_architecture_exceptions() {
+        let raw_configuration_string = String::from(
+            "architecture_exceptions:\n  - from: packs/admin\n    to: packs/billing\n    reason: Admin tooling",
+        );
+        let raw_configuration =
+            serde_yaml::from_str::<RawConfiguration>(&raw_configuration_string)
+                .expect("Could not deserialize architecture_exceptions");
+
+        assert_eq!(
+            raw_configuration.architecture_exceptions,
+            vec![ArchitectureException {
+                from: String::from("packs/admin"),
+                to: String::from("packs/billing"),
+                reason: String::from("Admin tooling"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_resolution_fallbacks() {
+        let raw_configuration_string = String::from(
+            "resolution_fallbacks:\n  - type: rbi\n  - type: ctags\n    path: .tags",
+        );
+        let raw_configuration =
+            serde_yaml::from_str::<RawConfiguration>(&raw_configuration_string)
+                .expect("Could not deserialize resolution_fallbacks");
+
+        let paths: Vec<&str> = raw_configuration
+            .resolution_fallbacks
+            .iter()
+            .map(ResolutionFallback::path)
+            .collect();
+        assert_eq!(paths, vec!["sorbet/rbi/**/*.rbi", ".tags"]);
+        assert_eq!(
+            raw_configuration.resolution_fallbacks[1].fallback_type,
+            ResolutionFallbackType::Ctags
+        );
+    }
+
+    #[test]
+    fn test_deserialize_notifications() {
+        let raw_configuration_string = String::from(
+            "notifications:\n  - url_env: SLACK_WEBHOOK_URL\n    format: slack\n    teams:\n      - payments\n  - url: https://example.com/hook",
+        );
+        let raw_configuration =
+            serde_yaml::from_str::<RawConfiguration>(&raw_configuration_string)
+                .expect("Could not deserialize notifications");
+
+        assert_eq!(
+            raw_configuration.notifications,
+            vec![
+                Notification {
+                    url: None,
+                    url_env: Some(String::from("SLACK_WEBHOOK_URL")),
+                    format: NotificationFormat::Slack,
+                    text: None,
+                    teams: vec![String::from("payments")],
+                },
+                Notification {
+                    url: Some(String::from("https://example.com/hook")),
+                    url_env: None,
+                    format: NotificationFormat::Json,
+                    text: None,
+                    teams: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_architecture_exception_requires_reason() {
+        let raw_configuration_string = String::from(
+            "architecture_exceptions:\n  - from: packs/admin\n    to: packs/billing",
+        );
+        let raw_configuration =
+            serde_yaml::from_str::<RawConfiguration>(&raw_configuration_string);
+
+        assert!(raw_configuration.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_inflection_acronyms() {
+        let raw_configuration_string =
+            String::from("inflections:\n  acronyms:\n    - API\n    - CSV");
+        let raw_configuration =
+            serde_yaml::from_str::<RawConfiguration>(&raw_configuration_string)
+                .expect("Could not deserialize inflections");
+
+        assert_eq!(raw_configuration.inflections.acronyms, vec!["API", "CSV"]);
+    }
 }
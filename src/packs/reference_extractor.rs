@@ -1,45 +1,252 @@
 use std::{
     collections::{HashMap, HashSet},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
-use tracing::debug;
+use tracing::{debug, field, info_span};
 
 use crate::packs::{
+    constant_resolver::{
+        ConstantDefinition, ConstantResolverConfiguration,
+        SharedConstantResolver, WarmConstantResolvers,
+    },
     get_experimental_constant_resolver, get_zeitwerk_constant_resolver,
-    process_files_with_cache, ProcessedFile,
+    interner::Interner,
+    pack::Pack,
+    parsing::ruby::zeitwerk::get_zeitwerk_constant_resolver_for_packs,
+    process_files_with_cache,
+    resolution_fallback::{load_fallback_definitions, with_fallback},
+    ProcessedFile,
 };
 
-use super::{checker::reference::Reference, Configuration, Sigil};
+use super::{checker::reference::Reference, Configuration, Sigil, SyntaxError};
+
+// Keyed by app root (see `Configuration::app_root_for_path`), where `None`
+// covers shared code, and every file when no roots are configured
+type ConstantResolversByAppRoot =
+    HashMap<Option<PathBuf>, SharedConstantResolver>;
+
+fn app_root_of(
+    configuration: &Configuration,
+    absolute_path: &Path,
+) -> Option<PathBuf> {
+    configuration
+        .app_root_for_path(absolute_path)
+        .map(|root| root.path.clone())
+}
+
+// The distinct app roots of the files, so that only the constant resolvers
+// needed to resolve their references are built
+fn app_roots(
+    configuration: &Configuration,
+    absolute_paths: &HashSet<PathBuf>,
+) -> HashSet<Option<PathBuf>> {
+    absolute_paths
+        .iter()
+        .map(|absolute_path| app_root_of(configuration, absolute_path))
+        .collect()
+}
+
+// Constants defined in an app root are not visible from other app roots
+fn is_visible_from(
+    configuration: &Configuration,
+    absolute_path_of_definition: &Path,
+    app_root: &Option<PathBuf>,
+) -> bool {
+    match app_root_of(configuration, absolute_path_of_definition) {
+        Some(definition_app_root) => {
+            app_root.as_ref() == Some(&definition_app_root)
+        }
+        None => true,
+    }
+}
+
+// The Zeitwerk constant resolver for the files of an app root, which only
+// resolves the constants visible from it. Warm resolvers are shared rather
+// than built again.
+fn zeitwerk_constant_resolver(
+    configuration: &Configuration,
+    app_root: &Option<PathBuf>,
+    fallback_definitions: &[ConstantDefinition],
+) -> SharedConstantResolver {
+    if let Some(constant_resolver) = configuration
+        .constant_resolvers
+        .as_ref()
+        .and_then(|warm| warm.get(app_root))
+    {
+        return constant_resolver;
+    }
+    let visible_packs: Vec<&Pack> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter(|pack| is_visible_from(configuration, &pack.yml, app_root))
+        .collect();
+    // Each app root caches its own constant definitions
+    let cache_directory = match app_root {
+        Some(path) => configuration.cache_directory.join("roots").join(path),
+        None => configuration.cache_directory.clone(),
+    };
+    let constant_resolver = get_zeitwerk_constant_resolver_for_packs(
+        &visible_packs,
+        &ConstantResolverConfiguration {
+            cache_directory: &cache_directory,
+            ..configuration.constant_resolver_configuration()
+        },
+    );
+    Arc::from(with_fallback(constant_resolver, fallback_definitions))
+}
+
+// The Zeitwerk constant resolvers of every app root, and of shared code
+pub(crate) fn build_warm_constant_resolvers(
+    configuration: &Configuration,
+) -> anyhow::Result<WarmConstantResolvers> {
+    let fallback_definitions = load_fallback_definitions(configuration)?;
+    let app_roots = std::iter::once(None).chain(
+        configuration
+            .roots
+            .iter()
+            .map(|root| Some(root.path.clone())),
+    );
+    Ok(WarmConstantResolvers {
+        by_app_root: app_roots
+            .map(|app_root| {
+                let constant_resolver = zeitwerk_constant_resolver(
+                    configuration,
+                    &app_root,
+                    &fallback_definitions,
+                );
+                (app_root, constant_resolver)
+            })
+            .collect(),
+    })
+}
+
+// The Zeitwerk constant resolver of every pack, which is the warm one of
+// shared code when no app roots are configured
+pub(crate) fn zeitwerk_constant_resolver_for_all_files(
+    configuration: &Configuration,
+) -> SharedConstantResolver {
+    if configuration.roots.is_empty() {
+        if let Some(constant_resolver) = configuration
+            .constant_resolvers
+            .as_ref()
+            .and_then(|warm| warm.get(&None))
+        {
+            return constant_resolver;
+        }
+    }
+    Arc::from(get_zeitwerk_constant_resolver(
+        &configuration.pack_set,
+        &configuration.constant_resolver_configuration(),
+    ))
+}
 
 // It might be nice to have this return a simpler type rather than the tuple
 // This method returns everything we need as input into packwerk checking
-// (references and sigils). Not sure on naming yet.
+// (references, sigils, and the syntax errors of files that did not parse).
+// Not sure on naming yet.
 #[allow(clippy::type_complexity)]
 pub(crate) fn get_all_references_and_sigils(
     configuration: &Configuration,
     absolute_paths: &HashSet<PathBuf>,
-) -> anyhow::Result<(Vec<Reference>, HashMap<PathBuf, Vec<Sigil>>)> {
-    let cache = configuration.get_cache();
+) -> anyhow::Result<(
+    Vec<Reference>,
+    HashMap<PathBuf, Vec<Sigil>>,
+    HashMap<PathBuf, Vec<SyntaxError>>,
+)> {
+    let processed_files =
+        process_files_for_references(configuration, absolute_paths)?;
+    resolve_references(configuration, absolute_paths, processed_files)
+}
 
+// The processed files needed to resolve the references of `absolute_paths`:
+// those files, or every included file for the experimental parser, which gets
+// constant definitions from them
+pub(crate) fn process_files_for_references(
+    configuration: &Configuration,
+    absolute_paths: &HashSet<PathBuf>,
+) -> anyhow::Result<Vec<ProcessedFile>> {
     debug!("Getting unresolved references (using cache if possible)");
-
-    let (constant_resolver, processed_files_to_check) = if configuration
-        .experimental_parser
-    {
-        // The experimental parser needs *all* processed files to get definitions
-        let all_processed_files: Vec<ProcessedFile> = process_files_with_cache(
+    let cache = configuration.get_cache();
+    if configuration.experimental_parser {
+        process_files_with_cache(
             &configuration.included_files,
             cache,
             configuration,
-        )?;
+        )
+    } else {
+        process_files_with_cache(absolute_paths, cache, configuration)
+    }
+}
 
-        let constant_resolver = get_experimental_constant_resolver(
-            &configuration.absolute_root,
-            &all_processed_files,
-            &configuration.ignored_definitions,
-        );
+// Resolves the references of `absolute_paths` from the files processed by
+// `process_files_for_references`
+#[allow(clippy::type_complexity)]
+pub(crate) fn resolve_references(
+    configuration: &Configuration,
+    absolute_paths: &HashSet<PathBuf>,
+    processed_files: Vec<ProcessedFile>,
+) -> anyhow::Result<(
+    Vec<Reference>,
+    HashMap<PathBuf, Vec<Sigil>>,
+    HashMap<PathBuf, Vec<SyntaxError>>,
+)> {
+    let span = info_span!(
+        "resolve",
+        files = absolute_paths.len(),
+        references = field::Empty
+    )
+    .entered();
+    // Warm constant resolvers already resolve from the fallbacks
+    let fallback_definitions = match &configuration.constant_resolvers {
+        Some(_) if !configuration.experimental_parser => Vec::new(),
+        _ => load_fallback_definitions(configuration)?,
+    };
+
+    let (constant_resolvers, processed_files_to_check): (
+        ConstantResolversByAppRoot,
+        Vec<ProcessedFile>,
+    ) = if configuration.experimental_parser {
+        // The experimental parser needs *all* processed files to get definitions
+        let all_processed_files = processed_files;
+
+        let constant_resolvers = app_roots(configuration, absolute_paths)
+            .into_iter()
+            .map(|app_root| {
+                let constant_resolver = if configuration.roots.is_empty() {
+                    get_experimental_constant_resolver(
+                        &configuration.absolute_root,
+                        &all_processed_files,
+                        &configuration.ignored_definitions,
+                    )
+                } else {
+                    let visible_processed_files: Vec<ProcessedFile> =
+                        all_processed_files
+                            .iter()
+                            .filter(|processed_file| {
+                                is_visible_from(
+                                    configuration,
+                                    &processed_file.absolute_path,
+                                    &app_root,
+                                )
+                            })
+                            .cloned()
+                            .collect();
+                    get_experimental_constant_resolver(
+                        &configuration.absolute_root,
+                        &visible_processed_files,
+                        &configuration.ignored_definitions,
+                    )
+                };
+                let constant_resolver: SharedConstantResolver = Arc::from(
+                    with_fallback(constant_resolver, &fallback_definitions),
+                );
+                (app_root, constant_resolver)
+            })
+            .collect();
 
         let processed_files_to_check = all_processed_files
             .into_iter()
@@ -48,18 +255,22 @@ pub(crate) fn get_all_references_and_sigils(
             })
             .collect();
 
-        (constant_resolver, processed_files_to_check)
+        (constant_resolvers, processed_files_to_check)
     } else {
-        let processed_files: Vec<ProcessedFile> =
-            process_files_with_cache(absolute_paths, cache, configuration)?;
-
         // The zeitwerk constant resolver doesn't look at processed files to get definitions
-        let constant_resolver = get_zeitwerk_constant_resolver(
-            &configuration.pack_set,
-            &configuration.constant_resolver_configuration(),
-        );
+        let constant_resolvers = app_roots(configuration, absolute_paths)
+            .into_iter()
+            .map(|app_root| {
+                let constant_resolver = zeitwerk_constant_resolver(
+                    configuration,
+                    &app_root,
+                    &fallback_definitions,
+                );
+                (app_root, constant_resolver)
+            })
+            .collect();
 
-        (constant_resolver, processed_files)
+        (constant_resolvers, processed_files)
     };
 
     // Now we're going to get all the files with sigils (i.e. processed_files_to_check where property sigils is not empty)
@@ -75,7 +286,20 @@ pub(crate) fn get_all_references_and_sigils(
         }
     }
 
+    let path_to_parse_errors: HashMap<PathBuf, Vec<SyntaxError>> =
+        processed_files_to_check
+            .iter()
+            .filter(|processed_file| !processed_file.parse_errors.is_empty())
+            .map(|processed_file| {
+                (
+                    processed_file.absolute_path.to_owned(),
+                    processed_file.parse_errors.to_owned(),
+                )
+            })
+            .collect();
+
     debug!("Turning unresolved references into fully qualified references");
+    let interner = Interner::default();
     let references: anyhow::Result<Vec<Reference>> = processed_files_to_check
         .par_iter()
         .try_fold(
@@ -83,12 +307,17 @@ pub(crate) fn get_all_references_and_sigils(
             // Start with an empty vector for each thread
             |mut acc, processed_file| {
                 // Try to fold results within a thread
+                let constant_resolver = &constant_resolvers[&app_root_of(
+                    configuration,
+                    &processed_file.absolute_path,
+                )];
                 for unresolved_ref in &processed_file.unresolved_references {
                     let mut refs = Reference::from_unresolved_reference(
                         configuration,
                         constant_resolver.as_ref(),
                         unresolved_ref,
                         &processed_file.absolute_path,
+                        &interner,
                     )?;
                     acc.append(&mut refs); // Collect references, return error if any
                 }
@@ -104,6 +333,8 @@ pub(crate) fn get_all_references_and_sigils(
             },
         );
     debug!("Finished turning unresolved references into fully qualified references");
+    let references = references?;
+    span.record("references", references.len());
 
-    Ok((references?, path_to_sigils))
+    Ok((references, path_to_sigils, path_to_parse_errors))
 }
@@ -0,0 +1,586 @@
+// Renders a standalone report of the violations in the codebase: an index page
+// with the pack dependency graph and sortable violation tables, and a page per
+// pack. Styles and scripts are inlined, so the output directory can be
+// published as-is, e.g. as a CI build artifact.
+pub(crate) mod owners;
+pub(crate) mod pull_request;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::f64::consts::PI;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use super::checker;
+use super::checker::Snippet;
+use super::Configuration;
+
+const STYLE: &str = "
+body { font-family: -apple-system, BlinkMacSystemFont, sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ddd; padding: 4px 8px; text-align: left; }
+th { background: #f4f4f4; cursor: pointer; user-select: none; }
+th[data-order=asc]::after { content: ' \\25B2'; }
+th[data-order=desc]::after { content: ' \\25BC'; }
+.new { color: #b00020; font-weight: bold; }
+svg text { font-size: 11px; }
+";
+
+// Sorts a table by the clicked column, numerically when both cells are numbers
+const SORT_SCRIPT: &str = "
+document.querySelectorAll('table.sortable th').forEach((th) => {
+  th.addEventListener('click', () => {
+    const table = th.closest('table');
+    const tbody = table.tBodies[0];
+    const ascending = th.dataset.order !== 'asc';
+    table.querySelectorAll('th').forEach((other) => delete other.dataset.order);
+    th.dataset.order = ascending ? 'asc' : 'desc';
+    const value = (row) => row.cells[th.cellIndex].textContent.trim();
+    const rows = Array.from(tbody.rows).sort((a, b) => {
+      const [x, y] = [value(a), value(b)];
+      const numeric = !isNaN(x) && !isNaN(y) && x !== '' && y !== '';
+      const order = numeric ? x - y : x.localeCompare(y);
+      return ascending ? order : -order;
+    });
+    rows.forEach((row) => tbody.appendChild(row));
+  });
+});
+";
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct ReportViolation {
+    pub referencing_pack_name: String,
+    pub defining_pack_name: String,
+    pub violation_type: String,
+    pub constant_name: String,
+    pub file: String,
+    pub line: usize,
+    pub strict: bool,
+    // Whether the violation is recorded in a package_todo.yml file
+    pub recorded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
+    // Link to the code of the violation, when `code_url` is configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Report {
+    // Pack name -> the packs it declares as dependencies
+    pub dependencies: BTreeMap<String, BTreeSet<String>>,
+    // Pack name -> its `owner:`, for the packs that have one
+    pub owners: BTreeMap<String, String>,
+    pub violations: Vec<ReportViolation>,
+}
+
+impl Report {
+    fn outgoing_violations<'a>(
+        &'a self,
+        pack_name: &'a str,
+    ) -> impl Iterator<Item = &'a ReportViolation> {
+        self.violations
+            .iter()
+            .filter(move |v| v.referencing_pack_name == pack_name)
+    }
+
+    fn incoming_violations<'a>(
+        &'a self,
+        pack_name: &'a str,
+    ) -> impl Iterator<Item = &'a ReportViolation> {
+        self.violations
+            .iter()
+            .filter(move |v| v.defining_pack_name == pack_name)
+    }
+}
+
+pub(crate) fn build_report(
+    configuration: &Configuration,
+) -> anyhow::Result<Report> {
+    let recorded_violations = &configuration.pack_set.all_violations;
+    let mut violations: Vec<ReportViolation> =
+        checker::find_all_violations(configuration)?
+            .into_iter()
+            .map(|violation| {
+                let recorded =
+                    recorded_violations.contains(&violation.identifier);
+                let identifier = violation.identifier;
+                let url = configuration.code_url.as_ref().map(|code_url| {
+                    code_url.for_location(
+                        &identifier.file,
+                        violation.source_location.line,
+                    )
+                });
+                ReportViolation {
+                    referencing_pack_name: identifier
+                        .referencing_pack_name
+                        .into(),
+                    defining_pack_name: identifier.defining_pack_name.into(),
+                    violation_type: identifier.violation_type,
+                    constant_name: identifier.constant_name.into(),
+                    file: identifier.file.into(),
+                    line: violation.source_location.line,
+                    strict: identifier.strict,
+                    recorded,
+                    snippet: violation.snippet,
+                    url,
+                }
+            })
+            .collect();
+    violations.sort();
+
+    let dependencies = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| {
+            (
+                pack.name.clone(),
+                pack.dependencies.iter().cloned().collect(),
+            )
+        })
+        .collect();
+
+    let owners = configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter_map(|pack| {
+            pack.owner
+                .as_ref()
+                .map(|owner| (pack.name.clone(), owner.clone()))
+        })
+        .collect();
+
+    Ok(Report {
+        dependencies,
+        owners,
+        violations,
+    })
+}
+
+pub(crate) fn write_html_report(
+    report: &Report,
+    output_directory: &Path,
+) -> anyhow::Result<()> {
+    let packs_directory = output_directory.join("packs");
+    fs::create_dir_all(&packs_directory)
+        .context(format!("Failed to create {}", packs_directory.display()))?;
+
+    fs::write(output_directory.join("index.html"), render_index(report)?)
+        .context("Failed to write index.html")?;
+    for pack_name in report.dependencies.keys() {
+        let path = packs_directory.join(pack_page_name(pack_name));
+        fs::write(&path, render_pack_page(report, pack_name)?)
+            .context(format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn render_index(report: &Report) -> Result<String, std::fmt::Error> {
+    let new_count = report.violations.iter().filter(|v| !v.recorded).count();
+    let mut by_type: BTreeMap<&str, usize> = BTreeMap::new();
+    for violation in &report.violations {
+        *by_type
+            .entry(violation.violation_type.as_str())
+            .or_default() += 1;
+    }
+
+    let mut body = String::new();
+    writeln!(body, "<h1>Packs report</h1>")?;
+    writeln!(
+        body,
+        "<p>{} pack(s), {} violation(s): {} new, {} recorded in package_todo.yml files</p>",
+        report.dependencies.len(),
+        report.violations.len(),
+        new_count,
+        report.violations.len() - new_count,
+    )?;
+    if !by_type.is_empty() {
+        writeln!(body, "<ul>")?;
+        for (violation_type, count) in by_type {
+            writeln!(body, "<li>{}: {}</li>", escape(violation_type), count)?;
+        }
+        writeln!(body, "</ul>")?;
+    }
+
+    writeln!(body, "<h2>Dependency graph</h2>")?;
+    writeln!(
+        body,
+        "<p>Gray arrows are declared dependencies. Red arrows are undeclared dependencies, i.e. dependency violations.</p>"
+    )?;
+    write_dependency_graph(&mut body, report)?;
+
+    writeln!(body, "<h2>Packs</h2>")?;
+    writeln!(body, "<table class=\"sortable\"><thead><tr><th>Pack</th><th>Dependencies</th><th>Outgoing violations</th><th>Incoming violations</th></tr></thead><tbody>")?;
+    for (pack_name, dependencies) in &report.dependencies {
+        writeln!(
+            body,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            pack_link(pack_name, "packs/"),
+            dependencies.len(),
+            report.outgoing_violations(pack_name).count(),
+            report.incoming_violations(pack_name).count(),
+        )?;
+    }
+    writeln!(body, "</tbody></table>")?;
+
+    writeln!(body, "<h2>Violations</h2>")?;
+    write_violations_table(&mut body, report.violations.iter(), "packs/")?;
+
+    Ok(page("Packs report", &body))
+}
+
+fn render_pack_page(
+    report: &Report,
+    pack_name: &str,
+) -> Result<String, std::fmt::Error> {
+    let mut body = String::new();
+    writeln!(
+        body,
+        "<p><a href=\"../index.html\">&larr; All packs</a></p>"
+    )?;
+    writeln!(body, "<h1>{}</h1>", escape(pack_name))?;
+
+    writeln!(body, "<h2>Dependencies</h2>")?;
+    match report.dependencies.get(pack_name) {
+        Some(dependencies) if !dependencies.is_empty() => {
+            writeln!(body, "<ul>")?;
+            for dependency in dependencies {
+                writeln!(body, "<li>{}</li>", pack_link(dependency, ""))?;
+            }
+            writeln!(body, "</ul>")?;
+        }
+        _ => writeln!(body, "<p>None</p>")?,
+    }
+
+    writeln!(body, "<h2>Outgoing violations</h2>")?;
+    write_violations_table(
+        &mut body,
+        report.outgoing_violations(pack_name),
+        "",
+    )?;
+    writeln!(body, "<h2>Incoming violations</h2>")?;
+    write_violations_table(
+        &mut body,
+        report.incoming_violations(pack_name),
+        "",
+    )?;
+
+    Ok(page(pack_name, &body))
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<style>{}</style>
+</head>
+<body>
+{}<script>{}</script>
+</body>
+</html>
+",
+        escape(title),
+        STYLE,
+        body,
+        SORT_SCRIPT
+    )
+}
+
+// `pack_directory` is the path from the current page to the pack pages
+fn write_violations_table<'a>(
+    body: &mut String,
+    violations: impl Iterator<Item = &'a ReportViolation>,
+    pack_directory: &str,
+) -> std::fmt::Result {
+    let mut violations = violations.peekable();
+    if violations.peek().is_none() {
+        return writeln!(body, "<p>None</p>");
+    }
+
+    writeln!(body, "<table class=\"sortable\"><thead><tr><th>Type</th><th>Constant</th><th>Referencing pack</th><th>Defining pack</th><th>File</th><th>Line</th><th>Status</th></tr></thead><tbody>")?;
+    for violation in violations {
+        let status = match (violation.recorded, violation.strict) {
+            (true, false) => "recorded",
+            (true, true) => "recorded (strict)",
+            (false, false) => "<span class=\"new\">new</span>",
+            (false, true) => "<span class=\"new\">new (strict)</span>",
+        };
+        writeln!(
+            body,
+            "<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&violation.violation_type),
+            escape(&violation.constant_name),
+            pack_link(&violation.referencing_pack_name, pack_directory),
+            pack_link(&violation.defining_pack_name, pack_directory),
+            file_cell(violation),
+            violation.line,
+            status,
+        )?;
+    }
+    writeln!(body, "</tbody></table>")
+}
+
+// Packs are laid out on a circle, with an arrow for each declared dependency and
+// each pair of packs with dependency violations between them
+fn write_dependency_graph(
+    body: &mut String,
+    report: &Report,
+) -> std::fmt::Result {
+    let pack_names: Vec<&String> = report.dependencies.keys().collect();
+    let radius = (40.0 * pack_names.len() as f64 / PI).max(150.0);
+    let center = radius + 150.0;
+    let positions: BTreeMap<&str, (f64, f64)> = pack_names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let angle = 2.0 * PI * index as f64 / pack_names.len() as f64;
+            (
+                name.as_str(),
+                (center + radius * angle.cos(), center + radius * angle.sin()),
+            )
+        })
+        .collect();
+
+    writeln!(
+        body,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size:.0}\" height=\"{size:.0}\" viewBox=\"0 0 {size:.0} {size:.0}\">",
+        size = center * 2.0
+    )?;
+    for (id, color) in [("declared", "#999"), ("violation", "#b00020")] {
+        writeln!(
+            body,
+            "<defs><marker id=\"{}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\"><path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"{}\"/></marker></defs>",
+            id, color
+        )?;
+    }
+
+    let mut edges: BTreeSet<(&str, &str, &str)> = BTreeSet::new();
+    for (pack_name, dependencies) in &report.dependencies {
+        for dependency in dependencies {
+            edges.insert((pack_name.as_str(), dependency.as_str(), "declared"));
+        }
+    }
+    for violation in &report.violations {
+        if violation.violation_type == "dependency" {
+            edges.insert((
+                violation.referencing_pack_name.as_str(),
+                violation.defining_pack_name.as_str(),
+                "violation",
+            ));
+        }
+    }
+    for (from, to, kind) in edges {
+        if let (Some(&(x1, y1)), Some(&(x2, y2))) =
+            (positions.get(from), positions.get(to))
+        {
+            // Stop short of the node, so the arrowhead is visible
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            if length == 0.0 {
+                continue;
+            }
+            let shortened = (length - 8.0) / length;
+            let color = if kind == "declared" {
+                "#999"
+            } else {
+                "#b00020"
+            };
+            writeln!(
+                body,
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" marker-end=\"url(#{})\"/>",
+                x1,
+                y1,
+                x1 + (x2 - x1) * shortened,
+                y1 + (y2 - y1) * shortened,
+                color,
+                kind
+            )?;
+        }
+    }
+    for (name, (x, y)) in &positions {
+        writeln!(
+            body,
+            "<a href=\"packs/{}\"><circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"6\" fill=\"#3367d6\"/><text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\">{}</text></a>",
+            pack_page_name(name),
+            x,
+            y,
+            x,
+            y - 10.0,
+            if *x < center { "end" } else { "start" },
+            escape(name)
+        )?;
+    }
+    writeln!(body, "</svg>")
+}
+
+fn pack_link(pack_name: &str, pack_directory: &str) -> String {
+    format!(
+        "<a href=\"{}{}\">{}</a>",
+        pack_directory,
+        pack_page_name(pack_name),
+        escape(pack_name)
+    )
+}
+
+// e.g. packs/foo -> packs--foo.html, and the root pack (.) -> root.html
+fn pack_page_name(pack_name: &str) -> String {
+    if pack_name == "." {
+        return String::from("root.html");
+    }
+    let name: String = pack_name
+        .chars()
+        .map(|c| match c {
+            '/' => String::from("--"),
+            c if c.is_ascii_alphanumeric() || c == '-' || c == '_' => {
+                c.to_string()
+            }
+            _ => String::from("_"),
+        })
+        .collect();
+    format!("{}.html", name)
+}
+
+// The file, linked to its code when possible, with the code around the
+// violation beneath it when captured
+fn file_cell(violation: &ReportViolation) -> String {
+    let file = match &violation.url {
+        Some(url) => {
+            format!(
+                "<a href=\"{}\">{}</a>",
+                escape(url),
+                escape(&violation.file)
+            )
+        }
+        None => escape(&violation.file),
+    };
+    let Some(snippet) = &violation.snippet else {
+        return file;
+    };
+    let code: Vec<String> = snippet
+        .lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            format!("{:>4} {}", snippet.first_line + index, escape(line))
+        })
+        .collect();
+    format!(
+        "<details><summary>{}</summary><pre>{}</pre></details>",
+        file,
+        code.join("\n")
+    )
+}
+
+// For the cells of Markdown tables
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+// A Markdown link to the code of the violation, when possible
+fn markdown_link(text: &str, url: Option<&str>) -> String {
+    match url {
+        Some(url) => format!("[{}]({})", escape_cell(text), url),
+        None => escape_cell(text),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn violation(
+        referencing_pack_name: &str,
+        defining_pack_name: &str,
+        recorded: bool,
+    ) -> ReportViolation {
+        ReportViolation {
+            referencing_pack_name: referencing_pack_name.to_string(),
+            defining_pack_name: defining_pack_name.to_string(),
+            violation_type: String::from("dependency"),
+            constant_name: String::from("::Bar"),
+            file: format!("{}/app/services/foo.rb", referencing_pack_name),
+            line: 3,
+            strict: false,
+            recorded,
+            snippet: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_pack_page_name() {
+        assert_eq!("root.html", pack_page_name("."));
+        assert_eq!("packs--foo.html", pack_page_name("packs/foo"));
+        assert_eq!("packs--foo_bar.html", pack_page_name("packs/foo bar"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;",
+            escape("<a href=\"x\">&</a>")
+        );
+    }
+
+    #[test]
+    fn test_file_cell() {
+        let mut violation = violation("packs/foo", "packs/bar", false);
+        assert_eq!("packs/foo/app/services/foo.rb", file_cell(&violation));
+
+        violation.snippet = Some(Snippet {
+            first_line: 2,
+            lines: vec![String::from("  def bar"), String::from("    Bar<1>")],
+        });
+        assert_eq!(
+            "<details><summary>packs/foo/app/services/foo.rb</summary><pre>   2   def bar\n   3     Bar&lt;1&gt;</pre></details>",
+            file_cell(&violation)
+        );
+
+        violation.url = Some(String::from("https://example.com/foo.rb#L3"));
+        assert_eq!(
+            "<details><summary><a href=\"https://example.com/foo.rb#L3\">packs/foo/app/services/foo.rb</a></summary><pre>   2   def bar\n   3     Bar&lt;1&gt;</pre></details>",
+            file_cell(&violation)
+        );
+    }
+
+    #[test]
+    fn test_render_pack_page() {
+        let report = Report {
+            dependencies: BTreeMap::from([
+                (
+                    String::from("packs/bar"),
+                    BTreeSet::from([String::from("packs/baz")]),
+                ),
+                (String::from("packs/baz"), BTreeSet::new()),
+                (String::from("packs/foo"), BTreeSet::new()),
+            ]),
+            owners: BTreeMap::new(),
+            violations: vec![
+                violation("packs/bar", "packs/foo", true),
+                violation("packs/foo", "packs/bar", false),
+            ],
+        };
+
+        let page = render_pack_page(&report, "packs/foo").unwrap();
+
+        assert!(page.contains("<h1>packs/foo</h1>"));
+        assert!(page.contains("<h2>Dependencies</h2>\n<p>None</p>"));
+        assert!(page.contains("<td><code>::Bar</code></td><td><a href=\"packs--foo.html\">packs/foo</a></td><td><a href=\"packs--bar.html\">packs/bar</a></td><td>packs/foo/app/services/foo.rb</td><td>3</td><td><span class=\"new\">new</span></td>"));
+        assert!(page.contains("<td><a href=\"packs--bar.html\">packs/bar</a></td><td><a href=\"packs--foo.html\">packs/foo</a></td><td>packs/bar/app/services/foo.rb</td><td>3</td><td>recorded</td>"));
+    }
+}
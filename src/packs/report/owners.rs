@@ -0,0 +1,274 @@
+// A digest of the violations of each team, i.e. the owner of the packs that
+// reference constants they shouldn't, for planning who pays down which debt
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use serde::Serialize;
+
+use super::{markdown_link, Report, ReportViolation};
+use crate::packs::cli::ReportFormat;
+use crate::packs::notifications::UNOWNED;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct OwnerDigest<'a> {
+    pub owner: String,
+    pub new_violations: usize,
+    pub recorded_violations: usize,
+    pub violations_by_type: BTreeMap<&'a str, usize>,
+    // Whether `groups` are keyed by the owner of the defining packs, rather
+    // than by referencing pack
+    pub by_defining_owner: bool,
+    pub groups: BTreeMap<String, Vec<&'a ReportViolation>>,
+}
+
+impl Report {
+    fn owner_of(&self, pack_name: &str) -> &str {
+        self.owners
+            .get(pack_name)
+            .map(String::as_str)
+            .unwrap_or(UNOWNED)
+    }
+}
+
+pub(crate) fn build_owner_digests(
+    report: &Report,
+    by_defining_owner: bool,
+) -> Vec<OwnerDigest<'_>> {
+    let mut digests: BTreeMap<&str, OwnerDigest> = BTreeMap::new();
+    for violation in &report.violations {
+        let owner = report.owner_of(&violation.referencing_pack_name);
+        let digest = digests.entry(owner).or_insert_with(|| OwnerDigest {
+            owner: owner.to_owned(),
+            new_violations: 0,
+            recorded_violations: 0,
+            violations_by_type: BTreeMap::new(),
+            by_defining_owner,
+            groups: BTreeMap::new(),
+        });
+        if violation.recorded {
+            digest.recorded_violations += 1;
+        } else {
+            digest.new_violations += 1;
+        }
+        *digest
+            .violations_by_type
+            .entry(&violation.violation_type)
+            .or_default() += 1;
+        let group = if by_defining_owner {
+            report.owner_of(&violation.defining_pack_name).to_owned()
+        } else {
+            violation.referencing_pack_name.clone()
+        };
+        digest.groups.entry(group).or_default().push(violation);
+    }
+    digests.into_values().collect()
+}
+
+// Writes a digest per owner to the `owners` directory of `output_directory`,
+// returning that directory
+pub(crate) fn write_owner_digests(
+    digests: &[OwnerDigest],
+    output_directory: &Path,
+    format: ReportFormat,
+) -> anyhow::Result<PathBuf> {
+    let extension = match format {
+        ReportFormat::Markdown => "md",
+        ReportFormat::Json => "json",
+        ReportFormat::Html => {
+            bail!("Owner digests are written as Markdown or JSON, so pass `--format markdown` or `--format json`")
+        }
+    };
+    let owners_directory = output_directory.join("owners");
+    fs::create_dir_all(&owners_directory)
+        .context(format!("Failed to create {}", owners_directory.display()))?;
+
+    for digest in digests {
+        let contents = match format {
+            ReportFormat::Json => serde_json::to_string_pretty(digest)
+                .context("Failed to serialize owner digest")?,
+            _ => render_markdown(digest)
+                .context("Failed to render owner digest")?,
+        };
+        let path = owners_directory.join(format!(
+            "{}.{}",
+            file_name(&digest.owner),
+            extension
+        ));
+        fs::write(&path, contents)
+            .context(format!("Failed to write {}", path.display()))?;
+    }
+    Ok(owners_directory)
+}
+
+fn render_markdown(digest: &OwnerDigest) -> Result<String, std::fmt::Error> {
+    let mut markdown = String::new();
+    writeln!(markdown, "# Violations of `{}`\n", digest.owner)?;
+    writeln!(
+        markdown,
+        "{} violation(s): {} new, {} recorded in package_todo.yml files\n",
+        digest.new_violations + digest.recorded_violations,
+        digest.new_violations,
+        digest.recorded_violations
+    )?;
+    for (violation_type, count) in &digest.violations_by_type {
+        writeln!(markdown, "- {}: {}", violation_type, count)?;
+    }
+
+    for (group, violations) in &digest.groups {
+        if digest.by_defining_owner {
+            writeln!(markdown, "\n## On packs owned by `{}`\n", group)?;
+        } else {
+            writeln!(markdown, "\n## From `{}`\n", group)?;
+        }
+        writeln!(
+            markdown,
+            "| Type | Constant | Referencing pack | Defining pack | File | Line | Status |"
+        )?;
+        writeln!(markdown, "| --- | --- | --- | --- | --- | --- | --- |")?;
+        for violation in violations {
+            let status = match (violation.recorded, violation.strict) {
+                (true, false) => "recorded",
+                (true, true) => "recorded (strict)",
+                (false, false) => "**new**",
+                (false, true) => "**new (strict)**",
+            };
+            writeln!(
+                markdown,
+                "| {} | `{}` | {} | {} | {} | {} | {} |",
+                violation.violation_type,
+                violation.constant_name,
+                violation.referencing_pack_name,
+                violation.defining_pack_name,
+                markdown_link(&violation.file, violation.url.as_deref()),
+                violation.line,
+                status
+            )?;
+        }
+    }
+    Ok(markdown)
+}
+
+// Owners are team names, e.g. `Payments Team` -> Payments_Team
+fn file_name(owner: &str) -> String {
+    owner
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeSet;
+
+    fn violation(
+        referencing_pack_name: &str,
+        defining_pack_name: &str,
+        recorded: bool,
+    ) -> ReportViolation {
+        ReportViolation {
+            referencing_pack_name: referencing_pack_name.to_string(),
+            defining_pack_name: defining_pack_name.to_string(),
+            violation_type: String::from("dependency"),
+            constant_name: String::from("::Bar"),
+            file: format!("{}/app/services/foo.rb", referencing_pack_name),
+            line: 3,
+            strict: false,
+            recorded,
+            snippet: None,
+            url: None,
+        }
+    }
+
+    fn report() -> Report {
+        Report {
+            dependencies: BTreeMap::from([
+                (String::from("packs/bar"), BTreeSet::new()),
+                (String::from("packs/baz"), BTreeSet::new()),
+                (String::from("packs/foo"), BTreeSet::new()),
+            ]),
+            owners: BTreeMap::from([
+                (String::from("packs/bar"), String::from("Payments")),
+                (String::from("packs/foo"), String::from("Shipping")),
+            ]),
+            violations: vec![
+                violation("packs/bar", "packs/foo", true),
+                violation("packs/baz", "packs/foo", false),
+                violation("packs/foo", "packs/bar", false),
+                violation("packs/foo", "packs/baz", true),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_build_owner_digests() {
+        let report = report();
+        let digests = build_owner_digests(&report, false);
+
+        assert_eq!(
+            digests
+                .iter()
+                .map(|digest| (
+                    digest.owner.as_str(),
+                    digest.new_violations,
+                    digest.recorded_violations,
+                    digest.groups.keys().cloned().collect::<Vec<String>>()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Payments", 0, 1, vec![String::from("packs/bar")]),
+                ("Shipping", 1, 1, vec![String::from("packs/foo")]),
+                ("unowned", 1, 0, vec![String::from("packs/baz")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_by_defining_owner() {
+        let report = report();
+        let digests = build_owner_digests(&report, true);
+        let shipping = digests
+            .iter()
+            .find(|digest| digest.owner == "Shipping")
+            .unwrap();
+
+        assert_eq!(
+            render_markdown(shipping).unwrap(),
+            "\
+# Violations of `Shipping`
+
+2 violation(s): 1 new, 1 recorded in package_todo.yml files
+
+- dependency: 2
+
+## On packs owned by `Payments`
+
+| Type | Constant | Referencing pack | Defining pack | File | Line | Status |
+| --- | --- | --- | --- | --- | --- | --- |
+| dependency | `::Bar` | packs/foo | packs/bar | packs/foo/app/services/foo.rb | 3 | **new** |
+
+## On packs owned by `unowned`
+
+| Type | Constant | Referencing pack | Defining pack | File | Line | Status |
+| --- | --- | --- | --- | --- | --- | --- |
+| dependency | `::Bar` | packs/foo | packs/baz | packs/foo/app/services/foo.rb | 3 | recorded |
+"
+        );
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!("Payments_Team", file_name("Payments Team"));
+        assert_eq!("team-a_b", file_name("team-a/b"));
+    }
+}
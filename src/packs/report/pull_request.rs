@@ -0,0 +1,321 @@
+// A Markdown summary of how the violations changed since a base revision, for
+// CI to post as a pull request comment: the violations that weren't recorded
+// at the merge base, those recorded then that are gone, and the change in
+// violations of each pack. Tables are cut short so the comment stays within
+// the size limits of code hosts.
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+
+use anyhow::{bail, Context};
+
+use super::{escape_cell, markdown_link, Report, ReportViolation};
+use crate::packs::checker::ViolationIdentifier;
+use crate::packs::package_todo::PackageTodo;
+use crate::packs::violation_store::ViolationStoreConfig;
+use crate::packs::{git, Configuration};
+
+// The rows of each table
+const MAX_ROWS: usize = 50;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Comparison<'a> {
+    pub base: String,
+    // Violations found now that weren't recorded at the base
+    pub new_violations: Vec<&'a ReportViolation>,
+    // Violations recorded at the base that aren't found now
+    pub resolved_violations: Vec<ViolationIdentifier>,
+    // Referencing pack name -> its violations recorded at the base, and found
+    // now, for the packs where they differ
+    pub pack_deltas: BTreeMap<String, (usize, usize)>,
+}
+
+// The violations recorded in the package_todo.yml files at the merge base of
+// `base` and HEAD. Packs that have since been removed or renamed aren't read.
+pub(crate) fn recorded_at_base(
+    configuration: &Configuration,
+    base: &str,
+) -> anyhow::Result<HashSet<ViolationIdentifier>> {
+    if configuration.violation_store != ViolationStoreConfig::Yml {
+        bail!("`--base` compares package_todo.yml files, so it requires `violation_store: yml` in packwerk.yml");
+    }
+    let absolute_root = &configuration.absolute_root;
+    let revision = git::merge_base(absolute_root, base)?;
+    let mut recorded = HashSet::new();
+    for pack in &configuration.pack_set.packs {
+        let Ok(relative_path) =
+            pack.package_todo_yml.strip_prefix(absolute_root)
+        else {
+            continue;
+        };
+        let Some(contents) =
+            git::file_at_revision(absolute_root, &revision, relative_path)?
+        else {
+            continue;
+        };
+        let package_todo: PackageTodo = serde_yaml::from_str(&contents)
+            .context(format!(
+                "Failed to deserialize {} at `{}`",
+                relative_path.display(),
+                base
+            ))?;
+        recorded.extend(package_todo.violation_identifiers(&pack.name));
+    }
+    Ok(recorded)
+}
+
+pub(crate) fn compare<'a>(
+    report: &'a Report,
+    recorded_at_base: &HashSet<ViolationIdentifier>,
+    base: &str,
+) -> Comparison<'a> {
+    let found: HashSet<ViolationIdentifier> =
+        report.violations.iter().map(identifier).collect();
+
+    let new_violations = report
+        .violations
+        .iter()
+        .filter(|violation| !recorded_at_base.contains(&identifier(violation)))
+        .collect();
+
+    let mut resolved_violations: Vec<ViolationIdentifier> = recorded_at_base
+        .iter()
+        .filter(|recorded| !found.contains(recorded))
+        .cloned()
+        .collect();
+    resolved_violations.sort_by_key(sort_key);
+
+    let mut pack_deltas: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for recorded in recorded_at_base {
+        pack_deltas
+            .entry(recorded.referencing_pack_name.to_string())
+            .or_default()
+            .0 += 1;
+    }
+    for violation in &report.violations {
+        pack_deltas
+            .entry(violation.referencing_pack_name.clone())
+            .or_default()
+            .1 += 1;
+    }
+    pack_deltas.retain(|_, (before, after)| before != after);
+
+    Comparison {
+        base: base.to_owned(),
+        new_violations,
+        resolved_violations,
+        pack_deltas,
+    }
+}
+
+// Recorded violations aren't strict, so neither is the identifier, to match
+fn identifier(violation: &ReportViolation) -> ViolationIdentifier {
+    ViolationIdentifier {
+        violation_type: violation.violation_type.clone(),
+        strict: false,
+        file: violation.file.as_str().into(),
+        constant_name: violation.constant_name.as_str().into(),
+        referencing_pack_name: violation.referencing_pack_name.as_str().into(),
+        defining_pack_name: violation.defining_pack_name.as_str().into(),
+        defining_file: None,
+    }
+}
+
+fn sort_key(
+    identifier: &ViolationIdentifier,
+) -> (String, String, String, String) {
+    (
+        identifier.referencing_pack_name.to_string(),
+        identifier.defining_pack_name.to_string(),
+        identifier.constant_name.to_string(),
+        identifier.file.to_string(),
+    )
+}
+
+pub(crate) fn render_markdown(
+    comparison: &Comparison,
+) -> Result<String, std::fmt::Error> {
+    let mut markdown = String::new();
+    writeln!(markdown, "## Packs report\n")?;
+    if comparison.new_violations.is_empty()
+        && comparison.resolved_violations.is_empty()
+    {
+        writeln!(
+            markdown,
+            "No violations added or resolved since `{}`.",
+            comparison.base
+        )?;
+        return Ok(markdown);
+    }
+    writeln!(
+        markdown,
+        "Since `{}`: {} new violation(s), {} resolved.",
+        comparison.base,
+        comparison.new_violations.len(),
+        comparison.resolved_violations.len()
+    )?;
+
+    if !comparison.new_violations.is_empty() {
+        writeln!(markdown, "\n### New violations\n")?;
+        writeln!(
+            markdown,
+            "| Type | Constant | Referencing pack | Defining pack | File | Status |"
+        )?;
+        writeln!(markdown, "| --- | --- | --- | --- | --- | --- |")?;
+        for violation in comparison.new_violations.iter().take(MAX_ROWS) {
+            let status = match (violation.recorded, violation.strict) {
+                (true, _) => "recorded",
+                (false, false) => "**not recorded**",
+                (false, true) => "**not recorded (strict)**",
+            };
+            writeln!(
+                markdown,
+                "| {} | `{}` | {} | {} | {} | {} |",
+                violation.violation_type,
+                violation.constant_name,
+                violation.referencing_pack_name,
+                violation.defining_pack_name,
+                markdown_link(
+                    &format!("{}:{}", violation.file, violation.line),
+                    violation.url.as_deref()
+                ),
+                status
+            )?;
+        }
+        write_omitted(&mut markdown, comparison.new_violations.len())?;
+    }
+
+    if !comparison.resolved_violations.is_empty() {
+        writeln!(markdown, "\n### Resolved violations\n")?;
+        writeln!(
+            markdown,
+            "| Type | Constant | Referencing pack | Defining pack | File |"
+        )?;
+        writeln!(markdown, "| --- | --- | --- | --- | --- |")?;
+        for violation in comparison.resolved_violations.iter().take(MAX_ROWS) {
+            writeln!(
+                markdown,
+                "| {} | `{}` | {} | {} | {} |",
+                violation.violation_type,
+                violation.constant_name,
+                violation.referencing_pack_name,
+                violation.defining_pack_name,
+                escape_cell(&violation.file),
+            )?;
+        }
+        write_omitted(&mut markdown, comparison.resolved_violations.len())?;
+    }
+
+    if !comparison.pack_deltas.is_empty() {
+        writeln!(markdown, "\n### Violations by pack\n")?;
+        writeln!(markdown, "| Pack | Before | After | Change |")?;
+        writeln!(markdown, "| --- | ---: | ---: | ---: |")?;
+        for (pack_name, (before, after)) in
+            comparison.pack_deltas.iter().take(MAX_ROWS)
+        {
+            writeln!(
+                markdown,
+                "| {} | {} | {} | {:+} |",
+                pack_name,
+                before,
+                after,
+                *after as i64 - *before as i64
+            )?;
+        }
+        write_omitted(&mut markdown, comparison.pack_deltas.len())?;
+    }
+    Ok(markdown)
+}
+
+fn write_omitted(markdown: &mut String, rows: usize) -> std::fmt::Result {
+    if rows > MAX_ROWS {
+        writeln!(markdown, "\n…and {} more", rows - MAX_ROWS)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeSet;
+
+    fn violation(file: &str, recorded: bool) -> ReportViolation {
+        ReportViolation {
+            referencing_pack_name: String::from("packs/foo"),
+            defining_pack_name: String::from("packs/bar"),
+            violation_type: String::from("dependency"),
+            constant_name: String::from("::Bar"),
+            file: file.to_owned(),
+            line: 3,
+            strict: false,
+            recorded,
+            snippet: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let report = Report {
+            dependencies: BTreeMap::from([
+                (String::from("packs/bar"), BTreeSet::new()),
+                (String::from("packs/foo"), BTreeSet::new()),
+            ]),
+            owners: BTreeMap::new(),
+            violations: vec![
+                violation("packs/foo/app/services/foo.rb", true),
+                violation("packs/foo/app/services/new_foo.rb", false),
+            ],
+        };
+        let recorded_at_base = HashSet::from([
+            identifier(&violation("packs/foo/app/services/foo.rb", true)),
+            identifier(&violation("packs/foo/app/services/old_foo.rb", true)),
+        ]);
+
+        let comparison = compare(&report, &recorded_at_base, "origin/main");
+
+        assert_eq!(
+            render_markdown(&comparison).unwrap(),
+            "\
+## Packs report
+
+Since `origin/main`: 1 new violation(s), 1 resolved.
+
+### New violations
+
+| Type | Constant | Referencing pack | Defining pack | File | Status |
+| --- | --- | --- | --- | --- | --- |
+| dependency | `::Bar` | packs/foo | packs/bar | packs/foo/app/services/new_foo.rb:3 | **not recorded** |
+
+### Resolved violations
+
+| Type | Constant | Referencing pack | Defining pack | File |
+| --- | --- | --- | --- | --- |
+| dependency | `::Bar` | packs/foo | packs/bar | packs/foo/app/services/old_foo.rb |
+"
+        );
+    }
+
+    #[test]
+    fn test_compare_pack_deltas() {
+        let report = Report {
+            dependencies: BTreeMap::new(),
+            owners: BTreeMap::new(),
+            violations: vec![
+                violation("packs/foo/app/services/foo.rb", false),
+                violation("packs/foo/app/services/new_foo.rb", false),
+            ],
+        };
+
+        let comparison = compare(&report, &HashSet::new(), "HEAD");
+
+        assert_eq!(
+            comparison.pack_deltas,
+            BTreeMap::from([(String::from("packs/foo"), (0, 2))])
+        );
+        assert_eq!(
+            render_markdown(&comparison).unwrap().lines().last(),
+            Some("| packs/foo | 0 | 2 | +2 |")
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// Constants that can't be resolved from the autoload paths, e.g. because
+// they're defined through metaprogramming, can be resolved from the secondary
+// sources listed under `resolution_fallbacks:` in packwerk.yml: Sorbet RBI
+// files or a ctags file. A fallback is only consulted when the primary
+// resolver finds nothing, and a constant is attributed to the pack that owns
+// the file the fallback says defines it.
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use tracing::debug;
+
+use super::constant_resolver::{ConstantDefinition, ConstantResolver};
+use super::file_utils::expand_glob;
+use super::parsing::ruby::api_collector::extract_constant_names;
+use super::parsing::ruby::zeitwerk::get_zeitwerk_constant_resolver_for_definitions;
+use super::raw_configuration::{ResolutionFallback, ResolutionFallbackType};
+use super::Configuration;
+
+struct ResolverWithFallback {
+    primary: Box<dyn ConstantResolver + Send + Sync>,
+    fallback: Box<dyn ConstantResolver + Send + Sync>,
+}
+
+impl ConstantResolver for ResolverWithFallback {
+    fn resolve(
+        &self,
+        fully_or_partially_qualified_constant: &str,
+        namespace_path: &[&str],
+    ) -> Option<Vec<ConstantDefinition>> {
+        self.primary
+            .resolve(fully_or_partially_qualified_constant, namespace_path)
+            .or_else(|| {
+                self.fallback.resolve(
+                    fully_or_partially_qualified_constant,
+                    namespace_path,
+                )
+            })
+    }
+
+    // Only the primary definitions, since those are what packs define
+    fn fully_qualified_constant_name_to_constant_definition_map(
+        &self,
+    ) -> &HashMap<String, Vec<ConstantDefinition>> {
+        self.primary
+            .fully_qualified_constant_name_to_constant_definition_map()
+    }
+}
+
+// The definitions from every configured fallback. When several fallbacks or
+// files define a constant, the first fallback, then the first file, wins.
+pub(crate) fn load_fallback_definitions(
+    configuration: &Configuration,
+) -> anyhow::Result<Vec<ConstantDefinition>> {
+    let mut definitions: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for fallback in &configuration.resolution_fallbacks {
+        debug!("Loading resolution fallback {:?}", fallback);
+        let fallback_definitions = match fallback.fallback_type {
+            ResolutionFallbackType::Rbi => {
+                rbi_definitions(&configuration.absolute_root, fallback)?
+            }
+            ResolutionFallbackType::Ctags => {
+                ctags_definitions(&configuration.absolute_root, fallback)?
+            }
+        };
+        for (name, path) in fallback_definitions {
+            definitions.entry(name).or_insert(path);
+        }
+    }
+    Ok(definitions
+        .into_iter()
+        .map(|(fully_qualified_name, absolute_path_of_definition)| {
+            ConstantDefinition {
+                fully_qualified_name,
+                absolute_path_of_definition,
+            }
+        })
+        .collect())
+}
+
+pub(crate) fn with_fallback(
+    primary: Box<dyn ConstantResolver + Send + Sync>,
+    fallback_definitions: &[ConstantDefinition],
+) -> Box<dyn ConstantResolver + Send + Sync> {
+    if fallback_definitions.is_empty() {
+        return primary;
+    }
+    Box::new(ResolverWithFallback {
+        primary,
+        fallback: get_zeitwerk_constant_resolver_for_definitions(
+            fallback_definitions.to_vec(),
+        ),
+    })
+}
+
+fn rbi_definitions(
+    absolute_root: &Path,
+    fallback: &ResolutionFallback,
+) -> anyhow::Result<Vec<(String, PathBuf)>> {
+    let mut files =
+        expand_glob(absolute_root.join(fallback.path()).to_str().unwrap());
+    files.sort();
+
+    let mut definitions = Vec::new();
+    for file in files {
+        let contents = std::fs::read_to_string(&file)
+            .context(format!("Failed to read {}", file.display()))?;
+        definitions.extend(
+            extract_constant_names(&contents)
+                .into_iter()
+                .map(|name| (name, file.clone())),
+        );
+    }
+    Ok(definitions)
+}
+
+fn ctags_definitions(
+    absolute_root: &Path,
+    fallback: &ResolutionFallback,
+) -> anyhow::Result<Vec<(String, PathBuf)>> {
+    let tags_path = absolute_root.join(fallback.path());
+    let contents = std::fs::read_to_string(&tags_path).context(format!(
+        "Failed to read the tags file at {}",
+        tags_path.display()
+    ))?;
+    Ok(parse_ctags(&contents)
+        .into_iter()
+        .map(|(name, file)| (name, absolute_root.join(file)))
+        .collect())
+}
+
+// Ruby classes, modules, and constants in a ctags file, as (fully qualified
+// name, file) pairs. Names are qualified by their `class:` or `module:` scope
+// field, unless ctags already qualified them (`--extras=+q`).
+fn parse_ctags(contents: &str) -> Vec<(String, String)> {
+    const RUBY_EXTENSIONS: [&str; 3] = ["rb", "rake", "rbi"];
+
+    contents
+        .lines()
+        .filter(|line| !line.starts_with("!_"))
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+            let name = columns.next()?;
+            let file = columns.next()?;
+            let extension = Path::new(file).extension()?.to_str()?;
+            if !RUBY_EXTENSIONS.contains(&extension) {
+                return None;
+            }
+
+            let mut kind = None;
+            let mut scope = None;
+            // Extension fields follow the address, which ends with `;"`
+            for field in columns
+                .skip_while(|column| !column.ends_with(";\""))
+                .skip(1)
+            {
+                match field.split_once(':') {
+                    Some(("kind", value)) => kind = Some(value),
+                    Some(("class" | "module", value)) => scope = Some(value),
+                    None => kind = Some(field),
+                    _ => {}
+                }
+            }
+            if !matches!(
+                kind?,
+                "c" | "class" | "m" | "module" | "C" | "constant"
+            ) {
+                return None;
+            }
+
+            let name = match scope {
+                Some(scope) if !name.contains("::") => {
+                    format!("::{}::{}", scope.trim_start_matches("::"), name)
+                }
+                _ => format!("::{}", name.trim_start_matches("::")),
+            };
+            Some((name, file.to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_ctags() {
+        let contents = "\
+!_TAG_FILE_FORMAT\t2\t/extended format/
+Foo\tpacks/foo/app/models/foo.rb\t/^class Foo$/;\"\tc
+Generated\tpacks/foo/app/models/foo.rb\t/^  Generated = Struct.new$/;\"\tC\tclass:Foo
+Bar::Baz\tpacks/bar/app/models/bar/baz.rb\t/^module Bar::Baz$/;\"\tkind:module
+call\tpacks/foo/app/models/foo.rb\t/^  def call$/;\"\tf\tclass:Foo
+Widget\tfrontend/widget.js\t/^class Widget$/;\"\tc
+";
+
+        assert_eq!(
+            vec![
+                (
+                    String::from("::Foo"),
+                    String::from("packs/foo/app/models/foo.rb")
+                ),
+                (
+                    String::from("::Foo::Generated"),
+                    String::from("packs/foo/app/models/foo.rb")
+                ),
+                (
+                    String::from("::Bar::Baz"),
+                    String::from("packs/bar/app/models/bar/baz.rb")
+                ),
+            ],
+            parse_ctags(contents)
+        );
+    }
+
+    #[test]
+    fn test_fallback_is_only_consulted_when_the_primary_resolver_fails() {
+        let definition = |name: &str, path: &str| ConstantDefinition {
+            fully_qualified_name: name.to_owned(),
+            absolute_path_of_definition: PathBuf::from(path),
+        };
+        let resolver = with_fallback(
+            get_zeitwerk_constant_resolver_for_definitions(vec![definition(
+                "::Foo", "foo.rb",
+            )]),
+            &[
+                definition("::Foo", "foo.rbi"),
+                definition("::Foo::Generated", "foo.rbi"),
+            ],
+        );
+
+        assert_eq!(
+            Some(vec![definition("::Foo", "foo.rb")]),
+            resolver.resolve("Foo", &[])
+        );
+        assert_eq!(
+            Some(vec![definition("::Foo::Generated", "foo.rbi")]),
+            resolver.resolve("Generated", &["Foo"])
+        );
+        assert_eq!(None, resolver.resolve("Missing", &[]));
+    }
+}
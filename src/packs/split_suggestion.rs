@@ -0,0 +1,376 @@
+// Suggests how a pack could be split into smaller packs, by clustering its
+// files so that as few of the references between them as possible cross the
+// boundaries of the clusters.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::checker::reference::Reference;
+use super::reference_extractor::get_all_references_and_sigils;
+use super::Configuration;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct SplitSuggestion {
+    pub pack: String,
+    #[serde(skip)]
+    pub pack_path: PathBuf,
+    pub files: usize,
+    // References from one file of the pack to another
+    pub internal_references: usize,
+    // Internal references between files of different candidates
+    pub cross_boundary_references: usize,
+    // Sorted from the largest candidate
+    pub candidates: Vec<SplitCandidate>,
+    // Files that neither reference nor are referenced by other files of the
+    // pack, which could go anywhere
+    pub unconnected_files: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct SplitCandidate {
+    pub files: Vec<String>,
+    // References between files of the candidate
+    pub internal_references: usize,
+    // References to and from files of the other candidates
+    pub cross_boundary_references: usize,
+}
+
+impl fmt::Display for SplitSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} file(s), {} internal reference(s)",
+            self.pack, self.files, self.internal_references
+        )?;
+        writeln!(
+            f,
+            "{} candidate(s), with {} reference(s) crossing their boundaries",
+            self.candidates.len(),
+            self.cross_boundary_references
+        )?;
+        for (index, candidate) in self.candidates.iter().enumerate() {
+            writeln!(
+                f,
+                "Candidate {}: {} file(s), {} internal reference(s), {} cross-boundary reference(s)",
+                index + 1,
+                candidate.files.len(),
+                candidate.internal_references,
+                candidate.cross_boundary_references
+            )?;
+            for line in directory_summary(&candidate.files, &self.pack_path) {
+                writeln!(f, "  {}", line)?;
+            }
+        }
+        writeln!(f, "Unconnected files: {}", self.unconnected_files.len())
+    }
+}
+
+// The directories of `files` relative to the pack, with the number of files in
+// each, or the file itself when it is alone in its directory
+fn directory_summary(files: &[String], pack_path: &Path) -> Vec<String> {
+    let mut files_by_directory: BTreeMap<PathBuf, Vec<&Path>> = BTreeMap::new();
+    for file in files {
+        let relative_file = Path::new(file)
+            .strip_prefix(pack_path)
+            .unwrap_or(Path::new(file));
+        let directory = relative_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        files_by_directory
+            .entry(directory)
+            .or_default()
+            .push(relative_file);
+    }
+    files_by_directory
+        .into_iter()
+        .map(|(directory, files)| match files.as_slice() {
+            [file] => file.display().to_string(),
+            files => {
+                format!("{}/ ({} file(s))", directory.display(), files.len())
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn suggest_split(
+    configuration: &Configuration,
+    pack_name: &str,
+    max_candidates: usize,
+) -> anyhow::Result<SplitSuggestion> {
+    let pack = configuration.pack_set.for_pack(pack_name)?;
+    let absolute_paths: HashSet<PathBuf> = configuration
+        .included_files
+        .iter()
+        .filter(|absolute_path| {
+            matches!(
+                configuration.pack_set.for_file(absolute_path),
+                Ok(Some(owner)) if owner.name == pack.name
+            )
+        })
+        .cloned()
+        .collect();
+    let (references, _sigils, _parse_errors) =
+        get_all_references_and_sigils(configuration, &absolute_paths)?;
+
+    let mut files: Vec<String> = absolute_paths
+        .iter()
+        .filter_map(|absolute_path| {
+            absolute_path
+                .strip_prefix(&configuration.absolute_root)
+                .ok()
+        })
+        .map(|relative_path| relative_path.display().to_string())
+        .collect();
+    files.sort();
+
+    let mut suggestion =
+        build_suggestion(&pack.name, files, &references, max_candidates);
+    suggestion.pack_path = pack.relative_path.clone();
+    Ok(suggestion)
+}
+
+fn build_suggestion(
+    pack_name: &str,
+    files: Vec<String>,
+    references: &[Reference],
+    max_candidates: usize,
+) -> SplitSuggestion {
+    let index_of: HashMap<&str, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| (file.as_str(), index))
+        .collect();
+
+    // The number of references between each pair of files, in either
+    // direction, keyed by the pair of indices in ascending order
+    let mut weights: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for reference in references {
+        if reference.defining_pack_name.as_deref() != Some(pack_name) {
+            continue;
+        }
+        let referencing = index_of.get(&*reference.relative_referencing_file);
+        let defining = reference
+            .relative_defining_file
+            .as_deref()
+            .and_then(|file| index_of.get(file));
+        let (Some(&referencing), Some(&defining)) = (referencing, defining)
+        else {
+            continue;
+        };
+        if referencing != defining {
+            *weights
+                .entry((referencing.min(defining), referencing.max(defining)))
+                .or_default() += 1;
+        }
+    }
+
+    let cluster_of = cluster(files.len(), &weights, max_candidates);
+
+    let mut files_by_cluster: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    let mut unconnected_files = vec![];
+    let connected: HashSet<usize> =
+        weights.keys().flat_map(|&(a, b)| [a, b]).collect();
+    for (index, file) in files.iter().enumerate() {
+        if connected.contains(&index) {
+            files_by_cluster
+                .entry(cluster_of[index])
+                .or_default()
+                .push(file.clone());
+        } else {
+            unconnected_files.push(file.clone());
+        }
+    }
+
+    let mut internal_by_cluster: HashMap<usize, usize> = HashMap::new();
+    let mut cross_boundary_by_cluster: HashMap<usize, usize> = HashMap::new();
+    let mut cross_boundary_references = 0;
+    for (&(a, b), &weight) in &weights {
+        let (cluster_a, cluster_b) = (cluster_of[a], cluster_of[b]);
+        if cluster_a == cluster_b {
+            *internal_by_cluster.entry(cluster_a).or_default() += weight;
+        } else {
+            *cross_boundary_by_cluster.entry(cluster_a).or_default() += weight;
+            *cross_boundary_by_cluster.entry(cluster_b).or_default() += weight;
+            cross_boundary_references += weight;
+        }
+    }
+
+    let mut candidates: Vec<SplitCandidate> = files_by_cluster
+        .into_iter()
+        .map(|(cluster, files)| SplitCandidate {
+            files,
+            internal_references: internal_by_cluster
+                .get(&cluster)
+                .copied()
+                .unwrap_or_default(),
+            cross_boundary_references: cross_boundary_by_cluster
+                .get(&cluster)
+                .copied()
+                .unwrap_or_default(),
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.files
+            .len()
+            .cmp(&a.files.len())
+            .then_with(|| a.files.cmp(&b.files))
+    });
+
+    SplitSuggestion {
+        pack: pack_name.to_owned(),
+        pack_path: PathBuf::from(pack_name),
+        files: files.len(),
+        internal_references: weights.values().sum(),
+        cross_boundary_references,
+        candidates,
+        unconnected_files,
+    }
+}
+
+// Average linkage clustering: the two clusters with the most references
+// between them, relative to their sizes, are merged until there are
+// `max_candidates` clusters of connected files, or no references between
+// clusters are left. Returns the cluster of each file.
+fn cluster(
+    file_count: usize,
+    weights: &BTreeMap<(usize, usize), usize>,
+    max_candidates: usize,
+) -> Vec<usize> {
+    let mut cluster_of: Vec<usize> = (0..file_count).collect();
+    let mut sizes: Vec<usize> = vec![1; file_count];
+    let mut links = weights.clone();
+    let mut cluster_count = weights
+        .keys()
+        .flat_map(|&(a, b)| [a, b])
+        .collect::<HashSet<usize>>()
+        .len();
+
+    while cluster_count > max_candidates.max(1) {
+        let score = |&(a, b): &(usize, usize), weight: usize| {
+            weight as f64 / (sizes[a] * sizes[b]) as f64
+        };
+        let best = links.iter().max_by(|(a, a_weight), (b, b_weight)| {
+            score(a, **a_weight)
+                .total_cmp(&score(b, **b_weight))
+                // Prefer the first pair on ties, for stable output
+                .then_with(|| b.cmp(a))
+        });
+        let Some((&(kept, merged), _)) = best else {
+            break;
+        };
+
+        sizes[kept] += sizes[merged];
+        sizes[merged] = 0;
+        for cluster in cluster_of.iter_mut() {
+            if *cluster == merged {
+                *cluster = kept;
+            }
+        }
+        let merged_links: Vec<((usize, usize), usize)> = links
+            .iter()
+            .filter(|((a, b), _)| *a == merged || *b == merged)
+            .map(|(pair, weight)| (*pair, *weight))
+            .collect();
+        for ((a, b), weight) in merged_links {
+            links.remove(&(a, b));
+            let other = if a == merged { b } else { a };
+            if other != kept {
+                *links
+                    .entry((kept.min(other), kept.max(other)))
+                    .or_default() += weight;
+            }
+        }
+        cluster_count -= 1;
+    }
+    cluster_of
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::interner::InternedStr;
+    use crate::packs::SourceLocation;
+    use pretty_assertions::assert_eq;
+
+    fn reference(from: &str, to: &str) -> Reference {
+        Reference {
+            constant_name: InternedStr::from("::Foo"),
+            defining_pack_name: Some(InternedStr::from("packs/monolith")),
+            relative_defining_file: Some(InternedStr::from(to)),
+            referencing_pack_name: InternedStr::from("packs/monolith"),
+            relative_referencing_file: InternedStr::from(from),
+            source_location: SourceLocation::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_suggestion() {
+        let files: Vec<String> = ["a", "b", "c", "d", "e", "f", "g"]
+            .iter()
+            .map(|name| format!("packs/monolith/{}.rb", name))
+            .collect();
+        let file = |name: &str| format!("packs/monolith/{}.rb", name);
+        let mut references = vec![];
+        // Two groups of files that reference each other a lot, and each other
+        // once
+        for (from, to) in [
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("d", "e"),
+            ("e", "f"),
+            ("f", "d"),
+        ] {
+            references.push(reference(&file(from), &file(to)));
+            references.push(reference(&file(to), &file(from)));
+        }
+        references.push(reference(&file("c"), &file("d")));
+
+        let suggestion =
+            build_suggestion("packs/monolith", files, &references, 2);
+
+        assert_eq!(
+            suggestion,
+            SplitSuggestion {
+                pack: String::from("packs/monolith"),
+                pack_path: PathBuf::from("packs/monolith"),
+                files: 7,
+                internal_references: 13,
+                cross_boundary_references: 1,
+                candidates: vec![
+                    SplitCandidate {
+                        files: vec![file("a"), file("b"), file("c")],
+                        internal_references: 6,
+                        cross_boundary_references: 1,
+                    },
+                    SplitCandidate {
+                        files: vec![file("d"), file("e"), file("f")],
+                        internal_references: 6,
+                        cross_boundary_references: 1,
+                    },
+                ],
+                unconnected_files: vec![file("g")],
+            }
+        );
+    }
+
+    #[test]
+    fn test_directory_summary() {
+        let files = vec![
+            String::from("packs/monolith/app/models/billing/charge.rb"),
+            String::from("packs/monolith/app/models/billing/invoice.rb"),
+            String::from("packs/monolith/app/services/shipping.rb"),
+        ];
+
+        assert_eq!(
+            directory_summary(&files, Path::new("packs/monolith")),
+            vec![
+                String::from("app/models/billing/ (2 file(s))"),
+                String::from("app/services/shipping.rb"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+// Roll-up metrics of the violations recorded in package_todo.yml files, by pack
+// or by product group, for reporting on the state of the codebase.
+pub(crate) mod history;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::pack::Pack;
+use super::Configuration;
+
+const UNGROUPED: &str = "(ungrouped)";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Stats {
+    pub packs: usize,
+    pub recorded_violations: usize,
+    pub recorded_violations_by_type: BTreeMap<String, usize>,
+}
+
+impl Stats {
+    fn add_pack(&mut self, pack: &Pack) {
+        self.packs += 1;
+        for violation in pack.all_violations() {
+            self.recorded_violations += 1;
+            *self
+                .recorded_violations_by_type
+                .entry(violation.violation_type)
+                .or_default() += 1;
+        }
+    }
+
+    fn add(&mut self, other: &Stats) {
+        self.packs += other.packs;
+        self.recorded_violations += other.recorded_violations;
+        for (violation_type, count) in &other.recorded_violations_by_type {
+            *self
+                .recorded_violations_by_type
+                .entry(violation_type.clone())
+                .or_default() += count;
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pack(s), {} recorded violation(s)",
+            self.packs, self.recorded_violations
+        )?;
+        if !self.recorded_violations_by_type.is_empty() {
+            let by_type: Vec<String> = self
+                .recorded_violations_by_type
+                .iter()
+                .map(|(violation_type, count)| {
+                    format!("{}: {}", violation_type, count)
+                })
+                .collect();
+            write!(f, " ({})", by_type.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+// Keyed by pack name, or by product group name when `by_product_group` is set.
+// Violations are attributed to the pack whose package_todo.yml records them.
+// With `only_pack_names`, other packs are left out.
+pub(crate) fn collect_stats(
+    configuration: &Configuration,
+    by_product_group: bool,
+    only_pack_names: Option<&BTreeSet<String>>,
+) -> BTreeMap<String, Stats> {
+    let mut stats: BTreeMap<String, Stats> = BTreeMap::new();
+    for pack in &configuration.pack_set.packs {
+        if only_pack_names.is_some_and(|names| !names.contains(&pack.name)) {
+            continue;
+        }
+        let key = key_for_pack(configuration, by_product_group, &pack.name);
+        stats.entry(key.to_owned()).or_default().add_pack(pack);
+    }
+    stats
+}
+
+// Rolls up stats keyed by pack name like `collect_stats`, e.g. those of a
+// snapshot taken with `stats --record`
+pub(crate) fn regroup_stats(
+    configuration: &Configuration,
+    stats_by_pack: &BTreeMap<String, Stats>,
+    by_product_group: bool,
+    only_pack_names: Option<&BTreeSet<String>>,
+) -> BTreeMap<String, Stats> {
+    let mut stats: BTreeMap<String, Stats> = BTreeMap::new();
+    for (pack_name, pack_stats) in stats_by_pack {
+        if only_pack_names.is_some_and(|names| !names.contains(pack_name)) {
+            continue;
+        }
+        let key = key_for_pack(configuration, by_product_group, pack_name);
+        stats.entry(key.to_owned()).or_default().add(pack_stats);
+    }
+    stats
+}
+
+fn key_for_pack<'a>(
+    configuration: &'a Configuration,
+    by_product_group: bool,
+    pack_name: &'a str,
+) -> &'a str {
+    if by_product_group {
+        configuration
+            .product_group_for_pack(pack_name)
+            .unwrap_or(UNGROUPED)
+    } else {
+        pack_name
+    }
+}
+
+pub(crate) fn total(stats: &BTreeMap<String, Stats>) -> Stats {
+    let mut total = Stats::default();
+    for key_stats in stats.values() {
+        total.add(key_stats);
+    }
+    total
+}
+
+pub(crate) fn write_stats(
+    f: &mut impl fmt::Write,
+    stats: &BTreeMap<String, Stats>,
+) -> fmt::Result {
+    for (key, key_stats) in stats {
+        writeln!(f, "{}: {}", key, key_stats)?;
+    }
+    writeln!(f, "Total: {}", total(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::configuration;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_collect_stats_by_product_group() {
+        let mut configuration = configuration::get(
+            PathBuf::from("tests/fixtures/contains_package_todo")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+        configuration.product_groups = BTreeMap::from([(
+            String::from("commerce"),
+            vec![glob::Pattern::new("packs/*").unwrap()],
+        )]);
+
+        let stats = collect_stats(&configuration, true, None);
+
+        assert_eq!(
+            stats.keys().collect::<Vec<&String>>(),
+            vec!["(ungrouped)", "commerce"]
+        );
+        assert_eq!(stats["(ungrouped)"].packs, 1);
+        assert_eq!(
+            stats["commerce"],
+            Stats {
+                packs: 2,
+                recorded_violations: 2,
+                recorded_violations_by_type: BTreeMap::from([(
+                    String::from("dependency"),
+                    2
+                )]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_collect_stats_of_some_packs() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/contains_package_todo")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+            &0,
+        )
+        .unwrap();
+
+        let stats = collect_stats(
+            &configuration,
+            false,
+            Some(&BTreeSet::from([String::from("packs/foo")])),
+        );
+
+        assert_eq!(stats.keys().collect::<Vec<&String>>(), vec!["packs/foo"]);
+        assert_eq!(stats["packs/foo"].recorded_violations, 2);
+    }
+
+    #[test]
+    fn test_write_stats() {
+        let stats = BTreeMap::from([
+            (
+                String::from("commerce"),
+                Stats {
+                    packs: 2,
+                    recorded_violations: 3,
+                    recorded_violations_by_type: BTreeMap::from([
+                        (String::from("dependency"), 1),
+                        (String::from("privacy"), 2),
+                    ]),
+                },
+            ),
+            (
+                String::from("platform"),
+                Stats {
+                    packs: 1,
+                    recorded_violations: 0,
+                    recorded_violations_by_type: BTreeMap::new(),
+                },
+            ),
+        ]);
+
+        let mut output = String::new();
+        write_stats(&mut output, &stats).unwrap();
+
+        let expected = "\
+commerce: 2 pack(s), 3 recorded violation(s) (dependency: 1, privacy: 2)
+platform: 1 pack(s), 0 recorded violation(s)
+Total: 3 pack(s), 3 recorded violation(s) (dependency: 1, privacy: 2)
+";
+        assert_eq!(expected, output);
+    }
+}
@@ -0,0 +1,267 @@
+// Snapshots of the stats of each pack, appended to .pks/stats-history.jsonl by
+// `stats --record`, e.g. from a scheduled CI job, so that `stats --diff` can
+// tell how the recorded violations changed since then without other tooling.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::Stats;
+use crate::packs::checker::waiver;
+
+const HISTORY_FILE: &str = ".pks/stats-history.jsonl";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    // e.g. `2024-03-31`
+    pub date: String,
+    // Keyed by pack name
+    pub stats: BTreeMap<String, Stats>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct StatsDiff {
+    // The date of the snapshot compared against
+    pub since: String,
+    pub before: Stats,
+    pub after: Stats,
+    // Key -> its recorded violations before and after, for the keys where
+    // they differ
+    pub changes: BTreeMap<String, (usize, usize)>,
+}
+
+impl fmt::Display for StatsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Since {}: {} -> {} recorded violation(s)",
+            self.since,
+            self.before.recorded_violations,
+            self.after.recorded_violations
+        )?;
+        if self.before.recorded_violations > 0 {
+            let change = self.after.recorded_violations as f64
+                - self.before.recorded_violations as f64;
+            write!(
+                f,
+                " ({:+.1}%)",
+                change * 100.0 / self.before.recorded_violations as f64
+            )?;
+        }
+        writeln!(f)?;
+
+        let mut violation_types: Vec<&String> = self
+            .before
+            .recorded_violations_by_type
+            .keys()
+            .chain(self.after.recorded_violations_by_type.keys())
+            .collect();
+        violation_types.sort();
+        violation_types.dedup();
+        for violation_type in violation_types {
+            let count = |stats: &Stats| {
+                stats
+                    .recorded_violations_by_type
+                    .get(violation_type)
+                    .copied()
+                    .unwrap_or(0)
+            };
+            write_change(
+                f,
+                violation_type,
+                count(&self.before),
+                count(&self.after),
+            )?;
+        }
+        for (key, (before, after)) in &self.changes {
+            write_change(f, key, *before, *after)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_change(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    before: usize,
+    after: usize,
+) -> fmt::Result {
+    writeln!(
+        f,
+        "  {}: {} -> {} ({:+})",
+        name,
+        before,
+        after,
+        after as i64 - before as i64
+    )
+}
+
+pub(crate) fn diff(
+    since: &str,
+    before: &BTreeMap<String, Stats>,
+    after: &BTreeMap<String, Stats>,
+) -> StatsDiff {
+    let mut changes: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for (key, stats) in before {
+        changes.entry(key.clone()).or_default().0 = stats.recorded_violations;
+    }
+    for (key, stats) in after {
+        changes.entry(key.clone()).or_default().1 = stats.recorded_violations;
+    }
+    changes.retain(|_, (before, after)| before != after);
+    StatsDiff {
+        since: since.to_owned(),
+        before: super::total(before),
+        after: super::total(after),
+        changes,
+    }
+}
+
+// Appends a snapshot of the stats to the history. Returns the path of the
+// history file.
+pub(crate) fn record(
+    absolute_root: &Path,
+    stats: BTreeMap<String, Stats>,
+) -> anyhow::Result<PathBuf> {
+    let path = absolute_root.join(HISTORY_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context(format!(
+            "Failed to create directory {}",
+            parent.display()
+        ))?;
+    }
+    let snapshot = Snapshot {
+        date: waiver::today(),
+        stats,
+    };
+    let line = serde_json::to_string(&snapshot)
+        .context("Failed to serialize stats")?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context(format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", line)
+        .context(format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+// The latest snapshot, or the latest one taken on or before `date`
+pub(crate) fn find_snapshot(
+    absolute_root: &Path,
+    date: Option<&str>,
+) -> anyhow::Result<Snapshot> {
+    let path = absolute_root.join(HISTORY_FILE);
+    let contents = fs::read_to_string(&path).context(format!(
+        "Failed to read {}. Run `pks stats --record` to take a snapshot",
+        path.display()
+    ))?;
+    let snapshots = parse_snapshots(&contents)
+        .context(format!("Failed to parse {}", path.display()))?;
+    latest_snapshot(snapshots, date).context(match date {
+        Some(date) => format!("No stats were recorded on or before {}", date),
+        None => format!("No stats are recorded in {}", path.display()),
+    })
+}
+
+fn parse_snapshots(contents: &str) -> anyhow::Result<Vec<Snapshot>> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .context(format!("Invalid snapshot on line {}", index + 1))
+        })
+        .collect()
+}
+
+// Snapshots are in the order they were taken, so the last of those with the
+// latest date wins. Dates are compared as strings, since they're formatted as
+// `YYYY-MM-DD`.
+fn latest_snapshot(
+    snapshots: Vec<Snapshot>,
+    date: Option<&str>,
+) -> Option<Snapshot> {
+    snapshots
+        .into_iter()
+        .filter(|snapshot| {
+            !date.is_some_and(|date| snapshot.date.as_str() > date)
+        })
+        .fold(None, |latest: Option<Snapshot>, snapshot| match latest {
+            Some(latest) if latest.date > snapshot.date => Some(latest),
+            _ => Some(snapshot),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn stats(recorded_violations: usize) -> Stats {
+        Stats {
+            packs: 1,
+            recorded_violations,
+            recorded_violations_by_type: BTreeMap::from([(
+                String::from("dependency"),
+                recorded_violations,
+            )]),
+        }
+    }
+
+    fn snapshot(date: &str, recorded_violations: usize) -> Snapshot {
+        Snapshot {
+            date: date.to_owned(),
+            stats: BTreeMap::from([(
+                String::from("packs/foo"),
+                stats(recorded_violations),
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_latest_snapshot() {
+        let snapshots = vec![
+            snapshot("2024-01-31", 10),
+            snapshot("2024-02-29", 8),
+            snapshot("2024-02-29", 7),
+            snapshot("2024-03-31", 5),
+        ];
+
+        assert_eq!(
+            Some(snapshot("2024-03-31", 5)),
+            latest_snapshot(snapshots.clone(), None)
+        );
+        assert_eq!(
+            Some(snapshot("2024-02-29", 7)),
+            latest_snapshot(snapshots.clone(), Some("2024-03-15"))
+        );
+        assert_eq!(None, latest_snapshot(snapshots, Some("2023-12-31")));
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = BTreeMap::from([
+            (String::from("packs/bar"), stats(20)),
+            (String::from("packs/foo"), stats(5)),
+        ]);
+        let after = BTreeMap::from([
+            (String::from("packs/bar"), stats(19)),
+            (String::from("packs/foo"), stats(5)),
+        ]);
+
+        assert_eq!(
+            diff("2024-03-01", &before, &after).to_string(),
+            "\
+Since 2024-03-01: 25 -> 24 recorded violation(s) (-4.0%)
+  dependency: 25 -> 24 (-1)
+  packs/bar: 20 -> 19 (-1)
+"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+// What `update` changed in the recorded violations, so reviewers don't have to
+// read `git diff` over dozens of package_todo.yml files: the entries added and
+// removed, the packs whose entries changed, and the counts by violation type.
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::checker::ViolationIdentifier;
+use super::pack::Pack;
+use super::package_todo::PackageTodo;
+use super::violation_store;
+use super::Configuration;
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct TodoDiff {
+    pub added: Vec<TodoEntry>,
+    pub removed: Vec<TodoEntry>,
+    // The referencing packs of the added and removed entries
+    pub packs_touched: BTreeSet<String>,
+    pub by_violation_type: BTreeMap<String, TodoCounts>,
+}
+
+// An entry of a package_todo.yml file: a violation type of a constant in a
+// file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct TodoEntry {
+    pub referencing_pack_name: String,
+    pub defining_pack_name: String,
+    pub constant_name: String,
+    pub violation_type: String,
+    pub file: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct TodoCounts {
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl From<ViolationIdentifier> for TodoEntry {
+    fn from(identifier: ViolationIdentifier) -> Self {
+        Self {
+            referencing_pack_name: identifier.referencing_pack_name.into(),
+            defining_pack_name: identifier.defining_pack_name.into(),
+            constant_name: identifier.constant_name.into(),
+            violation_type: identifier.violation_type,
+            file: identifier.file.into(),
+        }
+    }
+}
+
+impl TodoDiff {
+    pub(crate) fn new(
+        before: &BTreeSet<TodoEntry>,
+        after: &BTreeSet<TodoEntry>,
+    ) -> Self {
+        let added: Vec<TodoEntry> = after.difference(before).cloned().collect();
+        let removed: Vec<TodoEntry> =
+            before.difference(after).cloned().collect();
+        let mut packs_touched = BTreeSet::new();
+        let mut by_violation_type: BTreeMap<String, TodoCounts> =
+            BTreeMap::new();
+        for entry in &added {
+            packs_touched.insert(entry.referencing_pack_name.clone());
+            by_violation_type
+                .entry(entry.violation_type.clone())
+                .or_default()
+                .added += 1;
+        }
+        for entry in &removed {
+            packs_touched.insert(entry.referencing_pack_name.clone());
+            by_violation_type
+                .entry(entry.violation_type.clone())
+                .or_default()
+                .removed += 1;
+        }
+        Self {
+            added,
+            removed,
+            packs_touched,
+            by_violation_type,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for TodoDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No recorded violations changed");
+        }
+        writeln!(
+            f,
+            "Recorded violations: {} added, {} removed in {} pack(s)",
+            self.added.len(),
+            self.removed.len(),
+            self.packs_touched.len()
+        )?;
+        for (violation_type, counts) in &self.by_violation_type {
+            writeln!(
+                f,
+                "  {}: +{} -{}",
+                violation_type, counts.added, counts.removed
+            )?;
+        }
+        for pack_name in &self.packs_touched {
+            writeln!(f, "{}", pack_name)?;
+            let changes = self
+                .added
+                .iter()
+                .map(|entry| ('+', entry))
+                .chain(self.removed.iter().map(|entry| ('-', entry)))
+                .filter(|(_, entry)| &entry.referencing_pack_name == pack_name);
+            for (sign, entry) in changes {
+                writeln!(
+                    f,
+                    "  {} {} {} {} ({})",
+                    sign,
+                    entry.violation_type,
+                    entry.file,
+                    entry.constant_name,
+                    entry.defining_pack_name
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The violations recorded when the configuration was loaded
+pub(crate) fn loaded_entries(
+    configuration: &Configuration,
+) -> BTreeSet<TodoEntry> {
+    entries(
+        configuration
+            .pack_set
+            .packs
+            .iter()
+            .map(|pack| (pack.name.as_str(), &pack.package_todo)),
+    )
+}
+
+// The violations recorded in the violation store now, e.g. after `update`
+pub(crate) fn stored_entries(
+    configuration: &Configuration,
+) -> anyhow::Result<BTreeSet<TodoEntry>> {
+    let packs: HashSet<Pack> =
+        configuration.pack_set.packs.iter().cloned().collect();
+    let package_todos =
+        violation_store::for_configuration(configuration).load(&packs)?;
+    Ok(entries(package_todos.iter().map(
+        |(pack_name, package_todo)| (pack_name.as_str(), package_todo),
+    )))
+}
+
+fn entries<'a>(
+    package_todos: impl Iterator<Item = (&'a str, &'a PackageTodo)>,
+) -> BTreeSet<TodoEntry> {
+    package_todos
+        .flat_map(|(pack_name, package_todo)| {
+            package_todo.violation_identifiers(pack_name)
+        })
+        .map(TodoEntry::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry(
+        referencing_pack_name: &str,
+        violation_type: &str,
+        file: &str,
+    ) -> TodoEntry {
+        TodoEntry {
+            referencing_pack_name: referencing_pack_name.to_owned(),
+            defining_pack_name: String::from("packs/bar"),
+            constant_name: String::from("::Bar"),
+            violation_type: violation_type.to_owned(),
+            file: file.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_todo_diff() {
+        let unchanged = entry("packs/foo", "privacy", "packs/foo/a.rb");
+        let before = BTreeSet::from([
+            unchanged.clone(),
+            entry("packs/foo", "dependency", "packs/foo/gone.rb"),
+        ]);
+        let after = BTreeSet::from([
+            unchanged,
+            entry("packs/foo", "dependency", "packs/foo/b.rb"),
+            entry("packs/baz", "privacy", "packs/baz/c.rb"),
+        ]);
+
+        let diff = TodoDiff::new(&before, &after);
+
+        assert_eq!(
+            diff.to_string(),
+            "\
+Recorded violations: 2 added, 1 removed in 2 pack(s)
+  dependency: +1 -1
+  privacy: +1 -0
+packs/baz
+  + privacy packs/baz/c.rb ::Bar (packs/bar)
+packs/foo
+  + dependency packs/foo/b.rb ::Bar (packs/bar)
+  - dependency packs/foo/gone.rb ::Bar (packs/bar)
+"
+        );
+        assert_eq!(
+            TodoDiff::new(&after, &after).to_string(),
+            "No recorded violations changed\n"
+        );
+    }
+}
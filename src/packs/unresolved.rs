@@ -0,0 +1,165 @@
+// References whose defining pack couldn't be determined, e.g. because the
+// constant is defined through metaprogramming or outside of any pack. These
+// aren't checked, so listing them shows where the resolver has gaps.
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::checker::reference::Reference;
+use super::reference_extractor::get_all_references_and_sigils;
+use super::Configuration;
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct UnresolvedReport {
+    pub references: usize,
+    // Sorted from the most referenced
+    pub constants: Vec<UnresolvedConstant>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct UnresolvedConstant {
+    // As referenced, since it couldn't be fully qualified
+    pub constant: String,
+    pub references: usize,
+    // The files referencing it, sorted
+    pub files: Vec<String>,
+}
+
+impl UnresolvedReport {
+    pub(crate) fn new(references: &[Reference]) -> Self {
+        let mut files_by_constant: BTreeMap<&str, (usize, BTreeSet<&str>)> =
+            BTreeMap::new();
+        for reference in references {
+            if reference.defining_pack_name.is_some() {
+                continue;
+            }
+            let (count, files) = files_by_constant
+                .entry(&*reference.constant_name)
+                .or_default();
+            *count += 1;
+            files.insert(&*reference.relative_referencing_file);
+        }
+
+        let mut constants: Vec<UnresolvedConstant> = files_by_constant
+            .into_iter()
+            .map(|(constant, (references, files))| UnresolvedConstant {
+                constant: constant.to_owned(),
+                references,
+                files: files.into_iter().map(str::to_owned).collect(),
+            })
+            .collect();
+        // Stable on ties, which stay sorted by constant
+        constants.sort_by_key(|constant| Reverse(constant.references));
+        Self {
+            references: constants
+                .iter()
+                .map(|constant| constant.references)
+                .sum(),
+            constants,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.constants.is_empty()
+    }
+}
+
+impl fmt::Display for UnresolvedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} reference(s) to {} constant(s) could not be attributed to a pack:",
+            self.references,
+            self.constants.len()
+        )?;
+        for constant in &self.constants {
+            writeln!(
+                f,
+                "  {} ({} reference(s) in {} file(s))",
+                constant.constant,
+                constant.references,
+                constant.files.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// The unresolved references of all included files
+pub(crate) fn find_unresolved(
+    configuration: &Configuration,
+) -> anyhow::Result<UnresolvedReport> {
+    let (references, _sigils, _parse_errors) = get_all_references_and_sigils(
+        configuration,
+        &configuration.included_files,
+    )?;
+    Ok(UnresolvedReport::new(&references))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::interner::InternedStr;
+    use crate::packs::SourceLocation;
+    use pretty_assertions::assert_eq;
+
+    fn reference(
+        file: &str,
+        constant: &str,
+        defining_pack: Option<&str>,
+    ) -> Reference {
+        Reference {
+            constant_name: InternedStr::from(constant),
+            defining_pack_name: defining_pack.map(InternedStr::from),
+            relative_defining_file: None,
+            referencing_pack_name: InternedStr::from("packs/foo"),
+            relative_referencing_file: InternedStr::from(file),
+            source_location: SourceLocation::default(),
+        }
+    }
+
+    #[test]
+    fn test_unresolved_report() {
+        let references = vec![
+            reference("packs/foo/a.rb", "Bar", Some("packs/bar")),
+            reference("packs/foo/a.rb", "Dynamic", None),
+            reference("packs/foo/b.rb", "Dynamic", None),
+            reference("packs/foo/b.rb", "Dynamic", None),
+            reference("packs/foo/a.rb", "Baz", None),
+        ];
+
+        let report = UnresolvedReport::new(&references);
+
+        assert_eq!(
+            report,
+            UnresolvedReport {
+                references: 4,
+                constants: vec![
+                    UnresolvedConstant {
+                        constant: String::from("Dynamic"),
+                        references: 3,
+                        files: vec![
+                            String::from("packs/foo/a.rb"),
+                            String::from("packs/foo/b.rb"),
+                        ],
+                    },
+                    UnresolvedConstant {
+                        constant: String::from("Baz"),
+                        references: 1,
+                        files: vec![String::from("packs/foo/a.rb")],
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "\
+4 reference(s) to 2 constant(s) could not be attributed to a pack:
+  Dynamic (3 reference(s) in 2 file(s))
+  Baz (1 reference(s) in 1 file(s))
+"
+        );
+    }
+}
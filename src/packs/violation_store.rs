@@ -0,0 +1,386 @@
+// Where `update` records violations: a package_todo.yml file per pack, which
+// is packwerk's format, or a single JSON file or SQLite database for the whole
+// project, which spares very large projects from writing thousands of files
+// on every update.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use super::package_todo::{PackageTodo, ViolationGroup, YmlStore};
+use super::{pack::Pack, Configuration};
+
+#[derive(
+    PartialEq, Debug, Eq, Deserialize, Serialize, Default, Clone, Copy,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ViolationStoreKind {
+    #[default]
+    Yml,
+    Json,
+    Sqlite,
+}
+
+// The store selected in packwerk.yml, with the absolute path of its file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum ViolationStoreConfig {
+    #[default]
+    Yml,
+    Json(PathBuf),
+    #[cfg(feature = "sqlite")]
+    Sqlite(PathBuf),
+}
+
+pub(crate) trait ViolationStore {
+    // The recorded violations, keyed by the name of the responsible pack.
+    // Packs without recorded violations may be missing.
+    fn load(
+        &self,
+        packs: &HashSet<Pack>,
+    ) -> anyhow::Result<HashMap<String, PackageTodo>>;
+
+    // Records the violations of each of the packs, and forgets those of the
+    // packs given an empty `PackageTodo`. Other packs are left untouched.
+    // With `minimal_diff`, stores that can keep the order of existing entries
+    // do.
+    fn save(
+        &self,
+        configuration: &Configuration,
+        package_todos: &[(&Pack, PackageTodo)],
+        minimal_diff: bool,
+    ) -> anyhow::Result<()>;
+}
+
+// Responsible pack name -> defining pack name -> constant name -> violations
+type RecordedViolations =
+    BTreeMap<String, BTreeMap<String, BTreeMap<String, ViolationGroup>>>;
+
+pub(crate) fn resolve(
+    absolute_root: &Path,
+    kind: ViolationStoreKind,
+    path: Option<&Path>,
+) -> anyhow::Result<ViolationStoreConfig> {
+    match kind {
+        ViolationStoreKind::Yml => {
+            if path.is_some() {
+                bail!("`violation_store_path` in packwerk.yml requires `violation_store: json` or `violation_store: sqlite`");
+            }
+            Ok(ViolationStoreConfig::Yml)
+        }
+        ViolationStoreKind::Json => Ok(ViolationStoreConfig::Json(
+            absolute_root
+                .join(path.unwrap_or(Path::new("package_todo.json"))),
+        )),
+        #[cfg(feature = "sqlite")]
+        ViolationStoreKind::Sqlite => Ok(ViolationStoreConfig::Sqlite(
+            absolute_root
+                .join(path.unwrap_or(Path::new("package_todo.sqlite3"))),
+        )),
+        #[cfg(not(feature = "sqlite"))]
+        ViolationStoreKind::Sqlite => bail!(
+            "`violation_store: sqlite` in packwerk.yml requires pks to be built with the `sqlite` feature"
+        ),
+    }
+}
+
+pub(crate) fn open(config: &ViolationStoreConfig) -> Box<dyn ViolationStore> {
+    match config {
+        ViolationStoreConfig::Yml => Box::new(YmlStore),
+        ViolationStoreConfig::Json(path) => {
+            Box::new(JsonStore { path: path.clone() })
+        }
+        #[cfg(feature = "sqlite")]
+        ViolationStoreConfig::Sqlite(path) => {
+            Box::new(SqliteStore { path: path.clone() })
+        }
+    }
+}
+
+pub(crate) fn for_configuration(
+    configuration: &Configuration,
+) -> Box<dyn ViolationStore> {
+    open(&configuration.violation_store)
+}
+
+// Records the violations of every pack in one JSON file, keyed by pack name
+pub(crate) struct JsonStore {
+    path: PathBuf,
+}
+
+impl JsonStore {
+    fn read(&self) -> anyhow::Result<RecordedViolations> {
+        if !self.path.exists() {
+            return Ok(RecordedViolations::new());
+        }
+        let contents = fs::read_to_string(&self.path)
+            .context(format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "Failed to deserialize {}. Try deleting the file and running the `update` command to regenerate it.",
+                self.path.display()
+            )
+        })
+    }
+}
+
+impl ViolationStore for JsonStore {
+    fn load(
+        &self,
+        _packs: &HashSet<Pack>,
+    ) -> anyhow::Result<HashMap<String, PackageTodo>> {
+        Ok(self
+            .read()?
+            .into_iter()
+            .map(|(pack_name, violations_by_defining_pack)| {
+                (
+                    pack_name,
+                    PackageTodo {
+                        violations_by_defining_pack,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    fn save(
+        &self,
+        _configuration: &Configuration,
+        package_todos: &[(&Pack, PackageTodo)],
+        _minimal_diff: bool,
+    ) -> anyhow::Result<()> {
+        let mut recorded = self.read()?;
+        let previous = recorded.clone();
+        for (pack, package_todo) in package_todos {
+            if package_todo.violations_by_defining_pack.is_empty() {
+                recorded.remove(&pack.name);
+            } else {
+                recorded.insert(
+                    pack.name.clone(),
+                    package_todo.violations_by_defining_pack.clone(),
+                );
+            }
+        }
+        if recorded == previous {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create directory {}",
+                parent.display()
+            ))?;
+        }
+        let json = serde_json::to_string_pretty(&recorded)?;
+        fs::write(&self.path, json + "\n")
+            .context(format!("Failed to write {}", self.path.display()))
+    }
+}
+
+// Records the violations of each pack in a row of a SQLite database, written
+// in a single transaction
+#[cfg(feature = "sqlite")]
+pub(crate) struct SqliteStore {
+    path: PathBuf,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStore {
+    fn connect(&self) -> anyhow::Result<rusqlite::Connection> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create directory {}",
+                parent.display()
+            ))?;
+        }
+        let connection = rusqlite::Connection::open(&self.path)
+            .context(format!("Failed to open {}", self.path.display()))?;
+        // Each row is a pack's violations as JSON, like a value of JsonStore
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS package_todos (
+                pack TEXT PRIMARY KEY NOT NULL,
+                violations TEXT NOT NULL
+            )",
+        )?;
+        Ok(connection)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl ViolationStore for SqliteStore {
+    fn load(
+        &self,
+        _packs: &HashSet<Pack>,
+    ) -> anyhow::Result<HashMap<String, PackageTodo>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+        let connection = self.connect()?;
+        let mut statement =
+            connection.prepare("SELECT pack, violations FROM package_todos")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut package_todos = HashMap::new();
+        for row in rows {
+            let (pack_name, violations) = row?;
+            let violations_by_defining_pack = serde_json::from_str(&violations)
+                .context(format!(
+                    "Failed to deserialize the violations of `{}` in {}",
+                    pack_name,
+                    self.path.display()
+                ))?;
+            package_todos.insert(
+                pack_name,
+                PackageTodo {
+                    violations_by_defining_pack,
+                },
+            );
+        }
+        Ok(package_todos)
+    }
+
+    fn save(
+        &self,
+        _configuration: &Configuration,
+        package_todos: &[(&Pack, PackageTodo)],
+        _minimal_diff: bool,
+    ) -> anyhow::Result<()> {
+        let mut connection = self.connect()?;
+        let transaction = connection.transaction()?;
+        for (pack, package_todo) in package_todos {
+            if package_todo.violations_by_defining_pack.is_empty() {
+                transaction.execute(
+                    "DELETE FROM package_todos WHERE pack = ?1",
+                    [&pack.name],
+                )?;
+            } else {
+                let violations = serde_json::to_string(
+                    &package_todo.violations_by_defining_pack,
+                )?;
+                transaction.execute(
+                    "INSERT OR REPLACE INTO package_todos (pack, violations) VALUES (?1, ?2)",
+                    (&pack.name, &violations),
+                )?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_resolve() {
+        let root = Path::new("/app");
+        assert_eq!(
+            ViolationStoreConfig::Yml,
+            resolve(root, ViolationStoreKind::Yml, None).unwrap()
+        );
+        assert_eq!(
+            ViolationStoreConfig::Json(PathBuf::from("/app/package_todo.json")),
+            resolve(root, ViolationStoreKind::Json, None).unwrap()
+        );
+        assert_eq!(
+            ViolationStoreConfig::Json(PathBuf::from("/app/tmp/todos.json")),
+            resolve(
+                root,
+                ViolationStoreKind::Json,
+                Some(Path::new("tmp/todos.json"))
+            )
+            .unwrap()
+        );
+        assert!(resolve(
+            root,
+            ViolationStoreKind::Yml,
+            Some(Path::new("tmp/todos.json"))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_json_store_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("pks_json_store_{}.json", std::process::id()));
+        let store = JsonStore { path: path.clone() };
+        let configuration = Configuration::default();
+        let foo = Pack {
+            name: String::from("packs/foo"),
+            ..Pack::default()
+        };
+        let mut package_todo = PackageTodo::default();
+        package_todo
+            .violations_by_defining_pack
+            .entry(String::from("packs/bar"))
+            .or_default()
+            .insert(String::from("::Bar"), ViolationGroup::default());
+
+        store
+            .save(&configuration, &[(&foo, package_todo.clone())], false)
+            .unwrap();
+        let loaded = store.load(&HashSet::new()).unwrap();
+        assert_eq!(Some(&package_todo), loaded.get("packs/foo"));
+
+        store
+            .save(&configuration, &[(&foo, PackageTodo::default())], false)
+            .unwrap();
+        assert!(store.load(&HashSet::new()).unwrap().is_empty());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_store_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("pks_sqlite_store_{}.sqlite3", std::process::id()));
+        let store = SqliteStore { path: path.clone() };
+        let configuration = Configuration::default();
+        let foo = Pack {
+            name: String::from("packs/foo"),
+            ..Pack::default()
+        };
+        let bar = Pack {
+            name: String::from("packs/bar"),
+            ..Pack::default()
+        };
+        let mut foo_todo = PackageTodo::default();
+        foo_todo
+            .violations_by_defining_pack
+            .entry(String::from("packs/bar"))
+            .or_default()
+            .insert(String::from("::Bar"), ViolationGroup::default());
+        let mut bar_todo = PackageTodo::default();
+        bar_todo
+            .violations_by_defining_pack
+            .entry(String::from("packs/foo"))
+            .or_default()
+            .insert(String::from("::Foo"), ViolationGroup::default());
+
+        // Without a database, nothing is recorded
+        assert!(store.load(&HashSet::new()).unwrap().is_empty());
+
+        store
+            .save(
+                &configuration,
+                &[(&foo, foo_todo.clone()), (&bar, bar_todo.clone())],
+                false,
+            )
+            .unwrap();
+        let loaded = store.load(&HashSet::new()).unwrap();
+        assert_eq!(Some(&foo_todo), loaded.get("packs/foo"));
+        assert_eq!(Some(&bar_todo), loaded.get("packs/bar"));
+
+        // Packs that aren't saved are left untouched
+        store
+            .save(&configuration, &[(&foo, PackageTodo::default())], false)
+            .unwrap();
+        let loaded = store.load(&HashSet::new()).unwrap();
+        assert_eq!(None, loaded.get("packs/foo"));
+        assert_eq!(Some(&bar_todo), loaded.get("packs/bar"));
+        fs::remove_file(path).unwrap();
+    }
+}
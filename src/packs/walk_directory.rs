@@ -1,24 +1,33 @@
+use globset::GlobSet;
 use jwalk::WalkDirGeneric;
 use std::{
     collections::{HashMap, HashSet},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 use tracing::debug;
 
 use super::{
-    file_utils::build_glob_set, pack::Pack, raw_configuration::RawConfiguration,
+    file_utils::build_glob_set, pack::Pack, pksignore::PksIgnore,
+    raw_configuration::RawConfiguration,
 };
 
 pub struct WalkDirectoryResult {
     pub included_files: HashSet<PathBuf>,
     pub included_packs: HashSet<Pack>,
     pub owning_package_yml_for_file: HashMap<PathBuf, PathBuf>,
+    pub pksignore: PksIgnore,
+    // The package.yml files that could not be loaded, e.g. because they or
+    // their package_todo.yml are invalid YAML
+    pub pack_errors: Vec<(PathBuf, anyhow::Error)>,
 }
 
 #[derive(Debug, Default, Clone)]
 struct ProcessReadDirState {
     current_package_yml: PathBuf,
+    // Whether the entry is reached through a symlinked directory or is itself
+    // a symlink
+    via_symlink: bool,
 }
 
 impl jwalk::ClientState for ProcessReadDirState {
@@ -27,6 +36,50 @@ impl jwalk::ClientState for ProcessReadDirState {
     type DirEntryState = ProcessReadDirState;
 }
 
+// The directories whose package.yml defines a pack: those matching
+// `package_paths`, e.g. `packs/*` or `engines/*`, the application root, and
+// the root of each configured root, e.g. `apps/admin`
+struct PackDirectories {
+    package_paths_set: GlobSet,
+    root_paths: HashSet<PathBuf>,
+}
+
+impl PackDirectories {
+    fn new(raw: &RawConfiguration) -> Self {
+        // Directory globs are often written with a trailing slash, e.g.
+        // `components/*/`, but are matched against paths without one
+        let package_paths: Vec<String> = raw
+            .package_paths
+            .iter()
+            .map(|glob| glob.trim_end_matches('/').to_owned())
+            .filter(|glob| !glob.is_empty())
+            .collect();
+        Self {
+            package_paths_set: build_glob_set(&package_paths),
+            root_paths: raw
+                .roots
+                .iter()
+                .map(|root| PathBuf::from(root.path.trim_end_matches('/')))
+                .collect(),
+        }
+    }
+
+    fn contains(&self, relative_dir: &Path) -> bool {
+        relative_dir.as_os_str().is_empty()
+            || self.package_paths_set.is_match(relative_dir)
+            || self.root_paths.contains(relative_dir)
+    }
+}
+
+// Whether a package.yml in the directory, relative to the project root, would
+// define a pack
+pub(crate) fn is_pack_directory(
+    raw: &RawConfiguration,
+    relative_dir: &Path,
+) -> bool {
+    PackDirectories::new(raw).contains(relative_dir)
+}
+
 // We use jwalk to walk directories in parallel and compare them to the `include` and `exclude` patterns
 // specified in the `RawConfiguration`
 // https://docs.rs/jwalk/0.8.1/jwalk/struct.WalkDirGeneric.html#method.process_read_dir
@@ -42,8 +95,13 @@ pub(crate) fn walk_directory(
 
     let mut included_files: HashSet<PathBuf> = HashSet::new();
     let mut included_packs: HashSet<Pack> = HashSet::new();
+    let mut pack_errors: Vec<(PathBuf, anyhow::Error)> = Vec::new();
     let mut owning_package_yml_for_file: HashMap<PathBuf, PathBuf> =
         HashMap::new();
+    // Packs and files reached through symlinks, kept apart until it's known
+    // whether they're also reached without one
+    let mut symlinked_packs: Vec<Pack> = Vec::new();
+    let mut symlinked_files: Vec<(PathBuf, PathBuf)> = Vec::new();
 
     // Create this vector outside of the closure to avoid reallocating it
     let default_excluded_dirs = [
@@ -66,11 +124,14 @@ pub(crate) fn walk_directory(
     let all_excluded_dirs_set = build_glob_set(&all_excluded_dirs);
     let excluded_dirs_ref = Arc::new(all_excluded_dirs_set);
 
+    let pksignore = Arc::new(PksIgnore::from_root(&absolute_root)?);
+    let pksignore_ref = pksignore.clone();
+
     let absolute_root_ref = Arc::new(absolute_root.clone());
 
     let includes_set = build_glob_set(&raw.include);
     let excludes_set = build_glob_set(&raw.exclude);
-    let package_paths_set = build_glob_set(&raw.package_paths);
+    let pack_directories_ref = Arc::new(PackDirectories::new(raw));
 
     // TODO: Pull directory walker into separate module. Allow it to be called with implementations of a trait
     // so separate concerns can each be in their own place.
@@ -89,6 +150,7 @@ pub(crate) fn walk_directory(
         .follow_links(true)
         .root_read_dir_state(ProcessReadDirState {
             current_package_yml,
+            via_symlink: false,
         })
         .process_read_dir(
             move |_depth, absolute_dirname, read_dir_state, children| {
@@ -100,10 +162,21 @@ pub(crate) fn walk_directory(
                 let package_yml = absolute_dirname.join("package.yml");
 
                 // Even if the parent has set this on children, the existence of a new
-                // package.yml file should override it.
-                if package_yml.exists() {
+                // package.yml file should override it, unless it doesn't define a pack,
+                // e.g. that of a dummy app within an engine. Files under it then belong
+                // to the enclosing pack.
+                if package_yml.exists()
+                    && absolute_dirname
+                        .strip_prefix(cloned_absolute_root.as_ref())
+                        .is_ok_and(|relative_dirname| {
+                            pack_directories_ref.contains(relative_dirname)
+                        })
+                {
                     read_dir_state.current_package_yml = package_yml;
                 }
+                if absolute_dirname.is_symlink() {
+                    read_dir_state.via_symlink = true;
+                }
 
                 children.iter_mut().for_each(|child_dir_entry_result| {
                     if let Ok(child_dir_entry) = child_dir_entry_result {
@@ -112,11 +185,17 @@ pub(crate) fn walk_directory(
                             .client_state
                             .current_package_yml
                             .clone_from(&read_dir_state.current_package_yml);
+                        child_dir_entry.client_state.via_symlink =
+                            read_dir_state.via_symlink
+                                || child_dir_entry.path_is_symlink();
 
                         let relative_path = child_absolute_dirname
                             .strip_prefix(cloned_absolute_root.as_ref())
                             .unwrap();
                         if cloned_excluded_dirs.as_ref().is_match(relative_path)
+                            || (child_dir_entry.file_type.is_dir()
+                                && pksignore_ref
+                                    .is_ignored(relative_path, true))
                         {
                             child_dir_entry.read_children_path = None;
                         }
@@ -160,25 +239,37 @@ pub(crate) fn walk_directory(
 
         let current_package_yml =
             &unwrapped_entry.client_state.current_package_yml;
-
-        if &absolute_path == current_package_yml
-            // Ideally, we don't need the second part of this conditional, but it's here
-            // because there is a bug where the root pack doesn't match package_paths.
-            // We know we always want the root pack to be registered, since it's the catch-all pack for
-            // where constants are defined if they are not in another pack.
-            // We can remove this once we fix the bug.
-            && (package_paths_set.is_match(relative_path.parent().unwrap()) || absolute_path.parent().unwrap() == absolute_root)
-        {
-            let pack = Pack::from_path(&absolute_path, &absolute_root)?;
-            included_packs.insert(pack);
+        let via_symlink = unwrapped_entry.client_state.via_symlink;
+
+        // Only the package.yml files of pack directories become the current one
+        if &absolute_path == current_package_yml {
+            match Pack::from_path(
+                &absolute_path,
+                &absolute_root,
+                raw.package_todo_path.as_deref(),
+            ) {
+                Ok(pack) if via_symlink => symlinked_packs.push(pack),
+                Ok(pack) => {
+                    included_packs.insert(pack);
+                }
+                Err(error) => pack_errors.push((absolute_path.clone(), error)),
+            }
         }
 
         // This could be one line, but I'm keeping it separate for debugging purposes
         if includes_set.is_match(&relative_path) {
-            if !excludes_set.is_match(&relative_path) {
-                included_files.insert(absolute_path.clone());
-                owning_package_yml_for_file
-                    .insert(absolute_path, current_package_yml.clone());
+            // Ignored directories are not walked, so only the file itself needs checking
+            if !excludes_set.is_match(&relative_path)
+                && !pksignore.is_ignored(&relative_path, false)
+            {
+                if via_symlink {
+                    symlinked_files
+                        .push((absolute_path, current_package_yml.clone()));
+                } else {
+                    included_files.insert(absolute_path.clone());
+                    owning_package_yml_for_file
+                        .insert(absolute_path, current_package_yml.clone());
+                }
             } else {
                 // println!("file excluded: {}", relative_path.display())
             }
@@ -191,15 +282,96 @@ pub(crate) fn walk_directory(
         }
     }
 
+    include_symlinked(
+        &absolute_root,
+        symlinked_packs,
+        symlinked_files,
+        &mut included_packs,
+        &mut included_files,
+        &mut owning_package_yml_for_file,
+    );
+
     debug!("Finished directory walk");
 
     Ok(WalkDirectoryResult {
         included_files,
         included_packs,
         owning_package_yml_for_file,
+        pksignore: pksignore.as_ref().clone(),
+        pack_errors,
     })
 }
 
+// Packs and files reached through symlinks are attributed to the path they're
+// reached at, e.g. a shared component symlinked into `apps/admin/shared`
+// belongs to the `apps/admin/shared` pack. Those whose target is also walked
+// without a symlink are skipped rather than analyzed twice, and when several
+// symlinks lead to the same target, only the first of their paths is kept.
+fn include_symlinked(
+    absolute_root: &Path,
+    mut symlinked_packs: Vec<Pack>,
+    mut symlinked_files: Vec<(PathBuf, PathBuf)>,
+    included_packs: &mut HashSet<Pack>,
+    included_files: &mut HashSet<PathBuf>,
+    owning_package_yml_for_file: &mut HashMap<PathBuf, PathBuf>,
+) {
+    if symlinked_packs.is_empty() && symlinked_files.is_empty() {
+        return;
+    }
+    let canonical_root = absolute_root
+        .canonicalize()
+        .unwrap_or_else(|_| absolute_root.to_owned());
+    // The path a target is walked at without a symlink, if it's in the root
+    let unlinked_path = |canonical_path: &Path| {
+        canonical_path
+            .strip_prefix(&canonical_root)
+            .ok()
+            .map(|relative_path| absolute_root.join(relative_path))
+    };
+
+    // The package.yml kept for each one skipped as a duplicate
+    let mut kept_package_ymls: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let mut kept_by_canonical_yml: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let unlinked_package_ymls: HashSet<PathBuf> =
+        included_packs.iter().map(|pack| pack.yml.clone()).collect();
+    symlinked_packs.sort_by(|a, b| a.yml.cmp(&b.yml));
+    for pack in symlinked_packs {
+        let Ok(canonical_yml) = pack.yml.canonicalize() else {
+            continue;
+        };
+        if let Some(unlinked_yml) = unlinked_path(&canonical_yml)
+            .filter(|unlinked_yml| unlinked_package_ymls.contains(unlinked_yml))
+        {
+            kept_package_ymls.insert(pack.yml, unlinked_yml);
+        } else if let Some(kept_yml) = kept_by_canonical_yml.get(&canonical_yml)
+        {
+            kept_package_ymls.insert(pack.yml, kept_yml.clone());
+        } else {
+            kept_by_canonical_yml.insert(canonical_yml, pack.yml.clone());
+            included_packs.insert(pack);
+        }
+    }
+
+    let mut kept_canonical_files: HashSet<PathBuf> = HashSet::new();
+    symlinked_files.sort();
+    for (absolute_path, package_yml) in symlinked_files {
+        let Ok(canonical_path) = absolute_path.canonicalize() else {
+            continue;
+        };
+        let walked_unlinked = unlinked_path(&canonical_path)
+            .is_some_and(|path| included_files.contains(&path));
+        if walked_unlinked || !kept_canonical_files.insert(canonical_path) {
+            continue;
+        }
+        let package_yml = kept_package_ymls
+            .get(&package_yml)
+            .cloned()
+            .unwrap_or(package_yml);
+        included_files.insert(absolute_path.clone());
+        owning_package_yml_for_file.insert(absolute_path, package_yml);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -235,4 +407,119 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_walk_directory_with_package_paths_outside_packs(
+    ) -> anyhow::Result<()> {
+        let absolute_path = PathBuf::from("tests/fixtures/app_with_engines")
+            .canonicalize()
+            .expect("Could not canonicalize path");
+
+        let raw_config = RawConfiguration {
+            package_paths: vec![
+                "engines/*".to_string(),
+                "components/*/".to_string(),
+            ],
+            ..RawConfiguration::default()
+        };
+
+        let walk_directory_result =
+            walk_directory(absolute_path.clone(), &raw_config)?;
+        let mut pack_names: Vec<String> = walk_directory_result
+            .included_packs
+            .iter()
+            .map(|pack| pack.name.clone())
+            .collect();
+        pack_names.sort();
+        assert_eq!(
+            vec![".", "components/shipping", "engines/payments"],
+            pack_names
+        );
+
+        // The dummy app's package.yml doesn't define a pack
+        assert_eq!(
+            Some(&absolute_path.join("engines/payments/package.yml")),
+            walk_directory_result.owning_package_yml_for_file.get(
+                &absolute_path.join(
+                    "engines/payments/test/dummy/app/models/dummy_order.rb"
+                )
+            )
+        );
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_directory_with_symlinks() -> anyhow::Result<()> {
+        use std::fs;
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = std::env::temp_dir()
+            .join(format!("pks-symlinks-{}", std::process::id()));
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir)?;
+        }
+        let external = temp_dir.join("external/shared");
+        fs::create_dir_all(external.join("lib"))?;
+        fs::write(external.join("package.yml"), "")?;
+        fs::write(external.join("lib/shared.rb"), "class Shared\nend\n")?;
+        let root = temp_dir.join("app");
+        fs::create_dir_all(root.join("packs/foo/app/services"))?;
+        fs::create_dir_all(root.join("apps/a"))?;
+        fs::create_dir_all(root.join("apps/b"))?;
+        fs::write(root.join("package.yml"), "")?;
+        fs::write(root.join("packs/foo/package.yml"), "")?;
+        fs::write(
+            root.join("packs/foo/app/services/foo.rb"),
+            "class Foo\nend\n",
+        )?;
+        // Within the root, so also walked without the symlink
+        symlink(root.join("packs/foo"), root.join("packs/foo_link"))?;
+        // Outside of the root, and linked twice
+        symlink(&external, root.join("apps/a/shared"))?;
+        symlink(&external, root.join("apps/b/shared"))?;
+        let absolute_path = root.canonicalize()?;
+
+        let raw_config = RawConfiguration {
+            package_paths: vec![
+                "packs/*".to_string(),
+                "apps/*/shared".to_string(),
+            ],
+            ..RawConfiguration::default()
+        };
+        let walk_directory_result =
+            walk_directory(absolute_path.clone(), &raw_config)?;
+        fs::remove_dir_all(&temp_dir)?;
+
+        let mut pack_names: Vec<String> = walk_directory_result
+            .included_packs
+            .iter()
+            .map(|pack| pack.name.clone())
+            .collect();
+        pack_names.sort();
+        assert_eq!(vec![".", "apps/a/shared", "packs/foo"], pack_names);
+
+        let mut included_files: Vec<PathBuf> = walk_directory_result
+            .included_files
+            .iter()
+            .map(|path| path.strip_prefix(&absolute_path).unwrap().to_owned())
+            .collect();
+        included_files.sort();
+        assert_eq!(
+            vec![
+                PathBuf::from("apps/a/shared/lib/shared.rb"),
+                PathBuf::from("packs/foo/app/services/foo.rb"),
+            ],
+            included_files
+        );
+        assert_eq!(
+            Some(&absolute_path.join("apps/a/shared/package.yml")),
+            walk_directory_result
+                .owning_package_yml_for_file
+                .get(&absolute_path.join("apps/a/shared/lib/shared.rb"))
+        );
+
+        Ok(())
+    }
 }
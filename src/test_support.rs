@@ -0,0 +1,242 @@
+// Builders for unit-testing checker behavior in memory, without fixture
+// directories, for other crates and plugin authors. Enabled by the
+// `test-helpers` feature:
+//
+//     let configuration = ConfigurationBuilder::new()
+//         .pack(PackBuilder::new("packs/foo").build())
+//         .pack(PackBuilder::new("packs/bar").enforce_privacy(true).build())
+//         .build()?;
+//     let reference = ReferenceBuilder::new("::Bar")
+//         .referenced_from("packs/foo", "packs/foo/app/services/foo.rb")
+//         .defined_in("packs/bar", "packs/bar/app/services/bar.rb")
+//         .build();
+//     let violations = check(&configuration, &[reference])?;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+pub use crate::packs::checker::reference::Reference;
+pub use crate::packs::checker::{Violation, ViolationIdentifier};
+pub use crate::packs::configuration::Configuration;
+pub use crate::packs::pack::{CheckerSetting, Pack};
+
+use crate::packs::checker::find_violations_in_references;
+use crate::packs::checker::json::sorted;
+use crate::packs::checker::layer::Layers;
+use crate::packs::interner::InternedStr;
+use crate::packs::{PackSet, SourceLocation};
+
+pub struct PackBuilder {
+    pack: Pack,
+}
+
+impl PackBuilder {
+    // A pack enforcing nothing, at the path of its name
+    pub fn new(name: &str) -> Self {
+        let relative_path = PathBuf::from(name);
+        Self {
+            pack: Pack {
+                name: name.to_owned(),
+                yml: relative_path.join("package.yml"),
+                package_todo_yml: relative_path.join("package_todo.yml"),
+                relative_path,
+                ..Pack::default()
+            },
+        }
+    }
+
+    pub fn enforce_dependencies(mut self, setting: impl Into<Setting>) -> Self {
+        self.pack.enforce_dependencies = Some(setting.into().0);
+        self
+    }
+
+    pub fn enforce_privacy(mut self, setting: impl Into<Setting>) -> Self {
+        self.pack.enforce_privacy = Some(setting.into().0);
+        self
+    }
+
+    pub fn enforce_visibility(mut self, setting: impl Into<Setting>) -> Self {
+        self.pack.enforce_visibility = Some(setting.into().0);
+        self
+    }
+
+    pub fn enforce_layers(mut self, setting: impl Into<Setting>) -> Self {
+        self.pack.enforce_layers = Some(setting.into().0);
+        self
+    }
+
+    pub fn enforce_folder_privacy(
+        mut self,
+        setting: impl Into<Setting>,
+    ) -> Self {
+        self.pack.enforce_folder_privacy = Some(setting.into().0);
+        self
+    }
+
+    pub fn dependency(mut self, pack_name: &str) -> Self {
+        self.pack.dependencies.insert(pack_name.to_owned());
+        self
+    }
+
+    pub fn private_constant(mut self, constant_name: &str) -> Self {
+        self.pack.private_constants.insert(constant_name.to_owned());
+        self
+    }
+
+    pub fn visible_to(mut self, pack_name: &str) -> Self {
+        self.pack
+            .visible_to
+            .get_or_insert_with(HashSet::new)
+            .insert(pack_name.to_owned());
+        self
+    }
+
+    pub fn layer(mut self, layer: &str) -> Self {
+        self.pack.layer = Some(layer.to_owned());
+        self
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.pack.tags.push(tag.to_owned());
+        self
+    }
+
+    pub fn build(self) -> Pack {
+        self.pack
+    }
+}
+
+// An enforcement setting, from `true`/`false` or a `CheckerSetting`, e.g.
+// `CheckerSetting::Strict`
+pub struct Setting(CheckerSetting);
+
+impl From<bool> for Setting {
+    fn from(enforced: bool) -> Self {
+        Setting(if enforced {
+            CheckerSetting::True
+        } else {
+            CheckerSetting::False
+        })
+    }
+}
+
+impl From<CheckerSetting> for Setting {
+    fn from(setting: CheckerSetting) -> Self {
+        Setting(setting)
+    }
+}
+
+pub struct ReferenceBuilder {
+    constant_name: String,
+    referencing_pack_name: String,
+    relative_referencing_file: String,
+    defining_pack_name: Option<String>,
+    relative_defining_file: Option<String>,
+    source_location: SourceLocation,
+}
+
+impl ReferenceBuilder {
+    // A reference from the root pack to a constant defined outside of any
+    // pack, until set otherwise
+    pub fn new(constant_name: &str) -> Self {
+        Self {
+            constant_name: constant_name.to_owned(),
+            referencing_pack_name: String::from("."),
+            relative_referencing_file: String::from("app/services/file.rb"),
+            defining_pack_name: None,
+            relative_defining_file: None,
+            source_location: SourceLocation::new(1, 0),
+        }
+    }
+
+    pub fn referenced_from(
+        mut self,
+        pack_name: &str,
+        relative_file: &str,
+    ) -> Self {
+        self.referencing_pack_name = pack_name.to_owned();
+        self.relative_referencing_file = relative_file.to_owned();
+        self
+    }
+
+    pub fn defined_in(mut self, pack_name: &str, relative_file: &str) -> Self {
+        self.defining_pack_name = Some(pack_name.to_owned());
+        self.relative_defining_file = Some(relative_file.to_owned());
+        self
+    }
+
+    pub fn at(mut self, line: usize, column: usize) -> Self {
+        self.source_location = SourceLocation::new(line, column);
+        self
+    }
+
+    pub fn build(self) -> Reference {
+        Reference {
+            constant_name: InternedStr::from(self.constant_name),
+            defining_pack_name: self.defining_pack_name.map(InternedStr::from),
+            relative_defining_file: self
+                .relative_defining_file
+                .map(InternedStr::from),
+            referencing_pack_name: InternedStr::from(
+                self.referencing_pack_name,
+            ),
+            relative_referencing_file: InternedStr::from(
+                self.relative_referencing_file,
+            ),
+            source_location: self.source_location,
+        }
+    }
+}
+
+// A configuration of the given packs, and a root pack unless one is given,
+// which is never cached nor read from disk
+#[derive(Default)]
+pub struct ConfigurationBuilder {
+    packs: Vec<Pack>,
+    layers: Vec<String>,
+}
+
+impl ConfigurationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pack(mut self, pack: Pack) -> Self {
+        self.packs.push(pack);
+        self
+    }
+
+    // From the highest layer to the lowest, like `layers` in packwerk.yml
+    pub fn layers(mut self, layers: &[&str]) -> Self {
+        self.layers = layers.iter().map(|layer| layer.to_string()).collect();
+        self
+    }
+
+    pub fn build(self) -> anyhow::Result<Configuration> {
+        let mut packs: HashSet<Pack> = self.packs.into_iter().collect();
+        if !packs.iter().any(|pack| pack.name == ".") {
+            packs.insert(PackBuilder::new(".").build());
+        }
+        Ok(Configuration {
+            pack_set: PackSet::build(packs, HashMap::new())?,
+            layers: Layers {
+                layers: self.layers,
+            },
+            cache_enabled: false,
+            ..Configuration::default()
+        })
+    }
+}
+
+// The violations every checker finds in the references, sorted by file and
+// location
+pub fn check(
+    configuration: &Configuration,
+    references: &[Reference],
+) -> anyhow::Result<Vec<Violation>> {
+    let violations = find_violations_in_references(
+        configuration,
+        references,
+        &HashMap::new(),
+    )?;
+    Ok(sorted(violations.iter()).into_iter().cloned().collect())
+}
@@ -0,0 +1,38 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+
+mod common;
+
+#[test]
+fn test_annotate() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("annotate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("==> packs/foo/app/services/foo.rb <=="))
+        .stdout(predicate::str::contains("    ::Bar # pks: Dependency violation: `::Bar` belongs to `packs/bar`"))
+        .stdout(predicate::str::contains("Privacy violation: `::Bar` is private to `packs/bar`"))
+        .stdout(predicate::str::contains("    Baz\n"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_annotate_diff() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("annotate")
+        .arg("--diff")
+        .arg("packs/foo/app/services/foo.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--- a/packs/foo/app/services/foo.rb\n+++ b/packs/foo/app/services/foo.rb\n@@ -3,1 +3,1 @@\n-    ::Bar\n+    ::Bar # pks: "));
+
+    common::teardown();
+    Ok(())
+}
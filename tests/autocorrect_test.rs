@@ -0,0 +1,99 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs};
+
+mod common;
+
+#[test]
+fn test_autocorrect() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("autocorrect")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1 public facade(s) would fix the privacy violations:",
+        ))
+        .stdout(predicate::str::contains(
+            "  packs/bar/app/public/bar_facade.rb defines BarFacade, delegating to ::Bar\n    referenced from packs/foo/app/services/foo.rb (packs/foo)",
+        ));
+
+    assert!(!std::path::Path::new(
+        "tests/fixtures/simple_app/packs/bar/app/public"
+    )
+    .exists());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_autocorrect_with_apply() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("simple_app");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("autocorrect")
+        .arg("--apply")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Created packs/bar/app/public/bar_facade.rb",
+        ));
+
+    let facade = fs::read_to_string(
+        project_root.join("packs/bar/app/public/bar_facade.rb"),
+    )?;
+    assert!(facade.contains("module BarFacade"));
+    assert!(facade
+        .contains("::Bar.public_send(method_name, *args, **kwargs, &block)"));
+    assert!(facade.contains("#   packs/foo/app/services/foo.rb"));
+
+    // The facade is left alone the second time
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("autocorrect")
+        .arg("--apply")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(already exists)"))
+        .stdout(predicate::str::contains(
+            "Skipped packs/bar/app/public/bar_facade.rb, which already exists",
+        ));
+
+    fs::remove_dir_all(project_root)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_autocorrect_json() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("autocorrect")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let plan: serde_json::Value = serde_json::from_slice(&output)?;
+    let facade = &plan["facades"][0];
+    assert_eq!(facade["pack"], "packs/bar");
+    assert_eq!(facade["constant"], "::Bar");
+    assert_eq!(facade["facade"], "BarFacade");
+    assert_eq!(facade["exists"], false);
+    assert_eq!(
+        facade["references"][0]["file"],
+        "packs/foo/app/services/foo.rb"
+    );
+
+    common::teardown();
+    Ok(())
+}
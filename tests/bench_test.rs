@@ -0,0 +1,78 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_bench() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("bench")
+        .arg("--iterations")
+        .arg("2")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Timed 2 run(s) over"))
+        .stdout(predicate::str::contains("Cold cache:"))
+        .stdout(predicate::str::contains("Warm cache:"))
+        .stdout(predicate::str::contains("  todo_io "))
+        .stdout(predicate::str::contains("  total "));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_bench_with_json_format() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("bench")
+        .arg("--iterations")
+        .arg("1")
+        .arg("--cache")
+        .arg("warm")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json["iterations"], 1);
+    assert_eq!(json["variants"].as_array().unwrap().len(), 1);
+    assert_eq!(json["variants"][0]["cache"], "warm");
+    let names: Vec<&str> = json["variants"][0]["timings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|timing| timing["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["walk", "parse", "resolve", "check", "todo_io", "total"]
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_bench_with_zero_iterations() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("bench")
+        .arg("--iterations")
+        .arg("0")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--iterations"));
+
+    common::teardown();
+    Ok(())
+}
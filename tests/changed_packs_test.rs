@@ -0,0 +1,214 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::{error::Error, fs, path::Path, process::Command};
+mod common;
+
+#[test]
+#[serial]
+fn test_changed_packs() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("simple_app");
+    common::commit_all(&project_root);
+    fs::write(
+        project_root.join("packs/foo/app/services/foo.rb"),
+        "module Foo\nend\n",
+    )?;
+    fs::write(project_root.join("packs/baz/app/services/qux.rb"), "")?;
+    fs::remove_file(project_root.join("packs/bar/app/services/bar.rb"))?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("changed-packs")
+        .arg("--base")
+        .arg("HEAD")
+        .assert()
+        .success()
+        .stdout("packs/bar\npacks/baz\npacks/foo\n");
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_changed_packs_with_unknown_base() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("simple_app");
+    common::commit_all(&project_root);
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("changed-packs")
+        .arg("--base")
+        .arg("origin/missing")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Failed to find the merge base of `origin/missing`",
+        ));
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_check_changed_packs() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("simple_app");
+    common::commit_all(&project_root);
+
+    // packs/bar has no violations of its own
+    fs::write(project_root.join("packs/bar/app/services/quux.rb"), "")?;
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .arg("--changed-packs=HEAD")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files analyzed: 3"));
+
+    // packs/foo references private constants of packs/bar
+    fs::write(project_root.join("packs/foo/app/services/quux.rb"), "")?;
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .arg("--changed-packs=HEAD")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("violation(s) detected:"));
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_check_since() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("simple_app");
+    common::commit_all(&project_root);
+
+    // Nothing references ::SomeConcern
+    fs::write(
+        project_root.join("packs/bar/app/models/concerns/some_concern.rb"),
+        "module SomeConcern\nend\n",
+    )?;
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .arg("--since")
+        .arg("HEAD")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files analyzed: 1"));
+
+    // packs/foo/app/services/foo.rb is unchanged, but references ::Bar
+    fs::write(
+        project_root.join("packs/bar/app/services/bar.rb"),
+        "module Bar\n  def baz; end\nend\n",
+    )?;
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .arg("--since")
+        .arg("HEAD")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/foo.rb:3:4",
+        ));
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_stats_changed_packs() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("simple_app");
+    common::commit_all(&project_root);
+    fs::write(project_root.join("packs/bar/app/services/quux.rb"), "")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("stats")
+        .arg("--changed-packs=HEAD")
+        .assert()
+        .success()
+        .stdout("packs/bar: 1 pack(s), 0 recorded violation(s)\nTotal: 1 pack(s), 0 recorded violation(s)\n");
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
+
+fn git(directory: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(directory)
+        .args(["-c", "user.name=pks", "-c", "user.email=pks@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "`git {}` failed", args.join(" "));
+}
+
+#[test]
+#[serial]
+fn test_check_changed_in_shallow_clone() -> Result<(), Box<dyn Error>> {
+    let upstream = common::copy_fixture("simple_app");
+    common::commit_all(&upstream);
+    git(&upstream, &["branch", "--move", "--force", "main"]);
+    git(&upstream, &["checkout", "--quiet", "-b", "feature"]);
+    fs::write(
+        upstream.join("packs/bar/app/models/concerns/some_concern.rb"),
+        "module SomeConcern\nend\n",
+    )?;
+    git(
+        &upstream,
+        &["commit", "--quiet", "--all", "--message", "Feature"],
+    );
+    git(&upstream, &["checkout", "--quiet", "main"]);
+    fs::write(upstream.join("packs/baz/app/services/qux.rb"), "")?;
+    git(&upstream, &["add", "--all"]);
+    git(&upstream, &["commit", "--quiet", "--message", "Main"]);
+
+    // Like a CI runner, which only fetches the commit it builds
+    let clone = upstream.with_file_name(format!(
+        "{}-clone",
+        upstream.file_name().unwrap().to_string_lossy()
+    ));
+    if clone.exists() {
+        fs::remove_dir_all(&clone)?;
+    }
+    git(
+        upstream.parent().unwrap(),
+        &[
+            "clone",
+            "--quiet",
+            "--depth=1",
+            "--branch=feature",
+            &format!("file://{}", upstream.display()),
+            &clone.to_string_lossy(),
+        ],
+    );
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&clone)
+        .arg("check")
+        .arg("--changed")
+        .env("GITHUB_BASE_REF", "main")
+        .env_remove("CI_MERGE_REQUEST_TARGET_BRANCH_NAME")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files analyzed: 1"));
+
+    fs::remove_dir_all(clone)?;
+    fs::remove_dir_all(upstream)?;
+    Ok(())
+}
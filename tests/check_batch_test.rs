@@ -0,0 +1,62 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_batch() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures")
+        .arg("check-batch")
+        .arg("contains_package_todo")
+        .arg("app_with_parse_errors")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "contains_package_todo:\nNo violations detected!",
+        ))
+        .stdout(predicate::str::contains("app_with_parse_errors:\n"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_batch_with_violations() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures")
+        .arg("check-batch")
+        .arg("contains_package_todo")
+        .arg("simple_app")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "contains_package_todo:\nNo violations detected!",
+        ))
+        .stdout(predicate::str::contains("simple_app:\n"))
+        .stdout(predicate::str::contains("violation(s) detected:"))
+        .stderr(predicate::str::contains(
+            "Violations found in 1 of 2 project(s)!",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_batch_with_unknown_project() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures")
+        .arg("check-batch")
+        .arg("does_not_exist")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does_not_exist not found"));
+
+    common::teardown();
+    Ok(())
+}
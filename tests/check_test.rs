@@ -31,6 +31,141 @@ fn test_check() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_check_suggests_a_remediation_for_dependency_violations(
+) -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert!(stripped_output(output).contains("does not specify a dependency on `packs/bar`.\nSuggestion: add `packs/bar` to the dependencies of `packs/foo`."));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_quickfix_output_format() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--output-format")
+        .arg("quickfix")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb:3:4: Dependency violation: `::Bar` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`."))
+        .stdout(predicate::str::contains("\npacks/foo/app/services/foo.rb:3:4: Privacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"))
+        .stdout(predicate::str::contains("violation(s) detected").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_rubocop_output_format() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--output-format")
+        .arg("rubocop")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    let file = &json["files"][0];
+    assert_eq!(file["path"], "packs/foo/app/services/foo.rb");
+    let cop_names: Vec<&str> = file["offenses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|offense| offense["cop_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(cop_names, vec!["dependency", "privacy"]);
+    assert_eq!(file["offenses"][0]["location"]["line"], 3);
+    assert_eq!(file["offenses"][0]["location"]["column"], 4);
+    assert_eq!(json["summary"]["offense_count"], 2);
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_snippet_lines() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--snippet-lines")
+        .arg("1")
+        .arg("check")
+        .arg("--output-format")
+        .arg("json")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    let snippet = &json["violations"][0]["snippet"];
+    assert_eq!(snippet["first_line"], 2);
+    assert_eq!(
+        snippet["lines"],
+        serde_json::json!([
+            "  def calls_bar_without_a_stated_dependency",
+            "    ::Bar",
+            "  end"
+        ])
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_threads_and_max_memory() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--threads")
+        .arg("1")
+        .arg("--max-memory")
+        .arg("1K")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_invalid_max_memory() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--max-memory")
+        .arg("lots")
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --max-memory `lots`"));
+
+    common::teardown();
+    Ok(())
+}
+
 #[test]
 fn test_check_enforce_privacy_disabled() -> Result<(), Box<dyn Error>> {
     let output = Command::cargo_bin("packs")?
@@ -228,6 +363,44 @@ fn test_check_with_stale_violations_when_file_no_longer_exists(
     Ok(())
 }
 
+#[test]
+fn test_check_with_multiplied_violations() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_multiplied_violations")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("violation(s) detected:").not())
+        .stdout(predicate::str::contains(
+            "packs/foo references `::Bar` 2 time(s) in packs/foo/app/services/foo.rb, but its package_todo.yml records 1. Please remove the new reference(s) or run `packs update`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_fingerprinted_violations() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_fingerprinted_violations")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("violation(s) detected:").not())
+        // The recorded references in packs/foo have only moved
+        .stdout(predicate::str::contains("packs/foo references").not())
+        .stdout(predicate::str::contains(
+            "packs/baz references `::Bar` in packs/baz/app/services/baz.rb from code that its package_todo.yml does not record. Please remove the new reference(s) or run `packs update`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
 #[test]
 fn test_check_with_relationship_violations() -> Result<(), Box<dyn Error>> {
     Command::cargo_bin("packs")
@@ -285,6 +458,87 @@ fn test_check_with_strict_mode() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_check_with_strict_for() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/uses_strict_for")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/foo cannot have privacy violations on packs/bar because strict mode is enabled for privacy violations in the enforcing pack's package.yml file",
+        ))
+        .stdout(predicate::str::contains(
+            "packs/baz cannot have privacy violations",
+        ).not())
+        .stdout(predicate::str::contains(
+            "Privacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/baz`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_package_todo_path() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_central_todos")
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_timeout() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--no-cache")
+        .arg("check")
+        .arg("--timeout")
+        .arg("0")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Timed out before analyzing"))
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+    assert!(stripped_output
+        .contains("Warning: timed out before analyzing 9 file(s)"));
+    assert!(stripped_output.contains("packs/foo/app/services/foo.rb"));
+    // Recorded violations in files that weren't analyzed aren't stale
+    assert!(!stripped_output.contains("stale violations"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_within_timeout() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--timeout")
+        .arg("600")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("violation(s) detected:"))
+        .stdout(predicate::str::contains("timed out").not());
+
+    common::teardown();
+    Ok(())
+}
+
 #[test]
 fn test_check_contents() -> Result<(), Box<dyn Error>> {
     let project_root = "tests/fixtures/simple_app";
@@ -344,3 +598,578 @@ fn test_check_contents_ignoring_recorded_violations(
     common::teardown();
     Ok(())
 }
+
+#[test]
+fn test_check_group_by_constant() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--group-by")
+        .arg("constant")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+
+    assert!(stripped_output.contains("2 violation(s) detected in 1 group(s):"));
+    assert!(stripped_output.contains("`::Bar` (defined in packs/bar): 2 violation(s) (dependency, privacy) from 1 file(s) in 1 pack(s)"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_stdin_filelist() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--stdin-filelist")
+        .write_stdin(
+            "packs/foo/app/services/foo.rb\npacks/bar/app/services/bar.rb\n",
+        )
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_stdin_filelist_does_not_report_unchecked_todo_files_as_stale(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("check")
+        .arg("--stdin-filelist")
+        .write_stdin("packs/foo/app/services/foo.rb\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_empty_stdin_filelist() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--stdin-filelist")
+        .write_stdin("\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No files to check"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_explain() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--explain")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+
+    assert!(stripped_output.contains("2 violation(s) detected:"));
+    assert!(stripped_output.contains(
+        "packs/foo/app/services/foo.rb:3:4 references `::Bar`\n  Resolution:\n    `::Bar` is referenced within `Foo`\n    Tried `::Bar` (defined)\n    `::Bar` is defined in packs/bar/app/services/bar.rb\n"
+    ));
+    assert!(stripped_output
+        .contains("    packs/bar/app/services (contains the definition)\n"));
+    assert!(stripped_output.contains("    packs/foo/app/services/foo.rb belongs to `packs/foo`, since packs/foo/package.yml is the nearest package.yml\n"));
+    assert!(stripped_output.contains("  Reported by:\n    dependency: Dependency violation: `::Bar` belongs to `packs/bar`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_explain_for_another_constant() -> Result<(), Box<dyn Error>>
+{
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--explain")
+        .arg("--constant")
+        .arg("Baz")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "No violations on `::Baz` to explain",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_only_runs_required_packwerk_extensions(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_packwerk_extensions")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("Dependency violation: `::Bar`"))
+        .stdout(predicate::str::contains("Privacy violation").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_prints_a_summary() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No violations detected!\n\nSummary:",
+        ))
+        .stdout(predicate::str::contains("Files analyzed: 3"))
+        .stdout(predicate::str::contains("New violations: 0\n"))
+        .stdout(predicate::str::contains("Recorded violations: 2"))
+        .stdout(predicate::str::contains("Stale violations: 0"))
+        .stdout(predicate::str::contains("Elapsed time: "));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_json_output() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--output-format")
+        .arg("json")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("\"constant_name\": \"::Bar\""))
+        .stdout(predicate::str::contains(
+            "\"defining_file\": \"packs/bar/app/services/bar.rb\"",
+        ))
+        .stdout(predicate::str::contains("\"new_violations\": 2"))
+        .stdout(predicate::str::contains("\"dependency\": 1"))
+        .stdout(predicate::str::contains("\"privacy\": 1"))
+        .stdout(predicate::str::contains("\"elapsed_seconds\": "));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_multiple_outputs() -> Result<(), Box<dyn Error>> {
+    let json_path = "tests/fixtures/simple_app/tmp/artifacts/violations.json";
+    let quickfix_path = "tests/fixtures/simple_app/tmp/artifacts/quickfix.txt";
+
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--out")
+        .arg(format!("json={}", json_path))
+        .arg("--out")
+        .arg(format!("quickfix={}", quickfix_path))
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert!(stripped_output(output).contains("2 violation(s) detected:"));
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(json_path)?)?;
+    assert_eq!(json["summary"]["new_violations"], 2);
+    let quickfix = fs::read_to_string(quickfix_path)?;
+    assert!(quickfix.starts_with("packs/foo/app/services/foo.rb:3:4: "));
+
+    fs::remove_dir_all("tests/fixtures/simple_app/tmp/artifacts")?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_unknown_output_format() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--out")
+        .arg("xml=violations.xml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown output format `xml`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_ambiguous_constants() -> Result<(), Box<dyn Error>> {
+    // `::Order` is attributed to the pack of the first definition by path
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_ambiguous_constants")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert!(stripped_output(output).contains(
+        "Dependency violation: `::Order` belongs to `packs/legacy`, but `packs/shipping/package.yml` does not specify a dependency on `packs/legacy`."
+    ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_stream() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--stream")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+    assert!(stripped_output.contains("packs/foo/app/services/foo.rb:3:4\nDependency violation: `::Bar` belongs to `packs/bar`"));
+    assert!(stripped_output.contains("packs/foo/app/services/foo.rb:3:4\nPrivacy violation: `::Bar` is private to `packs/bar`"));
+    assert!(!stripped_output.contains("violation(s) detected:"));
+    assert!(stripped_output.contains("New violations: 2"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_stream_and_json_output() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--stream")
+        .arg("--output-format")
+        .arg("json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_nested_namespaces() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_nested_namespaces")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+    assert!(stripped_output.contains("2 violation(s) detected:"));
+    // `Payment::Receipt` within `Billing` is `::Billing::Payment::Receipt`,
+    // even though only `::Payment::Receipt` is defined
+    assert!(stripped_output.contains("packs/billing/app/services/billing/invoice.rb:6:6\nPrivacy violation: `::Payment::Receipt` is private to `packs/payments`, but referenced from `packs/billing`"));
+    // `class Billing::Refund` doesn't nest within `Billing`
+    assert!(stripped_output.contains("packs/billing/app/services/billing/refund.rb:3:4\nPrivacy violation: `::Payment` is private to `packs/payments`, but referenced from `packs/billing`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_mmap_threshold() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--mmap-threshold")
+        .arg("1")
+        .arg("--no-cache")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+    assert!(stripped_output.contains("2 violation(s) detected:"));
+    assert!(stripped_output.contains("packs/foo/app/services/foo.rb:3:4\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--mmap-threshold")
+        .arg("lots")
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --mmap-threshold `lots`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_extraction_handler_command() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_extraction_handlers")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+
+    assert!(stripped_output.contains("1 violation(s) detected:"));
+    assert!(stripped_output.contains("packs/foo/app/views/show.tmpl:2:"));
+    assert!(stripped_output
+        .contains("Dependency violation: `::Bar` belongs to `packs/bar`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_selected_checkers() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--checker")
+        .arg("privacy")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+
+    assert!(stripped_output.contains("1 violation(s) detected:"));
+    assert!(stripped_output
+        .contains("Privacy violation: `::Bar` is private to `packs/bar`"));
+    assert!(!stripped_output.contains("Dependency violation"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_selected_checkers_keeps_other_todos(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("check")
+        .arg("--checker")
+        .arg("privacy,visibility")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"))
+        .stdout(predicate::str::contains("stale").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_unknown_checker() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--checker")
+        .arg("privacy,depndency")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown checker `depndency`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_ignored_dependency() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_ignored_dependency")
+        .arg("check")
+        .assert()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+    assert!(!stripped_output.contains("Dependency violation"));
+    assert!(stripped_output
+        .contains("Privacy violation: `::Bar` is private to `packs/bar`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_annotated_ignored_dependency() -> Result<(), Box<dyn Error>>
+{
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_annotated_ignored_dependency")
+        .arg("check")
+        .assert()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+    assert!(!stripped_output.contains("Dependency violation"));
+    assert!(stripped_output
+        .contains("Privacy violation: `::Bar` is private to `packs/bar`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_collapsed_dirs() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_collapsed_dirs")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert!(stripped_output(output).contains(
+        "Dependency violation: `::Booking::Create` belongs to `packs/booking`"
+    ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_namespaces() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_namespaces")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = stripped_output(output);
+    assert!(stdout.contains(
+        "Dependency violation: `::Billing::Invoice` belongs to `packs/billing`"
+    ));
+    assert!(stdout.contains(
+        "Privacy violation: `::Billing::Invoice` is private to `packs/billing`"
+    ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_sample() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--sample")
+        .arg("1")
+        .arg("--seed")
+        .arg("42")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output = stripped_output(output);
+    assert!(stripped_output.contains("(rate 1, seed 42)"));
+    assert!(stripped_output.contains("Estimated new violations in all files:"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_sample_does_not_report_unsampled_todo_files_as_stale(
+) -> Result<(), Box<dyn Error>> {
+    // Neither file with a todo violation is in a sample this small
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("check")
+        .arg("--sample")
+        .arg("0.000001")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("stale").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_invalid_sample_rate() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--sample")
+        .arg("2")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The sample rate must be greater than 0 and at most 1",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{fs, path::PathBuf};
 
 //
@@ -169,3 +170,65 @@ enforce_dependencies: true
 
     fs::write(pack_yml, pack_yml_contents).unwrap();
 }
+
+// Copies a fixture to a temporary directory, for tests that move files around.
+// Each copy has its own directory, so tests copying the same fixture can run
+// in parallel.
+#[allow(dead_code)]
+pub fn copy_fixture(fixture: &str) -> PathBuf {
+    static COPIES: AtomicUsize = AtomicUsize::new(0);
+    let destination = std::env::temp_dir().join(format!(
+        "pks-{}-{}-{}",
+        fixture,
+        std::process::id(),
+        COPIES.fetch_add(1, Ordering::Relaxed)
+    ));
+    if destination.exists() {
+        fs::remove_dir_all(&destination).unwrap();
+    }
+    copy_dir(&PathBuf::from("tests/fixtures").join(fixture), &destination);
+    destination
+}
+
+fn copy_dir(source: &std::path::Path, destination: &std::path::Path) {
+    fs::create_dir_all(destination).unwrap();
+    for entry in fs::read_dir(source).unwrap() {
+        let entry = entry.unwrap();
+        let path = entry.path();
+        let target = destination.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &target);
+        } else {
+            fs::copy(&path, &target).unwrap();
+        }
+    }
+}
+
+// Turns a directory, e.g. a copied fixture, into a git repository with a
+// single commit of its files
+#[allow(dead_code)]
+pub fn commit_all(directory: &std::path::Path) {
+    fs::write(directory.join(".gitignore"), "tmp/\n").unwrap();
+    let commands: [&[&str]; 3] = [
+        &["init", "--quiet"],
+        &["add", "--all"],
+        &[
+            "-c",
+            "user.name=pks",
+            "-c",
+            "user.email=pks@example.com",
+            "commit",
+            "--quiet",
+            "--message",
+            "Initial commit",
+        ],
+    ];
+    for args in commands {
+        let status = std::process::Command::new("git")
+            .current_dir(directory)
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "`git {}` failed", args.join(" "));
+    }
+}
@@ -0,0 +1,38 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_dead_references() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_dead_references")
+        .arg("dead-references")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1 reference(s) to undefined constants in 1 pack(s):\npacks/foo\n  packs/foo/app/services/foo.rb:4 Billing::LegacyInvoice\n",
+        ))
+        .stdout(predicate::str::contains("ActiveRecord").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_no_dead_references() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("dead-references")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No references to undefined constants!",
+        ));
+
+    common::teardown();
+    Ok(())
+}
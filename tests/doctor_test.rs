@@ -0,0 +1,59 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_doctor() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_needing_doctor")
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[warning] pack_paths: `packs/orders/refunds` is nested in `packs/orders`"))
+        .stdout(predicate::str::contains("[warning] autoload: `autoload_roots` in packwerk.yml lists `lib/missing`, which matches no directory"))
+        .stdout(predicate::str::contains("[warning] autoload: `packs/utils` has 1 Ruby file(s) outside of tests, but no `app/*` directories"))
+        .stdout(predicate::str::contains("[info] cache: Caching is disabled"))
+        .stdout(predicate::str::contains("[info] parsing: Parsed 3 sample file(s)"))
+        .stdout(predicate::str::contains("0 error(s), 3 warning(s)"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_doctor_with_corrupt_todo() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_corrupt_todo")
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "[error] package_todo: Failed to deserialize the package_todo.yml file at",
+        ))
+        .stdout(predicate::str::contains("1 error(s), 0 warning(s)"))
+        .stderr(predicate::str::contains("Found 1 configuration error(s)!"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_doctor_with_json_output() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_needing_doctor")
+        .arg("doctor")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"severity\": \"warning\""))
+        .stdout(predicate::str::contains("\"check\": \"pack_paths\""));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs};
+
+mod common;
+
+#[test]
+fn test_enable_enforcement() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_to_invert");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("enable-enforcement")
+        .arg("enforce_privacy")
+        .arg("packs/b*")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Enabled `enforce_privacy` in 1 pack(s): packs/b",
+        ))
+        .stdout(predicate::str::contains("Recorded 3 new todo(s):"))
+        .stdout(predicate::str::contains("  packs/a: 1"))
+        .stdout(predicate::str::contains("  packs/c: 2"));
+
+    let b_yml = fs::read_to_string(project_root.join("packs/b/package.yml"))?;
+    assert!(b_yml.contains("enforce_privacy: true"));
+    assert!(project_root.join("packs/a/package_todo.yml").exists());
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("enable-enforcement")
+        .arg("enforce_privacy")
+        .arg("packs/b")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "`enforce_privacy` is already enabled in every matching pack",
+        ));
+
+    fs::remove_dir_all(project_root)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_enable_unknown_enforcement() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_to_invert")
+        .arg("enable-enforcement")
+        .arg("enforce_everything")
+        .arg("packs/*")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unknown enforcement `enforce_everything`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+
+mod common;
+
+#[test]
+fn test_list_packs_outside_of_packs_directory() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_engines")
+        .arg("list-packs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("engines/payments/package.yml"))
+        .stdout(predicate::str::contains("components/shipping/package.yml"))
+        .stdout(predicate::str::contains("test/dummy/package.yml").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_packs_outside_of_packs_directory() -> Result<(), Box<dyn Error>> {
+    // The dummy app's package.yml doesn't match `package_paths`, so its files
+    // belong to the engine
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_engines")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains("components/shipping/app/services/shipping/label.rb:4:6\nDependency violation: `::Payments::Charge` belongs to `engines/payments`, but `components/shipping/package.yml` does not specify a dependency on `engines/payments`."))
+        .stdout(predicate::str::contains("engines/payments/test/dummy/app/models/dummy_order.rb:3:4\nDependency violation: `::Shipping::Label` belongs to `components/shipping`, but `engines/payments/package.yml` does not specify a dependency on `components/shipping`."));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_packs_outside_of_packs_directory_with_experimental_parser(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_engines")
+        .arg("--experimental-parser")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains("but `engines/payments/package.yml` does not specify a dependency on `components/shipping`."));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+mod common;
+
+#[test]
+fn test_export_definitions_as_json() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("export-definitions")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"::Bar\": [\n    {\n      \"file\": \"packs/bar/app/services/bar.rb\",\n      \"pack\": \"packs/bar\"\n    }\n  ]",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_export_definitions_as_sql() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("export-definitions")
+        .arg("--format")
+        .arg("sql")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "DROP TABLE IF EXISTS definitions;",
+        ))
+        .stdout(predicate::str::contains(
+            "INSERT INTO definitions VALUES ('::Bar', 'packs/bar/app/services/bar.rb', 'packs/bar');",
+        ));
+
+    common::teardown();
+    Ok(())
+}
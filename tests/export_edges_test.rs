@@ -0,0 +1,46 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_export_edges() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("export-edges")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "from,to,declared,references,violations,dependency,privacy,visibility,layer,folder_privacy,tag_policy\n",
+        ))
+        .stdout(predicate::str::contains(
+            "\npacks/foo,packs/bar,false,1,1,1,1,0,0,0,0\n",
+        ))
+        .stdout(predicate::str::contains(
+            "\npacks/foo,packs/baz,true,1,0,0,0,0,0,0,0\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_export_edges_as_json() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("export-edges")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"from\": \"packs/foo\""))
+        .stdout(predicate::str::contains("\"to\": \"packs/bar\""))
+        .stdout(predicate::str::contains("\"dependency\": 1"))
+        .stdout(predicate::str::contains("\"privacy\": 1"));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::{error::Error, fs, process::Command};
+mod common;
+
+const PACKAGE_YML_PATH: &str =
+    "tests/fixtures/simple_app/packs/foo/package.yml";
+
+#[test]
+// Run in serial since the next test modifies the fixture
+#[serial]
+fn test_check_suggests_dependencies() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "# packs/foo/package.yml\ndependencies:\n- packs/bar\n- packs/baz\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_check_fix_dependencies() -> Result<(), Box<dyn Error>> {
+    let original = fs::read_to_string(PACKAGE_YML_PATH)?;
+
+    let assert = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--fix-dependencies")
+        .assert();
+    let actual = fs::read_to_string(PACKAGE_YML_PATH)?;
+    fs::write(PACKAGE_YML_PATH, original)?;
+
+    // The privacy violation remains
+    assert
+        .failure()
+        .stdout(predicate::str::contains(
+            "Added `packs/bar` as dependencies of `packs/foo`",
+        ))
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("Privacy violation"))
+        .stdout(predicate::str::contains("Dependency violation").not());
+    assert!(actual.contains("dependencies:\n- packs/bar\n- packs/baz\n"));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,5 @@
+class Refund
+  def order
+    Order
+  end
+end
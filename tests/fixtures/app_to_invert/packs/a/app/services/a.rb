@@ -0,0 +1,5 @@
+class A
+  def call
+    Api.new.call
+  end
+end
@@ -0,0 +1,6 @@
+class Api
+  def call
+    Utils.log
+    Result.new
+  end
+end
@@ -0,0 +1,5 @@
+class B
+  def call
+    Api.new.call
+  end
+end
@@ -0,0 +1,6 @@
+class C
+  def call
+    Result.new
+    B.new
+  end
+end
@@ -0,0 +1,4 @@
+class Utils
+  def self.log
+  end
+end
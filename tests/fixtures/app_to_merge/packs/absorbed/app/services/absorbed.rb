@@ -0,0 +1,6 @@
+class Absorbed
+  def call
+    Into.new
+    Shared.new
+  end
+end
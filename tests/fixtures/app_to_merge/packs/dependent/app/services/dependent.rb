@@ -0,0 +1,5 @@
+class Dependent
+  def call
+    Absorbed.new
+  end
+end
@@ -0,0 +1,4 @@
+class Into
+  def call
+  end
+end
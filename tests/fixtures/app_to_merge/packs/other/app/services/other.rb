@@ -0,0 +1,5 @@
+class Other
+  def call
+    Absorbed.new
+  end
+end
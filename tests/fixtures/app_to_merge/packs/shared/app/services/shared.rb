@@ -0,0 +1,4 @@
+class Shared
+  def call
+  end
+end
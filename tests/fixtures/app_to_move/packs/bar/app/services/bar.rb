@@ -0,0 +1,5 @@
+module Bar
+  def self.foo
+    Foo
+  end
+end
@@ -0,0 +1,5 @@
+module Foo
+  def self.bar
+    Bar
+  end
+end
@@ -0,0 +1,7 @@
+module Billing
+  class Charge
+    def invoice
+      Billing::Invoice.new
+    end
+  end
+end
@@ -0,0 +1,7 @@
+module Billing
+  class Invoice
+    def charges
+      Billing::Charge.new
+    end
+  end
+end
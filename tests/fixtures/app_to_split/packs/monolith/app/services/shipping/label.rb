@@ -0,0 +1,7 @@
+module Shipping
+  class Label
+    def parcel
+      Shipping::Parcel.new
+    end
+  end
+end
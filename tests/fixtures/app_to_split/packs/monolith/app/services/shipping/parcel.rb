@@ -0,0 +1,11 @@
+module Shipping
+  class Parcel
+    def label
+      Shipping::Label.new
+    end
+
+    def invoice
+      Billing::Invoice.new
+    end
+  end
+end
@@ -0,0 +1,3 @@
+# Superseded by packs/orders, but never deleted
+class Order
+end
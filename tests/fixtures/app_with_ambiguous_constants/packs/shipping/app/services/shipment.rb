@@ -0,0 +1,5 @@
+class Shipment
+  def order
+    Order.find(1)
+  end
+end
@@ -0,0 +1 @@
+module SomeConcern; end
@@ -0,0 +1,3 @@
+module Bar
+  def bar; end
+end
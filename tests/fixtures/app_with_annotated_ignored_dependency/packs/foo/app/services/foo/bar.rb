@@ -0,0 +1,5 @@
+# This defines ::Foo::Bar, which is different than ::Bar
+module Foo
+  module Bar
+  end
+end
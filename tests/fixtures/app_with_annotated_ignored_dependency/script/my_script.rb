@@ -0,0 +1 @@
+# some ruby script
@@ -0,0 +1,4 @@
+module Booking
+  class Create
+  end
+end
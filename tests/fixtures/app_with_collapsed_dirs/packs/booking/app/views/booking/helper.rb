@@ -0,0 +1,5 @@
+# Not autoloaded, so it does not define `::Booking::Helper`
+module Booking
+  module Helper
+  end
+end
@@ -0,0 +1,5 @@
+class Foo
+  def book
+    Booking::Create.new
+  end
+end
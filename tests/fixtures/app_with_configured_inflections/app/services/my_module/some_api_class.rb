@@ -0,0 +1,4 @@
+module MyModule
+  class SomeApiClass
+  end
+end
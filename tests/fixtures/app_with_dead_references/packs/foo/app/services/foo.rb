@@ -0,0 +1,6 @@
+class Foo < ActiveRecord::Base
+  def bill
+    Billing::Invoice.new
+    Billing::LegacyInvoice.new
+  end
+end
@@ -0,0 +1,7 @@
+module Shipping
+  class Label
+    def charge
+      Payments::Charge
+    end
+  end
+end
@@ -0,0 +1,5 @@
+class DummyOrder
+  def label
+    Shipping::Label
+  end
+end
@@ -0,0 +1,5 @@
+module Foo
+  def self.call
+    Bar.call
+  end
+end
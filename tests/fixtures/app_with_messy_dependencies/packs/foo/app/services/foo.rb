@@ -0,0 +1,5 @@
+module Foo
+  def bar
+    Bar
+  end
+end
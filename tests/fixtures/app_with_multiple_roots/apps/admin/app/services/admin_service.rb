@@ -0,0 +1,6 @@
+class AdminService
+  def call
+    User
+    Billing
+  end
+end
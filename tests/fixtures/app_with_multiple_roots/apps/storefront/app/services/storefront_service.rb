@@ -0,0 +1,6 @@
+class StorefrontService
+  def call
+    User
+    Billing
+  end
+end
@@ -0,0 +1,5 @@
+# Not moved to packs/billing yet
+module Billing
+  class Invoice
+  end
+end
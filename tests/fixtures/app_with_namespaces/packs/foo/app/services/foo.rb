@@ -0,0 +1,5 @@
+class Foo
+  def bill
+    Billing::Invoice.new
+  end
+end
@@ -0,0 +1,4 @@
+module Billing
+  class Payment
+  end
+end
@@ -0,0 +1,6 @@
+module Billing
+  module Tax
+    class Calculator
+    end
+  end
+end
@@ -0,0 +1,10 @@
+module Billing
+  class Invoice
+    def pay
+      Payment.new
+      Payment::Receipt.new
+      ::Payment::Receipt.new
+      Tax::Rate
+    end
+  end
+end
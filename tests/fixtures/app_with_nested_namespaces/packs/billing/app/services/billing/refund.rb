@@ -0,0 +1,5 @@
+class Billing::Refund
+  def pay
+    Payment.new
+  end
+end
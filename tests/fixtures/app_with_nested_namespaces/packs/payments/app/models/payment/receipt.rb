@@ -0,0 +1,4 @@
+class Payment
+  class Receipt
+  end
+end
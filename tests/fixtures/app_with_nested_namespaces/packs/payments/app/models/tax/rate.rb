@@ -0,0 +1,4 @@
+module Tax
+  class Rate
+  end
+end
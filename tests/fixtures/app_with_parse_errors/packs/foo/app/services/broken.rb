@@ -0,0 +1,7 @@
+module Foo
+  class Broken
+    def call
+      Bar.new(
+    end
+  end
+end
@@ -0,0 +1,15 @@
+module Foo
+  class Api
+    def self.build
+      new
+    end
+
+    def call
+      Internal.new.run
+    end
+
+    private
+
+    def log; end
+  end
+end
@@ -0,0 +1,5 @@
+module Foo
+  class Internal
+    def run; end
+  end
+end
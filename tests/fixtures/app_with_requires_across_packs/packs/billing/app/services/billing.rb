@@ -0,0 +1,7 @@
+require "json"
+require "ledger/formatting"
+require_relative "../../../ledger/app/services/ledger/entry"
+require_relative "../../../ledger/app/public/ledger/api"
+
+module Billing
+end
@@ -0,0 +1,4 @@
+module Ledger
+  class Api
+  end
+end
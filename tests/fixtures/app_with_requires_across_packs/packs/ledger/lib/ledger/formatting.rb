@@ -0,0 +1,4 @@
+module Ledger
+  module Formatting
+  end
+end
@@ -0,0 +1,3 @@
+module Widgets
+  Object.const_set(:Widget, Class.new)
+end
@@ -0,0 +1,5 @@
+module Foo
+  def self.build
+    Widget.new
+  end
+end
@@ -0,0 +1,5 @@
+module Foo
+  def recently_recorded
+    Bar
+  end
+end
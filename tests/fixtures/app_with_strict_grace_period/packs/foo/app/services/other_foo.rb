@@ -0,0 +1,5 @@
+module OtherFoo
+  def recorded_long_ago
+    Bar
+  end
+end
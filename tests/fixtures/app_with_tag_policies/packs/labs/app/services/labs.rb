@@ -0,0 +1,5 @@
+module Labs
+  def self.checkout
+    Checkout.new
+  end
+end
@@ -0,0 +1,5 @@
+module Payments
+  def self.charge
+    Labs::FraudModel.score
+  end
+end
@@ -0,0 +1,5 @@
+class Bar
+  def call
+    Dynamic.build
+  end
+end
@@ -0,0 +1,7 @@
+class Foo
+  def call
+    Bar.new
+    Dynamic.build
+    Dynamic.build
+  end
+end
@@ -0,0 +1,9 @@
+module Baz
+  def calls_bar_without_a_stated_dependency
+    Bar.call
+  end
+
+  def calls_bar_from_new_code
+    Bar.destroy_everything
+  end
+end
@@ -0,0 +1,12 @@
+module Foo
+  # A comment added after the violations were recorded,
+  # which shifts their lines
+
+  def calls_bar_without_a_stated_dependency
+    Bar.call
+  end
+
+  def calls_bar_again
+    Bar.new
+  end
+end
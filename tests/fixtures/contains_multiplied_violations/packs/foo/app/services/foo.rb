@@ -0,0 +1,9 @@
+module Foo
+  def calls_bar_without_a_stated_dependency
+    Bar
+  end
+
+  def calls_bar_again
+    Bar
+  end
+end
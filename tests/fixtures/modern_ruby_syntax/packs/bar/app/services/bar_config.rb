@@ -0,0 +1,4 @@
+class BarConfig
+  def call
+  end
+end
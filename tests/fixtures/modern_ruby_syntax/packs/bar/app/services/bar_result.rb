@@ -0,0 +1,4 @@
+class BarResult
+  def call
+  end
+end
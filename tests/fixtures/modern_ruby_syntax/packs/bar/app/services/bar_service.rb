@@ -0,0 +1,4 @@
+class BarService
+  def call
+  end
+end
@@ -0,0 +1,12 @@
+class Foo
+  def value_of(response)
+    case response
+    in BarResult(value:)
+      value
+    end
+  end
+
+  def service = BarService.new
+
+  def options = {BarConfig:}
+end
@@ -0,0 +1,5 @@
+module Bar
+  class Api
+    def call; end
+  end
+end
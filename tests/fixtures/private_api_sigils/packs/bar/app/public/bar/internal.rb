@@ -0,0 +1,7 @@
+# pack_private: true
+
+module Bar
+  class Internal
+    def call; end
+  end
+end
@@ -0,0 +1,7 @@
+# pack_public: true
+
+module Bar
+  class Helper
+    def call; end
+  end
+end
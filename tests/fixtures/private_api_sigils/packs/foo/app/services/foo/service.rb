@@ -0,0 +1,11 @@
+module Foo
+  class Service
+    def call
+      [
+        Bar::Api,
+        Bar::Internal,
+        Bar::Helper,
+      ]
+    end
+  end
+end
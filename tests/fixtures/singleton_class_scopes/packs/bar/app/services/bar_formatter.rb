@@ -0,0 +1,5 @@
+module BarFormatter
+  def self.format(value)
+    value.to_s
+  end
+end
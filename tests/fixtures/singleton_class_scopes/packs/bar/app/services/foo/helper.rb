@@ -0,0 +1,4 @@
+class Foo::Helper
+  def call
+  end
+end
@@ -0,0 +1,11 @@
+class Foo
+  class << self
+    # A constant of the singleton class, rather than `Foo::Helper`
+    class Helper
+    end
+
+    def build
+      BarService.new
+    end
+  end
+end
@@ -0,0 +1,7 @@
+module Formatting
+  module_function
+
+  def format(value)
+    BarFormatter.format(value)
+  end
+end
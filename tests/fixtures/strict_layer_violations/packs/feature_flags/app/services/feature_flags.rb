@@ -0,0 +1,2 @@
+# feature_flags, a utility pack, should not rely on Payments, a product pack
+Payments
@@ -0,0 +1,5 @@
+module Baz
+  def calls_bar
+    Bar
+  end
+end
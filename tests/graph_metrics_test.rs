@@ -0,0 +1,41 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_graph_metrics() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_dependency_cycles")
+        .arg("graph-metrics")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Packs: 4"))
+        .stdout(predicate::str::contains("Edges: 2"))
+        .stdout(predicate::str::contains("Cycles: 1"))
+        .stdout(predicate::str::contains("Condensation size: 3"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_graph_metrics_of_violations() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("graph-metrics")
+        .arg("--edges")
+        .arg("violations")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"edges\": 1"))
+        .stdout(predicate::str::contains("\"cycles\": 0"));
+
+    common::teardown();
+    Ok(())
+}
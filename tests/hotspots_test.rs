@@ -0,0 +1,48 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::{error::Error, fs, process::Command};
+mod common;
+
+#[test]
+#[serial]
+fn test_hotspots() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("contains_package_todo");
+    common::commit_all(&project_root);
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("hotspots")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Packs:\n  packs/foo: score 2, 1 commit(s), 2 violation(s)\n",
+        ))
+        .stdout(predicate::str::contains(
+            "  packs/foo/app/services/foo.rb: score 1, 1 commit(s), 1 violation(s)\n",
+        ));
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_hotspots_since() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("contains_package_todo");
+    common::commit_all(&project_root);
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("hotspots")
+        .arg("--since")
+        .arg("2999-01-01")
+        .assert()
+        .success()
+        .stdout("No changed files with violations\n");
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+
+mod common;
+
+#[test]
+fn test_info() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("info")
+        .arg("packs/foo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pack: packs/foo"))
+        .stdout(predicate::str::contains(
+            "Public paths: packs/foo/app/public",
+        ))
+        .stdout(predicate::str::contains("- enforce_dependencies: true"))
+        .stdout(predicate::str::contains("- enforce_layers: false"))
+        .stdout(predicate::str::contains("Dependencies (1):\n- packs/baz"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_info_json() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("info")
+        .arg("packs/bar")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"packs/bar\""))
+        .stdout(predicate::str::contains("\"incoming\": 2"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_info_unknown_pack() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("info")
+        .arg("packs/does_not_exist")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No pack found 'packs/does_not_exist'",
+        ));
+
+    common::teardown();
+    Ok(())
+}
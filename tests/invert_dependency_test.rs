@@ -0,0 +1,106 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs};
+
+mod common;
+
+#[test]
+fn test_invert_dependency() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_to_invert")
+        .arg("invert-dependency")
+        .arg("packs/a")
+        .arg("packs/b")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "`packs/a` depends on `packs/b` through 1 reference(s):",
+        ))
+        .stdout(predicate::str::contains("  packs/a/app/services/a.rb:3:"))
+        .stdout(predicate::str::contains(
+            "Move 2 file(s) into `packs/b_interface`:",
+        ))
+        .stdout(predicate::str::contains(
+            "  packs/b/app/models/result.rb (::Result)",
+        ))
+        .stdout(predicate::str::contains(
+            "  packs/b/app/services/api.rb (::Api)",
+        ))
+        .stdout(predicate::str::contains(
+            "Packs that would also depend on `packs/b_interface`: packs/b, packs/c",
+        ))
+        .stdout(predicate::str::contains(
+            "`packs/b_interface` would depend on: packs/utils",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_invert_dependency_with_apply() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_to_invert");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("invert-dependency")
+        .arg("packs/a")
+        .arg("packs/b")
+        .arg("--new-pack")
+        .arg("packs/b_api")
+        .arg("--apply")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Created `packs/b_api`, moving 2 file(s)",
+        ))
+        .stdout(predicate::str::contains(
+            "Updated the dependencies of `packs/a`",
+        ));
+
+    assert!(project_root
+        .join("packs/b_api/app/services/api.rb")
+        .exists());
+    assert!(project_root
+        .join("packs/b_api/app/models/result.rb")
+        .exists());
+    assert!(!project_root.join("packs/b/app/services/api.rb").exists());
+    let a_yml = fs::read_to_string(project_root.join("packs/a/package.yml"))?;
+    assert!(a_yml.contains("- packs/b_api"));
+    assert!(!a_yml.contains("- packs/b\n"));
+    let new_pack_yml =
+        fs::read_to_string(project_root.join("packs/b_api/package.yml"))?;
+    assert!(new_pack_yml.contains("- packs/utils"));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    fs::remove_dir_all(project_root)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_invert_dependency_on_itself() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_to_invert")
+        .arg("invert-dependency")
+        .arg("packs/a")
+        .arg("packs/a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot invert the dependency of `packs/a` on itself",
+        ));
+
+    common::teardown();
+    Ok(())
+}
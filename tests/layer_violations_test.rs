@@ -39,3 +39,25 @@ fn test_check_enforce_layers_disabled() -> Result<(), Box<dyn Error>> {
     common::teardown();
     Ok(())
 }
+
+#[test]
+fn test_check_with_strict_mode() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/strict_layer_violations")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output =
+        String::from_utf8_lossy(&strip_ansi_escapes::strip(output)).to_string();
+
+    // `enforce_architecture` is the deprecated name of `enforce_layers`
+    assert!(stripped_output.contains("packs/feature_flags (whose layer is `utilities`) cannot have layer violations on packs/payments (whose layer is `product`) because strict mode is enabled for layer violations in the enforcing pack's package.yml file"));
+
+    common::teardown();
+    Ok(())
+}
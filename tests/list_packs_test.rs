@@ -16,3 +16,40 @@ fn lint_packs() -> Result<(), Box<dyn Error>> {
         .stdout(predicate::str::contains("packs/foo/package.yml"));
     Ok(())
 }
+
+#[test]
+fn list_packs_with_filters() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("list-packs")
+        .arg("--enforcing")
+        .arg("dependencies")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("packs/foo/package.yml"))
+        .stdout(predicate::str::contains("packs/bar/package.yml").not());
+    Ok(())
+}
+
+#[test]
+fn list_packs_with_json_format() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("list-packs")
+        .arg("--has-todos")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["name"], "packs/foo");
+    assert_eq!(json[0]["todo_counts"]["outgoing"], 2);
+    Ok(())
+}
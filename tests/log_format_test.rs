@@ -0,0 +1,42 @@
+use assert_cmd::prelude::*;
+use std::{error::Error, process::Command};
+mod common;
+
+#[test]
+fn test_check_with_json_logs() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--log-format")
+        .arg("json")
+        .arg("--quiet")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+
+    let spans: Vec<serde_json::Value> = String::from_utf8(output)?
+        .lines()
+        .map(serde_json::from_str::<serde_json::Value>)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|line| line["type"] == "span")
+        .collect();
+    let stages: Vec<&str> = spans
+        .iter()
+        .map(|span| span["stage"].as_str().unwrap())
+        .collect();
+    assert_eq!(vec!["walk", "parse", "resolve", "check"], stages);
+
+    let parse = &spans[1];
+    assert!(parse["duration_seconds"].as_f64().is_some());
+    assert_eq!(parse["fields"]["cache_hits"], 0);
+    assert_eq!(parse["fields"]["cache_hit_rate"], 0.0);
+    assert_eq!(parse["fields"]["files"], parse["fields"]["cache_misses"]);
+    assert_eq!(spans[3]["fields"]["violations"], 2);
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs};
+
+mod common;
+
+#[test]
+fn test_merge() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_to_merge");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("merge")
+        .arg("packs/absorbed")
+        .arg("packs/into")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Merged `packs/absorbed` into `packs/into`, moving 1 file(s)",
+        ))
+        .stdout(predicate::str::contains(
+            "Updated `packs/dependent` to refer to `packs/into`",
+        ))
+        .stdout(predicate::str::contains(
+            "Updated `packs/other` to refer to `packs/into`",
+        ))
+        .stdout(predicate::str::contains(
+            "No violations found after merging!",
+        ));
+
+    assert!(!project_root.join("packs/absorbed").exists());
+    assert!(project_root
+        .join("packs/into/app/services/absorbed.rb")
+        .exists());
+
+    let into_yml =
+        fs::read_to_string(project_root.join("packs/into/package.yml"))?;
+    assert!(into_yml.contains("enforce_dependencies: strict"));
+    assert!(into_yml.contains("- packs/shared"));
+    // The recorded reference from packs/absorbed to packs/into is now within
+    // packs/into
+    assert!(!project_root.join("packs/into/package_todo.yml").exists());
+
+    let dependent_yml =
+        fs::read_to_string(project_root.join("packs/dependent/package.yml"))?;
+    assert!(dependent_yml.contains("- packs/into"));
+    assert!(!dependent_yml.contains("packs/absorbed"));
+
+    let other_todo =
+        fs::read_to_string(project_root.join("packs/other/package_todo.yml"))?;
+    assert!(other_todo.contains("packs/into:\n  \"::Absorbed\":"));
+
+    fs::remove_dir_all(project_root)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_merge_into_itself() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_to_merge")
+        .arg("merge")
+        .arg("packs/into")
+        .arg("packs/into")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot merge `packs/into` into itself",
+        ));
+
+    common::teardown();
+    Ok(())
+}
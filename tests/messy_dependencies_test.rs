@@ -0,0 +1,49 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, fs, process::Command};
+mod common;
+
+#[test]
+fn test_validate_duplicate_dependencies() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_messy_dependencies")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/foo/package.yml lists these dependencies more than once: packs/bar. Run `packs validate --fix` to remove the duplicates.",
+        ))
+        .stdout(predicate::str::contains(
+            "has 'packs/deleted' in its dependencies, but that pack cannot be found",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_fix_messy_dependencies() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_with_messy_dependencies");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("validate")
+        .arg("--fix")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Removed duplicate, self, and unknown dependencies from `packs/foo`",
+        ))
+        .stdout(predicate::str::contains("Packwerk validate succeeded!"));
+
+    assert_eq!(
+        "enforce_dependencies: true\ndependencies:\n- packs/bar\n",
+        fs::read_to_string(project_root.join("packs/foo/package.yml"))?
+    );
+
+    fs::remove_dir_all(project_root)?;
+    common::teardown();
+    Ok(())
+}
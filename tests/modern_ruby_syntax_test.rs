@@ -0,0 +1,42 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+fn assert_modern_syntax_references_are_checked(
+    extra_args: &[&str],
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/modern_ruby_syntax")
+        .args(extra_args)
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("3 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::BarResult` belongs to `packs/bar`",
+        ))
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::BarService` belongs to `packs/bar`",
+        ))
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::BarConfig` belongs to `packs/bar`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_finds_references_in_modern_syntax() -> Result<(), Box<dyn Error>>
+{
+    assert_modern_syntax_references_are_checked(&[])
+}
+
+#[test]
+fn test_check_finds_references_in_modern_syntax_with_experimental_parser(
+) -> Result<(), Box<dyn Error>> {
+    assert_modern_syntax_references_are_checked(&["--experimental-parser"])
+}
@@ -0,0 +1,40 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+
+mod common;
+
+#[test]
+fn test_check_with_multiple_roots() -> Result<(), Box<dyn Error>> {
+    // Both apps define `::User`, which each resolves within its own root
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_multiple_roots")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains("Dependency violation: `::Billing` belongs to `components/billing`, but `apps/admin/package.yml` does not specify a dependency on `components/billing`."))
+        .stdout(predicate::str::contains("Dependency violation: `::Billing` belongs to `components/billing`, but `apps/storefront/package.yml` does not specify a dependency on `components/billing`."))
+        .stdout(predicate::str::contains("`::User`").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_multiple_roots_and_experimental_parser(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_multiple_roots")
+        .arg("--experimental-parser")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains("`::User`").not());
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs};
+
+mod common;
+
+#[test]
+fn test_mv_pack_dry_run() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_to_move")
+        .arg("mv-pack")
+        .arg("packs/foo")
+        .arg("packs/customer/foo")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Move `packs/foo` to `packs/customer/foo`\n\
+             Update the package.yml of: packs/bar\n\
+             Update the package_todo.yml of: packs/bar\n\
+             Update packwerk.yml on line(s): 7\n",
+        ))
+        .stdout(predicate::str::contains("Moved").not());
+
+    assert!(
+        std::path::Path::new("tests/fixtures/app_to_move/packs/foo").exists()
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_mv_pack() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_to_move");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("mv-pack")
+        .arg("packs/foo")
+        .arg("packs/customer/foo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Moved `packs/foo` to `packs/customer/foo`",
+        ));
+
+    assert!(!project_root.join("packs/foo").exists());
+    assert!(project_root
+        .join("packs/customer/foo/app/services/foo.rb")
+        .exists());
+    let bar_yml =
+        fs::read_to_string(project_root.join("packs/bar/package.yml"))?;
+    assert!(bar_yml.contains("- packs/customer/foo"));
+    let bar_todo =
+        fs::read_to_string(project_root.join("packs/bar/package_todo.yml"))?;
+    assert!(bar_todo.contains("packs/customer/foo:"));
+    let foo_todo = fs::read_to_string(
+        project_root.join("packs/customer/foo/package_todo.yml"),
+    )?;
+    assert!(foo_todo.contains("- packs/customer/foo/app/services/foo.rb"));
+    let packwerk_yml = fs::read_to_string(project_root.join("packwerk.yml"))?;
+    assert!(packwerk_yml.contains("    - packs/customer/foo\n"));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    fs::remove_dir_all(project_root)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_mv_pack_to_existing_directory() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_to_move")
+        .arg("mv-pack")
+        .arg("packs/foo")
+        .arg("packs/bar")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot move `packs/foo`, since packs/bar already exists",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+
+mod common;
+
+#[test]
+fn test_parity_without_packwerk_in_gemfile() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("parity")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "packwerk was not found in the Gemfile",
+        ));
+
+    common::teardown();
+    Ok(())
+}
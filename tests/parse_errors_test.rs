@@ -0,0 +1,95 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_warns_about_unparseable_files() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_parse_errors")
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Warning: 1 file(s) could not be parsed",
+        ))
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/broken.rb:",
+        ))
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_strict_parsing() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_parse_errors")
+        .arg("check")
+        .arg("--strict-parsing")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/broken.rb:",
+        ))
+        .stderr(predicate::str::contains("Files could not be parsed!"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_strict_parsing_and_parseable_files(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_parse_errors")
+        .arg("check")
+        .arg("--strict-parsing")
+        .arg("packs/foo/app/services/foo.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"))
+        .stdout(predicate::str::contains("could not be parsed").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_parse_errors() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_parse_errors")
+        .arg("parse-errors")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/broken.rb:",
+        ))
+        .stdout(predicate::str::contains("foo.rb").not())
+        .stderr(predicate::str::contains("1 file(s) could not be parsed"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_parse_errors_without_any() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("parse-errors")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "All included files were parsed successfully!",
+        ));
+
+    common::teardown();
+    Ok(())
+}
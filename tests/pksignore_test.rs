@@ -0,0 +1,53 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+mod common;
+
+#[test]
+fn test_pksignore_excludes_files() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pksignore")
+        .arg("list-included-files")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb"))
+        .stdout(predicate::str::contains("old_foo.rb").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_todo_in_ignored_file() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pksignore")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("violation(s) detected").not())
+        .stdout(predicate::str::contains(
+            "There were stale violations found, please run `packs update`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_subset_with_todo_in_ignored_file() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pksignore")
+        .arg("check")
+        .arg("packs/foo/app/services/foo.rb")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "There were stale violations found, please run `packs update`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
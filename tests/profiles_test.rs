@@ -0,0 +1,104 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_without_profile() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_profiles")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_strict_profile() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_profiles")
+        .arg("--profile")
+        .arg("ci")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("\"strict_mode_violations\": [\n"))
+        .stdout(predicate::str::contains(
+            "packs/foo cannot have dependency violations on packs/bar because strict mode is enabled for all violations by the profile",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_check_with_strict_profile_after_cached_check(
+) -> Result<(), Box<dyn Error>> {
+    common::teardown();
+    // Caches the violations found without the profile
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_cached_profiles")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_cached_profiles")
+        .arg("--profile")
+        .arg("ci")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/foo cannot have dependency violations on packs/bar because strict mode is enabled for all violations by the profile",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_profile_disabling_checkers() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_profiles")
+        .arg("--profile")
+        .arg("no_privacy")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("Dependency violation: `::Bar`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_unknown_profile() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_profiles")
+        .arg("--profile")
+        .arg("dev")
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unknown profile `dev`. Profiles in packwerk.yml: ci, no_privacy",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+mod common;
+
+#[test]
+fn test_check_with_json_progress() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--progress")
+        .arg("json")
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            r#""stage":"parsing","completed":"#,
+        ))
+        .stderr(predicate::str::contains(
+            r#""stage":"checking","completed":"#,
+        ))
+        .stderr(predicate::str::contains(r#""done":true"#));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_quiet_progress() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("--progress")
+        .arg("json")
+        .arg("--quiet")
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("stage").not());
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, fs, process::Command};
+mod common;
+use pretty_assertions::assert_eq;
+
+const PACKAGE_TODO_PATH: &str =
+    "tests/fixtures/contains_stale_violations_no_file/packs/bar/package_todo.yml";
+
+#[test]
+fn test_prune_todos() -> Result<(), Box<dyn Error>> {
+    let original = fs::read_to_string(PACKAGE_TODO_PATH)?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_stale_violations_no_file")
+        .arg("prune-todos")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Removed 1 recorded violation(s) in deleted files from package_todo.yml files",
+        ));
+
+    let actual = fs::read_to_string(PACKAGE_TODO_PATH)?;
+    fs::write(PACKAGE_TODO_PATH, original)?;
+
+    let expected = "\
+# This file contains a list of dependencies that are not part of the long term plan for the
+# 'packs/bar' package.
+# We should generally work to reduce this list over time.
+#
+# You can regenerate this file using the following command:
+#
+# bin/packwerk update-todo
+---
+packs/foo:
+  \"::Foo\":
+    violations:
+    - privacy
+    files:
+    - packs/bar/app/services/bar.rb
+";
+    assert_eq!(expected, actual);
+
+    common::teardown();
+    Ok(())
+}
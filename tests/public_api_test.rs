@@ -0,0 +1,74 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_public_api() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_public_api")
+        .arg("public-api")
+        .arg("packs/foo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "packs/foo exports 1 constant(s) from packs/foo/app/public\n::Foo::Api (class, packs/foo/app/public/foo/api.rb)\n  #call\n  .build\n",
+        ))
+        .stdout(predicate::str::contains("Internal").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_public_api_json() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_public_api")
+        .arg("public-api")
+        .arg("packs/foo")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"::Foo::Api\""))
+        .stdout(predicate::str::contains("\"kind\": \"class\""));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_public_entrypoints() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_public_api")
+        .arg("public-entrypoints")
+        .arg("::Foo::Internal")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "::Foo::Internal is private to packs/foo. 1 public constant(s) reference it:\n::Foo::Api (class, packs/foo/app/public/foo/api.rb:8)\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_public_entrypoints_of_undefined_constant() -> Result<(), Box<dyn Error>>
+{
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_public_api")
+        .arg("public-entrypoints")
+        .arg("::Foo::Missing")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`::Foo::Missing` is not defined"));
+
+    common::teardown();
+    Ok(())
+}
@@ -1,9 +1,18 @@
 use assert_cmd::Command;
+use predicates::prelude::*;
 
 mod common;
 
 use regex::Regex;
 
+// The elapsed time in the summary varies from run to run
+fn normalize_elapsed_time(stdout: &str) -> String {
+    Regex::new(r"Elapsed time: [0-9.]+s")
+        .unwrap()
+        .replace(stdout, "Elapsed time: <elapsed>")
+        .into_owned()
+}
+
 #[test]
 fn test_pack_with_public_api_exposed_via_sigil(
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -21,13 +30,19 @@ fn test_pack_with_public_api_exposed_via_sigil(
     let ansi_escape =
         Regex::new(r"\x1B\[([0-9]{1,2}(;[0-9]{1,2})?)?[m|K]").unwrap();
     let stdout = ansi_escape.replace_all(&stdout_with_ansi, "");
+    let stdout = normalize_elapsed_time(&stdout);
 
     // Define the expected output as a multiline string
     let expected_output = r#"1 violation(s) detected:
 packs/foo/app/domain/foo/api.rb:7:8
 Privacy violation: `::Bar::Api3` is private to `packs/bar`, but referenced from `packs/foo`
 
-
+Summary:
+  Files analyzed: 4
+  New violations: 1 (privacy: 1)
+  Recorded violations: 0
+  Stale violations: 0
+  Elapsed time: <elapsed>
 "#;
 
     // Verify the process fails
@@ -62,13 +77,19 @@ fn test_pack_with_public_api_exposed_via_sigil_with_single_fine_input(
     let ansi_escape =
         Regex::new(r"\x1B\[([0-9]{1,2}(;[0-9]{1,2})?)?[m|K]").unwrap();
     let stdout = ansi_escape.replace_all(&stdout_with_ansi, "");
+    let stdout = normalize_elapsed_time(&stdout);
 
     // Define the expected output as a multiline string
     let expected_output = r#"1 violation(s) detected:
 packs/foo/app/domain/foo/api.rb:7:8
 Privacy violation: `::Bar::Api3` is private to `packs/bar`, but referenced from `packs/foo`
 
-
+Summary:
+  Files analyzed: 1
+  New violations: 1 (privacy: 1)
+  Recorded violations: 0
+  Stale violations: 0
+  Elapsed time: <elapsed>
 "#;
 
     // Verify the process fails
@@ -99,13 +120,19 @@ fn test_pack_with_public_api_exposed_via_sigil_with_experimental_parser(
     let ansi_escape =
         Regex::new(r"\x1B\[([0-9]{1,2}(;[0-9]{1,2})?)?[m|K]").unwrap();
     let stdout = ansi_escape.replace_all(&stdout_with_ansi, "");
+    let stdout = normalize_elapsed_time(&stdout);
 
     // Define the expected output as a multiline string
     let expected_output = r#"1 violation(s) detected:
 packs/foo/app/domain/foo/api.rb:7:8
 Privacy violation: `::Bar::Api3` is private to `packs/bar`, but referenced from `packs/foo`
 
-
+Summary:
+  Files analyzed: 4
+  New violations: 1 (privacy: 1)
+  Recorded violations: 0
+  Stale violations: 0
+  Elapsed time: <elapsed>
 "#;
 
     // Verify the process fails
@@ -117,3 +144,39 @@ Privacy violation: `::Bar::Api3` is private to `packs/bar`, but referenced from
     common::teardown();
     Ok(())
 }
+
+#[test]
+fn test_private_sigil_overrides_public_path(
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/private_api_sigils")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "Privacy violation: `::Bar::Internal` is private to `packs/bar`, but referenced from `packs/foo`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_public_api_honors_sigils() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/private_api_sigils")
+        .arg("public-api")
+        .arg("packs/bar")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("packs/bar exports 2 constant(s)"))
+        .stdout(predicate::str::contains("::Bar::Api (class"))
+        .stdout(predicate::str::contains("::Bar::Helper (class"))
+        .stdout(predicate::str::contains("::Bar::Internal").not());
+
+    common::teardown();
+    Ok(())
+}
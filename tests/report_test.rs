@@ -0,0 +1,183 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::{error::Error, fs, path::Path, process::Command};
+mod common;
+
+#[test]
+fn test_report_html() -> Result<(), Box<dyn Error>> {
+    let output = Path::new("tests/fixtures/contains_package_todo/tmp/report");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("report")
+        .arg("--format")
+        .arg("html")
+        .arg("--output")
+        .arg(output)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote report to"));
+
+    let index = fs::read_to_string(output.join("index.html"))?;
+    assert!(index.contains(
+        "3 pack(s), 2 violation(s): 0 new, 2 recorded in package_todo.yml files"
+    ));
+    assert!(index.contains("<a href=\"packs/packs--foo.html\">packs/foo</a>"));
+
+    let foo_page = fs::read_to_string(output.join("packs/packs--foo.html"))?;
+    assert!(foo_page.contains("packs/foo/app/services/other_foo.rb"));
+    assert!(output.join("packs/root.html").exists());
+
+    fs::remove_dir_all(output)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_report_by_owner() -> Result<(), Box<dyn Error>> {
+    let output =
+        Path::new("tests/fixtures/contains_package_todo/tmp/owner_report");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("report")
+        .arg("--by-owner")
+        .arg("--output")
+        .arg(output)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote 1 owner digest(s) to"));
+
+    let digest = fs::read_to_string(output.join("owners/unowned.md"))?;
+    assert!(digest.contains("# Violations of `unowned`"));
+    assert!(digest.contains(
+        "2 violation(s): 0 new, 2 recorded in package_todo.yml files"
+    ));
+    assert!(digest.contains("## From `packs/foo`"));
+    assert!(digest.contains(
+        "| dependency | `::Bar` | packs/foo | packs/bar | packs/foo/app/services/other_foo.rb |"
+    ));
+
+    fs::remove_dir_all(output)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_report_by_owner_with_json_output() -> Result<(), Box<dyn Error>> {
+    let output =
+        Path::new("tests/fixtures/contains_package_todo/tmp/owner_json_report");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("report")
+        .arg("--by-owner")
+        .arg("--by-defining-owner")
+        .arg("--format")
+        .arg("json")
+        .arg("--output")
+        .arg(output)
+        .assert()
+        .success();
+
+    let digest: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output.join("owners/unowned.json"),
+    )?)?;
+    assert_eq!(digest["owner"], "unowned");
+    assert_eq!(digest["recorded_violations"], 2);
+    assert_eq!(digest["groups"]["unowned"].as_array().unwrap().len(), 2);
+
+    fs::remove_dir_all(output)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_report_markdown_requires_by_owner() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("report")
+        .arg("--format")
+        .arg("markdown")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pass `--by-owner`"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_report_markdown_since_base() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("contains_package_todo");
+    common::commit_all(&project_root);
+    fs::write(
+        project_root.join("packs/foo/app/services/other_foo.rb"),
+        "module OtherFoo\nend\n",
+    )?;
+    fs::write(
+        project_root.join("packs/foo/app/services/new_foo.rb"),
+        "module NewFoo\n  def bar\n    Bar\n  end\nend\n",
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("report")
+        .arg("--format")
+        .arg("markdown")
+        .arg("--base")
+        .arg("HEAD")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Since `HEAD`: 1 new violation(s), 1 resolved.",
+        ))
+        .stdout(predicate::str::contains(
+            "| dependency | `::Bar` | packs/foo | packs/bar | packs/foo/app/services/new_foo.rb:3 | **not recorded** |",
+        ))
+        .stdout(predicate::str::contains(
+            "| dependency | `::Bar` | packs/foo | packs/bar | packs/foo/app/services/other_foo.rb |",
+        ))
+        .stdout(predicate::str::contains("Violations by pack").not());
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_report_links_to_code() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("contains_package_todo");
+    let mut packwerk_yml =
+        fs::read_to_string(project_root.join("packwerk.yml"))?;
+    packwerk_yml.push_str(
+        "\ncode_url: https://github.com/acme/app/blob/main/{file}#L{line}\n",
+    );
+    fs::write(project_root.join("packwerk.yml"), packwerk_yml)?;
+    let output = project_root.join("tmp/report");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("report")
+        .arg("--by-owner")
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .success();
+
+    let digest = fs::read_to_string(output.join("owners/unowned.md"))?;
+    assert!(digest.contains(
+        "| [packs/foo/app/services/other_foo.rb](https://github.com/acme/app/blob/main/packs/foo/app/services/other_foo.rb#L3) | 3 |"
+    ));
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
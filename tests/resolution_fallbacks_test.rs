@@ -0,0 +1,22 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_resolves_constants_from_ctags() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_resolution_fallbacks")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::Widget` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`.",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+fn assert_singleton_class_scopes_are_checked(
+    extra_args: &[&str],
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/singleton_class_scopes")
+        .args(extra_args)
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::BarService` belongs to `packs/bar`",
+        ))
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::BarFormatter` belongs to `packs/bar`",
+        ))
+        .stdout(predicate::str::contains("`::Foo::Helper`").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_resolves_references_in_singleton_classes(
+) -> Result<(), Box<dyn Error>> {
+    assert_singleton_class_scopes_are_checked(&[])
+}
+
+#[test]
+fn test_check_resolves_references_in_singleton_classes_with_experimental_parser(
+) -> Result<(), Box<dyn Error>> {
+    assert_singleton_class_scopes_are_checked(&["--experimental-parser"])
+}
@@ -0,0 +1,88 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, fs, process::Command};
+mod common;
+
+#[test]
+fn test_stats() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "packs/foo: 1 pack(s), 2 recorded violation(s) (dependency: 2)",
+        ))
+        .stdout(predicate::str::contains(
+            "Total: 3 pack(s), 2 recorded violation(s) (dependency: 2)",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_stats_by_product_group() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_product_groups")
+        .arg("stats")
+        .arg("--by-product-group")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "(ungrouped): 1 pack(s), 0 recorded violation(s)",
+        ))
+        .stdout(predicate::str::contains(
+            "commerce: 2 pack(s), 1 recorded violation(s) (privacy: 1)",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_stats_record_and_diff() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("contains_package_todo");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("stats")
+        .arg("--record")
+        .assert()
+        .success();
+    assert!(project_root.join(".pks/stats-history.jsonl").exists());
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("stats")
+        .arg("--diff")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Since "))
+        .stdout(predicate::str::contains(
+            "2 -> 2 recorded violation(s) (+0.0%)",
+        ))
+        .stdout(predicate::str::contains("  dependency: 2 -> 2 (+0)"));
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
+
+#[test]
+fn test_stats_diff_without_history() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("stats")
+        .arg("--diff")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pks stats --record"));
+
+    common::teardown();
+    Ok(())
+}
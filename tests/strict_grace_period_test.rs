@@ -0,0 +1,51 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, fs, process::Command};
+mod common;
+
+const STRICT_MESSAGE: &str = "packs/foo cannot have privacy violations on packs/bar because strict mode is enabled for privacy violations in the enforcing pack's package.yml file";
+
+#[test]
+fn test_check_with_strict_mode_grace_period() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_strict_grace_period")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    // Only the violation recorded before the grace period is strict
+    let output = String::from_utf8_lossy(&output);
+    assert_eq!(output.matches(STRICT_MESSAGE).count(), 1);
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_update_with_strict_mode_grace_period() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_with_strict_grace_period");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("update")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1 strict mode violation(s) detected.",
+        ));
+
+    let package_todo =
+        fs::read_to_string(project_root.join("packs/foo/package_todo.yml"))?;
+    assert!(package_todo.contains("    recorded_on:\n"));
+    assert!(package_todo.contains("packs/foo/app/services/foo.rb: "));
+    assert!(package_todo.contains("2999-01-01"));
+    assert!(!package_todo.contains("other_foo.rb"));
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}
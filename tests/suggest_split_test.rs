@@ -0,0 +1,51 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_suggest_split() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_to_split")
+        .arg("suggest-split")
+        .arg("packs/monolith")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "packs/monolith: 4 file(s), 5 internal reference(s)",
+        ))
+        .stdout(predicate::str::contains(
+            "2 candidate(s), with 1 reference(s) crossing their boundaries",
+        ))
+        .stdout(predicate::str::contains(
+            "Candidate 1: 2 file(s), 2 internal reference(s), 1 cross-boundary reference(s)\n  app/services/billing/ (2 file(s))",
+        ))
+        .stdout(predicate::str::contains(
+            "Candidate 2: 2 file(s), 2 internal reference(s), 1 cross-boundary reference(s)\n  app/services/shipping/ (2 file(s))",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_suggest_split_json() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_to_split")
+        .arg("suggest-split")
+        .arg("packs/monolith")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"cross_boundary_references\": 1"))
+        .stdout(predicate::str::contains(
+            "\"packs/monolith/app/services/billing/charge.rb\"",
+        ));
+
+    common::teardown();
+    Ok(())
+}
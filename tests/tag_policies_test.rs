@@ -0,0 +1,43 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_tag_policies() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_tag_policies")
+        .arg("check")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stripped_output =
+        String::from_utf8_lossy(&strip_ansi_escapes::strip(output)).to_string();
+
+    assert!(stripped_output.contains("1 violation(s) detected:"));
+    assert!(stripped_output.contains("packs/payments/app/services/payments.rb:3:4\nTag policy violation: `::Labs::FraudModel` belongs to `packs/labs`, which `packs/payments` cannot reference because packs tagged `pci` cannot depend on packs tagged `experimental` (PCI code must only use audited code)"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_with_tag_policies() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_tag_policies")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "`packs/checkout/package.yml` depends on `packs/labs`, but packs tagged `pci` cannot depend on packs tagged `experimental` (PCI code must only use audited code)",
+        ));
+
+    common::teardown();
+    Ok(())
+}
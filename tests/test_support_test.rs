@@ -0,0 +1,49 @@
+#![cfg(feature = "test-helpers")]
+
+use packs::test_support::{
+    check, CheckerSetting, ConfigurationBuilder, PackBuilder, ReferenceBuilder,
+};
+use std::error::Error;
+
+#[test]
+fn test_check_in_memory() -> Result<(), Box<dyn Error>> {
+    let configuration = ConfigurationBuilder::new()
+        .pack(
+            PackBuilder::new("packs/foo")
+                .enforce_dependencies(true)
+                .dependency("packs/baz")
+                .build(),
+        )
+        .pack(PackBuilder::new("packs/bar").enforce_privacy(true).build())
+        .pack(
+            PackBuilder::new("packs/baz")
+                .enforce_privacy(CheckerSetting::Strict)
+                .build(),
+        )
+        .build()?;
+    let references = vec![
+        ReferenceBuilder::new("::Bar")
+            .referenced_from("packs/foo", "packs/foo/app/services/foo.rb")
+            .defined_in("packs/bar", "packs/bar/app/services/bar.rb")
+            .at(3, 4)
+            .build(),
+        ReferenceBuilder::new("::Baz")
+            .referenced_from("packs/foo", "packs/foo/app/services/foo.rb")
+            .defined_in("packs/baz", "packs/baz/app/public/baz.rb")
+            .at(7, 4)
+            .build(),
+    ];
+
+    let violations = check(&configuration, &references)?;
+    let descriptions: Vec<String> =
+        violations.iter().map(|v| v.description()).collect();
+    assert_eq!(
+        descriptions,
+        vec![
+            "Dependency violation: `::Bar` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`.",
+            "Privacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`",
+        ]
+    );
+    assert!(violations.iter().all(|v| !v.identifier.strict));
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_unresolved() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_unresolved_constants")
+        .arg("unresolved")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "3 reference(s) to 1 constant(s) could not be attributed to a pack:",
+        ))
+        .stdout(predicate::str::contains(
+            "  Dynamic (3 reference(s) in 2 file(s))",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_unresolved_with_json_format() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_unresolved_constants")
+        .arg("unresolved")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json["references"], 3);
+    assert_eq!(json["constants"][0]["constant"], "Dynamic");
+    assert_eq!(
+        json["constants"][0]["files"],
+        serde_json::json!([
+            "packs/bar/app/services/bar.rb",
+            "packs/foo/app/services/foo.rb"
+        ])
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_report_unresolved_constants() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_unresolved_constants")
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Warning: 3 reference(s) to 1 constant(s) could not be attributed to a pack:",
+        ))
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    common::teardown();
+    Ok(())
+}
@@ -97,6 +97,7 @@ packs/bar:
 }
 
 #[test]
+#[serial]
 fn test_update_with_stale_violations() -> Result<(), Box<dyn Error>> {
     common::set_up_fixtures();
 
@@ -145,6 +146,56 @@ packs/bar:
     Ok(())
 }
 
+#[test]
+#[serial]
+fn test_update_partial() -> Result<(), Box<dyn Error>> {
+    common::set_up_fixtures();
+
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_stale_violations")
+        .arg("update")
+        .arg("--partial")
+        .arg("packs/foo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Successfully updated package_todo.yml files for 1 pack(s)!",
+        ));
+
+    let foo_todo = std::fs::read_to_string(
+        "tests/fixtures/contains_stale_violations/packs/foo/package_todo.yml",
+    )?;
+    assert!(foo_todo.contains("    violations:\n    - privacy\n    files:"));
+
+    // packs/bar's stale todo is left untouched
+    let bar_todo = std::fs::read_to_string(
+        "tests/fixtures/contains_stale_violations/packs/bar/package_todo.yml",
+    )?;
+    assert!(bar_todo.contains("packs/bar/app/services/bar.rb"));
+    common::set_up_fixtures();
+
+    Ok(())
+}
+
+#[test]
+fn test_update_partial_with_unknown_pack() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_stale_violations")
+        .arg("update")
+        .arg("--partial")
+        .arg("packs/missing*")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No pack matches `packs/missing*`"));
+
+    common::teardown();
+    Ok(())
+}
+
 #[test]
 fn test_update_with_packs_first_app() -> Result<(), Box<dyn Error>> {
     Command::cargo_bin("packs")?
@@ -215,3 +266,167 @@ fn test_update_with_strict_violations() -> anyhow::Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_update_with_minimal_diff() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("contains_package_todo");
+    let package_todo_yml_filepath =
+        project_root.join("packs/foo/package_todo.yml");
+    // Out of order, with a file that no longer references `::Bar`
+    std::fs::write(
+        &package_todo_yml_filepath,
+        "\
+# Recorded violations of packs/foo
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    files:
+    - packs/foo/app/services/other_foo.rb
+    - packs/foo/app/services/removed.rb
+    - packs/foo/app/services/foo.rb
+",
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("update")
+        .arg("--minimal-diff")
+        .assert()
+        .success();
+
+    let actual = std::fs::read_to_string(&package_todo_yml_filepath)?;
+    let expected = String::from(
+        "\
+# Recorded violations of packs/foo
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    files:
+    - packs/foo/app/services/other_foo.rb
+    - packs/foo/app/services/foo.rb
+",
+    );
+    std::fs::remove_dir_all(&project_root)?;
+    assert_eq!(expected, actual);
+
+    common::teardown();
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_update_with_json_output() -> Result<(), Box<dyn Error>> {
+    let json_path = "tests/fixtures/simple_app/tmp/artifacts/recorded.json";
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("update")
+        .arg("--out")
+        .arg(format!("json={}", json_path))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Successfully updated package_todo.yml files!",
+        ));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path)?)?;
+    let recorded = json["recorded_violations"].as_array().unwrap();
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(recorded[0]["identifier"]["constant_name"], "::Bar");
+    assert_eq!(json["strict_mode_violations"], serde_json::json!([]));
+
+    std::fs::remove_file(
+        "tests/fixtures/simple_app/packs/foo/package_todo.yml",
+    )?;
+    std::fs::remove_dir_all("tests/fixtures/simple_app/tmp/artifacts")?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_update_with_package_todo_path() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_with_central_todos");
+    let foo_todo = project_root.join(".pks/todos/packs/foo.yml");
+    let baz_todo = project_root.join("packs/baz/todo/package_todo.yml");
+    let expected_foo_todo = std::fs::read_to_string(&foo_todo)?;
+    let expected_baz_todo = std::fs::read_to_string(&baz_todo)?;
+    std::fs::remove_dir_all(project_root.join(".pks"))?;
+    std::fs::remove_file(&baz_todo)?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("update")
+        .assert()
+        .success();
+
+    let actual_foo_todo = std::fs::read_to_string(&foo_todo)?;
+    let actual_baz_todo = std::fs::read_to_string(&baz_todo)?;
+    let default_todo_exists =
+        project_root.join("packs/foo/package_todo.yml").exists();
+    std::fs::remove_dir_all(&project_root)?;
+    assert_eq!(expected_foo_todo, actual_foo_todo);
+    assert_eq!(expected_baz_todo, actual_baz_todo);
+    assert!(!default_todo_exists);
+
+    common::teardown();
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_update_prints_diff() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("contains_stale_violations");
+    let diff_json = project_root.join("tmp/todo_diff.json");
+    std::fs::create_dir_all(project_root.join("tmp"))?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("update")
+        .arg("--diff-json")
+        .arg(&diff_json)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\
+Recorded violations: 0 added, 3 removed in 2 pack(s)
+  dependency: +0 -2
+  privacy: +0 -1
+packs/bar
+  - dependency packs/bar/app/services/bar.rb ::Foo (packs/foo)
+  - privacy packs/bar/app/services/bar.rb ::Foo (packs/foo)
+packs/foo
+  - dependency packs/foo/app/services/foo.rb ::Bar (packs/bar)
+",
+        ));
+
+    let diff: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&diff_json)?)?;
+    assert_eq!(diff["removed"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        diff["packs_touched"],
+        serde_json::json!(["packs/bar", "packs/foo"])
+    );
+
+    // Nothing changes the second time
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("update")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No recorded violations changed"));
+
+    std::fs::remove_dir_all(project_root)?;
+    Ok(())
+}
@@ -70,3 +70,68 @@ fn test_validate_with_referencing_unknown_pack() -> Result<(), Box<dyn Error>> {
     common::teardown();
     Ok(())
 }
+
+#[test]
+fn test_validate_dependency_budgets() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_dependency_budgets")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 validation error(s) detected:"))
+        .stdout(predicate::str::contains("packs/foo/package.yml declares 2 dependencies, but its max_dependencies budget is 1"))
+        .stdout(predicate::str::contains("`packs/bar` is a dependency of 2 packs (packs/baz, packs/foo), but the max_dependents budget in packs/bar/package.yml is 1"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_ambiguous_constants() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_ambiguous_constants")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 validation error(s) detected:"))
+        .stdout(predicate::str::contains(
+            "`::Order` is defined by 2 files:\n  - packs/legacy/app/services/order.rb (packs/legacy)\n  - packs/orders/app/models/order.rb (packs/orders)\nReferences resolve to packs/legacy/app/services/order.rb.",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_gemspec_dependencies() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_gem_packs")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 validation error(s) detected:"))
+        .stdout(predicate::str::contains("packs/billing/package.yml declares a dependency on `packs/ledger`, but packs/billing/billing.gemspec doesn't depend on its gem `ledger`"))
+        .stdout(predicate::str::contains("packs/payments/payments.gemspec depends on the gem `ledger` of `packs/ledger`, but packs/payments/package.yml doesn't declare it as a dependency"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_requires_across_packs() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_requires_across_packs")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 validation error(s) detected:"))
+        .stdout(predicate::str::contains("packs/billing/app/services/billing.rb:2 requires packs/ledger/lib/ledger/formatting.rb, which is private to `packs/ledger`, from `packs/billing`"))
+        .stdout(predicate::str::contains("packs/billing/app/services/billing.rb:3 requires packs/ledger/app/services/ledger/entry.rb, which is private to `packs/ledger`, from `packs/billing`"));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::{error::Error, fs, process::Command};
+mod common;
+
+const PACKAGE_TODO_PATH: &str =
+    "tests/fixtures/app_with_unknown_todo_pack/packs/foo/package_todo.yml";
+
+#[test]
+// Run in serial since the next test modifies the fixture
+#[serial]
+fn test_validate_todo_with_unknown_pack() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_unknown_todo_pack")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 validation error(s) detected:"))
+        .stdout(predicate::str::contains(
+            "packs/foo/package_todo.yml records violations on `packs/deleted`, which does not exist: ::Deleted. Run `packs validate --fix` to remove them.",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_validate_fix_todo_with_unknown_pack() -> Result<(), Box<dyn Error>> {
+    let original_package_todo = fs::read_to_string(PACKAGE_TODO_PATH)?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_unknown_todo_pack")
+        .arg("validate")
+        .arg("--fix")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Removed 1 recorded violation(s) on packs that no longer exist from package_todo.yml files",
+        ))
+        .stdout(predicate::str::contains("Packwerk validate succeeded!"));
+
+    let package_todo = fs::read_to_string(PACKAGE_TODO_PATH)?;
+    fs::write(PACKAGE_TODO_PATH, original_package_todo)?;
+    assert!(package_todo.contains("packs/bar:"));
+    assert!(!package_todo.contains("packs/deleted"));
+
+    common::teardown();
+    Ok(())
+}
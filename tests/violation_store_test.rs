@@ -0,0 +1,81 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs};
+
+mod common;
+
+#[test]
+fn test_update_with_json_store() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_with_json_store");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("update")
+        .assert()
+        .success();
+
+    // Violations are recorded in one file rather than per pack
+    assert!(!project_root.join("packs/foo/package_todo.yml").exists());
+    let recorded: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(project_root.join("package_todo.json"))?,
+    )?;
+    assert_eq!(
+        recorded["packs/foo"]["packs/bar"]["::Bar"]["violations"][0],
+        "dependency"
+    );
+    assert_eq!(
+        recorded["packs/foo"]["packs/bar"]["::Bar"]["files"][0],
+        "packs/foo/app/services/foo.rb"
+    );
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    fs::remove_dir_all(project_root)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_prune_todos_with_json_store() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_with_json_store");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("update")
+        .assert()
+        .success();
+    fs::remove_file(project_root.join("packs/foo/app/services/foo.rb"))?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("prune-todos")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1 recorded violation(s)"));
+
+    let recorded: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(project_root.join("package_todo.json"))?,
+    )?;
+    assert_eq!(serde_json::json!({}), recorded);
+
+    fs::remove_dir_all(project_root)?;
+    common::teardown();
+    Ok(())
+}
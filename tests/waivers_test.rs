@@ -0,0 +1,50 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs};
+
+mod common;
+
+#[test]
+fn test_check_with_waivers() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_waivers")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb:7:4\nPrivacy violation: `::Baz` is private to `packs/baz`, but referenced from `packs/foo`"))
+        .stdout(predicate::str::contains("`::Bar`").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_waivers() -> Result<(), Box<dyn Error>> {
+    let project_root = common::copy_fixture("app_with_waivers");
+    fs::write(
+        project_root.join("packs/bar/package.yml"),
+        "enforce_privacy: true
+waivers:
+- constant: \"::Bar\"
+  referencing_pack: packs/missing
+  reason: \"\"
+  expires_on: next year
+",
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&project_root)
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("3 validation error(s) detected:"))
+        .stdout(predicate::str::contains("`waivers` in `packs/bar/package.yml` for `::Bar` referenced from `packs/missing` references `packs/missing`, but that pack cannot be found"))
+        .stdout(predicate::str::contains("`waivers` in `packs/bar/package.yml` for `::Bar` referenced from `packs/missing` must specify a `reason`"))
+        .stdout(predicate::str::contains("`waivers` in `packs/bar/package.yml` for `::Bar` referenced from `packs/missing` must specify `expires_on` as a date like 2024-03-31, not `next year`"));
+
+    fs::remove_dir_all(project_root)?;
+    Ok(())
+}